@@ -0,0 +1,50 @@
+/// Commands for cache maintenance
+use super::packs::{lock_aware, vanilla_progress_callback};
+use crate::util::cache_health::{self, CacheHealthReport};
+use crate::util::vanilla_textures::{self, VanillaCacheClearResult};
+use crate::AppError;
+
+/// Verify the vanilla and preview caches, optionally repairing them
+///
+/// # Arguments
+/// * `repair` - Re-extract missing vanilla files and remove corrupt previews
+/// * `window` - Window to emit extraction progress events to
+///
+/// # Returns
+/// Health report for each cache
+pub fn verify_caches_impl(
+    repair: bool,
+    window: Option<tauri::Window>,
+) -> Result<CacheHealthReport, AppError> {
+    // Repairs reuse the vanilla extraction progress events
+    let progress_callback = window.map(|w| vanilla_progress_callback(w, "verify_caches"));
+
+    cache_health::verify_caches(repair, progress_callback).map_err(|e| {
+        lock_aware(e, |e| {
+            AppError::io(format!("Cache verification failed: {}", e))
+        })
+    })
+}
+
+/// Remove cached vanilla versions
+///
+/// Removing the version in use means vanilla assets are extracted again
+/// the next time they're needed.
+///
+/// # Arguments
+/// * `version` - Version to remove (e.g. "1.21.4"); every version if None
+///
+/// # Returns
+/// The versions removed and the space freed
+///
+/// # Errors
+/// - IO_ERROR: The cache folder can't be found
+pub fn clear_vanilla_cache_impl(
+    version: Option<String>,
+) -> Result<VanillaCacheClearResult, AppError> {
+    let cache_root = vanilla_textures::get_vanilla_cache_root()
+        .map_err(|e| AppError::io(format!("Failed to find vanilla cache: {}", e)))?;
+    let result = vanilla_textures::clear_vanilla_cache(&cache_root, version.as_deref());
+    vanilla_textures::reset_active_cache_dir();
+    Ok(result)
+}
@@ -0,0 +1,59 @@
+/// Commands for custom model data reports
+use crate::util::custom_model_data::{self, CmdReport};
+use crate::util::pack_scanner;
+use crate::{validation, AppError};
+use tracing::info;
+
+/// Scan packs and collect a report of custom model data values
+fn scan_report(packs_dir: &str, pack_ids: Option<Vec<String>>) -> Result<CmdReport, AppError> {
+    validation::validate_directory(packs_dir, "Packs directory")?;
+    validation::validate_path_access(packs_dir, "Packs directory")?;
+
+    let mut packs = pack_scanner::scan_packs(packs_dir)
+        .map_err(|e| AppError::scan(format!("Failed to scan packs: {}", e)))?;
+    if let Some(ids) = pack_ids {
+        packs.retain(|p| ids.contains(&p.id));
+    }
+
+    Ok(custom_model_data::scan_custom_model_data(&packs))
+}
+
+/// Get all custom_model_data values in use per item, with collisions
+///
+/// # Arguments
+/// * `packs_dir` - Directory containing resource packs
+/// * `pack_ids` - Packs to include (all packs if omitted)
+///
+/// # Returns
+/// Values per item and every item/value pair mapped to more than one model
+pub fn get_custom_model_data_report_impl(
+    packs_dir: String,
+    pack_ids: Option<Vec<String>>,
+) -> Result<CmdReport, AppError> {
+    scan_report(&packs_dir, pack_ids)
+}
+
+/// Write the custom model data report as CSV
+///
+/// # Arguments
+/// * `packs_dir` - Directory containing resource packs
+/// * `pack_ids` - Packs to include (all packs if omitted)
+/// * `output_path` - File to write the CSV to
+///
+/// # Returns
+/// Path of the written report
+pub fn export_custom_model_data_report_impl(
+    packs_dir: String,
+    pack_ids: Option<Vec<String>>,
+    output_path: String,
+) -> Result<String, AppError> {
+    validation::validate_path_access(&output_path, "Report file")?;
+
+    let report = scan_report(&packs_dir, pack_ids)?;
+    std::fs::write(&output_path, custom_model_data::cmd_report_to_csv(&report))
+        .map_err(|e| AppError::io(format!("Failed to write report: {}", e)))?;
+
+    info!("Wrote report to {}", output_path);
+
+    Ok(output_path)
+}
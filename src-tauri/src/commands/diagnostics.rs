@@ -0,0 +1,74 @@
+/// Commands for diagnostics and support bundles
+use crate::util::diagnostics::{self, DiagnosticsReport};
+use crate::util::logging::{self, RecentLogs};
+use crate::util::stack_check::{self, StackReport};
+use crate::{validation, AppError};
+use tracing::info;
+
+/// Collect the full detection state as a diagnostics report
+pub fn get_diagnostics_impl() -> Result<DiagnosticsReport, AppError> {
+    Ok(diagnostics::collect_diagnostics())
+}
+
+/// Write the diagnostics report as pretty-printed JSON
+///
+/// # Arguments
+/// * `output_path` - File to write the report to
+///
+/// # Returns
+/// Path of the written report
+pub fn export_diagnostics_impl(output_path: String) -> Result<String, AppError> {
+    validation::validate_path_access(&output_path, "Diagnostics file")?;
+
+    let report = diagnostics::collect_diagnostics();
+    let json = serde_json::to_string_pretty(&report)
+        .map_err(|e| AppError::internal("Failed to serialize diagnostics", e.to_string()))?;
+
+    std::fs::write(&output_path, json)
+        .map_err(|e| AppError::io(format!("Failed to write diagnostics: {}", e)))?;
+
+    info!("Wrote report to {}", output_path);
+
+    Ok(output_path)
+}
+
+/// Get the last lines of the application log, e.g. to attach to a bug report
+///
+/// # Arguments
+/// * `limit` - Number of lines; `DEFAULT_RECENT_LINES` if None
+///
+/// # Errors
+/// - IO_ERROR: The log files can't be read
+pub fn get_recent_logs_impl(limit: Option<usize>) -> Result<RecentLogs, AppError> {
+    let log_dir = match logging::active_log_dir() {
+        Some(dir) => dir.to_path_buf(),
+        None => logging::default_log_dir()
+            .map_err(|e| AppError::io(format!("Failed to get log directory: {}", e)))?,
+    };
+    let lines = logging::recent_log_lines(&log_dir, limit.unwrap_or(logging::DEFAULT_RECENT_LINES))
+        .map_err(|e| AppError::io(format!("Failed to read logs: {}", e)))?;
+
+    Ok(RecentLogs {
+        log_dir: log_dir.to_string_lossy().to_string(),
+        lines,
+    })
+}
+
+/// Validate the pack stack an instance has enabled, without building anything
+///
+/// # Arguments
+/// * `instance_dir` - Instance or game folder (the one holding options.txt)
+///
+/// # Returns
+/// Enabled packs in priority order and everything found wrong with them
+pub fn check_instance_stack_impl(instance_dir: String) -> Result<StackReport, AppError> {
+    validation::validate_directory(&instance_dir, "Instance directory")?;
+    validation::validate_path_access(&instance_dir, "Instance directory")?;
+
+    let report = stack_check::check_instance_stack(std::path::Path::new(&instance_dir))
+        .map_err(|e| AppError::scan(format!("Stack check failed: {}", e)))?;
+
+    info!("{}: {}", report.game_dir, report.summary);
+
+    Ok(report)
+}
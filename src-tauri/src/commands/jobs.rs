@@ -0,0 +1,21 @@
+/// Commands for cancelling long-running jobs
+use crate::util::jobs;
+use crate::AppError;
+
+/// Create a job ID to pass to a scan, extraction or build
+///
+/// # Returns
+/// ID that `cancel_job_impl` accepts while the job runs
+pub fn create_job_impl() -> Result<String, AppError> {
+    Ok(jobs::create_job())
+}
+
+/// Ask a running job to stop
+///
+/// The job's command fails with a CANCELLED error at its next check.
+///
+/// # Returns
+/// Whether the job was still running
+pub fn cancel_job_impl(job_id: String) -> Result<bool, AppError> {
+    Ok(jobs::cancel_job(&job_id))
+}
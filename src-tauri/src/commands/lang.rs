@@ -0,0 +1,43 @@
+/// Commands for searching language entries
+use crate::util::lang_search::{self, LangSearchResult};
+use crate::util::pack_scanner;
+use crate::{validation, AppError};
+
+/// Find which packs define or rename a translation key or string
+///
+/// # Arguments
+/// * `packs_dir` - Directory containing resource packs
+/// * `pack_order` - Pack IDs in priority order (first = highest)
+/// * `query` - Text to find in keys and values (case-insensitive)
+/// * `locale` - Only search this locale (e.g., "en_us"); all if None
+///
+/// # Returns
+/// Matching entries with their locale and pack, the ones the game would
+/// show marked active
+///
+/// # Errors
+/// - VALIDATION_ERROR: Packs directory is invalid or the query is empty
+/// - SCAN_ERROR: Failed to scan packs
+pub fn search_lang_entries_impl(
+    packs_dir: String,
+    pack_order: Vec<String>,
+    query: String,
+    locale: Option<String>,
+) -> Result<LangSearchResult, AppError> {
+    validation::validate_directory(&packs_dir, "Packs directory")?;
+    validation::validate_path_access(&packs_dir, "Packs directory")?;
+    let query = query.trim();
+    if query.is_empty() {
+        return Err(AppError::validation("Search text is empty"));
+    }
+
+    let packs = pack_scanner::scan_packs(&packs_dir)
+        .map_err(|e| AppError::scan(format!("Failed to scan packs: {}", e)))?;
+
+    Ok(lang_search::search_lang_entries(
+        &packs,
+        &pack_order,
+        query,
+        locale.as_deref(),
+    ))
+}
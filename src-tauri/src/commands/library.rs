@@ -0,0 +1,166 @@
+/// Commands for removing packs from the library and restoring them
+use crate::commands::packs::lock_aware;
+use crate::util::pack_quarantine::{self, PackRemovalImpact, QuarantinedPack};
+use crate::util::{activity_log, settings};
+use crate::{validation, AppError};
+use std::path::Path;
+
+fn validate_packs_dir(packs_dir: &str) -> Result<(), AppError> {
+    validation::validate_directory(packs_dir, "Packs directory")?;
+    validation::validate_path_access(packs_dir, "Packs directory")
+}
+
+/// Check which saved projects would be affected by removing a pack
+///
+/// # Arguments
+/// * `packs_dir` - Directory containing resource packs
+/// * `pack_id` - File or folder name of the pack
+///
+/// # Errors
+/// - VALIDATION_ERROR: Invalid packs directory
+/// - IO_ERROR: Failed to load settings
+pub fn check_pack_removal_impl(
+    packs_dir: String,
+    pack_id: String,
+) -> Result<PackRemovalImpact, AppError> {
+    validate_packs_dir(&packs_dir)?;
+
+    let settings = settings::load_settings()
+        .map_err(|e| AppError::io(format!("Failed to load settings: {}", e)))?;
+    let references = pack_quarantine::pack_references(&settings.projects, &packs_dir, &pack_id);
+
+    Ok(PackRemovalImpact {
+        pack_id,
+        references,
+    })
+}
+
+/// Remove a pack from the library by moving it to quarantine
+///
+/// # Arguments
+/// * `packs_dir` - Directory containing resource packs
+/// * `pack_id` - File or folder name of the pack
+/// * `confirm_referenced` - Remove it even if saved projects use it
+///
+/// # Errors
+/// - VALIDATION_ERROR: Invalid packs directory or pack name, or the pack is
+///   used by saved projects and removal wasn't confirmed
+/// - FILE_LOCKED: The pack is open in another program
+/// - IO_ERROR: Failed to load settings or move the pack
+pub fn quarantine_pack_impl(
+    packs_dir: String,
+    pack_id: String,
+    confirm_referenced: bool,
+) -> Result<QuarantinedPack, AppError> {
+    let impact = check_pack_removal_impl(packs_dir.clone(), pack_id.clone())?;
+    if !impact.references.is_empty() && !confirm_referenced {
+        let projects: Vec<&str> = impact
+            .references
+            .iter()
+            .map(|r| r.project.as_str())
+            .collect();
+        return Err(AppError::validation(format!(
+            "{} is used by {} saved project(s)",
+            pack_id,
+            projects.len()
+        ))
+        .with_details(projects.join(", ")));
+    }
+
+    let quarantined =
+        pack_quarantine::quarantine_pack(Path::new(&packs_dir), &pack_id).map_err(|e| {
+            lock_aware(e, |e| {
+                AppError::io(format!("Failed to remove pack: {}", e)).with_path(packs_dir.as_str())
+            })
+        })?;
+
+    activity_log::record(
+        &packs_dir,
+        None,
+        "quarantinePack",
+        serde_json::json!({
+            "packId": pack_id,
+            "quarantineId": quarantined.id,
+            "projects": impact.references.len(),
+        }),
+        None,
+    );
+
+    Ok(quarantined)
+}
+
+/// List packs in a library's quarantine, most recently removed first
+///
+/// # Errors
+/// - VALIDATION_ERROR: Invalid packs directory
+/// - IO_ERROR: Failed to read the quarantine folder
+pub fn list_quarantined_packs_impl(packs_dir: String) -> Result<Vec<QuarantinedPack>, AppError> {
+    validate_packs_dir(&packs_dir)?;
+
+    pack_quarantine::list_quarantined(Path::new(&packs_dir))
+        .map_err(|e| AppError::io(format!("Failed to list quarantined packs: {}", e)))
+}
+
+/// Move a quarantined pack back into the library
+///
+/// # Arguments
+/// * `packs_dir` - Directory containing resource packs
+/// * `quarantine_id` - ID from `list_quarantined_packs`
+///
+/// # Returns
+/// Path of the restored pack
+///
+/// # Errors
+/// - VALIDATION_ERROR: Invalid packs directory
+/// - FILE_LOCKED: The pack is open in another program
+/// - IO_ERROR: Unknown entry, a pack with the same name exists, or the move
+///   failed
+pub fn restore_quarantined_pack_impl(
+    packs_dir: String,
+    quarantine_id: String,
+) -> Result<String, AppError> {
+    validate_packs_dir(&packs_dir)?;
+
+    let restored = pack_quarantine::restore_quarantined(Path::new(&packs_dir), &quarantine_id)
+        .map_err(|e| {
+            lock_aware(e, |e| {
+                AppError::io(format!("Failed to restore pack: {}", e))
+            })
+        })?;
+
+    activity_log::record(
+        &packs_dir,
+        None,
+        "restoreQuarantinedPack",
+        serde_json::json!({ "quarantineId": quarantine_id }),
+        None,
+    );
+
+    Ok(restored.to_string_lossy().to_string())
+}
+
+/// Permanently delete a quarantined pack
+///
+/// # Errors
+/// - VALIDATION_ERROR: Invalid packs directory
+/// - FILE_LOCKED: The pack is open in another program
+/// - IO_ERROR: Unknown entry or the delete failed
+pub fn purge_quarantined_pack_impl(
+    packs_dir: String,
+    quarantine_id: String,
+) -> Result<(), AppError> {
+    validate_packs_dir(&packs_dir)?;
+
+    pack_quarantine::purge_quarantined(Path::new(&packs_dir), &quarantine_id)
+        .map_err(|e| lock_aware(e, |e| AppError::io(format!("Failed to delete pack: {}", e))))?;
+
+    activity_log::record(
+        &packs_dir,
+        None,
+        "purgeQuarantinedPack",
+        serde_json::json!({ "quarantineId": quarantine_id }),
+        None,
+    );
+
+    Ok(())
+}
@@ -1,15 +1,92 @@
 /// Tauri command handlers
+pub mod cache;
+pub mod custom_model_data;
+pub mod diagnostics;
+pub mod jobs;
+pub mod lang;
+pub mod library;
+pub mod namespaces;
+pub mod optifine;
 pub mod packs;
+pub mod presets;
+pub mod projects;
+pub mod remote;
+pub mod reports;
+pub mod resolution;
+pub mod session;
+pub mod settings;
+pub mod textures;
 
+pub use cache::{clear_vanilla_cache_impl, verify_caches_impl};
+pub use custom_model_data::{
+    export_custom_model_data_report_impl, get_custom_model_data_report_impl,
+};
+pub use diagnostics::{
+    check_instance_stack_impl, export_diagnostics_impl, get_diagnostics_impl, get_recent_logs_impl,
+};
+pub use jobs::{cancel_job_impl, create_job_impl};
+pub use lang::search_lang_entries_impl;
+pub use library::{
+    check_pack_removal_impl, list_quarantined_packs_impl, purge_quarantined_pack_impl,
+    quarantine_pack_impl, restore_quarantined_pack_impl,
+};
+pub use namespaces::detect_namespace_collisions_impl;
+pub use optifine::get_ctm_report_impl;
 pub use packs::{
-    build_weaver_nest_impl, check_minecraft_installed_impl, detect_launchers_impl,
+    apply_queued_installs_impl, browse_pack_impl, build_merged_pack_impl, build_weaver_nest_impl,
+    check_minecraft_installed_impl, clear_stale_lock_impl, compress_pack_folder_impl,
+    compute_output_sha1_impl, detect_launchers_impl, diff_pack_models_impl,
+    discard_interrupted_build_impl, discard_queued_install_impl, download_cloud_placeholders_impl,
+    download_remote_pack_impl, extract_pack_archive_impl, extract_pack_file_impl,
     get_block_state_schema_impl, get_cached_vanilla_version_impl, get_colormap_path_impl,
-    get_default_packs_dir_impl, get_entity_version_variants_impl,
-    get_launcher_resourcepacks_dir_impl, get_pack_texture_path_impl,
-    get_suggested_minecraft_paths_impl, get_vanilla_mcmeta_path_impl,
-    get_vanilla_texture_path_impl, identify_launcher_impl,
-    initialize_vanilla_textures_from_custom_dir_impl, initialize_vanilla_textures_impl,
-    list_available_minecraft_versions_impl, load_model_json_impl, read_block_model_impl,
-    read_pack_file_impl, read_vanilla_jem_impl, resolve_block_state_impl, scan_packs_folder_impl,
-    set_vanilla_texture_version_impl, BuildWeaverNestRequest,
+    get_default_packs_dir_impl, get_entity_version_variants_impl, get_face_textures_impl,
+    get_feature_requirements_impl, get_launcher_resourcepacks_dir_impl,
+    get_override_dependencies_impl, get_pack_health_report_impl, get_pack_palettes_impl,
+    get_pack_texture_path_impl, get_running_games_impl, get_suggested_minecraft_paths_impl,
+    get_texture_usage_impl, get_vanilla_mcmeta_path_impl, get_vanilla_texture_path_impl,
+    identify_launcher_impl, initialize_vanilla_textures_from_custom_dir_impl,
+    initialize_vanilla_textures_impl, inspect_packs_folder_impl,
+    list_available_minecraft_versions_impl, list_interrupted_builds_impl,
+    list_launcher_instances_impl, list_modrinth_profiles_impl, list_queued_installs_impl,
+    list_vanilla_ids_impl, load_item_model_impl, load_model_json_impl, migrate_pack_impl,
+    read_block_model_impl, read_pack_file_impl, read_vanilla_jem_impl, repack_pack_folder_impl,
+    resolve_block_state_impl, resume_interrupted_build_impl, reveal_in_file_manager_impl,
+    scan_packs_folder_impl, set_vanilla_texture_version_impl, suggest_pack_order_impl,
+    BuildWeaverNestRequest,
+};
+pub use presets::{
+    apply_conflict_preset_impl, delete_conflict_preset_impl, export_conflict_preset_impl,
+    import_conflict_preset_impl, list_conflict_presets_impl, save_conflict_preset_impl,
+};
+pub use projects::{
+    batch_build_projects_impl, delete_project_impl, get_activity_log_impl,
+    get_applied_pack_status_impl, list_projects_impl, record_activity_impl, save_project_impl,
+    seed_overrides_from_project_impl, simulate_stacks_impl,
+};
+pub use remote::{
+    get_remote_server_status_impl, regenerate_remote_server_token_impl,
+    start_remote_server_if_enabled, start_remote_server_impl, stop_remote_server_impl,
+};
+pub use reports::{
+    export_pack_report_impl, get_conflict_report_impl, get_item_model_conflicts_impl,
+    get_namespace_coverage_impl,
+};
+pub use resolution::{
+    get_next_conflict_impl, record_conflict_resolution_impl, reset_conflict_resolution_impl,
+};
+pub use session::{restore_last_session_impl, update_session_stack_impl};
+pub use settings::{
+    get_conflict_severity_settings_impl, get_description_template_impl,
+    get_parallelism_settings_impl, get_post_build_hooks_impl, get_running_game_policy_impl,
+    get_vanilla_version_settings_impl, list_path_grants_impl, render_pack_description_impl,
+    revoke_path_grant_impl, select_folder_impl, set_conflict_severity_settings_impl,
+    set_description_template_impl, set_parallelism_settings_impl, set_post_build_hooks_impl,
+    set_running_game_policy_impl, set_vanilla_version_settings_impl,
+};
+pub use textures::{
+    export_animation_impl, export_comparison_images_impl, export_contact_sheet_impl,
+    get_animation_frames_impl, get_animation_info_impl, get_entity_groups_impl,
+    get_gui_regions_impl, get_texture_history_impl, get_texture_pyramid_impl,
+    get_texture_tile_impl, get_tinted_texture_impl, list_biomes_impl, list_gui_atlases_impl,
+    render_doc_previews_impl, render_preview_scene_impl, simulate_color_vision_impl,
 };
@@ -0,0 +1,34 @@
+/// Commands for detecting custom namespace collisions
+use crate::util::namespace_conflicts::{self, NamespaceCollision};
+use crate::util::{asset_indexer, pack_scanner};
+use crate::{validation, AppError};
+use tracing::info;
+
+/// Find custom namespaces (anything but minecraft:) provided by several packs
+///
+/// # Arguments
+/// * `packs_dir` - Directory containing resource packs
+/// * `pack_order` - Pack IDs in priority order (first = highest)
+///
+/// # Returns
+/// One entry per colliding namespace with its packs and a suggested owner.
+/// Pass `{ namespace: owner }` as `namespace_owners` when building to keep the
+/// namespace from every other pack.
+pub fn detect_namespace_collisions_impl(
+    packs_dir: String,
+    pack_order: Vec<String>,
+) -> Result<Vec<NamespaceCollision>, AppError> {
+    validation::validate_directory(&packs_dir, "Packs directory")?;
+    validation::validate_path_access(&packs_dir, "Packs directory")?;
+
+    let packs = pack_scanner::scan_packs(&packs_dir)
+        .map_err(|e| AppError::scan(format!("Failed to scan packs: {}", e)))?;
+    let (assets, providers) = asset_indexer::index_assets(&packs)
+        .map_err(|e| AppError::scan(format!("Asset indexing failed: {}", e)))?;
+
+    let collisions =
+        namespace_conflicts::detect_namespace_collisions(&assets, &providers, &pack_order);
+    info!("Found {} colliding namespaces", collisions.len());
+
+    Ok(collisions)
+}
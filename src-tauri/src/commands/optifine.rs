@@ -0,0 +1,27 @@
+/// Commands for OptiFine connected textures
+use crate::util::optifine::{self, CtmReport};
+use crate::util::pack_scanner;
+use crate::{validation, AppError};
+
+/// Get the CTM definitions of each pack and blocks several packs define
+///
+/// # Arguments
+/// * `packs_dir` - Directory containing resource packs
+/// * `pack_order` - Enabled pack IDs in priority order (first = highest);
+///   all packs are checked if empty
+///
+/// # Returns
+/// Definitions per pack with their blocks, and one conflict per block with
+/// CTM from two or more packs
+pub fn get_ctm_report_impl(
+    packs_dir: String,
+    pack_order: Vec<String>,
+) -> Result<CtmReport, AppError> {
+    validation::validate_directory(&packs_dir, "Packs directory")?;
+    validation::validate_path_access(&packs_dir, "Packs directory")?;
+
+    let packs = pack_scanner::scan_packs(&packs_dir)
+        .map_err(|e| AppError::scan(format!("Failed to scan packs: {}", e)))?;
+
+    Ok(optifine::ctm_report(&packs, &pack_order))
+}
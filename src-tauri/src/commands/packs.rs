@@ -5,15 +5,34 @@
 /// - Validates all inputs before processing
 /// - Separates concerns: validation → execution → response
 /// - Reduces boilerplate with validation module
-use crate::model::{OverrideSelection, ScanResult};
+use crate::model::{OverrideSelection, PackMeta, ScanResult};
+use crate::util::build_journal::{BuildPhase, InterruptedBuild};
+use crate::util::cloud_sync::{FolderAccessReport, PlaceholderDownload};
+use crate::util::content_hash::Sha1Result;
+use crate::util::feature_requirements::RequirementsSummary;
+use crate::util::instances::LauncherInstance;
+use crate::util::modrinth_profiles::ModrinthProfile;
+use crate::util::pack_browser::{self, PackTreeEntry};
+use crate::util::pack_builder::{MergeSummary, OutputFormat};
+use crate::util::pack_health::PackHealthReport;
+use crate::util::palette::PackPalette;
+use crate::util::progress::{self, FileProgress, ProgressTracker};
+use crate::util::running_game::{self, QueuedInstall, RunningGame, RunningGamePolicy};
+use crate::util::server_export::ServerExportOptions;
 use crate::util::{
-    asset_indexer, launcher_detection, mc_paths, pack_scanner, texture_index, vanilla_textures,
-    weaver_nest,
+    activity_log, alpha_bleed, asset_indexer, asset_stats, build_journal, cloud_sync, content_hash,
+    curseforge_instances, feature_requirements, file_manager, file_retry, index_cache,
+    install_status, instance_lock, instances, jobs, known_packs, launcher_detection, mc_paths,
+    mcmeta_pairs, missing_texture, modrinth_profiles, namespace_conflicts, override_closure,
+    pack_archive, pack_builder, pack_description, pack_format, pack_health, pack_migrator,
+    pack_order, pack_scanner, palette, post_build, projects, remote_download, session_cache,
+    settings, text_assets, texture_index, vanilla_textures, weaver_nest,
 };
 use crate::{validation, AppError};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+use tracing::{debug, info, warn};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct BuildWeaverNestRequest {
@@ -21,48 +40,145 @@ pub struct BuildWeaverNestRequest {
     pub pack_order: Vec<String>,
     pub overrides: HashMap<String, OverrideSelection>, // asset_id -> override payload
     pub output_dir: String,
+    /// Namespace -> the only pack allowed to provide it
+    #[serde(default)]
+    pub namespace_owners: HashMap<String, String>,
+    /// Recolor fully transparent texture pixels to avoid dark mipmap edges
+    #[serde(default)]
+    pub fix_alpha_bleed: bool,
+    /// Take the other textures of an overridden block from the same pack
+    #[serde(default = "override_closure::enabled_by_default")]
+    pub include_override_dependencies: bool,
+    /// Packs in the order left out of this build
+    #[serde(default)]
+    pub disabled_packs: Vec<String>,
+    /// Text assets (e.g., "minecraft:texts/splashes") built from every
+    /// enabled pack's unique lines instead of the winner's file
+    #[serde(default)]
+    pub merged_text_assets: Vec<String>,
+    /// Project the build is for, available to description templates
+    #[serde(default)]
+    pub project_name: Option<String>,
+    /// pack.mcmeta description template; the default one if None
+    #[serde(default)]
+    pub description_template: Option<String>,
+}
+
+/// Mipmap warnings listed individually in the build log
+const MAX_LOGGED_MIPMAP_WARNINGS: usize = 20;
+
+/// Texture .mcmeta warnings listed individually in the build log
+const MAX_LOGGED_MCMETA_WARNINGS: usize = 20;
+
+/// Report a cancelled job as CANCELLED, a file that stayed locked as
+/// FILE_LOCKED, a cache or output another instance is writing as
+/// INSTANCE_BUSY, other failures via `fallback` (with a hint to free disk
+/// space if that is what failed)
+pub(crate) fn lock_aware(
+    e: anyhow::Error,
+    fallback: impl FnOnce(anyhow::Error) -> AppError,
+) -> AppError {
+    if let Some(cancelled) = jobs::cancelled_job(&e) {
+        return AppError::cancelled(cancelled);
+    }
+    if let Some(busy) = instance_lock::busy_instance(&e) {
+        return AppError::instance_busy(busy);
+    }
+    if let Some(locked) = file_retry::locked_file(&e) {
+        return AppError::file_locked(locked.path.to_string_lossy());
+    }
+    let disk_full = crate::error::caused_by_disk_full(&e);
+    let err = fallback(e);
+    if disk_full {
+        err.with_hint(crate::error::ErrorHint::FreeDiskSpace)
+    } else {
+        err
+    }
 }
 
 /// Create a virtual vanilla pack entry
-fn create_vanilla_pack() -> Result<crate::model::PackMeta, AppError> {
+pub(crate) fn create_vanilla_pack() -> Result<crate::model::PackMeta, AppError> {
     let cache_dir = vanilla_textures::get_vanilla_cache_dir()
         .map_err(|e| AppError::io(format!("Failed to get vanilla cache dir: {}", e)))?;
 
     Ok(crate::model::PackMeta {
-        id: "minecraft:vanilla".to_string(),
+        id: vanilla_textures::VANILLA_PACK_ID.to_string(),
         name: "Minecraft (Vanilla)".to_string(),
         path: cache_dir.to_string_lossy().to_string(),
-        size: 0,
-        is_zip: false,
         description: Some("Default Minecraft textures".to_string()),
-        icon_data: None,
-        pack_format: None, // Vanilla textures don't have a pack format
+        // Vanilla textures have no icon or pack format
+        ..Default::default()
     })
 }
 
 /// Scan a resource packs directory and return all packs and assets
 ///
+/// # Arguments
+/// * `packs_dir` - Directory containing resource packs
+/// * `force_reindex` - Re-read every pack instead of reusing cached listings
+/// * `window` - Window to emit "scan:progress" and "index:progress" events to
+/// * `job_id` - Job from `create_job` that `cancel_job` can stop
+///
 /// # Errors
 /// - VALIDATION_ERROR: Directory doesn't exist or is invalid
 /// - SCAN_ERROR: Failed to scan packs
 ///
 /// # Returns
 /// Empty result if no packs found (not an error)
-pub fn scan_packs_folder_impl(packs_dir: String) -> Result<ScanResult, AppError> {
+pub fn scan_packs_folder_impl(
+    packs_dir: String,
+    force_reindex: bool,
+    window: Option<tauri::Window>,
+    job_id: Option<String>,
+) -> Result<ScanResult, AppError> {
     // Validate input
     validation::validate_directory(&packs_dir, "Packs directory")?;
+    validation::validate_path_access(&packs_dir, "Packs directory")?;
+    let job = jobs::start_job(job_id.as_deref());
+
+    let scan_tracker = window
+        .clone()
+        .map(|w| progress_tracker(w, progress::SCAN_PROGRESS_EVENT, "scan"));
+    let index_tracker =
+        window.map(|w| progress_tracker(w, progress::INDEX_PROGRESS_EVENT, "index"));
+    let scan_progress = |done: usize, total: usize, pack: Option<&str>| {
+        if let Some(tracker) = &scan_tracker {
+            tracker.report(done, total, pack);
+        }
+    };
+    let index_progress = |done: usize, total: usize, pack: Option<&str>| {
+        if let Some(tracker) = &index_tracker {
+            tracker.report(done, total, pack);
+        }
+    };
 
     // Scan for packs
-    let mut packs =
-        pack_scanner::scan_packs(&packs_dir).map_err(|e| AppError::scan(e.to_string()))?;
+    let mut packs = pack_scanner::scan_packs_with_progress(&packs_dir, Some(&scan_progress))
+        .map_err(|e| AppError::scan(e.to_string()))?;
+    jobs::check_cancelled(Some(job.token()))?;
 
     // Add vanilla pack at the end (lowest priority)
     let vanilla_pack = create_vanilla_pack()?;
     packs.push(vanilla_pack);
 
-    // Index assets (including vanilla)
-    let (assets, mut providers) = asset_indexer::index_assets(&packs)
-        .map_err(|e| AppError::scan(format!("Asset indexing failed: {}", e)))?;
+    if force_reindex {
+        if let Some(cache_dir) = index_cache::index_cache_dir() {
+            let removed = index_cache::invalidate(&cache_dir, &packs);
+            info!("Dropped {} cached pack index(es)", removed);
+        }
+    }
+
+    // Index assets (including vanilla), hashing contents for dedupe
+    let (assets, mut providers, hash_index) = asset_indexer::index_assets_with_hashes_and_progress(
+        &packs,
+        Some(&index_progress),
+        Some(job.token()),
+    )
+    .map_err(|e| {
+        lock_aware(e, |e| {
+            AppError::scan(format!("Asset indexing failed: {}", e))
+        })
+    })?;
 
     // For each asset, ensure vanilla pack is listed as a provider if texture exists
     for asset in &assets {
@@ -75,20 +191,137 @@ pub fn scan_packs_folder_impl(packs_dir: String) -> Result<ScanResult, AppError>
         }
     }
 
-    Ok(ScanResult {
+    // Tag small packs made to patch another pack
+    let vanilla_dir = vanilla_textures::get_vanilla_cache_dir().ok();
+    pack_order::tag_patch_packs(&mut packs, &providers, vanilla_dir.as_deref());
+
+    // Label popular packs with their canonical names and requirements
+    let known_packs = known_packs::identify_known_packs(&packs, &hash_index.pack_files);
+
+    let identical_providers =
+        content_hash::identical_provider_groups(&providers, &hash_index.pack_hashes);
+
+    // Counts and sizes by category for summary dashboards
+    let asset_stats = asset_stats::compute_asset_stats(&packs, &assets, &providers);
+
+    // Explain skipped cloud placeholders and sync/network/read-only folders
+    let folder_access = cloud_sync::inspect_packs_folder(Path::new(&packs_dir));
+    let folder_access = if folder_access.warnings.is_empty() {
+        None
+    } else {
+        Some(folder_access)
+    };
+
+    let result = ScanResult {
         packs,
         assets,
         providers,
+        known_packs,
+        identical_providers,
+        hash_timings: hash_index.timings,
+        folder_access,
+        os_metadata_files: hash_index.os_metadata_files,
+        asset_stats,
+    };
+
+    // Keep the scan to restore the session on the next launch; writing a
+    // large index takes a moment, so the result isn't held up by it
+    if let Some(snapshot_path) = session_cache::session_snapshot_path() {
+        let scan = result.clone();
+        std::thread::spawn(move || {
+            if let Err(e) = session_cache::remember_scan(&snapshot_path, &packs_dir, scan) {
+                warn!("Failed to save session snapshot: {:#}", e);
+            }
+        });
+    }
+
+    Ok(result)
+}
+
+/// Tracker emitting `Progress` payloads to the window as `event`
+pub(crate) fn progress_tracker(
+    window: tauri::Window,
+    event: &'static str,
+    phase: &str,
+) -> ProgressTracker {
+    use tauri::Emitter;
+
+    ProgressTracker::new(phase, move |payload| {
+        if let Err(e) = window.emit(event, payload) {
+            warn!("Failed to emit {} event: {}", event, e);
+        }
+    })
+}
+
+/// Emit "build-progress" events with (files copied, total), and
+/// "build:progress" events with the current file and ETA
+fn build_progress_emitter(window: tauri::Window) -> impl Fn(usize, usize, Option<&str>) + Sync {
+    use tauri::Emitter;
+
+    let tracker = progress_tracker(window.clone(), progress::BUILD_PROGRESS_EVENT, "build");
+    move |current: usize, total: usize, file: Option<&str>| {
+        if let Err(e) = window.emit("build-progress", (current, total)) {
+            warn!("Failed to emit progress event: {}", e);
+        }
+        tracker.report(current, total, file);
+    }
+}
+
+/// Vanilla extraction progress: "vanilla-texture-progress" events with
+/// (current, total), and "extract:progress" events with the current file
+pub(crate) fn vanilla_progress_callback(
+    window: tauri::Window,
+    label: &'static str,
+) -> vanilla_textures::ProgressCallback {
+    use std::sync::Arc;
+    use tauri::Emitter;
+
+    let tracker = progress_tracker(window.clone(), progress::EXTRACT_PROGRESS_EVENT, "extract");
+    Arc::new(move |current: usize, total: usize, file: Option<&str>| {
+        if let Err(e) = window.emit("vanilla-texture-progress", (current, total)) {
+            warn!("Failed to emit {} progress event: {}", label, e);
+        }
+        tracker.report(current, total, file);
     })
 }
 
 /// Build the Weaver Nest optimized resource pack
 ///
+/// Progress arrives as "build-progress" events with (files copied, total),
+/// and as "build:progress" events with the current file and ETA. Passing a
+/// `job_id` from `create_job` lets `cancel_job` stop the build; what it
+/// staged stays for `resume_interrupted_build`.
+///
 /// # Errors
-/// - VALIDATION_ERROR: Invalid input parameters
+/// - VALIDATION_ERROR: Invalid input parameters, or overrides the asset index can't satisfy
 /// - SCAN_ERROR: Failed to scan packs
 /// - BUILD_ERROR: Failed to build output pack
-pub fn build_weaver_nest_impl(request: BuildWeaverNestRequest) -> Result<String, AppError> {
+/// - CANCELLED: The job was cancelled
+pub fn build_weaver_nest_impl(
+    window: tauri::Window,
+    request: BuildWeaverNestRequest,
+    job_id: Option<String>,
+) -> Result<String, AppError> {
+    let progress = build_progress_emitter(window);
+    let job = jobs::start_job(job_id.as_deref());
+    run_build(request, false, Some(&progress), Some(job.token()), true)
+}
+
+/// Build into a staging folder under a journal, then move the result into place
+///
+/// # Arguments
+/// * `request` - Build request
+/// * `resume` - Keep files staged by an interrupted build instead of starting over
+/// * `progress` - Called with (files copied, total, file) while copying
+/// * `cancel` - Stop between files once set
+/// * `run_hooks` - Run the configured post-build hooks after installing
+pub(crate) fn run_build(
+    request: BuildWeaverNestRequest,
+    resume: bool,
+    progress: Option<FileProgress>,
+    cancel: Option<&jobs::CancelToken>,
+    run_hooks: bool,
+) -> Result<String, AppError> {
     // Validate all inputs in one call
     validation::validate_build_request(
         &request.packs_dir,
@@ -96,9 +329,31 @@ pub fn build_weaver_nest_impl(request: BuildWeaverNestRequest) -> Result<String,
         &request.overrides,
         &request.output_dir,
     )?;
+    validation::validate_disabled_packs(
+        &request.overrides,
+        &request.pack_order,
+        &request.disabled_packs,
+    )?;
+    validation::validate_path_access(&request.packs_dir, "Packs directory")?;
+    validation::validate_path_access(&request.output_dir, "Output directory")?;
+
+    // Fail early rather than partway through copying
+    let output_parent = Path::new(&request.output_dir)
+        .parent()
+        .filter(|parent| parent.is_dir());
+    if let Some(parent) = output_parent {
+        if !cloud_sync::is_writable(parent) {
+            return Err(AppError::validation(format!(
+                "Cannot write to {} (read-only folder). Choose another output location.",
+                parent.display()
+            ))
+            .with_hint(crate::error::ErrorHint::ChooseAnotherFolder)
+            .with_path(parent.to_string_lossy()));
+        }
+    }
 
     // Scan packs
-    let packs = pack_scanner::scan_packs(&request.packs_dir)
+    let mut packs = pack_scanner::scan_packs(&request.packs_dir)
         .map_err(|e| AppError::scan(format!("Pack scanning failed: {}", e)))?;
 
     if packs.is_empty() {
@@ -108,22 +363,746 @@ pub fn build_weaver_nest_impl(request: BuildWeaverNestRequest) -> Result<String,
     // Index assets
     let (assets, providers) = asset_indexer::index_assets(&packs)
         .map_err(|e| AppError::scan(format!("Asset indexing failed: {}", e)))?;
+    validation::validate_overrides_against_index(&request.overrides, &providers)?;
+
+    // Leave disabled packs and the overrides picking them out of the winners;
+    // they stay in the project's order and the index
+    let enabled_order = projects::enabled_pack_order(&request.pack_order, &request.disabled_packs);
+    let active_overrides = projects::active_overrides(&request.overrides, &request.disabled_packs);
+    let providers = projects::without_disabled_packs(&providers, &request.disabled_packs);
+
+    // Keep excluded namespaces from packs that don't own them
+    let providers =
+        namespace_conflicts::restrict_namespace_providers(&providers, &request.namespace_owners);
+
+    // Tag patch packs to warn about merging them without their base
+    let vanilla_dir = vanilla_textures::get_vanilla_cache_dir().ok();
+    pack_order::tag_patch_packs(&mut packs, &providers, vanilla_dir.as_deref());
+
+    // Pull the rest of each overridden block's textures from the same pack
+    let overrides = if request.include_override_dependencies {
+        let vanilla_pack = create_vanilla_pack()?;
+        override_closure::expand_override_dependencies(
+            &active_overrides,
+            &packs,
+            &vanilla_pack,
+            &providers,
+        )
+    } else {
+        active_overrides.clone()
+    };
+
+    // Keep other instances out of the output and its staging folder until
+    // the build is done
+    let output_path = Path::new(&request.output_dir);
+    let _output_lock = instance_lock::InstanceLock::acquire(
+        output_path,
+        &format!("building into {}", request.output_dir),
+    )
+    .map_err(|e| {
+        lock_aware(e, |e| {
+            AppError::build(format!("Failed to lock output: {}", e))
+        })
+    })?;
+
+    // Stage the build in the app cache; a leftover staging folder is only
+    // reused when resuming
+    let staging = build_journal::staging_dir_for(output_path)
+        .map_err(|e| AppError::validation(e.to_string()))?;
+    if !resume && staging.exists() {
+        file_retry::remove_dir_all_with_retry(&staging).map_err(|e| {
+            lock_aware(e, |e| {
+                AppError::io(format!("Failed to clear stale staging folder: {}", e))
+            })
+        })?;
+    }
+    let journal_dir = build_journal::default_journal_dir()
+        .map_err(|e| AppError::io(format!("Failed to get journal directory: {}", e)))?;
+    let request_json = serde_json::to_value(&request)
+        .map_err(|e| AppError::internal("Failed to serialize build request", e.to_string()))?;
+    let journal = build_journal::JournalWriter::start(
+        &journal_dir,
+        &request.output_dir,
+        &staging,
+        request_json,
+    )
+    .map_err(|e| AppError::build(format!("Failed to start build journal: {}", e)))?;
+    let staging_dir = staging.to_string_lossy().to_string();
 
     // Build Weaver Nest
-    weaver_nest::build_weaver_nest(
+    weaver_nest::build_weaver_nest_journaled(
         &packs,
         &assets,
         &providers,
-        &request.pack_order,
-        &request.overrides,
-        &request.output_dir,
+        &enabled_order,
+        &overrides,
+        &staging_dir,
+        Some(&journal),
+        resume,
+        progress,
+        cancel,
     )
-    .map_err(|e| AppError::build(format!("Weaver Nest generation failed: {}", e)))?;
+    .map_err(|e| {
+        lock_aware(e, |e| {
+            AppError::build(format!("Weaver Nest generation failed: {}", e))
+        })
+    })?;
+    let merged_texts = text_assets::write_merged_text_assets(
+        &packs,
+        &assets,
+        &providers,
+        &enabled_order,
+        &request.merged_text_assets,
+        &staging,
+        Some(&journal),
+    )
+    .map_err(|e| lock_aware(e, |e| AppError::build(format!("Text merge failed: {}", e))))?;
+
+    let mut log = vec![format!(
+        "Weaver Nest built successfully with {} assets",
+        assets.len()
+    )];
+    if overrides.len() > active_overrides.len() {
+        log.push(format!(
+            "Took {} texture(s) from the same pack as overridden blocks",
+            overrides.len() - active_overrides.len()
+        ));
+    }
+    if !request.disabled_packs.is_empty() {
+        log.push(format!(
+            "Left out {} disabled pack(s) and {} override(s) picking them",
+            request.disabled_packs.len(),
+            request.overrides.len() - active_overrides.len()
+        ));
+    }
+    if resume {
+        log.push(format!(
+            "Resumed interrupted build ({} file(s) written this run)",
+            journal.files_written()
+        ));
+    }
+    if merged_texts > 0 {
+        log.push(format!("Merged lines of {} text file(s)", merged_texts));
+    }
+
+    // Fill in the pack.mcmeta description from the project's or default template
+    let template = request.description_template.clone().or_else(|| {
+        settings::load_settings()
+            .ok()
+            .and_then(|settings| settings.description_template)
+    });
+    if let Some(template) = template.filter(|template| !template.trim().is_empty()) {
+        let context = pack_description::DescriptionContext {
+            project_name: request.project_name.clone().unwrap_or_default(),
+            pack_names: enabled_order
+                .iter()
+                .filter_map(|id| packs.iter().find(|p| &p.id == id))
+                .map(|p| p.name.clone())
+                .collect(),
+            built_at: std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .map_or(0, |d| d.as_secs()),
+        };
+        let described = pack_description::render_description(&template, &context)
+            .and_then(|description| pack_description::apply_description(&staging, &description));
+        if let Err(e) = described {
+            log.push(format!("Warning: Description template not applied: {}", e));
+        }
+    }
+    journal.set_phase(BuildPhase::PostProcessing);
+
+    // Summarize the mods the merged packs need
+    let merged_packs: Vec<&PackMeta> = packs
+        .iter()
+        .filter(|p| enabled_order.contains(&p.id))
+        .collect();
+    let requirements = feature_requirements::collect_requirements(&merged_packs);
+    log.push(requirements.summary);
+    log.extend(requirements.notes);
+    log.extend(pack_order::missing_patch_bases(&packs, &enabled_order));
+    log.extend(
+        validation::pack_format_issues(&packs, &enabled_order)
+            .into_iter()
+            .map(|issue| format!("Warning: {}", issue.message)),
+    );
+
+    // Point out splashes and credits that hide other packs' copies
+    let text_conflicts = text_assets::text_conflicts(
+        &providers,
+        &enabled_order,
+        &overrides,
+        &request.merged_text_assets,
+    );
+    log.extend(text_conflicts.iter().map(|conflict| {
+        format!(
+            "Warning: {} from {} replaces the one in {} (merge its lines to keep both)",
+            conflict.asset_id,
+            conflict.winner,
+            conflict.replaced.join(", ")
+        )
+    }));
+
+    // Verify every texture .mcmeta sits next to the texture it was made for
+    let mcmeta_issues: Vec<_> =
+        mcmeta_pairs::check_mcmeta_pairs(&assets, &providers, &enabled_order, &overrides)
+            .into_iter()
+            .chain(mcmeta_pairs::check_output_mcmeta(&staging))
+            .collect();
+    if !mcmeta_issues.is_empty() {
+        log.push(format!(
+            "{} texture .mcmeta file(s) don't match their texture:",
+            mcmeta_issues.len()
+        ));
+        log.extend(
+            mcmeta_issues
+                .iter()
+                .take(MAX_LOGGED_MCMETA_WARNINGS)
+                .map(|issue| format!("  Warning: {}", issue.message)),
+        );
+        if mcmeta_issues.len() > MAX_LOGGED_MCMETA_WARNINGS {
+            log.push(format!(
+                "  ...and {} more",
+                mcmeta_issues.len() - MAX_LOGGED_MCMETA_WARNINGS
+            ));
+        }
+    }
+
+    // Warn about textures that will break mipmaps in game
+    match pack_health::check_output_mipmaps(&staging) {
+        Ok(issues) if !issues.is_empty() => {
+            log.push(format!(
+                "{} texture(s) may render badly with mipmaps:",
+                issues.len()
+            ));
+            log.extend(
+                issues
+                    .iter()
+                    .take(MAX_LOGGED_MIPMAP_WARNINGS)
+                    .map(|issue| format!("  Warning: {}", issue.message)),
+            );
+            if issues.len() > MAX_LOGGED_MIPMAP_WARNINGS {
+                log.push(format!(
+                    "  ...and {} more",
+                    issues.len() - MAX_LOGGED_MIPMAP_WARNINGS
+                ));
+            }
+        }
+        Ok(_) => {}
+        Err(e) => log.push(format!("Skipped mipmap check: {}", e)),
+    }
+
+    // Optionally recolor transparent pixels so they don't darken mipmaps
+    if request.fix_alpha_bleed {
+        match alpha_bleed::fix_output_alpha_bleed(&staging) {
+            Ok(fixed) => log.push(format!("Fixed alpha bleeding in {} texture(s)", fixed)),
+            Err(e) => log.push(format!("Alpha bleed fix failed: {}", e)),
+        }
+    }
+
+    let fingerprint = install_status::input_fingerprint(
+        &request.packs_dir,
+        &enabled_order,
+        &active_overrides,
+        &request.namespace_owners,
+        request.fix_alpha_bleed,
+        request.include_override_dependencies,
+        &request.merged_text_assets,
+    );
+
+    // Don't swap files under a game running from the output's instance
+    // unless the user chose to
+    let games = running_game::games_using(&running_game::list_running_games(), output_path);
+    if !games.is_empty() {
+        let policy = settings::load_settings()
+            .map(|settings| settings.running_game)
+            .unwrap_or_default();
+        match policy {
+            RunningGamePolicy::Warn => {
+                log.push("Warning: Minecraft is running from this instance".to_string());
+                log.push("  Press F3+T in game to reload packs".to_string());
+            }
+            RunningGamePolicy::Defer => {
+                journal.set_phase(BuildPhase::WaitingForGame);
+                running_game::wait_for_games_to_exit(output_path, cancel)
+                    .map_err(|e| lock_aware(e, |e| AppError::build(e.to_string())))?;
+                log.push("Installed after Minecraft exited".to_string());
+            }
+            RunningGamePolicy::Queue => {
+                let queued = running_game::QueuedInstall {
+                    output_dir: request.output_dir.clone(),
+                    staging_dir: staging_dir.clone(),
+                    packs_dir: request.packs_dir.clone(),
+                    input_fingerprint: fingerprint,
+                    queued_at: std::time::SystemTime::now()
+                        .duration_since(std::time::UNIX_EPOCH)
+                        .map_or(0, |d| d.as_secs()),
+                };
+                running_game::default_queue_path()
+                    .and_then(|queue_path| running_game::queue_install(&queue_path, queued))
+                    .map_err(|e| AppError::io(format!("Failed to queue install: {}", e)))?;
+                if let Err(e) = journal.complete() {
+                    warn!("Failed to remove build journal: {}", e);
+                }
+                activity_log::record(
+                    &request.packs_dir,
+                    request.project_name.as_deref(),
+                    "queueInstall",
+                    serde_json::json!({ "outputDir": request.output_dir }),
+                    None,
+                );
+                log.push("Minecraft is running from this instance".to_string());
+                log.push("  The install is queued until the game exits".to_string());
+                return Ok(log.join("\n"));
+            }
+        }
+    }
+
+    // Move the finished build into the output folder
+    journal.set_phase(BuildPhase::Finalizing);
+    build_journal::promote_staging(&staging, output_path).map_err(|e| {
+        lock_aware(e, |e| {
+            AppError::build(format!("Failed to move build into place: {}", e))
+        })
+    })?;
+    if let Err(e) = journal.complete() {
+        warn!("Failed to remove build journal: {}", e);
+    }
+
+    // Stamp the build so installed copies can be checked for staleness
+    let stamped = install_status::default_stamp_dir().and_then(|stamp_dir| {
+        install_status::record_build_stamp(&stamp_dir, &request.output_dir, fingerprint)
+    });
+    if let Err(e) = stamped {
+        log.push(format!("Failed to record build stamp: {}", e));
+    }
+
+    activity_log::record(
+        &request.packs_dir,
+        request.project_name.as_deref(),
+        if resume { "resumeBuild" } else { "build" },
+        serde_json::json!({
+            "outputDir": request.output_dir,
+            "packOrder": request.pack_order,
+            "overrides": request.overrides.len(),
+        }),
+        None,
+    );
+
+    // Run post-build hooks; their output is appended to the build log
+    match settings::load_settings() {
+        Ok(settings) if run_hooks => log.extend(post_build::run_post_build_hooks(
+            &settings.post_build_hooks,
+            output_path,
+        )),
+        Ok(settings) if !settings.post_build_hooks.is_empty() => {
+            log.push("Skipped post-build hooks for a remote build".to_string())
+        }
+        Ok(_) => {}
+        Err(e) => log.push(format!("Skipped post-build hooks: {}", e)),
+    }
+
+    Ok(log.join("\n"))
+}
+
+/// List builds that were interrupted (crash, power loss, app closed mid-build)
+///
+/// # Returns
+/// Journals of unfinished builds, most recent first
+pub fn list_interrupted_builds_impl() -> Result<Vec<InterruptedBuild>, AppError> {
+    let journal_dir = build_journal::default_journal_dir()
+        .map_err(|e| AppError::io(format!("Failed to get journal directory: {}", e)))?;
+    Ok(build_journal::list_interrupted_builds(&journal_dir))
+}
+
+/// Remove an interrupted build's staging folder and journal
+///
+/// The output folder itself is left untouched.
+///
+/// # Arguments
+/// * `output_dir` - Output folder of the interrupted build
+///
+/// # Returns
+/// True if an interrupted build was found
+pub fn discard_interrupted_build_impl(output_dir: String) -> Result<bool, AppError> {
+    let journal_dir = build_journal::default_journal_dir()
+        .map_err(|e| AppError::io(format!("Failed to get journal directory: {}", e)))?;
+    let _output_lock = instance_lock::InstanceLock::acquire(
+        Path::new(&output_dir),
+        &format!("discarding the interrupted build of {}", output_dir),
+    )
+    .map_err(|e| lock_aware(e, |e| AppError::io(format!("Failed to lock output: {}", e))))?;
+    let packs_dir = build_journal::read_journal(&journal_dir, &output_dir)
+        .and_then(|journal| journal.request["packs_dir"].as_str().map(str::to_string));
+    let discarded = build_journal::discard_interrupted_build(&journal_dir, &output_dir)
+        .map_err(|e| AppError::io(format!("Failed to discard interrupted build: {}", e)))?;
+    if let (true, Some(packs_dir)) = (discarded, packs_dir) {
+        activity_log::record(
+            &packs_dir,
+            None,
+            "discardInterruptedBuild",
+            serde_json::json!({ "outputDir": output_dir }),
+            None,
+        );
+    }
+    Ok(discarded)
+}
+
+/// Clear a lock left behind by a crashed or killed task
+///
+/// For INSTANCE_BUSY errors whose holder is gone but couldn't be detected
+/// as gone (e.g. it ran on another machine sharing the folder). Only lock
+/// files can be removed, and never one this app is holding.
+///
+/// # Arguments
+/// * `lock_path` - Lock file from the INSTANCE_BUSY error's path
+/// * `force` - Remove it even if the holder still looks alive
+///
+/// # Returns
+/// True if a lock was removed
+///
+/// # Errors
+/// - VALIDATION_ERROR: The path isn't a lock file
+/// - INSTANCE_BUSY: The lock is still held (and `force` isn't set)
+pub fn clear_stale_lock_impl(lock_path: String, force: Option<bool>) -> Result<bool, AppError> {
+    let path = Path::new(&lock_path);
+    if !instance_lock::is_lock_file(path) {
+        return Err(AppError::validation(format!(
+            "Not a lock file: {}",
+            lock_path
+        )));
+    }
+    instance_lock::clear_lock(path, force.unwrap_or(false)).map_err(|e| {
+        lock_aware(e, |e| {
+            AppError::io(format!("Failed to clear lock: {}", e)).with_path(&lock_path)
+        })
+    })
+}
+
+/// Resume an interrupted build, keeping the files it already staged
+///
+/// # Arguments
+/// * `output_dir` - Output folder of the interrupted build
+/// * `job_id` - Job from `create_job` that `cancel_job` can stop
+/// * `run_hooks` - Run post-build hooks once the build is installed
+///
+/// # Returns
+/// Build log, as for a regular build
+pub fn resume_interrupted_build_impl(
+    output_dir: String,
+    job_id: Option<String>,
+    run_hooks: bool,
+) -> Result<String, AppError> {
+    let journal_dir = build_journal::default_journal_dir()
+        .map_err(|e| AppError::io(format!("Failed to get journal directory: {}", e)))?;
+    let journal = build_journal::read_journal(&journal_dir, &output_dir).ok_or_else(|| {
+        AppError::validation(format!("No interrupted build found for {}", output_dir))
+    })?;
+    let request: BuildWeaverNestRequest = serde_json::from_value(journal.request)
+        .map_err(|e| AppError::internal("Failed to read saved build request", e.to_string()))?;
+
+    let job = jobs::start_job(job_id.as_deref());
+    run_build(request, true, None, Some(job.token()), run_hooks)
+}
+
+/// List running Minecraft processes
+///
+/// # Arguments
+/// * `target_dir` - Only games that may be using this output folder
+pub fn get_running_games_impl(target_dir: Option<String>) -> Result<Vec<RunningGame>, AppError> {
+    let games = running_game::list_running_games();
+    Ok(match target_dir {
+        Some(target_dir) => running_game::games_using(&games, Path::new(&target_dir)),
+        None => games,
+    })
+}
+
+/// List builds waiting for Minecraft to exit before being installed
+pub fn list_queued_installs_impl() -> Result<Vec<QueuedInstall>, AppError> {
+    let queue_path = running_game::default_queue_path()
+        .map_err(|e| AppError::io(format!("Failed to get install queue: {}", e)))?;
+    Ok(running_game::load_queued_installs(&queue_path))
+}
+
+/// Move a queued build into its output folder and stamp it
+fn apply_queued_install(install: &QueuedInstall) -> Result<(), AppError> {
+    let output_path = Path::new(&install.output_dir);
+    let _output_lock = instance_lock::InstanceLock::acquire(
+        output_path,
+        &format!("installing into {}", install.output_dir),
+    )
+    .map_err(|e| {
+        lock_aware(e, |e| {
+            AppError::build(format!("Failed to lock output: {}", e))
+        })
+    })?;
+
+    build_journal::promote_staging(Path::new(&install.staging_dir), output_path).map_err(|e| {
+        lock_aware(e, |e| {
+            AppError::build(format!("Failed to move build into place: {}", e))
+        })
+    })?;
+    let stamped = install_status::default_stamp_dir().and_then(|stamp_dir| {
+        install_status::record_build_stamp(
+            &stamp_dir,
+            &install.output_dir,
+            install.input_fingerprint.clone(),
+        )
+    });
+    if let Err(e) = stamped {
+        warn!("Failed to record build stamp: {}", e);
+    }
+
+    activity_log::record(
+        &install.packs_dir,
+        None,
+        "applyQueuedInstall",
+        serde_json::json!({ "outputDir": install.output_dir }),
+        None,
+    );
+    Ok(())
+}
+
+/// Install queued builds whose game has exited
+///
+/// Builds whose instance still has a game running stay queued. Builds whose
+/// staged files are gone (e.g. replaced by a later build) are dropped.
+///
+/// # Returns
+/// A log line per queued build
+///
+/// # Errors
+/// - IO_ERROR: Failed to read or update the queue
+pub fn apply_queued_installs_impl() -> Result<Vec<String>, AppError> {
+    let queue_path = running_game::default_queue_path()
+        .map_err(|e| AppError::io(format!("Failed to get install queue: {}", e)))?;
+    let games = running_game::list_running_games();
+
+    let mut log = Vec::new();
+    for install in running_game::load_queued_installs(&queue_path) {
+        if !running_game::games_using(&games, Path::new(&install.output_dir)).is_empty() {
+            log.push(format!(
+                "Still waiting for Minecraft to exit: {}",
+                install.output_dir
+            ));
+            continue;
+        }
+
+        let result = if Path::new(&install.staging_dir).is_dir() {
+            apply_queued_install(&install)
+        } else {
+            Err(AppError::validation("Staged build no longer exists"))
+        };
+        running_game::take_queued_install(&queue_path, &install.output_dir)
+            .map_err(|e| AppError::io(format!("Failed to update install queue: {}", e)))?;
+        match result {
+            Ok(()) => log.push(format!("Installed {}", install.output_dir)),
+            Err(e) => log.push(format!("Dropped {}: {}", install.output_dir, e.message)),
+        }
+    }
+    Ok(log)
+}
+
+/// Drop a queued build without installing it
+///
+/// # Returns
+/// True if a build was queued for the output
+pub fn discard_queued_install_impl(output_dir: String) -> Result<bool, AppError> {
+    let queue_path = running_game::default_queue_path()
+        .map_err(|e| AppError::io(format!("Failed to get install queue: {}", e)))?;
+    let taken = running_game::take_queued_install(&queue_path, &output_dir)
+        .map_err(|e| AppError::io(format!("Failed to update install queue: {}", e)))?;
+
+    if let Some(install) = &taken {
+        let staging = Path::new(&install.staging_dir);
+        if staging.exists() {
+            file_retry::remove_dir_all_with_retry(staging).map_err(|e| {
+                lock_aware(e, |e| {
+                    AppError::io(format!("Failed to remove staged build: {}", e))
+                })
+            })?;
+        }
+    }
+    Ok(taken.is_some())
+}
+
+/// Check the packs folder for cloud sync, network and read-only problems
+///
+/// # Arguments
+/// * `packs_dir` - Directory containing resource packs
+///
+/// # Returns
+/// Detected sync client, placeholder packs and warnings
+pub fn inspect_packs_folder_impl(packs_dir: String) -> Result<FolderAccessReport, AppError> {
+    validation::validate_directory(&packs_dir, "Packs directory")?;
+    validation::validate_path_access(&packs_dir, "Packs directory")?;
+
+    Ok(cloud_sync::inspect_packs_folder(Path::new(&packs_dir)))
+}
+
+/// Ask the sync client to download packs that are only in the cloud
+///
+/// Rescan afterwards; iCloud downloads finish in the background.
+///
+/// # Arguments
+/// * `packs_dir` - Directory containing resource packs
+///
+/// # Returns
+/// Download outcome for each placeholder pack
+pub fn download_cloud_placeholders_impl(
+    packs_dir: String,
+) -> Result<Vec<PlaceholderDownload>, AppError> {
+    validation::validate_directory(&packs_dir, "Packs directory")?;
+    validation::validate_path_access(&packs_dir, "Packs directory")?;
+
+    let report = cloud_sync::inspect_packs_folder(Path::new(&packs_dir));
+    Ok(cloud_sync::download_placeholders(&report.placeholders))
+}
+
+/// Summarize the mods a merge of the given packs would need
+///
+/// # Arguments
+/// * `packs_dir` - Directory containing resource packs
+/// * `pack_order` - Packs included in the merge
+///
+/// # Returns
+/// Per-feature requirements and a one-line summary
+pub fn get_feature_requirements_impl(
+    packs_dir: String,
+    pack_order: Vec<String>,
+) -> Result<RequirementsSummary, AppError> {
+    validation::validate_directory(&packs_dir, "Packs directory")?;
+    validation::validate_path_access(&packs_dir, "Packs directory")?;
+
+    let packs = pack_scanner::scan_packs(&packs_dir)
+        .map_err(|e| AppError::scan(format!("Pack scanning failed: {}", e)))?;
+    let merged_packs: Vec<&PackMeta> = packs
+        .iter()
+        .filter(|p| pack_order.contains(&p.id))
+        .collect();
+
+    Ok(feature_requirements::collect_requirements(&merged_packs))
+}
+
+/// Check packs for textures that break mipmaps
+///
+/// # Arguments
+/// * `packs_dir` - Directory containing resource packs
+/// * `pack_ids` - Packs to check (all packs if None)
+///
+/// # Returns
+/// Health report with warnings for each checked pack
+pub fn get_pack_health_report_impl(
+    packs_dir: String,
+    pack_ids: Option<Vec<String>>,
+) -> Result<Vec<PackHealthReport>, AppError> {
+    validation::validate_directory(&packs_dir, "Packs directory")?;
+    validation::validate_path_access(&packs_dir, "Packs directory")?;
+
+    let packs = pack_scanner::scan_packs(&packs_dir)
+        .map_err(|e| AppError::scan(format!("Pack scanning failed: {}", e)))?;
+
+    packs
+        .iter()
+        .filter(|p| pack_ids.as_ref().map_or(true, |ids| ids.contains(&p.id)))
+        .map(|pack| {
+            pack_health::check_pack_health(pack)
+                .map_err(|e| AppError::scan(format!("Failed to check pack {}: {}", pack.id, e)))
+        })
+        .collect()
+}
+
+/// Suggest a starting pack order from coverage and addon detection
+///
+/// # Arguments
+/// * `packs_dir` - Directory containing resource packs
+/// * `pack_ids` - Packs to order (all packs if None)
+///
+/// # Returns
+/// Suggested order (addons on top, full-coverage packs at the bottom) with
+/// the stats behind each placement and each pack's closest match in style
+///
+/// # Errors
+/// - VALIDATION_ERROR: Packs directory is invalid
+/// - SCAN_ERROR: Failed to scan or index packs
+pub fn suggest_pack_order_impl(
+    packs_dir: String,
+    pack_ids: Option<Vec<String>>,
+) -> Result<pack_order::PackOrderSuggestion, AppError> {
+    validation::validate_directory(&packs_dir, "Packs directory")?;
+    validation::validate_path_access(&packs_dir, "Packs directory")?;
+
+    let packs: Vec<PackMeta> = pack_scanner::scan_packs(&packs_dir)
+        .map_err(|e| AppError::scan(format!("Pack scanning failed: {}", e)))?
+        .into_iter()
+        .filter(|p| pack_ids.as_ref().map_or(true, |ids| ids.contains(&p.id)))
+        .collect();
+    let (_, providers) = asset_indexer::index_assets(&packs)
+        .map_err(|e| AppError::scan(format!("Asset indexing failed: {}", e)))?;
+    let vanilla_dir = vanilla_textures::get_vanilla_cache_dir().ok();
+
+    let mut suggestion = pack_order::suggest_pack_order(&packs, &providers, vanilla_dir.as_deref());
+    // A pack whose palette can't be read just gets no style match
+    let palettes: Vec<PackPalette> = packs
+        .iter()
+        .filter_map(|pack| match palette::extract_pack_palette(pack) {
+            Ok(palette) => Some(palette),
+            Err(e) => {
+                warn!("Failed to extract palette of {}: {}", pack.id, e);
+                None
+            }
+        })
+        .collect();
+    pack_order::add_style_matches(&mut suggestion, &palettes);
+    Ok(suggestion)
+}
+
+/// Extract the dominant color palette of each pack
+///
+/// # Arguments
+/// * `packs_dir` - Directory containing resource packs
+/// * `pack_ids` - Packs to include (all packs if None)
+///
+/// # Returns
+/// Palette, average color, brightness and saturation per pack
+pub fn get_pack_palettes_impl(
+    packs_dir: String,
+    pack_ids: Option<Vec<String>>,
+) -> Result<Vec<PackPalette>, AppError> {
+    validation::validate_directory(&packs_dir, "Packs directory")?;
+    validation::validate_path_access(&packs_dir, "Packs directory")?;
 
-    Ok(format!(
-        "Weaver Nest built successfully with {} assets",
-        assets.len()
-    ))
+    let packs = pack_scanner::scan_packs(&packs_dir)
+        .map_err(|e| AppError::scan(format!("Pack scanning failed: {}", e)))?;
+
+    packs
+        .iter()
+        .filter(|p| pack_ids.as_ref().map_or(true, |ids| ids.contains(&p.id)))
+        .map(|pack| {
+            palette::extract_pack_palette(pack).map_err(|e| {
+                AppError::scan(format!("Failed to extract palette of {}: {}", pack.id, e))
+            })
+        })
+        .collect()
+}
+
+/// Compute the SHA-1 of a built pack for server distribution
+///
+/// # Arguments
+/// * `output_path` - Built pack folder or zip
+///
+/// # Returns
+/// SHA-1 hex digest and how long it took
+pub fn compute_output_sha1_impl(output_path: String) -> Result<Sha1Result, AppError> {
+    validation::validate_path_access(&output_path, "Output path")?;
+    if !Path::new(&output_path).exists() {
+        return Err(AppError::validation(format!(
+            "Output path does not exist: {}",
+            output_path
+        )));
+    }
+
+    content_hash::sha1_on_demand(Path::new(&output_path))
+        .map_err(|e| AppError::io(format!("Failed to hash output: {}", e)))
 }
 
 /// Get the default Minecraft resourcepacks directory
@@ -140,30 +1119,35 @@ pub fn get_default_packs_dir_impl() -> Result<String, AppError> {
 ///
 /// # Arguments
 /// * `window` - Tauri window handle for emitting progress events
+/// * `job_id` - Job from `create_job` that `cancel_job` can stop
 ///
 /// # Returns
 /// Path to the vanilla textures cache directory
-pub fn initialize_vanilla_textures_impl(window: tauri::Window) -> Result<String, AppError> {
-    use std::sync::Arc;
-    use tauri::Emitter;
+pub fn initialize_vanilla_textures_impl(
+    window: Option<tauri::Window>,
+    job_id: Option<String>,
+) -> Result<String, AppError> {
+    let progress_callback =
+        window.map(|w| vanilla_progress_callback(w, "initialize_vanilla_textures"));
+    let job = jobs::start_job(job_id.as_deref());
 
-    // Create progress callback that emits events to the frontend
-    let progress_callback = Arc::new(move |current: usize, total: usize| {
-        println!(
-            "[initialize_vanilla_textures] Emitting progress: {}/{}",
-            current, total
-        );
-        if let Err(e) = window.emit("vanilla-texture-progress", (current, total)) {
-            eprintln!(
-                "[initialize_vanilla_textures] Failed to emit progress event: {}",
-                e
-            );
-        }
-    });
+    vanilla_textures::initialize_vanilla_textures_with_progress(
+        progress_callback,
+        Some(job.token()),
+    )
+    .map(|p| p.to_string_lossy().to_string())
+    .map_err(|e| {
+        lock_aware(e, |e| {
+            AppError::io(format!("Failed to initialize vanilla textures: {}", e))
+        })
+    })
+}
 
-    vanilla_textures::initialize_vanilla_textures_with_progress(Some(progress_callback))
+/// Path of the missing-texture placeholder that models point broken references at
+fn missing_texture_path() -> Result<String, AppError> {
+    missing_texture::missing_texture_path()
         .map(|p| p.to_string_lossy().to_string())
-        .map_err(|e| AppError::io(format!("Failed to initialize vanilla textures: {}", e)))
+        .map_err(|e| AppError::io(format!("Failed to write missing texture: {}", e)))
 }
 
 /// Get the path to a vanilla texture file
@@ -174,6 +1158,10 @@ pub fn initialize_vanilla_textures_impl(window: tauri::Window) -> Result<String,
 /// # Returns
 /// Absolute path to the texture PNG file
 pub fn get_vanilla_texture_path_impl(asset_id: String) -> Result<String, AppError> {
+    if asset_id == missing_texture::MISSING_TEXTURE_ID {
+        return missing_texture_path();
+    }
+
     vanilla_textures::get_vanilla_texture_path(&asset_id)
         .map(|p| p.to_string_lossy().to_string())
         .map_err(|e| AppError::io(format!("Vanilla texture not found: {}", e)))
@@ -229,40 +1217,32 @@ pub fn get_cached_vanilla_version_impl() -> Result<Option<String>, AppError> {
 /// # Arguments
 /// * `version` - Version identifier (e.g., "1.21.4")
 /// * `window` - Tauri window handle for emitting progress events
+/// * `job_id` - Job from `create_job` that `cancel_job` can stop
 ///
 /// # Returns
 /// Path to the vanilla textures cache directory
 pub fn set_vanilla_texture_version_impl(
     version: String,
-    window: tauri::Window,
+    window: Option<tauri::Window>,
+    job_id: Option<String>,
 ) -> Result<String, AppError> {
-    use std::sync::Arc;
-    use tauri::Emitter;
-
-    // Create progress callback that emits events to the frontend
-    let progress_callback = Arc::new(move |current: usize, total: usize| {
-        println!(
-            "[set_vanilla_texture_version] Emitting progress: {}/{}",
-            current, total
-        );
-        if let Err(e) = window.emit("vanilla-texture-progress", (current, total)) {
-            eprintln!(
-                "[set_vanilla_texture_version] Failed to emit progress event: {}",
-                e
-            );
-        }
-    });
+    let progress_callback =
+        window.map(|w| vanilla_progress_callback(w, "set_vanilla_texture_version"));
+    let job = jobs::start_job(job_id.as_deref());
 
     vanilla_textures::extract_vanilla_textures_for_version_with_progress(
         &version,
-        Some(progress_callback),
+        progress_callback,
+        Some(job.token()),
     )
     .map(|p| p.to_string_lossy().to_string())
     .map_err(|e| {
-        AppError::io(format!(
-            "Failed to extract vanilla textures for version {}: {}",
-            version, e
-        ))
+        lock_aware(e, |e| {
+            AppError::io(format!(
+                "Failed to extract vanilla textures for version {}: {}",
+                version, e
+            ))
+        })
     })
 }
 
@@ -295,11 +1275,16 @@ pub fn get_suggested_minecraft_paths_impl() -> Result<Vec<String>, AppError> {
 pub fn initialize_vanilla_textures_from_custom_dir_impl(
     minecraft_dir: String,
 ) -> Result<String, AppError> {
+    validation::validate_path_access(&minecraft_dir, "Minecraft directory")?;
     let path = PathBuf::from(minecraft_dir);
 
     vanilla_textures::initialize_vanilla_textures_from_dir(&path)
         .map(|p| p.to_string_lossy().to_string())
-        .map_err(|e| AppError::io(format!("Failed to initialize vanilla textures: {}", e)))
+        .map_err(|e| {
+            lock_aware(e, |e| {
+                AppError::io(format!("Failed to initialize vanilla textures: {}", e))
+            })
+        })
 }
 
 /// Detect all Minecraft launchers on the system
@@ -318,6 +1303,7 @@ pub fn detect_launchers_impl() -> Result<Vec<launcher_detection::LauncherInfo>,
 /// # Returns
 /// Launcher information if valid Minecraft directory
 pub fn identify_launcher_impl(path: String) -> Result<launcher_detection::LauncherInfo, AppError> {
+    validation::validate_path_access(&path, "Launcher directory")?;
     let path_buf = PathBuf::from(&path);
 
     // Validate it's a Minecraft directory
@@ -349,6 +1335,7 @@ pub fn identify_launcher_impl(path: String) -> Result<launcher_detection::Launch
 pub fn get_launcher_resourcepacks_dir_impl(
     launcher_info: launcher_detection::LauncherInfo,
 ) -> Result<String, AppError> {
+    validation::validate_path_access(&launcher_info.minecraft_dir, "Launcher directory")?;
     let launcher_dir = PathBuf::from(&launcher_info.minecraft_dir);
 
     let resourcepacks_dir =
@@ -358,6 +1345,183 @@ pub fn get_launcher_resourcepacks_dir_impl(
     Ok(resourcepacks_dir.to_string_lossy().to_string())
 }
 
+/// List the Modrinth App's profiles with their resourcepacks folders
+///
+/// # Arguments
+/// * `profiles_dir` - The app's profiles folder (detected if None)
+///
+/// # Returns
+/// Profiles sorted by name; empty if the Modrinth App isn't found
+pub fn list_modrinth_profiles_impl(
+    profiles_dir: Option<String>,
+) -> Result<Vec<ModrinthProfile>, AppError> {
+    let profiles_dir = match profiles_dir {
+        Some(dir) => {
+            validation::validate_directory(&dir, "Profiles directory")?;
+            validation::validate_path_access(&dir, "Profiles directory")?;
+            PathBuf::from(dir)
+        }
+        None => match launcher_detection::detect_all_launchers()
+            .into_iter()
+            .find(|l| l.found && l.launcher_type == launcher_detection::LauncherType::Modrinth)
+        {
+            Some(launcher) => PathBuf::from(launcher.minecraft_dir),
+            None => return Ok(Vec::new()),
+        },
+    };
+
+    Ok(modrinth_profiles::list_profiles(&profiles_dir))
+}
+
+/// Instances of a launcher folder, for launchers whose instances are listed
+fn scan_launcher_instances(
+    launcher_dir: &Path,
+    launcher_type: &launcher_detection::LauncherType,
+) -> Vec<LauncherInstance> {
+    use launcher_detection::LauncherType;
+
+    match launcher_type {
+        LauncherType::PrismLauncher | LauncherType::MultiMC => {
+            instances::scan_instances(launcher_dir)
+        }
+        LauncherType::CurseForge => curseforge_instances::scan_curseforge_instances(launcher_dir),
+        _ => Vec::new(),
+    }
+}
+
+/// List Prism Launcher, MultiMC and CurseForge instances with their
+/// resourcepacks folders
+///
+/// # Arguments
+/// * `instances_dir` - A launcher's folder or its instances folder (every
+///   detected Prism Launcher, MultiMC and CurseForge folder if None)
+///
+/// # Returns
+/// Instances sorted by name within each launcher; empty if none are found
+pub fn list_launcher_instances_impl(
+    instances_dir: Option<String>,
+) -> Result<Vec<LauncherInstance>, AppError> {
+    if let Some(dir) = instances_dir {
+        validation::validate_directory(&dir, "Instances directory")?;
+        validation::validate_path_access(&dir, "Instances directory")?;
+        // Anything but a CurseForge folder is read as Prism Launcher/MultiMC
+        let launcher_type = match launcher_detection::identify_launcher_from_path(Path::new(&dir)) {
+            Ok(launcher_detection::LauncherType::CurseForge) => {
+                launcher_detection::LauncherType::CurseForge
+            }
+            _ => launcher_detection::LauncherType::PrismLauncher,
+        };
+        return Ok(scan_launcher_instances(Path::new(&dir), &launcher_type));
+    }
+
+    Ok(launcher_detection::detect_all_launchers()
+        .into_iter()
+        .filter(|l| l.found)
+        .flat_map(|l| scan_launcher_instances(Path::new(&l.minecraft_dir), &l.launcher_type))
+        .collect())
+}
+
+/// Download a remote pack (e.g., a Modrinth file) into the packs folder
+///
+/// Large files arrive in ranged chunks, and a download interrupted by a
+/// network error carries on from the bytes already on disk when retried
+/// with the same URL. Emits "download:progress" events with bytes on disk,
+/// the total and the transfer rate. Cancelling the job removes the partial
+/// file.
+///
+/// # Arguments
+/// * `window` - Tauri window handle for emitting progress events
+/// * `url` - File to download
+/// * `packs_dir` - Directory containing resource packs
+/// * `file_name` - Name to save as (taken from the URL if None)
+/// * `sha1` - Expected SHA-1, verified once the download completes
+/// * `size` - Expected size in bytes, if known
+/// * `overwrite` - Replace a pack of the same name instead of failing
+/// * `job_id` - Job to check for cancellation
+///
+/// # Returns
+/// Path, size and SHA-1 of the downloaded pack
+///
+/// # Errors
+/// - VALIDATION_ERROR: Invalid URL, file name or packs directory, or the
+///   file exists and `overwrite` isn't set
+/// - IO_ERROR: Download failed or the file didn't match the expected hash
+/// - CANCELLED: The job was cancelled
+#[allow(clippy::too_many_arguments)]
+pub fn download_remote_pack_impl(
+    window: Option<tauri::Window>,
+    url: String,
+    packs_dir: String,
+    file_name: Option<String>,
+    sha1: Option<String>,
+    size: Option<u64>,
+    overwrite: Option<bool>,
+    job_id: Option<String>,
+) -> Result<remote_download::DownloadedFile, AppError> {
+    validation::validate_directory(&packs_dir, "Packs directory")?;
+    validation::validate_path_access(&packs_dir, "Packs directory")?;
+    if !remote_download::is_download_url(&url) {
+        return Err(AppError::validation(
+            "URL must start with http:// or https://",
+        ));
+    }
+    let file_name = file_name
+        .filter(|name| !name.trim().is_empty())
+        .or_else(|| remote_download::file_name_from_url(&url))
+        .ok_or_else(|| AppError::validation("Could not tell a file name from the URL"))?;
+    if file_name.contains(['/', '\\']) || file_name.starts_with('.') {
+        return Err(AppError::validation(format!(
+            "Invalid file name: {}",
+            file_name
+        )));
+    }
+    let dest = Path::new(&packs_dir).join(&file_name);
+    let overwrite = overwrite.unwrap_or(false);
+    if dest.exists() && !overwrite {
+        return Err(
+            AppError::validation(format!("A pack named {} already exists", file_name))
+                .with_path(dest.to_string_lossy()),
+        );
+    }
+    let job = jobs::start_job(job_id.as_deref());
+
+    let tracker =
+        window.map(|w| progress_tracker(w, progress::DOWNLOAD_PROGRESS_EVENT, "download"));
+    let download_progress = |downloaded: u64, total: u64, bytes_per_sec: u64| {
+        if let Some(tracker) = &tracker {
+            tracker.report_bytes(downloaded, total, bytes_per_sec, Some(&file_name));
+        }
+    };
+
+    let downloaded = remote_download::download_resumable(
+        &url,
+        &dest,
+        size,
+        sha1.as_deref(),
+        overwrite,
+        Some(job.token()),
+        Some(&download_progress),
+    )
+    .map_err(|e| {
+        lock_aware(e, |e| {
+            AppError::io(format!("Failed to download pack: {}", e))
+        })
+    })?;
+    info!(
+        "Downloaded {} ({} bytes, {} resumed)",
+        downloaded.path, downloaded.size, downloaded.resumed_from
+    );
+    activity_log::record(
+        &packs_dir,
+        None,
+        "downloadRemotePack",
+        serde_json::json!({ "url": url, "path": downloaded.path, "sha1": downloaded.sha1 }),
+        None,
+    );
+
+    Ok(downloaded)
+}
+
 /// Get the full path to a texture file from a resource pack
 ///
 /// # Arguments
@@ -374,19 +1538,22 @@ pub fn get_pack_texture_path_impl(
     version_folders: Option<Vec<String>>,
     app_handle: &tauri::AppHandle,
 ) -> Result<String, AppError> {
-    println!(
-        "[get_pack_texture_path] Loading texture: {} from pack: {} (is_zip: {})",
+    debug!(
+        "Loading texture: {} from pack: {} (is_zip: {})",
         asset_id, pack_path, is_zip
     );
 
+    if asset_id == missing_texture::MISSING_TEXTURE_ID {
+        return missing_texture_path();
+    }
+
+    validation::validate_path_access(&pack_path, "Pack path")?;
+
     // Parse asset ID: "minecraft:block/stone" -> "assets/minecraft/textures/block/stone.png"
     let texture_path = asset_id.strip_prefix("minecraft:").unwrap_or(&asset_id);
 
     let relative_path = format!("assets/minecraft/textures/{}.png", texture_path);
-    println!(
-        "[get_pack_texture_path] Looking for file: {}",
-        relative_path
-    );
+    debug!("Looking for file: {}", relative_path);
 
     let mut candidate_paths: Vec<String> = Vec::new();
     candidate_paths.push(relative_path.clone());
@@ -405,7 +1572,7 @@ pub fn get_pack_texture_path_impl(
         let zip_path_str = &pack_path;
 
         // Extract the texture bytes from ZIP (try version-folder candidates too).
-        println!("[get_pack_texture_path] Extracting from ZIP: {}", zip_path_str);
+        debug!("Extracting from ZIP: {}", zip_path_str);
         let mut chosen_rel: Option<String> = None;
         let mut bytes: Option<Vec<u8>> = None;
         for cand in &candidate_paths {
@@ -422,10 +1589,7 @@ pub fn get_pack_texture_path_impl(
             AppError::validation(format!("Texture not found in ZIP: {}", relative_path))
         })?;
         let chosen_rel = chosen_rel.unwrap_or(relative_path.clone());
-        println!(
-            "[get_pack_texture_path] Successfully extracted {} bytes",
-            bytes.len()
-        );
+        debug!("Successfully extracted {} bytes", bytes.len());
 
         // Create a cache directory for this ZIP using Tauri's cache directory
         use tauri::Manager;
@@ -435,7 +1599,7 @@ pub fn get_pack_texture_path_impl(
             .map_err(|e| AppError::io(format!("Failed to get cache dir: {}", e)))?
             .join("weaverbird_textures");
 
-        println!("[get_pack_texture_path] Cache directory: {:?}", cache_dir);
+        debug!("Cache directory: {:?}", cache_dir);
         std::fs::create_dir_all(&cache_dir)
             .map_err(|e| AppError::io(format!("Failed to create cache dir: {}", e)))?;
 
@@ -452,18 +1616,16 @@ pub fn get_pack_texture_path_impl(
 
         // Write the texture to cache if it doesn't exist or is outdated
         if !cache_file.exists() {
-            println!("[get_pack_texture_path] Writing to cache: {:?}", cache_file);
-            std::fs::write(&cache_file, &bytes)
+            debug!("Writing to cache: {:?}", cache_file);
+            // Atomic, so another instance never reads a half-written texture
+            settings::write_file_atomic(&cache_file, &bytes)
                 .map_err(|e| AppError::io(format!("Failed to write cached texture: {}", e)))?;
         } else {
-            println!(
-                "[get_pack_texture_path] Using cached file: {:?}",
-                cache_file
-            );
+            debug!("Using cached file: {:?}", cache_file);
         }
 
         let result_path = cache_file.to_string_lossy().to_string();
-        println!("[get_pack_texture_path] Returning path: {}", result_path);
+        debug!("Returning path: {}", result_path);
         Ok(result_path)
     } else {
         // For directory packs, just combine the paths
@@ -491,21 +1653,24 @@ pub fn get_pack_texture_path_impl(
 /// * `pack_id` - ID of the resource pack to read from
 /// * `model_id` - Model ID (e.g., "minecraft:block/acacia_log_horizontal" or "block/dirt")
 /// * `packs_dir` - Directory containing resource packs
+/// * `display_context` - Display context (e.g. gui) whose transform to resolve into `displayTransform`
+/// * `variant` - Blockstate variant whose x/y rotation and uvlock to bake into the elements
 ///
 /// # Returns
-/// BlockModel JSON with parent inheritance applied
+/// BlockModel JSON with parent inheritance applied. Broken texture references
+/// point at the missing-texture placeholder and are listed in `missingTextures`.
 pub fn load_model_json_impl(
     pack_id: String,
     model_id: String,
     packs_dir: String,
+    display_context: Option<crate::util::block_models::DisplayContext>,
+    variant: Option<crate::util::blockstates::ResolvedModel>,
 ) -> Result<crate::util::block_models::BlockModel, AppError> {
-    println!(
-        "[load_model_json] pack_id: {}, model_id: {}",
-        pack_id, model_id
-    );
+    debug!("pack_id: {}, model_id: {}", pack_id, model_id);
 
     // Validate inputs
     validation::validate_directory(&packs_dir, "Packs directory")?;
+    validation::validate_path_access(&packs_dir, "Packs directory")?;
 
     // Create vanilla pack
     let vanilla_pack = create_vanilla_pack()?;
@@ -523,11 +1688,82 @@ pub fn load_model_json_impl(
             .clone()
     };
 
-    println!("[load_model_json] Loading from pack: {}", target_pack.name);
+    debug!("Loading from pack: {}", target_pack.name);
 
     // Load model with parent inheritance and vanilla fallback
-    crate::util::block_models::resolve_block_model(&target_pack, &model_id, &vanilla_pack)
-        .map_err(|e| AppError::io(format!("Failed to load model: {}", e)))
+    let mut model =
+        crate::util::block_models::resolve_block_model(&target_pack, &model_id, &vanilla_pack)
+            .map_err(|e| AppError::io(format!("Failed to load model: {}", e)))?;
+
+    // Point broken texture references at the placeholder so previews still render
+    crate::util::block_models::substitute_missing_textures(&mut model, &target_pack, &vanilla_pack);
+    // Rotate into place so sideways logs and turned stairs preview as placed
+    if let Some(variant) = variant {
+        crate::util::model_rotation::apply_variant_rotation(
+            &mut model,
+            variant.rot_x,
+            variant.rot_y,
+            variant.uvlock,
+        );
+    }
+    if let Some(context) = display_context {
+        model.apply_display_context(context);
+    }
+    Ok(model)
+}
+
+/// Resolve an item to the model the game would show, for previews
+///
+/// # Arguments
+/// * `pack_id` - ID of the resource pack to read from
+/// * `item_id` - Item ID (e.g., "minecraft:bow")
+/// * `packs_dir` - Directory containing resource packs
+/// * `predicates` - Predicate values picking an override (e.g. {"custom_model_data": 3})
+/// * `display_context` - Display context whose transform to resolve (gui if unset)
+///
+/// # Returns
+/// The chosen model with parents merged, its layer textures and the item's
+/// overrides. Broken texture references point at the missing-texture placeholder.
+///
+/// # Errors
+/// - VALIDATION_ERROR: Invalid packs directory, unknown pack or item model
+pub fn load_item_model_impl(
+    pack_id: String,
+    item_id: String,
+    packs_dir: String,
+    predicates: Option<HashMap<String, f64>>,
+    display_context: Option<crate::util::block_models::DisplayContext>,
+) -> Result<crate::util::item_models::ResolvedItemModel, AppError> {
+    validation::validate_directory(&packs_dir, "Packs directory")?;
+    validation::validate_path_access(&packs_dir, "Packs directory")?;
+
+    let vanilla_pack = create_vanilla_pack()?;
+    let target_pack = if pack_id == vanilla_textures::VANILLA_PACK_ID {
+        vanilla_pack.clone()
+    } else {
+        let packs = pack_scanner::scan_packs(&packs_dir)
+            .map_err(|e| AppError::scan(format!("Failed to scan packs: {}", e)))?;
+        packs
+            .into_iter()
+            .find(|p| p.id == pack_id)
+            .ok_or_else(|| AppError::validation(format!("Pack not found: {}", pack_id)))?
+    };
+
+    let mut item = crate::util::item_models::resolve_item_model(
+        &target_pack,
+        &item_id,
+        &vanilla_pack,
+        &predicates.unwrap_or_default(),
+    )?;
+    crate::util::block_models::substitute_missing_textures(
+        &mut item.model,
+        &target_pack,
+        &vanilla_pack,
+    );
+    item.model.apply_display_context(
+        display_context.unwrap_or(crate::util::block_models::DisplayContext::Gui),
+    );
+    Ok(item)
 }
 
 /// Read a Minecraft block model JSON file from texture ID
@@ -538,37 +1774,41 @@ pub fn load_model_json_impl(
 /// * `pack_id` - ID of the resource pack to read from
 /// * `texture_id` - Texture/asset ID (e.g., "minecraft:block/dirt")
 /// * `packs_dir` - Directory containing resource packs
+/// * `display_context` - Display context (e.g. gui) whose transform to resolve into `displayTransform`
 ///
 /// # Returns
-/// Fully resolved BlockModel JSON with parent inheritance applied
+/// Fully resolved BlockModel JSON with parent inheritance applied. Broken texture
+/// references point at the missing-texture placeholder and are listed in `missingTextures`.
 pub fn read_block_model_impl(
     pack_id: String,
     texture_id: String,
     packs_dir: String,
+    display_context: Option<crate::util::block_models::DisplayContext>,
 ) -> Result<crate::util::block_models::BlockModel, AppError> {
-    println!(
-        "[read_block_model] Starting - pack_id: {}, texture_id: {}",
+    debug!(
+        "Starting - pack_id: {}, texture_id: {}",
         pack_id, texture_id
     );
 
     // Validate inputs
     validation::validate_directory(&packs_dir, "Packs directory")?;
-    println!("[read_block_model] Validated packs_dir: {}", packs_dir);
+    validation::validate_path_access(&packs_dir, "Packs directory")?;
+    debug!("Validated packs_dir: {}", packs_dir);
 
     // Create vanilla pack first
     let vanilla_pack = create_vanilla_pack()?;
-    println!("[read_block_model] Created vanilla pack");
+    debug!("Created vanilla pack");
 
     // If requesting vanilla directly, use it
     let target_pack = if pack_id == "minecraft:vanilla" {
-        println!("[read_block_model] Using vanilla pack directly");
+        debug!("Using vanilla pack directly");
         vanilla_pack.clone()
     } else {
         // Scan packs to find the requested pack
-        println!("[read_block_model] Scanning packs...");
+        debug!("Scanning packs...");
         let packs = pack_scanner::scan_packs(&packs_dir)
             .map_err(|e| AppError::scan(format!("Failed to scan packs: {}", e)))?;
-        println!("[read_block_model] Found {} packs", packs.len());
+        debug!("Found {} packs", packs.len());
 
         // Find the target pack
         packs
@@ -578,19 +1818,16 @@ pub fn read_block_model_impl(
             .clone()
     };
 
-    println!(
-        "[read_block_model] Found target pack: {}, is_zip: {}",
+    debug!(
+        "Found target pack: {}, is_zip: {}",
         target_pack.name, target_pack.is_zip
     );
 
     // Try to build texture index for accurate lookup
-    println!("[read_block_model] Building texture index...");
+    debug!("Building texture index...");
     let texture_index = texture_index::TextureIndex::build(&target_pack, &vanilla_pack)
         .unwrap_or_else(|e| {
-            println!(
-                "[read_block_model] Failed to build index: {}, using fallback",
-                e
-            );
+            warn!("Failed to build index: {}, using fallback", e);
             texture_index::TextureIndex {
                 texture_to_blocks: HashMap::new(),
             }
@@ -602,19 +1839,16 @@ pub fn read_block_model_impl(
 
     // Try to look up block ID from texture index first
     let block_id = if let Some(primary_block) = texture_index.get_primary_block(texture_path) {
-        println!(
-            "[read_block_model] ✓ Found block from texture index: {}",
-            primary_block
-        );
+        debug!("✓ Found block from texture index: {}", primary_block);
         primary_block.to_string()
     } else {
-        println!("[read_block_model] Texture not in index, using heuristic fallback");
+        warn!("Texture not in index, using heuristic fallback");
         // Fall back to heuristic method
         crate::util::blockstates::texture_id_to_block_id(&texture_id)
             .ok_or_else(|| AppError::validation(format!("Not a block texture: {}", texture_id)))?
     };
 
-    println!("[read_block_model] Block ID: {}", block_id);
+    debug!("Block ID: {}", block_id);
 
     // Generate alternative block IDs to try (common naming variations)
     let mut block_id_candidates = vec![block_id.clone()];
@@ -642,93 +1876,331 @@ pub fn read_block_model_impl(
         }
     }
 
-    println!(
-        "[read_block_model] Trying block IDs: {:?}",
-        block_id_candidates
-    );
+    debug!("Trying block IDs: {:?}", block_id_candidates);
+
+    // Try to read blockstate from target pack, fall back to vanilla
+    // Try all candidate block IDs until one works
+    debug!("Reading blockstate from pack...");
+    let (blockstate, _used_block_id) = {
+        let mut found_blockstate = None;
+        let mut found_block_id = block_id.clone();
+
+        for candidate in &block_id_candidates {
+            debug!("Trying candidate: {}", candidate);
+            match crate::util::blockstates::read_blockstate(
+                &PathBuf::from(&target_pack.path),
+                candidate,
+                target_pack.is_zip,
+            ) {
+                Ok(bs) => {
+                    debug!("✓ Blockstate found in pack for: {}", candidate);
+                    found_blockstate = Some(bs);
+                    found_block_id = candidate.clone();
+                    break;
+                }
+                Err(_) => {
+                    debug!("✗ Not in pack: {}", candidate);
+                }
+            }
+        }
+
+        if let Some(bs) = found_blockstate {
+            (bs, found_block_id)
+        } else {
+            // Try vanilla blockstate with all candidates
+            debug!("Not in pack, trying vanilla...");
+            let mut found_vanilla = None;
+            for candidate in &block_id_candidates {
+                match crate::util::blockstates::read_blockstate(
+                    &PathBuf::from(&vanilla_pack.path),
+                    candidate,
+                    vanilla_pack.is_zip,
+                ) {
+                    Ok(bs) => {
+                        debug!("✓ Blockstate found in vanilla for: {}", candidate);
+                        found_vanilla = Some((bs, candidate.clone()));
+                        break;
+                    }
+                    Err(_) => {
+                        debug!("✗ Not in vanilla: {}", candidate);
+                    }
+                }
+            }
+
+            found_vanilla.ok_or_else(|| {
+                AppError::validation(format!(
+                    "Blockstate not found for any of: {:?}",
+                    block_id_candidates
+                ))
+            })?
+        }
+    };
+
+    // Get the default model from the blockstate
+    debug!("Getting default model from blockstate...");
+    let model_id = crate::util::blockstates::get_default_model(&blockstate).ok_or_else(|| {
+        AppError::validation(format!(
+            "No default model found in blockstate for {}",
+            block_id
+        ))
+    })?;
+    debug!("Model ID: {}", model_id);
+
+    // Resolve the model with parent inheritance
+    debug!("Resolving model with parent inheritance...");
+    let mut model =
+        crate::util::block_models::resolve_block_model(&target_pack, &model_id, &vanilla_pack)
+            .map_err(|e| AppError::io(format!("Failed to read block model: {}", e)))?;
+
+    // Point broken texture references at the placeholder so previews still render
+    crate::util::block_models::substitute_missing_textures(&mut model, &target_pack, &vanilla_pack);
+    if let Some(context) = display_context {
+        model.apply_display_context(context);
+    }
+
+    debug!("Complete!");
+    Ok(model)
+}
+
+/// Break a model down into the texture and source pack of each face
+///
+/// Lets the UI show a block whose textures come from several packs (e.g.
+/// top from one pack, sides from another) once overrides mix sources.
+///
+/// # Arguments
+/// * `pack_id` - ID of the pack to load the model from
+/// * `model_id` - Model ID (e.g., "minecraft:block/oak_log")
+/// * `packs_dir` - Directory containing resource packs
+/// * `pack_order` - Enabled pack IDs in priority order (first = highest)
+/// * `overrides` - Asset ID -> chosen pack
+/// * `variant` - Blockstate variant whose rotation to apply before reading faces
+///
+/// # Returns
+/// Direction -> textures on that face with their source pack
+///
+/// # Errors
+/// - VALIDATION_ERROR: Packs directory is invalid or pack not found
+/// - SCAN_ERROR: Failed to scan packs
+/// - IO_ERROR: Failed to load the model
+pub fn get_face_textures_impl(
+    pack_id: String,
+    model_id: String,
+    packs_dir: String,
+    pack_order: Vec<String>,
+    overrides: HashMap<String, OverrideSelection>,
+    variant: Option<crate::util::blockstates::ResolvedModel>,
+) -> Result<crate::util::face_textures::FaceTextureBreakdown, AppError> {
+    validation::validate_directory(&packs_dir, "Packs directory")?;
+    validation::validate_path_access(&packs_dir, "Packs directory")?;
+
+    let vanilla_pack = create_vanilla_pack()?;
+    let packs = pack_scanner::scan_packs(&packs_dir)
+        .map_err(|e| AppError::scan(format!("Failed to scan packs: {}", e)))?;
+    let target_pack = if pack_id == vanilla_pack.id {
+        vanilla_pack.clone()
+    } else {
+        packs
+            .iter()
+            .find(|p| p.id == pack_id)
+            .ok_or_else(|| AppError::validation(format!("Pack not found: {}", pack_id)))?
+            .clone()
+    };
+
+    let mut model =
+        crate::util::block_models::resolve_block_model(&target_pack, &model_id, &vanilla_pack)
+            .map_err(|e| AppError::io(format!("Failed to load model: {}", e)))?;
+    if let Some(variant) = variant {
+        crate::util::model_rotation::apply_variant_rotation(
+            &mut model,
+            variant.rot_x,
+            variant.rot_y,
+            variant.uvlock,
+        );
+    }
+
+    Ok(crate::util::face_textures::face_texture_breakdown(
+        &model_id,
+        &model,
+        &packs,
+        &pack_order,
+        &overrides,
+        &vanilla_pack,
+    ))
+}
+
+/// Every blockstate and model that draws a texture, per pack
+///
+/// Shows how far overriding a single texture reaches: a log texture is
+/// often shared by the log, its wood block and their stripped variants.
+///
+/// # Arguments
+/// * `packs_dir` - Directory containing resource packs
+/// * `texture_id` - Texture asset ID (e.g., "minecraft:block/oak_log")
+///
+/// # Returns
+/// Usage in each pack that draws the texture, vanilla ("minecraft:vanilla") last
+///
+/// # Errors
+/// - VALIDATION_ERROR: Packs directory is invalid
+/// - SCAN_ERROR: Failed to scan packs
+pub fn get_texture_usage_impl(
+    packs_dir: String,
+    texture_id: String,
+) -> Result<Vec<texture_index::TextureUsage>, AppError> {
+    validation::validate_directory(&packs_dir, "Packs directory")?;
+    validation::validate_path_access(&packs_dir, "Packs directory")?;
+
+    let vanilla_pack = create_vanilla_pack()?;
+    let packs = pack_scanner::scan_packs(&packs_dir)
+        .map_err(|e| AppError::scan(format!("Failed to scan packs: {}", e)))?;
+
+    Ok(packs
+        .iter()
+        .chain(std::iter::once(&vanilla_pack))
+        .map(|pack| texture_index::find_texture_usage(&texture_id, pack, &vanilla_pack))
+        .filter(|usage| !usage.models.is_empty())
+        .collect())
+}
+
+/// Textures that overriding a block to a pack would take along from it
+///
+/// Builds take an overridden block's other textures from the same pack
+/// (see `include_override_dependencies`); this previews which ones.
+///
+/// # Arguments
+/// * `packs_dir` - Directory containing resource packs
+/// * `asset_id` - Texture being overridden (e.g., "minecraft:block/oak_log")
+/// * `pack_id` - Pack it is overridden to
+///
+/// # Returns
+/// Texture asset IDs the pack provides for the same block, excluding `asset_id`
+///
+/// # Errors
+/// - VALIDATION_ERROR: Packs directory is invalid or pack not found
+/// - SCAN_ERROR: Failed to scan packs
+pub fn get_override_dependencies_impl(
+    packs_dir: String,
+    asset_id: String,
+    pack_id: String,
+) -> Result<Vec<String>, AppError> {
+    validation::validate_directory(&packs_dir, "Packs directory")?;
+    validation::validate_path_access(&packs_dir, "Packs directory")?;
 
-    // Try to read blockstate from target pack, fall back to vanilla
-    // Try all candidate block IDs until one works
-    println!("[read_block_model] Reading blockstate from pack...");
-    let (blockstate, _used_block_id) = {
-        let mut found_blockstate = None;
-        let mut found_block_id = block_id.clone();
+    let vanilla_pack = create_vanilla_pack()?;
+    let packs = pack_scanner::scan_packs(&packs_dir)
+        .map_err(|e| AppError::scan(format!("Failed to scan packs: {}", e)))?;
+    let pack = packs
+        .iter()
+        .find(|p| p.id == pack_id)
+        .ok_or_else(|| AppError::validation(format!("Pack not found: {}", pack_id)))?;
+
+    let files = crate::util::block_models::PackFiles::new(pack);
+    Ok(
+        override_closure::block_texture_closure(&asset_id, pack, &vanilla_pack)
+            .into_iter()
+            .filter(|texture_id| texture_id != &asset_id)
+            .filter(|texture_id| {
+                files.contains(&crate::util::animation::texture_path_for_asset(texture_id))
+            })
+            .collect(),
+    )
+}
 
-        for candidate in &block_id_candidates {
-            println!("[read_block_model] Trying candidate: {}", candidate);
-            match crate::util::blockstates::read_blockstate(
-                &PathBuf::from(&target_pack.path),
-                candidate,
-                target_pack.is_zip,
-            ) {
-                Ok(bs) => {
-                    println!(
-                        "[read_block_model] ✓ Blockstate found in pack for: {}",
-                        candidate
-                    );
-                    found_blockstate = Some(bs);
-                    found_block_id = candidate.clone();
-                    break;
-                }
-                Err(_) => {
-                    println!("[read_block_model] ✗ Not in pack: {}", candidate);
-                }
-            }
-        }
+/// Compare two packs' versions of the same model
+///
+/// # Arguments
+/// * `packs_dir` - Directory containing resource packs
+/// * `model_id` - Model both packs override (e.g., "minecraft:block/oak_stairs")
+/// * `left_pack_id` - First pack ("minecraft:vanilla" for the base game)
+/// * `right_pack_id` - Second pack
+///
+/// # Returns
+/// Structured diff saying whether the versions differ in shape or only in looks
+///
+/// # Errors
+/// - VALIDATION_ERROR: Packs directory is invalid or a pack was not found
+/// - SCAN_ERROR: Failed to scan packs
+/// - IO_ERROR: Failed to load either model
+pub fn diff_pack_models_impl(
+    packs_dir: String,
+    model_id: String,
+    left_pack_id: String,
+    right_pack_id: String,
+) -> Result<crate::util::model_diff::ModelDiff, AppError> {
+    validation::validate_directory(&packs_dir, "Packs directory")?;
+    validation::validate_path_access(&packs_dir, "Packs directory")?;
 
-        if let Some(bs) = found_blockstate {
-            (bs, found_block_id)
+    let vanilla_pack = create_vanilla_pack()?;
+    let packs = pack_scanner::scan_packs(&packs_dir)
+        .map_err(|e| AppError::scan(format!("Failed to scan packs: {}", e)))?;
+    let load = |pack_id: &str| -> Result<crate::util::block_models::BlockModel, AppError> {
+        let pack = if pack_id == vanilla_pack.id {
+            &vanilla_pack
         } else {
-            // Try vanilla blockstate with all candidates
-            println!("[read_block_model] Not in pack, trying vanilla...");
-            let mut found_vanilla = None;
-            for candidate in &block_id_candidates {
-                match crate::util::blockstates::read_blockstate(
-                    &PathBuf::from(&vanilla_pack.path),
-                    candidate,
-                    vanilla_pack.is_zip,
-                ) {
-                    Ok(bs) => {
-                        println!(
-                            "[read_block_model] ✓ Blockstate found in vanilla for: {}",
-                            candidate
-                        );
-                        found_vanilla = Some((bs, candidate.clone()));
-                        break;
-                    }
-                    Err(_) => {
-                        println!("[read_block_model] ✗ Not in vanilla: {}", candidate);
-                    }
-                }
-            }
-
-            found_vanilla.ok_or_else(|| {
-                AppError::validation(format!(
-                    "Blockstate not found for any of: {:?}",
-                    block_id_candidates
-                ))
-            })?
-        }
+            packs
+                .iter()
+                .find(|p| p.id == pack_id)
+                .ok_or_else(|| AppError::validation(format!("Pack not found: {}", pack_id)))?
+        };
+        crate::util::block_models::resolve_block_model(pack, &model_id, &vanilla_pack)
+            .map_err(|e| AppError::io(format!("Failed to load model from {}: {}", pack_id, e)))
     };
 
-    // Get the default model from the blockstate
-    println!("[read_block_model] Getting default model from blockstate...");
-    let model_id = crate::util::blockstates::get_default_model(&blockstate).ok_or_else(|| {
-        AppError::validation(format!(
-            "No default model found in blockstate for {}",
-            block_id
-        ))
-    })?;
-    println!("[read_block_model] Model ID: {}", model_id);
+    let left = load(&left_pack_id)?;
+    let right = load(&right_pack_id)?;
+    Ok(crate::util::model_diff::diff_models(
+        &model_id,
+        &left_pack_id,
+        &left,
+        &right_pack_id,
+        &right,
+    ))
+}
 
-    // Resolve the model with parent inheritance
-    println!("[read_block_model] Resolving model with parent inheritance...");
-    let result =
-        crate::util::block_models::resolve_block_model(&target_pack, &model_id, &vanilla_pack)
-            .map_err(|e| AppError::io(format!("Failed to read block model: {}", e)));
+/// Reject a block ID that neither vanilla nor the target pack has a blockstate for
+///
+/// Skipped for namespaced (modded) IDs and while the vanilla cache is empty,
+/// since there is nothing to check them against.
+fn ensure_known_block(
+    vanilla_pack: &crate::model::PackMeta,
+    target_pack: &crate::model::PackMeta,
+    block_id: &str,
+) -> Result<(), AppError> {
+    if block_id.contains(':') {
+        return Ok(());
+    }
+    let catalog = crate::util::id_catalog::cached_catalog(vanilla_pack);
+    if catalog.block_ids.is_empty() || catalog.knows_block(block_id) {
+        return Ok(());
+    }
+    let in_pack = target_pack.id != vanilla_pack.id
+        && crate::util::blockstates::find_blockstate_file(
+            &PathBuf::from(&target_pack.path),
+            block_id,
+            target_pack.is_zip,
+        )
+        .is_some();
+    if in_pack {
+        Ok(())
+    } else {
+        Err(AppError::validation(format!(
+            "Unknown block id: {}",
+            block_id
+        )))
+    }
+}
 
-    println!("[read_block_model] Complete!");
-    result
+/// List vanilla block and model IDs, for autocomplete
+///
+/// # Errors
+/// - IO_ERROR: Vanilla cache directory unavailable
+///
+/// # Returns
+/// Sorted block IDs (from blockstates) and model IDs; empty before the
+/// vanilla textures are extracted
+pub fn list_vanilla_ids_impl() -> Result<crate::util::id_catalog::IdCatalog, AppError> {
+    let vanilla_pack = create_vanilla_pack()?;
+    Ok((*crate::util::id_catalog::cached_catalog(&vanilla_pack)).clone())
 }
 
 /// Get the blockstate schema for a block (for UI generation)
@@ -748,34 +2220,32 @@ pub fn get_block_state_schema_impl(
     block_id: String,
     packs_dir: String,
 ) -> Result<crate::util::blockstates::BlockStateSchema, AppError> {
-    println!("=== [get_block_state_schema] START ===");
-    println!(
-        "[get_block_state_schema] pack_id: {}, block_id: {}",
-        pack_id, block_id
-    );
+    debug!("=== get_block_state_schema START ===");
+    debug!("pack_id: {}, block_id: {}", pack_id, block_id);
 
     // CRITICAL: Normalize block_id to strip texture path prefixes
     let normalized_block_id = if let Some(stripped) = block_id.strip_prefix("minecraft:block/") {
-        println!("[get_block_state_schema] Stripped 'minecraft:block/' prefix");
+        debug!("Stripped 'minecraft:block/' prefix");
         stripped.to_string()
     } else if let Some(stripped) = block_id.strip_prefix("block/") {
-        println!("[get_block_state_schema] Stripped 'block/' prefix");
+        debug!("Stripped 'block/' prefix");
         stripped.to_string()
     } else if let Some(stripped) = block_id.strip_prefix("minecraft:") {
-        println!("[get_block_state_schema] Stripped 'minecraft:' prefix");
+        debug!("Stripped 'minecraft:' prefix");
         stripped.to_string()
     } else {
-        println!("[get_block_state_schema] No prefix found, using as-is");
+        debug!("No prefix found, using as-is");
         block_id.clone()
     };
 
-    println!(
-        "[get_block_state_schema] Normalized block_id: {} -> {}",
+    debug!(
+        "Normalized block_id: {} -> {}",
         block_id, normalized_block_id
     );
 
     // Validate inputs
     validation::validate_directory(&packs_dir, "Packs directory")?;
+    validation::validate_path_access(&packs_dir, "Packs directory")?;
 
     // Create vanilla pack
     let vanilla_pack = create_vanilla_pack()?;
@@ -793,10 +2263,9 @@ pub fn get_block_state_schema_impl(
             .clone()
     };
 
-    println!(
-        "[get_block_state_schema] Reading blockstate from pack: {}",
-        target_pack.name
-    );
+    ensure_known_block(&vanilla_pack, &target_pack, &normalized_block_id)?;
+
+    debug!("Reading blockstate from pack: {}", target_pack.name);
 
     // Use universal blockstate finder to locate the file
     // This scans the directory and matches by normalizing names (removing underscores)
@@ -808,8 +2277,8 @@ pub fn get_block_state_schema_impl(
             &normalized_block_id,
             target_pack.is_zip,
         ) {
-            println!(
-                "[get_block_state_schema] Found blockstate in pack: {} -> {}",
+            debug!(
+                "Found blockstate in pack: {} -> {}",
                 normalized_block_id, actual_block_id
             );
             let bs = crate::util::blockstates::read_blockstate(
@@ -825,8 +2294,8 @@ pub fn get_block_state_schema_impl(
             &normalized_block_id,
             vanilla_pack.is_zip,
         ) {
-            println!(
-                "[get_block_state_schema] Found blockstate in vanilla: {} -> {}",
+            debug!(
+                "Found blockstate in vanilla: {} -> {}",
                 normalized_block_id, actual_block_id
             );
             let bs = crate::util::blockstates::read_blockstate(
@@ -870,35 +2339,36 @@ pub fn resolve_block_state_impl(
     state_props: Option<HashMap<String, String>>,
     seed: Option<u64>,
 ) -> Result<crate::util::blockstates::ResolutionResult, AppError> {
-    println!("=== [resolve_block_state] START ===");
-    println!(
-        "[resolve_block_state] pack_id: {}, block_id: {}, props: {:?}, seed: {:?}",
+    debug!("=== resolve_block_state START ===");
+    debug!(
+        "pack_id: {}, block_id: {}, props: {:?}, seed: {:?}",
         pack_id, block_id, state_props, seed
     );
 
     // CRITICAL: Normalize block_id to strip texture path prefixes
     // Input might be "minecraft:block/dark_oak_planks" but we need just "dark_oak_planks"
     let normalized_block_id = if let Some(stripped) = block_id.strip_prefix("minecraft:block/") {
-        println!("[resolve_block_state] Stripped 'minecraft:block/' prefix");
+        debug!("Stripped 'minecraft:block/' prefix");
         stripped.to_string()
     } else if let Some(stripped) = block_id.strip_prefix("block/") {
-        println!("[resolve_block_state] Stripped 'block/' prefix");
+        debug!("Stripped 'block/' prefix");
         stripped.to_string()
     } else if let Some(stripped) = block_id.strip_prefix("minecraft:") {
-        println!("[resolve_block_state] Stripped 'minecraft:' prefix");
+        debug!("Stripped 'minecraft:' prefix");
         stripped.to_string()
     } else {
-        println!("[resolve_block_state] No prefix found, using as-is");
+        debug!("No prefix found, using as-is");
         block_id.clone()
     };
 
-    println!(
-        "[resolve_block_state] Normalized block_id: {} -> {}",
+    debug!(
+        "Normalized block_id: {} -> {}",
         block_id, normalized_block_id
     );
 
     // Validate inputs
     validation::validate_directory(&packs_dir, "Packs directory")?;
+    validation::validate_path_access(&packs_dir, "Packs directory")?;
 
     // Create vanilla pack
     let vanilla_pack = create_vanilla_pack()?;
@@ -916,14 +2386,10 @@ pub fn resolve_block_state_impl(
             .clone()
     };
 
-    println!(
-        "[resolve_block_state] Reading blockstate from pack: {}",
-        target_pack.name
-    );
-    println!(
-        "[resolve_block_state] Using normalized block_id: {}",
-        normalized_block_id
-    );
+    ensure_known_block(&vanilla_pack, &target_pack, &normalized_block_id)?;
+
+    debug!("Reading blockstate from pack: {}", target_pack.name);
+    debug!("Using normalized block_id: {}", normalized_block_id);
 
     // Use universal blockstate finder to locate the file
     // This scans the directory and matches by normalizing names (removing underscores)
@@ -935,8 +2401,8 @@ pub fn resolve_block_state_impl(
             &normalized_block_id,
             target_pack.is_zip,
         ) {
-            println!(
-                "[resolve_block_state] Found blockstate in pack: {} -> {}",
+            debug!(
+                "Found blockstate in pack: {} -> {}",
                 normalized_block_id, actual_block_id
             );
             let bs = crate::util::blockstates::read_blockstate(
@@ -952,8 +2418,8 @@ pub fn resolve_block_state_impl(
             &normalized_block_id,
             vanilla_pack.is_zip,
         ) {
-            println!(
-                "[resolve_block_state] Found blockstate in vanilla: {} -> {}",
+            debug!(
+                "Found blockstate in vanilla: {} -> {}",
                 normalized_block_id, actual_block_id
             );
             let bs = crate::util::blockstates::read_blockstate(
@@ -970,10 +2436,7 @@ pub fn resolve_block_state_impl(
         }
     };
 
-    println!(
-        "[resolve_block_state] Successfully loaded blockstate for: {}",
-        used_block_id
-    );
+    debug!("Successfully loaded blockstate for: {}", used_block_id);
 
     // Build schema to get valid properties for this block
     let schema = crate::util::blockstates::build_block_state_schema(&blockstate, &used_block_id);
@@ -982,10 +2445,7 @@ pub fn resolve_block_state_impl(
     let valid_props: std::collections::HashSet<String> =
         schema.properties.iter().map(|p| p.name.clone()).collect();
 
-    println!(
-        "[resolve_block_state] Valid properties for this block: {:?}",
-        valid_props
-    );
+    debug!("Valid properties for this block: {:?}", valid_props);
 
     // CRITICAL: Merge provided state props with defaults, but ONLY include properties
     // that are actually defined in the blockstate schema. This filters out invalid
@@ -999,30 +2459,21 @@ pub fn resolve_block_state_impl(
                     merged.insert(key, value);
                 } else {
                     filtered_count += 1;
-                    println!(
-                        "[resolve_block_state] Filtered out invalid property: {}={}",
-                        key, value
-                    );
+                    debug!("Filtered out invalid property: {}={}", key, value);
                 }
             }
             if filtered_count > 0 {
-                println!(
-                    "[resolve_block_state] Filtered out {} invalid properties",
-                    filtered_count
-                );
+                debug!("Filtered out {} invalid properties", filtered_count);
             }
             Some(merged)
         }
         _ => {
-            println!(
-                "[resolve_block_state] Using default state: {:?}",
-                schema.default_state
-            );
+            debug!("Using default state: {:?}", schema.default_state);
             Some(schema.default_state.clone())
         }
     };
 
-    println!("[resolve_block_state] Final properties: {:?}", final_props);
+    debug!("Final properties: {:?}", final_props);
 
     // Resolve blockstate
     let resolution = crate::util::blockstates::resolve_blockstate(
@@ -1032,10 +2483,7 @@ pub fn resolve_block_state_impl(
         seed,
     )?;
 
-    println!(
-        "[resolve_block_state] Resolved {} models",
-        resolution.models.len()
-    );
+    debug!("Resolved {} models", resolution.models.len());
 
     Ok(resolution)
 }
@@ -1058,14 +2506,15 @@ pub fn read_pack_file_impl(
     is_zip: bool,
 ) -> Result<String, AppError> {
     use std::fs;
-    use std::path::Path;
 
-    println!(
-        "[read_pack_file] pack_path: {}, file_path: {}, is_zip: {}",
+    debug!(
+        "pack_path: {}, file_path: {}, is_zip: {}",
         pack_path, file_path, is_zip
     );
 
     if is_zip {
+        validation::validate_path_access(&pack_path, "Pack path")?;
+
         // Read from ZIP file
         let zip_file = fs::File::open(&pack_path)
             .map_err(|e| AppError::io(format!("Failed to open ZIP: {}", e)))?;
@@ -1086,18 +2535,449 @@ pub fn read_pack_file_impl(
         // Read from directory
         let full_path = if pack_path == "." {
             // Special case: read from project root (for __mocks__/cem/)
-            PathBuf::from(&file_path)
+            let relative = Path::new(&file_path);
+            if relative.is_absolute()
+                || relative
+                    .components()
+                    .any(|c| matches!(c, std::path::Component::ParentDir))
+            {
+                return Err(AppError::validation(format!(
+                    "Invalid file path: {}",
+                    file_path
+                )));
+            }
+            std::env::current_dir()
+                .map_err(|e| AppError::io(format!("Failed to get working directory: {}", e)))?
+                .join(&file_path)
         } else {
             Path::new(&pack_path).join(&file_path)
         };
+        validation::validate_path_access(&full_path.to_string_lossy(), "Pack file")?;
 
-        println!("[read_pack_file] Reading from: {}", full_path.display());
+        debug!("Reading from: {}", full_path.display());
 
         fs::read_to_string(&full_path)
             .map_err(|e| AppError::io(format!("Failed to read file: {}", e)))
     }
 }
 
+/// List a folder of a resource pack (directory or ZIP) as a file tree
+///
+/// Children are loaded lazily: call again with a folder's `path` to expand it.
+///
+/// # Arguments
+/// * `pack_path` - Path to the pack (directory or ZIP file)
+/// * `is_zip` - Whether the pack is a ZIP file
+/// * `dir` - Folder inside the pack (pack root if omitted)
+/// * `depth` - Levels of children to load (defaults to 1)
+///
+/// # Returns
+/// The folder with its child folders and files (sizes and kinds)
+///
+/// # Errors
+/// - VALIDATION_ERROR: Pack path is not accessible
+/// - IO_ERROR: Pack can't be read or the folder doesn't exist in it
+pub fn browse_pack_impl(
+    pack_path: String,
+    is_zip: bool,
+    dir: Option<String>,
+    depth: Option<usize>,
+) -> Result<PackTreeEntry, AppError> {
+    validation::validate_path_access(&pack_path, "Pack path")?;
+
+    pack_browser::browse_pack(
+        Path::new(&pack_path),
+        is_zip,
+        dir.as_deref().unwrap_or(""),
+        depth.unwrap_or(1),
+    )
+    .map_err(|e| AppError::io(format!("Failed to browse pack: {}", e)))
+}
+
+/// Show a pack (or any allowed file or folder) in the system file manager
+///
+/// Folders are opened; files such as zip packs are shown selected in their
+/// containing folder.
+///
+/// # Errors
+/// - VALIDATION_ERROR: Path is not accessible
+/// - IO_ERROR: Path doesn't exist or the file manager couldn't be launched
+pub fn reveal_in_file_manager_impl(path: String) -> Result<(), AppError> {
+    validation::validate_path_access(&path, "Path")?;
+
+    file_manager::reveal_in_file_manager(Path::new(&path))
+        .map_err(|e| AppError::io(format!("Failed to open file manager: {}", e)))
+}
+
+/// Copy a single file out of a resource pack (directory or ZIP)
+///
+/// # Arguments
+/// * `pack_path` - Path to the pack (directory or ZIP file)
+/// * `is_zip` - Whether the pack is a ZIP file
+/// * `file_path` - Relative path to the file within the pack
+/// * `destination` - File to write, or a folder to write it into under its own name
+///
+/// # Returns
+/// Path of the written file
+///
+/// # Errors
+/// - VALIDATION_ERROR: Pack or destination is not accessible
+/// - IO_ERROR: File not found in the pack or couldn't be written
+pub fn extract_pack_file_impl(
+    pack_path: String,
+    is_zip: bool,
+    file_path: String,
+    destination: String,
+) -> Result<String, AppError> {
+    validation::validate_path_access(&pack_path, "Pack path")?;
+    validation::validate_path_access(&destination, "Destination")?;
+
+    let dest = Path::new(&destination);
+    let written = if is_zip {
+        crate::util::zip::extract_zip_entry_to(&pack_path, &file_path, dest)
+    } else {
+        crate::util::zip::sanitize_entry_path(&file_path).and_then(|rel_path| {
+            let source = Path::new(&pack_path).join(&rel_path);
+            if !source.is_file() {
+                return Err(anyhow::anyhow!("{} not found in pack", file_path));
+            }
+            let target = match (dest.is_dir(), rel_path.file_name()) {
+                (true, Some(name)) => dest.join(name),
+                _ => dest.to_path_buf(),
+            };
+            file_retry::copy_with_retry(&source, &target)?;
+            Ok(target)
+        })
+    }
+    .map_err(|e| {
+        lock_aware(e, |e| {
+            AppError::io(format!("Failed to extract file: {}", e))
+        })
+    })?;
+
+    let written = written.to_string_lossy().to_string();
+    debug!("{} from {} -> {}", file_path, pack_path, written);
+    Ok(written)
+}
+
+/// Emits pack archive progress to the frontend as (current, total)
+fn archive_progress_emitter(window: tauri::Window) -> impl Fn(usize, usize) {
+    use tauri::Emitter;
+
+    move |current: usize, total: usize| {
+        if let Err(e) = window.emit("pack-archive-progress", (current, total)) {
+            warn!("Failed to emit progress event: {}", e);
+        }
+    }
+}
+
+/// Record an archive operation in the log of the packs folder holding `pack_path`
+fn record_archive_activity(pack_path: &str, action: &str, params: serde_json::Value) {
+    if let Some(packs_dir) = Path::new(pack_path).parent() {
+        activity_log::record(&packs_dir.to_string_lossy(), None, action, params, None);
+    }
+}
+
+/// Extract a whole zip pack into a folder, e.g. to edit it
+///
+/// Emits `pack-archive-progress` events with (files extracted, total).
+///
+/// # Arguments
+/// * `window` - Tauri window handle for emitting progress events
+/// * `pack_path` - Zip pack to extract
+/// * `destination` - Folder to create (may exist only if empty)
+///
+/// # Returns
+/// Path of the extracted folder
+///
+/// # Errors
+/// - VALIDATION_ERROR: Pack or destination is not accessible
+/// - IO_ERROR: Unsafe entry names, destination not empty, or extraction failed
+pub fn extract_pack_archive_impl(
+    window: Option<tauri::Window>,
+    pack_path: String,
+    destination: String,
+) -> Result<String, AppError> {
+    validation::validate_path_access(&pack_path, "Pack path")?;
+    validation::validate_path_access(&destination, "Destination")?;
+
+    let progress = window.map(archive_progress_emitter);
+    pack_archive::extract_pack_archive(
+        Path::new(&pack_path),
+        Path::new(&destination),
+        progress.as_ref().map(|p| p as &dyn Fn(usize, usize)),
+    )
+    .map_err(|e| {
+        lock_aware(e, |e| {
+            AppError::io(format!("Failed to extract pack: {}", e))
+        })
+    })?;
+    record_archive_activity(
+        &pack_path,
+        "extractPackArchive",
+        serde_json::json!({ "packPath": pack_path, "destination": destination }),
+    );
+
+    Ok(destination)
+}
+
+/// Compress a folder pack back into a zip
+///
+/// Emits `pack-archive-progress` events with (files compressed, total).
+///
+/// # Arguments
+/// * `window` - Tauri window handle for emitting progress events
+/// * `folder` - Folder pack to compress
+/// * `destination` - Zip file to write (replaced if it exists)
+///
+/// # Returns
+/// Path of the written zip
+///
+/// # Errors
+/// - VALIDATION_ERROR: Folder is invalid or paths are not accessible
+/// - IO_ERROR: Compression failed
+pub fn compress_pack_folder_impl(
+    window: Option<tauri::Window>,
+    folder: String,
+    destination: String,
+) -> Result<String, AppError> {
+    validation::validate_directory(&folder, "Pack folder")?;
+    validation::validate_path_access(&folder, "Pack folder")?;
+    validation::validate_path_access(&destination, "Destination")?;
+
+    let progress = window.map(archive_progress_emitter);
+    pack_archive::compress_pack_folder(
+        Path::new(&folder),
+        Path::new(&destination),
+        progress.as_ref().map(|p| p as &dyn Fn(usize, usize)),
+    )
+    .map_err(|e| {
+        lock_aware(e, |e| {
+            AppError::io(format!("Failed to compress pack: {}", e))
+        })
+    })?;
+    record_archive_activity(
+        &folder,
+        "compressPackFolder",
+        serde_json::json!({ "folder": folder, "destination": destination }),
+    );
+
+    Ok(destination)
+}
+
+/// Zip a folder pack as a clean distributable
+///
+/// Emits `pack-archive-progress` events with (files compressed, total).
+///
+/// # Arguments
+/// * `window` - Tauri window handle for emitting progress events
+/// * `folder` - Working folder of the pack (pack.mcmeta may be nested)
+/// * `destination` - Zip file to write (replaced if it exists)
+///
+/// # Returns
+/// Files written, the detected pack root and the junk files left out
+///
+/// # Errors
+/// - VALIDATION_ERROR: Folder is invalid or paths are not accessible
+/// - IO_ERROR: No pack.mcmeta found or compression failed
+pub fn repack_pack_folder_impl(
+    window: Option<tauri::Window>,
+    folder: String,
+    destination: String,
+) -> Result<pack_archive::RepackReport, AppError> {
+    validation::validate_directory(&folder, "Pack folder")?;
+    validation::validate_path_access(&folder, "Pack folder")?;
+    validation::validate_path_access(&destination, "Destination")?;
+
+    let progress = window.map(archive_progress_emitter);
+    let report = pack_archive::repack_pack_folder(
+        Path::new(&folder),
+        Path::new(&destination),
+        progress.as_ref().map(|p| p as &dyn Fn(usize, usize)),
+    )
+    .map_err(|e| lock_aware(e, |e| AppError::io(format!("Failed to repack pack: {}", e))))?;
+    record_archive_activity(
+        &folder,
+        "repackPackFolder",
+        serde_json::json!({ "folder": folder, "destination": destination }),
+    );
+
+    Ok(report)
+}
+
+/// Merge packs into a standalone folder or zip
+///
+/// Unlike a Weaver Nest build there is no journal, build stamp or
+/// post-build hook; the result is written once. Emits `build-progress`
+/// events with (files copied, total).
+///
+/// # Arguments
+/// * `window` - Tauri window handle for emitting progress events
+/// * `packs_dir` - Directory containing resource packs
+/// * `pack_order` - Pack IDs to merge, highest priority first
+/// * `overrides` - Asset ID -> chosen pack
+/// * `output_path` - Folder to write into, or zip file to create
+/// * `format` - Folder, zip, or zip for a server
+/// * `server` - Download URL, prompt and whether a server export is required
+///
+/// # Returns
+/// Where the merge was written and how many files it has, plus the SHA-1
+/// and server.properties lines of a server export
+///
+/// # Errors
+/// - VALIDATION_ERROR: Invalid input parameters, or overrides the asset index can't satisfy
+/// - SCAN_ERROR: Failed to scan packs
+/// - BUILD_ERROR: Failed to write the merged pack
+pub fn build_merged_pack_impl(
+    window: Option<tauri::Window>,
+    packs_dir: String,
+    pack_order: Vec<String>,
+    overrides: HashMap<String, OverrideSelection>,
+    output_path: String,
+    format: OutputFormat,
+    server: Option<ServerExportOptions>,
+) -> Result<MergeSummary, AppError> {
+    validation::validate_directory(&packs_dir, "Packs directory")?;
+    validation::validate_path_access(&packs_dir, "Packs directory")?;
+    validation::validate_pack_order(&pack_order)?;
+    validation::validate_overrides(&overrides, &pack_order)?;
+    validation::validate_path_access(&output_path, "Output")?;
+    let output = Path::new(&output_path);
+    if !output.parent().map_or(false, |parent| parent.is_dir()) {
+        return Err(AppError::validation(format!(
+            "Output folder does not exist: {}",
+            output_path
+        )));
+    }
+
+    let packs = pack_scanner::scan_packs(&packs_dir)
+        .map_err(|e| AppError::scan(format!("Pack scanning failed: {}", e)))?;
+    let (assets, providers) = asset_indexer::index_assets(&packs)
+        .map_err(|e| AppError::scan(format!("Asset indexing failed: {}", e)))?;
+    validation::validate_overrides_against_index(&overrides, &providers)?;
+
+    let progress = window.map(build_progress_emitter);
+    let summary = pack_builder::build_pack(
+        &packs,
+        &assets,
+        &providers,
+        &pack_order,
+        &overrides,
+        output,
+        format,
+        server.as_ref(),
+        progress.as_ref().map(|p| p as FileProgress),
+    )
+    .map_err(|e| {
+        lock_aware(e, |e| {
+            AppError::build(format!("Failed to merge packs: {}", e))
+        })
+    })?;
+    activity_log::record(
+        &packs_dir,
+        None,
+        "mergePacks",
+        serde_json::json!({
+            "outputPath": output_path,
+            "format": format,
+            "packOrder": pack_order,
+            "overrides": overrides.len(),
+            "sha1": summary.server.as_ref().map(|server| &server.sha1),
+        }),
+        None,
+    );
+
+    Ok(summary)
+}
+
+/// Write a copy of a pack upgraded to a newer pack format
+///
+/// Textures moved since the pack's format are renamed and pack.mcmeta is
+/// updated; the original pack is left alone.
+///
+/// # Arguments
+/// * `packs_dir` - Directory containing resource packs
+/// * `pack_id` - Pack to migrate
+/// * `target_format` - Format to migrate to (newest known release if None)
+/// * `output_path` - Folder to write the migrated pack to; must not exist
+///
+/// # Returns
+/// Renamed files and the files that couldn't be mapped automatically
+///
+/// # Errors
+/// - VALIDATION_ERROR: Unknown pack, output exists, or the pack is already at the format
+/// - SCAN_ERROR: Failed to scan packs
+/// - IO_ERROR: Failed to write the migrated pack
+pub fn migrate_pack_impl(
+    packs_dir: String,
+    pack_id: String,
+    target_format: Option<u32>,
+    output_path: String,
+) -> Result<pack_migrator::MigrationReport, AppError> {
+    validation::validate_directory(&packs_dir, "Packs directory")?;
+    validation::validate_path_access(&packs_dir, "Packs directory")?;
+    validation::validate_path_access(&output_path, "Output")?;
+    let output = Path::new(&output_path);
+    if !output.parent().map_or(false, |parent| parent.is_dir()) {
+        return Err(AppError::validation(format!(
+            "Output folder does not exist: {}",
+            output_path
+        )));
+    }
+    if output.exists() {
+        return Err(
+            AppError::validation(format!("Output already exists: {}", output_path))
+                .with_path(&output_path),
+        );
+    }
+
+    let packs = pack_scanner::scan_packs(&packs_dir)
+        .map_err(|e| AppError::scan(format!("Pack scanning failed: {}", e)))?;
+    let pack = packs
+        .iter()
+        .find(|p| p.id == pack_id)
+        .ok_or_else(|| AppError::validation(format!("Pack not found: {}", pack_id)))?;
+    let target_format = target_format.unwrap_or_else(pack_format::latest_release_format);
+    match pack.pack_format {
+        None => {
+            return Err(AppError::validation(format!(
+                "{} doesn't declare a pack_format",
+                pack.name
+            )))
+        }
+        Some(format) if format >= target_format => {
+            return Err(AppError::validation(format!(
+                "{} already uses pack format {}",
+                pack.name, format
+            )))
+        }
+        Some(_) => {}
+    }
+
+    let vanilla_dir = vanilla_textures::get_vanilla_cache_dir()
+        .ok()
+        .filter(|dir| dir.join("assets").is_dir());
+    let report = pack_migrator::migrate_pack(pack, target_format, output, vanilla_dir.as_deref())
+        .map_err(|e| {
+        lock_aware(e, |e| {
+            AppError::io(format!("Failed to migrate pack: {}", e))
+        })
+    })?;
+    activity_log::record(
+        &packs_dir,
+        None,
+        "migratePack",
+        serde_json::json!({
+            "packId": pack_id,
+            "targetFormat": target_format,
+            "outputPath": output_path,
+        }),
+        None,
+    );
+
+    Ok(report)
+}
+
 /// Read a vanilla JEM file from __mocks__/cem/ directory
 ///
 /// # Arguments
@@ -1122,10 +3002,7 @@ pub fn read_vanilla_jem_impl(entity_type: String) -> Result<String, AppError> {
         .join("cem")
         .join(format!("{}.jem", entity_type));
 
-    println!(
-        "[read_vanilla_jem] Reading vanilla JEM from: {}",
-        jem_path.display()
-    );
+    debug!("Reading vanilla JEM from: {}", jem_path.display());
 
     fs::read_to_string(&jem_path).map_err(|e| {
         AppError::io(format!(
@@ -1147,6 +3024,7 @@ pub fn get_entity_version_variants_impl(
 ) -> Result<HashMap<String, Vec<String>>, AppError> {
     // Validate input
     validation::validate_directory(&packs_dir, "Packs directory")?;
+    validation::validate_path_access(&packs_dir, "Packs directory")?;
 
     // Scan for packs
     let packs = pack_scanner::scan_packs(&packs_dir).map_err(|e| AppError::scan(e.to_string()))?;
@@ -0,0 +1,123 @@
+/// Commands for managing conflict resolution presets
+///
+/// Presets are persisted in the application settings file and can be
+/// exported/imported as JSON for sharing between users and projects.
+use crate::commands::packs::scan_packs_folder_impl;
+use crate::model::OverrideSelection;
+use crate::util::conflict_presets::ConflictPreset;
+use crate::util::{conflict_presets, settings};
+use crate::{validation, AppError};
+use std::collections::HashMap;
+use tracing::info;
+
+/// List all saved conflict presets
+pub fn list_conflict_presets_impl() -> Result<Vec<ConflictPreset>, AppError> {
+    let settings = settings::load_settings()
+        .map_err(|e| AppError::io(format!("Failed to load settings: {}", e)))?;
+    Ok(settings.conflict_presets)
+}
+
+/// Save a conflict preset, replacing any existing preset with the same name
+///
+/// # Errors
+/// - VALIDATION_ERROR: Preset is invalid
+/// - IO_ERROR: Failed to read or write settings
+pub fn save_conflict_preset_impl(preset: ConflictPreset) -> Result<Vec<ConflictPreset>, AppError> {
+    conflict_presets::validate_preset(&preset).map_err(|e| AppError::validation(e.to_string()))?;
+
+    let _settings_lock = settings::lock_settings();
+    let mut settings = settings::load_settings()
+        .map_err(|e| AppError::io(format!("Failed to load settings: {}", e)))?;
+
+    match settings
+        .conflict_presets
+        .iter_mut()
+        .find(|p| p.name == preset.name)
+    {
+        Some(existing) => *existing = preset,
+        None => settings.conflict_presets.push(preset),
+    }
+
+    settings::save_settings(&settings)
+        .map_err(|e| AppError::io(format!("Failed to save settings: {}", e)))?;
+
+    Ok(settings.conflict_presets)
+}
+
+/// Delete a saved conflict preset by name
+pub fn delete_conflict_preset_impl(name: String) -> Result<Vec<ConflictPreset>, AppError> {
+    let _settings_lock = settings::lock_settings();
+    let mut settings = settings::load_settings()
+        .map_err(|e| AppError::io(format!("Failed to load settings: {}", e)))?;
+
+    let before = settings.conflict_presets.len();
+    settings.conflict_presets.retain(|p| p.name != name);
+    if settings.conflict_presets.len() == before {
+        return Err(AppError::validation(format!("Preset not found: {}", name)));
+    }
+
+    settings::save_settings(&settings)
+        .map_err(|e| AppError::io(format!("Failed to save settings: {}", e)))?;
+
+    Ok(settings.conflict_presets)
+}
+
+/// Export a saved preset as shareable JSON
+pub fn export_conflict_preset_impl(name: String) -> Result<String, AppError> {
+    let preset = list_conflict_presets_impl()?
+        .into_iter()
+        .find(|p| p.name == name)
+        .ok_or_else(|| AppError::validation(format!("Preset not found: {}", name)))?;
+
+    conflict_presets::export_preset_json(&preset)
+        .map_err(|e| AppError::internal("Failed to export preset", e.to_string()))
+}
+
+/// Import a preset from shared JSON and save it
+///
+/// # Errors
+/// - VALIDATION_ERROR: JSON is malformed or the preset is invalid
+pub fn import_conflict_preset_impl(json: String) -> Result<ConflictPreset, AppError> {
+    let preset = conflict_presets::import_preset_json(&json)
+        .map_err(|e| AppError::validation(format!("{:#}", e)))?;
+
+    save_conflict_preset_impl(preset.clone())?;
+
+    Ok(preset)
+}
+
+/// Apply a preset to the packs in a directory
+///
+/// # Arguments
+/// * `packs_dir` - Resource packs directory
+/// * `pack_order` - Current pack priority order
+/// * `preset` - Preset to apply
+///
+/// # Returns
+/// Overrides (asset_id -> selection) that realise the preset on top of the pack order
+pub fn apply_conflict_preset_impl(
+    packs_dir: String,
+    pack_order: Vec<String>,
+    preset: ConflictPreset,
+) -> Result<HashMap<String, OverrideSelection>, AppError> {
+    conflict_presets::validate_preset(&preset).map_err(|e| AppError::validation(e.to_string()))?;
+    validation::validate_pack_order(&pack_order)?;
+
+    let scan = scan_packs_folder_impl(packs_dir, false, None, None)?;
+
+    let overrides = conflict_presets::apply_preset(
+        &preset,
+        &scan.packs,
+        &scan.assets,
+        &scan.providers,
+        &pack_order,
+    );
+
+    info!(
+        "Preset '{}' produced {} overrides",
+        preset.name,
+        overrides.len()
+    );
+
+    Ok(overrides)
+}
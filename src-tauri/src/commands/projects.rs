@@ -0,0 +1,407 @@
+/// Commands for saved projects and batch builds
+use crate::commands::packs::{create_vanilla_pack, run_build, BuildWeaverNestRequest};
+use crate::model::PackMeta;
+use crate::util::activity_log::{self, ActivityEntry};
+use crate::util::install_status::{self, AppliedPackReport};
+use crate::util::override_seeding::{self, OverrideSeed};
+use crate::util::projects::{self, BatchBuildSummary, Project};
+use crate::util::stack_simulation::{self, StackSimulation};
+use crate::util::{
+    asset_indexer, content_hash, launcher_detection, namespace_conflicts, override_closure,
+    pack_description, pack_scanner, settings,
+};
+use crate::{validation, AppError};
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
+use tracing::{info, warn};
+
+/// List all saved projects
+pub fn list_projects_impl() -> Result<Vec<Project>, AppError> {
+    let settings = settings::load_settings()
+        .map_err(|e| AppError::io(format!("Failed to load settings: {}", e)))?;
+    Ok(settings.projects)
+}
+
+/// Save a project, replacing any existing project with the same name
+///
+/// # Errors
+/// - VALIDATION_ERROR: Project name, pack order or overrides are invalid
+/// - IO_ERROR: Failed to read or write settings
+pub fn save_project_impl(project: Project) -> Result<Vec<Project>, AppError> {
+    if project.name.trim().is_empty() {
+        return Err(AppError::validation("Project name cannot be empty"));
+    }
+    validation::validate_pack_order(&project.pack_order)?;
+    if let Some(template) = &project.description_template {
+        pack_description::validate_template(template)
+            .map_err(|e| AppError::validation(e.to_string()))?;
+    }
+    validation::validate_overrides(&project.overrides, &project.pack_order)?;
+    validation::validate_disabled_packs(
+        &project.overrides,
+        &project.pack_order,
+        &project.disabled_packs,
+    )?;
+    for stack in &project.simulations {
+        validation::validate_pack_order(&stack.pack_order)?;
+        validation::validate_overrides(&stack.overrides, &stack.pack_order)?;
+    }
+    validation::validate_path_access(&project.packs_dir, "Packs directory")?;
+    validation::validate_path_access(&project.output_dir, "Output directory")?;
+
+    let _settings_lock = settings::lock_settings();
+    let mut settings = settings::load_settings()
+        .map_err(|e| AppError::io(format!("Failed to load settings: {}", e)))?;
+
+    let previous = settings
+        .projects
+        .iter()
+        .find(|p| p.name == project.name)
+        .and_then(|p| serde_json::to_value(p).ok());
+    let packs_dir = project.packs_dir.clone();
+    let project_name = project.name.clone();
+    let params = serde_json::to_value(&project)?;
+    projects::upsert_project(&mut settings.projects, project);
+
+    settings::save_settings(&settings)
+        .map_err(|e| AppError::io(format!("Failed to save settings: {}", e)))?;
+    activity_log::record(
+        &packs_dir,
+        Some(&project_name),
+        "saveProject",
+        params,
+        previous,
+    );
+
+    Ok(settings.projects)
+}
+
+/// Delete a saved project by name
+pub fn delete_project_impl(name: String) -> Result<Vec<Project>, AppError> {
+    let _settings_lock = settings::lock_settings();
+    let mut settings = settings::load_settings()
+        .map_err(|e| AppError::io(format!("Failed to load settings: {}", e)))?;
+
+    let deleted = settings
+        .projects
+        .iter()
+        .position(|p| p.name == name)
+        .map(|i| settings.projects.remove(i))
+        .ok_or_else(|| AppError::validation(format!("Project not found: {}", name)))?;
+
+    settings::save_settings(&settings)
+        .map_err(|e| AppError::io(format!("Failed to save settings: {}", e)))?;
+    activity_log::record(
+        &deleted.packs_dir,
+        Some(&name),
+        "deleteProject",
+        serde_json::json!({ "name": name }),
+        serde_json::to_value(&deleted).ok(),
+    );
+
+    Ok(settings.projects)
+}
+
+/// Build several saved projects, each to its own output directory
+///
+/// # Arguments
+/// * `names` - Projects to build, in order
+/// * `concurrency` - Maximum projects built at once (sequential if unset)
+/// * `run_hooks` - Run post-build hooks after each build
+///
+/// # Returns
+/// Combined summary; individual project failures are reported in the summary
+/// rather than failing the whole batch
+pub fn batch_build_projects_impl(
+    names: Vec<String>,
+    concurrency: Option<usize>,
+    run_hooks: bool,
+) -> Result<BatchBuildSummary, AppError> {
+    if names.is_empty() {
+        return Err(AppError::validation("No projects selected"));
+    }
+
+    let saved = list_projects_impl()?;
+    let selected = projects::select_projects(&saved, &names)
+        .map_err(|e| AppError::validation(e.to_string()))?;
+
+    info!(
+        "Building {} projects (concurrency: {:?})",
+        selected.len(),
+        concurrency
+    );
+
+    let summary = projects::run_batch(&selected, concurrency, |project| {
+        // Projects build side by side, so no per-file progress events
+        run_build(
+            BuildWeaverNestRequest {
+                packs_dir: project.packs_dir.clone(),
+                pack_order: project.pack_order.clone(),
+                overrides: project.overrides.clone(),
+                output_dir: project.output_dir.clone(),
+                namespace_owners: project.namespace_owners.clone(),
+                fix_alpha_bleed: project.fix_alpha_bleed,
+                include_override_dependencies: project.include_override_dependencies,
+                disabled_packs: project.disabled_packs.clone(),
+                merged_text_assets: project.merged_text_assets.clone(),
+                project_name: Some(project.name.clone()),
+                description_template: project.description_template.clone(),
+            },
+            false,
+            None,
+            None,
+            run_hooks,
+        )
+        .map_err(|e| e.message)
+    })
+    .map_err(|e| AppError::build(e.to_string()))?;
+
+    info!(
+        "{} succeeded, {} failed in {}ms",
+        summary.succeeded, summary.failed, summary.duration_ms
+    );
+
+    Ok(summary)
+}
+
+/// Report which detected instances have a project's output installed
+///
+/// Finds copies by name or by hash, whether each is enabled in options.txt,
+/// and whether it is up to date with the latest build and the project.
+///
+/// # Arguments
+/// * `project` - Current project (need not be saved)
+///
+/// # Returns
+/// Last build stamp and per-instance install state
+pub fn get_applied_pack_status_impl(project: Project) -> Result<AppliedPackReport, AppError> {
+    validation::validate_path_access(&project.packs_dir, "Packs directory")?;
+    validation::validate_path_access(&project.output_dir, "Output directory")?;
+
+    let stamp_dir = install_status::default_stamp_dir()
+        .map_err(|e| AppError::io(format!("Failed to get stamp directory: {}", e)))?;
+    let instances = install_status::known_instances(&launcher_detection::detect_all_launchers());
+
+    install_status::applied_pack_status(&project, instances, &stamp_dir)
+        .map_err(|e| AppError::validation(e.to_string()))
+}
+
+/// Compare two of a project's simulated stacks without building
+///
+/// Packs are scanned and indexed as for a build, with the project's
+/// namespace owners, disabled packs and override dependencies applied, then
+/// each stack's winners are picked and diffed. Nothing is written.
+///
+/// # Arguments
+/// * `project` - Current project (need not be saved)
+/// * `a` - Name of the first stack in `project.simulations`
+/// * `b` - Name of the second stack
+///
+/// # Errors
+/// - VALIDATION_ERROR: Unknown stack, or a stack's order or overrides are invalid
+/// - SCAN_ERROR: Pack scanning or indexing failed
+pub fn simulate_stacks_impl(
+    project: Project,
+    a: String,
+    b: String,
+) -> Result<StackSimulation, AppError> {
+    validation::validate_path_access(&project.packs_dir, "Packs directory")?;
+    let find = |name: &str| {
+        project
+            .simulations
+            .iter()
+            .find(|stack| stack.name == name)
+            .cloned()
+            .ok_or_else(|| AppError::validation(format!("Simulated stack not found: {}", name)))
+    };
+    let mut stacks = [find(&a)?, find(&b)?];
+    for stack in &stacks {
+        validation::validate_pack_order(&stack.pack_order)?;
+        validation::validate_overrides(&stack.overrides, &stack.pack_order)?;
+    }
+
+    let packs = pack_scanner::scan_packs(&project.packs_dir)
+        .map_err(|e| AppError::scan(format!("Pack scanning failed: {}", e)))?;
+    let (_, providers) = asset_indexer::index_assets(&packs)
+        .map_err(|e| AppError::scan(format!("Asset indexing failed: {}", e)))?;
+    let providers = projects::without_disabled_packs(&providers, &project.disabled_packs);
+    let providers =
+        namespace_conflicts::restrict_namespace_providers(&providers, &project.namespace_owners);
+
+    for stack in stacks.iter_mut() {
+        stack.pack_order = projects::enabled_pack_order(&stack.pack_order, &project.disabled_packs);
+        stack.overrides = projects::active_overrides(&stack.overrides, &project.disabled_packs);
+    }
+    if project.include_override_dependencies {
+        let vanilla_pack = create_vanilla_pack()?;
+        for stack in stacks.iter_mut() {
+            stack.overrides = override_closure::expand_override_dependencies(
+                &stack.overrides,
+                &packs,
+                &vanilla_pack,
+                &providers,
+            );
+        }
+    }
+
+    let [a, b] = stacks;
+    Ok(stack_simulation::simulate_stacks(&a, &b, &providers))
+}
+
+/// Seed a new project's overrides from an older project
+///
+/// Each old override moves to the new pack of the same lineage: same ID,
+/// same family, same known pack, or a byte-identical copy of the asset. Old
+/// packs still on disk are read for their names and hashes.
+///
+/// # Arguments
+/// * `project_name` - Saved project to take the overrides from
+/// * `packs_dir` - Packs directory of the new project
+///
+/// # Returns
+/// Carried-over overrides, where each old pack went, and the overrides that
+/// couldn't be carried over
+///
+/// # Errors
+/// - VALIDATION_ERROR: Unknown project or invalid packs directory
+/// - SCAN_ERROR: Failed to scan or index the new packs
+pub fn seed_overrides_from_project_impl(
+    project_name: String,
+    packs_dir: String,
+) -> Result<OverrideSeed, AppError> {
+    validation::validate_directory(&packs_dir, "Packs directory")?;
+    validation::validate_path_access(&packs_dir, "Packs directory")?;
+    let old_project = settings::load_settings()
+        .map_err(|e| AppError::io(format!("Failed to load settings: {}", e)))?
+        .projects
+        .into_iter()
+        .find(|p| p.name == project_name)
+        .ok_or_else(|| AppError::validation(format!("Project not found: {}", project_name)))?;
+
+    let new_packs = pack_scanner::scan_packs(&packs_dir)
+        .map_err(|e| AppError::scan(format!("Pack scanning failed: {}", e)))?;
+    let (_, new_providers, hash_index) = asset_indexer::index_assets_with_hashes(&new_packs)
+        .map_err(|e| AppError::scan(format!("Asset indexing failed: {}", e)))?;
+
+    let overridden_packs: HashSet<&str> = old_project
+        .overrides
+        .values()
+        .map(|selection| selection.pack_id.as_str())
+        .collect();
+    let old_packs: Vec<PackMeta> = if Path::new(&old_project.packs_dir).is_dir() {
+        pack_scanner::scan_packs(&old_project.packs_dir)
+            .unwrap_or_default()
+            .into_iter()
+            .filter(|pack| overridden_packs.contains(pack.id.as_str()))
+            .collect()
+    } else {
+        Vec::new()
+    };
+
+    // Hash the overridden assets of old packs that aren't in the new set
+    let mut old_hashes = HashMap::new();
+    for pack in old_packs
+        .iter()
+        .filter(|pack| !new_packs.iter().any(|p| p.id == pack.id))
+    {
+        let hashed =
+            asset_indexer::index_assets(std::slice::from_ref(pack)).and_then(|(assets, _)| {
+                let overridden: HashMap<String, Vec<String>> = assets
+                    .into_iter()
+                    .filter(|asset| {
+                        old_project
+                            .overrides
+                            .get(&asset.id)
+                            .map_or(false, |selection| selection.pack_id == pack.id)
+                    })
+                    .map(|asset| (asset.id, asset.files))
+                    .collect();
+                content_hash::hash_pack_assets(pack, &overridden)
+            });
+        match hashed {
+            Ok(result) => {
+                old_hashes.insert(pack.id.clone(), result.hashes);
+            }
+            Err(e) => warn!("Could not hash {} for override seeding: {}", pack.name, e),
+        }
+    }
+
+    let seed = override_seeding::seed_overrides(
+        &old_project.overrides,
+        &old_packs,
+        &new_packs,
+        &new_providers,
+        &old_hashes,
+        &hash_index.pack_hashes,
+        &hash_index.pack_files,
+    );
+    info!(
+        "Seeded {} of {} override(s) from {}",
+        seed.overrides.len(),
+        old_project.overrides.len(),
+        project_name
+    );
+    Ok(seed)
+}
+
+/// Record an operation the frontend performed, such as an override or pack
+/// order change
+///
+/// # Arguments
+/// * `packs_dir` - Packs directory the change belongs to
+/// * `action` - What was done (e.g., "setOverride", "setPackOrder")
+/// * `params` - Arguments of the change
+/// * `previous` - State the change replaced, for undo
+/// * `project_name` - Project the change was made in, if one is open
+///
+/// # Errors
+/// - VALIDATION_ERROR: Empty packs directory or action
+/// - IO_ERROR: Failed to write the log
+pub fn record_activity_impl(
+    packs_dir: String,
+    action: String,
+    params: serde_json::Value,
+    previous: Option<serde_json::Value>,
+    project_name: Option<String>,
+) -> Result<(), AppError> {
+    if packs_dir.trim().is_empty() || action.trim().is_empty() {
+        return Err(AppError::validation(
+            "Packs directory and action cannot be empty",
+        ));
+    }
+    let activity_dir = activity_log::default_activity_dir()
+        .map_err(|e| AppError::io(format!("Failed to get activity directory: {}", e)))?;
+    let entry = ActivityEntry::now(project_name.as_deref(), &action, params, previous);
+    activity_log::append_entry(&activity_dir, &packs_dir, &entry)
+        .map_err(|e| AppError::io(format!("Failed to record activity: {}", e)))
+}
+
+/// Recent operations on a packs directory, newest first
+///
+/// Changes other projects made in the same packs directory are left out.
+///
+/// # Arguments
+/// * `packs_dir` - Packs directory to read the log of
+/// * `actions` - Only these actions; all if unset or empty
+/// * `limit` - Maximum entries returned
+/// * `project_name` - Open project; only changes made outside any project if unset
+///
+/// # Errors
+/// - IO_ERROR: Failed to read the log
+pub fn get_activity_log_impl(
+    packs_dir: String,
+    actions: Option<Vec<String>>,
+    limit: Option<usize>,
+    project_name: Option<String>,
+) -> Result<Vec<ActivityEntry>, AppError> {
+    let activity_dir = activity_log::default_activity_dir()
+        .map_err(|e| AppError::io(format!("Failed to get activity directory: {}", e)))?;
+    activity_log::read_entries(
+        &activity_dir,
+        &packs_dir,
+        project_name.as_deref(),
+        &actions.unwrap_or_default(),
+        limit,
+    )
+    .map_err(|e| AppError::io(format!("Failed to read activity log: {}", e)))
+}
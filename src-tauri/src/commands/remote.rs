@@ -0,0 +1,572 @@
+/// Commands for the remote control server
+///
+/// `dispatch` maps JSON-RPC methods to the same command implementations
+/// the UI invokes. Methods are named like the Tauri commands and take the
+/// same camelCase arguments, and every command is exposed except the ones
+/// that only make sense in the app: folder pickers, revealing files, the
+/// asset protocol path and controlling the remote server itself. Post-build
+/// hooks run shell commands, so they can't be configured remotely either,
+/// and builds started here skip them unless the user allowed that in the
+/// app. Progress events aren't sent.
+use super::*;
+use crate::commands::packs::{run_build, BuildWeaverNestRequest};
+use crate::model::OverrideSelection;
+use crate::util::animation_export::ExportFormat;
+use crate::util::block_models::DisplayContext;
+use crate::util::blockstates::ResolvedModel;
+use crate::util::color_vision::ColorVisionFilter;
+use crate::util::comparison::ComparisonLayout;
+use crate::util::conflict_presets::ConflictPreset;
+use crate::util::conflicts::ConflictSeveritySettings;
+use crate::util::doc_previews::DocPreviewOptions;
+use crate::util::launcher_detection::LauncherInfo;
+use crate::util::pack_builder::OutputFormat;
+use crate::util::pack_description::DescriptionContext;
+use crate::util::parallelism::ParallelismSettings;
+use crate::util::projects::Project;
+use crate::util::report_export::{ReportFormat, ReportKind};
+use crate::util::resolution_queue::ResolutionAction;
+use crate::util::rpc_server::{self, RemoteServerStatus, RpcError};
+use crate::util::running_game::RunningGamePolicy;
+use crate::util::server_export::ServerExportOptions;
+use crate::util::tinting::Colormap;
+use crate::util::vanilla_textures::VanillaVersionSettings;
+use crate::util::weaver_nest::WinnerRules;
+use crate::util::{self, settings};
+use crate::AppError;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::collections::HashMap;
+use std::sync::Arc;
+use tracing::{info, warn};
+
+/// Deserialize a call's params; missing params count as `{}`
+fn params<T: DeserializeOwned>(params: Value) -> Result<T, RpcError> {
+    let params = match params {
+        Value::Null => Value::Object(Map::new()),
+        params => params,
+    };
+    serde_json::from_value(params).map_err(|e| RpcError::InvalidParams(e.to_string()))
+}
+
+fn result<T: Serialize>(result: Result<T, AppError>) -> Result<Value, RpcError> {
+    let value = result?;
+    serde_json::to_value(value)
+        .map_err(|e| AppError::internal("Failed to serialize result", e.to_string()).into())
+}
+
+/// Whether builds started over RPC run the post-build hooks
+///
+/// Hooks run shell commands, so remote builds skip them unless the user
+/// turned this on in the app.
+fn remote_build_hooks_enabled() -> bool {
+    settings::load_settings().map_or(false, |s| s.remote_server.run_build_hooks)
+}
+
+/// Define `REMOTE_METHODS` and `dispatch` from a table of
+/// `method(arg: Type, ...) => call;` entries
+///
+/// Each method's arguments are read from a camelCase params object, like
+/// Tauri does for commands.
+macro_rules! remote_methods {
+    ($($method:ident($($arg:ident: $ty:ty),* $(,)?) => $call:expr;)*) => {
+        /// Methods `dispatch` understands
+        pub const REMOTE_METHODS: &[&str] = &[$(stringify!($method),)* "list_methods"];
+
+        /// Run one remote call
+        pub fn dispatch(method: &str, raw: Value) -> Result<Value, RpcError> {
+            match method {
+                $(stringify!($method) => {
+                    #[derive(Deserialize)]
+                    #[serde(rename_all = "camelCase")]
+                    struct Params {
+                        $($arg: $ty,)*
+                    }
+                    let Params { $($arg,)* } = params(raw)?;
+                    result($call)
+                })*
+                "list_methods" => result(Ok(REMOTE_METHODS)),
+                _ => Err(RpcError::MethodNotFound),
+            }
+        }
+    };
+}
+
+remote_methods! {
+    scan_packs_folder(packs_dir: String, force_reindex: Option<bool>, job_id: Option<String>)
+        => scan_packs_folder_impl(packs_dir, force_reindex.unwrap_or(false), None, job_id);
+    build_weaver_nest(request: BuildWeaverNestRequest, job_id: Option<String>) => {
+        let job = util::jobs::start_job(job_id.as_deref());
+        run_build(request, false, None, Some(job.token()), remote_build_hooks_enabled())
+    };
+    restore_last_session() => restore_last_session_impl(None);
+    update_session_stack(
+        packs_dir: String,
+        pack_order: Vec<String>,
+        overrides: Option<HashMap<String, OverrideSelection>>,
+    ) => update_session_stack_impl(packs_dir, pack_order, overrides.unwrap_or_default());
+    create_job() => create_job_impl();
+    cancel_job(job_id: String) => cancel_job_impl(job_id);
+    get_default_packs_dir() => get_default_packs_dir_impl();
+    initialize_vanilla_textures(job_id: Option<String>)
+        => initialize_vanilla_textures_impl(None, job_id);
+    get_vanilla_texture_path(asset_id: String) => get_vanilla_texture_path_impl(asset_id);
+    get_vanilla_mcmeta_path(asset_id: String) => get_vanilla_mcmeta_path_impl(asset_id);
+    get_colormap_path(colormap_type: String) => get_colormap_path_impl(colormap_type);
+    check_minecraft_installed() => check_minecraft_installed_impl();
+    get_suggested_minecraft_paths() => get_suggested_minecraft_paths_impl();
+    initialize_vanilla_textures_from_custom_dir(minecraft_dir: String)
+        => initialize_vanilla_textures_from_custom_dir_impl(minecraft_dir);
+    list_available_minecraft_versions() => list_available_minecraft_versions_impl();
+    get_cached_vanilla_version() => get_cached_vanilla_version_impl();
+    set_vanilla_texture_version(version: String, job_id: Option<String>)
+        => set_vanilla_texture_version_impl(version, None, job_id);
+    detect_launchers() => detect_launchers_impl();
+    identify_launcher(path: String) => identify_launcher_impl(path);
+    get_launcher_resourcepacks_dir(launcher_info: LauncherInfo)
+        => get_launcher_resourcepacks_dir_impl(launcher_info);
+    read_block_model(
+        pack_id: String,
+        model_id: String,
+        packs_dir: String,
+        display_context: Option<DisplayContext>,
+    ) => read_block_model_impl(pack_id, model_id, packs_dir, display_context);
+    read_pack_file(pack_path: String, file_path: String, is_zip: bool)
+        => read_pack_file_impl(pack_path, file_path, is_zip);
+    browse_pack(pack_path: String, is_zip: bool, dir: Option<String>, depth: Option<usize>)
+        => browse_pack_impl(pack_path, is_zip, dir, depth);
+    extract_pack_file(pack_path: String, is_zip: bool, file_path: String, destination: String)
+        => extract_pack_file_impl(pack_path, is_zip, file_path, destination);
+    extract_pack_archive(pack_path: String, destination: String)
+        => extract_pack_archive_impl(None, pack_path, destination);
+    compress_pack_folder(folder: String, destination: String)
+        => compress_pack_folder_impl(None, folder, destination);
+    repack_pack_folder(folder: String, destination: String)
+        => repack_pack_folder_impl(None, folder, destination);
+    build_merged_pack(
+        packs_dir: String,
+        pack_order: Vec<String>,
+        overrides: HashMap<String, OverrideSelection>,
+        output_path: String,
+        format: OutputFormat,
+        server: Option<ServerExportOptions>,
+    ) => build_merged_pack_impl(
+        None, packs_dir, pack_order, overrides, output_path, format, server,
+    );
+    migrate_pack(
+        packs_dir: String,
+        pack_id: String,
+        target_format: Option<u32>,
+        output_path: String,
+    ) => migrate_pack_impl(packs_dir, pack_id, target_format, output_path);
+    read_vanilla_jem(entity_type: String) => read_vanilla_jem_impl(entity_type);
+    load_model_json(
+        pack_id: String,
+        model_id: String,
+        packs_dir: String,
+        display_context: Option<DisplayContext>,
+        variant: Option<ResolvedModel>,
+    ) => load_model_json_impl(pack_id, model_id, packs_dir, display_context, variant);
+    load_item_model(
+        pack_id: String,
+        item_id: String,
+        packs_dir: String,
+        predicates: Option<HashMap<String, f64>>,
+        display_context: Option<DisplayContext>,
+    ) => load_item_model_impl(pack_id, item_id, packs_dir, predicates, display_context);
+    get_face_textures(
+        pack_id: String,
+        model_id: String,
+        packs_dir: String,
+        pack_order: Vec<String>,
+        overrides: HashMap<String, OverrideSelection>,
+        variant: Option<ResolvedModel>,
+    ) => get_face_textures_impl(pack_id, model_id, packs_dir, pack_order, overrides, variant);
+    get_texture_usage(packs_dir: String, texture_id: String)
+        => get_texture_usage_impl(packs_dir, texture_id);
+    get_override_dependencies(packs_dir: String, asset_id: String, pack_id: String)
+        => get_override_dependencies_impl(packs_dir, asset_id, pack_id);
+    diff_pack_models(
+        packs_dir: String,
+        model_id: String,
+        left_pack_id: String,
+        right_pack_id: String,
+    ) => diff_pack_models_impl(packs_dir, model_id, left_pack_id, right_pack_id);
+    get_block_state_schema(pack_id: String, block_id: String, packs_dir: String)
+        => get_block_state_schema_impl(pack_id, block_id, packs_dir);
+    resolve_block_state(
+        pack_id: String,
+        block_id: String,
+        packs_dir: String,
+        state_props: Option<HashMap<String, String>>,
+        seed: Option<u64>,
+    ) => resolve_block_state_impl(pack_id, block_id, packs_dir, state_props, seed);
+    list_vanilla_ids() => list_vanilla_ids_impl();
+    get_entity_version_variants(packs_dir: String) => get_entity_version_variants_impl(packs_dir);
+    list_conflict_presets() => list_conflict_presets_impl();
+    save_conflict_preset(preset: ConflictPreset) => save_conflict_preset_impl(preset);
+    delete_conflict_preset(name: String) => delete_conflict_preset_impl(name);
+    export_conflict_preset(name: String) => export_conflict_preset_impl(name);
+    import_conflict_preset(json: String) => import_conflict_preset_impl(json);
+    apply_conflict_preset(packs_dir: String, pack_order: Vec<String>, preset: ConflictPreset)
+        => apply_conflict_preset_impl(packs_dir, pack_order, preset);
+    list_projects() => list_projects_impl();
+    save_project(project: Project) => save_project_impl(project);
+    delete_project(name: String) => delete_project_impl(name);
+    record_activity(
+        packs_dir: String,
+        action: String,
+        params: serde_json::Value,
+        previous: Option<serde_json::Value>,
+        project_name: Option<String>,
+    ) => record_activity_impl(packs_dir, action, params, previous, project_name);
+    get_activity_log(
+        packs_dir: String,
+        actions: Option<Vec<String>>,
+        limit: Option<usize>,
+        project_name: Option<String>,
+    ) => get_activity_log_impl(packs_dir, actions, limit, project_name);
+    batch_build_projects(names: Vec<String>, concurrency: Option<usize>)
+        => batch_build_projects_impl(names, concurrency, remote_build_hooks_enabled());
+    list_path_grants() => list_path_grants_impl();
+    revoke_path_grant(path: String) => revoke_path_grant_impl(path);
+    get_diagnostics() => get_diagnostics_impl();
+    get_recent_logs(limit: Option<usize>) => get_recent_logs_impl(limit);
+    export_diagnostics(output_path: String) => export_diagnostics_impl(output_path);
+    verify_caches(repair: bool) => verify_caches_impl(repair, None);
+    clear_vanilla_cache(version: Option<String>) => clear_vanilla_cache_impl(version);
+    get_animation_info(pack_path: String, asset_id: String, is_zip: bool)
+        => get_animation_info_impl(pack_path, asset_id, is_zip);
+    get_animation_frames(pack_path: String, asset_id: String, is_zip: bool)
+        => get_animation_frames_impl(pack_path, asset_id, is_zip);
+    get_texture_pyramid(pack_path: String, asset_id: String, is_zip: bool)
+        => get_texture_pyramid_impl(pack_path, asset_id, is_zip);
+    get_texture_tile(
+        pack_path: String,
+        asset_id: String,
+        is_zip: bool,
+        level: u32,
+        column: u32,
+        row: u32,
+    ) => get_texture_tile_impl(pack_path, asset_id, is_zip, level, column, row);
+    get_entity_groups(packs_dir: String) => get_entity_groups_impl(packs_dir);
+    get_gui_regions(asset_id: String, pack_path: Option<String>, is_zip: bool)
+        => get_gui_regions_impl(asset_id, pack_path, is_zip);
+    list_gui_atlases() => list_gui_atlases_impl();
+    get_tinted_texture(
+        pack_path: String,
+        is_zip: bool,
+        asset_id: String,
+        biome: Option<String>,
+        colormap: Option<Colormap>,
+    ) => get_tinted_texture_impl(pack_path, is_zip, asset_id, biome, colormap);
+    list_biomes() => list_biomes_impl();
+    get_texture_history(packs_dir: String, pack_id: String, asset_id: String)
+        => get_texture_history_impl(packs_dir, pack_id, asset_id);
+    get_custom_model_data_report(packs_dir: String, pack_ids: Option<Vec<String>>)
+        => get_custom_model_data_report_impl(packs_dir, pack_ids);
+    export_custom_model_data_report(
+        packs_dir: String,
+        pack_ids: Option<Vec<String>>,
+        output_path: String,
+    ) => export_custom_model_data_report_impl(packs_dir, pack_ids, output_path);
+    export_pack_report(
+        packs_dir: String,
+        pack_order: Vec<String>,
+        overrides: HashMap<String, OverrideSelection>,
+        kind: ReportKind,
+        format: ReportFormat,
+        output_path: String,
+    ) => export_pack_report_impl(packs_dir, pack_order, overrides, kind, format, output_path);
+    detect_namespace_collisions(packs_dir: String, pack_order: Vec<String>)
+        => detect_namespace_collisions_impl(packs_dir, pack_order);
+    get_ctm_report(packs_dir: String, pack_order: Vec<String>)
+        => get_ctm_report_impl(packs_dir, pack_order);
+    get_conflict_report(
+        packs_dir: String,
+        pack_order: Vec<String>,
+        overrides: HashMap<String, OverrideSelection>,
+        namespaces: Option<Vec<String>>,
+    ) => get_conflict_report_impl(packs_dir, pack_order, overrides, namespaces);
+    get_item_model_conflicts(packs_dir: String, pack_order: Vec<String>)
+        => get_item_model_conflicts_impl(packs_dir, pack_order);
+    get_namespace_coverage(
+        packs_dir: String,
+        pack_order: Vec<String>,
+        overrides: HashMap<String, OverrideSelection>,
+    ) => get_namespace_coverage_impl(packs_dir, pack_order, overrides);
+    search_lang_entries(
+        packs_dir: String,
+        pack_order: Vec<String>,
+        query: String,
+        locale: Option<String>,
+    ) => search_lang_entries_impl(packs_dir, pack_order, query, locale);
+    inspect_packs_folder(packs_dir: String) => inspect_packs_folder_impl(packs_dir);
+    download_cloud_placeholders(packs_dir: String) => download_cloud_placeholders_impl(packs_dir);
+    get_feature_requirements(packs_dir: String, pack_order: Vec<String>)
+        => get_feature_requirements_impl(packs_dir, pack_order);
+    get_pack_health_report(packs_dir: String, pack_ids: Option<Vec<String>>)
+        => get_pack_health_report_impl(packs_dir, pack_ids);
+    suggest_pack_order(packs_dir: String, pack_ids: Option<Vec<String>>)
+        => suggest_pack_order_impl(packs_dir, pack_ids);
+    get_pack_palettes(packs_dir: String, pack_ids: Option<Vec<String>>)
+        => get_pack_palettes_impl(packs_dir, pack_ids);
+    compute_output_sha1(output_path: String) => compute_output_sha1_impl(output_path);
+    get_parallelism_settings(packs_dir: Option<String>) => get_parallelism_settings_impl(packs_dir);
+    set_parallelism_settings(parallelism: ParallelismSettings)
+        => set_parallelism_settings_impl(parallelism);
+    get_vanilla_version_settings() => get_vanilla_version_settings_impl();
+    set_vanilla_version_settings(vanilla_version: VanillaVersionSettings)
+        => set_vanilla_version_settings_impl(vanilla_version);
+    get_conflict_severity_settings() => get_conflict_severity_settings_impl();
+    set_conflict_severity_settings(conflict_severity: ConflictSeveritySettings)
+        => set_conflict_severity_settings_impl(conflict_severity);
+    list_interrupted_builds() => list_interrupted_builds_impl();
+    discard_interrupted_build(output_dir: String) => discard_interrupted_build_impl(output_dir);
+    clear_stale_lock(lock_path: String, force: Option<bool>)
+        => clear_stale_lock_impl(lock_path, force);
+    resume_interrupted_build(output_dir: String, job_id: Option<String>)
+        => resume_interrupted_build_impl(output_dir, job_id, remote_build_hooks_enabled());
+    check_instance_stack(instance_dir: String) => check_instance_stack_impl(instance_dir);
+    render_preview_scene(
+        packs_dir: String,
+        pack_order: Vec<String>,
+        overrides: HashMap<String, OverrideSelection>,
+        output_path: String,
+        tile_size: Option<u32>,
+        color_filter: Option<ColorVisionFilter>,
+        rules: Option<WinnerRules>,
+    ) => render_preview_scene_impl(
+        packs_dir, pack_order, overrides, output_path, tile_size, color_filter, rules,
+    );
+    render_doc_previews(
+        packs_dir: String,
+        pack_order: Vec<String>,
+        overrides: HashMap<String, OverrideSelection>,
+        asset_ids: Vec<String>,
+        output_dir: String,
+        options: Option<DocPreviewOptions>,
+        rules: Option<WinnerRules>,
+    ) => render_doc_previews_impl(
+        packs_dir, pack_order, overrides, asset_ids, output_dir, options, rules,
+    );
+    export_contact_sheet(
+        packs_dir: String,
+        pack_order: Vec<String>,
+        overrides: HashMap<String, OverrideSelection>,
+        category: String,
+        output_path: String,
+        tile_size: Option<u32>,
+        columns: Option<u32>,
+    ) => export_contact_sheet_impl(
+        packs_dir, pack_order, overrides, category, output_path, tile_size, columns,
+    );
+    export_comparison_images(
+        packs_dir: String,
+        pack_order: Vec<String>,
+        overrides: HashMap<String, OverrideSelection>,
+        asset_ids: Vec<String>,
+        layout: ComparisonLayout,
+        size: u32,
+        output_dir: String,
+        rules: Option<WinnerRules>,
+    ) => export_comparison_images_impl(
+        packs_dir, pack_order, overrides, asset_ids, layout, size, output_dir, rules,
+    );
+    simulate_color_vision(input_path: String, filter: ColorVisionFilter, output_path: String)
+        => simulate_color_vision_impl(input_path, filter, output_path);
+    get_applied_pack_status(project: Project) => get_applied_pack_status_impl(project);
+    simulate_stacks(project: Project, a: String, b: String) => simulate_stacks_impl(project, a, b);
+    seed_overrides_from_project(project_name: String, packs_dir: String)
+        => seed_overrides_from_project_impl(project_name, packs_dir);
+    get_next_conflict(project_name: String, revisit_skipped: bool)
+        => get_next_conflict_impl(project_name, revisit_skipped);
+    record_conflict_resolution(
+        project_name: String,
+        asset_id: String,
+        action: ResolutionAction,
+        revisit_skipped: bool,
+    ) => record_conflict_resolution_impl(project_name, asset_id, action, revisit_skipped);
+    reset_conflict_resolution(project_name: String) => reset_conflict_resolution_impl(project_name);
+    check_pack_removal(packs_dir: String, pack_id: String)
+        => check_pack_removal_impl(packs_dir, pack_id);
+    quarantine_pack(packs_dir: String, pack_id: String, confirm_referenced: bool)
+        => quarantine_pack_impl(packs_dir, pack_id, confirm_referenced);
+    list_quarantined_packs(packs_dir: String) => list_quarantined_packs_impl(packs_dir);
+    restore_quarantined_pack(packs_dir: String, quarantine_id: String)
+        => restore_quarantined_pack_impl(packs_dir, quarantine_id);
+    purge_quarantined_pack(packs_dir: String, quarantine_id: String)
+        => purge_quarantined_pack_impl(packs_dir, quarantine_id);
+    get_running_games(target_dir: Option<String>) => get_running_games_impl(target_dir);
+    list_queued_installs() => list_queued_installs_impl();
+    apply_queued_installs() => apply_queued_installs_impl();
+    discard_queued_install(output_dir: String) => discard_queued_install_impl(output_dir);
+    get_running_game_policy() => get_running_game_policy_impl();
+    set_running_game_policy(policy: RunningGamePolicy) => set_running_game_policy_impl(policy);
+    get_description_template() => get_description_template_impl();
+    set_description_template(template: Option<String>) => set_description_template_impl(template);
+    render_pack_description(template: String, context: DescriptionContext)
+        => render_pack_description_impl(template, context);
+    list_modrinth_profiles(profiles_dir: Option<String>)
+        => list_modrinth_profiles_impl(profiles_dir);
+    list_launcher_instances(instances_dir: Option<String>)
+        => list_launcher_instances_impl(instances_dir);
+    download_remote_pack(
+        url: String,
+        packs_dir: String,
+        file_name: Option<String>,
+        sha1: Option<String>,
+        size: Option<u64>,
+        overwrite: Option<bool>,
+        job_id: Option<String>,
+    ) => download_remote_pack_impl(None, url, packs_dir, file_name, sha1, size, overwrite, job_id);
+    export_animation(
+        pack_path: String,
+        asset_id: String,
+        is_zip: bool,
+        format: ExportFormat,
+        size: u32,
+        output_path: String,
+    ) => export_animation_impl(pack_path, asset_id, is_zip, format, size, output_path);
+}
+
+fn load_settings() -> Result<settings::AppSettings, AppError> {
+    settings::load_settings().map_err(|e| AppError::io(format!("Failed to load settings: {}", e)))
+}
+
+fn save_settings(app_settings: &settings::AppSettings) -> Result<(), AppError> {
+    settings::save_settings(app_settings)
+        .map_err(|e| AppError::io(format!("Failed to save settings: {}", e)))
+}
+
+/// Start the server with the saved settings, generating a token if needed
+fn start_with(app_settings: &mut settings::AppSettings) -> Result<RemoteServerStatus, AppError> {
+    let remote = &mut app_settings.remote_server;
+    let token = remote
+        .token
+        .get_or_insert_with(rpc_server::generate_token)
+        .clone();
+    rpc_server::start_server(remote.port, remote.allow_lan, &token, Arc::new(dispatch)).map_err(
+        |e| {
+            AppError::io(format!("Failed to start remote server: {}", e))
+                .with_details(e.to_string())
+        },
+    )
+}
+
+/// Start the remote control server and keep it enabled on later starts
+///
+/// # Arguments
+/// * `port` - Port to listen on; the saved one if None
+/// * `allow_lan` - Listen on all interfaces; the saved choice if None
+/// * `run_build_hooks` - Run post-build hooks for builds started over RPC;
+///   the saved choice if None
+///
+/// # Returns
+/// Address and token clients use
+///
+/// # Errors
+/// - VALIDATION_ERROR: The server is already running
+/// - IO_ERROR: The port can't be bound, or settings can't be saved
+pub fn start_remote_server_impl(
+    port: Option<u16>,
+    allow_lan: Option<bool>,
+    run_build_hooks: Option<bool>,
+) -> Result<RemoteServerStatus, AppError> {
+    if rpc_server::server_status().running {
+        return Err(AppError::validation("Remote server is already running"));
+    }
+
+    let _settings_lock = settings::lock_settings();
+    let mut app_settings = load_settings()?;
+    if let Some(port) = port {
+        app_settings.remote_server.port = port;
+    }
+    if let Some(allow_lan) = allow_lan {
+        app_settings.remote_server.allow_lan = allow_lan;
+    }
+    if let Some(run_build_hooks) = run_build_hooks {
+        app_settings.remote_server.run_build_hooks = run_build_hooks;
+    }
+    let status = start_with(&mut app_settings)?;
+    app_settings.remote_server.enabled = true;
+    save_settings(&app_settings)?;
+    Ok(status)
+}
+
+/// Stop the remote control server and keep it off on later starts
+pub fn stop_remote_server_impl() -> Result<RemoteServerStatus, AppError> {
+    rpc_server::stop_server();
+    let _settings_lock = settings::lock_settings();
+    let mut app_settings = load_settings()?;
+    app_settings.remote_server.enabled = false;
+    save_settings(&app_settings)?;
+    Ok(rpc_server::server_status())
+}
+
+/// Whether the remote control server is running, and its address and token
+pub fn get_remote_server_status_impl() -> Result<RemoteServerStatus, AppError> {
+    Ok(rpc_server::server_status())
+}
+
+/// Replace the token, e.g. after it leaked
+///
+/// A running server switches to the new token in place, so the old one
+/// stops working without the port being released.
+pub fn regenerate_remote_server_token_impl() -> Result<RemoteServerStatus, AppError> {
+    let _settings_lock = settings::lock_settings();
+    let mut app_settings = load_settings()?;
+    let token = rpc_server::generate_token();
+    app_settings.remote_server.token = Some(token.clone());
+    save_settings(&app_settings)?;
+
+    rpc_server::set_server_token(&token);
+    Ok(rpc_server::server_status())
+}
+
+/// Start the server on launch if it was left enabled
+pub fn start_remote_server_if_enabled() {
+    let _settings_lock = settings::lock_settings();
+    let mut app_settings = match settings::load_settings() {
+        Ok(app_settings) if app_settings.remote_server.enabled => app_settings,
+        _ => return,
+    };
+    let had_token = app_settings.remote_server.token.is_some();
+    match start_with(&mut app_settings) {
+        Ok(_) if !had_token => {
+            if let Err(e) = settings::save_settings(&app_settings) {
+                warn!("Failed to save generated token: {}", e);
+            }
+        }
+        Ok(_) => {}
+        Err(e) => info!("{}", e.message),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_dispatch() {
+        let job_id = dispatch("create_job", Value::Null).unwrap();
+        let cancelled = dispatch("cancel_job", json!({ "jobId": job_id })).unwrap();
+        let missing = dispatch("cancel_job", json!({}));
+        let unknown = dispatch("select_folder", Value::Null);
+        let methods = dispatch("list_methods", Value::Null).unwrap();
+        let biomes = dispatch("list_biomes", Value::Null).unwrap();
+
+        assert!(job_id.as_str().unwrap().starts_with("job-"));
+        // Nothing started the job, so it's still registered
+        assert_eq!(cancelled, json!(true));
+        assert!(matches!(missing, Err(RpcError::InvalidParams(_))));
+        assert!(matches!(unknown, Err(RpcError::MethodNotFound)));
+        let methods: Vec<String> = serde_json::from_value(methods).unwrap();
+        assert!(methods.iter().any(|m| m == "export_animation"));
+        assert!(!methods.iter().any(|m| m == "start_remote_server"));
+        assert!(!methods.iter().any(|m| m.ends_with("post_build_hooks")));
+        assert!(!biomes.as_array().unwrap().is_empty());
+    }
+}
@@ -0,0 +1,191 @@
+/// Commands for pack and conflict reports
+use crate::model::OverrideSelection;
+use crate::util::conflicts::{self, ConflictReport};
+use crate::util::item_models::{self, ItemModelConflict};
+use crate::util::namespace_coverage::{self, NamespaceCoverage};
+use crate::util::report_export::{self, ReportFormat, ReportKind};
+use crate::util::{
+    asset_indexer, content_hash, known_packs, model_texture_deps, pack_scanner, settings,
+    vanilla_textures,
+};
+use crate::{validation, AppError};
+use std::collections::HashMap;
+use tracing::info;
+
+/// Get which pack wins each contested asset and which packs lose it
+///
+/// # Arguments
+/// * `packs_dir` - Directory containing resource packs
+/// * `pack_order` - Enabled pack IDs in priority order (first = highest)
+/// * `overrides` - Asset ID -> chosen pack
+/// * `namespaces` - Only report these namespaces (all if None or empty)
+///
+/// # Returns
+/// Conflicts grouped by namespace and category, most severe first, and
+/// asset counts per winning/losing pack pair
+///
+/// # Errors
+/// - VALIDATION_ERROR: Packs directory is invalid
+/// - SCAN_ERROR: Failed to scan or index packs
+/// - IO_ERROR: Failed to load settings
+pub fn get_conflict_report_impl(
+    packs_dir: String,
+    pack_order: Vec<String>,
+    overrides: HashMap<String, OverrideSelection>,
+    namespaces: Option<Vec<String>>,
+) -> Result<ConflictReport, AppError> {
+    validation::validate_directory(&packs_dir, "Packs directory")?;
+    validation::validate_path_access(&packs_dir, "Packs directory")?;
+
+    let packs = pack_scanner::scan_packs(&packs_dir)
+        .map_err(|e| AppError::scan(format!("Failed to scan packs: {}", e)))?;
+    let (_, providers, hash_index) = asset_indexer::index_assets_with_hashes(&packs)
+        .map_err(|e| AppError::scan(format!("Asset indexing failed: {}", e)))?;
+    let identical = content_hash::identical_provider_groups(&providers, &hash_index.pack_hashes);
+    let settings = settings::load_settings()
+        .map_err(|e| AppError::io(format!("Failed to load settings: {}", e)))?;
+
+    Ok(conflicts::build_conflict_report(
+        &pack_order,
+        &overrides,
+        &providers,
+        &identical,
+        &namespaces.unwrap_or_default(),
+        &settings.conflict_severity,
+    ))
+}
+
+/// Get items whose model more than one enabled pack provides
+///
+/// The texture conflict report only covers textures; this shows which
+/// pack's item model (and so its layers and overrides) the game will use.
+///
+/// # Arguments
+/// * `packs_dir` - Directory containing resource packs
+/// * `pack_order` - Enabled pack IDs in priority order (first = highest)
+///
+/// # Errors
+/// - VALIDATION_ERROR: Packs directory is invalid
+/// - SCAN_ERROR: Failed to scan packs
+pub fn get_item_model_conflicts_impl(
+    packs_dir: String,
+    pack_order: Vec<String>,
+) -> Result<Vec<ItemModelConflict>, AppError> {
+    validation::validate_directory(&packs_dir, "Packs directory")?;
+    validation::validate_path_access(&packs_dir, "Packs directory")?;
+
+    let packs = pack_scanner::scan_packs(&packs_dir)
+        .map_err(|e| AppError::scan(format!("Failed to scan packs: {}", e)))?;
+    let conflicts = item_models::find_item_model_conflicts(&packs, &pack_order);
+    info!(
+        "{} item model(s) provided by several packs",
+        conflicts.len()
+    );
+
+    Ok(conflicts)
+}
+
+/// Get how much of each namespace every pack covers and wins
+///
+/// # Arguments
+/// * `packs_dir` - Directory containing resource packs
+/// * `pack_order` - Enabled pack IDs in priority order (first = highest)
+/// * `overrides` - Asset ID -> chosen pack
+///
+/// # Returns
+/// Namespaces with the most assets first, each with its providing packs
+///
+/// # Errors
+/// - VALIDATION_ERROR: Packs directory is invalid
+/// - SCAN_ERROR: Failed to scan or index packs
+pub fn get_namespace_coverage_impl(
+    packs_dir: String,
+    pack_order: Vec<String>,
+    overrides: HashMap<String, OverrideSelection>,
+) -> Result<Vec<NamespaceCoverage>, AppError> {
+    validation::validate_directory(&packs_dir, "Packs directory")?;
+    validation::validate_path_access(&packs_dir, "Packs directory")?;
+
+    let packs = pack_scanner::scan_packs(&packs_dir)
+        .map_err(|e| AppError::scan(format!("Failed to scan packs: {}", e)))?;
+    let (_, providers) = asset_indexer::index_assets(&packs)
+        .map_err(|e| AppError::scan(format!("Asset indexing failed: {}", e)))?;
+
+    Ok(namespace_coverage::namespace_coverage(
+        &pack_order,
+        &overrides,
+        &providers,
+    ))
+}
+
+/// Write the pack inventory or conflict report as CSV or Markdown
+///
+/// # Arguments
+/// * `packs_dir` - Directory containing resource packs
+/// * `pack_order` - Enabled pack IDs in priority order (first = highest)
+/// * `overrides` - Asset ID -> chosen pack
+/// * `kind` - Which report to write
+/// * `format` - CSV or Markdown
+/// * `output_path` - File to write the report to
+///
+/// # Returns
+/// Path of the written report
+///
+/// # Errors
+/// - VALIDATION_ERROR: Packs directory or output path is invalid
+/// - SCAN_ERROR: Failed to scan or index packs
+/// - IO_ERROR: Failed to write the report
+pub fn export_pack_report_impl(
+    packs_dir: String,
+    pack_order: Vec<String>,
+    overrides: HashMap<String, OverrideSelection>,
+    kind: ReportKind,
+    format: ReportFormat,
+    output_path: String,
+) -> Result<String, AppError> {
+    validation::validate_directory(&packs_dir, "Packs directory")?;
+    validation::validate_path_access(&packs_dir, "Packs directory")?;
+    validation::validate_path_access(&output_path, "Report file")?;
+
+    let packs = pack_scanner::scan_packs(&packs_dir)
+        .map_err(|e| AppError::scan(format!("Failed to scan packs: {}", e)))?;
+
+    let report = match kind {
+        ReportKind::Packs => {
+            let (_, providers, index) = asset_indexer::index_assets_with_files(&packs)
+                .map_err(|e| AppError::scan(format!("Asset indexing failed: {}", e)))?;
+            let known = known_packs::identify_known_packs(&packs, &index.pack_files);
+            report_export::render_pack_inventory(format, &packs, &pack_order, &providers, &known)
+        }
+        ReportKind::Conflicts => {
+            let (_, providers, hash_index) = asset_indexer::index_assets_with_hashes(&packs)
+                .map_err(|e| AppError::scan(format!("Asset indexing failed: {}", e)))?;
+            let identical =
+                content_hash::identical_provider_groups(&providers, &hash_index.pack_hashes);
+            let vanilla_dir = vanilla_textures::get_vanilla_cache_dir().ok();
+            let model_issues = model_texture_deps::find_model_texture_issues(
+                &packs,
+                &pack_order,
+                &overrides,
+                &providers,
+                &identical,
+                vanilla_dir.as_deref(),
+            );
+            report_export::render_conflicts(
+                format,
+                &pack_order,
+                &overrides,
+                &providers,
+                &identical,
+                &model_issues,
+            )
+        }
+    };
+
+    std::fs::write(&output_path, report)
+        .map_err(|e| AppError::io(format!("Failed to write report: {}", e)))?;
+
+    info!("Wrote {:?} report ({:?}) to {}", kind, format, output_path);
+
+    Ok(output_path)
+}
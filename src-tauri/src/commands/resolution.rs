@@ -0,0 +1,135 @@
+/// Commands for guided conflict resolution
+use crate::util::conflicts::{self, ConflictReport, ConflictSeveritySettings};
+use crate::util::projects::{self, Project};
+use crate::util::resolution_queue::{self, ResolutionAction, ResolutionProgress};
+use crate::util::{asset_indexer, content_hash, pack_scanner, settings};
+use crate::{validation, AppError};
+use tracing::info;
+
+/// Conflicts of a project's enabled packs, order and overrides
+fn project_conflicts(
+    project: &Project,
+    severities: &ConflictSeveritySettings,
+) -> Result<ConflictReport, AppError> {
+    validation::validate_directory(&project.packs_dir, "Packs directory")?;
+    validation::validate_path_access(&project.packs_dir, "Packs directory")?;
+
+    let packs = pack_scanner::scan_packs(&project.packs_dir)
+        .map_err(|e| AppError::scan(format!("Failed to scan packs: {}", e)))?;
+    let (_, providers, hash_index) = asset_indexer::index_assets_with_hashes(&packs)
+        .map_err(|e| AppError::scan(format!("Asset indexing failed: {}", e)))?;
+    let identical = content_hash::identical_provider_groups(&providers, &hash_index.pack_hashes);
+
+    Ok(conflicts::build_conflict_report(
+        &projects::enabled_pack_order(&project.pack_order, &project.disabled_packs),
+        &projects::active_overrides(&project.overrides, &project.disabled_packs),
+        &projects::without_disabled_packs(&providers, &project.disabled_packs),
+        &identical,
+        &[],
+        severities,
+    ))
+}
+
+fn not_found(name: &str) -> AppError {
+    AppError::validation(format!("Project not found: {}", name))
+}
+
+/// Get the next conflict to resolve in a saved project
+///
+/// Conflicts come most severe first, grouped by category; ones won through
+/// an override count as resolved and ones between identical files are left
+/// out.
+///
+/// # Arguments
+/// * `project_name` - Saved project to resolve
+/// * `revisit_skipped` - Return skipped conflicts once nothing else is left
+///
+/// # Errors
+/// - VALIDATION_ERROR: Unknown project or invalid packs directory
+/// - SCAN_ERROR: Failed to scan or index packs
+/// - IO_ERROR: Failed to load settings
+pub fn get_next_conflict_impl(
+    project_name: String,
+    revisit_skipped: bool,
+) -> Result<ResolutionProgress, AppError> {
+    let settings = settings::load_settings()
+        .map_err(|e| AppError::io(format!("Failed to load settings: {}", e)))?;
+    let project = settings
+        .projects
+        .iter()
+        .find(|p| p.name == project_name)
+        .ok_or_else(|| not_found(&project_name))?;
+
+    let report = project_conflicts(project, &settings.conflict_severity)?;
+    Ok(resolution_queue::next_conflict(
+        &report,
+        &project.resolution,
+        revisit_skipped,
+    ))
+}
+
+/// Mark a conflict resolved, skipped or open again, then get the next one
+///
+/// # Arguments
+/// * `project_name` - Saved project to resolve
+/// * `asset_id` - Conflicting asset
+/// * `action` - What the user did with it
+/// * `revisit_skipped` - Return skipped conflicts once nothing else is left
+///
+/// # Errors
+/// - VALIDATION_ERROR: Unknown project or invalid packs directory
+/// - SCAN_ERROR: Failed to scan or index packs
+/// - IO_ERROR: Failed to read or write settings
+pub fn record_conflict_resolution_impl(
+    project_name: String,
+    asset_id: String,
+    action: ResolutionAction,
+    revisit_skipped: bool,
+) -> Result<ResolutionProgress, AppError> {
+    let _settings_lock = settings::lock_settings();
+    let mut settings = settings::load_settings()
+        .map_err(|e| AppError::io(format!("Failed to load settings: {}", e)))?;
+    let project = settings
+        .projects
+        .iter_mut()
+        .find(|p| p.name == project_name)
+        .ok_or_else(|| not_found(&project_name))?;
+    resolution_queue::apply_action(&mut project.resolution, &asset_id, action);
+    let project = project.clone();
+
+    settings::save_settings(&settings)
+        .map_err(|e| AppError::io(format!("Failed to save settings: {}", e)))?;
+    info!("{:?} conflict {} in '{}'", action, asset_id, project_name);
+
+    let report = project_conflicts(&project, &settings.conflict_severity)?;
+    Ok(resolution_queue::next_conflict(
+        &report,
+        &project.resolution,
+        revisit_skipped,
+    ))
+}
+
+/// Forget which conflicts of a project were resolved or skipped
+///
+/// Overrides stay; conflicts they decide still count as resolved.
+///
+/// # Errors
+/// - VALIDATION_ERROR: Unknown project
+/// - IO_ERROR: Failed to read or write settings
+pub fn reset_conflict_resolution_impl(project_name: String) -> Result<Project, AppError> {
+    let _settings_lock = settings::lock_settings();
+    let mut settings = settings::load_settings()
+        .map_err(|e| AppError::io(format!("Failed to load settings: {}", e)))?;
+    let project = settings
+        .projects
+        .iter_mut()
+        .find(|p| p.name == project_name)
+        .ok_or_else(|| not_found(&project_name))?;
+    project.resolution = Default::default();
+    let project = project.clone();
+
+    settings::save_settings(&settings)
+        .map_err(|e| AppError::io(format!("Failed to save settings: {}", e)))?;
+
+    Ok(project)
+}
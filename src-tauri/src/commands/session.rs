@@ -0,0 +1,120 @@
+/// Commands for restoring the last session on launch
+use crate::commands::packs::scan_packs_folder_impl;
+use crate::model::OverrideSelection;
+use crate::util::jobs;
+use crate::util::session_cache::{
+    self, RestoredSession, SessionReconciled, SessionSnapshot, SESSION_RECONCILED_EVENT,
+    SESSION_RECONCILE_FAILED_EVENT,
+};
+use crate::AppError;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use tracing::{info, warn};
+
+fn snapshot_path() -> Result<PathBuf, AppError> {
+    session_cache::session_snapshot_path()
+        .ok_or_else(|| AppError::io("Could not find cache directory"))
+}
+
+fn load_snapshot(path: &Path) -> Result<Option<SessionSnapshot>, AppError> {
+    session_cache::load_snapshot(path)
+        .map_err(|e| AppError::io(format!("Failed to load session snapshot: {:#}", e)))
+}
+
+/// Rescan the snapshot's folder and tell the window what changed
+fn reconcile(window: tauri::Window, previous: SessionSnapshot, job_id: String) {
+    use tauri::Emitter;
+
+    let packs_dir = previous.packs_dir.clone();
+    let scan = scan_packs_folder_impl(packs_dir.clone(), false, Some(window.clone()), Some(job_id));
+    let emitted = match scan {
+        Ok(scan) => {
+            let snapshot = session_cache::snapshot_from_scan(&packs_dir, scan, Some(&previous));
+            let changes = session_cache::diff_snapshots(&previous, &snapshot);
+            info!(
+                "Restored session reconciled: {} pack(s) added, {} removed, {} winner(s) changed",
+                changes.added_packs.len(),
+                changes.removed_packs.len(),
+                changes.changed_winners.len()
+            );
+            window.emit(
+                SESSION_RECONCILED_EVENT,
+                SessionReconciled { snapshot, changes },
+            )
+        }
+        Err(e) => {
+            warn!("Failed to reconcile restored session: {}", e.message);
+            window.emit(SESSION_RECONCILE_FAILED_EVENT, e)
+        }
+    };
+    if let Err(e) = emitted {
+        warn!("Failed to emit session event: {}", e);
+    }
+}
+
+/// Restore the last session from the snapshot saved by the last scan
+///
+/// Returns at once; the snapshot may be out of date. With a window, the
+/// packs folder is rescanned in the background, emitting the usual scan
+/// progress events and then "session:reconciled" with the fresh snapshot
+/// and what changed, or "session:reconcile-failed" with the error.
+///
+/// # Arguments
+/// * `window` - Window to reconcile for; without one nothing is rescanned
+///
+/// # Returns
+/// None if no session was saved yet
+///
+/// # Errors
+/// - IO_ERROR: The snapshot can't be read
+pub fn restore_last_session_impl(
+    window: Option<tauri::Window>,
+) -> Result<Option<RestoredSession>, AppError> {
+    let snapshot = match load_snapshot(&snapshot_path()?)? {
+        Some(snapshot) => snapshot,
+        None => return Ok(None),
+    };
+    info!(
+        "Restored session of {} ({} packs, {} assets)",
+        snapshot.packs_dir,
+        snapshot.scan.packs.len(),
+        snapshot.scan.assets.len()
+    );
+
+    let reconcile_job_id = window.map(|window| {
+        let job_id = jobs::create_job();
+        let previous = snapshot.clone();
+        let thread_job_id = job_id.clone();
+        std::thread::spawn(move || reconcile(window, previous, thread_job_id));
+        job_id
+    });
+
+    Ok(Some(RestoredSession {
+        snapshot,
+        possibly_stale: true,
+        reconcile_job_id,
+    }))
+}
+
+/// Record the pack order and overrides to restore with the session
+///
+/// # Returns
+/// Whether the saved session is of `packs_dir` and was updated
+///
+/// # Errors
+/// - IO_ERROR: The snapshot can't be read or written
+pub fn update_session_stack_impl(
+    packs_dir: String,
+    pack_order: Vec<String>,
+    overrides: HashMap<String, OverrideSelection>,
+) -> Result<bool, AppError> {
+    let path = snapshot_path()?;
+    let mut snapshot = match load_snapshot(&path)? {
+        Some(snapshot) if snapshot.packs_dir == packs_dir => snapshot,
+        _ => return Ok(false),
+    };
+    session_cache::update_stack(&mut snapshot, pack_order, overrides);
+    session_cache::save_snapshot(&path, &snapshot)
+        .map_err(|e| AppError::io(format!("Failed to save session snapshot: {:#}", e)))?;
+    Ok(true)
+}
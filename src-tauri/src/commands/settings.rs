@@ -0,0 +1,305 @@
+/// Commands for application settings
+use crate::util::conflicts::ConflictSeveritySettings;
+use crate::util::pack_description::{self, DescriptionContext};
+use crate::util::parallelism::{self, ParallelismSettings, ParallelismStatus, MAX_THREADS};
+use crate::util::post_build::PostBuildHook;
+use crate::util::running_game::RunningGamePolicy;
+use crate::util::vanilla_textures::{VanillaVersionSettings, VersionPreference};
+use crate::util::{path_policy, settings};
+use crate::{validation, AppError};
+use std::path::Path;
+use tracing::info;
+
+/// Get the configured post-build hooks
+pub fn get_post_build_hooks_impl() -> Result<Vec<PostBuildHook>, AppError> {
+    let settings = settings::load_settings()
+        .map_err(|e| AppError::io(format!("Failed to load settings: {}", e)))?;
+    Ok(settings.post_build_hooks)
+}
+
+/// Replace the configured post-build hooks
+///
+/// # Errors
+/// - VALIDATION_ERROR: A run-command hook has an empty command
+/// - IO_ERROR: Failed to read or write settings
+pub fn set_post_build_hooks_impl(
+    hooks: Vec<PostBuildHook>,
+) -> Result<Vec<PostBuildHook>, AppError> {
+    for hook in &hooks {
+        if let PostBuildHook::RunCommand { command } = hook {
+            if command.trim().is_empty() {
+                return Err(AppError::validation("Post-build command cannot be empty"));
+            }
+        }
+    }
+
+    let _settings_lock = settings::lock_settings();
+    let mut settings = settings::load_settings()
+        .map_err(|e| AppError::io(format!("Failed to load settings: {}", e)))?;
+    settings.post_build_hooks = hooks;
+    settings::save_settings(&settings)
+        .map_err(|e| AppError::io(format!("Failed to save settings: {}", e)))?;
+
+    Ok(settings.post_build_hooks)
+}
+
+/// Get the thread pool settings and the thread counts they resolve to
+///
+/// # Arguments
+/// * `packs_dir` - Optional packs directory used to detect the drive type
+pub fn get_parallelism_settings_impl(
+    packs_dir: Option<String>,
+) -> Result<ParallelismStatus, AppError> {
+    if let Some(dir) = &packs_dir {
+        validation::validate_path_access(dir, "Packs directory")?;
+    }
+
+    let settings = settings::load_settings()
+        .map_err(|e| AppError::io(format!("Failed to load settings: {}", e)))?;
+    let drive_kind = packs_dir
+        .as_deref()
+        .map_or(parallelism::DriveKind::Unknown, |dir| {
+            parallelism::detect_drive_kind(Path::new(dir))
+        });
+
+    Ok(ParallelismStatus {
+        next_start: parallelism::resolve_parallelism(
+            &settings.parallelism,
+            parallelism::cpu_count(),
+            drive_kind,
+        ),
+        active: parallelism::active_parallelism().cloned(),
+        settings: settings.parallelism,
+    })
+}
+
+/// Save thread pool settings; they apply on the next start
+///
+/// # Errors
+/// - VALIDATION_ERROR: A thread count is 0 or above the maximum
+/// - IO_ERROR: Failed to read or write settings
+pub fn set_parallelism_settings_impl(
+    parallelism: ParallelismSettings,
+) -> Result<ParallelismSettings, AppError> {
+    for (label, value) in [
+        ("Thread count", parallelism.thread_count),
+        ("IO parallelism", parallelism.io_parallelism),
+    ] {
+        if let Some(value) = value {
+            if value == 0 || value > MAX_THREADS {
+                return Err(AppError::validation(format!(
+                    "{} must be between 1 and {}",
+                    label, MAX_THREADS
+                )));
+            }
+        }
+    }
+
+    let _settings_lock = settings::lock_settings();
+    let mut settings = settings::load_settings()
+        .map_err(|e| AppError::io(format!("Failed to load settings: {}", e)))?;
+    settings.parallelism = parallelism;
+    settings::save_settings(&settings)
+        .map_err(|e| AppError::io(format!("Failed to save settings: {}", e)))?;
+
+    Ok(settings.parallelism)
+}
+
+/// Get the vanilla version preference
+pub fn get_vanilla_version_settings_impl() -> Result<VanillaVersionSettings, AppError> {
+    let settings = settings::load_settings()
+        .map_err(|e| AppError::io(format!("Failed to load settings: {}", e)))?;
+    Ok(settings.vanilla_version)
+}
+
+/// Save the vanilla version preference
+///
+/// Applies the next time vanilla assets are picked automatically; switching
+/// the cached version is still done explicitly.
+///
+/// # Errors
+/// - VALIDATION_ERROR: Pinned without a version, or a cache limit of 0
+/// - IO_ERROR: Failed to read or write settings
+pub fn set_vanilla_version_settings_impl(
+    vanilla_version: VanillaVersionSettings,
+) -> Result<VanillaVersionSettings, AppError> {
+    let pinned_missing = vanilla_version
+        .pinned_version
+        .as_deref()
+        .map_or(true, |v| v.trim().is_empty());
+    if vanilla_version.preference == VersionPreference::Pinned && pinned_missing {
+        return Err(AppError::validation("Choose a version to pin"));
+    }
+    if vanilla_version.cache_limit_mb == Some(0) {
+        return Err(AppError::validation(
+            "The vanilla cache limit must be at least 1 MB",
+        ));
+    }
+
+    let _settings_lock = settings::lock_settings();
+    let mut settings = settings::load_settings()
+        .map_err(|e| AppError::io(format!("Failed to load settings: {}", e)))?;
+    settings.vanilla_version = vanilla_version;
+    settings::save_settings(&settings)
+        .map_err(|e| AppError::io(format!("Failed to save settings: {}", e)))?;
+
+    Ok(settings.vanilla_version)
+}
+
+/// Get how severe conflicts of each asset category are
+pub fn get_conflict_severity_settings_impl() -> Result<ConflictSeveritySettings, AppError> {
+    let settings = settings::load_settings()
+        .map_err(|e| AppError::io(format!("Failed to load settings: {}", e)))?;
+    Ok(settings.conflict_severity)
+}
+
+/// Save how severe conflicts of each asset category are
+///
+/// # Errors
+/// - VALIDATION_ERROR: A category name is empty
+/// - IO_ERROR: Failed to read or write settings
+pub fn set_conflict_severity_settings_impl(
+    conflict_severity: ConflictSeveritySettings,
+) -> Result<ConflictSeveritySettings, AppError> {
+    if conflict_severity
+        .categories
+        .keys()
+        .any(|category| category.trim().is_empty())
+    {
+        return Err(AppError::validation("Category names cannot be empty"));
+    }
+
+    let _settings_lock = settings::lock_settings();
+    let mut settings = settings::load_settings()
+        .map_err(|e| AppError::io(format!("Failed to load settings: {}", e)))?;
+    settings.conflict_severity = conflict_severity;
+    settings::save_settings(&settings)
+        .map_err(|e| AppError::io(format!("Failed to save settings: {}", e)))?;
+
+    Ok(settings.conflict_severity)
+}
+
+/// Show the native folder picker and grant access to the chosen folder
+///
+/// Folder access is only granted through this dialog so the webview can't
+/// widen the path policy on its own.
+///
+/// # Returns
+/// The selected folder, or None if the dialog was cancelled
+pub fn select_folder_impl(
+    app_handle: &tauri::AppHandle,
+    default_path: Option<String>,
+) -> Result<Option<String>, AppError> {
+    use tauri_plugin_dialog::DialogExt;
+
+    let mut dialog = app_handle.dialog().file();
+    if let Some(dir) = default_path {
+        dialog = dialog.set_directory(dir);
+    }
+
+    let selected = match dialog.blocking_pick_folder() {
+        Some(selected) => selected,
+        None => return Ok(None),
+    };
+    let path = selected
+        .into_path()
+        .map_err(|e| AppError::io(format!("Invalid folder selection: {}", e)))?;
+
+    path_policy::grant_path(&path)
+        .map_err(|e| AppError::io(format!("Failed to grant folder access: {}", e)))?;
+
+    info!("Granted access to {}", path.display());
+
+    Ok(Some(path.to_string_lossy().to_string()))
+}
+
+/// List folders the user has granted access to
+pub fn list_path_grants_impl() -> Result<Vec<String>, AppError> {
+    let settings = settings::load_settings()
+        .map_err(|e| AppError::io(format!("Failed to load settings: {}", e)))?;
+    Ok(settings.granted_paths)
+}
+
+/// Revoke access to a previously granted folder
+pub fn revoke_path_grant_impl(path: String) -> Result<Vec<String>, AppError> {
+    path_policy::revoke_path(&path)
+        .map_err(|e| AppError::io(format!("Failed to revoke folder access: {}", e)))?;
+    list_path_grants_impl()
+}
+
+/// Get what builds do when Minecraft is running from the output's instance
+pub fn get_running_game_policy_impl() -> Result<RunningGamePolicy, AppError> {
+    let settings = settings::load_settings()
+        .map_err(|e| AppError::io(format!("Failed to load settings: {}", e)))?;
+    Ok(settings.running_game)
+}
+
+/// Save what builds do when Minecraft is running from the output's instance
+///
+/// # Errors
+/// - IO_ERROR: Failed to read or write settings
+pub fn set_running_game_policy_impl(
+    policy: RunningGamePolicy,
+) -> Result<RunningGamePolicy, AppError> {
+    let _settings_lock = settings::lock_settings();
+    let mut settings = settings::load_settings()
+        .map_err(|e| AppError::io(format!("Failed to load settings: {}", e)))?;
+    settings.running_game = policy;
+    settings::save_settings(&settings)
+        .map_err(|e| AppError::io(format!("Failed to save settings: {}", e)))?;
+
+    Ok(settings.running_game)
+}
+
+/// Get the description template used by builds that don't set their own
+pub fn get_description_template_impl() -> Result<Option<String>, AppError> {
+    let settings = settings::load_settings()
+        .map_err(|e| AppError::io(format!("Failed to load settings: {}", e)))?;
+    Ok(settings.description_template)
+}
+
+/// Save the default description template; None or blank turns it off
+///
+/// # Errors
+/// - VALIDATION_ERROR: The template uses an unknown variable or has an
+///   unclosed brace
+/// - IO_ERROR: Failed to read or write settings
+pub fn set_description_template_impl(template: Option<String>) -> Result<Option<String>, AppError> {
+    let template = template.filter(|template| !template.trim().is_empty());
+    if let Some(template) = &template {
+        pack_description::validate_template(template)
+            .map_err(|e| AppError::validation(e.to_string()))?;
+    }
+
+    let _settings_lock = settings::lock_settings();
+    let mut settings = settings::load_settings()
+        .map_err(|e| AppError::io(format!("Failed to load settings: {}", e)))?;
+    settings.description_template = template;
+    settings::save_settings(&settings)
+        .map_err(|e| AppError::io(format!("Failed to save settings: {}", e)))?;
+
+    Ok(settings.description_template)
+}
+
+/// Render a description template for a preview
+///
+/// # Arguments
+/// * `template` - Template to render
+/// * `context` - Project name and pack names; the date is today
+///
+/// # Errors
+/// - VALIDATION_ERROR: The template uses an unknown variable or has an
+///   unclosed brace
+pub fn render_pack_description_impl(
+    template: String,
+    context: DescriptionContext,
+) -> Result<String, AppError> {
+    let context = DescriptionContext {
+        built_at: std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map_or(0, |d| d.as_secs()),
+        ..context
+    };
+    pack_description::render_description(&template, &context)
+        .map_err(|e| AppError::validation(e.to_string()))
+}
@@ -0,0 +1,537 @@
+/// Commands for inspecting textures
+use crate::model::OverrideSelection;
+use crate::util::animation::{self, AnimationFrames, AnimationInfo};
+use crate::util::animation_export::{self, AnimationExport, ExportFormat};
+use crate::util::color_vision::{self, ColorVisionFilter};
+use crate::util::comparison::{self, ComparisonExport, ComparisonLayout};
+use crate::util::contact_sheet::{self, ContactSheetExport, SheetLayout};
+use crate::util::doc_previews::{self, DocPreviewOptions, DocPreviewSummary};
+use crate::util::entity_map::{self, EntityGroup};
+use crate::util::gui_atlas::{self, GuiAtlas};
+use crate::util::preview_scene::{self, ScenePreview};
+use crate::util::texture_history::{self, TextureHistoryEntry};
+use crate::util::texture_tiles::{self, TexturePyramid, TextureTile};
+use crate::util::tinting::{self, BiomeClimate, Colormap, TintedTexture};
+use crate::util::weaver_nest::WinnerRules;
+use crate::util::{asset_indexer, pack_scanner, vanilla_textures};
+use crate::{validation, AppError};
+use std::collections::HashMap;
+
+/// Get the animation timeline of a texture
+///
+/// # Arguments
+/// * `pack_path` - Base path to the resource pack (from PackMeta.path)
+/// * `asset_id` - Texture asset ID (e.g., "minecraft:block/water_still")
+/// * `is_zip` - Whether the pack is a ZIP file
+///
+/// # Returns
+/// Frame order, per-frame durations and interpolation flag, or None if the
+/// texture isn't animated
+pub fn get_animation_info_impl(
+    pack_path: String,
+    asset_id: String,
+    is_zip: bool,
+) -> Result<Option<AnimationInfo>, AppError> {
+    validation::validate_path_access(&pack_path, "Pack path")?;
+
+    animation::load_animation_info(&pack_path, is_zip, &asset_id)
+        .map_err(|e| AppError::io(format!("Failed to read animation for {}: {}", asset_id, e)))
+}
+
+/// Get every frame of an animated texture with its timing, for previews
+///
+/// # Arguments
+/// * `pack_path` - Base path to the resource pack (from PackMeta.path)
+/// * `asset_id` - Texture asset ID (e.g., "minecraft:block/water_still")
+/// * `is_zip` - Whether the pack is a ZIP file
+///
+/// # Returns
+/// The animation timeline and a base64 PNG per frame, or None if the
+/// texture isn't animated
+pub fn get_animation_frames_impl(
+    pack_path: String,
+    asset_id: String,
+    is_zip: bool,
+) -> Result<Option<AnimationFrames>, AppError> {
+    validation::validate_path_access(&pack_path, "Pack path")?;
+
+    animation::load_animation_frames(&pack_path, is_zip, &asset_id)
+        .map_err(|e| AppError::io(format!("Failed to read frames of {}: {}", asset_id, e)))
+}
+
+/// Get the levels and tile grid of a texture for zoomable previews
+///
+/// # Arguments
+/// * `pack_path` - Base path to the resource pack (from PackMeta.path)
+/// * `asset_id` - Texture asset ID (e.g., "minecraft:environment/end_sky")
+/// * `is_zip` - Whether the pack is a ZIP file
+///
+/// # Returns
+/// Size of each level, full size first; the last level fits in one tile
+pub fn get_texture_pyramid_impl(
+    pack_path: String,
+    asset_id: String,
+    is_zip: bool,
+) -> Result<TexturePyramid, AppError> {
+    validation::validate_path_access(&pack_path, "Pack path")?;
+
+    texture_tiles::texture_pyramid(&pack_path, is_zip, &asset_id)
+        .map_err(|e| AppError::io(format!("Failed to read {}: {}", asset_id, e)))
+}
+
+/// Get one tile of a texture pyramid level
+///
+/// # Arguments
+/// * `pack_path` - Base path to the resource pack (from PackMeta.path)
+/// * `asset_id` - Texture asset ID
+/// * `is_zip` - Whether the pack is a ZIP file
+/// * `level` - Pyramid level (0 = full size)
+/// * `column` - Tile column, from the left
+/// * `row` - Tile row, from the top
+///
+/// # Errors
+/// - VALIDATION_ERROR: The level or tile doesn't exist
+/// - IO_ERROR: The texture can't be read or decoded
+pub fn get_texture_tile_impl(
+    pack_path: String,
+    asset_id: String,
+    is_zip: bool,
+    level: u32,
+    column: u32,
+    row: u32,
+) -> Result<TextureTile, AppError> {
+    validation::validate_path_access(&pack_path, "Pack path")?;
+
+    texture_tiles::texture_tile(&pack_path, is_zip, &asset_id, level, column, row)
+        .map_err(|e| AppError::io(format!("Failed to read {}: {}", asset_id, e)))?
+        .ok_or_else(|| {
+            AppError::validation(format!(
+                "No tile {},{} at level {} of {}",
+                column, row, level, asset_id
+            ))
+        })
+}
+
+/// Export an animated texture as a GIF or animated WebP
+///
+/// # Arguments
+/// * `pack_path` - Base path to the resource pack (from PackMeta.path)
+/// * `asset_id` - Texture asset ID (e.g., "minecraft:block/water_still")
+/// * `is_zip` - Whether the pack is a ZIP file
+/// * `format` - Output format ("gif" or "webp")
+/// * `size` - Output width in pixels (scaled with nearest-neighbor)
+/// * `output_path` - File to write
+///
+/// # Returns
+/// Dimensions, frame count and duration of the written file
+///
+/// # Errors
+/// Returns an error if the texture isn't animated or encoding fails
+pub fn export_animation_impl(
+    pack_path: String,
+    asset_id: String,
+    is_zip: bool,
+    format: ExportFormat,
+    size: u32,
+    output_path: String,
+) -> Result<AnimationExport, AppError> {
+    validation::validate_path_access(&pack_path, "Pack path")?;
+    validation::validate_path_access(&output_path, "Output path")?;
+
+    animation_export::export_animation(
+        &pack_path,
+        is_zip,
+        &asset_id,
+        format,
+        size,
+        std::path::Path::new(&output_path),
+    )
+    .map_err(|e| AppError::build(format!("Failed to export {}: {}", asset_id, e)))
+}
+
+/// Get a texture's image across every version of a pack family
+///
+/// # Arguments
+/// * `packs_dir` - Directory containing resource packs
+/// * `pack_id` - Any pack in the family (e.g., "Faithful 32x - 1.21.zip")
+/// * `asset_id` - Texture asset ID (e.g., "minecraft:block/stone")
+///
+/// # Returns
+/// One entry per pack version, oldest first. Pass an entry's pack ID as an
+/// override to use that version of the texture.
+pub fn get_texture_history_impl(
+    packs_dir: String,
+    pack_id: String,
+    asset_id: String,
+) -> Result<Vec<TextureHistoryEntry>, AppError> {
+    validation::validate_directory(&packs_dir, "Packs directory")?;
+    validation::validate_path_access(&packs_dir, "Packs directory")?;
+
+    let packs = pack_scanner::scan_packs(&packs_dir)
+        .map_err(|e| AppError::scan(format!("Failed to scan packs: {}", e)))?;
+
+    texture_history::get_texture_history(&packs, &pack_id, &asset_id)
+        .map_err(|e| AppError::validation(e.to_string()))
+}
+
+/// Group the packs' entity textures by the entity they belong to
+///
+/// # Arguments
+/// * `packs_dir` - Directory containing resource packs
+///
+/// # Returns
+/// Entities sorted by name, each with its texture asset IDs
+///
+/// # Errors
+/// - VALIDATION_ERROR: Packs directory is invalid
+/// - SCAN_ERROR: Failed to scan or index packs
+pub fn get_entity_groups_impl(packs_dir: String) -> Result<Vec<EntityGroup>, AppError> {
+    validation::validate_directory(&packs_dir, "Packs directory")?;
+    validation::validate_path_access(&packs_dir, "Packs directory")?;
+
+    let packs = pack_scanner::scan_packs(&packs_dir)
+        .map_err(|e| AppError::scan(format!("Failed to scan packs: {}", e)))?;
+    let (_, providers) = asset_indexer::index_assets(&packs)
+        .map_err(|e| AppError::scan(format!("Asset indexing failed: {}", e)))?;
+
+    Ok(entity_map::group_entity_textures(providers.keys()))
+}
+
+/// Get the named regions (buttons, slots, icons) of a GUI texture
+///
+/// # Arguments
+/// * `asset_id` - GUI texture (e.g., "minecraft:gui/widgets")
+/// * `pack_path` - Pack whose copy to scale the regions to; vanilla size if
+///   not given
+/// * `is_zip` - Whether the pack is a ZIP file
+///
+/// # Returns
+/// Regions in the texture's pixels, or None for textures without known
+/// regions
+///
+/// # Errors
+/// - VALIDATION_ERROR: Pack path is not accessible
+/// - IO_ERROR: Failed to read the pack's texture
+pub fn get_gui_regions_impl(
+    asset_id: String,
+    pack_path: Option<String>,
+    is_zip: bool,
+) -> Result<Option<GuiAtlas>, AppError> {
+    match pack_path {
+        Some(pack_path) => {
+            validation::validate_path_access(&pack_path, "Pack path")?;
+            gui_atlas::pack_gui_regions(&pack_path, is_zip, &asset_id)
+                .map_err(|e| AppError::io(format!("Failed to read {}: {}", asset_id, e)))
+        }
+        None => {
+            let size = gui_atlas::GUI_SHEET_SIZE;
+            Ok(gui_atlas::gui_regions(&asset_id, size, size))
+        }
+    }
+}
+
+/// List the GUI textures with known regions
+pub fn list_gui_atlases_impl() -> Result<Vec<String>, AppError> {
+    Ok(gui_atlas::gui_atlas_ids())
+}
+
+/// Tint a grayscale texture (grass, leaves, vines) with its biome color
+///
+/// # Arguments
+/// * `pack_path` - Base path to the resource pack (from PackMeta.path)
+/// * `is_zip` - Whether the pack is a ZIP file
+/// * `asset_id` - Texture asset ID (e.g., "minecraft:block/grass_block_top")
+/// * `biome` - Biome to sample the colormap at; plains if not given
+/// * `colormap` - Grass or foliage; guessed from the texture if not given
+///
+/// # Returns
+/// The tint and the tinted texture as a base64 PNG. The pack's own colormap
+/// is used when it has one, the vanilla one otherwise.
+///
+/// # Errors
+/// - VALIDATION_ERROR: Pack path is not accessible or the biome is unknown
+/// - IO_ERROR: Failed to read the texture or colormap
+pub fn get_tinted_texture_impl(
+    pack_path: String,
+    is_zip: bool,
+    asset_id: String,
+    biome: Option<String>,
+    colormap: Option<Colormap>,
+) -> Result<TintedTexture, AppError> {
+    validation::validate_path_access(&pack_path, "Pack path")?;
+    let biome = biome.unwrap_or_else(|| tinting::DEFAULT_BIOME.to_string());
+    if tinting::biome_climate(&biome).is_none() {
+        return Err(AppError::validation(format!("Unknown biome: {}", biome)));
+    }
+
+    tinting::tint_pack_texture(&pack_path, is_zip, &asset_id, &biome, colormap)
+        .map_err(|e| AppError::io(format!("Failed to tint {}: {}", asset_id, e)))
+}
+
+/// List the biomes textures can be tinted for, with their climates
+pub fn list_biomes_impl() -> Result<Vec<BiomeClimate>, AppError> {
+    Ok(tinting::biome_climates())
+}
+
+/// Render a small scene (terrain, a house, a tree) with the textures a merge
+/// would use
+///
+/// # Arguments
+/// * `packs_dir` - Directory containing resource packs
+/// * `pack_order` - Pack IDs in priority order (first = highest)
+/// * `overrides` - Asset ID -> chosen pack
+/// * `output_path` - PNG to write
+/// * `tile_size` - Width of one block in pixels (defaults to 32)
+/// * `color_filter` - Optional color blindness simulation
+/// * `rules` - Disabled packs and namespace owners, as in a build
+///
+/// # Returns
+/// Image size and the pack each scene texture came from
+pub fn render_preview_scene_impl(
+    packs_dir: String,
+    pack_order: Vec<String>,
+    overrides: HashMap<String, OverrideSelection>,
+    output_path: String,
+    tile_size: Option<u32>,
+    color_filter: Option<ColorVisionFilter>,
+    rules: Option<WinnerRules>,
+) -> Result<ScenePreview, AppError> {
+    validation::validate_directory(&packs_dir, "Packs directory")?;
+    validation::validate_path_access(&packs_dir, "Packs directory")?;
+    validation::validate_path_access(&output_path, "Output path")?;
+
+    let tile_size = tile_size.unwrap_or(preview_scene::DEFAULT_TILE_SIZE);
+    if !(4..=preview_scene::MAX_TILE_SIZE).contains(&tile_size) {
+        return Err(AppError::validation(format!(
+            "Tile size must be between 4 and {} pixels",
+            preview_scene::MAX_TILE_SIZE
+        )));
+    }
+
+    let packs = pack_scanner::scan_packs(&packs_dir)
+        .map_err(|e| AppError::scan(format!("Failed to scan packs: {}", e)))?;
+    let vanilla_dir = vanilla_textures::get_vanilla_cache_dir().ok();
+
+    preview_scene::render_preview_scene(
+        &packs,
+        &pack_order,
+        &overrides,
+        &rules.unwrap_or_default(),
+        vanilla_dir.as_deref(),
+        tile_size,
+        color_filter,
+        std::path::Path::new(&output_path),
+    )
+    .map_err(|e| AppError::build(format!("Failed to render preview scene: {}", e)))
+}
+
+/// Export a labeled grid of one texture category as a PNG
+///
+/// # Arguments
+/// * `packs_dir` - Directory containing resource packs
+/// * `pack_order` - Pack IDs in priority order; a single ID exports that pack
+/// * `overrides` - Asset ID -> chosen pack
+/// * `category` - Texture folder under textures/ (e.g. "block", "item")
+/// * `output_path` - PNG to write
+/// * `tile_size` - Size of each texture in pixels, 8 to 256 (defaults to 64)
+/// * `columns` - Textures per row, up to 64 (defaults to 12)
+///
+/// # Returns
+/// Sheet size and how many textures it holds
+pub fn export_contact_sheet_impl(
+    packs_dir: String,
+    pack_order: Vec<String>,
+    overrides: HashMap<String, OverrideSelection>,
+    category: String,
+    output_path: String,
+    tile_size: Option<u32>,
+    columns: Option<u32>,
+) -> Result<ContactSheetExport, AppError> {
+    validation::validate_directory(&packs_dir, "Packs directory")?;
+    validation::validate_path_access(&packs_dir, "Packs directory")?;
+    validation::validate_path_access(&output_path, "Output path")?;
+
+    if pack_order.is_empty() {
+        return Err(AppError::validation("At least one pack is required"));
+    }
+    if category.is_empty() || category.contains("..") || category.starts_with('/') {
+        return Err(AppError::validation(format!(
+            "Invalid category: {}",
+            category
+        )));
+    }
+    let layout = SheetLayout {
+        tile_size: tile_size.unwrap_or(contact_sheet::DEFAULT_SHEET_TILE_SIZE),
+        columns: columns.unwrap_or(contact_sheet::DEFAULT_SHEET_COLUMNS),
+    };
+    if !(8..=contact_sheet::MAX_SHEET_TILE_SIZE).contains(&layout.tile_size) {
+        return Err(AppError::validation(format!(
+            "Tile size must be between 8 and {} pixels",
+            contact_sheet::MAX_SHEET_TILE_SIZE
+        )));
+    }
+    if !(1..=contact_sheet::MAX_SHEET_COLUMNS).contains(&layout.columns) {
+        return Err(AppError::validation(format!(
+            "Columns must be between 1 and {}",
+            contact_sheet::MAX_SHEET_COLUMNS
+        )));
+    }
+
+    let packs = pack_scanner::scan_packs(&packs_dir)
+        .map_err(|e| AppError::scan(format!("Failed to scan packs: {}", e)))?;
+
+    contact_sheet::export_contact_sheet(
+        &packs,
+        &pack_order,
+        &overrides,
+        &category,
+        layout,
+        std::path::Path::new(&output_path),
+    )
+    .map_err(|e| AppError::build(format!("Failed to export contact sheet: {}", e)))
+}
+
+/// Render documentation images (block renders, comparisons, contact sheets)
+/// for a list of assets into one folder
+///
+/// # Arguments
+/// * `packs_dir` - Directory containing resource packs
+/// * `pack_order` - Pack IDs in priority order (first = highest)
+/// * `overrides` - Asset ID -> chosen pack
+/// * `asset_ids` - Textures to render (e.g. "minecraft:block/stone")
+/// * `output_dir` - Folder to write the images and previews.json into
+/// * `options` - Which kinds of image to write; renders and sheets if None
+/// * `rules` - Disabled packs and namespace owners, as in a build
+///
+/// # Returns
+/// Written images relative to the output folder, plus what failed
+pub fn render_doc_previews_impl(
+    packs_dir: String,
+    pack_order: Vec<String>,
+    overrides: HashMap<String, OverrideSelection>,
+    asset_ids: Vec<String>,
+    output_dir: String,
+    options: Option<DocPreviewOptions>,
+    rules: Option<WinnerRules>,
+) -> Result<DocPreviewSummary, AppError> {
+    validation::validate_directory(&packs_dir, "Packs directory")?;
+    validation::validate_path_access(&packs_dir, "Packs directory")?;
+    validation::validate_path_access(&output_dir, "Output directory")?;
+
+    if asset_ids.is_empty() {
+        return Err(AppError::validation("No textures selected"));
+    }
+    let options = options.unwrap_or_default();
+    if !(4..=doc_previews::MAX_PREVIEW_SIZE).contains(&options.size) {
+        return Err(AppError::validation(format!(
+            "Size must be between 4 and {} pixels",
+            doc_previews::MAX_PREVIEW_SIZE
+        )));
+    }
+
+    let packs = pack_scanner::scan_packs(&packs_dir)
+        .map_err(|e| AppError::scan(format!("Failed to scan packs: {}", e)))?;
+    let vanilla_dir = vanilla_textures::get_vanilla_cache_dir().ok();
+
+    doc_previews::render_doc_previews(
+        &packs,
+        &pack_order,
+        &overrides,
+        &rules.unwrap_or_default(),
+        vanilla_dir.as_deref(),
+        &asset_ids,
+        &options,
+        std::path::Path::new(&output_dir),
+    )
+    .map_err(|e| AppError::build(format!("Failed to render previews: {}", e)))
+}
+
+/// Generate vanilla vs merged comparison images for selected textures
+///
+/// # Arguments
+/// * `packs_dir` - Directory containing resource packs
+/// * `pack_order` - Pack IDs in priority order (first = highest)
+/// * `overrides` - Asset ID -> chosen pack
+/// * `asset_ids` - Textures to compare (e.g. "minecraft:block/stone")
+/// * `layout` - Side by side, split, or a before/after pair for a slider
+/// * `size` - Output width of each texture in pixels
+/// * `output_dir` - Folder to write the images into
+/// * `rules` - Disabled packs and namespace owners, as in a build
+///
+/// # Returns
+/// Written images per asset, plus the assets that couldn't be compared
+#[allow(clippy::too_many_arguments)]
+pub fn export_comparison_images_impl(
+    packs_dir: String,
+    pack_order: Vec<String>,
+    overrides: HashMap<String, OverrideSelection>,
+    asset_ids: Vec<String>,
+    layout: ComparisonLayout,
+    size: u32,
+    output_dir: String,
+    rules: Option<WinnerRules>,
+) -> Result<ComparisonExport, AppError> {
+    validation::validate_directory(&packs_dir, "Packs directory")?;
+    validation::validate_path_access(&packs_dir, "Packs directory")?;
+    validation::validate_path_access(&output_dir, "Output directory")?;
+
+    if asset_ids.is_empty() {
+        return Err(AppError::validation("No textures selected"));
+    }
+    if size == 0 || size > comparison::MAX_COMPARISON_SIZE {
+        return Err(AppError::validation(format!(
+            "Size must be between 1 and {} pixels",
+            comparison::MAX_COMPARISON_SIZE
+        )));
+    }
+
+    let packs = pack_scanner::scan_packs(&packs_dir)
+        .map_err(|e| AppError::scan(format!("Failed to scan packs: {}", e)))?;
+    let vanilla_dir = vanilla_textures::get_vanilla_cache_dir()
+        .map_err(|e| AppError::io(format!("Vanilla textures are not available: {}", e)))?;
+
+    comparison::export_comparisons(
+        &asset_ids,
+        &packs,
+        &pack_order,
+        &overrides,
+        &rules.unwrap_or_default(),
+        &vanilla_dir,
+        layout,
+        size,
+        std::path::Path::new(&output_dir),
+    )
+    .map_err(|e| AppError::build(format!("Failed to export comparisons: {}", e)))
+}
+
+/// Write a copy of a preview image as seen with color blindness
+///
+/// Works on any PNG the app produces (textures, contact sheets, comparisons).
+///
+/// # Arguments
+/// * `input_path` - Image to filter
+/// * `filter` - Protanopia, deuteranopia or tritanopia
+/// * `output_path` - PNG to write (may equal `input_path`)
+///
+/// # Returns
+/// Path of the written image
+pub fn simulate_color_vision_impl(
+    input_path: String,
+    filter: ColorVisionFilter,
+    output_path: String,
+) -> Result<String, AppError> {
+    validation::validate_path_access(&input_path, "Input image")?;
+    validation::validate_path_access(&output_path, "Output path")?;
+
+    let input = std::path::Path::new(&input_path);
+    if !input.is_file() {
+        return Err(AppError::validation(format!(
+            "Image not found: {}",
+            input_path
+        )));
+    }
+
+    color_vision::simulate_color_vision_file(input, std::path::Path::new(&output_path), filter)
+        .map_err(|e| AppError::io(format!("Failed to filter image: {}", e)))?;
+    Ok(output_path)
+}
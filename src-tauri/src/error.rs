@@ -4,61 +4,202 @@
  * This implements the modern Tauri v2 pattern of custom error types
  * that automatically serialize to JSON for frontend consumption.
  */
-
+use crate::util::instance_lock::InstanceBusyError;
+use crate::util::jobs::{self, CancelledError};
 use serde::{Deserialize, Serialize};
 use std::fmt;
 
+/// How serious a validation issue is
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum Severity {
+    /// Blocks the operation
+    Error,
+    /// Worth fixing, but the operation can go ahead
+    Warning,
+}
+
+/// One problem found while validating a request
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ValidationIssue {
+    pub severity: Severity,
+    /// Error code the issue would have on its own (e.g., "IO_ERROR")
+    pub code: String,
+    pub message: String,
+}
+
+impl ValidationIssue {
+    /// Blocking issue from the error a single check returned
+    pub fn error(err: AppError) -> Self {
+        Self {
+            severity: Severity::Error,
+            code: err.code,
+            message: err.message,
+        }
+    }
+
+    /// Non-blocking issue
+    pub fn warning(message: impl Into<String>) -> Self {
+        Self {
+            severity: Severity::Warning,
+            code: "VALIDATION_ERROR".to_string(),
+            message: message.into(),
+        }
+    }
+}
+
+/// Remediation the frontend can offer as a one-click action
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ErrorHint {
+    /// The path is outside the folders the app may access
+    GrantFolderAccess,
+    /// The disk ran out of space
+    FreeDiskSpace,
+    /// Another process or task holds the file; trying again later works
+    WaitAndRetry,
+    /// The folder can't be written to
+    ChooseAnotherFolder,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AppError {
     pub code: String,
     pub message: String,
     pub details: Option<String>,
+    /// Every problem found, when validation collected more than one check
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub issues: Vec<ValidationIssue>,
+    /// Trying the same operation again may succeed
+    #[serde(default)]
+    pub retryable: bool,
+    /// What would fix it
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub hint: Option<ErrorHint>,
+    /// File or folder the error is about (boxed to keep every command's
+    /// error type small)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub path: Option<Box<str>>,
 }
 
 impl AppError {
-    /// Create a validation error
-    pub fn validation(message: impl Into<String>) -> Self {
+    fn new(code: &str, message: impl Into<String>) -> Self {
         Self {
-            code: "VALIDATION_ERROR".to_string(),
+            code: code.to_string(),
             message: message.into(),
             details: None,
+            issues: Vec::new(),
+            retryable: false,
+            hint: None,
+            path: None,
         }
     }
 
+    /// Create a validation error
+    pub fn validation(message: impl Into<String>) -> Self {
+        Self::new("VALIDATION_ERROR", message)
+    }
+
     /// Create a filesystem error
     pub fn io(message: impl Into<String>) -> Self {
-        Self {
-            code: "IO_ERROR".to_string(),
-            message: message.into(),
-            details: None,
-        }
+        Self::new("IO_ERROR", message)
     }
 
     /// Create a pack scanning error
     pub fn scan(message: impl Into<String>) -> Self {
-        Self {
-            code: "SCAN_ERROR".to_string(),
-            message: message.into(),
-            details: None,
-        }
+        Self::new("SCAN_ERROR", message)
     }
 
     /// Create a pack building error
     pub fn build(message: impl Into<String>) -> Self {
-        Self {
-            code: "BUILD_ERROR".to_string(),
-            message: message.into(),
-            details: None,
-        }
+        Self::new("BUILD_ERROR", message)
+    }
+
+    /// Create an error for a file another process kept locked
+    pub fn file_locked(path: impl Into<String>) -> Self {
+        let path = path.into();
+        Self::new(
+            "FILE_LOCKED",
+            format!("File is locked by another process: {}", path),
+        )
+        .with_details("Antivirus or sync software may be scanning it. Wait a moment and try again.")
+        .retryable()
+        .with_hint(ErrorHint::WaitAndRetry)
+        .with_path(path)
+    }
+
+    /// Create an error for a cache or output another task is writing
+    pub fn instance_busy(busy: &InstanceBusyError) -> Self {
+        Self::new("INSTANCE_BUSY", busy.to_string())
+            .with_details(format!(
+                "Wait for it to finish and try again. If no other Weaverbird window or CLI is running, clear the lock ({}).",
+                busy.lock_path.display()
+            ))
+            .retryable()
+            .with_hint(ErrorHint::WaitAndRetry)
+            .with_path(busy.lock_path.to_string_lossy())
+    }
+
+    /// Create an error for a job the user cancelled
+    pub fn cancelled(cancelled: &CancelledError) -> Self {
+        Self::new("CANCELLED", cancelled.to_string())
     }
 
     /// Create an internal error
     pub fn internal(message: impl Into<String>, details: impl Into<String>) -> Self {
-        Self {
-            code: "INTERNAL_ERROR".to_string(),
-            message: message.into(),
-            details: Some(details.into()),
-        }
+        Self::new("INTERNAL_ERROR", message).with_details(details)
+    }
+
+    /// Combine validation issues into one error
+    ///
+    /// # Returns
+    /// None if no issue is an error. Otherwise a single error keeps its own
+    /// code and message, several are summarized; either way all issues,
+    /// warnings included, are attached.
+    pub fn from_issues(issues: Vec<ValidationIssue>) -> Option<Self> {
+        let errors: Vec<&ValidationIssue> = issues
+            .iter()
+            .filter(|issue| issue.severity == Severity::Error)
+            .collect();
+        let first = errors.first()?;
+
+        let code = if errors.iter().all(|e| e.code == first.code) {
+            first.code.clone()
+        } else {
+            "VALIDATION_ERROR".to_string()
+        };
+        let message = if errors.len() == 1 {
+            first.message.clone()
+        } else {
+            let messages: Vec<&str> = errors.iter().map(|e| e.message.as_str()).collect();
+            format!(
+                "{} problems need fixing: {}",
+                errors.len(),
+                messages.join("; ")
+            )
+        };
+        let mut err = Self::new(&code, message);
+        err.issues = issues;
+        Some(err)
+    }
+
+    /// Mark the error as worth retrying
+    pub fn retryable(mut self) -> Self {
+        self.retryable = true;
+        self
+    }
+
+    /// Suggest a remediation
+    pub fn with_hint(mut self, hint: ErrorHint) -> Self {
+        self.hint = Some(hint);
+        self
+    }
+
+    /// Name the file or folder the error is about
+    pub fn with_path(mut self, path: impl Into<String>) -> Self {
+        self.path = Some(path.into().into_boxed_str());
+        self
     }
 
     /// Attach more context to the error
@@ -76,13 +217,42 @@ impl fmt::Display for AppError {
 
 impl From<anyhow::Error> for AppError {
     fn from(err: anyhow::Error) -> Self {
-        AppError::internal("Operation failed", err.to_string())
+        if let Some(cancelled) = jobs::cancelled_job(&err) {
+            return AppError::cancelled(cancelled);
+        }
+        let disk_full = caused_by_disk_full(&err);
+        let app_err = AppError::internal("Operation failed", err.to_string());
+        if disk_full {
+            app_err.with_hint(ErrorHint::FreeDiskSpace)
+        } else {
+            app_err
+        }
     }
 }
 
+/// Whether an I/O error means the disk is full (ENOSPC, ERROR_DISK_FULL,
+/// ERROR_HANDLE_DISK_FULL)
+pub fn is_disk_full(err: &std::io::Error) -> bool {
+    let codes: &[i32] = if cfg!(windows) { &[39, 112] } else { &[28] };
+    err.raw_os_error()
+        .map_or(false, |code| codes.contains(&code))
+}
+
+/// Whether any cause of an error is a full disk
+pub fn caused_by_disk_full(err: &anyhow::Error) -> bool {
+    err.chain()
+        .filter_map(|cause| cause.downcast_ref::<std::io::Error>())
+        .any(is_disk_full)
+}
+
 impl From<std::io::Error> for AppError {
     fn from(err: std::io::Error) -> Self {
-        AppError::io(err.to_string())
+        let app_err = AppError::io(err.to_string());
+        if is_disk_full(&err) {
+            app_err.with_hint(ErrorHint::FreeDiskSpace)
+        } else {
+            app_err
+        }
     }
 }
 
@@ -131,6 +301,39 @@ mod tests {
         assert_eq!(err.details, None);
     }
 
+    #[test]
+    fn test_file_locked_error() {
+        let err = AppError::file_locked("C:/packs/out/pack.mcmeta");
+        assert_eq!(err.code, "FILE_LOCKED");
+        assert!(err.message.contains("C:/packs/out/pack.mcmeta"));
+        assert!(err.details.is_some());
+        assert!(err.retryable);
+        assert_eq!(err.hint, Some(ErrorHint::WaitAndRetry));
+        assert_eq!(err.path.as_deref(), Some("C:/packs/out/pack.mcmeta"));
+    }
+
+    #[test]
+    fn test_instance_busy_error() {
+        let busy = InstanceBusyError {
+            lock_path: "/packs/.Weaver Nest.weaverbird-lock".into(),
+            owner: crate::util::instance_lock::LockOwner {
+                pid: 4242,
+                host: None,
+                task: "building into /packs/Weaver Nest".to_string(),
+                started_at: 0,
+            },
+            same_process: false,
+        };
+        let err = AppError::instance_busy(&busy);
+        assert_eq!(err.code, "INSTANCE_BUSY");
+        assert_eq!(
+            err.message,
+            "Another Weaverbird instance is building into /packs/Weaver Nest"
+        );
+        let details = err.details.unwrap();
+        assert!(details.contains(".Weaver Nest.weaverbird-lock"));
+    }
+
     #[test]
     fn test_internal_error() {
         let err = AppError::internal("operation failed", "detailed info");
@@ -147,6 +350,29 @@ mod tests {
         assert_eq!(err.details, Some("additional context".to_string()));
     }
 
+    #[test]
+    fn test_from_issues() {
+        let warning = ValidationIssue::warning("Pack a is listed twice");
+        let missing = ValidationIssue::error(AppError::io("Packs directory does not exist"));
+        let empty = ValidationIssue::error(AppError::validation("Pack order cannot be empty"));
+
+        let none = AppError::from_issues(vec![warning.clone()]);
+        let single = AppError::from_issues(vec![warning.clone(), missing.clone()]).unwrap();
+        let both = AppError::from_issues(vec![missing, warning, empty]).unwrap();
+
+        assert!(none.is_none());
+        assert_eq!(single.code, "IO_ERROR");
+        assert_eq!(single.message, "Packs directory does not exist");
+        assert_eq!(single.issues.len(), 2);
+        assert_eq!(both.code, "VALIDATION_ERROR");
+        assert_eq!(
+            both.message,
+            "2 problems need fixing: Packs directory does not exist; Pack order cannot be empty"
+        );
+        assert_eq!(both.issues.len(), 3);
+        assert_eq!(both.issues[1].severity, Severity::Warning);
+    }
+
     #[test]
     fn test_display() {
         let err = AppError::validation("test message");
@@ -159,6 +385,20 @@ mod tests {
         let app_err: AppError = io_err.into();
         assert_eq!(app_err.code, "IO_ERROR");
         assert!(app_err.message.contains("file not found"));
+        assert!(!app_err.retryable);
+        assert_eq!(app_err.hint, None);
+    }
+
+    #[test]
+    fn test_disk_full_hint() {
+        let code = if cfg!(windows) { 112 } else { 28 };
+        let io_err: AppError = std::io::Error::from_raw_os_error(code).into();
+        let wrapped: AppError = anyhow::Error::from(std::io::Error::from_raw_os_error(code))
+            .context("Failed to write pack.png")
+            .into();
+        assert_eq!(io_err.hint, Some(ErrorHint::FreeDiskSpace));
+        assert_eq!(wrapped.code, "INTERNAL_ERROR");
+        assert_eq!(wrapped.hint, Some(ErrorHint::FreeDiskSpace));
     }
 
     #[test]
@@ -197,6 +437,18 @@ mod tests {
         assert_eq!(err.details, Some("test details".to_string()));
     }
 
+    #[test]
+    fn test_cancelled_error() {
+        let err: AppError = anyhow::Error::new(CancelledError {
+            job_id: "job-7".to_string(),
+        })
+        .context("Extraction failed")
+        .into();
+        assert_eq!(err.code, "CANCELLED");
+        assert_eq!(err.message, "Job job-7 was cancelled");
+        assert!(!err.retryable);
+    }
+
     #[test]
     fn test_error_clone() {
         let err1 = AppError::scan("test error");
@@ -1,7 +1,7 @@
+pub mod commands;
 pub mod error;
 pub mod model;
 pub mod util;
-pub mod commands;
 pub mod validation;
 
-pub use error::{AppError, AppResult};
+pub use error::{AppError, AppResult, ErrorHint, Severity, ValidationIssue};
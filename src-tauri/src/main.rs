@@ -4,40 +4,160 @@
 )]
 
 use weaverbird_lib::commands::{
-    build_weaver_nest_impl, check_minecraft_installed_impl, detect_launchers_impl,
-    get_block_state_schema_impl, get_cached_vanilla_version_impl, get_colormap_path_impl,
-    get_default_packs_dir_impl, get_entity_version_variants_impl,
-    get_launcher_resourcepacks_dir_impl, get_pack_texture_path_impl,
-    get_suggested_minecraft_paths_impl, get_vanilla_mcmeta_path_impl,
-    get_vanilla_texture_path_impl, identify_launcher_impl,
+    apply_conflict_preset_impl, apply_queued_installs_impl, batch_build_projects_impl,
+    browse_pack_impl, build_merged_pack_impl, build_weaver_nest_impl, cancel_job_impl,
+    check_instance_stack_impl, check_minecraft_installed_impl, check_pack_removal_impl,
+    clear_stale_lock_impl, clear_vanilla_cache_impl, compress_pack_folder_impl,
+    compute_output_sha1_impl, create_job_impl, delete_conflict_preset_impl, delete_project_impl,
+    detect_launchers_impl, detect_namespace_collisions_impl, diff_pack_models_impl,
+    discard_interrupted_build_impl, discard_queued_install_impl, download_cloud_placeholders_impl,
+    download_remote_pack_impl, export_animation_impl, export_comparison_images_impl,
+    export_conflict_preset_impl, export_contact_sheet_impl, export_custom_model_data_report_impl,
+    export_diagnostics_impl, export_pack_report_impl, extract_pack_archive_impl,
+    extract_pack_file_impl, get_activity_log_impl, get_animation_frames_impl,
+    get_animation_info_impl, get_applied_pack_status_impl, get_block_state_schema_impl,
+    get_cached_vanilla_version_impl, get_colormap_path_impl, get_conflict_report_impl,
+    get_conflict_severity_settings_impl, get_ctm_report_impl, get_custom_model_data_report_impl,
+    get_default_packs_dir_impl, get_description_template_impl, get_diagnostics_impl,
+    get_entity_groups_impl, get_entity_version_variants_impl, get_face_textures_impl,
+    get_feature_requirements_impl, get_gui_regions_impl, get_item_model_conflicts_impl,
+    get_launcher_resourcepacks_dir_impl, get_namespace_coverage_impl, get_next_conflict_impl,
+    get_override_dependencies_impl, get_pack_health_report_impl, get_pack_palettes_impl,
+    get_pack_texture_path_impl, get_parallelism_settings_impl, get_post_build_hooks_impl,
+    get_recent_logs_impl, get_remote_server_status_impl, get_running_game_policy_impl,
+    get_running_games_impl, get_suggested_minecraft_paths_impl, get_texture_history_impl,
+    get_texture_pyramid_impl, get_texture_tile_impl, get_texture_usage_impl,
+    get_tinted_texture_impl, get_vanilla_mcmeta_path_impl, get_vanilla_texture_path_impl,
+    get_vanilla_version_settings_impl, identify_launcher_impl, import_conflict_preset_impl,
     initialize_vanilla_textures_from_custom_dir_impl, initialize_vanilla_textures_impl,
-    list_available_minecraft_versions_impl, load_model_json_impl, read_block_model_impl,
-    read_pack_file_impl, read_vanilla_jem_impl, resolve_block_state_impl, scan_packs_folder_impl,
-    set_vanilla_texture_version_impl, BuildWeaverNestRequest,
+    inspect_packs_folder_impl, list_available_minecraft_versions_impl, list_biomes_impl,
+    list_conflict_presets_impl, list_gui_atlases_impl, list_interrupted_builds_impl,
+    list_launcher_instances_impl, list_modrinth_profiles_impl, list_path_grants_impl,
+    list_projects_impl, list_quarantined_packs_impl, list_queued_installs_impl,
+    list_vanilla_ids_impl, load_item_model_impl, load_model_json_impl, migrate_pack_impl,
+    purge_quarantined_pack_impl, quarantine_pack_impl, read_block_model_impl, read_pack_file_impl,
+    read_vanilla_jem_impl, record_activity_impl, record_conflict_resolution_impl,
+    regenerate_remote_server_token_impl, render_doc_previews_impl, render_pack_description_impl,
+    render_preview_scene_impl, repack_pack_folder_impl, reset_conflict_resolution_impl,
+    resolve_block_state_impl, restore_last_session_impl, restore_quarantined_pack_impl,
+    resume_interrupted_build_impl, reveal_in_file_manager_impl, revoke_path_grant_impl,
+    save_conflict_preset_impl, save_project_impl, scan_packs_folder_impl, search_lang_entries_impl,
+    seed_overrides_from_project_impl, select_folder_impl, set_conflict_severity_settings_impl,
+    set_description_template_impl, set_parallelism_settings_impl, set_post_build_hooks_impl,
+    set_running_game_policy_impl, set_vanilla_texture_version_impl,
+    set_vanilla_version_settings_impl, simulate_color_vision_impl, simulate_stacks_impl,
+    start_remote_server_if_enabled, start_remote_server_impl, stop_remote_server_impl,
+    suggest_pack_order_impl, update_session_stack_impl, verify_caches_impl, BuildWeaverNestRequest,
 };
+use weaverbird_lib::util::activity_log::ActivityEntry;
+use weaverbird_lib::util::conflict_presets::ConflictPreset;
+use weaverbird_lib::util::conflicts::ConflictSeveritySettings;
+use weaverbird_lib::util::parallelism::{ParallelismSettings, ParallelismStatus};
+use weaverbird_lib::util::post_build::PostBuildHook;
+use weaverbird_lib::util::projects::{BatchBuildSummary, Project};
+use weaverbird_lib::util::vanilla_textures::VanillaVersionSettings;
+use weaverbird_lib::util::RemoteServerStatus;
 
 /// Tauri command wrapper for scanning resource packs (async for non-blocking UI)
 #[tauri::command]
 async fn scan_packs_folder(
+    window: tauri::Window,
     packs_dir: String,
+    force_reindex: Option<bool>,
+    job_id: Option<String>,
 ) -> Result<weaverbird_lib::model::ScanResult, weaverbird_lib::AppError> {
     // Use spawn_blocking for CPU/IO-heavy work with rayon parallelism
-    tokio::task::spawn_blocking(move || scan_packs_folder_impl(packs_dir))
+    tokio::task::spawn_blocking(move || {
+        scan_packs_folder_impl(
+            packs_dir,
+            force_reindex.unwrap_or(false),
+            Some(window),
+            job_id,
+        )
+    })
+    .await
+    .map_err(|e| weaverbird_lib::AppError::internal("Task join error", format!("{}", e)))?
+}
+
+/// Tauri command wrapper for restoring the last session from cache (async for non-blocking UI)
+#[tauri::command]
+async fn restore_last_session(
+    window: tauri::Window,
+) -> Result<Option<weaverbird_lib::util::RestoredSession>, weaverbird_lib::AppError> {
+    tokio::task::spawn_blocking(move || restore_last_session_impl(Some(window)))
         .await
         .map_err(|e| weaverbird_lib::AppError::internal("Task join error", format!("{}", e)))?
 }
 
+/// Tauri command wrapper for recording the session's pack order and overrides
+#[tauri::command]
+async fn update_session_stack(
+    packs_dir: String,
+    pack_order: Vec<String>,
+    overrides: Option<std::collections::HashMap<String, weaverbird_lib::model::OverrideSelection>>,
+) -> Result<bool, weaverbird_lib::AppError> {
+    tokio::task::spawn_blocking(move || {
+        update_session_stack_impl(packs_dir, pack_order, overrides.unwrap_or_default())
+    })
+    .await
+    .map_err(|e| weaverbird_lib::AppError::internal("Task join error", format!("{}", e)))?
+}
+
 /// Tauri command wrapper for building Weaver Nest (async for non-blocking UI)
 #[tauri::command]
 async fn build_weaver_nest(
+    window: tauri::Window,
     request: BuildWeaverNestRequest,
+    job_id: Option<String>,
 ) -> Result<String, weaverbird_lib::AppError> {
     // Use spawn_blocking for CPU/IO-heavy work with rayon parallelism
-    tokio::task::spawn_blocking(move || build_weaver_nest_impl(request))
+    tokio::task::spawn_blocking(move || build_weaver_nest_impl(window, request, job_id))
+        .await
+        .map_err(|e| weaverbird_lib::AppError::internal("Task join error", format!("{}", e)))?
+}
+
+/// Tauri command wrapper for creating a cancellable job ID
+#[tauri::command]
+fn create_job() -> Result<String, weaverbird_lib::AppError> {
+    create_job_impl()
+}
+
+/// Tauri command wrapper for cancelling a running job
+#[tauri::command]
+fn cancel_job(job_id: String) -> Result<bool, weaverbird_lib::AppError> {
+    cancel_job_impl(job_id)
+}
+
+/// Tauri command wrapper for starting the remote control server
+#[tauri::command]
+async fn start_remote_server(
+    port: Option<u16>,
+    allow_lan: Option<bool>,
+    run_build_hooks: Option<bool>,
+) -> Result<RemoteServerStatus, weaverbird_lib::AppError> {
+    tokio::task::spawn_blocking(move || start_remote_server_impl(port, allow_lan, run_build_hooks))
         .await
         .map_err(|e| weaverbird_lib::AppError::internal("Task join error", format!("{}", e)))?
 }
 
+/// Tauri command wrapper for stopping the remote control server
+#[tauri::command]
+fn stop_remote_server() -> Result<RemoteServerStatus, weaverbird_lib::AppError> {
+    stop_remote_server_impl()
+}
+
+/// Tauri command wrapper for getting the remote control server status
+#[tauri::command]
+fn get_remote_server_status() -> Result<RemoteServerStatus, weaverbird_lib::AppError> {
+    get_remote_server_status_impl()
+}
+
+/// Tauri command wrapper for replacing the remote control token
+#[tauri::command]
+fn regenerate_remote_server_token() -> Result<RemoteServerStatus, weaverbird_lib::AppError> {
+    regenerate_remote_server_token_impl()
+}
+
 /// Tauri command wrapper for getting default packs directory
 #[tauri::command]
 fn get_default_packs_dir() -> Result<String, weaverbird_lib::AppError> {
@@ -48,9 +168,10 @@ fn get_default_packs_dir() -> Result<String, weaverbird_lib::AppError> {
 #[tauri::command]
 async fn initialize_vanilla_textures(
     window: tauri::Window,
+    job_id: Option<String>,
 ) -> Result<String, weaverbird_lib::AppError> {
     // Use spawn_blocking for CPU/IO-heavy vanilla texture extraction
-    tokio::task::spawn_blocking(move || initialize_vanilla_textures_impl(window))
+    tokio::task::spawn_blocking(move || initialize_vanilla_textures_impl(Some(window), job_id))
         .await
         .map_err(|e| weaverbird_lib::AppError::internal("Task join error", format!("{}", e)))?
 }
@@ -117,11 +238,14 @@ fn get_cached_vanilla_version() -> Result<Option<String>, weaverbird_lib::AppErr
 async fn set_vanilla_texture_version(
     version: String,
     window: tauri::Window,
+    job_id: Option<String>,
 ) -> Result<String, weaverbird_lib::AppError> {
     // Use spawn_blocking for CPU/IO-heavy vanilla texture extraction
-    tokio::task::spawn_blocking(move || set_vanilla_texture_version_impl(version, window))
-        .await
-        .map_err(|e| weaverbird_lib::AppError::internal("Task join error", format!("{}", e)))?
+    tokio::task::spawn_blocking(move || {
+        set_vanilla_texture_version_impl(version, Some(window), job_id)
+    })
+    .await
+    .map_err(|e| weaverbird_lib::AppError::internal("Task join error", format!("{}", e)))?
 }
 
 /// Tauri command wrapper for detecting all launchers
@@ -165,8 +289,9 @@ fn read_block_model(
     pack_id: String,
     model_id: String,
     packs_dir: String,
+    display_context: Option<weaverbird_lib::util::block_models::DisplayContext>,
 ) -> Result<weaverbird_lib::util::block_models::BlockModel, weaverbird_lib::AppError> {
-    read_block_model_impl(pack_id, model_id, packs_dir)
+    read_block_model_impl(pack_id, model_id, packs_dir, display_context)
 }
 
 /// Tauri command wrapper for reading any file from a pack (directory or ZIP)
@@ -180,6 +305,121 @@ fn read_pack_file(
     read_pack_file_impl(pack_path, file_path, is_zip)
 }
 
+/// Tauri command wrapper for browsing a pack's file tree (async for non-blocking UI)
+#[tauri::command]
+async fn browse_pack(
+    pack_path: String,
+    is_zip: bool,
+    dir: Option<String>,
+    depth: Option<usize>,
+) -> Result<weaverbird_lib::util::pack_browser::PackTreeEntry, weaverbird_lib::AppError> {
+    tokio::task::spawn_blocking(move || browse_pack_impl(pack_path, is_zip, dir, depth))
+        .await
+        .map_err(|e| weaverbird_lib::AppError::internal("Task join error", format!("{}", e)))?
+}
+
+/// Tauri command wrapper for showing a path in the system file manager
+#[tauri::command]
+fn reveal_in_file_manager(path: String) -> Result<(), weaverbird_lib::AppError> {
+    reveal_in_file_manager_impl(path)
+}
+
+/// Tauri command wrapper for extracting a single file from a pack (async for non-blocking UI)
+#[tauri::command]
+async fn extract_pack_file(
+    pack_path: String,
+    is_zip: bool,
+    file_path: String,
+    destination: String,
+) -> Result<String, weaverbird_lib::AppError> {
+    tokio::task::spawn_blocking(move || {
+        extract_pack_file_impl(pack_path, is_zip, file_path, destination)
+    })
+    .await
+    .map_err(|e| weaverbird_lib::AppError::internal("Task join error", format!("{}", e)))?
+}
+
+/// Tauri command wrapper for extracting a whole zip pack into a folder (async for non-blocking UI)
+#[tauri::command]
+async fn extract_pack_archive(
+    window: tauri::Window,
+    pack_path: String,
+    destination: String,
+) -> Result<String, weaverbird_lib::AppError> {
+    tokio::task::spawn_blocking(move || {
+        extract_pack_archive_impl(Some(window), pack_path, destination)
+    })
+    .await
+    .map_err(|e| weaverbird_lib::AppError::internal("Task join error", format!("{}", e)))?
+}
+
+/// Tauri command wrapper for compressing a folder pack into a zip (async for non-blocking UI)
+#[tauri::command]
+async fn compress_pack_folder(
+    window: tauri::Window,
+    folder: String,
+    destination: String,
+) -> Result<String, weaverbird_lib::AppError> {
+    tokio::task::spawn_blocking(move || {
+        compress_pack_folder_impl(Some(window), folder, destination)
+    })
+    .await
+    .map_err(|e| weaverbird_lib::AppError::internal("Task join error", format!("{}", e)))?
+}
+
+/// Tauri command wrapper for zipping a folder pack as a clean distributable (async for non-blocking UI)
+#[tauri::command]
+async fn repack_pack_folder(
+    window: tauri::Window,
+    folder: String,
+    destination: String,
+) -> Result<weaverbird_lib::util::pack_archive::RepackReport, weaverbird_lib::AppError> {
+    tokio::task::spawn_blocking(move || repack_pack_folder_impl(Some(window), folder, destination))
+        .await
+        .map_err(|e| weaverbird_lib::AppError::internal("Task join error", format!("{}", e)))?
+}
+
+/// Tauri command wrapper for merging packs into a folder or zip (async for non-blocking UI)
+#[tauri::command]
+async fn build_merged_pack(
+    window: tauri::Window,
+    packs_dir: String,
+    pack_order: Vec<String>,
+    overrides: std::collections::HashMap<String, weaverbird_lib::model::OverrideSelection>,
+    output_path: String,
+    format: weaverbird_lib::util::pack_builder::OutputFormat,
+    server: Option<weaverbird_lib::util::server_export::ServerExportOptions>,
+) -> Result<weaverbird_lib::util::pack_builder::MergeSummary, weaverbird_lib::AppError> {
+    tokio::task::spawn_blocking(move || {
+        build_merged_pack_impl(
+            Some(window),
+            packs_dir,
+            pack_order,
+            overrides,
+            output_path,
+            format,
+            server,
+        )
+    })
+    .await
+    .map_err(|e| weaverbird_lib::AppError::internal("Task join error", format!("{}", e)))?
+}
+
+/// Tauri command wrapper for upgrading a pack to a newer pack format (async for non-blocking UI)
+#[tauri::command]
+async fn migrate_pack(
+    packs_dir: String,
+    pack_id: String,
+    target_format: Option<u32>,
+    output_path: String,
+) -> Result<weaverbird_lib::util::pack_migrator::MigrationReport, weaverbird_lib::AppError> {
+    tokio::task::spawn_blocking(move || {
+        migrate_pack_impl(packs_dir, pack_id, target_format, output_path)
+    })
+    .await
+    .map_err(|e| weaverbird_lib::AppError::internal("Task join error", format!("{}", e)))?
+}
+
 /// Tauri command wrapper for reading vanilla JEM files from __mocks__/cem/
 #[tauri::command]
 fn read_vanilla_jem(entity_type: String) -> Result<String, weaverbird_lib::AppError> {
@@ -192,8 +432,79 @@ fn load_model_json(
     pack_id: String,
     model_id: String,
     packs_dir: String,
+    display_context: Option<weaverbird_lib::util::block_models::DisplayContext>,
+    variant: Option<weaverbird_lib::util::blockstates::ResolvedModel>,
 ) -> Result<weaverbird_lib::util::block_models::BlockModel, weaverbird_lib::AppError> {
-    load_model_json_impl(pack_id, model_id, packs_dir)
+    load_model_json_impl(pack_id, model_id, packs_dir, display_context, variant)
+}
+
+/// Tauri command wrapper for resolving an item's model for previews
+#[tauri::command]
+fn load_item_model(
+    pack_id: String,
+    item_id: String,
+    packs_dir: String,
+    predicates: Option<std::collections::HashMap<String, f64>>,
+    display_context: Option<weaverbird_lib::util::block_models::DisplayContext>,
+) -> Result<weaverbird_lib::util::item_models::ResolvedItemModel, weaverbird_lib::AppError> {
+    load_item_model_impl(pack_id, item_id, packs_dir, predicates, display_context)
+}
+
+/// Tauri command wrapper for the per-face texture breakdown of a model (async for non-blocking UI)
+#[tauri::command]
+async fn get_face_textures(
+    pack_id: String,
+    model_id: String,
+    packs_dir: String,
+    pack_order: Vec<String>,
+    overrides: std::collections::HashMap<String, weaverbird_lib::model::OverrideSelection>,
+    variant: Option<weaverbird_lib::util::blockstates::ResolvedModel>,
+) -> Result<weaverbird_lib::util::face_textures::FaceTextureBreakdown, weaverbird_lib::AppError> {
+    tokio::task::spawn_blocking(move || {
+        get_face_textures_impl(pack_id, model_id, packs_dir, pack_order, overrides, variant)
+    })
+    .await
+    .map_err(|e| weaverbird_lib::AppError::internal("Task join error", format!("{}", e)))?
+}
+
+/// Tauri command wrapper for finding the blockstates and models that use a texture (async for non-blocking UI)
+#[tauri::command]
+async fn get_texture_usage(
+    packs_dir: String,
+    texture_id: String,
+) -> Result<Vec<weaverbird_lib::util::texture_index::TextureUsage>, weaverbird_lib::AppError> {
+    tokio::task::spawn_blocking(move || get_texture_usage_impl(packs_dir, texture_id))
+        .await
+        .map_err(|e| weaverbird_lib::AppError::internal("Task join error", format!("{}", e)))?
+}
+
+/// Tauri command wrapper for previewing the textures an override takes along (async for non-blocking UI)
+#[tauri::command]
+async fn get_override_dependencies(
+    packs_dir: String,
+    asset_id: String,
+    pack_id: String,
+) -> Result<Vec<String>, weaverbird_lib::AppError> {
+    tokio::task::spawn_blocking(move || {
+        get_override_dependencies_impl(packs_dir, asset_id, pack_id)
+    })
+    .await
+    .map_err(|e| weaverbird_lib::AppError::internal("Task join error", format!("{}", e)))?
+}
+
+/// Tauri command wrapper for diffing two packs' versions of a model (async for non-blocking UI)
+#[tauri::command]
+async fn diff_pack_models(
+    packs_dir: String,
+    model_id: String,
+    left_pack_id: String,
+    right_pack_id: String,
+) -> Result<weaverbird_lib::util::model_diff::ModelDiff, weaverbird_lib::AppError> {
+    tokio::task::spawn_blocking(move || {
+        diff_pack_models_impl(packs_dir, model_id, left_pack_id, right_pack_id)
+    })
+    .await
+    .map_err(|e| weaverbird_lib::AppError::internal("Task join error", format!("{}", e)))?
 }
 
 /// Tauri command wrapper for getting block state schema
@@ -223,6 +534,16 @@ async fn resolve_block_state(
     .map_err(|e| weaverbird_lib::AppError::internal("Task join error", format!("{}", e)))?
 }
 
+/// Tauri command wrapper for listing vanilla block and model IDs (async for non-blocking)
+#[tauri::command]
+async fn list_vanilla_ids(
+) -> Result<weaverbird_lib::util::id_catalog::IdCatalog, weaverbird_lib::AppError> {
+    // Use spawn_blocking for walking the vanilla cache
+    tokio::task::spawn_blocking(list_vanilla_ids_impl)
+        .await
+        .map_err(|e| weaverbird_lib::AppError::internal("Task join error", format!("{}", e)))?
+}
+
 /// Tauri command wrapper for getting entity version variants (async for non-blocking)
 #[tauri::command]
 async fn get_entity_version_variants(
@@ -234,11 +555,1008 @@ async fn get_entity_version_variants(
         .map_err(|e| weaverbird_lib::AppError::internal("Task join error", format!("{}", e)))?
 }
 
-fn main() {
+/// Tauri command wrapper for listing saved conflict presets
+#[tauri::command]
+fn list_conflict_presets() -> Result<Vec<ConflictPreset>, weaverbird_lib::AppError> {
+    list_conflict_presets_impl()
+}
+
+/// Tauri command wrapper for saving a conflict preset
+#[tauri::command]
+fn save_conflict_preset(
+    preset: ConflictPreset,
+) -> Result<Vec<ConflictPreset>, weaverbird_lib::AppError> {
+    save_conflict_preset_impl(preset)
+}
+
+/// Tauri command wrapper for deleting a conflict preset
+#[tauri::command]
+fn delete_conflict_preset(name: String) -> Result<Vec<ConflictPreset>, weaverbird_lib::AppError> {
+    delete_conflict_preset_impl(name)
+}
+
+/// Tauri command wrapper for exporting a conflict preset as JSON
+#[tauri::command]
+fn export_conflict_preset(name: String) -> Result<String, weaverbird_lib::AppError> {
+    export_conflict_preset_impl(name)
+}
+
+/// Tauri command wrapper for importing a conflict preset from JSON
+#[tauri::command]
+fn import_conflict_preset(json: String) -> Result<ConflictPreset, weaverbird_lib::AppError> {
+    import_conflict_preset_impl(json)
+}
+
+/// Tauri command wrapper for applying a conflict preset (async for non-blocking UI)
+#[tauri::command]
+async fn apply_conflict_preset(
+    packs_dir: String,
+    pack_order: Vec<String>,
+    preset: ConflictPreset,
+) -> Result<
+    std::collections::HashMap<String, weaverbird_lib::model::OverrideSelection>,
+    weaverbird_lib::AppError,
+> {
+    // Use spawn_blocking for I/O-heavy pack scanning and texture probing
+    tokio::task::spawn_blocking(move || apply_conflict_preset_impl(packs_dir, pack_order, preset))
+        .await
+        .map_err(|e| weaverbird_lib::AppError::internal("Task join error", format!("{}", e)))?
+}
+
+/// Tauri command wrapper for listing saved projects
+#[tauri::command]
+fn list_projects() -> Result<Vec<Project>, weaverbird_lib::AppError> {
+    list_projects_impl()
+}
+
+/// Tauri command wrapper for saving a project
+#[tauri::command]
+fn save_project(project: Project) -> Result<Vec<Project>, weaverbird_lib::AppError> {
+    save_project_impl(project)
+}
+
+/// Tauri command wrapper for deleting a project
+#[tauri::command]
+fn delete_project(name: String) -> Result<Vec<Project>, weaverbird_lib::AppError> {
+    delete_project_impl(name)
+}
+
+/// Tauri command wrapper for recording a frontend change in the activity log
+#[tauri::command]
+fn record_activity(
+    packs_dir: String,
+    action: String,
+    params: serde_json::Value,
+    previous: Option<serde_json::Value>,
+    project_name: Option<String>,
+) -> Result<(), weaverbird_lib::AppError> {
+    record_activity_impl(packs_dir, action, params, previous, project_name)
+}
+
+/// Tauri command wrapper for reading the activity log of a packs directory
+#[tauri::command]
+fn get_activity_log(
+    packs_dir: String,
+    actions: Option<Vec<String>>,
+    limit: Option<usize>,
+    project_name: Option<String>,
+) -> Result<Vec<ActivityEntry>, weaverbird_lib::AppError> {
+    get_activity_log_impl(packs_dir, actions, limit, project_name)
+}
+
+/// Tauri command wrapper for building several projects (async for non-blocking UI)
+#[tauri::command]
+async fn batch_build_projects(
+    names: Vec<String>,
+    concurrency: Option<usize>,
+) -> Result<BatchBuildSummary, weaverbird_lib::AppError> {
+    // Use spawn_blocking for CPU/IO-heavy builds
+    tokio::task::spawn_blocking(move || batch_build_projects_impl(names, concurrency, true))
+        .await
+        .map_err(|e| weaverbird_lib::AppError::internal("Task join error", format!("{}", e)))?
+}
+
+/// Tauri command wrapper for getting post-build hooks
+#[tauri::command]
+fn get_post_build_hooks() -> Result<Vec<PostBuildHook>, weaverbird_lib::AppError> {
+    get_post_build_hooks_impl()
+}
+
+/// Tauri command wrapper for setting post-build hooks
+#[tauri::command]
+fn set_post_build_hooks(
+    hooks: Vec<PostBuildHook>,
+) -> Result<Vec<PostBuildHook>, weaverbird_lib::AppError> {
+    set_post_build_hooks_impl(hooks)
+}
+
+/// Tauri command wrapper for picking a folder and granting access to it
+#[tauri::command]
+async fn select_folder(
+    app_handle: tauri::AppHandle,
+    default_path: Option<String>,
+) -> Result<Option<String>, weaverbird_lib::AppError> {
+    // The native dialog blocks, so keep it off the async runtime
+    tokio::task::spawn_blocking(move || select_folder_impl(&app_handle, default_path))
+        .await
+        .map_err(|e| weaverbird_lib::AppError::internal("Task join error", format!("{}", e)))?
+}
+
+/// Tauri command wrapper for listing granted folders
+#[tauri::command]
+fn list_path_grants() -> Result<Vec<String>, weaverbird_lib::AppError> {
+    list_path_grants_impl()
+}
+
+/// Tauri command wrapper for revoking a granted folder
+#[tauri::command]
+fn revoke_path_grant(path: String) -> Result<Vec<String>, weaverbird_lib::AppError> {
+    revoke_path_grant_impl(path)
+}
+
+/// Tauri command wrapper for collecting diagnostics (async for non-blocking UI)
+#[tauri::command]
+async fn get_diagnostics(
+) -> Result<weaverbird_lib::util::diagnostics::DiagnosticsReport, weaverbird_lib::AppError> {
+    // Use spawn_blocking for filesystem-heavy launcher and version detection
+    tokio::task::spawn_blocking(get_diagnostics_impl)
+        .await
+        .map_err(|e| weaverbird_lib::AppError::internal("Task join error", format!("{}", e)))?
+}
+
+/// Tauri command wrapper for reading the end of the application log
+#[tauri::command]
+async fn get_recent_logs(
+    limit: Option<usize>,
+) -> Result<weaverbird_lib::util::logging::RecentLogs, weaverbird_lib::AppError> {
+    tokio::task::spawn_blocking(move || get_recent_logs_impl(limit))
+        .await
+        .map_err(|e| weaverbird_lib::AppError::internal("Task join error", format!("{}", e)))?
+}
+
+/// Tauri command wrapper for exporting diagnostics to a file (async for non-blocking UI)
+#[tauri::command]
+async fn export_diagnostics(output_path: String) -> Result<String, weaverbird_lib::AppError> {
+    tokio::task::spawn_blocking(move || export_diagnostics_impl(output_path))
+        .await
+        .map_err(|e| weaverbird_lib::AppError::internal("Task join error", format!("{}", e)))?
+}
+
+/// Tauri command wrapper for verifying and repairing caches (async for non-blocking UI)
+#[tauri::command]
+async fn verify_caches(
+    repair: bool,
+    window: tauri::Window,
+) -> Result<weaverbird_lib::util::cache_health::CacheHealthReport, weaverbird_lib::AppError> {
+    // Use spawn_blocking for I/O-heavy cache scanning and re-extraction
+    tokio::task::spawn_blocking(move || verify_caches_impl(repair, Some(window)))
+        .await
+        .map_err(|e| weaverbird_lib::AppError::internal("Task join error", format!("{}", e)))?
+}
+
+/// Tauri command wrapper for removing cached vanilla versions
+#[tauri::command]
+async fn clear_vanilla_cache(
+    version: Option<String>,
+) -> Result<weaverbird_lib::util::VanillaCacheClearResult, weaverbird_lib::AppError> {
+    tokio::task::spawn_blocking(move || clear_vanilla_cache_impl(version))
+        .await
+        .map_err(|e| weaverbird_lib::AppError::internal("Task join error", format!("{}", e)))?
+}
+
+/// Tauri command wrapper for getting animated texture metadata
+#[tauri::command]
+fn get_animation_info(
+    pack_path: String,
+    asset_id: String,
+    is_zip: bool,
+) -> Result<Option<weaverbird_lib::util::animation::AnimationInfo>, weaverbird_lib::AppError> {
+    get_animation_info_impl(pack_path, asset_id, is_zip)
+}
+
+/// Tauri command wrapper for splitting an animated texture into frames
+#[tauri::command]
+async fn get_animation_frames(
+    pack_path: String,
+    asset_id: String,
+    is_zip: bool,
+) -> Result<Option<weaverbird_lib::util::animation::AnimationFrames>, weaverbird_lib::AppError> {
+    tokio::task::spawn_blocking(move || get_animation_frames_impl(pack_path, asset_id, is_zip))
+        .await
+        .map_err(|e| weaverbird_lib::AppError::internal("Task join error", format!("{}", e)))?
+}
+
+/// Tauri command wrapper for getting the preview levels of a large texture
+#[tauri::command]
+async fn get_texture_pyramid(
+    pack_path: String,
+    asset_id: String,
+    is_zip: bool,
+) -> Result<weaverbird_lib::util::texture_tiles::TexturePyramid, weaverbird_lib::AppError> {
+    tokio::task::spawn_blocking(move || get_texture_pyramid_impl(pack_path, asset_id, is_zip))
+        .await
+        .map_err(|e| weaverbird_lib::AppError::internal("Task join error", format!("{}", e)))?
+}
+
+/// Tauri command wrapper for getting one preview tile of a large texture
+#[tauri::command]
+async fn get_texture_tile(
+    pack_path: String,
+    asset_id: String,
+    is_zip: bool,
+    level: u32,
+    column: u32,
+    row: u32,
+) -> Result<weaverbird_lib::util::texture_tiles::TextureTile, weaverbird_lib::AppError> {
+    tokio::task::spawn_blocking(move || {
+        get_texture_tile_impl(pack_path, asset_id, is_zip, level, column, row)
+    })
+    .await
+    .map_err(|e| weaverbird_lib::AppError::internal("Task join error", format!("{}", e)))?
+}
+
+/// Tauri command wrapper for grouping entity textures by entity
+#[tauri::command]
+async fn get_entity_groups(
+    packs_dir: String,
+) -> Result<Vec<weaverbird_lib::util::entity_map::EntityGroup>, weaverbird_lib::AppError> {
+    tokio::task::spawn_blocking(move || get_entity_groups_impl(packs_dir))
+        .await
+        .map_err(|e| weaverbird_lib::AppError::internal("Task join error", format!("{}", e)))?
+}
+
+/// Tauri command wrapper for getting the named regions of a GUI texture
+#[tauri::command]
+async fn get_gui_regions(
+    asset_id: String,
+    pack_path: Option<String>,
+    is_zip: bool,
+) -> Result<Option<weaverbird_lib::util::gui_atlas::GuiAtlas>, weaverbird_lib::AppError> {
+    tokio::task::spawn_blocking(move || get_gui_regions_impl(asset_id, pack_path, is_zip))
+        .await
+        .map_err(|e| weaverbird_lib::AppError::internal("Task join error", format!("{}", e)))?
+}
+
+/// Tauri command wrapper for listing GUI textures with known regions
+#[tauri::command]
+fn list_gui_atlases() -> Result<Vec<String>, weaverbird_lib::AppError> {
+    list_gui_atlases_impl()
+}
+
+/// Tauri command wrapper for tinting a texture with its biome color
+#[tauri::command]
+async fn get_tinted_texture(
+    pack_path: String,
+    is_zip: bool,
+    asset_id: String,
+    biome: Option<String>,
+    colormap: Option<weaverbird_lib::util::tinting::Colormap>,
+) -> Result<weaverbird_lib::util::tinting::TintedTexture, weaverbird_lib::AppError> {
+    tokio::task::spawn_blocking(move || {
+        get_tinted_texture_impl(pack_path, is_zip, asset_id, biome, colormap)
+    })
+    .await
+    .map_err(|e| weaverbird_lib::AppError::internal("Task join error", format!("{}", e)))?
+}
+
+/// Tauri command wrapper for listing biomes with their climates
+#[tauri::command]
+fn list_biomes(
+) -> Result<Vec<weaverbird_lib::util::tinting::BiomeClimate>, weaverbird_lib::AppError> {
+    list_biomes_impl()
+}
+
+/// Tauri command wrapper for getting a texture's history across pack versions
+#[tauri::command]
+async fn get_texture_history(
+    packs_dir: String,
+    pack_id: String,
+    asset_id: String,
+) -> Result<Vec<weaverbird_lib::util::texture_history::TextureHistoryEntry>, weaverbird_lib::AppError>
+{
+    // Use spawn_blocking for pack scanning and texture reads
+    tokio::task::spawn_blocking(move || get_texture_history_impl(packs_dir, pack_id, asset_id))
+        .await
+        .map_err(|e| weaverbird_lib::AppError::internal("Task join error", format!("{}", e)))?
+}
+
+/// Tauri command wrapper for the custom model data report (async for non-blocking UI)
+#[tauri::command]
+async fn get_custom_model_data_report(
+    packs_dir: String,
+    pack_ids: Option<Vec<String>>,
+) -> Result<weaverbird_lib::util::custom_model_data::CmdReport, weaverbird_lib::AppError> {
+    tokio::task::spawn_blocking(move || get_custom_model_data_report_impl(packs_dir, pack_ids))
+        .await
+        .map_err(|e| weaverbird_lib::AppError::internal("Task join error", format!("{}", e)))?
+}
+
+/// Tauri command wrapper for exporting the custom model data report as CSV
+#[tauri::command]
+async fn export_custom_model_data_report(
+    packs_dir: String,
+    pack_ids: Option<Vec<String>>,
+    output_path: String,
+) -> Result<String, weaverbird_lib::AppError> {
+    tokio::task::spawn_blocking(move || {
+        export_custom_model_data_report_impl(packs_dir, pack_ids, output_path)
+    })
+    .await
+    .map_err(|e| weaverbird_lib::AppError::internal("Task join error", format!("{}", e)))?
+}
+
+/// Tauri command wrapper for exporting the pack inventory or conflict report
+#[tauri::command]
+async fn export_pack_report(
+    packs_dir: String,
+    pack_order: Vec<String>,
+    overrides: std::collections::HashMap<String, weaverbird_lib::model::OverrideSelection>,
+    kind: weaverbird_lib::util::report_export::ReportKind,
+    format: weaverbird_lib::util::report_export::ReportFormat,
+    output_path: String,
+) -> Result<String, weaverbird_lib::AppError> {
+    tokio::task::spawn_blocking(move || {
+        export_pack_report_impl(packs_dir, pack_order, overrides, kind, format, output_path)
+    })
+    .await
+    .map_err(|e| weaverbird_lib::AppError::internal("Task join error", format!("{}", e)))?
+}
+
+/// Tauri command wrapper for detecting custom namespace collisions (async for non-blocking UI)
+#[tauri::command]
+async fn detect_namespace_collisions(
+    packs_dir: String,
+    pack_order: Vec<String>,
+) -> Result<
+    Vec<weaverbird_lib::util::namespace_conflicts::NamespaceCollision>,
+    weaverbird_lib::AppError,
+> {
+    tokio::task::spawn_blocking(move || detect_namespace_collisions_impl(packs_dir, pack_order))
+        .await
+        .map_err(|e| weaverbird_lib::AppError::internal("Task join error", format!("{}", e)))?
+}
+
+/// Tauri command wrapper for reporting OptiFine connected textures
+#[tauri::command]
+async fn get_ctm_report(
+    packs_dir: String,
+    pack_order: Vec<String>,
+) -> Result<weaverbird_lib::util::optifine::CtmReport, weaverbird_lib::AppError> {
+    tokio::task::spawn_blocking(move || get_ctm_report_impl(packs_dir, pack_order))
+        .await
+        .map_err(|e| weaverbird_lib::AppError::internal("Task join error", format!("{}", e)))?
+}
+
+/// Tauri command wrapper for computing the conflict report of a pack order
+#[tauri::command]
+async fn get_conflict_report(
+    packs_dir: String,
+    pack_order: Vec<String>,
+    overrides: std::collections::HashMap<String, weaverbird_lib::model::OverrideSelection>,
+    namespaces: Option<Vec<String>>,
+) -> Result<weaverbird_lib::util::conflicts::ConflictReport, weaverbird_lib::AppError> {
+    tokio::task::spawn_blocking(move || {
+        get_conflict_report_impl(packs_dir, pack_order, overrides, namespaces)
+    })
+    .await
+    .map_err(|e| weaverbird_lib::AppError::internal("Task join error", format!("{}", e)))?
+}
+
+/// Tauri command wrapper for items whose model several packs provide
+#[tauri::command]
+async fn get_item_model_conflicts(
+    packs_dir: String,
+    pack_order: Vec<String>,
+) -> Result<Vec<weaverbird_lib::util::item_models::ItemModelConflict>, weaverbird_lib::AppError> {
+    tokio::task::spawn_blocking(move || get_item_model_conflicts_impl(packs_dir, pack_order))
+        .await
+        .map_err(|e| weaverbird_lib::AppError::internal("Task join error", format!("{}", e)))?
+}
+
+/// Tauri command wrapper for the per-namespace coverage of a pack order
+#[tauri::command]
+async fn get_namespace_coverage(
+    packs_dir: String,
+    pack_order: Vec<String>,
+    overrides: std::collections::HashMap<String, weaverbird_lib::model::OverrideSelection>,
+) -> Result<
+    Vec<weaverbird_lib::util::namespace_coverage::NamespaceCoverage>,
+    weaverbird_lib::AppError,
+> {
+    tokio::task::spawn_blocking(move || {
+        get_namespace_coverage_impl(packs_dir, pack_order, overrides)
+    })
+    .await
+    .map_err(|e| weaverbird_lib::AppError::internal("Task join error", format!("{}", e)))?
+}
+
+/// Tauri command wrapper for searching language entries across packs
+#[tauri::command]
+async fn search_lang_entries(
+    packs_dir: String,
+    pack_order: Vec<String>,
+    query: String,
+    locale: Option<String>,
+) -> Result<weaverbird_lib::util::lang_search::LangSearchResult, weaverbird_lib::AppError> {
+    tokio::task::spawn_blocking(move || {
+        search_lang_entries_impl(packs_dir, pack_order, query, locale)
+    })
+    .await
+    .map_err(|e| weaverbird_lib::AppError::internal("Task join error", format!("{}", e)))?
+}
+
+/// Tauri command wrapper for checking the packs folder for cloud sync problems
+#[tauri::command]
+async fn inspect_packs_folder(
+    packs_dir: String,
+) -> Result<weaverbird_lib::util::cloud_sync::FolderAccessReport, weaverbird_lib::AppError> {
+    tokio::task::spawn_blocking(move || inspect_packs_folder_impl(packs_dir))
+        .await
+        .map_err(|e| weaverbird_lib::AppError::internal("Task join error", format!("{}", e)))?
+}
+
+/// Tauri command wrapper for downloading cloud-only packs (async, may wait on the network)
+#[tauri::command]
+async fn download_cloud_placeholders(
+    packs_dir: String,
+) -> Result<Vec<weaverbird_lib::util::cloud_sync::PlaceholderDownload>, weaverbird_lib::AppError> {
+    tokio::task::spawn_blocking(move || download_cloud_placeholders_impl(packs_dir))
+        .await
+        .map_err(|e| weaverbird_lib::AppError::internal("Task join error", format!("{}", e)))?
+}
+
+/// Tauri command wrapper for summarizing mod requirements of a merge (async for non-blocking UI)
+#[tauri::command]
+async fn get_feature_requirements(
+    packs_dir: String,
+    pack_order: Vec<String>,
+) -> Result<weaverbird_lib::util::feature_requirements::RequirementsSummary, weaverbird_lib::AppError>
+{
+    tokio::task::spawn_blocking(move || get_feature_requirements_impl(packs_dir, pack_order))
+        .await
+        .map_err(|e| weaverbird_lib::AppError::internal("Task join error", format!("{}", e)))?
+}
+
+/// Tauri command wrapper for checking packs for mipmap problems
+#[tauri::command]
+async fn get_pack_health_report(
+    packs_dir: String,
+    pack_ids: Option<Vec<String>>,
+) -> Result<Vec<weaverbird_lib::util::pack_health::PackHealthReport>, weaverbird_lib::AppError> {
+    tokio::task::spawn_blocking(move || get_pack_health_report_impl(packs_dir, pack_ids))
+        .await
+        .map_err(|e| weaverbird_lib::AppError::internal("Task join error", format!("{}", e)))?
+}
+
+/// Tauri command wrapper for suggesting a starting pack order (async for non-blocking UI)
+#[tauri::command]
+async fn suggest_pack_order(
+    packs_dir: String,
+    pack_ids: Option<Vec<String>>,
+) -> Result<weaverbird_lib::util::pack_order::PackOrderSuggestion, weaverbird_lib::AppError> {
+    tokio::task::spawn_blocking(move || suggest_pack_order_impl(packs_dir, pack_ids))
+        .await
+        .map_err(|e| weaverbird_lib::AppError::internal("Task join error", format!("{}", e)))?
+}
+
+/// Tauri command wrapper for extracting pack color palettes
+#[tauri::command]
+async fn get_pack_palettes(
+    packs_dir: String,
+    pack_ids: Option<Vec<String>>,
+) -> Result<Vec<weaverbird_lib::util::palette::PackPalette>, weaverbird_lib::AppError> {
+    tokio::task::spawn_blocking(move || get_pack_palettes_impl(packs_dir, pack_ids))
+        .await
+        .map_err(|e| weaverbird_lib::AppError::internal("Task join error", format!("{}", e)))?
+}
+
+/// Tauri command wrapper for hashing a built pack with SHA-1
+#[tauri::command]
+async fn compute_output_sha1(
+    output_path: String,
+) -> Result<weaverbird_lib::util::content_hash::Sha1Result, weaverbird_lib::AppError> {
+    tokio::task::spawn_blocking(move || compute_output_sha1_impl(output_path))
+        .await
+        .map_err(|e| weaverbird_lib::AppError::internal("Task join error", format!("{}", e)))?
+}
+
+/// Tauri command wrapper for reading thread pool settings
+#[tauri::command]
+fn get_parallelism_settings(
+    packs_dir: Option<String>,
+) -> Result<ParallelismStatus, weaverbird_lib::AppError> {
+    get_parallelism_settings_impl(packs_dir)
+}
+
+/// Tauri command wrapper for saving thread pool settings
+#[tauri::command]
+fn set_parallelism_settings(
+    parallelism: ParallelismSettings,
+) -> Result<ParallelismSettings, weaverbird_lib::AppError> {
+    set_parallelism_settings_impl(parallelism)
+}
+
+/// Tauri command wrapper for reading the vanilla version preference
+#[tauri::command]
+fn get_vanilla_version_settings() -> Result<VanillaVersionSettings, weaverbird_lib::AppError> {
+    get_vanilla_version_settings_impl()
+}
+
+/// Tauri command wrapper for saving the vanilla version preference
+#[tauri::command]
+fn set_vanilla_version_settings(
+    vanilla_version: VanillaVersionSettings,
+) -> Result<VanillaVersionSettings, weaverbird_lib::AppError> {
+    set_vanilla_version_settings_impl(vanilla_version)
+}
+
+/// Tauri command wrapper for reading conflict severities
+#[tauri::command]
+fn get_conflict_severity_settings() -> Result<ConflictSeveritySettings, weaverbird_lib::AppError> {
+    get_conflict_severity_settings_impl()
+}
+
+/// Tauri command wrapper for saving conflict severities
+#[tauri::command]
+fn set_conflict_severity_settings(
+    conflict_severity: ConflictSeveritySettings,
+) -> Result<ConflictSeveritySettings, weaverbird_lib::AppError> {
+    set_conflict_severity_settings_impl(conflict_severity)
+}
+
+/// Tauri command wrapper for listing builds that didn't finish
+#[tauri::command]
+fn list_interrupted_builds(
+) -> Result<Vec<weaverbird_lib::util::build_journal::InterruptedBuild>, weaverbird_lib::AppError> {
+    list_interrupted_builds_impl()
+}
+
+/// Tauri command wrapper for discarding an interrupted build
+#[tauri::command]
+async fn discard_interrupted_build(output_dir: String) -> Result<bool, weaverbird_lib::AppError> {
+    tokio::task::spawn_blocking(move || discard_interrupted_build_impl(output_dir))
+        .await
+        .map_err(|e| weaverbird_lib::AppError::internal("Task join error", format!("{}", e)))?
+}
+
+/// Tauri command wrapper for clearing a lock left behind by a crash
+#[tauri::command]
+async fn clear_stale_lock(
+    lock_path: String,
+    force: Option<bool>,
+) -> Result<bool, weaverbird_lib::AppError> {
+    tokio::task::spawn_blocking(move || clear_stale_lock_impl(lock_path, force))
+        .await
+        .map_err(|e| weaverbird_lib::AppError::internal("Task join error", format!("{}", e)))?
+}
+
+/// Tauri command wrapper for resuming an interrupted build
+#[tauri::command]
+async fn resume_interrupted_build(
+    output_dir: String,
+    job_id: Option<String>,
+) -> Result<String, weaverbird_lib::AppError> {
+    tokio::task::spawn_blocking(move || resume_interrupted_build_impl(output_dir, job_id, true))
+        .await
+        .map_err(|e| weaverbird_lib::AppError::internal("Task join error", format!("{}", e)))?
+}
+
+/// Tauri command wrapper for validating an instance's enabled pack stack
+#[tauri::command]
+async fn check_instance_stack(
+    instance_dir: String,
+) -> Result<weaverbird_lib::util::stack_check::StackReport, weaverbird_lib::AppError> {
+    tokio::task::spawn_blocking(move || check_instance_stack_impl(instance_dir))
+        .await
+        .map_err(|e| weaverbird_lib::AppError::internal("Task join error", format!("{}", e)))?
+}
+
+/// Tauri command wrapper for rendering a preview scene
+#[tauri::command]
+async fn render_preview_scene(
+    packs_dir: String,
+    pack_order: Vec<String>,
+    overrides: std::collections::HashMap<String, weaverbird_lib::model::OverrideSelection>,
+    output_path: String,
+    tile_size: Option<u32>,
+    color_filter: Option<weaverbird_lib::util::color_vision::ColorVisionFilter>,
+    rules: Option<weaverbird_lib::util::weaver_nest::WinnerRules>,
+) -> Result<weaverbird_lib::util::preview_scene::ScenePreview, weaverbird_lib::AppError> {
+    tokio::task::spawn_blocking(move || {
+        render_preview_scene_impl(
+            packs_dir,
+            pack_order,
+            overrides,
+            output_path,
+            tile_size,
+            color_filter,
+            rules,
+        )
+    })
+    .await
+    .map_err(|e| weaverbird_lib::AppError::internal("Task join error", format!("{}", e)))?
+}
+
+/// Tauri command wrapper for rendering documentation previews in one batch
+#[tauri::command]
+async fn render_doc_previews(
+    packs_dir: String,
+    pack_order: Vec<String>,
+    overrides: std::collections::HashMap<String, weaverbird_lib::model::OverrideSelection>,
+    asset_ids: Vec<String>,
+    output_dir: String,
+    options: Option<weaverbird_lib::util::doc_previews::DocPreviewOptions>,
+    rules: Option<weaverbird_lib::util::weaver_nest::WinnerRules>,
+) -> Result<weaverbird_lib::util::doc_previews::DocPreviewSummary, weaverbird_lib::AppError> {
+    tokio::task::spawn_blocking(move || {
+        render_doc_previews_impl(
+            packs_dir, pack_order, overrides, asset_ids, output_dir, options, rules,
+        )
+    })
+    .await
+    .map_err(|e| weaverbird_lib::AppError::internal("Task join error", format!("{}", e)))?
+}
+
+/// Tauri command wrapper for exporting a texture contact sheet
+#[tauri::command]
+async fn export_contact_sheet(
+    packs_dir: String,
+    pack_order: Vec<String>,
+    overrides: std::collections::HashMap<String, weaverbird_lib::model::OverrideSelection>,
+    category: String,
+    output_path: String,
+    tile_size: Option<u32>,
+    columns: Option<u32>,
+) -> Result<weaverbird_lib::util::contact_sheet::ContactSheetExport, weaverbird_lib::AppError> {
+    tokio::task::spawn_blocking(move || {
+        export_contact_sheet_impl(
+            packs_dir,
+            pack_order,
+            overrides,
+            category,
+            output_path,
+            tile_size,
+            columns,
+        )
+    })
+    .await
+    .map_err(|e| weaverbird_lib::AppError::internal("Task join error", format!("{}", e)))?
+}
+
+/// Tauri command wrapper for exporting before/after comparison images
+#[tauri::command]
+#[allow(clippy::too_many_arguments)]
+async fn export_comparison_images(
+    packs_dir: String,
+    pack_order: Vec<String>,
+    overrides: std::collections::HashMap<String, weaverbird_lib::model::OverrideSelection>,
+    asset_ids: Vec<String>,
+    layout: weaverbird_lib::util::comparison::ComparisonLayout,
+    size: u32,
+    output_dir: String,
+    rules: Option<weaverbird_lib::util::weaver_nest::WinnerRules>,
+) -> Result<weaverbird_lib::util::comparison::ComparisonExport, weaverbird_lib::AppError> {
+    tokio::task::spawn_blocking(move || {
+        export_comparison_images_impl(
+            packs_dir, pack_order, overrides, asset_ids, layout, size, output_dir, rules,
+        )
+    })
+    .await
+    .map_err(|e| weaverbird_lib::AppError::internal("Task join error", format!("{}", e)))?
+}
+
+/// Tauri command wrapper for simulating color blindness on a preview image
+#[tauri::command]
+async fn simulate_color_vision(
+    input_path: String,
+    filter: weaverbird_lib::util::color_vision::ColorVisionFilter,
+    output_path: String,
+) -> Result<String, weaverbird_lib::AppError> {
+    tokio::task::spawn_blocking(move || simulate_color_vision_impl(input_path, filter, output_path))
+        .await
+        .map_err(|e| weaverbird_lib::AppError::internal("Task join error", format!("{}", e)))?
+}
+
+/// Tauri command wrapper for checking where a project's output is installed
+#[tauri::command]
+async fn get_applied_pack_status(
+    project: Project,
+) -> Result<weaverbird_lib::util::install_status::AppliedPackReport, weaverbird_lib::AppError> {
+    tokio::task::spawn_blocking(move || get_applied_pack_status_impl(project))
+        .await
+        .map_err(|e| weaverbird_lib::AppError::internal("Task join error", format!("{}", e)))?
+}
+
+/// Tauri command wrapper for comparing two simulated pack stacks
+#[tauri::command]
+async fn simulate_stacks(
+    project: Project,
+    a: String,
+    b: String,
+) -> Result<weaverbird_lib::util::stack_simulation::StackSimulation, weaverbird_lib::AppError> {
+    tokio::task::spawn_blocking(move || simulate_stacks_impl(project, a, b))
+        .await
+        .map_err(|e| weaverbird_lib::AppError::internal("Task join error", format!("{}", e)))?
+}
+
+/// Tauri command wrapper for seeding overrides from an older project (async for non-blocking UI)
+#[tauri::command]
+async fn seed_overrides_from_project(
+    project_name: String,
+    packs_dir: String,
+) -> Result<weaverbird_lib::util::override_seeding::OverrideSeed, weaverbird_lib::AppError> {
+    tokio::task::spawn_blocking(move || seed_overrides_from_project_impl(project_name, packs_dir))
+        .await
+        .map_err(|e| weaverbird_lib::AppError::internal("Task join error", format!("{}", e)))?
+}
+
+/// Tauri command wrapper for getting the next conflict to resolve
+#[tauri::command]
+async fn get_next_conflict(
+    project_name: String,
+    revisit_skipped: bool,
+) -> Result<weaverbird_lib::util::resolution_queue::ResolutionProgress, weaverbird_lib::AppError> {
+    tokio::task::spawn_blocking(move || get_next_conflict_impl(project_name, revisit_skipped))
+        .await
+        .map_err(|e| weaverbird_lib::AppError::internal("Task join error", format!("{}", e)))?
+}
+
+/// Tauri command wrapper for resolving, skipping or reopening a conflict
+#[tauri::command]
+async fn record_conflict_resolution(
+    project_name: String,
+    asset_id: String,
+    action: weaverbird_lib::util::resolution_queue::ResolutionAction,
+    revisit_skipped: bool,
+) -> Result<weaverbird_lib::util::resolution_queue::ResolutionProgress, weaverbird_lib::AppError> {
+    tokio::task::spawn_blocking(move || {
+        record_conflict_resolution_impl(project_name, asset_id, action, revisit_skipped)
+    })
+    .await
+    .map_err(|e| weaverbird_lib::AppError::internal("Task join error", format!("{}", e)))?
+}
+
+/// Tauri command wrapper for forgetting a project's resolution progress
+#[tauri::command]
+fn reset_conflict_resolution(project_name: String) -> Result<Project, weaverbird_lib::AppError> {
+    reset_conflict_resolution_impl(project_name)
+}
+
+/// Tauri command wrapper for checking which projects use a pack before removal
+#[tauri::command]
+async fn check_pack_removal(
+    packs_dir: String,
+    pack_id: String,
+) -> Result<weaverbird_lib::util::pack_quarantine::PackRemovalImpact, weaverbird_lib::AppError> {
+    tokio::task::spawn_blocking(move || check_pack_removal_impl(packs_dir, pack_id))
+        .await
+        .map_err(|e| weaverbird_lib::AppError::internal("Task join error", format!("{}", e)))?
+}
+
+/// Tauri command wrapper for moving a pack to quarantine
+#[tauri::command]
+async fn quarantine_pack(
+    packs_dir: String,
+    pack_id: String,
+    confirm_referenced: bool,
+) -> Result<weaverbird_lib::util::pack_quarantine::QuarantinedPack, weaverbird_lib::AppError> {
+    tokio::task::spawn_blocking(move || {
+        quarantine_pack_impl(packs_dir, pack_id, confirm_referenced)
+    })
+    .await
+    .map_err(|e| weaverbird_lib::AppError::internal("Task join error", format!("{}", e)))?
+}
+
+/// Tauri command wrapper for listing quarantined packs
+#[tauri::command]
+async fn list_quarantined_packs(
+    packs_dir: String,
+) -> Result<Vec<weaverbird_lib::util::pack_quarantine::QuarantinedPack>, weaverbird_lib::AppError> {
+    tokio::task::spawn_blocking(move || list_quarantined_packs_impl(packs_dir))
+        .await
+        .map_err(|e| weaverbird_lib::AppError::internal("Task join error", format!("{}", e)))?
+}
+
+/// Tauri command wrapper for restoring a quarantined pack
+#[tauri::command]
+async fn restore_quarantined_pack(
+    packs_dir: String,
+    quarantine_id: String,
+) -> Result<String, weaverbird_lib::AppError> {
+    tokio::task::spawn_blocking(move || restore_quarantined_pack_impl(packs_dir, quarantine_id))
+        .await
+        .map_err(|e| weaverbird_lib::AppError::internal("Task join error", format!("{}", e)))?
+}
+
+/// Tauri command wrapper for permanently deleting a quarantined pack
+#[tauri::command]
+async fn purge_quarantined_pack(
+    packs_dir: String,
+    quarantine_id: String,
+) -> Result<(), weaverbird_lib::AppError> {
+    tokio::task::spawn_blocking(move || purge_quarantined_pack_impl(packs_dir, quarantine_id))
+        .await
+        .map_err(|e| weaverbird_lib::AppError::internal("Task join error", format!("{}", e)))?
+}
+
+/// Tauri command wrapper for listing running Minecraft processes
+#[tauri::command]
+async fn get_running_games(
+    target_dir: Option<String>,
+) -> Result<Vec<weaverbird_lib::util::running_game::RunningGame>, weaverbird_lib::AppError> {
+    tokio::task::spawn_blocking(move || get_running_games_impl(target_dir))
+        .await
+        .map_err(|e| weaverbird_lib::AppError::internal("Task join error", format!("{}", e)))?
+}
+
+/// Tauri command wrapper for listing builds waiting for Minecraft to exit
+#[tauri::command]
+fn list_queued_installs(
+) -> Result<Vec<weaverbird_lib::util::running_game::QueuedInstall>, weaverbird_lib::AppError> {
+    list_queued_installs_impl()
+}
+
+/// Tauri command wrapper for installing queued builds whose game has exited
+#[tauri::command]
+async fn apply_queued_installs() -> Result<Vec<String>, weaverbird_lib::AppError> {
+    tokio::task::spawn_blocking(apply_queued_installs_impl)
+        .await
+        .map_err(|e| weaverbird_lib::AppError::internal("Task join error", format!("{}", e)))?
+}
+
+/// Tauri command wrapper for dropping a queued build
+#[tauri::command]
+fn discard_queued_install(output_dir: String) -> Result<bool, weaverbird_lib::AppError> {
+    discard_queued_install_impl(output_dir)
+}
+
+/// Tauri command wrapper for getting what builds do while Minecraft runs
+#[tauri::command]
+fn get_running_game_policy(
+) -> Result<weaverbird_lib::util::running_game::RunningGamePolicy, weaverbird_lib::AppError> {
+    get_running_game_policy_impl()
+}
+
+/// Tauri command wrapper for setting what builds do while Minecraft runs
+#[tauri::command]
+fn set_running_game_policy(
+    policy: weaverbird_lib::util::running_game::RunningGamePolicy,
+) -> Result<weaverbird_lib::util::running_game::RunningGamePolicy, weaverbird_lib::AppError> {
+    set_running_game_policy_impl(policy)
+}
+
+/// Tauri command wrapper for getting the default pack description template
+#[tauri::command]
+fn get_description_template() -> Result<Option<String>, weaverbird_lib::AppError> {
+    get_description_template_impl()
+}
+
+/// Tauri command wrapper for setting the default pack description template
+#[tauri::command]
+fn set_description_template(
+    template: Option<String>,
+) -> Result<Option<String>, weaverbird_lib::AppError> {
+    set_description_template_impl(template)
+}
+
+/// Tauri command wrapper for previewing a pack description template
+#[tauri::command]
+fn render_pack_description(
+    template: String,
+    context: weaverbird_lib::util::pack_description::DescriptionContext,
+) -> Result<String, weaverbird_lib::AppError> {
+    render_pack_description_impl(template, context)
+}
+
+/// Tauri command wrapper for listing Modrinth App profiles
+#[tauri::command]
+fn list_modrinth_profiles(
+    profiles_dir: Option<String>,
+) -> Result<Vec<weaverbird_lib::util::modrinth_profiles::ModrinthProfile>, weaverbird_lib::AppError>
+{
+    list_modrinth_profiles_impl(profiles_dir)
+}
+
+/// Tauri command wrapper for listing Prism Launcher, MultiMC and CurseForge instances
+#[tauri::command]
+fn list_launcher_instances(
+    instances_dir: Option<String>,
+) -> Result<Vec<weaverbird_lib::util::instances::LauncherInstance>, weaverbird_lib::AppError> {
+    list_launcher_instances_impl(instances_dir)
+}
+
+/// Tauri command wrapper for downloading a remote pack (async for non-blocking UI)
+#[tauri::command]
+#[allow(clippy::too_many_arguments)]
+async fn download_remote_pack(
+    window: tauri::Window,
+    url: String,
+    packs_dir: String,
+    file_name: Option<String>,
+    sha1: Option<String>,
+    size: Option<u64>,
+    overwrite: Option<bool>,
+    job_id: Option<String>,
+) -> Result<weaverbird_lib::util::DownloadedFile, weaverbird_lib::AppError> {
+    tokio::task::spawn_blocking(move || {
+        download_remote_pack_impl(
+            Some(window),
+            url,
+            packs_dir,
+            file_name,
+            sha1,
+            size,
+            overwrite,
+            job_id,
+        )
+    })
+    .await
+    .map_err(|e| weaverbird_lib::AppError::internal("Task join error", format!("{}", e)))?
+}
+
+/// Tauri command wrapper for exporting an animated texture
+#[tauri::command]
+async fn export_animation(
+    pack_path: String,
+    asset_id: String,
+    is_zip: bool,
+    format: weaverbird_lib::util::animation_export::ExportFormat,
+    size: u32,
+    output_path: String,
+) -> Result<weaverbird_lib::util::animation_export::AnimationExport, weaverbird_lib::AppError> {
+    // Use spawn_blocking for CPU-heavy frame rendering and encoding
+    tokio::task::spawn_blocking(move || {
+        export_animation_impl(pack_path, asset_id, is_zip, format, size, output_path)
+    })
+    .await
+    .map_err(|e| weaverbird_lib::AppError::internal("Task join error", format!("{}", e)))?
+}
+
+/// Serve a weaverbird://asset/<pack path>/<file path> request
+fn asset_protocol_response(
+    request: &tauri::http::Request<Vec<u8>>,
+) -> tauri::http::Response<Vec<u8>> {
+    let range = request
+        .headers()
+        .get(tauri::http::header::RANGE)
+        .and_then(|value| value.to_str().ok());
+    let asset = weaverbird_lib::util::asset_protocol::serve_asset(
+        request.uri().host(),
+        request.uri().path(),
+        range,
+    );
+
+    let mut response = tauri::http::Response::builder().status(asset.status);
+    for (name, value) in &asset.headers {
+        response = response.header(*name, value);
+    }
+    response.body(asset.body).unwrap_or_default()
+}
+
+fn main() {
+    let log_dir = weaverbird_lib::util::logging::default_log_dir().ok();
+    weaverbird_lib::util::logging::init_logging(log_dir.as_deref());
+
+    // Size the thread pools before any parallel work runs
+    let parallelism = weaverbird_lib::util::settings::load_settings()
+        .map(|settings| settings.parallelism)
+        .unwrap_or_default();
+    let packs_dir = weaverbird_lib::util::mc_paths::get_default_resourcepacks_dir().ok();
+    weaverbird_lib::util::parallelism::init_parallelism(&parallelism, packs_dir.as_deref());
+    start_remote_server_if_enabled();
+
     tauri::Builder::default()
         .plugin(tauri_plugin_dialog::init())
         .plugin(tauri_plugin_fs::init())
         .plugin(tauri_plugin_shell::init())
+        // Pack files for <img>/<audio>/fetch() without command round trips;
+        // read off the webview thread since ZIP entries can be large
+        .register_asynchronous_uri_scheme_protocol(
+            weaverbird_lib::util::asset_protocol::ASSET_PROTOCOL_SCHEME,
+            |_ctx, request, responder| {
+                std::thread::spawn(move || responder.respond(asset_protocol_response(&request)));
+            },
+        )
         .setup(|app| {
             #[cfg(target_os = "macos")]
             {
@@ -283,7 +1601,15 @@ fn main() {
         })
         .invoke_handler(tauri::generate_handler![
             scan_packs_folder,
+            restore_last_session,
+            update_session_stack,
             build_weaver_nest,
+            create_job,
+            cancel_job,
+            start_remote_server,
+            stop_remote_server,
+            get_remote_server_status,
+            regenerate_remote_server_token,
             get_default_packs_dir,
             initialize_vanilla_textures,
             get_vanilla_texture_path,
@@ -303,9 +1629,111 @@ fn main() {
             read_pack_file,
             read_vanilla_jem,
             load_model_json,
+            load_item_model,
             get_block_state_schema,
             resolve_block_state,
-            get_entity_version_variants
+            get_entity_version_variants,
+            list_conflict_presets,
+            save_conflict_preset,
+            delete_conflict_preset,
+            export_conflict_preset,
+            import_conflict_preset,
+            apply_conflict_preset,
+            list_projects,
+            save_project,
+            delete_project,
+            batch_build_projects,
+            get_post_build_hooks,
+            set_post_build_hooks,
+            select_folder,
+            list_path_grants,
+            revoke_path_grant,
+            get_diagnostics,
+            get_recent_logs,
+            export_diagnostics,
+            verify_caches,
+            clear_vanilla_cache,
+            get_animation_info,
+            get_animation_frames,
+            get_texture_pyramid,
+            get_texture_tile,
+            export_animation,
+            get_texture_history,
+            get_entity_groups,
+            get_custom_model_data_report,
+            export_custom_model_data_report,
+            detect_namespace_collisions,
+            get_conflict_report,
+            get_namespace_coverage,
+            get_item_model_conflicts,
+            search_lang_entries,
+            get_ctm_report,
+            get_feature_requirements,
+            get_pack_health_report,
+            get_pack_palettes,
+            compute_output_sha1,
+            get_parallelism_settings,
+            set_parallelism_settings,
+            get_vanilla_version_settings,
+            set_vanilla_version_settings,
+            get_conflict_severity_settings,
+            set_conflict_severity_settings,
+            list_interrupted_builds,
+            discard_interrupted_build,
+            clear_stale_lock,
+            resume_interrupted_build,
+            check_instance_stack,
+            render_preview_scene,
+            render_doc_previews,
+            export_contact_sheet,
+            export_comparison_images,
+            simulate_color_vision,
+            inspect_packs_folder,
+            download_cloud_placeholders,
+            get_applied_pack_status,
+            export_pack_report,
+            browse_pack,
+            reveal_in_file_manager,
+            extract_pack_file,
+            get_face_textures,
+            diff_pack_models,
+            get_override_dependencies,
+            get_texture_usage,
+            suggest_pack_order,
+            extract_pack_archive,
+            compress_pack_folder,
+            repack_pack_folder,
+            build_merged_pack,
+            migrate_pack,
+            list_vanilla_ids,
+            record_activity,
+            get_activity_log,
+            simulate_stacks,
+            get_next_conflict,
+            record_conflict_resolution,
+            reset_conflict_resolution,
+            check_pack_removal,
+            quarantine_pack,
+            list_quarantined_packs,
+            restore_quarantined_pack,
+            purge_quarantined_pack,
+            get_running_games,
+            list_queued_installs,
+            apply_queued_installs,
+            discard_queued_install,
+            get_running_game_policy,
+            set_running_game_policy,
+            get_gui_regions,
+            list_gui_atlases,
+            get_tinted_texture,
+            list_biomes,
+            get_description_template,
+            set_description_template,
+            render_pack_description,
+            list_modrinth_profiles,
+            seed_overrides_from_project,
+            download_remote_pack,
+            list_launcher_instances
         ])
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
@@ -1,10 +1,14 @@
+use crate::util::asset_stats::AssetStats;
+use crate::util::cloud_sync::FolderAccessReport;
+use crate::util::content_hash::HashTimings;
+use crate::util::known_packs::KnownPackMatch;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
 /// Metadata about a discovered resource pack
 ///
 /// Either a zip file or directory containing pack.mcmeta
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct PackMeta {
     /// Unique identifier for this pack
     pub id: String,
@@ -25,6 +29,13 @@ pub struct PackMeta {
     /// Pack format version from pack.mcmeta (indicates Minecraft version compatibility)
     #[serde(skip_serializing_if = "Option::is_none")]
     pub pack_format: Option<u32>,
+    /// Base packs this pack patches: a small pack relying on their textures
+    /// or named after them (see `pack_order::detect_patch_packs`)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub patch_for: Vec<String>,
+    /// Minecraft versions the pack format is meant for (e.g., "1.20 - 1.20.1")
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub compatible_mc_versions: Option<String>,
 }
 
 /// A single asset (texture, model, config, etc.) with metadata
@@ -36,6 +47,32 @@ pub struct AssetRecord {
     pub labels: Vec<String>,
     /// File paths within packs that contain this asset
     pub files: Vec<String>,
+    /// Animation from the texture's .png.mcmeta, taken from the first pack
+    /// that animates it
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub animation: Option<TextureAnimation>,
+}
+
+/// Animation section of a texture's .png.mcmeta
+///
+/// Kept as declared: frame size and order are only resolved against the
+/// image (see `animation::parse_animation`), which indexing doesn't decode.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TextureAnimation {
+    /// Default game ticks per frame
+    pub frametime: u32,
+    /// Whether to blend between consecutive frames
+    pub interpolate: bool,
+    /// Frame width in pixels, if set
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub width: Option<u32>,
+    /// Frame height in pixels, if set
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub height: Option<u32>,
+    /// Custom frame order as frame indices; None plays every frame in turn
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub frames: Option<Vec<u32>>,
 }
 
 /// Information about which pack provides an asset
@@ -67,17 +104,46 @@ pub struct ScanResult {
     pub assets: Vec<AssetRecord>,
     /// Mapping of asset IDs to the pack IDs that provide them
     pub providers: HashMap<String, Vec<String>>,
+    /// Packs recognized from the known-pack database, keyed by pack ID
+    #[serde(default, rename = "knownPacks")]
+    pub known_packs: HashMap<String, KnownPackMatch>,
+    /// Asset ID -> groups of packs providing byte-identical copies
+    #[serde(default, rename = "identicalProviders")]
+    pub identical_providers: HashMap<String, Vec<Vec<String>>>,
+    /// Cost of content hashing during the scan
+    #[serde(default, rename = "hashTimings")]
+    pub hash_timings: HashTimings,
+    /// Sync, network and read-only problems with the packs folder
+    #[serde(default, rename = "folderAccess")]
+    pub folder_access: Option<FolderAccessReport>,
+    /// Pack ID -> macOS metadata files (__MACOSX, ._*) left out of the index
+    #[serde(default, rename = "osMetadataFiles")]
+    pub os_metadata_files: HashMap<String, usize>,
+    /// Asset counts and sizes by category, per pack and overall
+    #[serde(default, rename = "assetStats")]
+    pub asset_stats: AssetStats,
 }
 
-/// Progress tracking for long-running operations
+/// Progress of a long-running operation, sent as a progress event
 ///
-/// Currently defined but not yet implemented
+/// See `util::progress` for the events and how they're throttled.
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
 pub struct Progress {
+    /// Operation ("scan", "index", "extract", "build" or "download")
     pub phase: String,
     pub completed: u64,
     pub total: u64,
     pub bytes: Option<u64>,
+    /// Transfer rate, for downloads
+    #[serde(default)]
+    pub bytes_per_sec: Option<u64>,
+    /// Item finished last (pack name or file path)
+    #[serde(default)]
+    pub current_file: Option<String>,
+    /// Estimated time left, once there's a rate to go by
+    #[serde(default)]
+    pub eta_ms: Option<u64>,
 }
 
 #[cfg(test)]
@@ -91,10 +157,9 @@ mod tests {
             name: "Test Pack".to_string(),
             path: "/path/to/pack".to_string(),
             size: 1024,
-            is_zip: false,
             description: Some("Test description".to_string()),
             icon_data: Some("base64_icon_data".to_string()),
-            pack_format: None,
+            ..Default::default()
         };
 
         let json = serde_json::to_string(&pack).expect("should serialize");
@@ -116,6 +181,7 @@ mod tests {
                 "stone".to_string(),
             ],
             files: vec!["assets/minecraft/textures/block/stone.png".to_string()],
+            animation: None,
         };
 
         let json = serde_json::to_string(&asset).expect("should serialize");
@@ -173,9 +239,7 @@ mod tests {
                 path: "/path/to/pack1".to_string(),
                 size: 2048,
                 is_zip: true,
-                description: None,
-                icon_data: None,
-                pack_format: None,
+                ..Default::default()
             }],
             assets: vec![AssetRecord {
                 id: "minecraft:block/dirt".to_string(),
@@ -185,6 +249,7 @@ mod tests {
                     "dirt".to_string(),
                 ],
                 files: vec!["assets/minecraft/textures/block/dirt.png".to_string()],
+                animation: None,
             }],
             providers: {
                 let mut map = HashMap::new();
@@ -194,6 +259,12 @@ mod tests {
                 );
                 map
             },
+            known_packs: HashMap::new(),
+            identical_providers: HashMap::new(),
+            hash_timings: HashTimings::default(),
+            folder_access: None,
+            os_metadata_files: HashMap::new(),
+            asset_stats: AssetStats::default(),
         };
 
         let json = serde_json::to_string(&scan_result).expect("should serialize");
@@ -211,6 +282,9 @@ mod tests {
             completed: 50,
             total: 100,
             bytes: Some(1024000),
+            bytes_per_sec: None,
+            current_file: Some("Faithful 32x".to_string()),
+            eta_ms: Some(1500),
         };
 
         let json = serde_json::to_string(&progress).expect("should serialize");
@@ -220,6 +294,8 @@ mod tests {
         assert_eq!(deserialized.completed, 50);
         assert_eq!(deserialized.total, 100);
         assert_eq!(deserialized.bytes, Some(1024000));
+        assert_eq!(deserialized.current_file.as_deref(), Some("Faithful 32x"));
+        assert!(json.contains("\"etaMs\":1500"));
     }
 
     #[test]
@@ -231,8 +307,7 @@ mod tests {
             size: 512,
             is_zip: true,
             description: Some("Description".to_string()),
-            icon_data: None,
-            pack_format: None,
+            ..Default::default()
         };
 
         let pack2 = pack1.clone();
@@ -247,6 +322,7 @@ mod tests {
             id: "test:asset".to_string(),
             labels: vec!["test".to_string()],
             files: vec!["file.png".to_string()],
+            animation: None,
         };
 
         let asset2 = asset1.clone();
@@ -0,0 +1,230 @@
+/// Append-only log of state-changing operations, per packs directory
+///
+/// Builds, merges, archive changes and project edits are recorded by the
+/// backend once they succeed. Override and pack order edits only live in the
+/// frontend until a build, so it records those itself through a command.
+/// Entries keep their parameters and, where known, the state they replaced,
+/// so one log backs both undo and the "recent activity" list. Each packs
+/// directory gets a JSON Lines file under the config directory. Entries
+/// name the project they were made in, so projects sharing a packs
+/// directory only see their own changes plus those to the folder itself.
+use crate::util::content_hash::xxh3_hex;
+use crate::util::settings;
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::fs::{self, OpenOptions};
+use std::io::{Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+use tracing::warn;
+
+/// Folder under the config directory holding activity logs
+const ACTIVITY_DIR: &str = "activity";
+
+/// One recorded operation
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ActivityEntry {
+    /// Unix timestamp in milliseconds
+    pub timestamp: u64,
+    /// What was done (e.g., "build", "saveProject", "setOverride")
+    pub action: String,
+    /// Project the operation was made in; None for changes to the packs
+    /// directory itself, which every project using it sees
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub project: Option<String>,
+    /// Arguments of the operation
+    pub params: serde_json::Value,
+    /// State the operation replaced, for undo
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub previous: Option<serde_json::Value>,
+}
+
+impl ActivityEntry {
+    /// Entry stamped with the current time
+    pub fn now(
+        project: Option<&str>,
+        action: &str,
+        params: serde_json::Value,
+        previous: Option<serde_json::Value>,
+    ) -> Self {
+        let timestamp = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map(|d| d.as_millis() as u64)
+            .unwrap_or(0);
+        Self {
+            timestamp,
+            action: action.to_string(),
+            project: project.map(str::to_string),
+            params,
+            previous,
+        }
+    }
+}
+
+/// Default activity directory (~/.config/weaverbird/activity)
+pub fn default_activity_dir() -> Result<PathBuf> {
+    Ok(settings::get_config_dir()?.join(ACTIVITY_DIR))
+}
+
+fn log_path(activity_dir: &Path, packs_dir: &str) -> PathBuf {
+    let key = packs_dir.trim_end_matches(['/', '\\']);
+    activity_dir.join(format!("{}.jsonl", xxh3_hex(key.as_bytes())))
+}
+
+/// Whether a log is empty or its last line is complete
+fn ends_with_newline(file: &mut fs::File) -> bool {
+    let mut last = [0u8; 1];
+    match file.seek(SeekFrom::End(-1)) {
+        Ok(_) => file
+            .read_exact(&mut last)
+            .map_or(true, |_| last[0] == b'\n'),
+        // Seeking before the start: the file is empty
+        Err(_) => true,
+    }
+}
+
+/// Append an entry to the log of a packs directory
+pub fn append_entry(activity_dir: &Path, packs_dir: &str, entry: &ActivityEntry) -> Result<()> {
+    fs::create_dir_all(activity_dir)
+        .with_context(|| format!("Failed to create {:?}", activity_dir))?;
+    let path = log_path(activity_dir, packs_dir);
+    let mut line = serde_json::to_string(entry)?;
+    line.push('\n');
+
+    let mut file = OpenOptions::new()
+        .create(true)
+        .read(true)
+        .append(true)
+        .open(&path)
+        .with_context(|| format!("Failed to open {:?}", path))?;
+    // Start a fresh line after one a crash cut short
+    if !ends_with_newline(&mut file) {
+        line.insert(0, '\n');
+    }
+    // One write per line so concurrent appends don't interleave
+    file.write_all(line.as_bytes())
+        .with_context(|| format!("Failed to write {:?}", path))
+}
+
+/// Entries of a packs directory's log, newest first
+///
+/// Lines that don't parse (e.g. cut short by a crash) are skipped.
+///
+/// # Arguments
+/// * `project` - Project whose entries to include besides the ones made
+///   outside any project
+/// * `actions` - Only these actions; all if empty
+/// * `limit` - Maximum entries returned
+pub fn read_entries(
+    activity_dir: &Path,
+    packs_dir: &str,
+    project: Option<&str>,
+    actions: &[String],
+    limit: Option<usize>,
+) -> Result<Vec<ActivityEntry>> {
+    let path = log_path(activity_dir, packs_dir);
+    let contents = match fs::read_to_string(&path) {
+        Ok(contents) => contents,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(e).with_context(|| format!("Failed to read {:?}", path)),
+    };
+
+    Ok(contents
+        .lines()
+        .rev()
+        .filter_map(|line| serde_json::from_str::<ActivityEntry>(line).ok())
+        .filter(|entry| entry.project.is_none() || entry.project.as_deref() == project)
+        .filter(|entry| actions.is_empty() || actions.contains(&entry.action))
+        .take(limit.unwrap_or(usize::MAX))
+        .collect())
+}
+
+/// Record an operation in the default log, logging rather than failing on errors
+///
+/// The operation already happened; a log that can't be written shouldn't
+/// turn it into an error.
+pub fn record(
+    packs_dir: &str,
+    project: Option<&str>,
+    action: &str,
+    params: serde_json::Value,
+    previous: Option<serde_json::Value>,
+) {
+    let entry = ActivityEntry::now(project, action, params, previous);
+    let result = default_activity_dir().and_then(|dir| append_entry(&dir, packs_dir, &entry));
+    if let Err(e) = result {
+        warn!("Failed to record {}: {}", action, e);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_append_and_read_entries() {
+        let temp_dir = std::env::temp_dir().join("test_activity_log");
+        fs::remove_dir_all(&temp_dir).ok();
+        let packs_dir = "/packs";
+
+        for (i, action) in ["setPackOrder", "build", "setOverride", "build"]
+            .iter()
+            .enumerate()
+        {
+            let mut entry =
+                ActivityEntry::now(None, action, serde_json::json!({ "step": i }), None);
+            entry.timestamp = i as u64;
+            append_entry(&temp_dir, packs_dir, &entry).unwrap();
+        }
+        // A torn line is skipped, and the next entry starts on its own line
+        let mut file = OpenOptions::new()
+            .append(true)
+            .open(log_path(&temp_dir, "/packs/"))
+            .unwrap();
+        file.write_all(b"{\"timestamp\": 9, \"act").unwrap();
+        let mut entry = ActivityEntry::now(None, "build", serde_json::json!({ "step": 4 }), None);
+        entry.timestamp = 4;
+        append_entry(&temp_dir, packs_dir, &entry).unwrap();
+
+        let all = read_entries(&temp_dir, packs_dir, None, &[], None).unwrap();
+        let builds =
+            read_entries(&temp_dir, packs_dir, None, &["build".to_string()], Some(1)).unwrap();
+        let other = read_entries(&temp_dir, "/other", None, &[], None).unwrap();
+
+        // Clean up
+        fs::remove_dir_all(&temp_dir).ok();
+
+        let timestamps: Vec<u64> = all.iter().map(|e| e.timestamp).collect();
+        assert_eq!(timestamps, vec![4, 3, 2, 1, 0]);
+        assert_eq!(builds.len(), 1);
+        assert_eq!(builds[0].params["step"], 4);
+        assert!(other.is_empty());
+    }
+
+    #[test]
+    fn test_read_entries_by_project() {
+        let temp_dir = std::env::temp_dir().join("test_activity_log_projects");
+        fs::remove_dir_all(&temp_dir).ok();
+        let packs_dir = "/packs";
+        for (project, action) in [
+            (Some("Survival"), "setOverride"),
+            (Some("Creative"), "setPackOrder"),
+            (None, "quarantinePack"),
+        ] {
+            let entry = ActivityEntry::now(project, action, serde_json::json!({}), None);
+            append_entry(&temp_dir, packs_dir, &entry).unwrap();
+        }
+
+        let survival = read_entries(&temp_dir, packs_dir, Some("Survival"), &[], None).unwrap();
+        let no_project = read_entries(&temp_dir, packs_dir, None, &[], None).unwrap();
+
+        // Clean up
+        fs::remove_dir_all(&temp_dir).ok();
+
+        let actions = |entries: &[ActivityEntry]| -> Vec<String> {
+            entries.iter().map(|e| e.action.clone()).collect()
+        };
+        assert_eq!(actions(&survival), vec!["quarantinePack", "setOverride"]);
+        assert_eq!(actions(&no_project), vec!["quarantinePack"]);
+    }
+}
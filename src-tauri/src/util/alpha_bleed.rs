@@ -0,0 +1,197 @@
+/// Alpha bleeding fix for built textures
+///
+/// Fully transparent pixels still carry a color, usually black or white.
+/// When the game averages pixels into smaller mipmap levels, that hidden
+/// color bleeds into the edges of cutout textures (leaves, flowers), giving
+/// them dark or light halos at a distance. This fills every transparent pixel
+/// with the color of its nearest visible neighbors, leaving alpha unchanged.
+use crate::util::pack_health::is_atlas_texture;
+use crate::util::{pack_scanner, parallelism};
+use anyhow::{Context, Result};
+use image::RgbaImage;
+use rayon::prelude::*;
+use std::path::Path;
+
+/// Indices of the up to 8 pixels around a pixel
+fn neighbors(index: usize, width: usize, height: usize) -> impl Iterator<Item = usize> {
+    let (x, y) = (index % width, index / width);
+    let xs = x.saturating_sub(1)..=(x + 1).min(width - 1);
+    (y.saturating_sub(1)..=(y + 1).min(height - 1))
+        .flat_map(move |ny| xs.clone().map(move |nx| ny * width + nx))
+        .filter(move |&neighbor| neighbor != index)
+}
+
+/// Spread visible colors into the fully transparent pixels of an image
+///
+/// Transparent pixels are filled in rings outward from the visible ones, each
+/// taking the average color of its already filled neighbors. Every pixel is
+/// queued once, so the work grows linearly with the texture size.
+///
+/// # Returns
+/// True if any pixel color changed
+pub fn fix_alpha_bleed(image: &mut RgbaImage) -> bool {
+    let (width, height) = (image.width() as usize, image.height() as usize);
+    let mut filled: Vec<bool> = image.pixels().map(|p| p[3] > 0).collect();
+    if filled.iter().all(|f| *f) || !filled.iter().any(|f| *f) {
+        return false;
+    }
+
+    // First ring: transparent pixels bordering visible ones
+    let mut queued = filled.clone();
+    let mut ring: Vec<usize> = Vec::new();
+    for index in 0..filled.len() {
+        if !filled[index] && neighbors(index, width, height).any(|n| filled[n]) {
+            queued[index] = true;
+            ring.push(index);
+        }
+    }
+
+    let mut changed = false;
+    while !ring.is_empty() {
+        // Colors come only from earlier rings, so a ring is read before it is written
+        let colors: Vec<[u8; 3]> = ring
+            .iter()
+            .map(|&index| {
+                let mut sum = [0u32; 3];
+                let mut count = 0;
+                for neighbor in neighbors(index, width, height).filter(|&n| filled[n]) {
+                    let pixel =
+                        image.get_pixel((neighbor % width) as u32, (neighbor / width) as u32);
+                    for (total, channel) in sum.iter_mut().zip(pixel.0.iter()) {
+                        *total += *channel as u32;
+                    }
+                    count += 1;
+                }
+                sum.map(|total| (total / count) as u8)
+            })
+            .collect();
+
+        let mut next = Vec::new();
+        for (&index, [r, g, b]) in ring.iter().zip(colors) {
+            let pixel = image.get_pixel_mut((index % width) as u32, (index / width) as u32);
+            if pixel[0] != r || pixel[1] != g || pixel[2] != b {
+                *pixel = image::Rgba([r, g, b, pixel[3]]);
+                changed = true;
+            }
+            filled[index] = true;
+            for neighbor in neighbors(index, width, height) {
+                if !queued[neighbor] {
+                    queued[neighbor] = true;
+                    next.push(neighbor);
+                }
+            }
+        }
+        ring = next;
+    }
+
+    changed
+}
+
+/// Fix alpha bleeding in every atlas texture of a built output pack
+///
+/// # Returns
+/// Number of textures rewritten
+pub fn fix_output_alpha_bleed(output_dir: &Path) -> Result<usize> {
+    let textures: Vec<String> = pack_scanner::list_folder_files(output_dir)
+        .into_iter()
+        .filter(|f| is_atlas_texture(f))
+        .collect();
+
+    let results: Vec<Result<bool>> = parallelism::with_cpu_pool(|| {
+        textures
+            .par_iter()
+            .map(|file| {
+                let path = output_dir.join(file);
+                let mut image = image::open(&path)
+                    .with_context(|| format!("Failed to read {}", file))?
+                    .to_rgba8();
+                if !fix_alpha_bleed(&mut image) {
+                    return Ok(false);
+                }
+                image
+                    .save(&path)
+                    .with_context(|| format!("Failed to write {}", file))?;
+                Ok(true)
+            })
+            .collect()
+    });
+
+    let mut fixed = 0;
+    for result in results {
+        if result? {
+            fixed += 1;
+        }
+    }
+    Ok(fixed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use image::Rgba;
+    use std::fs;
+
+    #[test]
+    fn test_fix_alpha_bleed_fills_transparent_pixels() {
+        let mut image = RgbaImage::from_pixel(3, 1, Rgba([0, 0, 0, 0]));
+        image.put_pixel(0, 0, Rgba([40, 200, 40, 255]));
+
+        let changed = fix_alpha_bleed(&mut image);
+
+        assert!(changed);
+        assert_eq!(image.get_pixel(1, 0), &Rgba([40, 200, 40, 0]));
+        assert_eq!(image.get_pixel(2, 0), &Rgba([40, 200, 40, 0]));
+        assert_eq!(image.get_pixel(0, 0), &Rgba([40, 200, 40, 255]));
+    }
+
+    #[test]
+    fn test_fix_alpha_bleed_averages_nearest_ring() {
+        let mut image = RgbaImage::new(4, 1);
+        image.put_pixel(0, 0, Rgba([100, 0, 0, 255]));
+        image.put_pixel(3, 0, Rgba([0, 0, 200, 255]));
+        let mut large = RgbaImage::new(256, 256);
+        large.put_pixel(0, 0, Rgba([7, 8, 9, 255]));
+
+        fix_alpha_bleed(&mut image);
+        fix_alpha_bleed(&mut large);
+
+        // Each gap pixel borders one visible pixel, never the other ring member
+        assert_eq!(image.get_pixel(1, 0), &Rgba([100, 0, 0, 0]));
+        assert_eq!(image.get_pixel(2, 0), &Rgba([0, 0, 200, 0]));
+        assert_eq!(large.get_pixel(255, 255), &Rgba([7, 8, 9, 0]));
+    }
+
+    #[test]
+    fn test_fix_alpha_bleed_skips_opaque_and_empty() {
+        let mut opaque = RgbaImage::from_pixel(2, 2, Rgba([1, 2, 3, 255]));
+        let mut empty = RgbaImage::new(2, 2);
+
+        assert!(!fix_alpha_bleed(&mut opaque));
+        assert!(!fix_alpha_bleed(&mut empty));
+    }
+
+    #[test]
+    fn test_fix_output_alpha_bleed() {
+        let temp_dir = std::env::temp_dir().join("test_alpha_bleed");
+        let blocks = temp_dir.join("assets/minecraft/textures/block");
+        fs::create_dir_all(&blocks).expect("Failed to create test directory");
+        let mut leaves = RgbaImage::new(2, 2);
+        leaves.put_pixel(0, 0, Rgba([30, 120, 30, 255]));
+        leaves
+            .save(blocks.join("oak_leaves.png"))
+            .expect("Failed to write image");
+        RgbaImage::from_pixel(2, 2, Rgba([9, 9, 9, 255]))
+            .save(blocks.join("stone.png"))
+            .expect("Failed to write image");
+
+        let fixed = fix_output_alpha_bleed(&temp_dir);
+        let leaves = image::open(blocks.join("oak_leaves.png")).map(|i| i.to_rgba8());
+
+        // Clean up
+        fs::remove_dir_all(&temp_dir).ok();
+
+        assert_eq!(fixed.expect("fix should succeed"), 1);
+        let leaves = leaves.expect("Failed to reopen image");
+        assert_eq!(leaves.get_pixel(1, 1), &Rgba([30, 120, 30, 0]));
+    }
+}
@@ -0,0 +1,509 @@
+/// Animated texture metadata
+///
+/// Parses `.png.mcmeta` animation sections into a frame-accurate timeline
+/// (frame order, per-frame durations in ms, frame rectangles, interpolation)
+/// so the frontend and exporters don't have to reimplement the mcmeta spec.
+use crate::model::{PackMeta, TextureAnimation};
+use crate::util::image_probe;
+use anyhow::{anyhow, Context, Result};
+use image::RgbaImage;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::Path;
+use tracing::warn;
+
+/// Duration of one game tick in milliseconds
+pub const TICK_MS: u32 = 50;
+
+/// Raw `.mcmeta` file contents
+#[derive(Debug, Deserialize)]
+struct McmetaFile {
+    animation: Option<AnimationSection>,
+}
+
+/// Raw `animation` section of a `.mcmeta` file
+#[derive(Debug, Deserialize)]
+struct AnimationSection {
+    frametime: Option<u32>,
+    interpolate: Option<bool>,
+    width: Option<u32>,
+    height: Option<u32>,
+    frames: Option<Vec<FrameSpec>>,
+}
+
+/// A frame entry: either a bare index or an index with a custom time
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum FrameSpec {
+    Index(u32),
+    Detailed { index: u32, time: Option<u32> },
+}
+
+impl FrameSpec {
+    fn index(&self) -> u32 {
+        match self {
+            FrameSpec::Index(index) => *index,
+            FrameSpec::Detailed { index, .. } => *index,
+        }
+    }
+}
+
+/// One step of the animation timeline
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AnimationFrame {
+    /// Index of the frame within the texture
+    pub index: u32,
+    /// How long the frame is shown, in milliseconds
+    pub duration_ms: u32,
+    /// Left edge of the frame in the texture, in pixels
+    pub x: u32,
+    /// Top edge of the frame in the texture, in pixels
+    pub y: u32,
+}
+
+/// Fully resolved animation timeline for a texture
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AnimationInfo {
+    pub frame_width: u32,
+    pub frame_height: u32,
+    /// Number of frames present in the texture image
+    pub frame_count: u32,
+    /// Whether to blend between consecutive frames
+    pub interpolate: bool,
+    /// Playback order with per-frame durations
+    pub frames: Vec<AnimationFrame>,
+    pub total_duration_ms: u32,
+}
+
+/// Resolve an animation timeline from `.mcmeta` JSON and the texture size
+///
+/// # Returns
+/// None if the mcmeta has no animation section
+pub fn parse_animation(
+    mcmeta_json: &str,
+    image_width: u32,
+    image_height: u32,
+) -> Result<Option<AnimationInfo>> {
+    let mcmeta: McmetaFile = serde_json::from_str(mcmeta_json).context("Invalid mcmeta JSON")?;
+    let animation = match mcmeta.animation {
+        Some(animation) => animation,
+        None => return Ok(None),
+    };
+
+    // Frames default to squares the size of the texture's shorter side
+    let default_size = image_width.min(image_height);
+    let (frame_width, frame_height) = match (animation.width, animation.height) {
+        (Some(w), Some(h)) => (w, h),
+        (Some(w), None) => (w, image_height),
+        (None, Some(h)) => (image_width, h),
+        (None, None) => (default_size, default_size),
+    };
+
+    if frame_width == 0 || frame_height == 0 {
+        return Err(anyhow!("Animation frame size must be non-zero"));
+    }
+
+    let columns = image_width / frame_width;
+    let rows = image_height / frame_height;
+    let frame_count = columns * rows;
+    if frame_count == 0 {
+        return Err(anyhow!(
+            "Frame size {}x{} doesn't fit in a {}x{} texture",
+            frame_width,
+            frame_height,
+            image_width,
+            image_height
+        ));
+    }
+
+    // Minecraft treats a frametime below one tick as one tick
+    let default_ticks = animation.frametime.unwrap_or(1).max(1);
+
+    let order: Vec<(u32, u32)> = match animation.frames {
+        Some(specs) => specs
+            .into_iter()
+            .map(|spec| match spec {
+                FrameSpec::Index(index) => (index, default_ticks),
+                FrameSpec::Detailed { index, time } => {
+                    (index, time.unwrap_or(default_ticks).max(1))
+                }
+            })
+            // Out-of-range frames are skipped, as in game
+            .filter(|(index, _)| *index < frame_count)
+            .collect(),
+        None => (0..frame_count).map(|i| (i, default_ticks)).collect(),
+    };
+
+    let frames: Vec<AnimationFrame> = order
+        .into_iter()
+        .map(|(index, ticks)| AnimationFrame {
+            index,
+            duration_ms: ticks * TICK_MS,
+            x: (index % columns) * frame_width,
+            y: (index / columns) * frame_height,
+        })
+        .collect();
+
+    let total_duration_ms = frames.iter().map(|f| f.duration_ms).sum();
+
+    Ok(Some(AnimationInfo {
+        frame_width,
+        frame_height,
+        frame_count,
+        interpolate: animation.interpolate.unwrap_or(false),
+        frames,
+        total_duration_ms,
+    }))
+}
+
+/// Read the animation section of `.mcmeta` JSON as declared
+///
+/// Unlike `parse_animation`, this needs no image, so the indexer can attach
+/// it to assets without decoding textures.
+///
+/// # Returns
+/// None if the mcmeta has no animation section
+pub fn parse_texture_animation(mcmeta_json: &str) -> Result<Option<TextureAnimation>> {
+    let mcmeta: McmetaFile = serde_json::from_str(mcmeta_json).context("Invalid mcmeta JSON")?;
+    Ok(mcmeta.animation.map(|animation| TextureAnimation {
+        frametime: animation.frametime.unwrap_or(1).max(1),
+        interpolate: animation.interpolate.unwrap_or(false),
+        width: animation.width,
+        height: animation.height,
+        frames: animation
+            .frames
+            .map(|specs| specs.iter().map(FrameSpec::index).collect()),
+    }))
+}
+
+/// Animations declared by a pack's textures, keyed by texture asset ID
+///
+/// The indexer files "water_still.png.mcmeta" under the asset
+/// "minecraft:block/water_still.png"; its animation belongs to
+/// "minecraft:block/water_still". Unreadable or invalid mcmeta files are
+/// skipped.
+///
+/// # Arguments
+/// * `pack` - Pack the listing was made from
+/// * `assets` - Asset ID -> files, as listed by the indexer
+pub fn pack_animations(
+    pack: &PackMeta,
+    assets: &HashMap<String, Vec<String>>,
+) -> HashMap<String, TextureAnimation> {
+    let mut animations = HashMap::new();
+
+    for (asset_id, files) in assets {
+        let texture_id = match asset_id.strip_suffix(".png") {
+            Some(texture_id) => texture_id,
+            None => continue,
+        };
+        for file in files.iter().filter(|f| f.ends_with(".png.mcmeta")) {
+            let parsed = read_pack_bytes(&pack.path, pack.is_zip, file)
+                .and_then(|bytes| parse_texture_animation(&String::from_utf8_lossy(&bytes)));
+            match parsed {
+                Ok(Some(animation)) => {
+                    animations.insert(texture_id.to_string(), animation);
+                }
+                Ok(None) => {}
+                Err(e) => warn!("Skipping {} in {}: {}", file, pack.name, e),
+            }
+        }
+    }
+
+    animations
+}
+
+/// Convert a texture asset ID to its path within a pack
+///
+/// Example: "minecraft:block/water_still" -> "assets/minecraft/textures/block/water_still.png"
+pub fn texture_path_for_asset(asset_id: &str) -> String {
+    let (namespace, path) = asset_id.split_once(':').unwrap_or(("minecraft", asset_id));
+    format!("assets/{}/textures/{}.png", namespace, path)
+}
+
+/// Decode a texture, keeping only the first frame of animated strips
+pub fn decode_first_frame(bytes: &[u8]) -> Result<RgbaImage> {
+    let image = image::load_from_memory(bytes)?.to_rgba8();
+    let (width, height) = image.dimensions();
+    // Animated textures are vertical strips of square frames
+    if height > width {
+        Ok(image::imageops::crop_imm(&image, 0, 0, width, width).to_image())
+    } else {
+        Ok(image)
+    }
+}
+
+/// Read a file from a pack directory or ZIP
+pub fn read_pack_bytes(pack_path: &str, is_zip: bool, relative_path: &str) -> Result<Vec<u8>> {
+    if is_zip {
+        crate::util::zip::extract_zip_entry(pack_path, relative_path)
+    } else {
+        let full_path = crate::util::zip::safe_join(Path::new(pack_path), relative_path)?;
+        std::fs::read(&full_path).with_context(|| format!("Failed to read {:?}", full_path))
+    }
+}
+
+/// Read many files from a pack directory or ZIP, opening a ZIP only once
+///
+/// Files that can't be read are left out of the result.
+///
+/// # Returns
+/// Relative path -> contents
+pub fn read_pack_files(
+    pack_path: &str,
+    is_zip: bool,
+    relative_paths: &[String],
+) -> Result<HashMap<String, Vec<u8>>> {
+    if is_zip {
+        return crate::util::zip::extract_zip_entries(pack_path, relative_paths);
+    }
+    Ok(relative_paths
+        .iter()
+        .filter_map(|path| {
+            let bytes = read_pack_bytes(pack_path, false, path).ok()?;
+            Some((path.clone(), bytes))
+        })
+        .collect())
+}
+
+/// Load the animation timeline of a texture in a pack
+///
+/// # Returns
+/// None if the texture isn't animated
+pub fn load_animation_info(
+    pack_path: &str,
+    is_zip: bool,
+    asset_id: &str,
+) -> Result<Option<AnimationInfo>> {
+    let texture_path = texture_path_for_asset(asset_id);
+    let mcmeta = match read_pack_bytes(pack_path, is_zip, &format!("{}.mcmeta", texture_path)) {
+        Ok(bytes) => bytes,
+        Err(_) => return Ok(None),
+    };
+
+    let (width, height) = image_probe::pack_file_dimensions(pack_path, is_zip, &texture_path)?;
+
+    parse_animation(&String::from_utf8_lossy(&mcmeta), width, height)
+}
+
+/// Timeline of an animated texture with every frame as its own image
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AnimationFrames {
+    /// Playback order and timing; each frame's `index` points into `images`
+    pub info: AnimationInfo,
+    /// Base64 PNG of each frame in the texture, by frame index
+    pub images: Vec<String>,
+}
+
+/// Split an animated texture into frame images for previews
+///
+/// # Returns
+/// None if the texture isn't animated
+pub fn load_animation_frames(
+    pack_path: &str,
+    is_zip: bool,
+    asset_id: &str,
+) -> Result<Option<AnimationFrames>> {
+    use base64::{engine::general_purpose, Engine as _};
+
+    let texture_path = texture_path_for_asset(asset_id);
+    let mcmeta = match read_pack_bytes(pack_path, is_zip, &format!("{}.mcmeta", texture_path)) {
+        Ok(bytes) => bytes,
+        Err(_) => return Ok(None),
+    };
+
+    let png = read_pack_bytes(pack_path, is_zip, &texture_path)?;
+    let texture = image::load_from_memory(&png)
+        .context("Failed to decode texture")?
+        .to_rgba8();
+    let (width, height) = texture.dimensions();
+    let info = match parse_animation(&String::from_utf8_lossy(&mcmeta), width, height)? {
+        Some(info) => info,
+        None => return Ok(None),
+    };
+
+    let columns = width / info.frame_width;
+    let images = (0..info.frame_count)
+        .map(|index| {
+            let frame = image::imageops::crop_imm(
+                &texture,
+                (index % columns) * info.frame_width,
+                (index / columns) * info.frame_height,
+                info.frame_width,
+                info.frame_height,
+            )
+            .to_image();
+            let mut buffer = Vec::new();
+            frame
+                .write_to(
+                    &mut std::io::Cursor::new(&mut buffer),
+                    image::ImageOutputFormat::Png,
+                )
+                .context("Failed to encode frame")?;
+            Ok(general_purpose::STANDARD.encode(&buffer))
+        })
+        .collect::<Result<Vec<String>>>()?;
+
+    Ok(Some(AnimationFrames { info, images }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    #[test]
+    fn test_parse_animation_defaults() {
+        let info = parse_animation(r#"{"animation": {}}"#, 16, 64)
+            .unwrap()
+            .expect("should be animated");
+
+        assert_eq!((info.frame_width, info.frame_height), (16, 16));
+        assert_eq!(info.frame_count, 4);
+        assert!(!info.interpolate);
+        assert_eq!(info.frames.len(), 4);
+        assert_eq!(info.frames[2].y, 32);
+        assert_eq!(info.frames[2].duration_ms, 50);
+        assert_eq!(info.total_duration_ms, 200);
+    }
+
+    #[test]
+    fn test_parse_animation_custom_frames() {
+        let json = r#"{
+            "animation": {
+                "frametime": 2,
+                "interpolate": true,
+                "frames": [3, {"index": 0, "time": 10}, 7, {"index": 1}]
+            }
+        }"#;
+        let info = parse_animation(json, 16, 64).unwrap().unwrap();
+
+        assert!(info.interpolate);
+        // Frame 7 is out of range and skipped
+        let order: Vec<(u32, u32)> = info
+            .frames
+            .iter()
+            .map(|f| (f.index, f.duration_ms))
+            .collect();
+        assert_eq!(order, vec![(3, 100), (0, 500), (1, 100)]);
+        assert_eq!(info.total_duration_ms, 700);
+    }
+
+    #[test]
+    fn test_parse_animation_non_square_frames() {
+        let json = r#"{"animation": {"width": 16, "height": 8}}"#;
+        let info = parse_animation(json, 32, 16).unwrap().unwrap();
+
+        assert_eq!(info.frame_count, 4);
+        assert_eq!((info.frames[1].x, info.frames[1].y), (16, 0));
+        assert_eq!((info.frames[2].x, info.frames[2].y), (0, 8));
+    }
+
+    #[test]
+    fn test_parse_animation_without_section() {
+        assert!(parse_animation(r#"{"villager": {}}"#, 16, 16)
+            .unwrap()
+            .is_none());
+        assert!(parse_animation("not json", 16, 16).is_err());
+    }
+
+    #[test]
+    fn test_parse_texture_animation() {
+        let json = r#"{"animation": {"frametime": 0, "width": 16, "frames": [2, {"index": 0, "time": 4}]}}"#;
+        let animation = parse_texture_animation(json).unwrap().unwrap();
+
+        // A frametime below one tick counts as one tick
+        assert_eq!(animation.frametime, 1);
+        assert!(!animation.interpolate);
+        assert_eq!((animation.width, animation.height), (Some(16), None));
+        assert_eq!(animation.frames, Some(vec![2, 0]));
+        assert!(parse_texture_animation(r#"{"villager": {}}"#)
+            .unwrap()
+            .is_none());
+    }
+
+    #[test]
+    fn test_pack_animations_and_frames() {
+        use base64::{engine::general_purpose, Engine as _};
+
+        let temp_dir = std::env::temp_dir().join("test_animation_frames");
+        fs::remove_dir_all(&temp_dir).ok();
+        let textures = temp_dir.join("assets/minecraft/textures/block");
+        fs::create_dir_all(&textures).unwrap();
+        // Two 2x2 frames stacked: red on top, blue below
+        let mut strip = RgbaImage::new(2, 4);
+        for (_, y, pixel) in strip.enumerate_pixels_mut() {
+            *pixel = if y < 2 {
+                image::Rgba([255, 0, 0, 255])
+            } else {
+                image::Rgba([0, 0, 255, 255])
+            };
+        }
+        strip.save(textures.join("lava.png")).unwrap();
+        fs::write(
+            textures.join("lava.png.mcmeta"),
+            r#"{"animation": {"frametime": 3, "frames": [1, 0]}}"#,
+        )
+        .unwrap();
+        fs::write(textures.join("broken.png.mcmeta"), "{").unwrap();
+        let pack = PackMeta {
+            id: "pack".to_string(),
+            name: "pack".to_string(),
+            path: temp_dir.to_string_lossy().to_string(),
+            ..Default::default()
+        };
+        let assets = HashMap::from([
+            (
+                "minecraft:block/lava".to_string(),
+                vec!["assets/minecraft/textures/block/lava.png".to_string()],
+            ),
+            (
+                "minecraft:block/lava.png".to_string(),
+                vec!["assets/minecraft/textures/block/lava.png.mcmeta".to_string()],
+            ),
+            (
+                "minecraft:block/broken.png".to_string(),
+                vec!["assets/minecraft/textures/block/broken.png.mcmeta".to_string()],
+            ),
+        ]);
+
+        let animations = pack_animations(&pack, &assets);
+        let frames = load_animation_frames(&pack.path, false, "minecraft:block/lava").unwrap();
+        let still = load_animation_frames(&pack.path, false, "minecraft:block/stone").unwrap();
+
+        // Clean up
+        fs::remove_dir_all(&temp_dir).ok();
+
+        assert_eq!(animations.len(), 1);
+        assert_eq!(animations["minecraft:block/lava"].frames, Some(vec![1, 0]));
+        let frames = frames.expect("lava is animated");
+        assert_eq!(frames.images.len(), 2);
+        let order: Vec<(u32, u32)> = frames
+            .info
+            .frames
+            .iter()
+            .map(|f| (f.index, f.duration_ms))
+            .collect();
+        assert_eq!(order, vec![(1, 150), (0, 150)]);
+        let second = general_purpose::STANDARD.decode(&frames.images[1]).unwrap();
+        let second = image::load_from_memory(&second).unwrap().to_rgba8();
+        assert_eq!(second.dimensions(), (2, 2));
+        assert_eq!(second.get_pixel(0, 0), &image::Rgba([0, 0, 255, 255]));
+        assert!(still.is_none());
+    }
+
+    #[test]
+    fn test_texture_path_for_asset() {
+        assert_eq!(
+            texture_path_for_asset("minecraft:block/water_still"),
+            "assets/minecraft/textures/block/water_still.png"
+        );
+        assert_eq!(
+            texture_path_for_asset("block/lava_still"),
+            "assets/minecraft/textures/block/lava_still.png"
+        );
+    }
+}
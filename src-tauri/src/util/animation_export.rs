@@ -0,0 +1,353 @@
+/// Animated texture export
+///
+/// Renders an animated texture's timeline (frame order, frametime and
+/// interpolation) to a looping GIF or animated WebP, scaled with
+/// nearest-neighbor so pixel art stays crisp when shared outside the app.
+use crate::util::animation::{self, AnimationInfo, TICK_MS};
+use anyhow::{anyhow, Context, Result};
+use image::codecs::gif::{GifEncoder, Repeat};
+use image::imageops::{self, FilterType};
+use image::{Delay, Frame, RgbaImage};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::Path;
+use tracing::info;
+
+/// Largest output width accepted for exports, in pixels
+pub const MAX_EXPORT_SIZE: u32 = 2048;
+
+/// Output file format for animation exports
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ExportFormat {
+    Gif,
+    Webp,
+}
+
+/// A single rendered output frame
+#[derive(Debug, Clone)]
+pub struct RenderedFrame {
+    pub image: RgbaImage,
+    pub duration_ms: u32,
+}
+
+/// Summary of a finished export
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AnimationExport {
+    pub output_path: String,
+    pub width: u32,
+    pub height: u32,
+    /// Number of frames written (interpolated textures get one per tick)
+    pub frame_count: usize,
+    pub total_duration_ms: u32,
+}
+
+/// Render the animation timeline into output frames
+///
+/// Interpolated animations are expanded into one frame per game tick,
+/// blending toward the next frame the way the game does.
+///
+/// # Arguments
+/// * `texture` - The full texture image containing every frame
+/// * `info` - Resolved animation timeline
+/// * `size` - Output width in pixels; height keeps the frame's aspect ratio
+pub fn render_frames(
+    texture: &RgbaImage,
+    info: &AnimationInfo,
+    size: u32,
+) -> Result<Vec<RenderedFrame>> {
+    if size == 0 || size > MAX_EXPORT_SIZE {
+        return Err(anyhow!(
+            "Export size must be between 1 and {} pixels",
+            MAX_EXPORT_SIZE
+        ));
+    }
+    if info.frames.is_empty() {
+        return Err(anyhow!("Animation has no frames"));
+    }
+
+    let out_width = size;
+    let out_height =
+        ((info.frame_height as u64 * size as u64) / info.frame_width as u64).max(1) as u32;
+
+    let sources: Vec<RgbaImage> = info
+        .frames
+        .iter()
+        .map(|frame| {
+            if frame.x + info.frame_width > texture.width()
+                || frame.y + info.frame_height > texture.height()
+            {
+                return Err(anyhow!("Frame {} lies outside the texture", frame.index));
+            }
+            Ok(imageops::crop_imm(
+                texture,
+                frame.x,
+                frame.y,
+                info.frame_width,
+                info.frame_height,
+            )
+            .to_image())
+        })
+        .collect::<Result<_>>()?;
+
+    let mut rendered = Vec::new();
+    for (i, frame) in info.frames.iter().enumerate() {
+        let current = &sources[i];
+
+        if info.interpolate && info.frames.len() > 1 {
+            let next = &sources[(i + 1) % sources.len()];
+            let ticks = (frame.duration_ms / TICK_MS).max(1);
+            for tick in 0..ticks {
+                let blended = blend_frames(current, next, tick as f32 / ticks as f32);
+                rendered.push(RenderedFrame {
+                    image: scale(&blended, out_width, out_height),
+                    duration_ms: TICK_MS,
+                });
+            }
+        } else {
+            rendered.push(RenderedFrame {
+                image: scale(current, out_width, out_height),
+                duration_ms: frame.duration_ms,
+            });
+        }
+    }
+
+    Ok(rendered)
+}
+
+/// Blend the color of two frames, keeping the alpha of the current one
+fn blend_frames(current: &RgbaImage, next: &RgbaImage, t: f32) -> RgbaImage {
+    let mut out = current.clone();
+    for (dst, src) in out.pixels_mut().zip(next.pixels()) {
+        for c in 0..3 {
+            let a = dst[c] as f32;
+            let b = src[c] as f32;
+            dst[c] = (a + (b - a) * t).round() as u8;
+        }
+    }
+    out
+}
+
+fn scale(image: &RgbaImage, width: u32, height: u32) -> RgbaImage {
+    if image.dimensions() == (width, height) {
+        image.clone()
+    } else {
+        imageops::resize(image, width, height, FilterType::Nearest)
+    }
+}
+
+/// Write frames as an infinitely looping GIF
+pub fn encode_gif(frames: &[RenderedFrame], output_path: &Path) -> Result<()> {
+    let file = fs::File::create(output_path)
+        .with_context(|| format!("Failed to create {:?}", output_path))?;
+    let mut encoder = GifEncoder::new(file);
+    encoder.set_repeat(Repeat::Infinite)?;
+
+    encoder
+        .encode_frames(frames.iter().map(|frame| {
+            Frame::from_parts(
+                frame.image.clone(),
+                0,
+                0,
+                Delay::from_numer_denom_ms(frame.duration_ms, 1),
+            )
+        }))
+        .context("Failed to encode GIF")
+}
+
+/// Write frames as an infinitely looping lossless animated WebP
+pub fn encode_webp(frames: &[RenderedFrame], output_path: &Path) -> Result<()> {
+    let (width, height) = frames
+        .first()
+        .map(|frame| frame.image.dimensions())
+        .ok_or_else(|| anyhow!("No frames to encode"))?;
+
+    let mut config = webp::WebPConfig::new().map_err(|_| anyhow!("Failed to init WebP config"))?;
+    config.lossless = 1;
+
+    let mut encoder = webp::AnimEncoder::new(width, height, &config);
+    encoder.set_loop_count(0);
+
+    let mut timestamp_ms: i32 = 0;
+    for frame in frames {
+        encoder.add_frame(webp::AnimFrame::from_rgba(
+            frame.image.as_raw(),
+            width,
+            height,
+            timestamp_ms,
+        ));
+        timestamp_ms += frame.duration_ms as i32;
+    }
+
+    let data = encoder
+        .try_encode()
+        .map_err(|e| anyhow!("Failed to encode WebP: {:?}", e))?;
+    fs::write(output_path, &*data).with_context(|| format!("Failed to write {:?}", output_path))
+}
+
+/// Export an animated texture from a pack
+///
+/// # Arguments
+/// * `pack_path` - Base path to the resource pack
+/// * `is_zip` - Whether the pack is a ZIP file
+/// * `asset_id` - Texture asset ID (e.g., "minecraft:block/water_still")
+/// * `format` - Output format
+/// * `size` - Output width in pixels
+/// * `output_path` - File to write
+pub fn export_animation(
+    pack_path: &str,
+    is_zip: bool,
+    asset_id: &str,
+    format: ExportFormat,
+    size: u32,
+    output_path: &Path,
+) -> Result<AnimationExport> {
+    let info = animation::load_animation_info(pack_path, is_zip, asset_id)?
+        .ok_or_else(|| anyhow!("Texture {} is not animated", asset_id))?;
+
+    let png = animation::read_pack_bytes(
+        pack_path,
+        is_zip,
+        &animation::texture_path_for_asset(asset_id),
+    )?;
+    let texture = image::load_from_memory(&png)
+        .context("Failed to decode texture")?
+        .to_rgba8();
+
+    let frames = render_frames(&texture, &info, size)?;
+
+    if let Some(parent) = output_path.parent() {
+        fs::create_dir_all(parent).context("Failed to create output directory")?;
+    }
+    match format {
+        ExportFormat::Gif => encode_gif(&frames, output_path)?,
+        ExportFormat::Webp => encode_webp(&frames, output_path)?,
+    }
+
+    let (width, height) = frames[0].image.dimensions();
+    info!(
+        "Wrote {} frames ({}x{}) to {:?}",
+        frames.len(),
+        width,
+        height,
+        output_path
+    );
+
+    Ok(AnimationExport {
+        output_path: output_path.to_string_lossy().to_string(),
+        width,
+        height,
+        frame_count: frames.len(),
+        total_duration_ms: frames.iter().map(|f| f.duration_ms).sum(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use image::Rgba;
+
+    /// Two 2x2 frames stacked vertically: black then white
+    fn two_frame_texture() -> RgbaImage {
+        RgbaImage::from_fn(2, 4, |_, y| {
+            if y < 2 {
+                Rgba([0, 0, 0, 255])
+            } else {
+                Rgba([255, 255, 255, 255])
+            }
+        })
+    }
+
+    #[test]
+    fn test_render_frames_scales_with_nearest_neighbor() {
+        let info = animation::parse_animation(r#"{"animation": {"frametime": 4}}"#, 2, 4)
+            .unwrap()
+            .unwrap();
+        let frames = render_frames(&two_frame_texture(), &info, 8).unwrap();
+
+        assert_eq!(frames.len(), 2);
+        assert_eq!(frames[0].image.dimensions(), (8, 8));
+        assert_eq!(frames[0].duration_ms, 200);
+        assert_eq!(frames[0].image.get_pixel(7, 7), &Rgba([0, 0, 0, 255]));
+        assert_eq!(frames[1].image.get_pixel(0, 0), &Rgba([255, 255, 255, 255]));
+    }
+
+    #[test]
+    fn test_render_frames_interpolates_per_tick() {
+        let info = animation::parse_animation(
+            r#"{"animation": {"frametime": 2, "interpolate": true}}"#,
+            2,
+            4,
+        )
+        .unwrap()
+        .unwrap();
+        let frames = render_frames(&two_frame_texture(), &info, 2).unwrap();
+
+        assert_eq!(frames.len(), 4);
+        assert!(frames.iter().all(|f| f.duration_ms == TICK_MS));
+        assert_eq!(frames[0].image.get_pixel(0, 0), &Rgba([0, 0, 0, 255]));
+        assert_eq!(frames[1].image.get_pixel(0, 0), &Rgba([128, 128, 128, 255]));
+        assert_eq!(frames[3].image.get_pixel(0, 0), &Rgba([128, 128, 128, 255]));
+    }
+
+    #[test]
+    fn test_render_frames_rejects_bad_size() {
+        let info = animation::parse_animation(r#"{"animation": {}}"#, 2, 4)
+            .unwrap()
+            .unwrap();
+
+        assert!(render_frames(&two_frame_texture(), &info, 0).is_err());
+        assert!(render_frames(&two_frame_texture(), &info, MAX_EXPORT_SIZE + 1).is_err());
+    }
+
+    #[test]
+    fn test_export_animation_gif_and_webp() {
+        let temp_dir = std::env::temp_dir().join("test_animation_export");
+        let textures = temp_dir.join("pack/assets/minecraft/textures/block");
+        fs::create_dir_all(&textures).expect("Failed to create test directory");
+        two_frame_texture()
+            .save(textures.join("blink.png"))
+            .expect("Failed to write texture");
+        fs::write(
+            textures.join("blink.png.mcmeta"),
+            r#"{"animation": {"frametime": 5}}"#,
+        )
+        .expect("Failed to write mcmeta");
+
+        let pack = temp_dir.join("pack").to_string_lossy().to_string();
+        let gif_path = temp_dir.join("out/blink.gif");
+        let webp_path = temp_dir.join("out/blink.webp");
+        let gif = export_animation(
+            &pack,
+            false,
+            "minecraft:block/blink",
+            ExportFormat::Gif,
+            16,
+            &gif_path,
+        );
+        let webp = export_animation(
+            &pack,
+            false,
+            "minecraft:block/blink",
+            ExportFormat::Webp,
+            16,
+            &webp_path,
+        );
+        let gif_bytes = fs::read(&gif_path).unwrap_or_default();
+        let webp_bytes = fs::read(&webp_path).unwrap_or_default();
+
+        // Clean up
+        fs::remove_dir_all(&temp_dir).ok();
+
+        let gif = gif.expect("GIF export should succeed");
+        assert_eq!((gif.width, gif.height, gif.frame_count), (16, 16, 2));
+        assert_eq!(gif.total_duration_ms, 500);
+        assert!(gif_bytes.starts_with(b"GIF89a"));
+
+        let webp = webp.expect("WebP export should succeed");
+        assert_eq!(webp.frame_count, 2);
+        assert_eq!(&webp_bytes[0..4], b"RIFF");
+        assert_eq!(&webp_bytes[8..12], b"WEBP");
+    }
+}
@@ -1,62 +1,286 @@
 /// Index assets from resource packs (both zip and uncompressed)
 use crate::model::{AssetRecord, PackMeta};
+use crate::util::animation;
+use crate::util::content_hash::{self, AssetHashes, HashTimings};
+use crate::util::entity_map;
+use crate::util::index_cache;
+use crate::util::jobs::{self, CancelToken};
+use crate::util::optifine;
+use crate::util::parallelism;
+use crate::util::progress::FileProgress;
+use crate::util::text_assets;
+use crate::util::vanilla_textures::VANILLA_PACK_ID;
 use crate::util::zip;
 use anyhow::Result;
+use once_cell::sync::Lazy;
 use rayon::prelude::*;
 use std::collections::HashMap;
 use std::path::Path;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Mutex;
+use std::time::{Instant, UNIX_EPOCH};
+use tracing::{debug, info, warn};
 use walkdir::WalkDir;
+use xxhash_rust::xxh3::Xxh3;
 
 const ASSET_PATH_PREFIX: &str = "assets/";
 const TEXTURE_PATH: &str = "textures/";
 const CEM_PATH: &str = "assets/minecraft/optifine/cem/";
+/// CEM directory relative to a namespace
+const CEM_DIR: &str = "optifine/cem/";
+/// Folder macOS adds to zips for resource forks
+const MACOS_METADATA_DIR: &str = "__MACOSX";
+/// Files that make up a custom entity model (models, parts, random-model rules, textures)
+const CEM_EXTENSIONS: &[&str] = &["jem", "jpm", "properties", "png"];
 
 /// Index all assets from a list of packs
 pub fn index_assets(
     packs: &[PackMeta],
 ) -> Result<(Vec<AssetRecord>, HashMap<String, Vec<String>>)> {
-    println!(
-        "[index_assets] Starting PARALLEL asset indexing for {} packs",
-        packs.len()
-    );
+    let (assets, providers, _) = index_packs(packs, false, None, None)?;
+    Ok((assets, providers))
+}
+
+/// Content hashes and other details gathered while indexing
+#[derive(Debug, Clone, Default)]
+pub struct AssetHashIndex {
+    /// Pack ID -> asset hashes
+    pub pack_hashes: HashMap<String, AssetHashes>,
+    pub timings: HashTimings,
+    /// Pack ID -> macOS metadata files left out of the index (packs with none omitted)
+    pub os_metadata_files: HashMap<String, usize>,
+    /// Pack ID -> indexed file paths, so later steps needn't list packs again
+    pub pack_files: HashMap<String, Vec<String>>,
+}
+
+/// Assets, providers (asset_id -> [pack_ids]) and content hashes
+pub type HashedIndex = (
+    Vec<AssetRecord>,
+    HashMap<String, Vec<String>>,
+    AssetHashIndex,
+);
+
+/// Index all assets and hash their contents in the same parallel pass
+pub fn index_assets_with_hashes(packs: &[PackMeta]) -> Result<HashedIndex> {
+    index_packs(packs, true, None, None)
+}
+
+/// Index all assets, keeping each pack's file listing but skipping hashing
+pub fn index_assets_with_files(packs: &[PackMeta]) -> Result<HashedIndex> {
+    index_packs(packs, false, None, None)
+}
+
+/// Like `index_assets_with_hashes`, calling `progress` with (packs indexed,
+/// total, pack name) and stopping between packs once `cancel` is set
+pub fn index_assets_with_hashes_and_progress(
+    packs: &[PackMeta],
+    progress: Option<FileProgress>,
+    cancel: Option<&CancelToken>,
+) -> Result<HashedIndex> {
+    index_packs(packs, true, progress, cancel)
+}
+
+/// Content hashes of folder packs hashed in this session, by pack path,
+/// with the fingerprint of the files they were computed from
+static FOLDER_HASHES: Lazy<Mutex<HashMap<String, (u64, AssetHashes)>>> =
+    Lazy::new(|| Mutex::new(HashMap::new()));
+
+/// Fingerprint of a folder pack's indexed files: their paths, sizes and
+/// modification times
+///
+/// Folder packs aren't in the index cache, so this is what tells a rescan
+/// whether their hashes from earlier in the session still hold. Stat'ing
+/// the files is far cheaper than reading them.
+fn folder_fingerprint(pack: &PackMeta, assets: &HashMap<String, Vec<String>>) -> Option<u64> {
+    let root = Path::new(&pack.path);
+    let mut files: Vec<&String> = assets.values().flatten().collect();
+    files.sort();
+
+    let mut hasher = Xxh3::new();
+    for file in files {
+        let metadata = std::fs::metadata(root.join(file)).ok()?;
+        let modified = metadata.modified().ok()?.duration_since(UNIX_EPOCH).ok()?;
+        hasher.update(file.as_bytes());
+        hasher.update(&metadata.len().to_le_bytes());
+        hasher.update(&modified.as_nanos().to_le_bytes());
+    }
+    Some(hasher.digest())
+}
+
+/// Hashes computed earlier in the session for a folder pack, if its files
+/// haven't changed since
+fn remembered_folder_hashes(pack_path: &str, fingerprint: u64) -> Option<AssetHashes> {
+    let remembered = FOLDER_HASHES.lock().unwrap_or_else(|e| e.into_inner());
+    remembered
+        .get(pack_path)
+        .filter(|(remembered, _)| *remembered == fingerprint)
+        .map(|(_, hashes)| hashes.clone())
+}
+
+fn remember_folder_hashes(pack_path: &str, fingerprint: u64, hashes: &AssetHashes) {
+    FOLDER_HASHES
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .insert(pack_path.to_string(), (fingerprint, hashes.clone()));
+}
+
+/// Index packs, optionally hashing each pack's assets as soon as it is listed
+fn index_packs(
+    packs: &[PackMeta],
+    hash_contents: bool,
+    progress: Option<FileProgress>,
+    cancel: Option<&CancelToken>,
+) -> Result<HashedIndex> {
+    let start = Instant::now();
+    info!("Starting PARALLEL asset indexing for {} packs", packs.len());
+
+    // Listings of unchanged ZIP packs come from the on-disk cache
+    let cache_dir = index_cache::index_cache_dir();
+    let indexed = AtomicUsize::new(0);
+    if let Some(progress) = progress {
+        progress(0, packs.len(), None);
+    }
 
     // Parallelize indexing of individual packs
-    let pack_results: Vec<_> = packs
-        .par_iter()
-        .enumerate()
-        .map(|(i, pack)| {
-            println!(
-                "[index_assets] Indexing pack {}/{}: {} (is_zip: {})",
-                i + 1,
-                packs.len(),
-                pack.name,
-                pack.is_zip
-            );
-            let pack_assets = if pack.is_zip {
-                index_zip_pack(&pack.path, &pack.id)
-            } else {
-                index_folder_pack(&pack.path, &pack.id)
-            };
-
-            match pack_assets {
-                Ok(assets) => {
-                    println!(
-                        "[index_assets] Found {} assets in pack {}",
-                        assets.len(),
-                        pack.name
-                    );
-                    Ok((pack.id.clone(), assets))
+    let pack_results: Vec<_> = parallelism::with_io_pool(|| {
+        packs
+            .par_iter()
+            .enumerate()
+            .map(|(i, pack)| {
+                jobs::check_cancelled(cancel)?;
+                let cached = cache_dir
+                    .as_deref()
+                    .and_then(|dir| index_cache::load(dir, pack));
+                let (assets, os_metadata_files, animations) = match &cached {
+                    Some(entry) => {
+                        debug!(
+                            "Using cached index for pack {}/{}: {}",
+                            i + 1,
+                            packs.len(),
+                            pack.name
+                        );
+                        (
+                            entry.assets.clone(),
+                            entry.os_metadata_files,
+                            entry.animations.clone(),
+                        )
+                    }
+                    None => {
+                        debug!(
+                            "Indexing pack {}/{}: {} (is_zip: {})",
+                            i + 1,
+                            packs.len(),
+                            pack.name,
+                            pack.is_zip
+                        );
+                        let (assets, os_metadata_files) = if pack.is_zip {
+                            index_zip_pack(&pack.path, &pack.id)?
+                        } else {
+                            index_folder_pack(&pack.path, &pack.id)?
+                        };
+                        let animations = animation::pack_animations(pack, &assets);
+                        (assets, os_metadata_files, animations)
+                    }
+                };
+                debug!("Found {} assets in pack {}", assets.len(), pack.name);
+
+                let from_cache = cached.is_some();
+                // The vanilla cache only changes with the Minecraft version and
+                // is too large to hash on every scan
+                let hash_pack = hash_contents && pack.id != VANILLA_PACK_ID;
+                let folder_fingerprint = if hash_pack && !pack.is_zip {
+                    folder_fingerprint(pack, &assets)
+                } else {
+                    None
+                };
+                let cached_hashes = cached.and_then(|entry| entry.hashes).or_else(|| {
+                    folder_fingerprint
+                        .and_then(|fingerprint| remembered_folder_hashes(&pack.path, fingerprint))
+                });
+                let reused = cached_hashes.is_some();
+                let hashes = match (hash_pack, cached_hashes) {
+                    (false, _) => None,
+                    (true, Some(hashes)) => Some(content_hash::PackHashResult {
+                        hashes,
+                        ..Default::default()
+                    }),
+                    (true, None) => {
+                        jobs::check_cancelled(cancel)?;
+                        // Without hashes the pack is only left out of duplicate detection
+                        match content_hash::hash_pack_assets(pack, &assets) {
+                            Ok(result) => Some(result),
+                            Err(e) => {
+                                warn!("Could not hash contents of {}: {:#}", pack.name, e);
+                                None
+                            }
+                        }
+                    }
+                };
+                let newly_hashed = hashes.is_some() && !reused;
+                if let (Some(fingerprint), Some(hashes), true) =
+                    (folder_fingerprint, &hashes, newly_hashed)
+                {
+                    remember_folder_hashes(&pack.path, fingerprint, &hashes.hashes);
                 }
-                Err(e) => Err(e),
-            }
-        })
-        .collect::<Result<Vec<_>>>()?;
+
+                // Save new listings, and cached ones that just gained hashes
+                if let (Some(dir), true) = (cache_dir.as_deref(), !from_cache || newly_hashed) {
+                    if let Err(e) = index_cache::store(
+                        dir,
+                        pack,
+                        &assets,
+                        os_metadata_files,
+                        &animations,
+                        hashes.as_ref().map(|h| &h.hashes),
+                    ) {
+                        warn!("Could not cache index of {}: {}", pack.name, e);
+                    }
+                }
+
+                let count = indexed.fetch_add(1, Ordering::Relaxed) + 1;
+                if let Some(progress) = progress {
+                    progress(count, packs.len(), Some(&pack.name));
+                }
+
+                Ok((
+                    pack.id.clone(),
+                    assets,
+                    hashes,
+                    os_metadata_files,
+                    animations,
+                ))
+            })
+            .collect::<Result<Vec<_>>>()
+    })?;
 
     // Merge results sequentially (this is fast compared to I/O)
     let mut assets_map: HashMap<String, AssetRecord> = HashMap::new();
     let mut providers: HashMap<String, Vec<String>> = HashMap::new();
+    let mut hash_index = AssetHashIndex::default();
+
+    for (pack_id, pack_assets, hashes, os_metadata_files, animations) in pack_results {
+        if os_metadata_files > 0 {
+            info!(
+                "Skipped {} macOS metadata file(s) in {}",
+                os_metadata_files, pack_id
+            );
+            hash_index
+                .os_metadata_files
+                .insert(pack_id.clone(), os_metadata_files);
+        }
+        if let Some(hashes) = hashes {
+            hash_index.timings.files_hashed += hashes.files_hashed;
+            hash_index.timings.bytes_hashed += hashes.bytes_hashed;
+            hash_index.timings.hash_ms += hashes.hash_ms;
+            hash_index
+                .pack_hashes
+                .insert(pack_id.clone(), hashes.hashes);
+        }
+
+        let mut pack_files: Vec<String> = pack_assets.values().flatten().cloned().collect();
+        pack_files.sort();
+        hash_index.pack_files.insert(pack_id.clone(), pack_files);
 
-    for (pack_id, pack_assets) in pack_results {
         for (asset_id, files) in pack_assets {
             // Track provider
             providers
@@ -64,7 +288,8 @@ pub fn index_assets(
                 .or_insert_with(Vec::new)
                 .push(pack_id.clone());
 
-            // Merge into assets map
+            // Merge into assets map; the first pack animating a texture sets its animation
+            let animation = animations.get(&asset_id);
             assets_map
                 .entry(asset_id.clone())
                 .and_modify(|record| {
@@ -73,11 +298,15 @@ pub fn index_assets(
                             record.files.push(file.clone());
                         }
                     }
+                    if record.animation.is_none() {
+                        record.animation = animation.cloned();
+                    }
                 })
                 .or_insert_with(|| AssetRecord {
                     id: asset_id.clone(),
                     labels: extract_labels(&asset_id),
                     files,
+                    animation: animation.cloned(),
                 });
         }
     }
@@ -85,18 +314,38 @@ pub fn index_assets(
     let mut assets: Vec<AssetRecord> = assets_map.into_values().collect();
     assets.sort_by(|a, b| a.id.cmp(&b.id));
 
-    Ok((assets, providers))
+    hash_index.timings.total_ms = start.elapsed().as_millis() as u64;
+    if hash_contents {
+        info!(
+            "Hashed {} files ({} bytes) in {}ms of {}ms total",
+            hash_index.timings.files_hashed,
+            hash_index.timings.bytes_hashed,
+            hash_index.timings.hash_ms,
+            hash_index.timings.total_ms
+        );
+    }
+
+    Ok((assets, providers, hash_index))
 }
 
+/// Asset ID -> files of one pack, and the number of macOS metadata files skipped
+type PackAssets = (HashMap<String, Vec<String>>, usize);
+
 /// Index assets from a zip pack
-fn index_zip_pack(zip_path: &str, _pack_id: &str) -> Result<HashMap<String, Vec<String>>> {
-    println!("[index_zip_pack] Listing files in ZIP: {}", zip_path);
-    let files = zip::list_zip_files(zip_path)?;
-    println!("[index_zip_pack] Found {} files in ZIP", files.len());
+fn index_zip_pack(zip_path: &str, _pack_id: &str) -> Result<PackAssets> {
+    debug!("Listing files in ZIP: {}", zip_path);
+    let (os_metadata, files): (Vec<String>, Vec<String>) = zip::list_zip_files(zip_path)?
+        .into_iter()
+        .partition(|file| is_os_metadata_file(file));
+    debug!(
+        "Found {} files in ZIP ({} macOS metadata skipped)",
+        files.len(),
+        os_metadata.len()
+    );
 
     // Debug: Print first few files to see their structure
     for (i, file) in files.iter().take(10).enumerate() {
-        println!("[index_zip_pack] Sample file {}: {}", i, file);
+        debug!("Sample file {}: {}", i, file);
     }
 
     // Debug: Show which files are being rejected and why
@@ -104,18 +353,18 @@ fn index_zip_pack(zip_path: &str, _pack_id: &str) -> Result<HashMap<String, Vec<
     for file in files.iter() {
         if extract_asset_id(&file).is_none() {
             if rejected_count < 5 {
-                println!("[index_zip_pack] REJECTED (not a texture): {}", file);
+                debug!("REJECTED (not a texture): {}", file);
             }
             rejected_count += 1;
         }
     }
-    println!("[index_zip_pack] Total rejected files: {}", rejected_count);
+    debug!("Total rejected files: {}", rejected_count);
 
     let mut assets_map: HashMap<String, Vec<String>> = HashMap::new();
 
     for (i, file) in files.iter().enumerate() {
         if i % 1000 == 0 {
-            println!("[index_zip_pack] Processing file {}/{}", i, files.len());
+            debug!("Processing file {}/{}", i, files.len());
         }
         if let Some(asset_id) = extract_asset_id(&file) {
             assets_map
@@ -124,18 +373,17 @@ fn index_zip_pack(zip_path: &str, _pack_id: &str) -> Result<HashMap<String, Vec<
                 .push(file.clone());
         }
     }
-    println!(
-        "[index_zip_pack] Extracted {} unique assets",
-        assets_map.len()
-    );
+    group_cem_parts(&mut assets_map);
+    debug!("Extracted {} unique assets", assets_map.len());
 
-    Ok(assets_map)
+    Ok((assets_map, os_metadata.len()))
 }
 
 /// Index assets from an uncompressed folder pack
-fn index_folder_pack(folder_path: &str, _pack_id: &str) -> Result<HashMap<String, Vec<String>>> {
+fn index_folder_pack(folder_path: &str, _pack_id: &str) -> Result<PackAssets> {
     let path = Path::new(folder_path);
     let mut assets_map: HashMap<String, Vec<String>> = HashMap::new();
+    let mut os_metadata = 0;
 
     for entry in WalkDir::new(path)
         .into_iter()
@@ -147,6 +395,10 @@ fn index_folder_pack(folder_path: &str, _pack_id: &str) -> Result<HashMap<String
             .strip_prefix(path)
             .map(|p| p.to_string_lossy().to_string())?;
 
+        if is_os_metadata_file(&rel_path) {
+            os_metadata += 1;
+            continue;
+        }
         if let Some(asset_id) = extract_asset_id(&rel_path) {
             assets_map
                 .entry(asset_id)
@@ -154,13 +406,66 @@ fn index_folder_pack(folder_path: &str, _pack_id: &str) -> Result<HashMap<String
                 .push(rel_path);
         }
     }
+    group_cem_parts(&mut assets_map);
 
-    Ok(assets_map)
+    Ok((assets_map, os_metadata))
+}
+
+/// Move custom entity model parts into the group of their entity
+///
+/// Parts (.jpm) are named after the entity that uses them (e.g.
+/// "parts/iron_golem_head.jpm" for iron_golem.jem), but their file name
+/// alone gives them a group of their own. Each part-only group joins the
+/// entity of the same pack whose name is its longest prefix, so a build
+/// takes an entity's model and parts from one pack.
+fn group_cem_parts(assets: &mut HashMap<String, Vec<String>>) {
+    let has_ext =
+        |files: &[String], ext: &str| files.iter().any(|file| file.to_lowercase().ends_with(ext));
+    let entities: Vec<String> = assets
+        .iter()
+        .filter(|(id, files)| is_cem_asset(id) && has_ext(files, ".jem"))
+        .map(|(id, _)| id.clone())
+        .collect();
+    let parts: Vec<String> = assets
+        .iter()
+        .filter(|(id, files)| {
+            is_cem_asset(id)
+                && files
+                    .iter()
+                    .all(|file| file.to_lowercase().ends_with(".jpm"))
+        })
+        .map(|(id, _)| id.clone())
+        .collect();
+
+    for part in parts {
+        let owner = entities
+            .iter()
+            .filter(|entity| {
+                part.strip_prefix(entity.as_str())
+                    .map_or(false, |rest| rest.starts_with('_'))
+            })
+            .max_by_key(|entity| entity.len());
+        if let Some(owner) = owner {
+            let files = assets.remove(&part).unwrap_or_default();
+            assets.entry(owner.clone()).or_default().extend(files);
+        }
+    }
+}
+
+/// Whether a pack file is macOS metadata rather than content
+///
+/// Zips made on macOS carry a `__MACOSX/` folder and `._name` AppleDouble
+/// files next to real files. A `._stone.png` isn't a texture, and merging it
+/// as one can clobber the real asset.
+pub fn is_os_metadata_file(file_path: &str) -> bool {
+    file_path
+        .split(['/', '\\'])
+        .any(|part| part == MACOS_METADATA_DIR || part.starts_with("._"))
 }
 
 /// Extract asset ID from a file path
 /// E.g., "assets/minecraft/textures/block/stone.png" -> "minecraft:block/stone"
-fn extract_asset_id(file_path: &str) -> Option<String> {
+pub fn extract_asset_id(file_path: &str) -> Option<String> {
     // Must be in assets/
     if !file_path.starts_with(ASSET_PATH_PREFIX) {
         return None;
@@ -177,7 +482,26 @@ fn extract_asset_id(file_path: &str) -> Option<String> {
     let namespace = parts[0];
     let rest = parts[1];
 
-    // For now, only index texture files in textures/ subdirectory
+    // Custom entity model files are grouped per entity
+    if let Some(cem_file) = rest.strip_prefix(CEM_DIR) {
+        return cem_entity_for_file(cem_file).map(|entity| format!("{}:cem/{}", namespace, entity));
+    }
+
+    // Connected texture files are grouped per CTM folder
+    if let Some(ctm_file) = rest.strip_prefix(optifine::CTM_DIR) {
+        return optifine::ctm_group_for_file(ctm_file)
+            .map(|group| format!("{}:ctm/{}", namespace, group));
+    }
+
+    // Splashes, credits and other texts are indexed by file name
+    if let Some(text_file) = rest.strip_prefix(text_assets::TEXTS_DIR) {
+        let name = text_file
+            .rsplit_once('.')
+            .map_or(text_file, |(name, _)| name);
+        return Some(format!("{}:{}{}", namespace, text_assets::TEXTS_DIR, name));
+    }
+
+    // Otherwise only index texture files in textures/ subdirectory
     if !rest.starts_with(TEXTURE_PATH) {
         return None;
     }
@@ -194,6 +518,38 @@ fn extract_asset_id(file_path: &str) -> Option<String> {
     Some(format!("{}:{}", namespace, asset_path))
 }
 
+/// Entity a file inside optifine/cem/ belongs to
+///
+/// Version folders and OptiFine random-model suffixes are ignored so every
+/// variant groups with its entity:
+/// - "cow.jem" -> Some("cow")
+/// - "21.4/cow.jem" -> Some("cow")
+/// - "cow2.jem" -> Some("cow")
+/// - "cow.properties" -> Some("cow")
+fn cem_entity_for_file(cem_file: &str) -> Option<String> {
+    let file_name = cem_file.rsplit('/').next()?;
+    let (stem, ext) = file_name.rsplit_once('.')?;
+    if !CEM_EXTENSIONS.contains(&ext.to_lowercase().as_str()) {
+        return None;
+    }
+
+    let entity = stem.trim_end_matches(|c: char| c.is_ascii_digit());
+    let entity = if entity.is_empty() { stem } else { entity };
+    Some(entity.to_lowercase())
+}
+
+/// Whether an asset ID refers to a custom entity model group
+pub fn is_cem_asset(asset_id: &str) -> bool {
+    asset_id
+        .split_once(':')
+        .map_or(false, |(_, path)| path.starts_with("cem/"))
+}
+
+/// Whether an asset ID groups several files (custom entity models, CTM)
+pub fn is_grouped_asset(asset_id: &str) -> bool {
+    is_cem_asset(asset_id) || optifine::is_ctm_asset(asset_id)
+}
+
 /// Extract labels from an asset ID
 /// E.g., "minecraft:block/stone" -> ["minecraft", "block", "stone"]
 fn extract_labels(asset_id: &str) -> Vec<String> {
@@ -217,6 +573,16 @@ fn extract_labels(asset_id: &str) -> Vec<String> {
         }
     }
 
+    // Entity textures are also found by their entity ("husk", "trapped_chest")
+    if let Some(entity) = entity_map::entity_for_texture(asset_id) {
+        let name = entity
+            .split_once(':')
+            .map_or(entity.as_str(), |(_, name)| name);
+        if !labels.iter().any(|label| label == name) {
+            labels.push(name.to_string());
+        }
+    }
+
     labels
 }
 
@@ -230,10 +596,7 @@ fn extract_labels(asset_id: &str) -> Vec<String> {
 ///
 /// Returns: {"cow": ["21.4", "21.5"]}
 pub fn scan_entity_version_variants(packs: &[PackMeta]) -> Result<HashMap<String, Vec<String>>> {
-    println!(
-        "[scan_entity_version_variants] Scanning {} packs for JEM version variants",
-        packs.len()
-    );
+    debug!("Scanning {} packs for JEM version variants", packs.len());
 
     let mut entity_variants: HashMap<String, std::collections::HashSet<String>> = HashMap::new();
 
@@ -268,15 +631,9 @@ pub fn scan_entity_version_variants(packs: &[PackMeta]) -> Result<HashMap<String
         })
         .collect();
 
-    println!(
-        "[scan_entity_version_variants] Found {} entities with version variants",
-        result.len()
-    );
+    debug!("Found {} entities with version variants", result.len());
     for (entity, versions) in &result {
-        println!(
-            "[scan_entity_version_variants]   {}: {:?}",
-            entity, versions
-        );
+        debug!("  {}: {:?}", entity, versions);
     }
 
     Ok(result)
@@ -409,6 +766,18 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_extract_asset_id_text_files() {
+        assert_eq!(
+            extract_asset_id("assets/minecraft/texts/splashes.txt"),
+            Some("minecraft:texts/splashes".to_string())
+        );
+        assert_eq!(
+            extract_asset_id("assets/minecraft/texts/credits.json"),
+            Some("minecraft:texts/credits".to_string())
+        );
+    }
+
     #[test]
     fn test_extract_asset_id_invalid_paths() {
         // No assets/ prefix
@@ -429,6 +798,95 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_extract_asset_id_cem_files() {
+        assert_eq!(
+            extract_asset_id("assets/minecraft/optifine/cem/cow.jem"),
+            Some("minecraft:cem/cow".to_string())
+        );
+        assert_eq!(
+            extract_asset_id("assets/minecraft/optifine/cem/21.4/cow.jem"),
+            Some("minecraft:cem/cow".to_string())
+        );
+        assert_eq!(
+            extract_asset_id("assets/minecraft/optifine/cem/cow2.jem"),
+            Some("minecraft:cem/cow".to_string())
+        );
+        assert_eq!(
+            extract_asset_id("assets/minecraft/optifine/cem/cow.properties"),
+            Some("minecraft:cem/cow".to_string())
+        );
+        assert_eq!(
+            extract_asset_id("assets/minecraft/optifine/cem/parts/cow_head.jpm"),
+            Some("minecraft:cem/cow_head".to_string())
+        );
+        assert_eq!(
+            extract_asset_id("assets/minecraft/optifine/cem/readme.txt"),
+            None
+        );
+        assert!(is_cem_asset("minecraft:cem/cow"));
+        assert!(!is_cem_asset("minecraft:entity/cow/cow"));
+    }
+
+    #[test]
+    fn test_cem_parts_join_their_entity() {
+        let temp_dir = std::env::temp_dir().join("test_index_cem_parts");
+        std::fs::remove_dir_all(&temp_dir).ok();
+        let cem = temp_dir.join("assets/minecraft/optifine/cem");
+        std::fs::create_dir_all(cem.join("parts")).expect("Failed to create test directory");
+        for file in [
+            "golem.jem",
+            "iron_golem.jem",
+            "parts/iron_golem_head.jpm",
+            "parts/golem_arm.jpm",
+            "parts/spare_wing.jpm",
+        ] {
+            std::fs::write(cem.join(file), "{}").expect("Failed to write file");
+        }
+
+        let (assets, _) = index_folder_pack(&temp_dir.to_string_lossy(), "pack").unwrap();
+
+        // Clean up
+        std::fs::remove_dir_all(&temp_dir).ok();
+
+        let mut iron_golem = assets["minecraft:cem/iron_golem"].clone();
+        iron_golem.sort();
+        assert_eq!(iron_golem.len(), 2);
+        assert!(iron_golem[1].ends_with("iron_golem_head.jpm"));
+        assert_eq!(assets["minecraft:cem/golem"].len(), 2);
+        // A part no entity claims keeps its own group
+        assert!(assets.contains_key("minecraft:cem/spare_wing"));
+        assert!(!assets.contains_key("minecraft:cem/iron_golem_head"));
+    }
+
+    #[test]
+    fn test_extract_asset_id_ctm_files() {
+        assert_eq!(
+            extract_asset_id("assets/minecraft/optifine/ctm/glass/glass.properties"),
+            Some("minecraft:ctm/glass".to_string())
+        );
+        assert_eq!(
+            extract_asset_id("assets/minecraft/optifine/ctm/glass/46.png"),
+            Some("minecraft:ctm/glass".to_string())
+        );
+        assert!(is_grouped_asset("minecraft:ctm/glass"));
+        assert!(is_grouped_asset("minecraft:cem/cow"));
+        assert!(!is_grouped_asset("minecraft:block/glass"));
+    }
+
+    #[test]
+    fn test_is_os_metadata_file() {
+        assert!(is_os_metadata_file(
+            "__MACOSX/assets/minecraft/textures/block/._stone.png"
+        ));
+        assert!(is_os_metadata_file(
+            "assets/minecraft/textures/block/._stone.png"
+        ));
+        assert!(!is_os_metadata_file(
+            "assets/minecraft/textures/block/stone.png"
+        ));
+    }
+
     #[test]
     fn test_extract_labels() {
         let labels = extract_labels("minecraft:block/stone");
@@ -448,6 +906,23 @@ mod tests {
         assert!(labels.contains(&"stone".to_string()));
     }
 
+    #[test]
+    fn test_extract_labels_entity() {
+        let labels = extract_labels("minecraft:entity/chest/trapped_left");
+        assert_eq!(
+            labels,
+            vec![
+                "minecraft",
+                "entity",
+                "chest",
+                "trapped_left",
+                "trapped_chest"
+            ]
+        );
+        let labels = extract_labels("minecraft:entity/cow/cow");
+        assert_eq!(labels, vec!["minecraft", "entity", "cow", "cow"]);
+    }
+
     #[test]
     fn test_extract_labels_custom_namespace() {
         let labels = extract_labels("mymod:item/sword");
@@ -494,10 +969,7 @@ mod tests {
             name: "Test Pack".to_string(),
             path: pack_dir.to_string_lossy().to_string(),
             size: 1000,
-            is_zip: false,
-            description: None,
-            icon_data: None,
-            pack_format: None,
+            ..Default::default()
         };
 
         let result = index_assets(&[pack]);
@@ -544,10 +1016,7 @@ mod tests {
             name: "Pack 1".to_string(),
             path: pack1_dir.to_string_lossy().to_string(),
             size: 1000,
-            is_zip: false,
-            description: None,
-            icon_data: None,
-            pack_format: None,
+            ..Default::default()
         };
 
         let pack2 = PackMeta {
@@ -555,10 +1024,7 @@ mod tests {
             name: "Pack 2".to_string(),
             path: pack2_dir.to_string_lossy().to_string(),
             size: 1000,
-            is_zip: false,
-            description: None,
-            icon_data: None,
-            pack_format: None,
+            ..Default::default()
         };
 
         let result = index_assets(&[pack1, pack2]);
@@ -599,10 +1065,7 @@ mod tests {
             name: "Test Pack".to_string(),
             path: pack_dir.to_string_lossy().to_string(),
             size: 1000,
-            is_zip: false,
-            description: None,
-            icon_data: None,
-            pack_format: None,
+            ..Default::default()
         };
 
         let result = index_assets(&[pack]);
@@ -619,4 +1082,36 @@ mod tests {
         assert_eq!(assets[1].id, "minecraft:block/monkey");
         assert_eq!(assets[2].id, "minecraft:block/zebra");
     }
+
+    #[test]
+    fn test_index_with_hashes_skips_vanilla_and_reuses_folder_hashes() {
+        let temp_dir = std::env::temp_dir().join("test_asset_index_hash_reuse");
+        std::fs::remove_dir_all(&temp_dir).ok();
+        let make_pack = |id: &str| {
+            let pack_dir = temp_dir.join(id.replace(':', "_"));
+            let asset_dir = pack_dir.join("assets/minecraft/textures/block");
+            std::fs::create_dir_all(&asset_dir).unwrap();
+            std::fs::write(asset_dir.join("stone.png"), "fake png data").unwrap();
+            PackMeta {
+                id: id.to_string(),
+                name: id.to_string(),
+                path: pack_dir.to_string_lossy().to_string(),
+                size: 13,
+                ..Default::default()
+            }
+        };
+        let packs = vec![make_pack("folder_pack"), make_pack(VANILLA_PACK_ID)];
+
+        let (_, _, first) = index_assets_with_hashes(&packs).unwrap();
+        let (_, _, second) = index_assets_with_hashes(&packs).unwrap();
+
+        // Clean up
+        std::fs::remove_dir_all(&temp_dir).ok();
+
+        assert!(first.pack_hashes.contains_key("folder_pack"));
+        assert!(!first.pack_hashes.contains_key(VANILLA_PACK_ID));
+        assert_eq!(first.timings.files_hashed, 1);
+        assert_eq!(second.pack_hashes, first.pack_hashes);
+        assert_eq!(second.timings.files_hashed, 0);
+    }
 }
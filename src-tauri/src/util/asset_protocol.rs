@@ -0,0 +1,318 @@
+/// weaverbird:// protocol for loading pack files directly
+///
+/// The webview can point an <img>, <audio> or fetch() at
+/// "weaverbird://asset/<pack path>/<file path>" instead of asking a command
+/// for base64 data. The pack path is the percent-encoded path of the pack's
+/// folder or ZIP, so no state is needed to find it; it must be inside a
+/// location the path policy allows. Responses carry a content type from
+/// the file extension and honor single byte ranges, which audio and video
+/// elements rely on for seeking.
+///
+/// Windows webviews can't use custom schemes, so Tauri serves the same
+/// protocol as "http://weaverbird.localhost/asset/..."; both forms parse.
+use crate::util::animation::read_pack_bytes;
+use crate::util::path_policy;
+use std::path::Path;
+use tracing::debug;
+
+/// URI scheme the protocol is registered under
+pub const ASSET_PROTOCOL_SCHEME: &str = "weaverbird";
+
+/// First path segment (or host) of asset URIs
+const ASSET_PREFIX: &str = "asset";
+
+/// Content types by file extension (lowercase)
+const MIME_TYPES: &[(&str, &str)] = &[
+    ("png", "image/png"),
+    ("jpg", "image/jpeg"),
+    ("jpeg", "image/jpeg"),
+    ("gif", "image/gif"),
+    ("webp", "image/webp"),
+    ("json", "application/json"),
+    ("mcmeta", "application/json"),
+    ("jem", "application/json"),
+    ("jpm", "application/json"),
+    ("ogg", "audio/ogg"),
+    ("properties", "text/plain; charset=utf-8"),
+    ("txt", "text/plain; charset=utf-8"),
+    ("lang", "text/plain; charset=utf-8"),
+    ("fsh", "text/plain; charset=utf-8"),
+    ("vsh", "text/plain; charset=utf-8"),
+    ("glsl", "text/plain; charset=utf-8"),
+];
+
+/// A pack file named by an asset URI
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AssetLocation {
+    /// Folder or ZIP of the pack
+    pub pack_path: String,
+    /// '/'-separated path inside the pack
+    pub file_path: String,
+}
+
+/// Part of a file a request asks for
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ByteRange {
+    /// No Range header, or one this server ignores
+    Full,
+    /// Inclusive start and end offsets
+    Partial(usize, usize),
+    /// A range starting past the end of the file
+    Unsatisfiable,
+}
+
+/// Response to an asset request, independent of the HTTP types Tauri uses
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AssetResponse {
+    pub status: u16,
+    pub headers: Vec<(&'static str, String)>,
+    pub body: Vec<u8>,
+}
+
+impl AssetResponse {
+    fn error(status: u16, message: impl Into<String>) -> Self {
+        Self {
+            status,
+            headers: vec![("Content-Type", "text/plain; charset=utf-8".to_string())],
+            body: message.into().into_bytes(),
+        }
+    }
+}
+
+/// Decode %XX escapes; None if an escape or the result is invalid
+fn percent_decode(value: &str) -> Option<String> {
+    let bytes = value.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] == b'%' {
+            let hex = value.get(i + 1..i + 3)?;
+            decoded.push(u8::from_str_radix(hex, 16).ok()?);
+            i += 3;
+        } else {
+            decoded.push(bytes[i]);
+            i += 1;
+        }
+    }
+    String::from_utf8(decoded).ok()
+}
+
+/// Pack and file an asset URI names
+///
+/// # Arguments
+/// * `host` - URI host ("asset" for weaverbird://asset/...)
+/// * `path` - URI path, still percent-encoded
+pub fn parse_asset_uri(host: Option<&str>, path: &str) -> Option<AssetLocation> {
+    let path = path.trim_start_matches('/');
+    let rest = if host == Some(ASSET_PREFIX) {
+        path
+    } else {
+        path.strip_prefix(ASSET_PREFIX)?.strip_prefix('/')?
+    };
+    let (pack_path, file_path) = rest.split_once('/')?;
+    let pack_path = percent_decode(pack_path)?;
+    let file_path = percent_decode(file_path)?;
+    if pack_path.is_empty() || file_path.is_empty() {
+        return None;
+    }
+    Some(AssetLocation {
+        pack_path,
+        file_path,
+    })
+}
+
+/// Content type for a file, by extension
+pub fn mime_type_for(file_path: &str) -> &'static str {
+    let extension = file_path
+        .rsplit_once('.')
+        .map(|(_, ext)| ext.to_lowercase())
+        .unwrap_or_default();
+    MIME_TYPES
+        .iter()
+        .find(|(ext, _)| *ext == extension)
+        .map_or("application/octet-stream", |(_, mime)| mime)
+}
+
+/// Parse a Range header against a file length
+///
+/// Only single ranges are served ("bytes=0-99", "bytes=100-",
+/// "bytes=-100"); anything else gets the whole file.
+pub fn parse_byte_range(header: Option<&str>, len: usize) -> ByteRange {
+    let spec = match header.and_then(|h| h.trim().strip_prefix("bytes=")) {
+        Some(spec) if !spec.contains(',') => spec,
+        _ => return ByteRange::Full,
+    };
+    let (start, end) = match spec.split_once('-') {
+        Some(bounds) => bounds,
+        None => return ByteRange::Full,
+    };
+    let (start, end) = match (start.trim(), end.trim()) {
+        ("", suffix) => match suffix.parse::<usize>() {
+            Ok(0) | Err(_) => return ByteRange::Full,
+            Ok(suffix) => (len.saturating_sub(suffix), len.saturating_sub(1)),
+        },
+        (start, "") => match start.parse::<usize>() {
+            Ok(start) => (start, len.saturating_sub(1)),
+            Err(_) => return ByteRange::Full,
+        },
+        (start, end) => match (start.parse::<usize>(), end.parse::<usize>()) {
+            (Ok(start), Ok(end)) if start <= end => (start, end.min(len.saturating_sub(1))),
+            _ => return ByteRange::Full,
+        },
+    };
+    if start >= len {
+        return ByteRange::Unsatisfiable;
+    }
+    ByteRange::Partial(start, end)
+}
+
+/// Answer an asset request
+///
+/// # Arguments
+/// * `host` - URI host
+/// * `path` - URI path, still percent-encoded
+/// * `range` - Range header, if any
+pub fn serve_asset(host: Option<&str>, path: &str, range: Option<&str>) -> AssetResponse {
+    let location = match parse_asset_uri(host, path) {
+        Some(location) => location,
+        None => return AssetResponse::error(400, format!("Not an asset URI: {}", path)),
+    };
+    if let Err(e) = path_policy::check_path(&location.pack_path) {
+        return AssetResponse::error(403, e.to_string());
+    }
+
+    let is_zip = Path::new(&location.pack_path).is_file();
+    let bytes = match read_pack_bytes(&location.pack_path, is_zip, &location.file_path) {
+        Ok(bytes) => bytes,
+        Err(e) => {
+            debug!("{}: {}", location.file_path, e);
+            return AssetResponse::error(404, format!("Not found: {}", location.file_path));
+        }
+    };
+
+    let len = bytes.len();
+    let mut headers = vec![
+        (
+            "Content-Type",
+            mime_type_for(&location.file_path).to_string(),
+        ),
+        ("Accept-Ranges", "bytes".to_string()),
+        // Windows serves the protocol from its own origin
+        ("Access-Control-Allow-Origin", "*".to_string()),
+    ];
+    match parse_byte_range(range, len) {
+        ByteRange::Full => AssetResponse {
+            status: 200,
+            headers,
+            body: bytes,
+        },
+        ByteRange::Partial(start, end) => {
+            headers.push(("Content-Range", format!("bytes {}-{}/{}", start, end, len)));
+            AssetResponse {
+                status: 206,
+                headers,
+                body: bytes[start..=end].to_vec(),
+            }
+        }
+        ByteRange::Unsatisfiable => {
+            let mut response = AssetResponse::error(416, "Range not satisfiable");
+            response
+                .headers
+                .push(("Content-Range", format!("bytes */{}", len)));
+            response
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    #[test]
+    fn test_parse_asset_uri() {
+        let expected = Some(AssetLocation {
+            pack_path: "/packs/My Pack.zip".to_string(),
+            file_path: "assets/minecraft/textures/block/stone.png".to_string(),
+        });
+        let encoded = "%2Fpacks%2FMy%20Pack.zip/assets/minecraft/textures/block/stone.png";
+
+        assert_eq!(
+            parse_asset_uri(Some("asset"), &format!("/{}", encoded)),
+            expected
+        );
+        assert_eq!(
+            parse_asset_uri(Some("weaverbird.localhost"), &format!("/asset/{}", encoded)),
+            expected
+        );
+        assert_eq!(parse_asset_uri(Some("asset"), "/%2Fpacks"), None);
+        assert_eq!(parse_asset_uri(Some("other"), "/other/a/b"), None);
+        assert_eq!(parse_asset_uri(Some("asset"), "/%zz/file.png"), None);
+    }
+
+    #[test]
+    fn test_parse_byte_range() {
+        assert_eq!(parse_byte_range(None, 100), ByteRange::Full);
+        assert_eq!(
+            parse_byte_range(Some("bytes=0-9"), 100),
+            ByteRange::Partial(0, 9)
+        );
+        assert_eq!(
+            parse_byte_range(Some("bytes=90-"), 100),
+            ByteRange::Partial(90, 99)
+        );
+        assert_eq!(
+            parse_byte_range(Some("bytes=-10"), 100),
+            ByteRange::Partial(90, 99)
+        );
+        assert_eq!(
+            parse_byte_range(Some("bytes=50-500"), 100),
+            ByteRange::Partial(50, 99)
+        );
+        assert_eq!(
+            parse_byte_range(Some("bytes=100-"), 100),
+            ByteRange::Unsatisfiable
+        );
+        assert_eq!(
+            parse_byte_range(Some("bytes=0-1,5-6"), 100),
+            ByteRange::Full
+        );
+    }
+
+    #[test]
+    fn test_serve_asset() {
+        let temp_dir = std::env::temp_dir().join("test_asset_protocol");
+        fs::remove_dir_all(&temp_dir).ok();
+        let pack_dir = temp_dir.join("pack");
+        let sounds = pack_dir.join("assets/minecraft/sounds");
+        fs::create_dir_all(&sounds).unwrap();
+        fs::write(sounds.join("dig.ogg"), "0123456789").unwrap();
+        path_policy::register_root(&temp_dir);
+        let encoded_pack = pack_dir.to_string_lossy().replace('/', "%2F");
+        let path = format!("/{}/assets/minecraft/sounds/dig.ogg", encoded_pack);
+
+        let full = serve_asset(Some("asset"), &path, None);
+        let partial = serve_asset(Some("asset"), &path, Some("bytes=2-4"));
+        let missing = serve_asset(
+            Some("asset"),
+            &format!("/{}/assets/minecraft/sounds/none.ogg", encoded_pack),
+            None,
+        );
+
+        // Clean up
+        fs::remove_dir_all(&temp_dir).ok();
+
+        assert_eq!(full.status, 200);
+        assert_eq!(full.body, b"0123456789");
+        assert!(full
+            .headers
+            .contains(&("Content-Type", "audio/ogg".to_string())));
+        assert_eq!(partial.status, 206);
+        assert_eq!(partial.body, b"234");
+        assert!(partial
+            .headers
+            .contains(&("Content-Range", "bytes 2-4/10".to_string())));
+        assert_eq!(missing.status, 404);
+        assert_eq!(serve_asset(Some("asset"), "/nonsense", None).status, 400);
+    }
+}
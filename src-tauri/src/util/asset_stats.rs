@@ -0,0 +1,224 @@
+/// Asset counts and sizes by category
+///
+/// Summary dashboards show how much of each kind of asset (block, item,
+/// entity, gui...) every pack ships. Working that out in the frontend means
+/// walking the whole index, so it's grouped here once per scan. The category
+/// is the one conflict reports use (see `conflicts::asset_category`), so
+/// custom entity models, CTM groups and texts come out as "cem", "ctm" and
+/// "texts".
+use crate::model::{AssetRecord, PackMeta};
+use crate::util::conflicts::asset_category;
+use crate::util::parallelism;
+use crate::util::zip;
+use anyhow::Result;
+use rayon::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::path::Path;
+use tracing::warn;
+use walkdir::WalkDir;
+
+/// Totals for one category
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CategoryStats {
+    /// Distinct assets
+    pub assets: usize,
+    /// Files making up those assets
+    pub files: usize,
+    /// Uncompressed size of those files
+    pub bytes: u64,
+}
+
+impl CategoryStats {
+    fn add(&mut self, other: &CategoryStats) {
+        self.assets += other.assets;
+        self.files += other.files;
+        self.bytes += other.bytes;
+    }
+}
+
+/// Category totals per pack and across all packs
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AssetStats {
+    /// Category -> totals over every pack. Assets count each asset once;
+    /// files and bytes add up every pack's copy.
+    pub overall: BTreeMap<String, CategoryStats>,
+    /// Pack ID -> category -> totals for that pack
+    pub by_pack: HashMap<String, BTreeMap<String, CategoryStats>>,
+}
+
+/// Size of every file in a pack, keyed by its path within the pack
+fn pack_file_sizes(pack: &PackMeta) -> Result<HashMap<String, u64>> {
+    if pack.is_zip {
+        return zip::list_zip_file_sizes(&pack.path);
+    }
+
+    let root = Path::new(&pack.path);
+    let mut sizes = HashMap::new();
+    for entry in WalkDir::new(root).into_iter().filter_map(|e| e.ok()) {
+        if !entry.file_type().is_file() {
+            continue;
+        }
+        if let (Ok(rel_path), Ok(metadata)) = (entry.path().strip_prefix(root), entry.metadata()) {
+            sizes.insert(rel_path.to_string_lossy().to_string(), metadata.len());
+        }
+    }
+    Ok(sizes)
+}
+
+/// Totals by category for the assets a pack provides
+///
+/// # Arguments
+/// * `assets` - Indexed assets the pack provides
+/// * `sizes` - Path within the pack -> file size
+fn category_totals(
+    assets: &[&AssetRecord],
+    sizes: &HashMap<String, u64>,
+) -> BTreeMap<String, CategoryStats> {
+    let mut totals: BTreeMap<String, CategoryStats> = BTreeMap::new();
+    for asset in assets {
+        let stats = totals
+            .entry(asset_category(&asset.id).to_string())
+            .or_default();
+        stats.assets += 1;
+        // An asset's files are merged across packs; count this pack's own
+        for size in asset.files.iter().filter_map(|file| sizes.get(file)) {
+            stats.files += 1;
+            stats.bytes += size;
+        }
+    }
+    totals
+}
+
+/// Group indexed assets by category, per pack and overall
+///
+/// # Arguments
+/// * `packs` - Indexed packs
+/// * `assets` - Indexed assets
+/// * `providers` - Asset ID -> IDs of the packs providing it
+///
+/// Packs whose files can't be listed are counted without sizes.
+pub fn compute_asset_stats(
+    packs: &[PackMeta],
+    assets: &[AssetRecord],
+    providers: &HashMap<String, Vec<String>>,
+) -> AssetStats {
+    let mut pack_assets: HashMap<&str, Vec<&AssetRecord>> = HashMap::new();
+    for asset in assets {
+        for pack_id in providers.get(&asset.id).into_iter().flatten() {
+            pack_assets.entry(pack_id.as_str()).or_default().push(asset);
+        }
+    }
+
+    let by_pack: HashMap<String, BTreeMap<String, CategoryStats>> =
+        parallelism::with_io_pool(|| {
+            packs
+                .par_iter()
+                .map(|pack| {
+                    let sizes = pack_file_sizes(pack).unwrap_or_else(|e| {
+                        warn!("Could not read file sizes of {}: {}", pack.name, e);
+                        HashMap::new()
+                    });
+                    let provided = pack_assets
+                        .get(pack.id.as_str())
+                        .map_or(&[][..], |assets| assets.as_slice());
+                    (pack.id.clone(), category_totals(provided, &sizes))
+                })
+                .collect()
+        });
+
+    let mut overall: BTreeMap<String, CategoryStats> = BTreeMap::new();
+    for totals in by_pack.values() {
+        for (category, stats) in totals {
+            overall.entry(category.clone()).or_default().add(stats);
+        }
+    }
+    // Assets shared by several packs count once overall
+    for stats in overall.values_mut() {
+        stats.assets = 0;
+    }
+    for asset in assets {
+        overall
+            .entry(asset_category(&asset.id).to_string())
+            .or_default()
+            .assets += 1;
+    }
+
+    AssetStats { overall, by_pack }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    fn pack(id: &str, path: &Path) -> PackMeta {
+        PackMeta {
+            id: id.to_string(),
+            name: id.to_string(),
+            path: path.to_string_lossy().to_string(),
+            ..Default::default()
+        }
+    }
+
+    fn asset(id: &str, file: &str) -> AssetRecord {
+        AssetRecord {
+            id: id.to_string(),
+            labels: Vec::new(),
+            files: vec![file.to_string()],
+            animation: None,
+        }
+    }
+
+    #[test]
+    fn test_compute_asset_stats() {
+        let temp_dir = std::env::temp_dir().join("test_compute_asset_stats");
+        fs::remove_dir_all(&temp_dir).ok();
+        let stone = "assets/minecraft/textures/block/stone.png";
+        let diamond = "assets/minecraft/textures/item/diamond.png";
+        for (pack_id, files) in [("a", vec![stone, diamond]), ("b", vec![stone])] {
+            for file in files {
+                let path = temp_dir.join(pack_id).join(file);
+                fs::create_dir_all(path.parent().unwrap()).unwrap();
+                fs::write(path, vec![0u8; 10]).unwrap();
+            }
+        }
+        let packs = vec![
+            pack("a", &temp_dir.join("a")),
+            pack("b", &temp_dir.join("b")),
+        ];
+        let assets = vec![
+            asset("minecraft:block/stone", stone),
+            asset("minecraft:item/diamond", diamond),
+        ];
+        let providers: HashMap<String, Vec<String>> = [
+            ("minecraft:block/stone", vec!["a", "b"]),
+            ("minecraft:item/diamond", vec!["a"]),
+        ]
+        .iter()
+        .map(|(id, packs)| {
+            (
+                id.to_string(),
+                packs.iter().map(|p| p.to_string()).collect(),
+            )
+        })
+        .collect();
+
+        let stats = compute_asset_stats(&packs, &assets, &providers);
+
+        // Clean up
+        fs::remove_dir_all(&temp_dir).ok();
+
+        let block = CategoryStats {
+            assets: 1,
+            files: 2,
+            bytes: 20,
+        };
+        assert_eq!(stats.overall["block"], block);
+        assert_eq!(stats.overall["item"].bytes, 10);
+        assert_eq!(stats.by_pack["b"]["block"].bytes, 10);
+        assert!(!stats.by_pack["b"].contains_key("item"));
+    }
+}
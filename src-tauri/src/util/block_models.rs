@@ -6,11 +6,13 @@
 /// - Resolving parent model inheritance
 /// - Extracting texture references
 use crate::model::PackMeta;
+use crate::util::missing_texture::MISSING_TEXTURE_ID;
 use crate::{AppError, AppResult};
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::fs;
 use std::path::Path;
+use tracing::{debug, warn};
 
 /// A Minecraft block model JSON structure
 ///
@@ -33,6 +35,166 @@ pub struct BlockModel {
     /// Ambient occlusion flag
     #[serde(skip_serializing_if = "Option::is_none")]
     pub ambientocclusion: Option<bool>,
+
+    /// Broken texture references replaced with the missing-texture
+    /// placeholder (filled in by `substitute_missing_textures`)
+    #[serde(
+        default,
+        rename = "missingTextures",
+        skip_serializing_if = "Vec::is_empty"
+    )]
+    pub missing_textures: Vec<MissingTextureRef>,
+
+    /// Builtin model the parent chain ends in, if any
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub builtin: Option<BuiltinModel>,
+
+    /// Display transforms per context (e.g., "gui", "ground", "fixed")
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub display: Option<HashMap<String, DisplayTransform>>,
+
+    /// Transform for the display context the caller asked for, with the
+    /// game's fallbacks and limits applied (see `apply_display_context`)
+    #[serde(
+        default,
+        rename = "displayTransform",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub display_transform: Option<DisplayTransform>,
+}
+
+/// How a model is posed in one display context
+///
+/// Applied around the block center: translate (in 1/16 block units), then
+/// rotate (degrees, X then Y then Z), then scale.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct DisplayTransform {
+    #[serde(default)]
+    pub rotation: [f32; 3],
+    #[serde(default)]
+    pub translation: [f32; 3],
+    #[serde(default = "unit_scale")]
+    pub scale: [f32; 3],
+}
+
+fn unit_scale() -> [f32; 3] {
+    [1.0, 1.0, 1.0]
+}
+
+impl Default for DisplayTransform {
+    fn default() -> Self {
+        DisplayTransform {
+            rotation: [0.0; 3],
+            translation: [0.0; 3],
+            scale: unit_scale(),
+        }
+    }
+}
+
+/// Where an item model is being shown
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DisplayContext {
+    /// Inventory slots
+    Gui,
+    /// Dropped on the ground
+    Ground,
+    /// Item frames
+    Fixed,
+    /// Worn on the head
+    Head,
+    ThirdpersonRighthand,
+    ThirdpersonLefthand,
+    FirstpersonRighthand,
+    FirstpersonLefthand,
+}
+
+impl DisplayContext {
+    /// Key of the context in a model's `display` object
+    pub fn key(self) -> &'static str {
+        match self {
+            DisplayContext::Gui => "gui",
+            DisplayContext::Ground => "ground",
+            DisplayContext::Fixed => "fixed",
+            DisplayContext::Head => "head",
+            DisplayContext::ThirdpersonRighthand => "thirdperson_righthand",
+            DisplayContext::ThirdpersonLefthand => "thirdperson_lefthand",
+            DisplayContext::FirstpersonRighthand => "firstperson_righthand",
+            DisplayContext::FirstpersonLefthand => "firstperson_lefthand",
+        }
+    }
+
+    /// Context the game uses when this one isn't defined
+    fn fallback(self) -> Option<DisplayContext> {
+        match self {
+            DisplayContext::ThirdpersonLefthand => Some(DisplayContext::ThirdpersonRighthand),
+            DisplayContext::FirstpersonLefthand => Some(DisplayContext::FirstpersonRighthand),
+            _ => None,
+        }
+    }
+}
+
+impl BlockModel {
+    /// Transform the game would use for a display context
+    ///
+    /// Left-hand contexts fall back to the right hand, anything else
+    /// undefined is the identity. Translation is limited to ±80 and scale
+    /// to 4, like the game.
+    pub fn display_transform_for(&self, context: DisplayContext) -> DisplayTransform {
+        let display = match &self.display {
+            Some(display) => display,
+            None => return DisplayTransform::default(),
+        };
+        let transform = display
+            .get(context.key())
+            .or_else(|| context.fallback().and_then(|f| display.get(f.key())))
+            .copied()
+            .unwrap_or_default();
+
+        DisplayTransform {
+            rotation: transform.rotation,
+            translation: transform.translation.map(|t| t.clamp(-80.0, 80.0)),
+            scale: transform.scale.map(|s| s.clamp(-4.0, 4.0)),
+        }
+    }
+
+    /// Fill in `display_transform` for a display context
+    pub fn apply_display_context(&mut self, context: DisplayContext) {
+        self.display_transform = Some(self.display_transform_for(context));
+    }
+}
+
+/// Models the game generates in code instead of loading from JSON
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum BuiltinModel {
+    /// `builtin/generated`: flat item built from `layer0`, `layer1`, ...
+    Generated,
+    /// `builtin/entity`: drawn by an entity renderer (chests, shulker boxes, ...)
+    Entity,
+}
+
+impl BuiltinModel {
+    /// Builtin named by a parent reference, if it is one
+    pub fn from_parent(parent_id: &str) -> Option<Self> {
+        match parent_id.strip_prefix("minecraft:").unwrap_or(parent_id) {
+            "builtin/generated" => Some(BuiltinModel::Generated),
+            "builtin/entity" => Some(BuiltinModel::Entity),
+            _ => None,
+        }
+    }
+}
+
+/// A texture reference in a model that points at nothing
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MissingTextureRef {
+    /// Texture variable (e.g., "side")
+    pub variable: String,
+    /// What it resolved to (e.g., "minecraft:block/foo" or "#top")
+    pub reference: String,
+    /// Why it's broken
+    pub reason: String,
 }
 
 /// A cuboid element in a Minecraft model
@@ -57,7 +219,7 @@ pub struct ModelElement {
 }
 
 /// Rotation information for a model element
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct ElementRotation {
     /// Origin point [x, y, z]
     pub origin: [f32; 3],
@@ -74,7 +236,7 @@ pub struct ElementRotation {
 }
 
 /// A face of a model element
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct ElementFace {
     /// Texture variable reference (e.g., "#all" or "#texture0")
     pub texture: String,
@@ -105,57 +267,51 @@ pub struct ElementFace {
 /// # Returns
 /// The parsed BlockModel JSON
 pub fn read_block_model(pack: &PackMeta, model_id: &str) -> AppResult<BlockModel> {
-    println!("[read_block_model] Reading model: {}", model_id);
-    println!(
-        "[read_block_model] From pack: {} at {}",
-        pack.name, pack.path
-    );
+    debug!("Reading model: {}", model_id);
+    debug!("From pack: {} at {}", pack.name, pack.path);
 
     // Normalize the model ID
     let normalized = normalize_model_id(model_id);
-    println!("[read_block_model] Normalized ID: {}", normalized);
+    debug!("Normalized ID: {}", normalized);
 
     // Convert to file path: "minecraft:block/dirt" -> "assets/minecraft/models/block/dirt.json"
     let relative_path = model_id_to_path(&normalized);
-    println!("[read_block_model] Relative path: {}", relative_path);
+    debug!("Relative path: {}", relative_path);
 
     let pack_path = Path::new(&pack.path);
 
     let contents = if pack.is_zip {
         // Read from ZIP archive
-        println!("[read_block_model] Pack is ZIP, extracting from archive");
+        debug!("Pack is ZIP, extracting from archive");
         let zip_path_str = pack_path
             .to_str()
             .ok_or_else(|| AppError::validation("Invalid pack path"))?;
 
         let bytes =
             crate::util::zip::extract_zip_entry(zip_path_str, &relative_path).map_err(|e| {
-                println!("[read_block_model] ✗ ZIP extraction failed: {}", e);
+                debug!("✗ ZIP extraction failed: {}", e);
                 AppError::validation(format!("Model not found in ZIP: {}", e))
             })?;
 
-        println!("[read_block_model] ✓ Successfully extracted from ZIP");
+        debug!("✓ Successfully extracted from ZIP");
         String::from_utf8(bytes)
             .map_err(|e| AppError::validation(format!("Invalid UTF-8 in model: {}", e)))?
     } else {
         // Directory pack - just read the file
         let full_path = pack_path.join(&relative_path);
-        println!(
-            "[read_block_model] Pack is directory, reading from: {}",
-            full_path.display()
-        );
+        debug!("Pack is directory, reading from: {}", full_path.display());
 
         if !full_path.exists() {
-            println!("[read_block_model] ✗ File does not exist");
+            debug!("✗ File does not exist");
             return Err(AppError::validation(format!(
                 "Model not found: {}",
                 relative_path
             )));
         }
 
-        println!("[read_block_model] ✓ File exists, reading...");
+        debug!("✓ File exists, reading...");
         fs::read_to_string(&full_path).map_err(|e| {
-            println!("[read_block_model] ✗ Failed to read file: {}", e);
+            debug!("✗ Failed to read file: {}", e);
             AppError::io(format!("Failed to read model file: {}", e))
         })?
     };
@@ -174,35 +330,26 @@ pub fn read_block_model_with_fallback(
     model_id: &str,
     vanilla_pack: &PackMeta,
 ) -> AppResult<BlockModel> {
-    println!(
-        "[read_block_model_with_fallback] Trying pack: {} ({})",
-        pack.name, pack.path
-    );
+    debug!("Trying pack: {} ({})", pack.name, pack.path);
     match read_block_model(pack, model_id) {
         Ok(model) => {
-            println!("[read_block_model_with_fallback] ✓ Found in pack");
+            debug!("✓ Found in pack");
             Ok(model)
         }
         Err(pack_err) => {
-            println!(
-                "[read_block_model_with_fallback] ✗ Not in pack: {}",
-                pack_err
-            );
-            println!(
-                "[read_block_model_with_fallback] Trying vanilla: {} ({})",
+            debug!("✗ Not in pack: {}", pack_err);
+            debug!(
+                "Trying vanilla: {} ({})",
                 vanilla_pack.name, vanilla_pack.path
             );
             // Try vanilla as fallback
             match read_block_model(vanilla_pack, model_id) {
                 Ok(model) => {
-                    println!("[read_block_model_with_fallback] ✓ Found in vanilla");
+                    debug!("✓ Found in vanilla");
                     Ok(model)
                 }
                 Err(vanilla_err) => {
-                    println!(
-                        "[read_block_model_with_fallback] ✗ Not in vanilla: {}",
-                        vanilla_err
-                    );
+                    debug!("✗ Not in vanilla: {}", vanilla_err);
                     Err(vanilla_err)
                 }
             }
@@ -212,13 +359,68 @@ pub fn read_block_model_with_fallback(
 
 /// Resolve a block model with all parent inheritance
 ///
-/// Recursively loads parent models and merges textures/elements
+/// Recursively loads parent models and merges textures/elements.
+/// Chains ending in `builtin/generated` get flat elements built from their
+/// layer textures; chains ending in `builtin/entity` are returned without
+/// elements and with `builtin` set, so callers can use entity rendering.
 pub fn resolve_block_model(
     pack: &PackMeta,
     model_id: &str,
     vanilla_pack: &PackMeta,
 ) -> AppResult<BlockModel> {
-    resolve_block_model_with_depth(pack, model_id, vanilla_pack, 0)
+    let mut model = resolve_block_model_with_depth(pack, model_id, vanilla_pack, 0)?;
+
+    if model.builtin == Some(BuiltinModel::Generated) && model.elements.is_none() {
+        let elements = generated_elements(model.textures.as_ref());
+        debug!(
+            "Generated {} layer element(s) for {}",
+            elements.len(),
+            model_id
+        );
+        model.elements = Some(elements);
+    }
+
+    Ok(model)
+}
+
+/// Flat elements for a `builtin/generated` item, one per layer texture
+///
+/// The game extrudes each layer pixel by pixel; a one-pixel-thick plane per
+/// layer is close enough for previews. Layers are tinted by their index,
+/// like the game does (e.g. leather armor overlays).
+fn generated_elements(textures: Option<&HashMap<String, String>>) -> Vec<ModelElement> {
+    let mut layers: Vec<u32> = textures
+        .into_iter()
+        .flat_map(|t| t.keys())
+        .filter_map(|key| key.strip_prefix("layer")?.parse().ok())
+        .collect();
+    layers.sort_unstable();
+
+    layers
+        .into_iter()
+        .enumerate()
+        .map(|(order, layer)| {
+            // Nudge later layers outward so they draw over earlier ones
+            let offset = order as f32 * 0.01;
+            let face = |uv: [f32; 4]| ElementFace {
+                texture: format!("#layer{}", layer),
+                uv: Some(uv),
+                rotation: None,
+                cullface: None,
+                tintindex: Some(layer as i32),
+            };
+            ModelElement {
+                from: [0.0, 0.0, 7.5 - offset],
+                to: [16.0, 16.0, 8.5 + offset],
+                rotation: None,
+                faces: HashMap::from([
+                    ("south".to_string(), face([0.0, 0.0, 16.0, 16.0])),
+                    ("north".to_string(), face([16.0, 0.0, 0.0, 16.0])),
+                ]),
+                shade: None,
+            }
+        })
+        .collect()
 }
 
 /// Internal function with depth tracking to prevent infinite recursion
@@ -237,28 +439,27 @@ fn resolve_block_model_with_depth(
         )));
     }
 
-    println!(
-        "[resolve_block_model] Depth {}: Loading model {}",
-        depth, model_id
-    );
+    debug!("Depth {}: Loading model {}", depth, model_id);
     let mut model = read_block_model_with_fallback(pack, model_id, vanilla_pack)?;
 
+    // Builtin parents have no JSON to load; mark the model instead
+    if let Some(builtin) = model.parent.as_deref().and_then(BuiltinModel::from_parent) {
+        debug!("Depth {}: Builtin parent {:?}", depth, builtin);
+        model.parent = None;
+        model.builtin = Some(builtin);
+        return Ok(model);
+    }
+
     // If there's a parent, recursively resolve it
     if let Some(parent_id) = &model.parent.clone() {
-        println!(
-            "[resolve_block_model] Depth {}: Found parent: {}",
-            depth, parent_id
-        );
+        debug!("Depth {}: Found parent: {}", depth, parent_id);
         let parent_model =
             resolve_block_model_with_depth(pack, parent_id, vanilla_pack, depth + 1)?;
 
         // Merge parent into current model
         model = merge_models(parent_model, model);
     } else {
-        println!(
-            "[resolve_block_model] Depth {}: No parent (base model)",
-            depth
-        );
+        debug!("Depth {}: No parent (base model)", depth);
     }
 
     Ok(model)
@@ -289,6 +490,15 @@ fn merge_models(parent: BlockModel, child: BlockModel) -> BlockModel {
         merged.ambientocclusion = child.ambientocclusion;
     }
 
+    // Child display transforms replace the parent's per context
+    if let Some(child_display) = child.display {
+        if let Some(parent_display) = &mut merged.display {
+            parent_display.extend(child_display);
+        } else {
+            merged.display = Some(child_display);
+        }
+    }
+
     // Clear parent reference since we've merged
     merged.parent = None;
 
@@ -349,6 +559,120 @@ pub fn resolve_textures(model: &BlockModel) -> HashMap<String, String> {
     resolved
 }
 
+/// Relative path of a texture ID inside a pack
+///
+/// "minecraft:block/dirt" -> "assets/minecraft/textures/block/dirt.png"
+fn texture_id_to_path(texture_id: &str) -> String {
+    let (namespace, path) = texture_id
+        .split_once(':')
+        .unwrap_or(("minecraft", texture_id));
+    format!("assets/{}/textures/{}.png", namespace, path)
+}
+
+/// Files of a pack, listed once so many lookups don't reopen a zip
+pub(crate) enum PackFiles {
+    Folder(std::path::PathBuf),
+    Zip(HashSet<String>),
+}
+
+impl PackFiles {
+    pub(crate) fn new(pack: &PackMeta) -> Self {
+        if pack.is_zip {
+            let names = crate::util::zip::list_zip_files(&pack.path).unwrap_or_default();
+            PackFiles::Zip(names.into_iter().collect())
+        } else {
+            PackFiles::Folder(Path::new(&pack.path).to_path_buf())
+        }
+    }
+
+    pub(crate) fn contains(&self, relative_path: &str) -> bool {
+        match self {
+            PackFiles::Folder(root) => root.join(relative_path).is_file(),
+            PackFiles::Zip(names) => names.contains(relative_path),
+        }
+    }
+}
+
+/// Replace texture references that point at nothing with the placeholder
+///
+/// A reference is broken if it names a variable the model never defines
+/// (including ones used by element faces), or a texture found in neither
+/// the pack nor vanilla. Each broken variable is pointed at
+/// `MISSING_TEXTURE_ID` and recorded in `model.missing_textures`, so
+/// previews render a checkerboard instead of failing.
+///
+/// # Returns
+/// Number of broken references found
+pub fn substitute_missing_textures(
+    model: &mut BlockModel,
+    pack: &PackMeta,
+    vanilla_pack: &PackMeta,
+) -> usize {
+    let resolved = resolve_textures(model);
+    let mut broken: Vec<MissingTextureRef> = Vec::new();
+
+    let mut pack_files: Option<(PackFiles, PackFiles)> = None;
+    let mut variables: Vec<(&String, &String)> = resolved.iter().collect();
+    variables.sort();
+    for (variable, value) in variables {
+        if value == MISSING_TEXTURE_ID {
+            continue;
+        }
+        let reason = if value.starts_with('#') {
+            format!("Variable {} is not defined", value)
+        } else {
+            let (in_pack, in_vanilla) = pack_files
+                .get_or_insert_with(|| (PackFiles::new(pack), PackFiles::new(vanilla_pack)));
+            let relative_path = texture_id_to_path(value);
+            if in_pack.contains(&relative_path) || in_vanilla.contains(&relative_path) {
+                continue;
+            }
+            format!("{} not found in {} or vanilla", relative_path, pack.name)
+        };
+        broken.push(MissingTextureRef {
+            variable: variable.clone(),
+            reference: value.clone(),
+            reason,
+        });
+    }
+
+    // Faces may use variables the textures map never mentions
+    let mut undefined: Vec<String> = model
+        .elements
+        .iter()
+        .flatten()
+        .flat_map(|element| element.faces.values())
+        .filter_map(|face| face.texture.strip_prefix('#'))
+        .filter(|variable| !resolved.contains_key(*variable))
+        .map(|variable| variable.to_string())
+        .collect();
+    undefined.sort();
+    undefined.dedup();
+    for variable in undefined {
+        broken.push(MissingTextureRef {
+            reference: format!("#{}", variable),
+            reason: format!("Variable #{} is used by a face but not defined", variable),
+            variable,
+        });
+    }
+
+    if broken.is_empty() {
+        return 0;
+    }
+
+    let textures = model.textures.get_or_insert_with(HashMap::new);
+    for missing in &broken {
+        warn!(
+            "#{} -> {}: {}",
+            missing.variable, missing.reference, missing.reason
+        );
+        textures.insert(missing.variable.clone(), MISSING_TEXTURE_ID.to_string());
+    }
+    let count = broken.len();
+    model.missing_textures.extend(broken);
+    count
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -405,6 +729,10 @@ mod tests {
             ])),
             elements: None,
             ambientocclusion: None,
+            missing_textures: Vec::new(),
+            builtin: None,
+            display: None,
+            display_transform: None,
         };
 
         let resolved = resolve_textures(&model);
@@ -428,6 +756,10 @@ mod tests {
             ])),
             elements: None,
             ambientocclusion: None,
+            missing_textures: Vec::new(),
+            builtin: None,
+            display: None,
+            display_transform: None,
         };
 
         let resolved = resolve_textures(&model);
@@ -452,6 +784,10 @@ mod tests {
             ])),
             elements: None,
             ambientocclusion: None,
+            missing_textures: Vec::new(),
+            builtin: None,
+            display: None,
+            display_transform: None,
         };
 
         let resolved = resolve_textures(&model);
@@ -476,6 +812,10 @@ mod tests {
             textures: None,
             elements: None,
             ambientocclusion: None,
+            missing_textures: Vec::new(),
+            builtin: None,
+            display: None,
+            display_transform: None,
         };
 
         let resolved = resolve_textures(&model);
@@ -492,6 +832,10 @@ mod tests {
             )])),
             elements: None,
             ambientocclusion: None,
+            missing_textures: Vec::new(),
+            builtin: None,
+            display: None,
+            display_transform: None,
         };
 
         let resolved = resolve_textures(&model);
@@ -509,6 +853,10 @@ mod tests {
             ])),
             elements: None,
             ambientocclusion: Some(true),
+            missing_textures: Vec::new(),
+            builtin: None,
+            display: None,
+            display_transform: None,
         };
 
         let child = BlockModel {
@@ -519,6 +867,10 @@ mod tests {
             )])),
             elements: None,
             ambientocclusion: None,
+            missing_textures: Vec::new(),
+            builtin: None,
+            display: None,
+            display_transform: None,
         };
 
         let merged = merge_models(parent, child);
@@ -560,6 +912,10 @@ mod tests {
             textures: None,
             elements: Some(parent_elements),
             ambientocclusion: None,
+            missing_textures: Vec::new(),
+            builtin: None,
+            display: None,
+            display_transform: None,
         };
 
         let child = BlockModel {
@@ -567,6 +923,10 @@ mod tests {
             textures: None,
             elements: Some(child_elements.clone()),
             ambientocclusion: None,
+            missing_textures: Vec::new(),
+            builtin: None,
+            display: None,
+            display_transform: None,
         };
 
         let merged = merge_models(parent, child);
@@ -587,6 +947,10 @@ mod tests {
             textures: None,
             elements: None,
             ambientocclusion: Some(true),
+            missing_textures: Vec::new(),
+            builtin: None,
+            display: None,
+            display_transform: None,
         };
 
         let child = BlockModel {
@@ -594,6 +958,10 @@ mod tests {
             textures: None,
             elements: None,
             ambientocclusion: Some(false),
+            missing_textures: Vec::new(),
+            builtin: None,
+            display: None,
+            display_transform: None,
         };
 
         let merged = merge_models(parent, child);
@@ -612,6 +980,10 @@ mod tests {
             )])),
             elements: None,
             ambientocclusion: Some(true),
+            missing_textures: Vec::new(),
+            builtin: None,
+            display: None,
+            display_transform: None,
         };
 
         let json = serde_json::to_string(&model).expect("should serialize");
@@ -660,4 +1032,172 @@ mod tests {
         assert!(deserialized.rotation.is_some());
         assert_eq!(deserialized.rotation.as_ref().unwrap().angle, 45.0);
     }
+
+    #[test]
+    fn test_substitute_missing_textures() {
+        let temp_dir = std::env::temp_dir().join("test_missing_textures");
+        let folder_pack = |name: &str, textures: &[&str]| {
+            let root = temp_dir.join(name);
+            let dir = root.join("assets/minecraft/textures/block");
+            fs::create_dir_all(&dir).expect("Failed to create test directory");
+            for texture in textures {
+                fs::write(dir.join(format!("{}.png", texture)), b"png").unwrap();
+            }
+            PackMeta {
+                id: name.to_string(),
+                name: name.to_string(),
+                path: root.to_string_lossy().to_string(),
+                ..Default::default()
+            }
+        };
+        let pack = folder_pack("pack", &["custom"]);
+        let vanilla = folder_pack("vanilla", &["stone"]);
+
+        let face = |texture: &str| ElementFace {
+            texture: texture.to_string(),
+            uv: None,
+            rotation: None,
+            cullface: None,
+            tintindex: None,
+        };
+        let mut model = BlockModel {
+            parent: None,
+            textures: Some(HashMap::from([
+                ("side".to_string(), "minecraft:block/custom".to_string()),
+                ("top".to_string(), "block/stone".to_string()),
+                ("bottom".to_string(), "minecraft:block/typo".to_string()),
+                ("particle".to_string(), "#nothing".to_string()),
+            ])),
+            elements: Some(vec![ModelElement {
+                from: [0.0, 0.0, 0.0],
+                to: [16.0, 16.0, 16.0],
+                rotation: None,
+                faces: HashMap::from([
+                    ("north".to_string(), face("#side")),
+                    ("up".to_string(), face("#front")),
+                ]),
+                shade: None,
+            }]),
+            ambientocclusion: None,
+            missing_textures: Vec::new(),
+            builtin: None,
+            display: None,
+            display_transform: None,
+        };
+
+        let count = substitute_missing_textures(&mut model, &pack, &vanilla);
+
+        // Clean up
+        fs::remove_dir_all(&temp_dir).ok();
+
+        assert_eq!(count, 3);
+        let broken: Vec<(&str, &str)> = model
+            .missing_textures
+            .iter()
+            .map(|m| (m.variable.as_str(), m.reference.as_str()))
+            .collect();
+        assert_eq!(
+            broken,
+            vec![
+                ("bottom", "minecraft:block/typo"),
+                ("particle", "#nothing"),
+                ("front", "#front"),
+            ]
+        );
+        let textures = model.textures.as_ref().unwrap();
+        assert_eq!(textures["bottom"], MISSING_TEXTURE_ID);
+        assert_eq!(textures["front"], MISSING_TEXTURE_ID);
+        assert_eq!(textures["side"], "minecraft:block/custom");
+        assert_eq!(textures["top"], "block/stone");
+
+        let json = serde_json::to_string(&model).unwrap();
+        assert!(json.contains("\"missingTextures\":"));
+    }
+
+    #[test]
+    fn test_resolve_builtin_parents() {
+        let temp_dir = std::env::temp_dir().join("test_builtin_models");
+        let models = temp_dir.join("assets/minecraft/models/item");
+        fs::create_dir_all(&models).expect("Failed to create test directory");
+        fs::write(
+            models.join("generated.json"),
+            r#"{"parent": "builtin/generated"}"#,
+        )
+        .unwrap();
+        fs::write(
+            models.join("leather_boots.json"),
+            r#"{"parent": "minecraft:item/generated", "textures": {
+                "layer1": "minecraft:item/leather_boots_overlay",
+                "layer0": "minecraft:item/leather_boots"}}"#,
+        )
+        .unwrap();
+        fs::write(models.join("chest.json"), r#"{"parent": "builtin/entity"}"#).unwrap();
+        let pack = PackMeta {
+            id: "pack".to_string(),
+            name: "pack".to_string(),
+            path: temp_dir.to_string_lossy().to_string(),
+            ..Default::default()
+        };
+
+        let boots = resolve_block_model(&pack, "item/leather_boots", &pack);
+        let chest = resolve_block_model(&pack, "item/chest", &pack);
+
+        // Clean up
+        fs::remove_dir_all(&temp_dir).ok();
+
+        let boots = boots.expect("generated model should resolve");
+        assert_eq!(boots.builtin, Some(BuiltinModel::Generated));
+        assert_eq!(boots.parent, None);
+        let elements = boots.elements.unwrap();
+        assert_eq!(elements.len(), 2);
+        assert_eq!(elements[0].faces["south"].texture, "#layer0");
+        assert_eq!(elements[1].faces["south"].texture, "#layer1");
+        assert_eq!(elements[1].faces["north"].tintindex, Some(1));
+
+        let chest = chest.expect("entity model should resolve");
+        assert_eq!(chest.builtin, Some(BuiltinModel::Entity));
+        assert!(chest.elements.is_none());
+    }
+
+    #[test]
+    fn test_display_transforms_merge_and_fallback() {
+        let parent: BlockModel = serde_json::from_str(
+            r#"{"display": {
+                "gui": {"rotation": [30, 225, 0], "scale": [0.625, 0.625, 0.625]},
+                "thirdperson_righthand": {"rotation": [75, 45, 0], "translation": [0, 2.5, 0]}
+            }}"#,
+        )
+        .unwrap();
+        let child: BlockModel = serde_json::from_str(
+            r#"{"parent": "block/block", "display": {
+                "ground": {"translation": [0, 200, 0], "scale": [9, 9, 9]}
+            }}"#,
+        )
+        .unwrap();
+
+        let mut merged = merge_models(parent, child);
+
+        let gui = merged.display_transform_for(DisplayContext::Gui);
+        assert_eq!(gui.rotation, [30.0, 225.0, 0.0]);
+        assert_eq!(gui.translation, [0.0, 0.0, 0.0]);
+        assert_eq!(gui.scale, [0.625, 0.625, 0.625]);
+
+        // Left hand falls back to the right hand
+        let left = merged.display_transform_for(DisplayContext::ThirdpersonLefthand);
+        assert_eq!(left.rotation, [75.0, 45.0, 0.0]);
+
+        // Out-of-range values are limited like in game
+        let ground = merged.display_transform_for(DisplayContext::Ground);
+        assert_eq!(ground.translation, [0.0, 80.0, 0.0]);
+        assert_eq!(ground.scale, [4.0, 4.0, 4.0]);
+
+        assert_eq!(
+            merged.display_transform_for(DisplayContext::Fixed),
+            DisplayTransform::default()
+        );
+
+        merged.apply_display_context(DisplayContext::Gui);
+        let json = serde_json::to_string(&merged).unwrap();
+        assert!(json.contains("\"displayTransform\":"));
+    }
 }
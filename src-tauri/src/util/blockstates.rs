@@ -7,6 +7,7 @@ use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::fs;
 use std::path::Path;
+use tracing::{debug, warn};
 
 /// A blockstate file structure
 ///
@@ -138,10 +139,7 @@ pub fn find_blockstate_file(pack_path: &Path, block_id: &str, is_zip: bool) -> O
     for file in blockstate_files {
         let normalized_file = file.replace('_', "").to_lowercase();
         if normalized_file == normalized_input {
-            println!(
-                "[find_blockstate_file] Matched '{}' -> '{}' via normalization",
-                block_id, file
-            );
+            debug!("Matched '{}' -> '{}' via normalization", block_id, file);
             return Some(file);
         }
     }
@@ -159,17 +157,14 @@ pub fn find_blockstate_file(pack_path: &Path, block_id: &str, is_zip: bool) -> O
 /// # Returns
 /// The parsed Blockstate structure
 pub fn read_blockstate(pack_path: &Path, block_id: &str, is_zip: bool) -> AppResult<Blockstate> {
-    println!("=== [read_blockstate] START ===");
-    println!("[read_blockstate] pack_path: {:?}", pack_path);
-    println!("[read_blockstate] block_id: {}", block_id);
-    println!("[read_blockstate] is_zip: {}", is_zip);
+    debug!("=== read_blockstate START ===");
+    debug!("pack_path: {:?}", pack_path);
+    debug!("block_id: {}", block_id);
+    debug!("is_zip: {}", is_zip);
 
     // Blockstates are at: assets/minecraft/blockstates/{block_id}.json
     let relative_path = format!("assets/minecraft/blockstates/{}.json", block_id);
-    println!(
-        "[read_blockstate] Constructed relative_path: {}",
-        relative_path
-    );
+    debug!("Constructed relative_path: {}", relative_path);
 
     let contents = if is_zip {
         // Read from ZIP archive
@@ -475,20 +470,20 @@ pub fn resolve_blockstate(
     state_props: Option<HashMap<String, String>>,
     seed: Option<u64>,
 ) -> AppResult<ResolutionResult> {
-    println!("=== [resolve_blockstate] START ===");
-    println!("[resolve_blockstate] block_id: {}", block_id);
-    println!("[resolve_blockstate] state_props: {:?}", state_props);
+    debug!("=== resolve_blockstate START ===");
+    debug!("block_id: {}", block_id);
+    debug!("state_props: {:?}", state_props);
 
     let props = state_props.unwrap_or_default();
-    println!("[resolve_blockstate] Using props: {:?}", props);
+    debug!("Using props: {:?}", props);
     let mut resolved_models = Vec::new();
 
     // Handle variants format
     if let Some(variants) = &blockstate.variants {
         let variant_key = make_variant_key(&props);
-        println!("[resolve_blockstate] Made variant key: '{}'", variant_key);
-        println!(
-            "[resolve_blockstate] Available variants: {:?}",
+        debug!("Made variant key: '{}'", variant_key);
+        debug!(
+            "Available variants: {:?}",
             variants.keys().collect::<Vec<_>>()
         );
 
@@ -499,29 +494,27 @@ pub fn resolve_blockstate(
             variants.len() == 1 && (variants.contains_key("") || variants.contains_key("normal"));
 
         let variant = if has_only_default {
-            println!(
-                "[resolve_blockstate] Blockstate has only default variant, using it regardless of props"
-            );
+            debug!("Blockstate has only default variant, using it regardless of props");
             variants.get("").or_else(|| variants.get("normal"))
         } else {
             // Try exact match, then empty string, then "normal"
             variants
                 .get(&variant_key)
                 .or_else(|| {
-                    println!("[resolve_blockstate] No exact match, trying empty string");
+                    debug!("No exact match, trying empty string");
                     variants.get("")
                 })
                 .or_else(|| {
-                    println!("[resolve_blockstate] No empty string, trying 'normal'");
+                    debug!("No empty string, trying 'normal'");
                     variants.get("normal")
                 })
         };
 
         if let Some(var) = variant {
-            println!("[resolve_blockstate] Found variant!");
+            debug!("Found variant!");
             collect_models_from_variant(var, seed, &mut resolved_models)?;
         } else {
-            println!("[resolve_blockstate] ERROR: No variant found!");
+            warn!("No variant found!");
             return Err(AppError::validation(format!(
                 "No variant found for key: '{}' in block '{}'",
                 variant_key, block_id
@@ -0,0 +1,494 @@
+/// Crash-safe build journal
+///
+/// Builds write into a staging folder in the app cache and keep a small
+/// journal (phase, files written, last file) in the config directory. The
+/// staged files are moved into the output only once everything is written.
+/// A build that fails cleans both up; a journal still present on the next
+/// launch means the build was interrupted: its staging folder can be
+/// discarded, or the build resumed, skipping files that were already staged.
+use crate::util::content_hash::xxh3_hex;
+use crate::util::{file_retry, instance_lock, path_policy, settings};
+use anyhow::{anyhow, Context, Result};
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use tracing::{info, warn};
+use walkdir::WalkDir;
+
+/// Journal directory inside the config directory
+const JOURNAL_DIR: &str = "build_journals";
+
+/// Staging directory inside the app's cache directory
+const STAGING_DIR: &str = "staging";
+
+/// Suffix of staged files that are still being written
+const PARTIAL_SUFFIX: &str = ".weaverbird-partial";
+
+/// Files written between journal flushes
+const FLUSH_INTERVAL: usize = 200;
+
+/// Output folders with a build running in this process
+static ACTIVE_BUILDS: Lazy<Mutex<HashSet<String>>> = Lazy::new(|| Mutex::new(HashSet::new()));
+
+/// Stage a build is in
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum BuildPhase {
+    /// Copying winning files into staging
+    Copying,
+    /// Checking and fixing up staged textures
+    PostProcessing,
+    /// Built, holding the install until Minecraft exits
+    WaitingForGame,
+    /// Moving staged files into the output
+    Finalizing,
+}
+
+/// Persisted state of a running build
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BuildJournal {
+    pub output_dir: String,
+    pub staging_dir: String,
+    pub phase: BuildPhase,
+    pub files_written: usize,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last_file: Option<String>,
+    /// Seconds since the Unix epoch
+    pub started_at: u64,
+    pub updated_at: u64,
+    /// Build request, kept so the build can be resumed
+    pub request: serde_json::Value,
+}
+
+/// Journal of a build that didn't finish
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct InterruptedBuild {
+    #[serde(flatten)]
+    pub journal: BuildJournal,
+    /// Whether the staging folder still exists (resuming reuses it)
+    pub staging_exists: bool,
+}
+
+fn now_secs() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+/// Default journal directory (~/.config/weaverbird/build_journals)
+pub fn default_journal_dir() -> Result<PathBuf> {
+    Ok(settings::get_config_dir()?.join(JOURNAL_DIR))
+}
+
+/// Staging folder for an output folder, in the app cache
+///
+/// "/packs/Weaver Nest" stages into "<cache>/weaverbird/staging/Weaver Nest-<hash>".
+/// Staging there rather than next to the output keeps half-built packs out
+/// of the user's folders and inside the path policy.
+pub fn staging_dir_for(output_dir: &Path) -> Result<PathBuf> {
+    let name = output_dir
+        .file_name()
+        .ok_or_else(|| anyhow!("Output directory has no name: {:?}", output_dir))?;
+    let cache_dir =
+        dirs::cache_dir().ok_or_else(|| anyhow!("Could not determine cache directory"))?;
+    let staging = cache_dir.join("weaverbird").join(STAGING_DIR).join(format!(
+        "{}-{}",
+        name.to_string_lossy(),
+        xxh3_hex(output_dir.to_string_lossy().as_bytes())
+    ));
+    path_policy::check(&staging)?;
+    Ok(staging)
+}
+
+/// Journal file for an output folder
+fn journal_path(journal_dir: &Path, output_dir: &str) -> PathBuf {
+    journal_dir.join(format!("{}.json", xxh3_hex(output_dir.as_bytes())))
+}
+
+/// Read the journal of an output folder, if one exists
+pub fn read_journal(journal_dir: &Path, output_dir: &str) -> Option<BuildJournal> {
+    let contents = fs::read_to_string(journal_path(journal_dir, output_dir)).ok()?;
+    serde_json::from_str(&contents).ok()
+}
+
+/// Journals of builds that didn't finish, excluding builds running now in
+/// this or another instance
+pub fn list_interrupted_builds(journal_dir: &Path) -> Vec<InterruptedBuild> {
+    let active = ACTIVE_BUILDS.lock().map(|a| a.clone()).unwrap_or_default();
+    let entries = match fs::read_dir(journal_dir) {
+        Ok(entries) => entries,
+        Err(_) => return Vec::new(),
+    };
+
+    let mut builds: Vec<InterruptedBuild> = entries
+        .filter_map(|e| e.ok())
+        .filter(|e| e.path().extension().map_or(false, |ext| ext == "json"))
+        .filter_map(|e| fs::read_to_string(e.path()).ok())
+        .filter_map(|contents| serde_json::from_str::<BuildJournal>(&contents).ok())
+        .filter(|journal| !active.contains(&journal.output_dir))
+        .filter(|journal| !instance_lock::is_locked(Path::new(&journal.output_dir)))
+        .map(|journal| InterruptedBuild {
+            staging_exists: Path::new(&journal.staging_dir).exists(),
+            journal,
+        })
+        .collect();
+    builds.sort_by_key(|b| std::cmp::Reverse(b.journal.updated_at));
+    builds
+}
+
+/// Remove an interrupted build's staging folder and journal
+///
+/// # Returns
+/// True if a journal was found
+pub fn discard_interrupted_build(journal_dir: &Path, output_dir: &str) -> Result<bool> {
+    let journal = match read_journal(journal_dir, output_dir) {
+        Some(journal) => journal,
+        None => return Ok(false),
+    };
+
+    let staging = Path::new(&journal.staging_dir);
+    if staging.exists() {
+        fs::remove_dir_all(staging)
+            .with_context(|| format!("Failed to remove staging folder {:?}", staging))?;
+    }
+    fs::remove_file(journal_path(journal_dir, output_dir)).ok();
+    info!("Discarded interrupted build of {}", output_dir);
+    Ok(true)
+}
+
+/// Write a staged file under a temporary name and rename it into place
+///
+/// A staged file that exists is therefore complete, which is what lets a
+/// resumed build skip it. Unlike settings, staged files aren't synced to disk
+/// one by one; that would make large builds crawl.
+pub fn write_staged_file(path: &Path, contents: &[u8]) -> Result<()> {
+    let file_name = path
+        .file_name()
+        .ok_or_else(|| anyhow!("Invalid file path: {:?}", path))?;
+    let partial = path.with_file_name(format!("{}{}", file_name.to_string_lossy(), PARTIAL_SUFFIX));
+    file_retry::write_with_retry(&partial, contents)?;
+    file_retry::rename_with_retry(&partial, path)
+}
+
+/// Move every staged file into the output folder, then remove staging
+///
+/// Files already in the output are overwritten; other files are left alone.
+/// Files are renamed into place, or copied when the output is on another
+/// volume than the cache.
+pub fn promote_staging(staging_dir: &Path, output_dir: &Path) -> Result<usize> {
+    let mut moved = 0;
+    for entry in WalkDir::new(staging_dir)
+        .into_iter()
+        .filter_map(|e| e.ok())
+        .filter(|e| e.file_type().is_file())
+        .filter(|e| !e.file_name().to_string_lossy().ends_with(PARTIAL_SUFFIX))
+    {
+        let relative = entry.path().strip_prefix(staging_dir)?;
+        let target = output_dir.join(relative);
+        if let Some(parent) = target.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        if let Err(e) = file_retry::rename_with_retry(entry.path(), &target) {
+            if !file_retry::is_cross_device(&e) {
+                return Err(e)
+                    .with_context(|| format!("Failed to move {:?} into the output", relative));
+            }
+            file_retry::copy_with_retry(entry.path(), &target)
+                .with_context(|| format!("Failed to copy {:?} into the output", relative))?;
+        }
+        moved += 1;
+    }
+
+    fs::remove_dir_all(staging_dir).ok();
+    Ok(moved)
+}
+
+/// Journal of the build running in this process
+///
+/// A build that fails drops the writer without calling `complete`, which
+/// removes the journal and the staging folder: the failure was reported,
+/// so there is nothing to resume. Only a build that never got to return
+/// (crash, power loss, app closed) leaves its journal on disk, which is
+/// exactly what marks it as interrupted.
+pub struct JournalWriter {
+    path: PathBuf,
+    state: Mutex<BuildJournal>,
+    completed: bool,
+}
+
+impl JournalWriter {
+    /// Start journaling a build
+    ///
+    /// # Arguments
+    /// * `journal_dir` - Where journals are kept
+    /// * `output_dir` - Final output folder
+    /// * `staging_dir` - Folder files are written to first
+    /// * `request` - Build request, stored for resuming
+    pub fn start(
+        journal_dir: &Path,
+        output_dir: &str,
+        staging_dir: &Path,
+        request: serde_json::Value,
+    ) -> Result<JournalWriter> {
+        fs::create_dir_all(journal_dir).context("Failed to create journal directory")?;
+
+        {
+            let mut active = ACTIVE_BUILDS
+                .lock()
+                .map_err(|_| anyhow!("Build registry lock poisoned"))?;
+            if !active.insert(output_dir.to_string()) {
+                return Err(anyhow!("A build into {} is already running", output_dir));
+            }
+        }
+
+        let now = now_secs();
+        let writer = JournalWriter {
+            path: journal_path(journal_dir, output_dir),
+            state: Mutex::new(BuildJournal {
+                output_dir: output_dir.to_string(),
+                staging_dir: staging_dir.to_string_lossy().to_string(),
+                phase: BuildPhase::Copying,
+                files_written: 0,
+                last_file: None,
+                started_at: now,
+                updated_at: now,
+                request,
+            }),
+            completed: false,
+        };
+        writer.flush()?;
+        Ok(writer)
+    }
+
+    fn flush(&self) -> Result<()> {
+        let json = {
+            let mut state = self
+                .state
+                .lock()
+                .map_err(|_| anyhow!("Journal lock poisoned"))?;
+            state.updated_at = now_secs();
+            serde_json::to_string_pretty(&*state)?
+        };
+        settings::write_file_atomic(&self.path, json.as_bytes())
+    }
+
+    /// Move to a new phase and persist it
+    pub fn set_phase(&self, phase: BuildPhase) {
+        if let Ok(mut state) = self.state.lock() {
+            state.phase = phase;
+        }
+        if let Err(e) = self.flush() {
+            warn!("Failed to update journal: {}", e);
+        }
+    }
+
+    /// Record a written file; the journal is persisted periodically
+    pub fn record_file(&self, file: &str) {
+        let should_flush = match self.state.lock() {
+            Ok(mut state) => {
+                state.files_written += 1;
+                state.last_file = Some(file.to_string());
+                state.files_written % FLUSH_INTERVAL == 0
+            }
+            Err(_) => false,
+        };
+        if should_flush {
+            if let Err(e) = self.flush() {
+                warn!("Failed to update journal: {}", e);
+            }
+        }
+    }
+
+    /// Files recorded so far
+    pub fn files_written(&self) -> usize {
+        self.state.lock().map(|s| s.files_written).unwrap_or(0)
+    }
+
+    /// Mark the build finished and delete the journal
+    ///
+    /// The staging folder is left for the caller (e.g. a queued install
+    /// still needs it).
+    pub fn complete(mut self) -> Result<()> {
+        self.completed = true;
+        fs::remove_file(&self.path).or_else(|e| {
+            if e.kind() == std::io::ErrorKind::NotFound {
+                Ok(())
+            } else {
+                Err(e)
+            }
+        })?;
+        Ok(())
+    }
+}
+
+impl Drop for JournalWriter {
+    fn drop(&mut self) {
+        let state = match self.state.lock() {
+            Ok(state) => state,
+            Err(_) => return,
+        };
+        if let Ok(mut active) = ACTIVE_BUILDS.lock() {
+            active.remove(&state.output_dir);
+        }
+        if !self.completed {
+            info!("Cleaning up after failed build of {}", state.output_dir);
+            fs::remove_dir_all(&state.staging_dir).ok();
+            fs::remove_file(&self.path).ok();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_staging_dir_for() {
+        let staging = staging_dir_for(Path::new("/packs/Weaver Nest")).unwrap();
+        let other = staging_dir_for(Path::new("/other/Weaver Nest")).unwrap();
+
+        assert!(staging.starts_with(dirs::cache_dir().unwrap().join("weaverbird")));
+        assert!(staging
+            .file_name()
+            .unwrap()
+            .to_string_lossy()
+            .starts_with("Weaver Nest-"));
+        assert_ne!(staging, other);
+    }
+
+    #[test]
+    fn test_interrupted_build_is_listed_and_discarded() {
+        let temp_dir = std::env::temp_dir().join("test_build_journal_interrupted");
+        let journal_dir = temp_dir.join("journals");
+        let output = temp_dir.join("out").to_string_lossy().to_string();
+        let staging = temp_dir.join("staging");
+        fs::create_dir_all(&staging).expect("Failed to create test directory");
+
+        let writer = JournalWriter::start(
+            &journal_dir,
+            &output,
+            &staging,
+            serde_json::json!({"output_dir": output}),
+        )
+        .expect("should start journal");
+        writer.record_file("pack.mcmeta");
+        let while_running = list_interrupted_builds(&journal_dir).len();
+        writer.set_phase(BuildPhase::PostProcessing);
+        // Simulate a crash: the writer goes away without cleaning up
+        ACTIVE_BUILDS.lock().unwrap().remove(&output);
+        std::mem::forget(writer);
+
+        let interrupted = list_interrupted_builds(&journal_dir);
+        let discarded = discard_interrupted_build(&journal_dir, &output);
+        let after = list_interrupted_builds(&journal_dir).len();
+        let staging_left = staging.exists();
+
+        // Clean up
+        fs::remove_dir_all(&temp_dir).ok();
+
+        assert_eq!(while_running, 0);
+        assert_eq!(interrupted.len(), 1);
+        assert_eq!(interrupted[0].journal.phase, BuildPhase::PostProcessing);
+        assert_eq!(interrupted[0].journal.files_written, 1);
+        assert_eq!(
+            interrupted[0].journal.last_file.as_deref(),
+            Some("pack.mcmeta")
+        );
+        assert!(interrupted[0].staging_exists);
+        assert!(discarded.expect("discard should succeed"));
+        assert_eq!(after, 0);
+        assert!(!staging_left);
+    }
+
+    #[test]
+    fn test_completed_build_leaves_no_journal() {
+        let temp_dir = std::env::temp_dir().join("test_build_journal_complete");
+        let journal_dir = temp_dir.join("journals");
+        let output = temp_dir.join("out").to_string_lossy().to_string();
+
+        let writer = JournalWriter::start(
+            &journal_dir,
+            &output,
+            &temp_dir.join("staging"),
+            serde_json::Value::Null,
+        )
+        .expect("should start journal");
+        let second = JournalWriter::start(
+            &journal_dir,
+            &output,
+            &temp_dir.join("staging"),
+            serde_json::Value::Null,
+        );
+        writer.complete().expect("should complete");
+        let remaining = list_interrupted_builds(&journal_dir).len();
+
+        // Clean up
+        fs::remove_dir_all(&temp_dir).ok();
+
+        assert!(
+            second.is_err(),
+            "concurrent builds into one folder are refused"
+        );
+        assert_eq!(remaining, 0);
+    }
+
+    #[test]
+    fn test_failed_build_cleans_up() {
+        let temp_dir = std::env::temp_dir().join("test_build_journal_failed");
+        let journal_dir = temp_dir.join("journals");
+        let output = temp_dir.join("out").to_string_lossy().to_string();
+        let staging = temp_dir.join("staging");
+        fs::create_dir_all(&staging).expect("Failed to create test directory");
+        fs::write(staging.join("pack.mcmeta"), "{}").unwrap();
+
+        let writer = JournalWriter::start(&journal_dir, &output, &staging, serde_json::Value::Null)
+            .expect("should start journal");
+        // The build returns an error, dropping the writer
+        drop(writer);
+        let interrupted = list_interrupted_builds(&journal_dir).len();
+        let staging_left = staging.exists();
+
+        // Clean up
+        fs::remove_dir_all(&temp_dir).ok();
+
+        assert_eq!(interrupted, 0);
+        assert!(!staging_left);
+    }
+
+    #[test]
+    fn test_promote_staging_keeps_unrelated_output_files() {
+        let temp_dir = std::env::temp_dir().join("test_build_journal_promote");
+        let staging = temp_dir.join("staging");
+        let output = temp_dir.join("out");
+        fs::create_dir_all(staging.join("assets")).expect("Failed to create test directory");
+        fs::create_dir_all(&output).expect("Failed to create test directory");
+        fs::write(staging.join("pack.mcmeta"), "new").unwrap();
+        fs::write(staging.join("assets/a.png"), "a").unwrap();
+        fs::write(staging.join("assets/b.png.weaverbird-partial"), "b").unwrap();
+        fs::write(output.join("pack.mcmeta"), "old").unwrap();
+        fs::write(output.join("notes.txt"), "keep").unwrap();
+
+        let moved = promote_staging(&staging, &output);
+        let mcmeta = fs::read_to_string(output.join("pack.mcmeta"));
+        let notes_kept = output.join("notes.txt").exists();
+        let asset_moved = output.join("assets/a.png").exists();
+        let partial_moved = output.join("assets/b.png.weaverbird-partial").exists();
+        let staging_left = staging.exists();
+
+        // Clean up
+        fs::remove_dir_all(&temp_dir).ok();
+
+        assert_eq!(moved.expect("promote should succeed"), 2);
+        assert_eq!(mcmeta.unwrap(), "new");
+        assert!(notes_kept);
+        assert!(asset_moved);
+        assert!(!partial_moved);
+        assert!(!staging_left);
+    }
+}
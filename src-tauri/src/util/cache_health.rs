@@ -0,0 +1,341 @@
+/// Cache health checks and self-repair
+///
+/// Verifies the vanilla texture cache against its source JAR and the preview
+/// cache of textures extracted from ZIP packs. Interrupted extractions (files
+/// present but no marker) and missing or truncated files are repaired by
+/// re-extracting only what's missing instead of wiping the whole cache.
+use crate::util::instance_lock;
+use crate::util::vanilla_textures::{self, ExtractableEntry, ExtractionManifest, ProgressCallback};
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use tracing::info;
+use walkdir::WalkDir;
+
+/// Overall state of a cache
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum CacheStatus {
+    /// Cache is complete and consistent
+    Healthy,
+    /// Cache has never been populated
+    Missing,
+    /// Cache is incomplete or its marker is missing
+    Partial,
+    /// Problems were found and fixed
+    Repaired,
+}
+
+/// Health of the vanilla texture cache
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VanillaCacheHealth {
+    pub status: CacheStatus,
+    pub cache_dir: String,
+    /// Version recorded in the extraction manifest (or legacy marker)
+    pub marker_version: Option<String>,
+    /// JAR the cache was checked against
+    pub jar_path: Option<String>,
+    pub expected_files: usize,
+    /// Files missing or truncated before repair
+    pub missing_files: usize,
+    pub repaired_files: usize,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// Health of the preview cache (textures extracted from ZIP packs)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PreviewCacheHealth {
+    pub status: CacheStatus,
+    pub cache_dir: String,
+    pub files: usize,
+    /// Empty or undecodable files found
+    pub corrupt_files: usize,
+    /// Corrupt files deleted (they are re-extracted on demand)
+    pub removed_files: usize,
+}
+
+/// Combined cache health report
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CacheHealthReport {
+    pub vanilla: VanillaCacheHealth,
+    pub preview: PreviewCacheHealth,
+}
+
+/// Get the directory holding textures extracted from ZIP packs for previews
+pub fn get_preview_cache_dir() -> Option<PathBuf> {
+    dirs::cache_dir().map(|dir| dir.join("weaverbird_textures"))
+}
+
+/// Verify all caches, optionally repairing problems
+pub fn verify_caches(
+    repair: bool,
+    progress_callback: Option<ProgressCallback>,
+) -> Result<CacheHealthReport> {
+    let vanilla_dir = vanilla_textures::get_vanilla_cache_dir()?;
+    let manifest = vanilla_textures::read_manifest(&vanilla_dir);
+    let marker_version = vanilla_textures::get_cached_version()?;
+    let has_files = vanilla_dir.join("assets").exists();
+
+    // Pick the JAR to compare against: the recorded source JAR, the marker's
+    // version, or the latest installed version if an extraction was
+    // interrupted before anything was recorded
+    let jar_path = match (&manifest, &marker_version) {
+        (Some(m), _) if Path::new(&m.jar_path).exists() => Some(Ok(PathBuf::from(&m.jar_path))),
+        (_, Some(version)) => Some(vanilla_textures::find_version_jar(version)),
+        (_, None) if has_files => Some(vanilla_textures::find_latest_version_jar()),
+        _ => None,
+    };
+
+    let vanilla = match jar_path {
+        None => VanillaCacheHealth {
+            status: CacheStatus::Missing,
+            cache_dir: vanilla_dir.to_string_lossy().to_string(),
+            marker_version,
+            jar_path: None,
+            expected_files: 0,
+            missing_files: 0,
+            repaired_files: 0,
+            error: None,
+        },
+        Some(Err(e)) => VanillaCacheHealth {
+            status: CacheStatus::Partial,
+            cache_dir: vanilla_dir.to_string_lossy().to_string(),
+            marker_version,
+            jar_path: None,
+            expected_files: 0,
+            missing_files: 0,
+            repaired_files: 0,
+            error: Some(format!("Source JAR unavailable: {}", e)),
+        },
+        Some(Ok(jar)) => {
+            check_vanilla_cache(&vanilla_dir, &jar, manifest, repair, progress_callback)?
+        }
+    };
+
+    let preview = match get_preview_cache_dir() {
+        Some(dir) => check_preview_cache(&dir, repair),
+        None => PreviewCacheHealth {
+            status: CacheStatus::Missing,
+            cache_dir: String::new(),
+            files: 0,
+            corrupt_files: 0,
+            removed_files: 0,
+        },
+    };
+
+    Ok(CacheHealthReport { vanilla, preview })
+}
+
+/// Check a vanilla cache directory against its source JAR
+pub fn check_vanilla_cache(
+    cache_dir: &Path,
+    jar_path: &Path,
+    manifest: Option<ExtractionManifest>,
+    repair: bool,
+    progress_callback: Option<ProgressCallback>,
+) -> Result<VanillaCacheHealth> {
+    let entries = vanilla_textures::list_extractable_entries(jar_path)?;
+    let missing: Vec<ExtractableEntry> = entries
+        .iter()
+        .filter(|entry| !is_entry_intact(cache_dir, entry))
+        .cloned()
+        .collect();
+
+    let marker_ok = match &manifest {
+        Some(manifest) => manifest.matches_jar(jar_path)?,
+        None => false,
+    };
+
+    info!(
+        "Vanilla cache: {}/{} files missing (marker ok: {})",
+        missing.len(),
+        entries.len(),
+        marker_ok
+    );
+
+    let mut repaired_files = 0;
+    let status = if missing.is_empty() && marker_ok {
+        CacheStatus::Healthy
+    } else if repair {
+        let _cache_lock =
+            instance_lock::InstanceLock::acquire(cache_dir, "repairing the vanilla texture cache")?;
+        vanilla_textures::extract_entries(jar_path, cache_dir, &missing, progress_callback, None)?;
+        vanilla_textures::write_manifest(cache_dir, &ExtractionManifest::for_jar(jar_path)?)?;
+        repaired_files = missing.len();
+        CacheStatus::Repaired
+    } else {
+        CacheStatus::Partial
+    };
+
+    Ok(VanillaCacheHealth {
+        status,
+        cache_dir: cache_dir.to_string_lossy().to_string(),
+        marker_version: manifest.map(|m| m.version),
+        jar_path: Some(jar_path.to_string_lossy().to_string()),
+        expected_files: entries.len(),
+        missing_files: missing.len(),
+        repaired_files,
+        error: None,
+    })
+}
+
+/// Whether a cached file exists with the size recorded in the JAR
+fn is_entry_intact(cache_dir: &Path, entry: &ExtractableEntry) -> bool {
+    fs::metadata(cache_dir.join(&entry.rel_path))
+        .map(|m| m.is_file() && m.len() == entry.size)
+        .unwrap_or(false)
+}
+
+/// Check the preview cache for empty or undecodable images
+pub fn check_preview_cache(cache_dir: &Path, repair: bool) -> PreviewCacheHealth {
+    let cache_dir_str = cache_dir.to_string_lossy().to_string();
+    if !cache_dir.exists() {
+        return PreviewCacheHealth {
+            status: CacheStatus::Missing,
+            cache_dir: cache_dir_str,
+            files: 0,
+            corrupt_files: 0,
+            removed_files: 0,
+        };
+    }
+
+    let files: Vec<PathBuf> = WalkDir::new(cache_dir)
+        .into_iter()
+        .filter_map(|e| e.ok())
+        .filter(|e| e.file_type().is_file())
+        .map(|e| e.into_path())
+        .collect();
+
+    let corrupt: Vec<&PathBuf> = files.iter().filter(|f| is_corrupt_cached_file(f)).collect();
+
+    let removed_files = if repair {
+        corrupt
+            .iter()
+            .filter(|f| fs::remove_file(f).is_ok())
+            .count()
+    } else {
+        0
+    };
+
+    let status = match (corrupt.is_empty(), repair) {
+        (true, _) => CacheStatus::Healthy,
+        (false, true) => CacheStatus::Repaired,
+        (false, false) => CacheStatus::Partial,
+    };
+
+    PreviewCacheHealth {
+        status,
+        cache_dir: cache_dir_str,
+        files: files.len(),
+        corrupt_files: corrupt.len(),
+        removed_files,
+    }
+}
+
+/// Whether a cached file is empty or an unreadable PNG
+fn is_corrupt_cached_file(path: &Path) -> bool {
+    let len = fs::metadata(path).map(|m| m.len()).unwrap_or(0);
+    if len == 0 {
+        return true;
+    }
+
+    let is_png = path
+        .extension()
+        .map_or(false, |ext| ext.eq_ignore_ascii_case("png"));
+    is_png && image::image_dimensions(path).is_err()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+    use zip::write::FileOptions;
+
+    fn write_test_jar(path: &Path, entries: &[(&str, &[u8])]) {
+        fs::create_dir_all(path.parent().unwrap()).expect("Failed to create jar directory");
+        let file = fs::File::create(path).expect("Failed to create test jar");
+        let mut writer = zip::ZipWriter::new(file);
+        for (name, data) in entries {
+            writer
+                .start_file(*name, FileOptions::default())
+                .expect("Failed to start jar entry");
+            writer.write_all(data).expect("Failed to write jar entry");
+        }
+        writer.finish().expect("Failed to finish test jar");
+    }
+
+    #[test]
+    fn test_repair_partial_vanilla_cache() {
+        let temp_dir = std::env::temp_dir().join("test_cache_health_vanilla");
+        let jar_path = temp_dir.join("versions/1.21.4/1.21.4.jar");
+        let cache_dir = temp_dir.join("cache");
+        write_test_jar(
+            &jar_path,
+            &[
+                ("assets/minecraft/textures/block/stone.png", b"stone"),
+                ("assets/minecraft/textures/block/dirt.png", b"dirt"),
+                ("assets/minecraft/models/block/dirt.json", b"{}"),
+                ("net/minecraft/Main.class", b"code"),
+            ],
+        );
+
+        // Simulate an interrupted extraction: one file present, one truncated, no marker
+        let textures = cache_dir.join("assets/minecraft/textures/block");
+        fs::create_dir_all(&textures).expect("Failed to create cache");
+        fs::write(textures.join("stone.png"), b"stone").expect("Failed to write file");
+        fs::write(textures.join("dirt.png"), b"di").expect("Failed to write file");
+
+        let check = check_vanilla_cache(&cache_dir, &jar_path, None, false, None);
+        let repair = check_vanilla_cache(&cache_dir, &jar_path, None, true, None);
+        let dirt = fs::read(textures.join("dirt.png")).ok();
+        let manifest = vanilla_textures::read_manifest(&cache_dir);
+        let recheck = check_vanilla_cache(&cache_dir, &jar_path, manifest.clone(), false, None);
+
+        // Clean up
+        fs::remove_dir_all(&temp_dir).ok();
+
+        let check = check.expect("check should succeed");
+        assert_eq!(check.status, CacheStatus::Partial);
+        assert_eq!(check.expected_files, 3);
+        assert_eq!(check.missing_files, 2);
+
+        let repair = repair.expect("repair should succeed");
+        assert_eq!(repair.status, CacheStatus::Repaired);
+        assert_eq!(repair.repaired_files, 2);
+        assert_eq!(dirt.as_deref(), Some(&b"dirt"[..]));
+        assert_eq!(manifest.map(|m| m.version).as_deref(), Some("1.21.4"));
+
+        assert_eq!(recheck.unwrap().status, CacheStatus::Healthy);
+    }
+
+    #[test]
+    fn test_preview_cache_removes_corrupt_files() {
+        let temp_dir = std::env::temp_dir().join("test_cache_health_preview");
+        fs::create_dir_all(&temp_dir).expect("Failed to create test directory");
+        image::RgbaImage::new(4, 4)
+            .save(temp_dir.join("good.png"))
+            .expect("Failed to write image");
+        fs::write(temp_dir.join("empty.png"), b"").expect("Failed to write file");
+        fs::write(temp_dir.join("garbage.png"), b"not a png").expect("Failed to write file");
+
+        let check = check_preview_cache(&temp_dir, false);
+        let repair = check_preview_cache(&temp_dir, true);
+        let good_kept = temp_dir.join("good.png").exists();
+        let garbage_removed = !temp_dir.join("garbage.png").exists();
+
+        // Clean up
+        fs::remove_dir_all(&temp_dir).ok();
+
+        assert_eq!(check.status, CacheStatus::Partial);
+        assert_eq!(check.corrupt_files, 2);
+        assert_eq!(repair.status, CacheStatus::Repaired);
+        assert_eq!(repair.removed_files, 2);
+        assert!(good_kept);
+        assert!(garbage_removed);
+    }
+}
@@ -0,0 +1,473 @@
+/// Cloud-synced, network and read-only pack folder detection
+///
+/// Packs folders inside OneDrive, Dropbox, iCloud Drive or Google Drive may
+/// hold placeholder files whose contents haven't been downloaded yet. Reading
+/// them can hang, fail, or return garbage, which used to surface as cryptic
+/// zip errors. This module spots such folders and placeholders up front so
+/// scans can skip them with a clear message, and can ask the sync client to
+/// download them.
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::io::Read;
+use std::path::{Component, Path, PathBuf};
+use tracing::debug;
+
+/// Name of the file used to test whether a folder is writable
+const WRITE_PROBE: &str = ".weaverbird-write-probe";
+
+/// Cloud storage client that syncs a folder
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum SyncProvider {
+    OneDrive,
+    Dropbox,
+    ICloud,
+    GoogleDrive,
+}
+
+impl SyncProvider {
+    fn label(self) -> &'static str {
+        match self {
+            SyncProvider::OneDrive => "OneDrive",
+            SyncProvider::Dropbox => "Dropbox",
+            SyncProvider::ICloud => "iCloud Drive",
+            SyncProvider::GoogleDrive => "Google Drive",
+        }
+    }
+}
+
+/// How a placeholder was recognized
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum PlaceholderKind {
+    /// iCloud replaced the file with a hidden ".name.icloud" stub
+    ICloudStub,
+    /// The file reports a size but has no data on disk
+    Dataless,
+    /// Windows marks the file as stored only in the cloud
+    CloudOnly,
+}
+
+/// A pack whose contents aren't available locally
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PlaceholderFile {
+    /// Pack file name as it will appear once downloaded
+    pub name: String,
+    /// Path of the file on disk (the stub for iCloud)
+    pub path: String,
+    pub kind: PlaceholderKind,
+}
+
+/// What a packs folder's location means for reading and writing it
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FolderAccessReport {
+    pub path: String,
+    pub sync_provider: Option<SyncProvider>,
+    /// Folder is on a network share
+    pub network: bool,
+    /// Nothing can be written into the folder
+    pub read_only: bool,
+    /// Packs that need downloading before they can be read
+    pub placeholders: Vec<PlaceholderFile>,
+    /// Human-readable explanation of each problem found
+    pub warnings: Vec<String>,
+}
+
+/// Outcome of asking for a placeholder to be downloaded
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum DownloadStatus {
+    /// Contents are now on disk
+    Downloaded,
+    /// The sync client accepted the request and downloads in the background
+    Requested,
+    Failed,
+}
+
+/// Download result for one placeholder
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PlaceholderDownload {
+    pub name: String,
+    pub status: DownloadStatus,
+    pub error: Option<String>,
+}
+
+/// Recognize the sync client from the folder names in a path
+pub fn detect_sync_provider(path: &Path) -> Option<SyncProvider> {
+    for component in path.components() {
+        let name = match component {
+            Component::Normal(name) => name.to_string_lossy().to_lowercase(),
+            _ => continue,
+        };
+        // macOS File Provider folders are named "<Provider>-<account>"
+        // under ~/Library/CloudStorage
+        if name == "onedrive" || name.starts_with("onedrive -") || name.starts_with("onedrive-") {
+            return Some(SyncProvider::OneDrive);
+        }
+        if name == "dropbox" || name.starts_with("dropbox (") || name.starts_with("dropbox-") {
+            return Some(SyncProvider::Dropbox);
+        }
+        if name == "icloud drive"
+            || name == "iclouddrive"
+            || name == "com~apple~clouddocs"
+            || name == "mobile documents"
+        {
+            return Some(SyncProvider::ICloud);
+        }
+        if name == "google drive" || name == "my drive" || name.starts_with("googledrive-") {
+            return Some(SyncProvider::GoogleDrive);
+        }
+    }
+    None
+}
+
+/// Whether a path is on a network share
+pub fn is_network_path(path: &Path) -> bool {
+    let text = path.to_string_lossy();
+    if text.starts_with("\\\\") || text.starts_with("//") {
+        return true;
+    }
+    network_mount_contains(path)
+}
+
+#[cfg(target_os = "linux")]
+fn network_mount_contains(path: &Path) -> bool {
+    const NETWORK_FS: &[&str] = &["nfs", "nfs4", "cifs", "smb3", "smbfs", "fuse.sshfs", "9p"];
+
+    let mounts = match fs::read_to_string("/proc/mounts") {
+        Ok(mounts) => mounts,
+        Err(_) => return false,
+    };
+    let path = path.canonicalize().unwrap_or_else(|_| path.to_path_buf());
+
+    // The most specific mount point containing the path decides
+    mounts
+        .lines()
+        .filter_map(|line| {
+            let mut fields = line.split_whitespace();
+            let _device = fields.next()?;
+            let mount_point = fields.next()?.replace("\\040", " ");
+            let fs_type = fields.next()?;
+            Some((PathBuf::from(mount_point), fs_type.to_string()))
+        })
+        .filter(|(mount_point, _)| path.starts_with(mount_point))
+        .max_by_key(|(mount_point, _)| mount_point.components().count())
+        .map_or(false, |(_, fs_type)| NETWORK_FS.contains(&fs_type.as_str()))
+}
+
+#[cfg(not(target_os = "linux"))]
+fn network_mount_contains(_path: &Path) -> bool {
+    false
+}
+
+/// Whether files can be created in a folder
+pub fn is_writable(dir: &Path) -> bool {
+    let probe = dir.join(WRITE_PROBE);
+    match fs::OpenOptions::new()
+        .write(true)
+        .create_new(true)
+        .open(&probe)
+    {
+        Ok(_) => {
+            fs::remove_file(&probe).ok();
+            true
+        }
+        // A probe left behind by a crash still proves the folder is writable
+        Err(e) if e.kind() == std::io::ErrorKind::AlreadyExists => fs::remove_file(&probe).is_ok(),
+        Err(_) => false,
+    }
+}
+
+/// Original file name of an iCloud stub (".Pack.zip.icloud" -> "Pack.zip")
+pub fn icloud_stub_target(file_name: &str) -> Option<&str> {
+    let name = file_name.strip_prefix('.')?.strip_suffix(".icloud")?;
+    if name.is_empty() {
+        None
+    } else {
+        Some(name)
+    }
+}
+
+#[cfg(windows)]
+fn placeholder_kind(metadata: &fs::Metadata, _synced: bool) -> Option<PlaceholderKind> {
+    use std::os::windows::fs::MetadataExt;
+
+    const FILE_ATTRIBUTE_OFFLINE: u32 = 0x1000;
+    const FILE_ATTRIBUTE_RECALL_ON_OPEN: u32 = 0x40000;
+    const FILE_ATTRIBUTE_RECALL_ON_DATA_ACCESS: u32 = 0x400000;
+
+    let attributes = metadata.file_attributes();
+    if attributes
+        & (FILE_ATTRIBUTE_OFFLINE
+            | FILE_ATTRIBUTE_RECALL_ON_OPEN
+            | FILE_ATTRIBUTE_RECALL_ON_DATA_ACCESS)
+        != 0
+    {
+        Some(PlaceholderKind::CloudOnly)
+    } else {
+        None
+    }
+}
+
+#[cfg(unix)]
+fn placeholder_kind(metadata: &fs::Metadata, synced: bool) -> Option<PlaceholderKind> {
+    use std::os::unix::fs::MetadataExt;
+
+    // Sparse files also look like this, so only trust it inside synced folders
+    if synced && metadata.is_file() && metadata.len() > 0 && metadata.blocks() == 0 {
+        Some(PlaceholderKind::Dataless)
+    } else {
+        None
+    }
+}
+
+#[cfg(not(any(unix, windows)))]
+fn placeholder_kind(_metadata: &fs::Metadata, _synced: bool) -> Option<PlaceholderKind> {
+    None
+}
+
+/// Whether a pack file's contents are missing locally
+///
+/// Used by the scanner to skip placeholders instead of failing on them.
+pub fn is_placeholder(path: &Path) -> bool {
+    match fs::metadata(path) {
+        Ok(metadata) => placeholder_kind(&metadata, detect_sync_provider(path).is_some()).is_some(),
+        Err(_) => false,
+    }
+}
+
+/// Inspect a packs folder for sync, network and read-only problems
+pub fn inspect_packs_folder(packs_dir: &Path) -> FolderAccessReport {
+    let sync_provider = detect_sync_provider(packs_dir);
+    let network = is_network_path(packs_dir);
+    let read_only = packs_dir.is_dir() && !is_writable(packs_dir);
+
+    let mut placeholders = Vec::new();
+    if let Ok(entries) = fs::read_dir(packs_dir) {
+        for entry in entries.flatten() {
+            let file_name = entry.file_name().to_string_lossy().to_string();
+            let path = entry.path();
+
+            if let Some(target) = icloud_stub_target(&file_name) {
+                if target.ends_with(".zip") || !target.contains('.') {
+                    placeholders.push(PlaceholderFile {
+                        name: target.to_string(),
+                        path: path.to_string_lossy().to_string(),
+                        kind: PlaceholderKind::ICloudStub,
+                    });
+                }
+                continue;
+            }
+            if file_name.starts_with('.') {
+                continue;
+            }
+
+            // Folder packs are only unreadable if their pack.mcmeta is
+            let check = if path.is_dir() {
+                path.join("pack.mcmeta")
+            } else if file_name.ends_with(".zip") {
+                path.clone()
+            } else {
+                continue;
+            };
+            if let Ok(metadata) = fs::metadata(&check) {
+                if let Some(kind) = placeholder_kind(&metadata, sync_provider.is_some()) {
+                    placeholders.push(PlaceholderFile {
+                        name: file_name,
+                        path: path.to_string_lossy().to_string(),
+                        kind,
+                    });
+                }
+            }
+        }
+    }
+    placeholders.sort_by(|a, b| a.name.cmp(&b.name));
+
+    let mut warnings = Vec::new();
+    if let Some(provider) = sync_provider {
+        warnings.push(format!(
+            "The packs folder is synced by {}. Packs that aren't downloaded yet are skipped.",
+            provider.label()
+        ));
+    }
+    if network {
+        warnings.push(
+            "The packs folder is on a network share. Scans and builds may be slow.".to_string(),
+        );
+    }
+    if read_only {
+        warnings.push(
+            "The packs folder is read-only. Builds must be written somewhere else.".to_string(),
+        );
+    }
+    if !placeholders.is_empty() {
+        warnings.push(format!(
+            "{} pack(s) are only in the cloud: {}",
+            placeholders.len(),
+            placeholders
+                .iter()
+                .map(|p| p.name.as_str())
+                .collect::<Vec<_>>()
+                .join(", ")
+        ));
+    }
+
+    FolderAccessReport {
+        path: packs_dir.to_string_lossy().to_string(),
+        sync_provider,
+        network,
+        read_only,
+        placeholders,
+        warnings,
+    }
+}
+
+/// Read a file to the end, which makes the sync client fetch it
+fn hydrate_by_reading(path: &Path) -> std::io::Result<()> {
+    let mut file = fs::File::open(path)?;
+    let mut buffer = [0u8; 64 * 1024];
+    while file.read(&mut buffer)? > 0 {}
+    Ok(())
+}
+
+#[cfg(target_os = "macos")]
+fn request_icloud_download(target: &Path) -> Result<(), String> {
+    let status = std::process::Command::new("brctl")
+        .arg("download")
+        .arg(target)
+        .status()
+        .map_err(|e| format!("Failed to run brctl: {}", e))?;
+    if status.success() {
+        Ok(())
+    } else {
+        Err(format!("brctl exited with {}", status))
+    }
+}
+
+#[cfg(not(target_os = "macos"))]
+fn request_icloud_download(_target: &Path) -> Result<(), String> {
+    Err("iCloud downloads can only be requested on macOS".to_string())
+}
+
+/// Ask the sync client to download placeholder packs
+///
+/// Cloud-only files are read through, which blocks until they arrive. iCloud
+/// stubs are handed to the iCloud daemon, which downloads in the background,
+/// so those need a rescan later.
+pub fn download_placeholders(placeholders: &[PlaceholderFile]) -> Vec<PlaceholderDownload> {
+    placeholders
+        .iter()
+        .map(|placeholder| {
+            let path = Path::new(&placeholder.path);
+            let result = match placeholder.kind {
+                PlaceholderKind::ICloudStub => {
+                    let target = path.with_file_name(&placeholder.name);
+                    request_icloud_download(&target).map(|_| DownloadStatus::Requested)
+                }
+                PlaceholderKind::Dataless | PlaceholderKind::CloudOnly => {
+                    let file = if path.is_dir() {
+                        path.join("pack.mcmeta")
+                    } else {
+                        path.to_path_buf()
+                    };
+                    hydrate_by_reading(&file)
+                        .map(|_| DownloadStatus::Downloaded)
+                        .map_err(|e| e.to_string())
+                }
+            };
+
+            debug!("Download of {}: {:?}", placeholder.name, result);
+            match result {
+                Ok(status) => PlaceholderDownload {
+                    name: placeholder.name.clone(),
+                    status,
+                    error: None,
+                },
+                Err(error) => PlaceholderDownload {
+                    name: placeholder.name.clone(),
+                    status: DownloadStatus::Failed,
+                    error: Some(error),
+                },
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_detect_sync_provider() {
+        assert_eq!(
+            detect_sync_provider(Path::new("C:/Users/alex/OneDrive/Minecraft/resourcepacks")),
+            Some(SyncProvider::OneDrive)
+        );
+        assert_eq!(
+            detect_sync_provider(Path::new(
+                "/Users/alex/Library/CloudStorage/OneDrive-Personal/packs"
+            )),
+            Some(SyncProvider::OneDrive)
+        );
+        assert_eq!(
+            detect_sync_provider(Path::new("/home/alex/Dropbox (Personal)/packs")),
+            Some(SyncProvider::Dropbox)
+        );
+        assert_eq!(
+            detect_sync_provider(Path::new(
+                "/Users/alex/Library/Mobile Documents/com~apple~CloudDocs/packs"
+            )),
+            Some(SyncProvider::ICloud)
+        );
+        assert_eq!(
+            detect_sync_provider(Path::new("G:/My Drive/packs")),
+            Some(SyncProvider::GoogleDrive)
+        );
+        assert_eq!(
+            detect_sync_provider(Path::new("/home/alex/.minecraft/resourcepacks")),
+            None
+        );
+    }
+
+    #[test]
+    fn test_icloud_stub_target() {
+        assert_eq!(
+            icloud_stub_target(".Faithful.zip.icloud"),
+            Some("Faithful.zip")
+        );
+        assert_eq!(icloud_stub_target("Faithful.zip"), None);
+        assert_eq!(icloud_stub_target(".icloud"), None);
+    }
+
+    #[test]
+    fn test_network_path() {
+        assert!(is_network_path(Path::new("\\\\nas\\share\\packs")));
+        assert!(is_network_path(Path::new("//nas/share/packs")));
+    }
+
+    #[test]
+    fn test_inspect_packs_folder() {
+        let temp_dir = std::env::temp_dir().join("test_cloud_sync_inspect");
+        fs::remove_dir_all(&temp_dir).ok();
+        fs::create_dir_all(&temp_dir).expect("Failed to create test directory");
+        fs::write(temp_dir.join("Local.zip"), b"PK").expect("Failed to write pack");
+        fs::write(temp_dir.join(".Remote.zip.icloud"), b"").expect("Failed to write stub");
+
+        let report = inspect_packs_folder(&temp_dir);
+        let probe_left = temp_dir.join(WRITE_PROBE).exists();
+
+        // Clean up
+        fs::remove_dir_all(&temp_dir).ok();
+
+        assert_eq!(report.sync_provider, None);
+        assert!(!report.read_only);
+        assert!(!probe_left);
+        assert_eq!(report.placeholders.len(), 1);
+        assert_eq!(report.placeholders[0].name, "Remote.zip");
+        assert_eq!(report.placeholders[0].kind, PlaceholderKind::ICloudStub);
+        assert_eq!(report.warnings.len(), 1);
+    }
+}
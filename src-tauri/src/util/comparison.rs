@@ -0,0 +1,351 @@
+/// Before/after comparison images
+///
+/// Renders vanilla and merged versions of textures next to each other for
+/// changelogs: side by side with captions, split down the middle in one
+/// image, or as a pair of aligned images for a slider. Textures are scaled
+/// with nearest-neighbor to any size so pixel art stays crisp.
+use crate::model::{OverrideSelection, PackMeta};
+use crate::util::animation::{self, decode_first_frame};
+use crate::util::bitmap_font::{self, GLYPH_HEIGHT};
+use crate::util::weaver_nest::{self, WinnerRules};
+use anyhow::{anyhow, Context, Result};
+use image::imageops::{self, FilterType};
+use image::{Rgba, RgbaImage};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::Path;
+use tracing::info;
+
+/// Largest allowed output width of one texture
+pub const MAX_COMPARISON_SIZE: u32 = 2048;
+
+/// Space around and between panels
+const PADDING: u32 = 8;
+
+/// Width of the divider in split images
+const DIVIDER_WIDTH: u32 = 2;
+
+const BACKGROUND: Rgba<u8> = Rgba([32, 32, 36, 255]);
+const DIVIDER_COLOR: Rgba<u8> = Rgba([255, 255, 255, 255]);
+const CAPTION_COLOR: Rgba<u8> = Rgba([230, 230, 235, 255]);
+
+/// How the two versions are arranged
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ComparisonLayout {
+    /// Vanilla and merged next to each other, with captions
+    SideBySide,
+    /// Left half vanilla, right half merged, in one image
+    Split,
+    /// Two aligned images (_before/_after) for a slider
+    Pair,
+}
+
+/// Comparison written for one asset
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ComparisonImage {
+    pub asset_id: String,
+    /// Pack the merged texture comes from
+    pub merged_pack_id: String,
+    /// Written files (two for the pair layout)
+    pub files: Vec<String>,
+    pub width: u32,
+    pub height: u32,
+}
+
+/// Result of a comparison export
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ComparisonExport {
+    pub images: Vec<ComparisonImage>,
+    /// Assets that couldn't be compared, with the reason
+    pub errors: Vec<String>,
+}
+
+/// File name stem for an asset ("minecraft:block/stone" -> "minecraft_block_stone")
+pub(crate) fn file_stem(asset_id: &str) -> String {
+    asset_id
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '-' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect()
+}
+
+/// Caption scale that suits the panel size
+fn caption_scale(size: u32) -> u32 {
+    (size / 128).clamp(1, 4)
+}
+
+/// Place vanilla and merged panels next to each other with captions
+fn side_by_side(before: &RgbaImage, after: &RgbaImage, after_caption: &str) -> RgbaImage {
+    let (panel_w, panel_h) = before.dimensions();
+    let scale = caption_scale(panel_w);
+    let caption_h = GLYPH_HEIGHT * scale + PADDING;
+
+    let mut canvas = RgbaImage::from_pixel(
+        panel_w * 2 + PADDING * 3,
+        panel_h + caption_h + PADDING * 2,
+        BACKGROUND,
+    );
+    for (index, (panel, caption)) in [(before, "Vanilla"), (after, after_caption)]
+        .iter()
+        .enumerate()
+    {
+        let x = PADDING + index as u32 * (panel_w + PADDING);
+        let text = bitmap_font::fit_text(caption, panel_w, scale);
+        let text_x = x + (panel_w - bitmap_font::text_width(&text, scale)) / 2;
+        bitmap_font::draw_text(&mut canvas, text_x, PADDING, &text, CAPTION_COLOR, scale);
+        imageops::overlay(&mut canvas, *panel, x as i64, (PADDING + caption_h) as i64);
+    }
+    canvas
+}
+
+/// Left half of the vanilla panel joined to the right half of the merged one
+fn split(before: &RgbaImage, after: &RgbaImage) -> RgbaImage {
+    let (width, height) = before.dimensions();
+    let middle = width / 2;
+    let mut canvas = before.clone();
+    imageops::replace(
+        &mut canvas,
+        &imageops::crop_imm(after, middle, 0, width - middle, height).to_image(),
+        middle as i64,
+        0,
+    );
+
+    let divider_start = middle.saturating_sub(DIVIDER_WIDTH / 2);
+    for x in divider_start..(divider_start + DIVIDER_WIDTH).min(width) {
+        for y in 0..height {
+            canvas.put_pixel(x, y, DIVIDER_COLOR);
+        }
+    }
+    canvas
+}
+
+/// Write the comparison of one asset
+#[allow(clippy::too_many_arguments)]
+fn compare_asset(
+    asset_id: &str,
+    packs: &[PackMeta],
+    pack_order: &[String],
+    overrides: &HashMap<String, OverrideSelection>,
+    rules: &WinnerRules,
+    vanilla_dir: &Path,
+    layout: ComparisonLayout,
+    size: u32,
+    output_dir: &Path,
+) -> Result<ComparisonImage> {
+    let vanilla_file = vanilla_dir.join(animation::texture_path_for_asset(asset_id));
+    let vanilla_bytes =
+        std::fs::read(&vanilla_file).map_err(|_| anyhow!("No vanilla texture for {}", asset_id))?;
+    let (merged_pack_id, merged_bytes) =
+        weaver_nest::read_winning_texture(asset_id, packs, pack_order, overrides, rules)
+            .ok_or_else(|| anyhow!("No pack in the merge provides {}", asset_id))?;
+
+    let before = decode_first_frame(&vanilla_bytes)
+        .with_context(|| format!("Failed to decode vanilla {}", asset_id))?;
+    let after = decode_first_frame(&merged_bytes)
+        .with_context(|| format!("Failed to decode {} from {}", asset_id, merged_pack_id))?;
+
+    // Both panels take the vanilla aspect ratio so they line up
+    let height = ((before.height() as u64 * size as u64) / before.width() as u64).max(1) as u32;
+    let before = imageops::resize(&before, size, height, FilterType::Nearest);
+    let after = imageops::resize(&after, size, height, FilterType::Nearest);
+
+    let stem = file_stem(asset_id);
+    let outputs: Vec<(String, RgbaImage)> = match layout {
+        ComparisonLayout::SideBySide => {
+            let caption = format!("Merged ({})", merged_pack_id.trim_end_matches(".zip"));
+            vec![(
+                format!("{}_compare.png", stem),
+                side_by_side(&before, &after, &caption),
+            )]
+        }
+        ComparisonLayout::Split => vec![(format!("{}_split.png", stem), split(&before, &after))],
+        ComparisonLayout::Pair => vec![
+            (format!("{}_before.png", stem), before),
+            (format!("{}_after.png", stem), after),
+        ],
+    };
+
+    let (width, height) = outputs[0].1.dimensions();
+    let mut files = Vec::new();
+    for (name, image) in outputs {
+        let path = output_dir.join(name);
+        image
+            .save(&path)
+            .with_context(|| format!("Failed to write {:?}", path))?;
+        files.push(path.to_string_lossy().to_string());
+    }
+
+    Ok(ComparisonImage {
+        asset_id: asset_id.to_string(),
+        merged_pack_id,
+        files,
+        width,
+        height,
+    })
+}
+
+/// Generate vanilla vs merged comparison images for the given textures
+///
+/// Assets that can't be compared (no vanilla or merged version) are listed in
+/// `errors` rather than failing the whole export.
+///
+/// # Arguments
+/// * `asset_ids` - Texture asset IDs (e.g. "minecraft:block/stone")
+/// * `packs` - Scanned packs
+/// * `pack_order` - Pack IDs in priority order (first = highest)
+/// * `overrides` - Asset ID -> chosen pack
+/// * `rules` - Disabled packs and namespace owners, as in a build
+/// * `vanilla_dir` - Vanilla texture cache
+/// * `layout` - How the two versions are arranged
+/// * `size` - Output width of each texture in pixels
+/// * `output_dir` - Folder to write into
+#[allow(clippy::too_many_arguments)]
+pub fn export_comparisons(
+    asset_ids: &[String],
+    packs: &[PackMeta],
+    pack_order: &[String],
+    overrides: &HashMap<String, OverrideSelection>,
+    rules: &WinnerRules,
+    vanilla_dir: &Path,
+    layout: ComparisonLayout,
+    size: u32,
+    output_dir: &Path,
+) -> Result<ComparisonExport> {
+    if size == 0 || size > MAX_COMPARISON_SIZE {
+        return Err(anyhow!(
+            "Comparison size must be between 1 and {} pixels",
+            MAX_COMPARISON_SIZE
+        ));
+    }
+    std::fs::create_dir_all(output_dir)
+        .with_context(|| format!("Failed to create {:?}", output_dir))?;
+
+    let mut images = Vec::new();
+    let mut errors = Vec::new();
+    for asset_id in asset_ids {
+        match compare_asset(
+            asset_id,
+            packs,
+            pack_order,
+            overrides,
+            rules,
+            vanilla_dir,
+            layout,
+            size,
+            output_dir,
+        ) {
+            Ok(image) => images.push(image),
+            Err(e) => errors.push(format!("{}: {}", asset_id, e)),
+        }
+    }
+
+    info!(
+        "Wrote {} comparison(s), {} skipped",
+        images.len(),
+        errors.len()
+    );
+
+    Ok(ComparisonExport { images, errors })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    const STONE: &str = "assets/minecraft/textures/block/stone.png";
+
+    fn write_texture(root: &Path, color: [u8; 4]) {
+        let path = root.join(STONE);
+        fs::create_dir_all(path.parent().unwrap()).expect("Failed to create test directory");
+        RgbaImage::from_pixel(16, 16, Rgba(color))
+            .save(path)
+            .expect("Failed to write image");
+    }
+
+    #[test]
+    fn test_file_stem() {
+        assert_eq!(file_stem("minecraft:block/stone"), "minecraft_block_stone");
+        assert_eq!(file_stem("create:item/wrench-2"), "create_item_wrench-2");
+    }
+
+    #[test]
+    fn test_split_joins_halves() {
+        let before = RgbaImage::from_pixel(8, 4, Rgba([255, 0, 0, 255]));
+        let after = RgbaImage::from_pixel(8, 4, Rgba([0, 0, 255, 255]));
+
+        let image = split(&before, &after);
+
+        assert_eq!(image.dimensions(), (8, 4));
+        assert_eq!(image.get_pixel(0, 0), &Rgba([255, 0, 0, 255]));
+        assert_eq!(image.get_pixel(4, 0), &DIVIDER_COLOR);
+        assert_eq!(image.get_pixel(7, 3), &Rgba([0, 0, 255, 255]));
+    }
+
+    #[test]
+    fn test_export_comparisons() {
+        let temp_dir = std::env::temp_dir().join("test_comparison");
+        let vanilla = temp_dir.join("vanilla");
+        let pack_dir = temp_dir.join("pack");
+        write_texture(&vanilla, [120, 120, 120, 255]);
+        write_texture(&pack_dir, [200, 40, 40, 255]);
+        let packs = vec![PackMeta {
+            id: "pack".to_string(),
+            name: "pack".to_string(),
+            path: pack_dir.to_string_lossy().to_string(),
+            ..Default::default()
+        }];
+        let assets = vec![
+            "minecraft:block/stone".to_string(),
+            "minecraft:block/dirt".to_string(),
+        ];
+        let out = temp_dir.join("out");
+
+        let pair = export_comparisons(
+            &assets,
+            &packs,
+            &["pack".to_string()],
+            &HashMap::new(),
+            &WinnerRules::default(),
+            &vanilla,
+            ComparisonLayout::Pair,
+            64,
+            &out,
+        );
+        let after = image::open(out.join("minecraft_block_stone_after.png")).map(|i| i.to_rgba8());
+        let side = export_comparisons(
+            &assets[..1],
+            &packs,
+            &["pack".to_string()],
+            &HashMap::new(),
+            &WinnerRules::default(),
+            &vanilla,
+            ComparisonLayout::SideBySide,
+            64,
+            &out,
+        );
+
+        // Clean up
+        fs::remove_dir_all(&temp_dir).ok();
+
+        let pair = pair.expect("export should succeed");
+        assert_eq!(pair.images.len(), 1);
+        assert_eq!(pair.images[0].files.len(), 2);
+        assert_eq!(pair.images[0].merged_pack_id, "pack");
+        assert_eq!(pair.errors.len(), 1);
+        let after = after.expect("after image should be written");
+        assert_eq!(after.dimensions(), (64, 64));
+        assert_eq!(after.get_pixel(10, 10), &Rgba([200, 40, 40, 255]));
+
+        let side = side.expect("export should succeed");
+        assert!(side.images[0].width > 128);
+    }
+}
@@ -0,0 +1,398 @@
+/// Conflict resolution presets
+///
+/// A preset is a named, reusable set of rules plus an automatic resolution
+/// policy (e.g. "HD-first" or "Vanilla-faithful"). Presets are stored in the
+/// application settings and can be exported/imported as standalone JSON so
+/// users can share them. Rules match packs by name rather than by ID, which
+/// lets one preset apply to any project.
+use crate::model::{AssetRecord, OverrideSelection, PackMeta};
+use crate::util::vanilla_textures::VANILLA_PACK_ID;
+use crate::util::{image_probe, parallelism};
+use anyhow::{anyhow, Context, Result};
+use rayon::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// Texture width assumed for vanilla when the vanilla texture can't be read
+const VANILLA_TEXTURE_SIZE: u32 = 16;
+
+/// How conflicts not covered by a rule are resolved
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ResolutionPolicy {
+    /// Highest pack in the pack order wins (no overrides generated)
+    PackOrder,
+    /// The provider with the largest texture wins
+    HighestResolution,
+    /// The provider whose texture resolution is closest to vanilla wins
+    VanillaFaithful,
+}
+
+/// A rule that pins matching assets to a preferred pack
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PresetRule {
+    /// Asset ID pattern, `*` matches any sequence (e.g., "minecraft:block/*_log")
+    pub pattern: String,
+    /// Case-insensitive substring matched against pack names and IDs
+    pub prefer_pack: String,
+}
+
+/// A named set of rules plus an auto-resolution policy
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConflictPreset {
+    pub name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    pub policy: ResolutionPolicy,
+    #[serde(default)]
+    pub rules: Vec<PresetRule>,
+}
+
+/// Serialize a preset to shareable JSON
+pub fn export_preset_json(preset: &ConflictPreset) -> Result<String> {
+    serde_json::to_string_pretty(preset).context("Failed to serialize preset")
+}
+
+/// Parse and validate a preset from shared JSON
+pub fn import_preset_json(json: &str) -> Result<ConflictPreset> {
+    let preset: ConflictPreset = serde_json::from_str(json).context("Invalid preset JSON")?;
+    validate_preset(&preset)?;
+    Ok(preset)
+}
+
+/// Validate that a preset is usable
+pub fn validate_preset(preset: &ConflictPreset) -> Result<()> {
+    if preset.name.trim().is_empty() {
+        return Err(anyhow!("Preset name cannot be empty"));
+    }
+
+    for (i, rule) in preset.rules.iter().enumerate() {
+        if rule.pattern.trim().is_empty() {
+            return Err(anyhow!("Rule {} has an empty asset pattern", i + 1));
+        }
+        if rule.prefer_pack.trim().is_empty() {
+            return Err(anyhow!("Rule {} has an empty preferred pack", i + 1));
+        }
+    }
+
+    Ok(())
+}
+
+/// Apply a preset to an indexed set of packs
+///
+/// Returns the overrides needed to realise the preset on top of `pack_order`.
+/// Assets where the preset agrees with the pack order are left untouched.
+pub fn apply_preset(
+    preset: &ConflictPreset,
+    packs: &[PackMeta],
+    assets: &[AssetRecord],
+    providers: &HashMap<String, Vec<String>>,
+    pack_order: &[String],
+) -> HashMap<String, OverrideSelection> {
+    let pack_map: HashMap<&str, &PackMeta> = packs.iter().map(|p| (p.id.as_str(), p)).collect();
+
+    // Resolution policies read texture headers from the packs
+    parallelism::with_io_pool(|| {
+        assets
+            .par_iter()
+            .filter_map(|asset| {
+                let candidates = ordered_candidates(providers.get(&asset.id)?, pack_order);
+                if candidates.len() < 2 {
+                    return None;
+                }
+
+                let chosen = choose_provider(preset, asset, &candidates, &pack_map)?;
+                if chosen == candidates[0] {
+                    return None;
+                }
+
+                Some((
+                    asset.id.clone(),
+                    OverrideSelection {
+                        pack_id: chosen.to_string(),
+                        variant_path: None,
+                    },
+                ))
+            })
+            .collect()
+    })
+}
+
+/// Order an asset's providers by their position in the pack order
+///
+/// Providers missing from a non-empty pack order are dropped.
+fn ordered_candidates<'a>(providers: &'a [String], pack_order: &[String]) -> Vec<&'a str> {
+    let mut candidates: Vec<(usize, &str)> = providers
+        .iter()
+        .filter_map(
+            |pack_id| match pack_order.iter().position(|id| id == pack_id) {
+                Some(pos) => Some((pos, pack_id.as_str())),
+                None if pack_order.is_empty() => Some((usize::MAX, pack_id.as_str())),
+                None => None,
+            },
+        )
+        .collect();
+
+    candidates.sort_by_key(|(pos, _)| *pos);
+    candidates.into_iter().map(|(_, id)| id).collect()
+}
+
+/// Pick the provider a preset selects for one asset
+fn choose_provider<'a>(
+    preset: &ConflictPreset,
+    asset: &AssetRecord,
+    candidates: &[&'a str],
+    pack_map: &HashMap<&str, &PackMeta>,
+) -> Option<&'a str> {
+    // Rules take precedence over the policy; the first matching rule applies
+    for rule in &preset.rules {
+        if !matches_pattern(&rule.pattern, &asset.id) {
+            continue;
+        }
+        let needle = rule.prefer_pack.to_lowercase();
+        let preferred = candidates.iter().find(|id| {
+            id.to_lowercase().contains(&needle)
+                || pack_map
+                    .get(*id)
+                    .map_or(false, |p| p.name.to_lowercase().contains(&needle))
+        });
+        if let Some(pack_id) = preferred {
+            return Some(pack_id);
+        }
+    }
+
+    match preset.policy {
+        ResolutionPolicy::PackOrder => candidates.first().copied(),
+        ResolutionPolicy::HighestResolution => {
+            let widths = candidate_widths(asset, candidates, pack_map);
+            // Iterate in reverse so ties resolve to the higher-priority pack
+            widths
+                .iter()
+                .rev()
+                .max_by_key(|(_, width)| *width)
+                .map(|(id, _)| *id)
+        }
+        ResolutionPolicy::VanillaFaithful => {
+            let target = pack_map
+                .get(VANILLA_PACK_ID)
+                .and_then(|vanilla| texture_width(vanilla, asset))
+                .unwrap_or(VANILLA_TEXTURE_SIZE);
+            let widths = candidate_widths(asset, candidates, pack_map);
+            widths
+                .iter()
+                .min_by_key(|(_, width)| (*width as i64 - target as i64).abs())
+                .map(|(id, _)| *id)
+        }
+    }
+}
+
+/// Read texture widths for each candidate, skipping unreadable textures
+fn candidate_widths<'a>(
+    asset: &AssetRecord,
+    candidates: &[&'a str],
+    pack_map: &HashMap<&str, &PackMeta>,
+) -> Vec<(&'a str, u32)> {
+    candidates
+        .iter()
+        .filter_map(|id| {
+            let pack = pack_map.get(id)?;
+            texture_width(pack, asset).map(|w| (*id, w))
+        })
+        .collect()
+}
+
+/// Read the width of an asset's texture in a pack (header only)
+fn texture_width(pack: &PackMeta, asset: &AssetRecord) -> Option<u32> {
+    asset
+        .files
+        .iter()
+        .filter(|f| f.ends_with(".png"))
+        .find_map(|file| read_texture_dimensions(pack, file))
+        .map(|(width, _)| width)
+}
+
+/// Read image dimensions of a file inside a pack without decoding pixels
+pub fn read_texture_dimensions(pack: &PackMeta, file: &str) -> Option<(u32, u32)> {
+    image_probe::pack_file_dimensions(&pack.path, pack.is_zip, file).ok()
+}
+
+/// Match an asset ID against a pattern where `*` matches any sequence
+pub fn matches_pattern(pattern: &str, asset_id: &str) -> bool {
+    let parts: Vec<&str> = pattern.split('*').collect();
+    if parts.len() == 1 {
+        return pattern == asset_id;
+    }
+
+    let first = parts[0];
+    let last = parts[parts.len() - 1];
+    if !asset_id.starts_with(first) || asset_id.len() < first.len() + last.len() {
+        return false;
+    }
+
+    let mut remaining = &asset_id[first.len()..];
+    for part in &parts[1..parts.len() - 1] {
+        match remaining.find(part) {
+            Some(idx) => remaining = &remaining[idx + part.len()..],
+            None => return false,
+        }
+    }
+
+    remaining.ends_with(last)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::Path;
+
+    fn make_pack(id: &str, path: &str) -> PackMeta {
+        PackMeta {
+            id: id.to_string(),
+            name: id.trim_end_matches(".zip").to_string(),
+            path: path.to_string(),
+            ..Default::default()
+        }
+    }
+
+    fn write_png(pack_dir: &Path, rel: &str, size: u32) {
+        let path = pack_dir.join(rel);
+        std::fs::create_dir_all(path.parent().unwrap()).expect("Failed to create test directory");
+        image::RgbaImage::new(size, size)
+            .save(&path)
+            .expect("Failed to write test texture");
+    }
+
+    #[test]
+    fn test_matches_pattern() {
+        assert!(matches_pattern(
+            "minecraft:block/stone",
+            "minecraft:block/stone"
+        ));
+        assert!(!matches_pattern(
+            "minecraft:block/stone",
+            "minecraft:block/dirt"
+        ));
+        assert!(matches_pattern(
+            "minecraft:block/*",
+            "minecraft:block/stone"
+        ));
+        assert!(matches_pattern("*_log", "minecraft:block/oak_log"));
+        assert!(matches_pattern(
+            "minecraft:*/oak_*",
+            "minecraft:block/oak_planks"
+        ));
+        assert!(!matches_pattern(
+            "minecraft:item/*",
+            "minecraft:block/stone"
+        ));
+        assert!(!matches_pattern("a*a", "a"));
+    }
+
+    #[test]
+    fn test_import_preset_rejects_empty_name() {
+        let json = r#"{"name": " ", "policy": "packOrder"}"#;
+        assert!(import_preset_json(json).is_err());
+    }
+
+    #[test]
+    fn test_export_import_roundtrip() {
+        let preset = ConflictPreset {
+            name: "Vanilla-faithful".to_string(),
+            description: Some("Keep the 16x look".to_string()),
+            policy: ResolutionPolicy::VanillaFaithful,
+            rules: vec![PresetRule {
+                pattern: "minecraft:entity/*".to_string(),
+                prefer_pack: "fresh animations".to_string(),
+            }],
+        };
+
+        let json = export_preset_json(&preset).expect("should export");
+        assert!(json.contains("\"preferPack\""));
+
+        let imported = import_preset_json(&json).expect("should import");
+        assert_eq!(imported.name, "Vanilla-faithful");
+        assert_eq!(imported.policy, ResolutionPolicy::VanillaFaithful);
+        assert_eq!(imported.rules.len(), 1);
+    }
+
+    #[test]
+    fn test_apply_preset_rule_and_resolution_policy() {
+        let temp_dir = std::env::temp_dir().join("test_apply_conflict_preset");
+        let low_dir = temp_dir.join("low_res");
+        let hd_dir = temp_dir.join("hd_pack");
+        write_png(&low_dir, "assets/minecraft/textures/block/stone.png", 16);
+        write_png(&hd_dir, "assets/minecraft/textures/block/stone.png", 64);
+        write_png(&low_dir, "assets/minecraft/textures/block/dirt.png", 16);
+        write_png(&hd_dir, "assets/minecraft/textures/block/dirt.png", 64);
+
+        let packs = vec![
+            make_pack("low_res", &low_dir.to_string_lossy()),
+            make_pack("hd_pack", &hd_dir.to_string_lossy()),
+        ];
+        let assets: Vec<AssetRecord> = ["stone", "dirt"]
+            .iter()
+            .map(|name| AssetRecord {
+                id: format!("minecraft:block/{}", name),
+                labels: Vec::new(),
+                files: vec![format!("assets/minecraft/textures/block/{}.png", name)],
+                animation: None,
+            })
+            .collect();
+        let providers: HashMap<String, Vec<String>> = assets
+            .iter()
+            .map(|a| {
+                (
+                    a.id.clone(),
+                    vec!["low_res".to_string(), "hd_pack".to_string()],
+                )
+            })
+            .collect();
+        let pack_order = vec!["low_res".to_string(), "hd_pack".to_string()];
+
+        let preset = ConflictPreset {
+            name: "HD-first".to_string(),
+            description: None,
+            policy: ResolutionPolicy::HighestResolution,
+            rules: vec![PresetRule {
+                pattern: "minecraft:block/dirt".to_string(),
+                prefer_pack: "LOW".to_string(),
+            }],
+        };
+
+        let overrides = apply_preset(&preset, &packs, &assets, &providers, &pack_order);
+
+        // Clean up
+        std::fs::remove_dir_all(&temp_dir).ok();
+
+        // Stone goes to the HD pack via the policy
+        assert_eq!(overrides["minecraft:block/stone"].pack_id, "hd_pack");
+        // Dirt is pinned to the order winner by a rule, so no override is needed
+        assert!(!overrides.contains_key("minecraft:block/dirt"));
+    }
+
+    #[test]
+    fn test_apply_preset_pack_order_policy_is_noop() {
+        let preset = ConflictPreset {
+            name: "Order".to_string(),
+            description: None,
+            policy: ResolutionPolicy::PackOrder,
+            rules: Vec::new(),
+        };
+        let assets = vec![AssetRecord {
+            id: "minecraft:block/stone".to_string(),
+            labels: Vec::new(),
+            files: vec!["assets/minecraft/textures/block/stone.png".to_string()],
+            animation: None,
+        }];
+        let providers = HashMap::from([(
+            "minecraft:block/stone".to_string(),
+            vec!["a".to_string(), "b".to_string()],
+        )]);
+        let pack_order = vec!["b".to_string(), "a".to_string()];
+
+        let overrides = apply_preset(&preset, &[], &assets, &providers, &pack_order);
+        assert!(overrides.is_empty());
+    }
+}
@@ -0,0 +1,427 @@
+/// Conflicts across a pack order
+///
+/// The providers map says which packs ship an asset; this works out, for a
+/// given order and overrides, which pack wins each contested asset and
+/// which packs lose it. Conflicts are grouped by namespace and asset
+/// category (block, item, entity, ...) and summed per pair of packs, so the
+/// UI can say "Pack A overrides 412 textures from Pack B".
+///
+/// Each conflict is also rated: a clashing GUI or font breaks the interface,
+/// a clashing stone variant barely shows, and a clash between byte-identical
+/// files changes nothing. Severities per category are user-configurable; the
+/// report lists the most severe groups first and names the unresolved
+/// conflicts that should be confirmed before building anyway.
+use crate::model::OverrideSelection;
+use crate::util::namespace_conflicts::namespace_of;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use tracing::info;
+
+/// Why a pack wins an asset
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum WinReason {
+    /// Picked for the asset by the user
+    Override,
+    /// Highest in the pack order
+    Priority,
+}
+
+/// How much a conflict matters, least first
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ConflictSeverity {
+    /// Every provider ships the same file, so the winner makes no difference
+    Negligible,
+    Low,
+    Medium,
+    High,
+    /// Breaks the interface (GUI, fonts)
+    Critical,
+}
+
+/// Built-in severities of asset categories
+const DEFAULT_CATEGORY_SEVERITIES: &[(&str, ConflictSeverity)] = &[
+    ("gui", ConflictSeverity::Critical),
+    ("font", ConflictSeverity::Critical),
+    ("colormap", ConflictSeverity::High),
+    ("environment", ConflictSeverity::High),
+    ("misc", ConflictSeverity::High),
+    ("entity", ConflictSeverity::Medium),
+    ("cem", ConflictSeverity::Medium),
+    ("item", ConflictSeverity::Medium),
+    ("mob_effect", ConflictSeverity::Medium),
+    ("block", ConflictSeverity::Low),
+    ("ctm", ConflictSeverity::Low),
+    ("painting", ConflictSeverity::Low),
+    ("particle", ConflictSeverity::Low),
+    ("texts", ConflictSeverity::Low),
+];
+
+/// How severe conflicts of each asset category are
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct ConflictSeveritySettings {
+    /// Category -> severity
+    pub categories: BTreeMap<String, ConflictSeverity>,
+    /// Severity of categories not listed
+    pub default_severity: ConflictSeverity,
+    /// Unresolved conflicts at or above this need confirming before a build
+    pub confirm_build_at: ConflictSeverity,
+}
+
+impl Default for ConflictSeveritySettings {
+    fn default() -> Self {
+        Self {
+            categories: DEFAULT_CATEGORY_SEVERITIES
+                .iter()
+                .map(|(category, severity)| (category.to_string(), *severity))
+                .collect(),
+            default_severity: ConflictSeverity::Medium,
+            confirm_build_at: ConflictSeverity::High,
+        }
+    }
+}
+
+impl ConflictSeveritySettings {
+    /// Severity of a conflict over `asset_id`
+    ///
+    /// # Arguments
+    /// * `identical` - Whether every enabled provider ships the same bytes
+    pub fn severity_of(&self, asset_id: &str, identical: bool) -> ConflictSeverity {
+        if identical {
+            return ConflictSeverity::Negligible;
+        }
+        self.categories
+            .get(asset_category(asset_id))
+            .copied()
+            .unwrap_or(self.default_severity)
+    }
+}
+
+/// An asset provided by more than one enabled pack
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AssetConflict {
+    pub asset_id: String,
+    pub winner: String,
+    pub reason: WinReason,
+    /// Other enabled providers, highest priority first
+    pub losers: Vec<String>,
+    /// Whether the losers' copies are byte-identical to the winner's
+    pub identical: bool,
+    pub severity: ConflictSeverity,
+}
+
+/// Conflicts of one namespace and asset category
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConflictGroup {
+    pub namespace: String,
+    /// First folder of the asset path (e.g., "block", "entity", "cem")
+    pub category: String,
+    /// Most severe of the group's conflicts
+    pub severity: ConflictSeverity,
+    /// Most severe first, then by asset ID
+    pub conflicts: Vec<AssetConflict>,
+}
+
+/// How many assets one pack takes from another
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PackOverride {
+    pub winner: String,
+    pub loser: String,
+    pub count: usize,
+    /// Category -> assets
+    pub by_category: BTreeMap<String, usize>,
+}
+
+/// Structured conflict report for a pack order
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConflictReport {
+    /// Most severe first, then by namespace and category
+    pub groups: Vec<ConflictGroup>,
+    /// Most assets first
+    pub pack_overrides: Vec<PackOverride>,
+    /// Assets provided by more than one enabled pack
+    pub total_conflicts: usize,
+    /// Of those, assets won through an override
+    pub overridden: usize,
+    /// Assets won by priority alone whose conflict is severe enough to
+    /// confirm before building, most severe first
+    pub needs_confirmation: Vec<String>,
+}
+
+/// Category of an asset: the first folder of its path ("other" if none)
+///
+/// "minecraft:block/stone" -> "block", "minecraft:cem/cow" -> "cem"
+pub fn asset_category(asset_id: &str) -> &str {
+    let path = asset_id
+        .split_once(':')
+        .map(|(_, path)| path)
+        .unwrap_or(asset_id);
+    match path.split_once('/') {
+        Some((category, _)) if !category.is_empty() => category,
+        _ => "other",
+    }
+}
+
+/// Compute winners and losers of every contested asset
+///
+/// # Arguments
+/// * `pack_order` - Enabled pack IDs in priority order (first = highest)
+/// * `overrides` - Asset ID -> chosen pack; ignored unless the pack is an
+///   enabled provider
+/// * `providers` - Asset ID -> pack IDs providing it
+/// * `identical` - Asset ID -> groups of packs with byte-identical copies
+/// * `namespaces` - Only report assets in these namespaces (all if empty)
+/// * `severities` - How severe each category's conflicts are
+pub fn build_conflict_report(
+    pack_order: &[String],
+    overrides: &HashMap<String, OverrideSelection>,
+    providers: &HashMap<String, Vec<String>>,
+    identical: &HashMap<String, Vec<Vec<String>>>,
+    namespaces: &[String],
+    severities: &ConflictSeveritySettings,
+) -> ConflictReport {
+    let mut groups: BTreeMap<(String, String), Vec<AssetConflict>> = BTreeMap::new();
+    let mut pairs: BTreeMap<(String, String), PackOverride> = BTreeMap::new();
+    let mut report = ConflictReport::default();
+    let mut confirm: Vec<(ConflictSeverity, String)> = Vec::new();
+
+    let mut asset_ids: Vec<&String> = providers
+        .keys()
+        .filter(|id| namespaces.is_empty() || namespaces.iter().any(|ns| ns == namespace_of(id)))
+        .collect();
+    asset_ids.sort();
+    for asset_id in asset_ids {
+        // Enabled providers in priority order
+        let enabled: Vec<&String> = pack_order
+            .iter()
+            .filter(|id| providers[asset_id].contains(id))
+            .collect();
+        if enabled.len() < 2 {
+            continue;
+        }
+
+        let override_pack = overrides
+            .get(asset_id)
+            .map(|o| &o.pack_id)
+            .filter(|id| enabled.contains(id));
+        let (winner, reason) = match override_pack {
+            Some(pack_id) => (pack_id, WinReason::Override),
+            None => (enabled[0], WinReason::Priority),
+        };
+        let losers: Vec<String> = enabled
+            .iter()
+            .filter(|id| **id != winner)
+            .map(|id| id.to_string())
+            .collect();
+        let all_identical = identical.get(asset_id).map_or(false, |groups| {
+            groups
+                .iter()
+                .any(|group| group.contains(winner) && losers.iter().all(|l| group.contains(l)))
+        });
+        let severity = severities.severity_of(asset_id, all_identical);
+
+        let category = asset_category(asset_id).to_string();
+        for loser in &losers {
+            let pair = pairs
+                .entry((winner.clone(), loser.clone()))
+                .or_insert_with(|| PackOverride {
+                    winner: winner.clone(),
+                    loser: loser.clone(),
+                    count: 0,
+                    by_category: BTreeMap::new(),
+                });
+            pair.count += 1;
+            *pair.by_category.entry(category.clone()).or_default() += 1;
+        }
+
+        report.total_conflicts += 1;
+        if reason == WinReason::Override {
+            report.overridden += 1;
+        } else if severity >= severities.confirm_build_at {
+            confirm.push((severity, asset_id.clone()));
+        }
+        groups
+            .entry((namespace_of(asset_id).to_string(), category))
+            .or_default()
+            .push(AssetConflict {
+                asset_id: asset_id.clone(),
+                winner: winner.clone(),
+                reason,
+                losers,
+                identical: all_identical,
+                severity,
+            });
+    }
+
+    report.groups = groups
+        .into_iter()
+        .map(|((namespace, category), mut conflicts)| {
+            // Stable, so equal severities stay sorted by asset ID
+            conflicts.sort_by_key(|c| std::cmp::Reverse(c.severity));
+            ConflictGroup {
+                namespace,
+                category,
+                severity: conflicts[0].severity,
+                conflicts,
+            }
+        })
+        .collect();
+    report
+        .groups
+        .sort_by_key(|group| std::cmp::Reverse(group.severity));
+    confirm.sort_by_key(|(severity, _)| std::cmp::Reverse(*severity));
+    report.needs_confirmation = confirm.into_iter().map(|(_, id)| id).collect();
+    report.pack_overrides = pairs.into_values().collect();
+    report
+        .pack_overrides
+        .sort_by_key(|pair| std::cmp::Reverse(pair.count));
+
+    info!(
+        "{} contested asset(s) in {} group(s), {} won by override, {} to confirm",
+        report.total_conflicts,
+        report.groups.len(),
+        report.overridden,
+        report.needs_confirmation.len()
+    );
+
+    report
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_build_conflict_report() {
+        let providers: HashMap<String, Vec<String>> = [
+            ("minecraft:block/stone", vec!["b", "a"]),
+            ("minecraft:block/dirt", vec!["a", "b", "c"]),
+            ("minecraft:item/apple", vec!["a", "b"]),
+            ("minecraft:block/sand", vec!["a"]),
+            ("fresh:entity/cow", vec!["a", "disabled"]),
+        ]
+        .into_iter()
+        .map(|(id, packs)| {
+            (
+                id.to_string(),
+                packs.into_iter().map(|p| p.to_string()).collect(),
+            )
+        })
+        .collect();
+        let order: Vec<String> = ["a", "b", "c"].iter().map(|s| s.to_string()).collect();
+        let overrides = HashMap::from([(
+            "minecraft:item/apple".to_string(),
+            OverrideSelection {
+                pack_id: "b".to_string(),
+                variant_path: None,
+            },
+        )]);
+
+        let severities = ConflictSeveritySettings::default();
+        let none = HashMap::new();
+
+        let report = build_conflict_report(&order, &overrides, &providers, &none, &[], &severities);
+        let fresh_only = build_conflict_report(
+            &order,
+            &overrides,
+            &providers,
+            &none,
+            &["fresh".to_string()],
+            &severities,
+        );
+
+        assert_eq!(report.total_conflicts, 3);
+        assert_eq!(report.overridden, 1);
+        let groups: Vec<(&str, &str, usize)> = report
+            .groups
+            .iter()
+            .map(|g| (g.namespace.as_str(), g.category.as_str(), g.conflicts.len()))
+            .collect();
+        // Item conflicts outrank block conflicts
+        assert_eq!(
+            groups,
+            vec![("minecraft", "item", 1), ("minecraft", "block", 2)]
+        );
+        assert_eq!(report.groups[1].conflicts[0].losers, vec!["b", "c"]);
+        assert_eq!(report.groups[0].conflicts[0].reason, WinReason::Override);
+
+        let a_over_b = &report.pack_overrides[0];
+        assert_eq!(
+            (a_over_b.winner.as_str(), a_over_b.loser.as_str()),
+            ("a", "b")
+        );
+        assert_eq!(a_over_b.count, 2);
+        assert_eq!(a_over_b.by_category["block"], 2);
+        assert!(report
+            .pack_overrides
+            .iter()
+            .any(|p| p.winner == "b" && p.loser == "a" && p.by_category["item"] == 1));
+        // "fresh:entity/cow" only conflicts with a pack outside the order
+        assert_eq!(fresh_only.total_conflicts, 0);
+        assert!(fresh_only.pack_overrides.is_empty());
+    }
+
+    #[test]
+    fn test_conflict_severity() {
+        let providers: HashMap<String, Vec<String>> = [
+            "minecraft:gui/widgets",
+            "minecraft:font/ascii",
+            "minecraft:block/stone",
+            "minecraft:item/apple",
+        ]
+        .into_iter()
+        .map(|id| (id.to_string(), vec!["a".to_string(), "b".to_string()]))
+        .collect();
+        let order = vec!["a".to_string(), "b".to_string()];
+        // Both packs ship the same font
+        let identical = HashMap::from([(
+            "minecraft:font/ascii".to_string(),
+            vec![vec!["a".to_string(), "b".to_string()]],
+        )]);
+        let overrides = HashMap::from([(
+            "minecraft:gui/widgets".to_string(),
+            OverrideSelection {
+                pack_id: "b".to_string(),
+                variant_path: None,
+            },
+        )]);
+        let mut severities = ConflictSeveritySettings::default();
+        severities
+            .categories
+            .insert("item".to_string(), ConflictSeverity::High);
+
+        let report =
+            build_conflict_report(&order, &overrides, &providers, &identical, &[], &severities);
+
+        let ranked: Vec<(&str, ConflictSeverity)> = report
+            .groups
+            .iter()
+            .map(|g| (g.category.as_str(), g.severity))
+            .collect();
+        assert_eq!(
+            ranked,
+            vec![
+                ("gui", ConflictSeverity::Critical),
+                ("item", ConflictSeverity::High),
+                ("block", ConflictSeverity::Low),
+                ("font", ConflictSeverity::Negligible),
+            ]
+        );
+        assert!(report.groups[3].conflicts[0].identical);
+        // The GUI conflict was resolved by an override
+        assert_eq!(report.needs_confirmation, vec!["minecraft:item/apple"]);
+    }
+
+    #[test]
+    fn test_asset_category() {
+        assert_eq!(asset_category("minecraft:block/stone"), "block");
+        assert_eq!(asset_category("minecraft:cem/cow"), "cem");
+        assert_eq!(asset_category("minecraft:pack"), "other");
+    }
+}
@@ -0,0 +1,446 @@
+/// Contact sheet export
+///
+/// Lays out every texture of one category (e.g. "block", "item") as a
+/// labeled grid in a single PNG, ready to share. The textures come from one
+/// pack or from the merged result of several packs: pass a single pack ID as
+/// the order for the former.
+use crate::model::{OverrideSelection, PackMeta};
+use crate::util::animation::{decode_first_frame, read_pack_bytes};
+use crate::util::bitmap_font::{self, GLYPH_HEIGHT};
+use crate::util::{pack_scanner, parallelism};
+use anyhow::{anyhow, Context, Result};
+use image::imageops::{self, FilterType};
+use image::{Rgba, RgbaImage};
+use rayon::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::collections::HashMap;
+use std::path::Path;
+use tracing::info;
+
+/// Default size of each texture on a sheet in pixels
+pub const DEFAULT_SHEET_TILE_SIZE: u32 = 64;
+
+/// Largest allowed texture size on a sheet
+pub const MAX_SHEET_TILE_SIZE: u32 = 256;
+
+/// Default number of columns
+pub const DEFAULT_SHEET_COLUMNS: u32 = 12;
+
+/// Most columns on one sheet
+pub const MAX_SHEET_COLUMNS: u32 = 64;
+
+/// Largest sheet in pixels (256 MB as RGBA)
+pub const MAX_SHEET_PIXELS: u64 = 64 * 1024 * 1024;
+
+/// Most textures placed on one sheet
+pub const MAX_SHEET_TEXTURES: usize = 2048;
+
+/// Space around each texture
+const PADDING: u32 = 6;
+
+/// Height of the label under each texture
+const LABEL_HEIGHT: u32 = GLYPH_HEIGHT + 4;
+
+/// Scale of the title text
+const TITLE_SCALE: u32 = 2;
+
+const BACKGROUND: Rgba<u8> = Rgba([32, 32, 36, 255]);
+const CELL_BACKGROUND: Rgba<u8> = Rgba([48, 48, 54, 255]);
+const CHECKER_LIGHT: Rgba<u8> = Rgba([64, 64, 70, 255]);
+const LABEL_COLOR: Rgba<u8> = Rgba([210, 210, 215, 255]);
+const TITLE_COLOR: Rgba<u8> = Rgba([255, 255, 255, 255]);
+
+/// Layout of a contact sheet
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SheetLayout {
+    /// Size of each texture in pixels
+    pub tile_size: u32,
+    pub columns: u32,
+}
+
+/// A written contact sheet
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ContactSheetExport {
+    pub output_path: String,
+    pub width: u32,
+    pub height: u32,
+    /// Textures on the sheet
+    pub textures: usize,
+    /// Textures left off because the sheet was full
+    pub truncated: usize,
+}
+
+/// One texture to place on the sheet
+struct SheetEntry {
+    label: String,
+    pack_id: String,
+    path: String,
+}
+
+/// Whether a file is a texture of the category, e.g. "block" matches
+/// "assets/minecraft/textures/block/stone.png" and ".../block/sub/x.png"
+fn category_label(file: &str, category: &str) -> Option<String> {
+    let rest = file.strip_prefix("assets/")?;
+    let (namespace, rest) = rest.split_once('/')?;
+    let name = rest
+        .strip_prefix("textures/")?
+        .strip_prefix(category)?
+        .strip_prefix('/')?
+        .strip_suffix(".png")?;
+
+    Some(if namespace == "minecraft" {
+        name.to_string()
+    } else {
+        format!("{}:{}", namespace, name)
+    })
+}
+
+/// Pick the texture for each label: an override wins, then the first pack in
+/// order providing it
+fn collect_entries(
+    packs: &[PackMeta],
+    pack_order: &[String],
+    overrides: &HashMap<String, OverrideSelection>,
+    category: &str,
+) -> Result<Vec<SheetEntry>> {
+    let mut entries: BTreeMap<String, SheetEntry> = BTreeMap::new();
+
+    // Lowest priority first, so higher packs replace earlier picks
+    for pack_id in pack_order.iter().rev() {
+        let pack = packs
+            .iter()
+            .find(|p| &p.id == pack_id)
+            .ok_or_else(|| anyhow!("Pack not found: {}", pack_id))?;
+        for file in pack_scanner::list_pack_files(pack)? {
+            if let Some(label) = category_label(&file, category) {
+                entries.insert(
+                    label.clone(),
+                    SheetEntry {
+                        label,
+                        pack_id: pack.id.clone(),
+                        path: file,
+                    },
+                );
+            }
+        }
+    }
+
+    for entry in entries.values_mut() {
+        let asset_id = match entry.label.split_once(':') {
+            Some((namespace, name)) => format!("{}:{}/{}", namespace, category, name),
+            None => format!("minecraft:{}/{}", category, entry.label),
+        };
+        if let Some(selection) = overrides.get(&asset_id) {
+            entry.pack_id = selection.pack_id.clone();
+            if let Some(variant) = &selection.variant_path {
+                entry.path = variant.clone();
+            }
+        }
+    }
+
+    Ok(entries.into_values().collect())
+}
+
+/// Load a texture scaled to fit a square tile, first frame only
+fn load_tile(pack: &PackMeta, path: &str, tile_size: u32) -> Result<RgbaImage> {
+    let bytes = read_pack_bytes(&pack.path, pack.is_zip, path)?;
+    let image = decode_first_frame(&bytes).with_context(|| format!("Failed to decode {}", path))?;
+
+    let (width, height) = image.dimensions();
+    let scale = tile_size as f32 / width.max(height) as f32;
+    let target_w = ((width as f32 * scale).round() as u32).max(1);
+    let target_h = ((height as f32 * scale).round() as u32).max(1);
+    Ok(imageops::resize(
+        &image,
+        target_w,
+        target_h,
+        FilterType::Nearest,
+    ))
+}
+
+/// Fill a rectangle, with a checkerboard so transparency stays visible
+fn fill_cell(sheet: &mut RgbaImage, x: u32, y: u32, size: u32) {
+    let square = (size / 8).max(1);
+    for dy in 0..size {
+        for dx in 0..size {
+            let light = ((dx / square) + (dy / square)) % 2 == 0;
+            sheet.put_pixel(
+                x + dx,
+                y + dy,
+                if light {
+                    CHECKER_LIGHT
+                } else {
+                    CELL_BACKGROUND
+                },
+            );
+        }
+    }
+}
+
+/// Columns actually used for a number of tiles
+fn sheet_columns(layout: SheetLayout, tiles: usize) -> u32 {
+    layout
+        .columns
+        .clamp(1, MAX_SHEET_COLUMNS)
+        .min(tiles.max(1) as u32)
+}
+
+/// Width and height of a sheet, computed wide so huge layouts can't overflow
+fn sheet_size(title: &str, tiles: usize, layout: SheetLayout) -> (u64, u64) {
+    let cell_w = (layout.tile_size + PADDING * 2) as u64;
+    let cell_h = (layout.tile_size + PADDING * 2 + LABEL_HEIGHT) as u64;
+    let columns = sheet_columns(layout, tiles) as u64;
+    let rows = (tiles as u64 + columns - 1) / columns;
+    let header = (GLYPH_HEIGHT * TITLE_SCALE + PADDING * 3) as u64;
+
+    let width = (columns * cell_w + (PADDING * 2) as u64)
+        .max((bitmap_font::text_width(title, TITLE_SCALE) + PADDING * 4) as u64);
+    let height = header + rows * cell_h + PADDING as u64;
+    (width, height)
+}
+
+/// Draw labeled tiles into a grid
+///
+/// The sheet must already be checked against `MAX_SHEET_PIXELS`.
+fn compose_sheet(
+    title: &str,
+    tiles: &[(String, Option<RgbaImage>)],
+    layout: SheetLayout,
+) -> RgbaImage {
+    let cell_w = layout.tile_size + PADDING * 2;
+    let cell_h = layout.tile_size + PADDING * 2 + LABEL_HEIGHT;
+    let columns = sheet_columns(layout, tiles.len());
+    let header = GLYPH_HEIGHT * TITLE_SCALE + PADDING * 3;
+
+    let (width, height) = sheet_size(title, tiles.len(), layout);
+    let mut sheet = RgbaImage::from_pixel(width as u32, height as u32, BACKGROUND);
+
+    bitmap_font::draw_text(
+        &mut sheet,
+        PADDING * 2,
+        PADDING * 2,
+        title,
+        TITLE_COLOR,
+        TITLE_SCALE,
+    );
+
+    for (index, (label, tile)) in tiles.iter().enumerate() {
+        let column = index as u32 % columns;
+        let row = index as u32 / columns;
+        let x = PADDING + column * cell_w + PADDING;
+        let y = header + row * cell_h + PADDING;
+
+        fill_cell(&mut sheet, x, y, layout.tile_size);
+        if let Some(tile) = tile {
+            let offset_x = (layout.tile_size - tile.width()) / 2;
+            let offset_y = (layout.tile_size - tile.height()) / 2;
+            imageops::overlay(
+                &mut sheet,
+                tile,
+                (x + offset_x) as i64,
+                (y + offset_y) as i64,
+            );
+        }
+
+        let text = bitmap_font::fit_text(label, cell_w - 2, 1);
+        let text_x = x - PADDING + (cell_w - bitmap_font::text_width(&text, 1)) / 2;
+        bitmap_font::draw_text(
+            &mut sheet,
+            text_x,
+            y + layout.tile_size + 3,
+            &text,
+            LABEL_COLOR,
+            1,
+        );
+    }
+
+    sheet
+}
+
+/// Export a contact sheet of one texture category
+///
+/// # Arguments
+/// * `packs` - Scanned packs
+/// * `pack_order` - Pack IDs in priority order; a single ID exports that pack
+/// * `overrides` - Asset ID -> chosen pack
+/// * `category` - Texture folder under textures/ (e.g. "block", "item")
+/// * `layout` - Tile size and column count; the tile size is capped at
+///   `MAX_SHEET_TILE_SIZE` and sheets over `MAX_SHEET_PIXELS` are refused
+/// * `output_path` - PNG to write
+pub fn export_contact_sheet(
+    packs: &[PackMeta],
+    pack_order: &[String],
+    overrides: &HashMap<String, OverrideSelection>,
+    category: &str,
+    layout: SheetLayout,
+    output_path: &Path,
+) -> Result<ContactSheetExport> {
+    let mut entries = collect_entries(packs, pack_order, overrides, category)?;
+    if entries.is_empty() {
+        return Err(anyhow!("No \"{}\" textures found", category));
+    }
+    let truncated = entries.len().saturating_sub(MAX_SHEET_TEXTURES);
+    entries.truncate(MAX_SHEET_TEXTURES);
+
+    let layout = SheetLayout {
+        tile_size: layout.tile_size.clamp(1, MAX_SHEET_TILE_SIZE),
+        columns: layout.columns,
+    };
+    let source = match pack_order {
+        [single] => single.trim_end_matches(".zip").to_string(),
+        _ => format!("Merged ({} packs)", pack_order.len()),
+    };
+    let title = format!("{} - {} ({})", source, category, entries.len());
+    // Check before decoding anything: the sheet is allocated in one piece
+    let (width, height) = sheet_size(&title, entries.len(), layout);
+    if width * height > MAX_SHEET_PIXELS {
+        return Err(anyhow!(
+            "A {}x{} sheet of {} textures is too large; use a smaller tile size",
+            width,
+            height,
+            entries.len()
+        ));
+    }
+
+    let pack_map: HashMap<&str, &PackMeta> = packs.iter().map(|p| (p.id.as_str(), p)).collect();
+    let tiles: Vec<(String, Option<RgbaImage>)> = parallelism::with_cpu_pool(|| {
+        entries
+            .par_iter()
+            .map(|entry| {
+                let tile = pack_map
+                    .get(entry.pack_id.as_str())
+                    .and_then(|pack| load_tile(pack, &entry.path, layout.tile_size).ok());
+                (entry.label.clone(), tile)
+            })
+            .collect()
+    });
+
+    let sheet = compose_sheet(&title, &tiles, layout);
+
+    if let Some(parent) = output_path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    sheet
+        .save(output_path)
+        .with_context(|| format!("Failed to write {:?}", output_path))?;
+
+    info!(
+        "Wrote {} textures ({}x{}) to {:?}",
+        tiles.len(),
+        sheet.width(),
+        sheet.height(),
+        output_path
+    );
+
+    Ok(ContactSheetExport {
+        output_path: output_path.to_string_lossy().to_string(),
+        width: sheet.width(),
+        height: sheet.height(),
+        textures: tiles.len(),
+        truncated,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    #[test]
+    fn test_category_label() {
+        assert_eq!(
+            category_label("assets/minecraft/textures/block/stone.png", "block"),
+            Some("stone".to_string())
+        );
+        assert_eq!(
+            category_label("assets/create/textures/block/gearbox.png", "block"),
+            Some("create:gearbox".to_string())
+        );
+        assert_eq!(
+            category_label("assets/minecraft/textures/blocks/stone.png", "block"),
+            None
+        );
+        assert_eq!(
+            category_label("assets/minecraft/textures/block/stone.png.mcmeta", "block"),
+            None
+        );
+    }
+
+    #[test]
+    fn test_sheet_size_budget() {
+        let full = |tile_size| {
+            let layout = SheetLayout {
+                tile_size,
+                columns: DEFAULT_SHEET_COLUMNS,
+            };
+            let (width, height) = sheet_size("title", MAX_SHEET_TEXTURES, layout);
+            width * height
+        };
+
+        assert!(full(DEFAULT_SHEET_TILE_SIZE) <= MAX_SHEET_PIXELS);
+        assert!(full(MAX_SHEET_TILE_SIZE) > MAX_SHEET_PIXELS);
+        assert_eq!(
+            sheet_columns(
+                SheetLayout {
+                    tile_size: 16,
+                    columns: 10_000
+                },
+                5000
+            ),
+            MAX_SHEET_COLUMNS
+        );
+    }
+
+    #[test]
+    fn test_export_contact_sheet_merges_by_priority() {
+        let temp_dir = std::env::temp_dir().join("test_contact_sheet");
+        let mut packs = Vec::new();
+        for (id, files) in [("low", &["stone", "dirt"][..]), ("high", &["stone"][..])] {
+            let dir = temp_dir.join(id).join("assets/minecraft/textures/block");
+            fs::create_dir_all(&dir).expect("Failed to create test directory");
+            for name in files {
+                RgbaImage::from_pixel(16, 16, Rgba([200, 100, 50, 255]))
+                    .save(dir.join(format!("{}.png", name)))
+                    .expect("Failed to write image");
+            }
+            packs.push(PackMeta {
+                id: id.to_string(),
+                name: id.to_string(),
+                path: temp_dir.join(id).to_string_lossy().to_string(),
+                ..Default::default()
+            });
+        }
+        let output = temp_dir.join("sheet.png");
+        let order = ["high".to_string(), "low".to_string()];
+        let layout = SheetLayout {
+            tile_size: 32,
+            columns: 4,
+        };
+
+        let entries = collect_entries(&packs, &order, &HashMap::new(), "block");
+        let export =
+            export_contact_sheet(&packs, &order, &HashMap::new(), "block", layout, &output);
+        let missing =
+            export_contact_sheet(&packs, &order, &HashMap::new(), "item", layout, &output);
+        let written = image::open(&output).map(|i| i.to_rgba8());
+
+        // Clean up
+        fs::remove_dir_all(&temp_dir).ok();
+
+        let entries = entries.expect("collecting should succeed");
+        let picks: Vec<(&str, &str)> = entries
+            .iter()
+            .map(|e| (e.label.as_str(), e.pack_id.as_str()))
+            .collect();
+        assert_eq!(picks, vec![("dirt", "low"), ("stone", "high")]);
+
+        let export = export.expect("export should succeed");
+        assert_eq!(export.textures, 2);
+        assert_eq!(export.truncated, 0);
+        assert!(missing.is_err());
+        let written = written.expect("sheet should be readable");
+        assert_eq!(written.dimensions(), (export.width, export.height));
+    }
+}
@@ -0,0 +1,286 @@
+/// Content hashing for dedupe and provenance
+///
+/// Asset contents are hashed with xxHash (XXH3), a fast non-cryptographic
+/// hash, while packs are indexed. That is enough to spot packs shipping
+/// byte-identical copies of an asset. SHA-1 is only needed for server
+/// resource-pack distribution and is computed on demand for the output.
+use crate::model::PackMeta;
+use crate::util::{post_build, zip};
+use anyhow::{anyhow, Context, Result};
+use rayon::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::Read;
+use std::path::Path;
+use std::time::Instant;
+use xxhash_rust::xxh3::{xxh3_64, Xxh3};
+
+/// Asset ID -> combined content hash of its files, for one pack
+pub type AssetHashes = HashMap<String, u64>;
+
+/// Cost of hashing during indexing
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HashTimings {
+    pub files_hashed: usize,
+    pub bytes_hashed: u64,
+    /// Time spent hashing, summed across packs
+    pub hash_ms: u64,
+    /// Wall-clock time of the whole indexing pass, hashing included
+    pub total_ms: u64,
+}
+
+/// Result of an on-demand SHA-1
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Sha1Result {
+    pub sha1: String,
+    pub duration_ms: u64,
+}
+
+/// Hashes of one pack's assets plus what it cost
+#[derive(Debug, Clone, Default)]
+pub struct PackHashResult {
+    pub hashes: AssetHashes,
+    pub files_hashed: usize,
+    pub bytes_hashed: u64,
+    pub hash_ms: u64,
+}
+
+/// XXH3 of a byte slice as 16 hex characters
+pub fn xxh3_hex(bytes: &[u8]) -> String {
+    format!("{:016x}", xxh3_64(bytes))
+}
+
+/// Combine an asset's file hashes into one, independent of file order
+fn combine_file_hashes(mut files: Vec<(String, u64)>) -> u64 {
+    if files.len() == 1 {
+        return files[0].1;
+    }
+    files.sort();
+    let mut hasher = Xxh3::new();
+    for (path, hash) in files {
+        hasher.update(path.as_bytes());
+        hasher.update(&hash.to_le_bytes());
+    }
+    hasher.digest()
+}
+
+/// Hash every file of the given assets in one pack
+///
+/// # Arguments
+/// * `pack` - Pack to read from
+/// * `assets` - Asset ID -> file paths within the pack
+pub fn hash_pack_assets(
+    pack: &PackMeta,
+    assets: &HashMap<String, Vec<String>>,
+) -> Result<PackHashResult> {
+    let start = Instant::now();
+    let files: Vec<&String> = assets.values().flatten().collect();
+
+    let pooled = if pack.is_zip {
+        zip::pooled_archive(&pack.path)?
+    } else {
+        None
+    };
+
+    let file_hashes: HashMap<&String, (u64, u64)> = if let Some(archive) = pooled {
+        // Pooled archives are cheap to clone, so each thread reads its own
+        files
+            .par_iter()
+            .map_init(
+                || archive.clone(),
+                |archive, path| {
+                    let bytes = zip::read_archive_entry(archive, path)
+                        .with_context(|| format!("Failed to read zip entry {}", path))?;
+                    Ok((*path, (xxh3_64(&bytes), bytes.len() as u64)))
+                },
+            )
+            .collect::<Result<_>>()?
+    } else if pack.is_zip {
+        // A zip archive can't be shared across threads; read it sequentially
+        let file = File::open(&pack.path)
+            .map_err(|e| anyhow!("Failed to open zip {}: {}", pack.path, e))?;
+        let mut archive =
+            ::zip::ZipArchive::new(file).map_err(|e| anyhow!("Failed to read zip: {}", e))?;
+        let mut hashes = HashMap::new();
+        for path in files {
+            let mut entry = archive
+                .by_name(path)
+                .map_err(|e| anyhow!("Failed to read zip entry {}: {}", path, e))?;
+            let mut bytes = Vec::with_capacity(entry.size() as usize);
+            entry
+                .read_to_end(&mut bytes)
+                .with_context(|| format!("Failed to read zip entry {}", path))?;
+            hashes.insert(path, (xxh3_64(&bytes), bytes.len() as u64));
+        }
+        hashes
+    } else {
+        let root = Path::new(&pack.path);
+        files
+            .par_iter()
+            .map(|path| {
+                let bytes = std::fs::read(root.join(path))
+                    .with_context(|| format!("Failed to read {}", path))?;
+                Ok((*path, (xxh3_64(&bytes), bytes.len() as u64)))
+            })
+            .collect::<Result<_>>()?
+    };
+
+    let hashes = assets
+        .iter()
+        .map(|(asset_id, paths)| {
+            let files = paths
+                .iter()
+                .filter_map(|p| file_hashes.get(p).map(|(hash, _)| (p.clone(), *hash)))
+                .collect();
+            (asset_id.clone(), combine_file_hashes(files))
+        })
+        .collect();
+
+    Ok(PackHashResult {
+        hashes,
+        files_hashed: file_hashes.len(),
+        bytes_hashed: file_hashes.values().map(|(_, size)| size).sum(),
+        hash_ms: start.elapsed().as_millis() as u64,
+    })
+}
+
+/// Group each asset's providers by identical content
+///
+/// # Arguments
+/// * `providers` - Asset ID -> pack IDs, in priority order
+/// * `pack_hashes` - Pack ID -> asset hashes
+///
+/// # Returns
+/// Asset ID -> groups of two or more packs whose copies are byte-identical
+pub fn identical_provider_groups(
+    providers: &HashMap<String, Vec<String>>,
+    pack_hashes: &HashMap<String, AssetHashes>,
+) -> HashMap<String, Vec<Vec<String>>> {
+    let mut result = HashMap::new();
+
+    for (asset_id, pack_ids) in providers {
+        if pack_ids.len() < 2 {
+            continue;
+        }
+
+        // Keep groups in provider order
+        let mut groups: Vec<(u64, Vec<String>)> = Vec::new();
+        for pack_id in pack_ids {
+            let hash = match pack_hashes.get(pack_id).and_then(|h| h.get(asset_id)) {
+                Some(hash) => *hash,
+                None => continue,
+            };
+            match groups.iter_mut().find(|(h, _)| *h == hash) {
+                Some((_, group)) => group.push(pack_id.clone()),
+                None => groups.push((hash, vec![pack_id.clone()])),
+            }
+        }
+
+        let duplicates: Vec<Vec<String>> = groups
+            .into_iter()
+            .map(|(_, group)| group)
+            .filter(|group| group.len() > 1)
+            .collect();
+        if !duplicates.is_empty() {
+            result.insert(asset_id.clone(), duplicates);
+        }
+    }
+
+    result
+}
+
+/// Compute the SHA-1 of a build output for server distribution, with timing
+pub fn sha1_on_demand(output: &Path) -> Result<Sha1Result> {
+    let start = Instant::now();
+    let sha1 = post_build::hash_output_sha1(output)?;
+
+    Ok(Sha1Result {
+        sha1,
+        duration_ms: start.elapsed().as_millis() as u64,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    fn folder_pack(id: &str, path: &Path) -> PackMeta {
+        PackMeta {
+            id: id.to_string(),
+            name: id.to_string(),
+            path: path.to_string_lossy().to_string(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_xxh3_hex() {
+        assert_eq!(xxh3_hex(b"stone").len(), 16);
+        assert_eq!(xxh3_hex(b"stone"), xxh3_hex(b"stone"));
+        assert_ne!(xxh3_hex(b"stone"), xxh3_hex(b"dirt"));
+    }
+
+    #[test]
+    fn test_hash_pack_assets_and_identical_groups() {
+        let temp_dir = std::env::temp_dir().join("test_content_hash");
+        let file = "assets/minecraft/textures/block/stone.png";
+        for (pack, contents) in [("a", "same"), ("b", "same"), ("c", "different")] {
+            let path = temp_dir.join(pack).join(file);
+            fs::create_dir_all(path.parent().unwrap()).expect("Failed to create test directory");
+            fs::write(path, contents).expect("Failed to write file");
+        }
+        let assets: HashMap<String, Vec<String>> =
+            [("minecraft:block/stone".to_string(), vec![file.to_string()])]
+                .into_iter()
+                .collect();
+
+        let results: Vec<(String, Result<PackHashResult>)> = ["c", "a", "b"]
+            .iter()
+            .map(|id| {
+                let pack = folder_pack(id, &temp_dir.join(id));
+                (id.to_string(), hash_pack_assets(&pack, &assets))
+            })
+            .collect();
+
+        // Clean up
+        fs::remove_dir_all(&temp_dir).ok();
+
+        let mut pack_hashes = HashMap::new();
+        for (id, result) in results {
+            let result = result.expect("hashing should succeed");
+            assert_eq!(result.files_hashed, 1);
+            pack_hashes.insert(id, result.hashes);
+        }
+        let providers: HashMap<String, Vec<String>> = [(
+            "minecraft:block/stone".to_string(),
+            vec!["c".to_string(), "a".to_string(), "b".to_string()],
+        )]
+        .into_iter()
+        .collect();
+
+        let groups = identical_provider_groups(&providers, &pack_hashes);
+
+        assert_eq!(
+            groups.get("minecraft:block/stone"),
+            Some(&vec![vec!["a".to_string(), "b".to_string()]])
+        );
+    }
+
+    #[test]
+    fn test_combine_file_hashes_ignores_order() {
+        let a = combine_file_hashes(vec![
+            ("x.png".to_string(), 1),
+            ("x.png.mcmeta".to_string(), 2),
+        ]);
+        let b = combine_file_hashes(vec![
+            ("x.png.mcmeta".to_string(), 2),
+            ("x.png".to_string(), 1),
+        ]);
+
+        assert_eq!(a, b);
+    }
+}
@@ -0,0 +1,199 @@
+/// Instances of the CurseForge app
+///
+/// CurseForge detection finds the shared `minecraft/Install` folder, but
+/// packs belong in an instance: each one is a game folder of its own under
+/// the sibling `minecraft/Instances`. Instances are listed with the name,
+/// Minecraft version and mod loader from their minecraftinstance.json (or
+/// the manifest.json of a modpack that was unpacked by hand), in the same
+/// shape as Prism Launcher and MultiMC instances.
+use crate::util::instances::LauncherInstance;
+use serde_json::Value;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Per-instance metadata file written by the CurseForge app
+pub const MINECRAFT_INSTANCE_FILE: &str = "minecraftinstance.json";
+/// Modpack manifest, for instances the app didn't write metadata for
+pub const MODPACK_MANIFEST: &str = "manifest.json";
+
+/// String at a JSON path, if set and not empty
+fn string_at(value: &Value, path: &[&str]) -> Option<String> {
+    path.iter()
+        .try_fold(value, |value, key| value.get(key))
+        .and_then(Value::as_str)
+        .filter(|s| !s.is_empty())
+        .map(str::to_string)
+}
+
+/// Loader and version from a CurseForge loader ID (e.g., "forge-47.2.0"
+/// or "fabric-0.14.21-1.20.1")
+pub fn parse_loader_id(id: &str) -> Option<(String, Option<String>)> {
+    let (loader, rest) = match id.split_once('-') {
+        Some(parts) => parts,
+        None => (id, ""),
+    };
+    if loader.is_empty() {
+        return None;
+    }
+    let version = rest.split('-').next().filter(|v| !v.is_empty());
+    Some((loader.to_lowercase(), version.map(str::to_string)))
+}
+
+/// The Instances folder for a CurseForge folder
+///
+/// Takes the detected `minecraft/Install` folder, the `minecraft` folder
+/// or the Instances folder itself.
+pub fn curseforge_instances_dir(curseforge_dir: &Path) -> PathBuf {
+    match curseforge_dir.file_name().and_then(|n| n.to_str()) {
+        Some("Instances") => curseforge_dir.to_path_buf(),
+        Some("Install") => curseforge_dir.with_file_name("Instances"),
+        _ => curseforge_dir.join("Instances"),
+    }
+}
+
+/// Read one instance folder
+///
+/// Missing or unreadable metadata leaves only the folder name.
+pub fn read_curseforge_instance(instance_dir: &Path) -> LauncherInstance {
+    let read_json = |name: &str| -> Value {
+        fs::read_to_string(instance_dir.join(name))
+            .ok()
+            .and_then(|contents| serde_json::from_str(&contents).ok())
+            .unwrap_or(Value::Null)
+    };
+    let folder_name = instance_dir
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let instance = read_json(MINECRAFT_INSTANCE_FILE);
+
+    let (name, game_version, loader_id, loader_version, image) = if instance.is_object() {
+        (
+            string_at(&instance, &["name"]),
+            string_at(&instance, &["gameVersion"])
+                .or_else(|| string_at(&instance, &["baseModLoader", "minecraftVersion"])),
+            string_at(&instance, &["baseModLoader", "name"]),
+            string_at(&instance, &["baseModLoader", "forgeVersion"]),
+            string_at(&instance, &["profileImagePath"]),
+        )
+    } else {
+        let manifest = read_json(MODPACK_MANIFEST);
+        let primary_loader = manifest
+            .pointer("/minecraft/modLoaders")
+            .and_then(Value::as_array)
+            .and_then(|loaders| {
+                loaders
+                    .iter()
+                    .find(|l| l.get("primary").and_then(Value::as_bool) == Some(true))
+                    .or_else(|| loaders.first())
+            })
+            .and_then(|l| string_at(l, &["id"]));
+        (
+            string_at(&manifest, &["name"]),
+            string_at(&manifest, &["minecraft", "version"]),
+            primary_loader,
+            None,
+            None,
+        )
+    };
+    let (loader, id_version) = loader_id
+        .as_deref()
+        .and_then(parse_loader_id)
+        .map_or((None, None), |(loader, version)| (Some(loader), version));
+
+    LauncherInstance {
+        name: name.unwrap_or(folder_name),
+        path: instance_dir.to_string_lossy().to_string(),
+        // The instance folder is the game folder
+        resourcepacks_dir: instance_dir
+            .join("resourcepacks")
+            .to_string_lossy()
+            .to_string(),
+        icon_key: None,
+        icon_path: image.filter(|path| Path::new(path).is_file()),
+        game_version,
+        loader,
+        loader_version: loader_version.or(id_version),
+    }
+}
+
+/// List the instances of a CurseForge folder, sorted by name
+pub fn scan_curseforge_instances(curseforge_dir: &Path) -> Vec<LauncherInstance> {
+    let entries = match fs::read_dir(curseforge_instances_dir(curseforge_dir)) {
+        Ok(entries) => entries,
+        Err(_) => return Vec::new(),
+    };
+
+    let mut instances: Vec<LauncherInstance> = entries
+        .filter_map(|e| e.ok())
+        .filter(|e| e.path().is_dir() && !e.file_name().to_string_lossy().starts_with('.'))
+        .map(|e| read_curseforge_instance(&e.path()))
+        .collect();
+    instances.sort_by_key(|instance| instance.name.to_lowercase());
+    instances
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_loader_id() {
+        assert_eq!(
+            parse_loader_id("forge-47.2.0"),
+            Some(("forge".to_string(), Some("47.2.0".to_string())))
+        );
+        assert_eq!(
+            parse_loader_id("fabric-0.14.21-1.20.1"),
+            Some(("fabric".to_string(), Some("0.14.21".to_string())))
+        );
+        assert_eq!(parse_loader_id("-1"), None);
+        assert_eq!(
+            curseforge_instances_dir(Path::new("/cf/minecraft/Install")),
+            Path::new("/cf/minecraft/Instances")
+        );
+    }
+
+    #[test]
+    fn test_scan_curseforge_instances() {
+        let temp_dir = std::env::temp_dir().join("test_curseforge_instances");
+        fs::remove_dir_all(&temp_dir).ok();
+        let install = temp_dir.join("minecraft").join("Install");
+        let instances = temp_dir.join("minecraft").join("Instances");
+        fs::create_dir_all(&install).unwrap();
+        fs::create_dir_all(instances.join("ATM9")).unwrap();
+        fs::create_dir_all(instances.join("Unpacked")).unwrap();
+        fs::write(
+            instances.join("ATM9").join(MINECRAFT_INSTANCE_FILE),
+            r#"{"name": "All the Mods 9", "gameVersion": "1.20.1",
+                "baseModLoader": {"name": "forge-47.2.0", "forgeVersion": "47.2.0",
+                "minecraftVersion": "1.20.1", "type": 1}, "profileImagePath": null}"#,
+        )
+        .unwrap();
+        fs::write(
+            instances.join("Unpacked").join(MODPACK_MANIFEST),
+            r#"{"minecraft": {"version": "1.21.1", "modLoaders": [
+                {"id": "neoforge-21.1.77", "primary": true}]}, "name": "Cobblemon"}"#,
+        )
+        .unwrap();
+
+        let listed = scan_curseforge_instances(&install);
+
+        // Clean up
+        fs::remove_dir_all(&temp_dir).ok();
+
+        assert_eq!(listed.len(), 2);
+        let atm = &listed[0];
+        assert_eq!(atm.name, "All the Mods 9");
+        assert_eq!(atm.game_version.as_deref(), Some("1.20.1"));
+        assert_eq!(atm.loader.as_deref(), Some("forge"));
+        assert_eq!(atm.loader_version.as_deref(), Some("47.2.0"));
+        assert_eq!(atm.icon_path, None);
+        assert!(Path::new(&atm.resourcepacks_dir).ends_with("ATM9/resourcepacks"));
+        let unpacked = &listed[1];
+        assert_eq!(unpacked.name, "Cobblemon");
+        assert_eq!(unpacked.game_version.as_deref(), Some("1.21.1"));
+        assert_eq!(unpacked.loader.as_deref(), Some("neoforge"));
+        assert_eq!(unpacked.loader_version.as_deref(), Some("21.1.77"));
+    }
+}
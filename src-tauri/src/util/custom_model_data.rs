@@ -0,0 +1,413 @@
+/// Custom model data (CMD) registry
+///
+/// Collects every `custom_model_data` value used by item models across packs,
+/// both legacy `overrides` predicates in `models/item/*.json` and 1.21.4+ item
+/// definitions in `items/*.json` (`range_dispatch` on `custom_model_data`).
+/// Values that map the same item to different models are reported as
+/// collisions.
+use crate::model::PackMeta;
+use crate::util::animation::read_pack_bytes;
+use crate::util::report_export::csv_field;
+use crate::util::{pack_scanner, parallelism};
+use anyhow::{Context, Result};
+use rayon::prelude::*;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::BTreeMap;
+use tracing::{info, warn};
+
+const CMD_PROPERTY: &str = "minecraft:custom_model_data";
+
+/// Where a custom model data value was declared
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum CmdSource {
+    /// `overrides` predicate in `models/item/<item>.json`
+    LegacyOverride,
+    /// `range_dispatch` entry in `items/<item>.json`
+    ItemDefinition,
+}
+
+/// A single custom model data value declared by a pack
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CmdEntry {
+    /// Item ID (e.g., "minecraft:diamond_sword")
+    pub item: String,
+    pub value: f64,
+    /// Model the value selects
+    pub model: String,
+    pub pack_id: String,
+    /// File the value was declared in, relative to the pack root
+    pub file: String,
+    pub source: CmdSource,
+}
+
+/// All values used by one item
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CmdItemReport {
+    pub item: String,
+    /// Distinct values in ascending order
+    pub values: Vec<f64>,
+    pub entries: Vec<CmdEntry>,
+}
+
+/// One item/value pair mapped to different models
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CmdCollision {
+    pub item: String,
+    pub value: f64,
+    pub entries: Vec<CmdEntry>,
+}
+
+/// Custom model data usage across packs
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CmdReport {
+    pub items: Vec<CmdItemReport>,
+    pub collisions: Vec<CmdCollision>,
+    pub total_entries: usize,
+}
+
+/// Item ID for an item model or item definition path
+///
+/// Examples:
+/// - "assets/minecraft/models/item/stick.json" -> ("minecraft:stick", LegacyOverride)
+/// - "assets/minecraft/items/stick.json" -> ("minecraft:stick", ItemDefinition)
+fn parse_item_file(path: &str) -> Option<(String, CmdSource)> {
+    let rest = path.strip_prefix("assets/")?.strip_suffix(".json")?;
+    let (namespace, rest) = rest.split_once('/')?;
+
+    if let Some(item) = rest.strip_prefix("models/item/") {
+        Some((format!("{}:{}", namespace, item), CmdSource::LegacyOverride))
+    } else {
+        rest.strip_prefix("items/")
+            .map(|item| (format!("{}:{}", namespace, item), CmdSource::ItemDefinition))
+    }
+}
+
+/// Short description of an item model node: its model ID or node type
+fn describe_model(node: &Value) -> String {
+    node.get("model")
+        .and_then(Value::as_str)
+        .or_else(|| node.get("type").and_then(Value::as_str))
+        .unwrap_or("unknown")
+        .to_string()
+}
+
+/// Collect `range_dispatch` thresholds on custom model data anywhere in an item model tree
+fn collect_dispatch_entries(node: &Value, out: &mut Vec<(f64, String)>) {
+    match node {
+        Value::Object(map) => {
+            let is_cmd_dispatch = map.get("type").and_then(Value::as_str)
+                == Some("minecraft:range_dispatch")
+                && map.get("property").and_then(Value::as_str) == Some(CMD_PROPERTY);
+
+            if is_cmd_dispatch {
+                for entry in map
+                    .get("entries")
+                    .and_then(Value::as_array)
+                    .into_iter()
+                    .flatten()
+                {
+                    if let Some(threshold) = entry.get("threshold").and_then(Value::as_f64) {
+                        let model = entry.get("model").map(describe_model).unwrap_or_default();
+                        out.push((threshold, model));
+                    }
+                }
+            }
+
+            for child in map.values() {
+                collect_dispatch_entries(child, out);
+            }
+        }
+        Value::Array(items) => {
+            for child in items {
+                collect_dispatch_entries(child, out);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Extract custom model data values from an item model or item definition
+///
+/// # Returns
+/// (value, model) pairs in declaration order
+pub fn parse_cmd_values(json: &str, source: CmdSource) -> Result<Vec<(f64, String)>> {
+    let root: Value = serde_json::from_str(json).context("Invalid item model JSON")?;
+    let mut values = Vec::new();
+
+    match source {
+        CmdSource::LegacyOverride => {
+            for entry in root
+                .get("overrides")
+                .and_then(Value::as_array)
+                .into_iter()
+                .flatten()
+            {
+                let value = entry
+                    .get("predicate")
+                    .and_then(|p| p.get("custom_model_data"))
+                    .and_then(Value::as_f64);
+                let model = entry.get("model").and_then(Value::as_str);
+                if let (Some(value), Some(model)) = (value, model) {
+                    values.push((value, model.to_string()));
+                }
+            }
+        }
+        CmdSource::ItemDefinition => collect_dispatch_entries(&root, &mut values),
+    }
+
+    Ok(values)
+}
+
+/// List item model and item definition files in a pack
+fn list_item_files(pack: &PackMeta) -> Result<Vec<String>> {
+    Ok(pack_scanner::list_pack_files(pack)?
+        .into_iter()
+        .filter(|f| parse_item_file(f).is_some())
+        .collect())
+}
+
+/// Collect custom model data entries declared by a single pack
+pub fn scan_pack_cmd(pack: &PackMeta) -> Result<Vec<CmdEntry>> {
+    let mut entries = Vec::new();
+
+    for file in list_item_files(pack)? {
+        let (item, source) = match parse_item_file(&file) {
+            Some(parsed) => parsed,
+            None => continue,
+        };
+        let bytes = read_pack_bytes(&pack.path, pack.is_zip, &file);
+        let values =
+            match bytes.and_then(|b| parse_cmd_values(&String::from_utf8_lossy(&b), source)) {
+                Ok(values) => values,
+                Err(e) => {
+                    warn!("Skipping {} in {}: {}", file, pack.id, e);
+                    continue;
+                }
+            };
+
+        entries.extend(values.into_iter().map(|(value, model)| CmdEntry {
+            item: item.clone(),
+            value,
+            model,
+            pack_id: pack.id.clone(),
+            file: file.clone(),
+            source,
+        }));
+    }
+
+    Ok(entries)
+}
+
+/// Group entries per item and find values that map to more than one model
+pub fn build_cmd_report(entries: Vec<CmdEntry>) -> CmdReport {
+    let total_entries = entries.len();
+    let mut by_item: BTreeMap<String, Vec<CmdEntry>> = BTreeMap::new();
+    for entry in entries {
+        by_item.entry(entry.item.clone()).or_default().push(entry);
+    }
+
+    let mut items = Vec::new();
+    let mut collisions = Vec::new();
+
+    for (item, mut entries) in by_item {
+        entries.sort_by(|a, b| {
+            a.value
+                .partial_cmp(&b.value)
+                .unwrap_or(std::cmp::Ordering::Equal)
+        });
+
+        let mut values: Vec<f64> = entries.iter().map(|e| e.value).collect();
+        values.dedup();
+
+        for value in &values {
+            let same_value: Vec<CmdEntry> = entries
+                .iter()
+                .filter(|e| e.value == *value)
+                .cloned()
+                .collect();
+            let first_model = &same_value[0].model;
+            if same_value.iter().any(|e| &e.model != first_model) {
+                collisions.push(CmdCollision {
+                    item: item.clone(),
+                    value: *value,
+                    entries: same_value,
+                });
+            }
+        }
+
+        items.push(CmdItemReport {
+            item,
+            values,
+            entries,
+        });
+    }
+
+    CmdReport {
+        items,
+        collisions,
+        total_entries,
+    }
+}
+
+/// Build a custom model data report for a set of packs
+pub fn scan_custom_model_data(packs: &[PackMeta]) -> CmdReport {
+    let entries: Vec<CmdEntry> = parallelism::with_io_pool(|| {
+        packs
+            .par_iter()
+            .flat_map(|pack| match scan_pack_cmd(pack) {
+                Ok(entries) => entries,
+                Err(e) => {
+                    warn!("Failed to scan {}: {}", pack.id, e);
+                    Vec::new()
+                }
+            })
+            .collect()
+    });
+
+    let report = build_cmd_report(entries);
+    info!(
+        "{} values across {} items, {} collisions",
+        report.total_entries,
+        report.items.len(),
+        report.collisions.len()
+    );
+    report
+}
+
+/// Render a report as CSV (one row per entry) for spreadsheets
+pub fn cmd_report_to_csv(report: &CmdReport) -> String {
+    let mut csv = String::from("item,value,model,pack,file,collision\n");
+    for item in &report.items {
+        for entry in &item.entries {
+            let collision = report
+                .collisions
+                .iter()
+                .any(|c| c.item == entry.item && c.value == entry.value);
+            csv.push_str(&format!(
+                "{},{},{},{},{},{}\n",
+                csv_field(&entry.item),
+                entry.value,
+                csv_field(&entry.model),
+                csv_field(&entry.pack_id),
+                csv_field(&entry.file),
+                collision
+            ));
+        }
+    }
+    csv
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    #[test]
+    fn test_parse_item_file() {
+        assert_eq!(
+            parse_item_file("assets/minecraft/models/item/stick.json"),
+            Some(("minecraft:stick".to_string(), CmdSource::LegacyOverride))
+        );
+        assert_eq!(
+            parse_item_file("assets/minecraft/items/stick.json"),
+            Some(("minecraft:stick".to_string(), CmdSource::ItemDefinition))
+        );
+        assert_eq!(
+            parse_item_file("assets/minecraft/models/block/stone.json"),
+            None
+        );
+    }
+
+    #[test]
+    fn test_parse_legacy_overrides() {
+        let json = r#"{
+            "parent": "item/handheld",
+            "overrides": [
+                {"predicate": {"custom_model_data": 1001}, "model": "custom:item/ruby_sword"},
+                {"predicate": {"pulling": 1}, "model": "item/bow_pulling_0"},
+                {"predicate": {"custom_model_data": 1002}, "model": "custom:item/jade_sword"}
+            ]
+        }"#;
+
+        let values = parse_cmd_values(json, CmdSource::LegacyOverride).unwrap();
+
+        assert_eq!(
+            values,
+            vec![
+                (1001.0, "custom:item/ruby_sword".to_string()),
+                (1002.0, "custom:item/jade_sword".to_string())
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_item_definition_range_dispatch() {
+        let json = r#"{
+            "model": {
+                "type": "minecraft:range_dispatch",
+                "property": "minecraft:custom_model_data",
+                "fallback": {"type": "minecraft:model", "model": "item/stick"},
+                "entries": [
+                    {"threshold": 5, "model": {"type": "minecraft:model", "model": "custom:item/wand"}},
+                    {"threshold": 6, "model": {"type": "minecraft:condition"}}
+                ]
+            }
+        }"#;
+
+        let values = parse_cmd_values(json, CmdSource::ItemDefinition).unwrap();
+
+        assert_eq!(
+            values,
+            vec![
+                (5.0, "custom:item/wand".to_string()),
+                (6.0, "minecraft:condition".to_string())
+            ]
+        );
+    }
+
+    #[test]
+    fn test_report_flags_collisions_across_packs() {
+        let temp_dir = std::env::temp_dir().join("test_custom_model_data");
+        let model = r#"{"overrides": [{"predicate": {"custom_model_data": 7}, "model": "MODEL"}]}"#;
+        let mut packs = Vec::new();
+        for (name, target) in [
+            ("a", "a:item/one"),
+            ("b", "b:item/two"),
+            ("c", "a:item/one"),
+        ] {
+            let dir = temp_dir.join(name).join("assets/minecraft/models/item");
+            fs::create_dir_all(&dir).expect("Failed to create test directory");
+            fs::write(dir.join("stick.json"), model.replace("MODEL", target))
+                .expect("Failed to write model");
+            packs.push(PackMeta {
+                id: name.to_string(),
+                name: name.to_string(),
+                path: temp_dir.join(name).to_string_lossy().to_string(),
+                ..Default::default()
+            });
+        }
+
+        let report = scan_custom_model_data(&packs);
+        let ab_only = scan_custom_model_data(&[packs[0].clone(), packs[2].clone()]);
+
+        // Clean up
+        fs::remove_dir_all(&temp_dir).ok();
+
+        assert_eq!(report.total_entries, 3);
+        assert_eq!(report.items.len(), 1);
+        assert_eq!(report.items[0].values, vec![7.0]);
+        assert_eq!(report.collisions.len(), 1);
+        assert_eq!(report.collisions[0].entries.len(), 3);
+        assert!(ab_only.collisions.is_empty());
+
+        let csv = cmd_report_to_csv(&report);
+        assert!(csv.starts_with("item,value,model,pack,file,collision\n"));
+        assert!(csv.contains("minecraft:stick,7,b:item/two,b,"));
+    }
+}
@@ -0,0 +1,352 @@
+/// Documentation imagery in one call
+///
+/// Pack authors keep pictures of their textures in READMEs and wikis, and
+/// regenerating them by hand after every build gets old. This renders
+/// everything for a list of assets into one folder: an isometric block
+/// render per block texture (other textures are scaled up flat), vanilla
+/// comparisons, a contact sheet per texture category the assets come from,
+/// and optionally the preview scene. previews.json lists what was written
+/// so a docs build can pick the images up. An asset that can't be rendered
+/// is reported and the rest of the batch carries on.
+use crate::model::{OverrideSelection, PackMeta};
+use crate::util::comparison::{self, ComparisonLayout};
+use crate::util::contact_sheet::{self, SheetLayout};
+use crate::util::preview_scene;
+use crate::util::weaver_nest::WinnerRules;
+use anyhow::{anyhow, Context, Result};
+use image::imageops::{self, FilterType};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeSet, HashMap};
+use std::path::Path;
+use tracing::info;
+
+/// Manifest written next to the images
+pub const PREVIEW_MANIFEST_FILE: &str = "previews.json";
+
+/// Default size of renders and comparisons in pixels
+pub const DEFAULT_PREVIEW_SIZE: u32 = 128;
+
+/// Largest allowed size of renders and comparisons
+pub const MAX_PREVIEW_SIZE: u32 = preview_scene::MAX_TILE_SIZE;
+
+/// Subfolders of the output folder
+const RENDERS_DIR: &str = "renders";
+const COMPARISONS_DIR: &str = "comparisons";
+const SHEETS_DIR: &str = "sheets";
+const SCENE_FILE: &str = "scene.png";
+
+/// What to render
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct DocPreviewOptions {
+    /// Render each asset on its own
+    pub renders: bool,
+    /// Layout of vanilla comparisons; none are written if None
+    pub comparison_layout: Option<ComparisonLayout>,
+    /// Write a contact sheet for each texture category of the assets
+    pub contact_sheets: bool,
+    /// Also render the preview scene
+    pub scene: bool,
+    /// Width of renders and comparisons in pixels
+    pub size: u32,
+}
+
+impl Default for DocPreviewOptions {
+    fn default() -> Self {
+        Self {
+            renders: true,
+            comparison_layout: None,
+            contact_sheets: true,
+            scene: false,
+            size: DEFAULT_PREVIEW_SIZE,
+        }
+    }
+}
+
+/// What a batch wrote
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DocPreviewSummary {
+    pub output_dir: String,
+    /// Written images, relative to the output folder with '/' separators
+    pub files: Vec<String>,
+    /// Assets or images that couldn't be rendered, with the reason
+    pub errors: Vec<String>,
+}
+
+/// Texture folder of an asset ("minecraft:entity/chest/normal" -> "entity/chest")
+pub fn texture_category(asset_id: &str) -> Option<&str> {
+    let path = asset_id.split_once(':').map_or(asset_id, |(_, path)| path);
+    path.rsplit_once('/').map(|(category, _)| category)
+}
+
+/// File name for an asset ("minecraft:block/stone" -> "minecraft_block_stone.png")
+fn file_name(asset_id: &str) -> String {
+    format!("{}.png", comparison::file_stem(asset_id))
+}
+
+/// Path relative to the output folder, for the manifest
+fn relative(output_dir: &Path, path: &Path) -> String {
+    path.strip_prefix(output_dir)
+        .unwrap_or(path)
+        .to_string_lossy()
+        .replace('\\', "/")
+}
+
+/// Render one asset: a shaded cube for block textures, the texture
+/// scaled up with nearest-neighbor otherwise
+#[allow(clippy::too_many_arguments)]
+fn render_asset(
+    asset_id: &str,
+    packs: &[PackMeta],
+    pack_order: &[String],
+    overrides: &HashMap<String, OverrideSelection>,
+    rules: &WinnerRules,
+    vanilla_dir: Option<&Path>,
+    size: u32,
+    output_path: &Path,
+) -> Result<()> {
+    let (texture, _) = preview_scene::resolve_asset_texture(
+        asset_id,
+        packs,
+        pack_order,
+        overrides,
+        rules,
+        vanilla_dir,
+    )
+    .ok_or_else(|| anyhow!("texture not found"))?;
+
+    let image = if texture_category(asset_id) == Some("block") {
+        preview_scene::render_block_icon(&texture, size)
+    } else {
+        let height =
+            (size as u64 * texture.height() as u64 / texture.width().max(1) as u64).max(1) as u32;
+        imageops::resize(&texture, size, height, FilterType::Nearest)
+    };
+    image
+        .save(output_path)
+        .with_context(|| format!("Failed to write {:?}", output_path))
+}
+
+/// Render documentation images for a list of assets
+///
+/// # Arguments
+/// * `packs` - Scanned packs
+/// * `pack_order` - Pack IDs in priority order (first = highest)
+/// * `overrides` - Asset ID -> chosen pack
+/// * `rules` - Disabled packs and namespace owners, as in a build
+/// * `vanilla_dir` - Vanilla texture cache; needed for comparisons
+/// * `asset_ids` - Texture asset IDs (e.g. "minecraft:block/stone")
+/// * `options` - Which kinds of image to write
+/// * `output_dir` - Folder to write into; previews.json goes at its root
+#[allow(clippy::too_many_arguments)]
+pub fn render_doc_previews(
+    packs: &[PackMeta],
+    pack_order: &[String],
+    overrides: &HashMap<String, OverrideSelection>,
+    rules: &WinnerRules,
+    vanilla_dir: Option<&Path>,
+    asset_ids: &[String],
+    options: &DocPreviewOptions,
+    output_dir: &Path,
+) -> Result<DocPreviewSummary> {
+    if !(4..=MAX_PREVIEW_SIZE).contains(&options.size) {
+        return Err(anyhow!(
+            "Preview size must be between 4 and {} pixels",
+            MAX_PREVIEW_SIZE
+        ));
+    }
+    std::fs::create_dir_all(output_dir)
+        .with_context(|| format!("Failed to create {:?}", output_dir))?;
+
+    let mut files = Vec::new();
+    let mut errors = Vec::new();
+
+    if options.renders {
+        let renders_dir = output_dir.join(RENDERS_DIR);
+        std::fs::create_dir_all(&renders_dir)?;
+        for asset_id in asset_ids {
+            let path = renders_dir.join(file_name(asset_id));
+            match render_asset(
+                asset_id,
+                packs,
+                pack_order,
+                overrides,
+                rules,
+                vanilla_dir,
+                options.size,
+                &path,
+            ) {
+                Ok(()) => files.push(relative(output_dir, &path)),
+                Err(e) => errors.push(format!("{}: {}", asset_id, e)),
+            }
+        }
+    }
+
+    if let Some(layout) = options.comparison_layout {
+        match vanilla_dir {
+            Some(vanilla_dir) => {
+                let export = comparison::export_comparisons(
+                    asset_ids,
+                    packs,
+                    pack_order,
+                    overrides,
+                    rules,
+                    vanilla_dir,
+                    layout,
+                    options.size,
+                    &output_dir.join(COMPARISONS_DIR),
+                )?;
+                files.extend(
+                    export
+                        .images
+                        .iter()
+                        .flat_map(|image| &image.files)
+                        .map(|file| relative(output_dir, Path::new(file))),
+                );
+                errors.extend(export.errors);
+            }
+            None => errors.push("Comparisons skipped: vanilla textures are not available".into()),
+        }
+    }
+
+    if options.contact_sheets {
+        let categories: BTreeSet<&str> = asset_ids
+            .iter()
+            .filter_map(|id| texture_category(id))
+            .collect();
+        let layout = SheetLayout {
+            tile_size: contact_sheet::DEFAULT_SHEET_TILE_SIZE,
+            columns: contact_sheet::DEFAULT_SHEET_COLUMNS,
+        };
+        for category in categories {
+            let path = output_dir
+                .join(SHEETS_DIR)
+                .join(format!("{}.png", category.replace('/', "_")));
+            match contact_sheet::export_contact_sheet(
+                packs, pack_order, overrides, category, layout, &path,
+            ) {
+                Ok(_) => files.push(relative(output_dir, &path)),
+                Err(e) => errors.push(format!("Contact sheet {}: {}", category, e)),
+            }
+        }
+    }
+
+    if options.scene {
+        let path = output_dir.join(SCENE_FILE);
+        match preview_scene::render_preview_scene(
+            packs,
+            pack_order,
+            overrides,
+            rules,
+            vanilla_dir,
+            preview_scene::DEFAULT_TILE_SIZE,
+            None,
+            &path,
+        ) {
+            Ok(_) => files.push(relative(output_dir, &path)),
+            Err(e) => errors.push(format!("Scene: {}", e)),
+        }
+    }
+
+    files.sort();
+    let summary = DocPreviewSummary {
+        output_dir: output_dir.to_string_lossy().to_string(),
+        files,
+        errors,
+    };
+    let manifest = serde_json::to_string_pretty(&summary)?;
+    std::fs::write(output_dir.join(PREVIEW_MANIFEST_FILE), manifest)
+        .with_context(|| format!("Failed to write {}", PREVIEW_MANIFEST_FILE))?;
+
+    info!(
+        "Wrote {} image(s) to {:?}, {} error(s)",
+        summary.files.len(),
+        output_dir,
+        summary.errors.len()
+    );
+
+    Ok(summary)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use image::{Rgba, RgbaImage};
+    use std::fs;
+
+    fn write_texture(pack_dir: &Path, path: &str, color: [u8; 4]) {
+        let file = pack_dir.join("assets/minecraft/textures").join(path);
+        fs::create_dir_all(file.parent().unwrap()).unwrap();
+        RgbaImage::from_pixel(16, 16, Rgba(color))
+            .save(file)
+            .unwrap();
+    }
+
+    #[test]
+    fn test_texture_category() {
+        assert_eq!(texture_category("minecraft:block/stone"), Some("block"));
+        assert_eq!(
+            texture_category("minecraft:entity/chest/normal"),
+            Some("entity/chest")
+        );
+        assert_eq!(texture_category("minecraft:stone"), None);
+    }
+
+    #[test]
+    fn test_render_doc_previews() {
+        let temp_dir = std::env::temp_dir().join("test_doc_previews");
+        fs::remove_dir_all(&temp_dir).ok();
+        let pack_dir = temp_dir.join("pack");
+        write_texture(&pack_dir, "block/stone.png", [120, 120, 120, 255]);
+        write_texture(&pack_dir, "item/stick.png", [150, 100, 50, 255]);
+        let packs = vec![PackMeta {
+            id: "pack".to_string(),
+            name: "pack".to_string(),
+            path: pack_dir.to_string_lossy().to_string(),
+            ..Default::default()
+        }];
+        let asset_ids = vec![
+            "minecraft:block/stone".to_string(),
+            "minecraft:item/stick".to_string(),
+            "minecraft:block/missing".to_string(),
+        ];
+        let output = temp_dir.join("docs");
+
+        let summary = render_doc_previews(
+            &packs,
+            &["pack".to_string()],
+            &HashMap::new(),
+            &WinnerRules::default(),
+            None,
+            &asset_ids,
+            &DocPreviewOptions {
+                size: 32,
+                ..Default::default()
+            },
+            &output,
+        )
+        .unwrap();
+        let stick = image::open(output.join("renders/minecraft_item_stick.png")).unwrap();
+        let manifest = fs::read_to_string(output.join(PREVIEW_MANIFEST_FILE)).unwrap();
+
+        // Clean up
+        fs::remove_dir_all(&temp_dir).ok();
+
+        assert_eq!(
+            summary.files,
+            vec![
+                "renders/minecraft_block_stone.png",
+                "renders/minecraft_item_stick.png",
+                "sheets/block.png",
+                "sheets/item.png",
+            ]
+        );
+        assert_eq!(
+            summary.errors,
+            vec!["minecraft:block/missing: texture not found"]
+        );
+        assert_eq!((stick.width(), stick.height()), (32, 32));
+        assert!(manifest.contains("sheets/item.png"));
+    }
+}
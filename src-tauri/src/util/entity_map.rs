@@ -0,0 +1,300 @@
+/// Entity names for entity textures
+///
+/// Block textures map to blocks through blockstates, but nothing in a pack
+/// says which entity `entity/zombie/husk.png` or `entity/chest/trapped.png`
+/// belongs to. Most entity textures live in a folder named after their
+/// entity (`entity/cow/*` is the cow), so that is the default; a built-in
+/// table covers the folders shared by several entities, renamed ones and
+/// armor layers. Textures of one entity are grouped so multi-texture
+/// entities (a sheep and its wool, a horse and its markings) show up once.
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// How a table entry names the entity
+#[derive(Debug, Clone, Copy)]
+enum EntityRule {
+    /// Always this entity
+    Is(&'static str),
+    /// The entity named by the texture's file name
+    File,
+}
+
+/// Texture path (after the namespace) -> entity
+///
+/// A key ending in '/' or '*' matches everything starting with it, other
+/// keys match exactly. The longest matching key wins.
+const ENTITY_TABLE: &[(&str, EntityRule)] = &[
+    // Armor: 1.21.2+ equipment layers and the older per-material layers
+    ("entity/equipment/humanoid/", EntityRule::Is("armor")),
+    (
+        "entity/equipment/humanoid_leggings/",
+        EntityRule::Is("armor"),
+    ),
+    ("entity/equipment/horse_body/", EntityRule::Is("horse")),
+    ("entity/equipment/llama_body/", EntityRule::Is("llama")),
+    ("entity/equipment/wolf_body/", EntityRule::Is("wolf")),
+    ("entity/equipment/wings/", EntityRule::Is("elytra")),
+    ("entity/equipment/pig_saddle/", EntityRule::Is("pig")),
+    (
+        "entity/equipment/strider_saddle/",
+        EntityRule::Is("strider"),
+    ),
+    ("entity/equipment/camel_saddle/", EntityRule::Is("camel")),
+    ("entity/equipment/horse_saddle/", EntityRule::Is("horse")),
+    (
+        "entity/equipment/happy_ghast_body/",
+        EntityRule::Is("happy_ghast"),
+    ),
+    ("models/armor/", EntityRule::Is("armor")),
+    // Block entities
+    ("entity/chest/", EntityRule::Is("chest")),
+    ("entity/chest/ender", EntityRule::Is("ender_chest")),
+    ("entity/chest/trapped*", EntityRule::Is("trapped_chest")),
+    ("entity/banner_base", EntityRule::Is("banner")),
+    ("entity/banner/", EntityRule::Is("banner")),
+    ("entity/shield_base*", EntityRule::Is("shield")),
+    ("entity/shield/", EntityRule::Is("shield")),
+    ("entity/signs/", EntityRule::Is("sign")),
+    ("entity/signs/hanging/", EntityRule::Is("hanging_sign")),
+    (
+        "entity/enchanting_table_book",
+        EntityRule::Is("enchanting_table"),
+    ),
+    ("entity/trident_riptide", EntityRule::Is("trident")),
+    ("entity/end_gateway_beam", EntityRule::Is("end_gateway")),
+    // Folders shared by several entities
+    ("entity/zombie/husk", EntityRule::Is("husk")),
+    ("entity/zombie/drowned*", EntityRule::Is("drowned")),
+    (
+        "entity/skeleton/wither_skeleton",
+        EntityRule::Is("wither_skeleton"),
+    ),
+    ("entity/skeleton/stray*", EntityRule::Is("stray")),
+    ("entity/skeleton/bogged*", EntityRule::Is("bogged")),
+    ("entity/spider/cave_spider", EntityRule::Is("cave_spider")),
+    ("entity/slime/magmacube", EntityRule::Is("magma_cube")),
+    ("entity/cow/red_mooshroom", EntityRule::Is("mooshroom")),
+    ("entity/cow/brown_mooshroom", EntityRule::Is("mooshroom")),
+    ("entity/cat/ocelot", EntityRule::Is("ocelot")),
+    ("entity/horse/donkey", EntityRule::Is("donkey")),
+    ("entity/horse/mule", EntityRule::Is("mule")),
+    (
+        "entity/horse/horse_skeleton",
+        EntityRule::Is("skeleton_horse"),
+    ),
+    ("entity/horse/horse_zombie", EntityRule::Is("zombie_horse")),
+    ("entity/ghast/happy_ghast*", EntityRule::Is("happy_ghast")),
+    ("entity/fish/tropical*", EntityRule::Is("tropical_fish")),
+    ("entity/fish/", EntityRule::File),
+    ("entity/illager/", EntityRule::File),
+    ("entity/illager/vex_charging", EntityRule::Is("vex")),
+    ("entity/piglin/", EntityRule::File),
+    ("entity/hoglin/", EntityRule::File),
+    ("entity/squid/", EntityRule::File),
+    ("entity/projectiles/", EntityRule::File),
+    ("entity/guardian_elder", EntityRule::Is("elder_guardian")),
+    ("entity/guardian_beam", EntityRule::Is("guardian")),
+    // Renamed
+    ("entity/bear/", EntityRule::Is("polar_bear")),
+    ("entity/enderdragon/", EntityRule::Is("ender_dragon")),
+    ("entity/steve", EntityRule::Is("player")),
+    ("entity/alex", EntityRule::Is("player")),
+    ("entity/player/", EntityRule::Is("player")),
+];
+
+/// Textures of one entity
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EntityGroup {
+    /// e.g. "minecraft:zombie"
+    pub entity: String,
+    /// e.g. "Zombie"
+    pub label: String,
+    /// Texture asset IDs, sorted
+    pub textures: Vec<String>,
+}
+
+/// Whether a table key matches a texture path
+fn key_matches(key: &str, path: &str) -> bool {
+    match key.strip_suffix('*') {
+        Some(prefix) => path.starts_with(prefix),
+        None if key.ends_with('/') => path.starts_with(key),
+        None => path == key,
+    }
+}
+
+/// File name of a texture path without its folders
+fn file_stem(path: &str) -> &str {
+    path.rsplit('/').next().unwrap_or(path)
+}
+
+/// Entity a texture belongs to
+///
+/// "minecraft:entity/zombie/husk" -> "minecraft:husk",
+/// "minecraft:entity/chest/trapped_left" -> "minecraft:trapped_chest"
+///
+/// # Returns
+/// None for textures that aren't entity textures
+pub fn entity_for_texture(asset_id: &str) -> Option<String> {
+    let (namespace, path) = asset_id.split_once(':').unwrap_or(("minecraft", asset_id));
+
+    let rule = ENTITY_TABLE
+        .iter()
+        .filter(|(key, _)| key_matches(key, path))
+        .max_by_key(|(key, _)| key.len());
+    let entity = match rule {
+        Some((_, EntityRule::Is(entity))) => entity,
+        Some((_, EntityRule::File)) => file_stem(path),
+        // Otherwise the folder under entity/, or the file if there is none
+        None => {
+            let rest = path.strip_prefix("entity/")?;
+            rest.split('/').next().filter(|name| !name.is_empty())?
+        }
+    };
+
+    Some(format!("{}:{}", namespace, entity))
+}
+
+/// Display name of an entity: "minecraft:ender_dragon" -> "Ender Dragon"
+pub fn entity_label(entity: &str) -> String {
+    let name = entity.split_once(':').map_or(entity, |(_, name)| name);
+    name.split('_')
+        .filter(|word| !word.is_empty())
+        .map(|word| {
+            let mut chars = word.chars();
+            match chars.next() {
+                Some(first) => first.to_uppercase().chain(chars).collect(),
+                None => String::new(),
+            }
+        })
+        .collect::<Vec<String>>()
+        .join(" ")
+}
+
+/// Group entity textures by the entity they belong to
+///
+/// # Arguments
+/// * `asset_ids` - Texture asset IDs; non-entity textures are left out
+///
+/// # Returns
+/// Entities sorted by label
+pub fn group_entity_textures<'a, I>(asset_ids: I) -> Vec<EntityGroup>
+where
+    I: IntoIterator<Item = &'a String>,
+{
+    let mut groups: BTreeMap<String, Vec<String>> = BTreeMap::new();
+    for asset_id in asset_ids {
+        if let Some(entity) = entity_for_texture(asset_id) {
+            groups.entry(entity).or_default().push(asset_id.clone());
+        }
+    }
+
+    let mut groups: Vec<EntityGroup> = groups
+        .into_iter()
+        .map(|(entity, mut textures)| {
+            textures.sort();
+            EntityGroup {
+                label: entity_label(&entity),
+                entity,
+                textures,
+            }
+        })
+        .collect();
+    groups.sort_by(|a, b| a.label.cmp(&b.label).then_with(|| a.entity.cmp(&b.entity)));
+    groups
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_entity_for_texture() {
+        let cases = [
+            ("minecraft:entity/zombie/zombie", Some("minecraft:zombie")),
+            ("minecraft:entity/zombie/husk", Some("minecraft:husk")),
+            (
+                "minecraft:entity/zombie/drowned_outer_layer",
+                Some("minecraft:drowned"),
+            ),
+            (
+                "minecraft:entity/creeper/creeper_armor",
+                Some("minecraft:creeper"),
+            ),
+            (
+                "minecraft:entity/chest/christmas_left",
+                Some("minecraft:chest"),
+            ),
+            (
+                "minecraft:entity/chest/trapped_right",
+                Some("minecraft:trapped_chest"),
+            ),
+            (
+                "minecraft:entity/chest/ender",
+                Some("minecraft:ender_chest"),
+            ),
+            (
+                "minecraft:entity/equipment/humanoid/diamond",
+                Some("minecraft:armor"),
+            ),
+            (
+                "minecraft:models/armor/iron_layer_2",
+                Some("minecraft:armor"),
+            ),
+            (
+                "minecraft:entity/illager/pillager",
+                Some("minecraft:pillager"),
+            ),
+            (
+                "minecraft:entity/fish/tropical_a_pattern_1",
+                Some("minecraft:tropical_fish"),
+            ),
+            (
+                "minecraft:entity/bear/polarbear",
+                Some("minecraft:polar_bear"),
+            ),
+            (
+                "minecraft:entity/experience_orb",
+                Some("minecraft:experience_orb"),
+            ),
+            ("mymod:entity/wyvern/wyvern_red", Some("mymod:wyvern")),
+            ("minecraft:block/stone", None),
+        ];
+        for (asset_id, entity) in cases {
+            assert_eq!(
+                entity_for_texture(asset_id).as_deref(),
+                entity,
+                "{}",
+                asset_id
+            );
+        }
+    }
+
+    #[test]
+    fn test_group_entity_textures() {
+        let ids: Vec<String> = [
+            "minecraft:entity/sheep/sheep_fur",
+            "minecraft:entity/sheep/sheep",
+            "minecraft:entity/enderdragon/dragon",
+            "minecraft:entity/enderdragon/dragon_eyes",
+            "minecraft:block/dirt",
+        ]
+        .iter()
+        .map(|s| s.to_string())
+        .collect();
+
+        let groups = group_entity_textures(&ids);
+
+        assert_eq!(groups.len(), 2);
+        assert_eq!(groups[0].label, "Ender Dragon");
+        assert_eq!(groups[0].textures.len(), 2);
+        assert_eq!(groups[1].entity, "minecraft:sheep");
+        assert_eq!(
+            groups[1].textures,
+            vec![
+                "minecraft:entity/sheep/sheep",
+                "minecraft:entity/sheep/sheep_fur"
+            ]
+        );
+    }
+}
@@ -0,0 +1,316 @@
+/// Per-face texture sources of a block model
+///
+/// Overrides can pick a block's textures from different packs, so one block
+/// may end up with its top from one pack and its sides from another. This
+/// walks the element faces of a resolved model and reports, per direction,
+/// the texture drawn there and the pack a build would take it from.
+use crate::model::{OverrideSelection, PackMeta};
+use crate::util::animation;
+use crate::util::block_models::{resolve_textures, BlockModel, PackFiles};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use tracing::info;
+
+/// Where one face texture comes from
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FaceTextureSource {
+    /// Texture reference on the face (e.g. "#side")
+    pub variable: String,
+    /// Texture ID after resolving variables, None if the variable is undefined
+    pub texture_id: Option<String>,
+    /// Texture file inside the source pack
+    pub texture_path: Option<String>,
+    /// Pack a build would take the texture from, None if nothing provides it
+    pub pack_id: Option<String>,
+}
+
+/// Texture sources of every face direction of a model
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FaceTextureBreakdown {
+    pub model_id: String,
+    /// Direction (north, up, ...) -> textures drawn on it, base layer first
+    pub faces: BTreeMap<String, Vec<FaceTextureSource>>,
+    /// Packs the faces come from, in order of first use
+    pub source_packs: Vec<String>,
+}
+
+/// "block/dirt" -> "minecraft:block/dirt"
+fn normalize_texture_id(texture_id: &str) -> String {
+    if texture_id.contains(':') {
+        texture_id.to_string()
+    } else {
+        format!("minecraft:{}", texture_id)
+    }
+}
+
+/// Looks up which pack a build would take a texture from
+struct TextureSources<'a> {
+    packs: &'a [PackMeta],
+    pack_order: &'a [String],
+    overrides: &'a HashMap<String, OverrideSelection>,
+    vanilla_pack: &'a PackMeta,
+    files: HashMap<String, PackFiles>,
+}
+
+impl<'a> TextureSources<'a> {
+    fn has_file(&mut self, pack: &PackMeta, path: &str) -> bool {
+        self.files
+            .entry(pack.id.clone())
+            .or_insert_with(|| PackFiles::new(pack))
+            .contains(path)
+    }
+
+    /// An override wins, then the first pack in priority order, then vanilla
+    fn find(&mut self, texture_id: &str) -> Option<(String, String)> {
+        let file = animation::texture_path_for_asset(texture_id);
+        let packs = self.packs;
+
+        if let Some(selection) = self.overrides.get(texture_id) {
+            let path = selection
+                .variant_path
+                .clone()
+                .unwrap_or_else(|| file.clone());
+            if let Some(pack) = packs.iter().find(|p| p.id == selection.pack_id) {
+                if self.has_file(pack, &path) {
+                    return Some((pack.id.clone(), path));
+                }
+            }
+        }
+
+        for pack_id in self.pack_order {
+            if let Some(pack) = packs.iter().find(|p| &p.id == pack_id) {
+                if self.has_file(pack, &file) {
+                    return Some((pack.id.clone(), file));
+                }
+            }
+        }
+
+        let vanilla_pack = self.vanilla_pack;
+        if self.has_file(vanilla_pack, &file) {
+            return Some((vanilla_pack.id.clone(), file));
+        }
+        None
+    }
+}
+
+/// Work out which texture and pack each face of a model uses
+///
+/// # Arguments
+/// * `model_id` - ID of the model, echoed in the result
+/// * `model` - Resolved model (parents merged, variant rotation applied)
+/// * `packs` - Scanned packs
+/// * `pack_order` - Pack IDs in priority order (first = highest)
+/// * `overrides` - Asset ID -> chosen pack
+/// * `vanilla_pack` - Vanilla texture cache, used when no pack provides a texture
+pub fn face_texture_breakdown(
+    model_id: &str,
+    model: &BlockModel,
+    packs: &[PackMeta],
+    pack_order: &[String],
+    overrides: &HashMap<String, OverrideSelection>,
+    vanilla_pack: &PackMeta,
+) -> FaceTextureBreakdown {
+    let variables = resolve_textures(model);
+    let mut sources = TextureSources {
+        packs,
+        pack_order,
+        overrides,
+        vanilla_pack,
+        files: HashMap::new(),
+    };
+
+    let mut faces: BTreeMap<String, Vec<FaceTextureSource>> = BTreeMap::new();
+    let mut source_packs: Vec<String> = Vec::new();
+
+    for element in model.elements.iter().flatten() {
+        // HashMap order varies between runs; keep the output stable
+        let mut element_faces: Vec<_> = element.faces.iter().collect();
+        element_faces.sort_by(|a, b| a.0.cmp(b.0));
+
+        for (direction, face) in element_faces {
+            let entries = faces.entry(direction.clone()).or_default();
+            if entries.iter().any(|e| e.variable == face.texture) {
+                continue;
+            }
+
+            let texture_id = match face.texture.strip_prefix('#') {
+                Some(name) => variables
+                    .get(name)
+                    .filter(|value| !value.starts_with('#'))
+                    .map(|value| normalize_texture_id(value)),
+                None => Some(normalize_texture_id(&face.texture)),
+            };
+            let found = texture_id.as_deref().and_then(|id| sources.find(id));
+
+            if let Some((pack_id, _)) = &found {
+                if !source_packs.contains(pack_id) {
+                    source_packs.push(pack_id.clone());
+                }
+            }
+            let (pack_id, texture_path) = match found {
+                Some((pack_id, path)) => (Some(pack_id), Some(path)),
+                None => (None, None),
+            };
+
+            entries.push(FaceTextureSource {
+                variable: face.texture.clone(),
+                texture_id,
+                texture_path,
+                pack_id,
+            });
+        }
+    }
+
+    info!(
+        "{}: {} faces from {} packs",
+        model_id,
+        faces.len(),
+        source_packs.len()
+    );
+
+    FaceTextureBreakdown {
+        model_id: model_id.to_string(),
+        faces,
+        source_packs,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::util::block_models::{ElementFace, ModelElement};
+    use crate::util::test_support::folder_pack;
+    use std::fs;
+
+    const LOG_TOP: (&str, &str) = ("assets/minecraft/textures/block/test_log_top.png", "png");
+    const LOG_SIDE: (&str, &str) = ("assets/minecraft/textures/block/test_log.png", "png");
+
+    fn face(texture: &str) -> ElementFace {
+        ElementFace {
+            texture: texture.to_string(),
+            uv: None,
+            rotation: None,
+            cullface: None,
+            tintindex: None,
+        }
+    }
+
+    fn log_model() -> BlockModel {
+        let textures: HashMap<String, String> = [
+            ("end", "block/test_log_top"),
+            ("side", "minecraft:block/test_log"),
+            ("particle", "#side"),
+        ]
+        .iter()
+        .map(|(k, v)| (k.to_string(), v.to_string()))
+        .collect();
+        let faces = [
+            ("up", "#end"),
+            ("north", "#particle"),
+            ("down", "#undefined"),
+        ]
+        .iter()
+        .map(|(k, v)| (k.to_string(), face(v)))
+        .collect();
+
+        BlockModel {
+            parent: None,
+            textures: Some(textures),
+            elements: Some(vec![ModelElement {
+                from: [0.0, 0.0, 0.0],
+                to: [16.0, 16.0, 16.0],
+                rotation: None,
+                faces,
+                shade: None,
+            }]),
+            ambientocclusion: None,
+            missing_textures: Vec::new(),
+            builtin: None,
+            display: None,
+            display_transform: None,
+        }
+    }
+
+    #[test]
+    fn test_breakdown_reports_mixed_sources() {
+        let temp_dir = std::env::temp_dir().join("test_face_textures_mixed");
+        fs::remove_dir_all(&temp_dir).ok();
+        let pack_a = folder_pack(&temp_dir, "pack_a", &[LOG_TOP]);
+        let pack_b = folder_pack(&temp_dir, "pack_b", &[LOG_TOP, LOG_SIDE]);
+        let vanilla = folder_pack(&temp_dir, "vanilla", &[]);
+        let packs = vec![pack_a, pack_b];
+        let order = vec!["pack_a".to_string(), "pack_b".to_string()];
+
+        let breakdown = face_texture_breakdown(
+            "minecraft:block/test_log",
+            &log_model(),
+            &packs,
+            &order,
+            &HashMap::new(),
+            &vanilla,
+        );
+
+        // Clean up
+        fs::remove_dir_all(&temp_dir).ok();
+
+        let up = &breakdown.faces["up"][0];
+        assert_eq!(
+            up.texture_id.as_deref(),
+            Some("minecraft:block/test_log_top")
+        );
+        assert_eq!(up.pack_id.as_deref(), Some("pack_a"));
+        assert_eq!(
+            up.texture_path.as_deref(),
+            Some("assets/minecraft/textures/block/test_log_top.png")
+        );
+        let north = &breakdown.faces["north"][0];
+        assert_eq!(
+            north.texture_id.as_deref(),
+            Some("minecraft:block/test_log")
+        );
+        assert_eq!(north.pack_id.as_deref(), Some("pack_b"));
+        let down = &breakdown.faces["down"][0];
+        assert_eq!(down.texture_id, None);
+        assert_eq!(down.pack_id, None);
+        assert_eq!(breakdown.source_packs, vec!["pack_b", "pack_a"]);
+    }
+
+    #[test]
+    fn test_breakdown_honors_overrides_and_vanilla() {
+        let temp_dir = std::env::temp_dir().join("test_face_textures_overrides");
+        fs::remove_dir_all(&temp_dir).ok();
+        let pack_a = folder_pack(&temp_dir, "pack_a", &[LOG_TOP]);
+        let pack_b = folder_pack(&temp_dir, "pack_b", &[LOG_TOP]);
+        let vanilla = folder_pack(&temp_dir, "vanilla", &[LOG_SIDE]);
+        let packs = vec![pack_a, pack_b];
+        let order = vec!["pack_a".to_string(), "pack_b".to_string()];
+        let mut overrides = HashMap::new();
+        overrides.insert(
+            "minecraft:block/test_log_top".to_string(),
+            OverrideSelection {
+                pack_id: "pack_b".to_string(),
+                variant_path: None,
+            },
+        );
+
+        let breakdown = face_texture_breakdown(
+            "minecraft:block/test_log",
+            &log_model(),
+            &packs,
+            &order,
+            &overrides,
+            &vanilla,
+        );
+
+        // Clean up
+        fs::remove_dir_all(&temp_dir).ok();
+
+        assert_eq!(breakdown.faces["up"][0].pack_id.as_deref(), Some("pack_b"));
+        assert_eq!(
+            breakdown.faces["north"][0].pack_id.as_deref(),
+            Some("vanilla")
+        );
+    }
+}
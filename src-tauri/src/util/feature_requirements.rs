@@ -0,0 +1,311 @@
+/// Mod feature detection and requirements summary
+///
+/// Detects OptiFine-style features (CTM, CIT, emissive textures, custom
+/// entity models, random entities, custom sky) and core shaders in each pack,
+/// then aggregates them into a summary of the mods the merged pack needs,
+/// e.g. "Requires Continuity + CIT Resewn, or OptiFine".
+use crate::model::PackMeta;
+use crate::util::{pack_scanner, parallelism};
+use anyhow::Result;
+use rayon::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+use tracing::warn;
+
+/// A feature that needs a mod (or special handling) to work in game
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum PackFeature {
+    /// Connected textures (optifine/ctm)
+    ConnectedTextures,
+    /// Custom item textures (optifine/cit)
+    CustomItemTextures,
+    /// Emissive textures (emissive.properties, *_e.png)
+    Emissive,
+    /// Custom entity models (optifine/cem .jem/.jpm)
+    CustomEntityModels,
+    /// Random/variant entity textures (optifine/random, optifine/mob)
+    RandomEntities,
+    /// Custom sky boxes (optifine/sky)
+    CustomSky,
+    /// Vanilla core shader overrides (shaders/core)
+    CoreShaders,
+}
+
+impl PackFeature {
+    /// Human-readable feature name
+    pub fn label(self) -> &'static str {
+        match self {
+            PackFeature::ConnectedTextures => "Connected textures",
+            PackFeature::CustomItemTextures => "Custom item textures",
+            PackFeature::Emissive => "Emissive textures",
+            PackFeature::CustomEntityModels => "Custom entity models",
+            PackFeature::RandomEntities => "Random entity textures",
+            PackFeature::CustomSky => "Custom sky",
+            PackFeature::CoreShaders => "Core shaders",
+        }
+    }
+
+    /// Fabric/Quilt mod providing the feature, if one is needed
+    pub fn mod_name(self) -> Option<&'static str> {
+        match self {
+            PackFeature::ConnectedTextures => Some("Continuity"),
+            PackFeature::CustomItemTextures => Some("CIT Resewn"),
+            PackFeature::Emissive | PackFeature::RandomEntities => Some("Entity Texture Features"),
+            PackFeature::CustomEntityModels => Some("Entity Model Features"),
+            PackFeature::CustomSky => Some("FabricSkyBoxes Interop"),
+            PackFeature::CoreShaders => None,
+        }
+    }
+
+    /// Whether OptiFine alone supports the feature
+    pub fn optifine_supported(self) -> bool {
+        !matches!(self, PackFeature::CoreShaders)
+    }
+}
+
+/// Mods needed for one feature and the packs using it
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FeatureRequirement {
+    pub feature: PackFeature,
+    pub label: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub mod_name: Option<String>,
+    pub optifine_supported: bool,
+    /// Packs using the feature
+    pub pack_ids: Vec<String>,
+}
+
+/// Requirements of a merged pack
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RequirementsSummary {
+    pub requirements: Vec<FeatureRequirement>,
+    /// One-line summary (e.g. "Requires Continuity + CIT Resewn, or OptiFine")
+    pub summary: String,
+    /// Caveats that don't fit the summary line
+    pub notes: Vec<String>,
+}
+
+/// Feature implied by a single file path, if any
+pub fn feature_for_path(path: &str) -> Option<PackFeature> {
+    let path = path.to_lowercase();
+    if !path.starts_with("assets/") {
+        return None;
+    }
+
+    let in_optifine = |dir: &str| {
+        path.contains(&format!("/optifine/{}/", dir))
+            || path.contains(&format!("/mcpatcher/{}/", dir))
+    };
+
+    if in_optifine("ctm") {
+        Some(PackFeature::ConnectedTextures)
+    } else if in_optifine("cit") {
+        Some(PackFeature::CustomItemTextures)
+    } else if in_optifine("cem") && (path.ends_with(".jem") || path.ends_with(".jpm")) {
+        Some(PackFeature::CustomEntityModels)
+    } else if in_optifine("random") || in_optifine("mob") {
+        Some(PackFeature::RandomEntities)
+    } else if in_optifine("sky") {
+        Some(PackFeature::CustomSky)
+    } else if path.ends_with("/optifine/emissive.properties")
+        || (path.contains("/textures/") && path.ends_with("_e.png"))
+    {
+        Some(PackFeature::Emissive)
+    } else if path.contains("/shaders/core/") {
+        Some(PackFeature::CoreShaders)
+    } else {
+        None
+    }
+}
+
+/// Detect the features used by a list of pack file paths
+pub fn detect_features<'a>(files: impl IntoIterator<Item = &'a String>) -> BTreeSet<PackFeature> {
+    files
+        .into_iter()
+        .filter_map(|f| feature_for_path(f))
+        .collect()
+}
+
+/// Detect the features used by a pack
+pub fn detect_pack_features(pack: &PackMeta) -> Result<BTreeSet<PackFeature>> {
+    Ok(detect_features(&pack_scanner::list_pack_files(pack)?))
+}
+
+/// Combine per-pack features into a requirements summary
+///
+/// # Arguments
+/// * `pack_features` - (pack ID, detected features) for each merged pack
+pub fn summarize_requirements(
+    pack_features: &[(String, BTreeSet<PackFeature>)],
+) -> RequirementsSummary {
+    let mut by_feature: BTreeMap<PackFeature, Vec<String>> = BTreeMap::new();
+    for (pack_id, features) in pack_features {
+        for feature in features {
+            by_feature
+                .entry(*feature)
+                .or_default()
+                .push(pack_id.clone());
+        }
+    }
+
+    let requirements: Vec<FeatureRequirement> = by_feature
+        .into_iter()
+        .map(|(feature, pack_ids)| FeatureRequirement {
+            feature,
+            label: feature.label().to_string(),
+            mod_name: feature.mod_name().map(str::to_string),
+            optifine_supported: feature.optifine_supported(),
+            pack_ids,
+        })
+        .collect();
+
+    let mut mods: Vec<&str> = Vec::new();
+    for requirement in &requirements {
+        if let Some(name) = &requirement.mod_name {
+            if !mods.contains(&name.as_str()) {
+                mods.push(name);
+            }
+        }
+    }
+
+    let summary = if mods.is_empty() {
+        "No mods required".to_string()
+    } else if requirements.iter().all(|r| r.optifine_supported) {
+        format!("Requires {}, or OptiFine", mods.join(" + "))
+    } else {
+        format!("Requires {}", mods.join(" + "))
+    };
+
+    let mut notes = Vec::new();
+    if let Some(shaders) = requirements
+        .iter()
+        .find(|r| r.feature == PackFeature::CoreShaders)
+    {
+        notes.push(format!(
+            "Core shaders from {} may not work alongside OptiFine or Iris shader packs",
+            shaders.pack_ids.join(", ")
+        ));
+    }
+
+    RequirementsSummary {
+        requirements,
+        summary,
+        notes,
+    }
+}
+
+/// Detect features in the given packs and summarize what the merge needs
+pub fn collect_requirements(packs: &[&PackMeta]) -> RequirementsSummary {
+    let pack_features: Vec<(String, BTreeSet<PackFeature>)> = parallelism::with_io_pool(|| {
+        packs
+            .par_iter()
+            .map(|pack| {
+                let features = detect_pack_features(pack).unwrap_or_else(|e| {
+                    warn!("Failed to scan {}: {}", pack.id, e);
+                    BTreeSet::new()
+                });
+                (pack.id.clone(), features)
+            })
+            .collect()
+    });
+
+    summarize_requirements(&pack_features)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_feature_for_path() {
+        let cases = [
+            (
+                "assets/minecraft/optifine/ctm/glass/1.png",
+                Some(PackFeature::ConnectedTextures),
+            ),
+            (
+                "assets/minecraft/mcpatcher/cit/sword.properties",
+                Some(PackFeature::CustomItemTextures),
+            ),
+            (
+                "assets/minecraft/optifine/cem/cow.jem",
+                Some(PackFeature::CustomEntityModels),
+            ),
+            (
+                "assets/minecraft/optifine/random/entity/cow/cow2.png",
+                Some(PackFeature::RandomEntities),
+            ),
+            (
+                "assets/minecraft/textures/entity/creeper/creeper_e.png",
+                Some(PackFeature::Emissive),
+            ),
+            (
+                "assets/minecraft/shaders/core/rendertype_solid.fsh",
+                Some(PackFeature::CoreShaders),
+            ),
+            ("assets/minecraft/textures/block/stone.png", None),
+            ("pack.png", None),
+        ];
+
+        for (path, expected) in cases {
+            assert_eq!(feature_for_path(path), expected, "{}", path);
+        }
+    }
+
+    #[test]
+    fn test_summarize_requirements_with_optifine_alternative() {
+        let packs = vec![
+            (
+                "ctm_pack".to_string(),
+                [PackFeature::ConnectedTextures].into_iter().collect(),
+            ),
+            (
+                "cit_pack".to_string(),
+                [
+                    PackFeature::CustomItemTextures,
+                    PackFeature::ConnectedTextures,
+                ]
+                .into_iter()
+                .collect(),
+            ),
+        ];
+
+        let summary = summarize_requirements(&packs);
+
+        assert_eq!(
+            summary.summary,
+            "Requires Continuity + CIT Resewn, or OptiFine"
+        );
+        assert_eq!(summary.requirements.len(), 2);
+        assert_eq!(
+            summary.requirements[0].pack_ids,
+            vec!["ctm_pack", "cit_pack"]
+        );
+        assert!(summary.notes.is_empty());
+    }
+
+    #[test]
+    fn test_summarize_requirements_core_shaders_and_empty() {
+        let shaders = summarize_requirements(&[
+            (
+                "shader_pack".to_string(),
+                [PackFeature::CoreShaders].into_iter().collect(),
+            ),
+            (
+                "etf_pack".to_string(),
+                [PackFeature::Emissive, PackFeature::RandomEntities]
+                    .into_iter()
+                    .collect(),
+            ),
+        ]);
+        let empty = summarize_requirements(&[("plain".to_string(), BTreeSet::new())]);
+
+        assert_eq!(shaders.summary, "Requires Entity Texture Features");
+        assert_eq!(shaders.notes.len(), 1);
+        assert_eq!(empty.summary, "No mods required");
+        assert!(empty.requirements.is_empty());
+    }
+}
@@ -0,0 +1,126 @@
+/// Show files and folders in the platform file manager
+///
+/// Folders are opened; files are revealed with the file selected where the
+/// platform supports it (Finder, Explorer, and Linux file managers that
+/// implement the freedesktop FileManager1 interface). Other Linux setups
+/// fall back to opening the containing folder.
+use anyhow::{anyhow, Context, Result};
+use std::path::Path;
+use std::process::{Command, Stdio};
+use tracing::info;
+
+/// Open a folder, or show a file selected in its folder
+pub fn reveal_in_file_manager(path: &Path) -> Result<()> {
+    if !path.exists() {
+        return Err(anyhow!("{} does not exist", path.display()));
+    }
+
+    if path.is_dir() {
+        open_folder(path)
+    } else {
+        select_file(path)
+    }?;
+
+    info!("Revealed {}", path.display());
+    Ok(())
+}
+
+fn open_folder(path: &Path) -> Result<()> {
+    #[cfg(target_os = "macos")]
+    let program = "open";
+    #[cfg(target_os = "windows")]
+    let program = "explorer";
+    #[cfg(not(any(target_os = "macos", target_os = "windows")))]
+    let program = "xdg-open";
+
+    Command::new(program)
+        .arg(path)
+        .spawn()
+        .with_context(|| format!("Failed to launch {}", program))?;
+
+    Ok(())
+}
+
+#[cfg(target_os = "macos")]
+fn select_file(path: &Path) -> Result<()> {
+    Command::new("open")
+        .arg("-R")
+        .arg(path)
+        .spawn()
+        .context("Failed to launch open")?;
+    Ok(())
+}
+
+#[cfg(target_os = "windows")]
+fn select_file(path: &Path) -> Result<()> {
+    // Explorer wants "/select," and the path as one argument
+    let mut arg = std::ffi::OsString::from("/select,");
+    arg.push(path);
+    Command::new("explorer")
+        .arg(arg)
+        .spawn()
+        .context("Failed to launch explorer")?;
+    Ok(())
+}
+
+#[cfg(not(any(target_os = "macos", target_os = "windows")))]
+fn select_file(path: &Path) -> Result<()> {
+    let shown = Command::new("dbus-send")
+        .args([
+            "--session",
+            "--print-reply",
+            "--dest=org.freedesktop.FileManager1",
+            "--type=method_call",
+            "/org/freedesktop/FileManager1",
+            "org.freedesktop.FileManager1.ShowItems",
+        ])
+        .arg(format!("array:string:{}", file_uri(path)))
+        .arg("string:")
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .status()
+        .map_or(false, |status| status.success());
+
+    if shown {
+        return Ok(());
+    }
+    match path.parent() {
+        Some(parent) => open_folder(parent),
+        None => open_folder(path),
+    }
+}
+
+/// `file://` URI for an absolute path, percent-encoding unsafe bytes
+#[cfg_attr(any(target_os = "macos", target_os = "windows"), allow(dead_code))]
+fn file_uri(path: &Path) -> String {
+    let absolute = path.canonicalize().unwrap_or_else(|_| path.to_path_buf());
+    let mut uri = String::from("file://");
+    for byte in absolute.to_string_lossy().replace('\\', "/").bytes() {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' | b'/' => {
+                uri.push(byte as char)
+            }
+            _ => uri.push_str(&format!("%{:02X}", byte)),
+        }
+    }
+    uri
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_file_uri_encodes_unsafe_characters() {
+        assert_eq!(
+            file_uri(Path::new("/packs/My Pack #2.zip")),
+            "file:///packs/My%20Pack%20%232.zip"
+        );
+    }
+
+    #[test]
+    fn test_reveal_missing_path_fails() {
+        let missing = std::env::temp_dir().join("test_file_manager_missing.zip");
+        assert!(reveal_in_file_manager(&missing).is_err());
+    }
+}
@@ -0,0 +1,295 @@
+/// Retry file operations that other processes briefly block
+///
+/// On Windows, antivirus scanners and sync clients open freshly written files
+/// for a moment, and creating, renaming or deleting a file meanwhile fails
+/// with a sharing violation. These wrappers retry such failures with backoff.
+/// If the file is still locked after the last attempt they return a
+/// [`FileLockedError`] naming it, which commands report as FILE_LOCKED.
+/// Every path is checked against the path policy first.
+use crate::util::path_policy;
+use anyhow::{Context, Result};
+use std::fmt;
+use std::fs::{self, File};
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use tracing::warn;
+
+/// Waits between attempts (about 1.5s in total)
+const RETRY_DELAYS_MS: [u64; 6] = [25, 50, 100, 200, 400, 800];
+
+/// A file stayed locked by another process through every retry
+#[derive(Debug, Clone)]
+pub struct FileLockedError {
+    pub path: PathBuf,
+    pub operation: &'static str,
+    pub attempts: usize,
+}
+
+impl fmt::Display for FileLockedError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} is locked by another process (could not {} after {} attempts)",
+            self.path.display(),
+            self.operation,
+            self.attempts
+        )
+    }
+}
+
+impl std::error::Error for FileLockedError {}
+
+/// Whether an error means another process holds the file open
+#[cfg(windows)]
+fn is_lock_error(e: &io::Error) -> bool {
+    // ERROR_SHARING_VIOLATION, ERROR_LOCK_VIOLATION
+    matches!(e.raw_os_error(), Some(32) | Some(33))
+}
+
+#[cfg(not(windows))]
+fn is_lock_error(_e: &io::Error) -> bool {
+    false
+}
+
+/// Whether an error may go away on its own
+///
+/// Windows also reports ERROR_ACCESS_DENIED while a file is pending
+/// deletion or being scanned, so that is retried too, but only reported as
+/// a lock if a sharing violation was seen.
+#[cfg(windows)]
+fn is_transient(e: &io::Error) -> bool {
+    is_lock_error(e) || e.raw_os_error() == Some(5)
+}
+
+#[cfg(not(windows))]
+fn is_transient(_e: &io::Error) -> bool {
+    false
+}
+
+fn retry_with<T>(
+    path: &Path,
+    operation: &'static str,
+    delays_ms: &[u64],
+    is_transient: impl Fn(&io::Error) -> bool,
+    is_lock_error: impl Fn(&io::Error) -> bool,
+    mut op: impl FnMut() -> io::Result<T>,
+) -> Result<T> {
+    let mut locked = false;
+    let mut attempts = 0;
+    loop {
+        attempts += 1;
+        let error = match op() {
+            Ok(value) => return Ok(value),
+            Err(e) => e,
+        };
+        locked |= is_lock_error(&error);
+
+        if !is_transient(&error) {
+            return Err(error).with_context(|| format!("Failed to {} {:?}", operation, path));
+        }
+        match delays_ms.get(attempts - 1) {
+            Some(delay) => std::thread::sleep(Duration::from_millis(*delay)),
+            None if locked => {
+                warn!(
+                    "Giving up on {:?} after {} attempts: {}",
+                    path, attempts, error
+                );
+                return Err(FileLockedError {
+                    path: path.to_path_buf(),
+                    operation,
+                    attempts,
+                }
+                .into());
+            }
+            None => {
+                return Err(error).with_context(|| format!("Failed to {} {:?}", operation, path))
+            }
+        }
+    }
+}
+
+fn retry<T>(path: &Path, operation: &'static str, op: impl FnMut() -> io::Result<T>) -> Result<T> {
+    retry_with(
+        path,
+        operation,
+        &RETRY_DELAYS_MS,
+        is_transient,
+        is_lock_error,
+        op,
+    )
+}
+
+/// `fs::write`, retried while the file is locked
+pub fn write_with_retry(path: &Path, contents: &[u8]) -> Result<()> {
+    path_policy::check(path)?;
+    retry(path, "write", || fs::write(path, contents))
+}
+
+/// `File::create`, retried while the file is locked
+pub fn create_file_with_retry(path: &Path) -> Result<File> {
+    path_policy::check(path)?;
+    retry(path, "create", || File::create(path))
+}
+
+/// `fs::rename`, retried while either file is locked
+pub fn rename_with_retry(from: &Path, to: &Path) -> Result<()> {
+    path_policy::check(from)?;
+    path_policy::check(to)?;
+    retry(to, "replace", || fs::rename(from, to))
+}
+
+/// `fs::copy`, retried while either file is locked
+pub fn copy_with_retry(from: &Path, to: &Path) -> Result<u64> {
+    path_policy::check(from)?;
+    path_policy::check(to)?;
+    retry(to, "copy to", || fs::copy(from, to))
+}
+
+/// `fs::remove_file`, retried while the file is locked
+pub fn remove_file_with_retry(path: &Path) -> Result<()> {
+    path_policy::check(path)?;
+    retry(path, "delete", || fs::remove_file(path))
+}
+
+/// `fs::remove_dir_all`, retried while a file inside is locked
+pub fn remove_dir_all_with_retry(path: &Path) -> Result<()> {
+    path_policy::check(path)?;
+    retry(path, "delete", || fs::remove_dir_all(path))
+}
+
+/// Whether a rename failed because the paths are on different volumes,
+/// where only copying can move the file
+pub fn is_cross_device(error: &anyhow::Error) -> bool {
+    error
+        .chain()
+        .filter_map(|cause| cause.downcast_ref::<io::Error>())
+        .any(is_cross_device_error)
+}
+
+#[cfg(windows)]
+fn is_cross_device_error(e: &io::Error) -> bool {
+    // ERROR_NOT_SAME_DEVICE
+    e.raw_os_error() == Some(17)
+}
+
+#[cfg(unix)]
+fn is_cross_device_error(e: &io::Error) -> bool {
+    e.raw_os_error() == Some(libc::EXDEV)
+}
+
+#[cfg(not(any(unix, windows)))]
+fn is_cross_device_error(_e: &io::Error) -> bool {
+    false
+}
+
+/// The locked file behind an error, if a retry gave up on one
+pub fn locked_file(error: &anyhow::Error) -> Option<&FileLockedError> {
+    error
+        .chain()
+        .find_map(|cause| cause.downcast_ref::<FileLockedError>())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::Cell;
+
+    fn locked() -> io::Error {
+        io::Error::new(io::ErrorKind::Other, "sharing violation")
+    }
+
+    #[test]
+    fn test_retry_succeeds_once_unlocked() {
+        let calls = Cell::new(0);
+        let result = retry_with(
+            Path::new("pack.png"),
+            "write",
+            &[0, 0, 0],
+            |_| true,
+            |_| true,
+            || {
+                calls.set(calls.get() + 1);
+                if calls.get() < 3 {
+                    Err(locked())
+                } else {
+                    Ok(7)
+                }
+            },
+        );
+
+        assert_eq!(result.unwrap(), 7);
+        assert_eq!(calls.get(), 3);
+    }
+
+    #[test]
+    fn test_retry_reports_locked_file() {
+        let result: Result<()> = retry_with(
+            Path::new("pack.png"),
+            "replace",
+            &[0, 0],
+            |_| true,
+            |_| true,
+            || Err(locked()),
+        );
+
+        let error = result.unwrap_err();
+        let locked = locked_file(&error).expect("should be a lock error");
+        assert_eq!(locked.path, Path::new("pack.png"));
+        assert_eq!(locked.attempts, 3);
+        assert!(error.to_string().contains("locked by another process"));
+    }
+
+    #[test]
+    fn test_permanent_errors_fail_immediately() {
+        let calls = Cell::new(0);
+        let result: Result<()> = retry_with(
+            Path::new("missing.png"),
+            "delete",
+            &[0, 0],
+            |_| false,
+            |_| false,
+            || {
+                calls.set(calls.get() + 1);
+                Err(io::Error::new(io::ErrorKind::NotFound, "not found"))
+            },
+        );
+
+        let error = result.unwrap_err();
+        assert_eq!(calls.get(), 1);
+        assert!(locked_file(&error).is_none());
+    }
+
+    #[test]
+    fn test_wrappers_on_unlocked_files() {
+        let temp_dir = std::env::temp_dir().join("test_file_retry");
+        fs::create_dir_all(&temp_dir).expect("Failed to create test directory");
+        let a = temp_dir.join("a.txt");
+        let b = temp_dir.join("b.txt");
+
+        let written = write_with_retry(&a, b"hello");
+        let renamed = rename_with_retry(&a, &b);
+        let contents = fs::read_to_string(&b);
+        let removed = remove_file_with_retry(&b);
+        let exists_after = b.exists();
+
+        // Clean up
+        fs::remove_dir_all(&temp_dir).ok();
+
+        assert!(written.is_ok());
+        assert!(renamed.is_ok());
+        assert_eq!(contents.unwrap(), "hello");
+        assert!(removed.is_ok());
+        assert!(!exists_after);
+    }
+
+    #[test]
+    fn test_writes_outside_allowed_roots_are_denied() {
+        let outside = Path::new("/weaverbird_denied_test/pack.png");
+
+        let result = write_with_retry(outside, b"png");
+
+        assert!(result.unwrap_err().to_string().contains("Access denied"));
+        assert!(!outside.exists());
+    }
+}
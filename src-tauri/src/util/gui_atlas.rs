@@ -0,0 +1,238 @@
+/// Named regions of vanilla GUI textures
+///
+/// Before 1.20.2 the game drew its GUI from sprite sheets: widgets.png holds
+/// every button state and the hotbar, icons.png the hearts, armor and food,
+/// and each container texture its background plus the arrows and flames
+/// drawn over it. Comparing two packs' sheets side by side shows mostly empty
+/// space, so this table names the parts the game draws. Coordinates are in
+/// the vanilla 256x256 layout and scaled to HD textures. Newer versions ship
+/// these as separate files under gui/sprites, which need no regions.
+use crate::util::animation::texture_path_for_asset;
+use crate::util::image_probe;
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+
+/// Width and height of the vanilla GUI sheets
+pub const GUI_SHEET_SIZE: u32 = 256;
+
+/// (name, x, y, width, height) in the vanilla layout
+type RegionSpec = (&'static str, u32, u32, u32, u32);
+
+/// Texture path (after the namespace) -> its regions
+const GUI_LAYOUTS: &[(&str, &[RegionSpec])] = &[
+    (
+        "gui/widgets",
+        &[
+            ("hotbar", 0, 0, 182, 22),
+            ("hotbar_selection", 0, 22, 24, 24),
+            ("offhand_left", 24, 22, 29, 24),
+            ("offhand_right", 53, 22, 29, 24),
+            ("button_disabled", 0, 46, 200, 20),
+            ("button", 0, 66, 200, 20),
+            ("button_highlighted", 0, 86, 200, 20),
+            ("language_button", 0, 106, 20, 20),
+            ("language_button_highlighted", 0, 126, 20, 20),
+            ("accessibility_button", 0, 146, 20, 20),
+            ("accessibility_button_highlighted", 0, 166, 20, 20),
+        ],
+    ),
+    (
+        "gui/icons",
+        &[
+            ("crosshair", 0, 0, 15, 15),
+            ("heart_container", 16, 0, 9, 9),
+            ("heart_full", 52, 0, 9, 9),
+            ("heart_half", 61, 0, 9, 9),
+            ("armor_empty", 16, 9, 9, 9),
+            ("armor_half", 25, 9, 9, 9),
+            ("armor_full", 34, 9, 9, 9),
+            ("air_bubble", 16, 18, 9, 9),
+            ("air_bubble_popping", 25, 18, 9, 9),
+            ("food_empty", 16, 27, 9, 9),
+            ("food_full", 52, 27, 9, 9),
+            ("food_half", 61, 27, 9, 9),
+            ("experience_bar_background", 0, 64, 182, 5),
+            ("experience_bar_progress", 0, 69, 182, 5),
+        ],
+    ),
+    (
+        "gui/container/inventory",
+        &[
+            ("background", 0, 0, 176, 166),
+            ("armor_slots", 7, 7, 18, 72),
+            ("player_preview", 25, 7, 51, 72),
+            ("offhand_slot", 76, 61, 18, 18),
+            ("crafting_grid", 97, 17, 36, 36),
+            ("crafting_result", 153, 27, 18, 18),
+            ("main_slots", 7, 83, 162, 54),
+            ("hotbar_slots", 7, 141, 162, 18),
+            ("effect_background", 0, 166, 120, 32),
+        ],
+    ),
+    (
+        "gui/container/generic_54",
+        &[
+            ("chest_slots", 7, 17, 162, 108),
+            ("player_inventory", 0, 126, 176, 96),
+        ],
+    ),
+    (
+        "gui/container/crafting_table",
+        &[
+            ("background", 0, 0, 176, 166),
+            ("crafting_grid", 29, 16, 54, 54),
+            ("result_slot", 119, 30, 26, 26),
+        ],
+    ),
+    (
+        "gui/container/furnace",
+        &[
+            ("background", 0, 0, 176, 166),
+            ("input_slot", 55, 16, 18, 18),
+            ("fuel_slot", 55, 52, 18, 18),
+            ("result_slot", 111, 30, 26, 26),
+            ("lit_progress", 176, 0, 14, 14),
+            ("burn_progress", 176, 14, 24, 17),
+        ],
+    ),
+    (
+        "gui/container/dispenser",
+        &[("background", 0, 0, 176, 166), ("slots", 61, 16, 54, 54)],
+    ),
+    (
+        "gui/container/hopper",
+        &[("background", 0, 0, 176, 133), ("slots", 43, 19, 90, 18)],
+    ),
+    (
+        "gui/container/creative_inventory/tabs",
+        &[
+            ("tab_top_unselected", 0, 0, 26, 32),
+            ("tab_top_selected", 0, 32, 26, 32),
+            ("tab_bottom_unselected", 0, 64, 26, 32),
+            ("tab_bottom_selected", 0, 96, 26, 32),
+            ("scroller", 232, 0, 12, 15),
+            ("scroller_disabled", 244, 0, 12, 15),
+        ],
+    ),
+];
+
+/// A named part of a GUI texture, in the texture's own pixels
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GuiRegion {
+    pub name: String,
+    pub x: u32,
+    pub y: u32,
+    pub width: u32,
+    pub height: u32,
+}
+
+/// Regions of one GUI texture
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GuiAtlas {
+    pub asset_id: String,
+    /// Texture size the regions were scaled to
+    pub width: u32,
+    pub height: u32,
+    /// Texture pixels per vanilla pixel (2.0 for a 512x512 sheet)
+    pub scale: f64,
+    pub regions: Vec<GuiRegion>,
+}
+
+/// Regions of a texture in the vanilla layout
+fn layout_for(asset_id: &str) -> Option<&'static [RegionSpec]> {
+    let path = asset_id.split_once(':').map_or(asset_id, |(_, path)| path);
+    GUI_LAYOUTS
+        .iter()
+        .find(|(layout_path, _)| *layout_path == path)
+        .map(|(_, regions)| *regions)
+}
+
+/// GUI textures with known regions
+pub fn gui_atlas_ids() -> Vec<String> {
+    GUI_LAYOUTS
+        .iter()
+        .map(|(path, _)| format!("minecraft:{}", path))
+        .collect()
+}
+
+/// Regions of a GUI texture scaled to its size
+///
+/// # Arguments
+/// * `asset_id` - GUI texture (e.g., "minecraft:gui/widgets")
+/// * `width` - Width of the pack's texture (256 for vanilla)
+/// * `height` - Height of the pack's texture; the larger side sets the scale
+///
+/// # Returns
+/// None for textures without known regions
+pub fn gui_regions(asset_id: &str, width: u32, height: u32) -> Option<GuiAtlas> {
+    let layout = layout_for(asset_id)?;
+    let scale = width.max(height) as f64 / GUI_SHEET_SIZE as f64;
+    let scaled = |value: u32| (value as f64 * scale).round() as u32;
+
+    let regions = layout
+        .iter()
+        .map(|&(name, x, y, w, h)| GuiRegion {
+            name: name.to_string(),
+            x: scaled(x),
+            y: scaled(y),
+            width: scaled(w),
+            height: scaled(h),
+        })
+        // Sheets trimmed to their used rows lose what's below
+        .filter(|region| region.x + region.width <= width && region.y + region.height <= height)
+        .collect();
+
+    Some(GuiAtlas {
+        asset_id: asset_id.to_string(),
+        width,
+        height,
+        scale,
+        regions,
+    })
+}
+
+/// Regions of a pack's copy of a GUI texture
+///
+/// # Returns
+/// None for textures without known regions
+pub fn pack_gui_regions(pack_path: &str, is_zip: bool, asset_id: &str) -> Result<Option<GuiAtlas>> {
+    if layout_for(asset_id).is_none() {
+        return Ok(None);
+    }
+    let (width, height) =
+        image_probe::pack_file_dimensions(pack_path, is_zip, &texture_path_for_asset(asset_id))?;
+    Ok(gui_regions(asset_id, width, height))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_gui_regions() {
+        let vanilla = gui_regions("minecraft:gui/widgets", 256, 256).unwrap();
+        let hd = gui_regions("minecraft:gui/widgets", 512, 512).unwrap();
+        let trimmed = gui_regions("gui/container/inventory", 256, 166).unwrap();
+
+        let button = vanilla.regions.iter().find(|r| r.name == "button").unwrap();
+        assert_eq!(
+            (button.x, button.y, button.width, button.height),
+            (0, 66, 200, 20)
+        );
+        let button = hd.regions.iter().find(|r| r.name == "button").unwrap();
+        assert_eq!(
+            (button.x, button.y, button.width, button.height),
+            (0, 132, 400, 40)
+        );
+        assert_eq!(hd.scale, 2.0);
+        assert!(trimmed.regions.iter().any(|r| r.name == "background"));
+        assert!(!trimmed
+            .regions
+            .iter()
+            .any(|r| r.name == "effect_background"));
+        assert!(gui_regions("minecraft:block/stone", 16, 16).is_none());
+        assert!(gui_atlas_ids().contains(&"minecraft:gui/icons".to_string()));
+    }
+}
@@ -0,0 +1,134 @@
+/// Block and model IDs of a pack, for autocomplete and early validation
+///
+/// The frontend offers these as suggestions in search and override fields,
+/// and block commands check requested IDs against them before scanning
+/// packs. Listing the vanilla cache walks a few thousand files, so the
+/// result is kept until the cache is re-extracted.
+use crate::model::PackMeta;
+use crate::util::{model_texture_deps, texture_index};
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+use std::time::SystemTime;
+use tracing::info;
+
+/// Catalog with the pack path and blockstates mtime it was built at
+type CachedCatalog = ((String, Option<SystemTime>), Arc<IdCatalog>);
+
+/// Catalog of the pack listed last
+static CACHED: Lazy<Mutex<Option<CachedCatalog>>> = Lazy::new(|| Mutex::new(None));
+
+/// Block and model IDs, sorted
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct IdCatalog {
+    /// Blocks with a blockstate (e.g., "oak_stairs")
+    pub block_ids: Vec<String>,
+    /// Models (e.g., "minecraft:block/oak_stairs")
+    pub model_ids: Vec<String>,
+}
+
+/// "Oak_Stairs" and "oakstairs" both match "oak_stairs", as in blockstate lookup
+fn block_key(block_id: &str) -> String {
+    block_id.replace('_', "").to_lowercase()
+}
+
+impl IdCatalog {
+    /// List the IDs a pack ships
+    pub fn build(pack: &PackMeta) -> IdCatalog {
+        let mut block_ids = texture_index::list_block_ids(pack);
+        block_ids.sort();
+        block_ids.dedup();
+
+        let mut model_ids: Vec<String> = model_texture_deps::list_model_files(pack)
+            .unwrap_or_default()
+            .iter()
+            .filter_map(|f| model_texture_deps::model_id_for_path(f))
+            .collect();
+        model_ids.sort();
+        model_ids.dedup();
+
+        IdCatalog {
+            block_ids,
+            model_ids,
+        }
+    }
+
+    /// Whether a block ID, without namespace, has a blockstate here
+    pub fn knows_block(&self, block_id: &str) -> bool {
+        let key = block_key(block_id);
+        self.block_ids.iter().any(|id| block_key(id) == key)
+    }
+}
+
+/// Catalog of a folder pack, reused while its blockstates are unchanged
+///
+/// Meant for the vanilla cache; re-extraction rewrites the blockstates
+/// folder, which invalidates the cached catalog.
+pub fn cached_catalog(pack: &PackMeta) -> Arc<IdCatalog> {
+    let modified = std::fs::metadata(Path::new(&pack.path).join("assets/minecraft/blockstates"))
+        .and_then(|m| m.modified())
+        .ok();
+
+    let mut cached = CACHED.lock().unwrap_or_else(|e| e.into_inner());
+    let key = (pack.path.clone(), modified);
+    if let Some((cached_key, catalog)) = cached.as_ref() {
+        if cached_key == &key && key.1.is_some() {
+            return catalog.clone();
+        }
+    }
+
+    let catalog = Arc::new(IdCatalog::build(pack));
+    info!(
+        "Listed {} block(s) and {} model(s) in {}",
+        catalog.block_ids.len(),
+        catalog.model_ids.len(),
+        pack.name
+    );
+    *cached = Some((key, catalog.clone()));
+    catalog
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::util::vanilla_textures::VANILLA_PACK_ID;
+    use std::fs;
+
+    #[test]
+    fn test_build_catalog() {
+        let temp_dir = std::env::temp_dir().join("test_id_catalog");
+        fs::remove_dir_all(&temp_dir).ok();
+        for file in [
+            "assets/minecraft/blockstates/stone.json",
+            "assets/minecraft/blockstates/oak_stairs.json",
+            "assets/minecraft/models/block/stone.json",
+            "assets/minecraft/models/item/stone.json",
+            "assets/minecraft/textures/block/stone.png",
+        ] {
+            let path = temp_dir.join(file);
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            fs::write(path, "{}").unwrap();
+        }
+        let pack = PackMeta {
+            id: VANILLA_PACK_ID.to_string(),
+            name: "Vanilla".to_string(),
+            path: temp_dir.to_string_lossy().to_string(),
+            ..Default::default()
+        };
+
+        let catalog = IdCatalog::build(&pack);
+
+        // Clean up
+        fs::remove_dir_all(&temp_dir).ok();
+
+        assert_eq!(catalog.block_ids, vec!["oak_stairs", "stone"]);
+        assert_eq!(
+            catalog.model_ids,
+            vec!["minecraft:block/stone", "minecraft:item/stone"]
+        );
+        assert!(catalog.knows_block("oakstairs"));
+        assert!(!catalog.knows_block("ruby_block"));
+    }
+}
@@ -0,0 +1,180 @@
+/// Header-only image dimension probing
+///
+/// Reports and previews often need only a texture's size, and asking the
+/// image crate for it still reads the whole file (or, in a ZIP, inflates
+/// the whole entry). PNG stores its size in the IHDR chunk right after the
+/// signature and JPEG in its frame header, so reading the first KB is
+/// enough for nearly every file. Files whose header doesn't fit in it
+/// (e.g. a JPEG with a large EXIF block) are read in full instead.
+use crate::util::animation;
+use crate::util::zip::{self, safe_join};
+use anyhow::{anyhow, Context, Result};
+use std::fs::File;
+use std::io::{Cursor, Read};
+use std::path::Path;
+
+/// Bytes read from the start of a file before falling back to a full read
+pub const PROBE_BYTES: usize = 1024;
+
+const PNG_SIGNATURE: &[u8] = b"\x89PNG\r\n\x1a\n";
+
+/// Size from a PNG's IHDR chunk, which must directly follow the signature
+fn png_dimensions(header: &[u8]) -> Option<(u32, u32)> {
+    if header.len() < 24 || !header.starts_with(PNG_SIGNATURE) || &header[12..16] != b"IHDR" {
+        return None;
+    }
+    let width = u32::from_be_bytes([header[16], header[17], header[18], header[19]]);
+    let height = u32::from_be_bytes([header[20], header[21], header[22], header[23]]);
+    Some((width, height))
+}
+
+/// Start-of-frame markers (SOF0-SOF15 except DHT, JPG and DAC)
+fn is_start_of_frame(marker: u8) -> bool {
+    (0xC0..=0xCF).contains(&marker) && !matches!(marker, 0xC4 | 0xC8 | 0xCC)
+}
+
+/// Size from a JPEG's frame header, walking the segments before it
+fn jpeg_dimensions(header: &[u8]) -> Option<(u32, u32)> {
+    if !header.starts_with(&[0xFF, 0xD8]) {
+        return None;
+    }
+    let mut pos = 2;
+    while pos + 4 <= header.len() {
+        if header[pos] != 0xFF {
+            return None;
+        }
+        let marker = header[pos + 1];
+        // Fill bytes and markers without a length
+        if marker == 0xFF {
+            pos += 1;
+            continue;
+        }
+        if marker == 0x01 || (0xD0..=0xD9).contains(&marker) {
+            pos += 2;
+            continue;
+        }
+        let length = u16::from_be_bytes([header[pos + 2], header[pos + 3]]) as usize;
+        if is_start_of_frame(marker) {
+            // Length, sample precision, then height and width
+            let frame = header.get(pos + 5..pos + 9)?;
+            let height = u16::from_be_bytes([frame[0], frame[1]]);
+            let width = u16::from_be_bytes([frame[2], frame[3]]);
+            return Some((width as u32, height as u32));
+        }
+        if length < 2 {
+            return None;
+        }
+        pos += 2 + length;
+    }
+    None
+}
+
+/// Width and height from the start of a PNG or JPEG file
+///
+/// # Returns
+/// None if the format isn't recognized, the size isn't within `header`,
+/// or either side is zero
+pub fn probe_dimensions(header: &[u8]) -> Option<(u32, u32)> {
+    png_dimensions(header)
+        .or_else(|| jpeg_dimensions(header))
+        .filter(|&(width, height)| width > 0 && height > 0)
+}
+
+/// Width and height of an image held in memory
+pub fn bytes_dimensions(bytes: &[u8]) -> Result<(u32, u32)> {
+    if let Some(dimensions) = probe_dimensions(bytes) {
+        return Ok(dimensions);
+    }
+    image::io::Reader::new(Cursor::new(bytes))
+        .with_guessed_format()?
+        .into_dimensions()
+        .context("Failed to read texture dimensions")
+}
+
+/// Width and height of an image file, reading only its header when possible
+pub fn file_dimensions(path: &Path) -> Result<(u32, u32)> {
+    let mut header = Vec::with_capacity(PROBE_BYTES);
+    File::open(path)
+        .and_then(|file| file.take(PROBE_BYTES as u64).read_to_end(&mut header))
+        .with_context(|| format!("Failed to read {:?}", path))?;
+    if let Some(dimensions) = probe_dimensions(&header) {
+        return Ok(dimensions);
+    }
+    image::image_dimensions(path).map_err(|e| anyhow!("Failed to read {:?}: {}", path, e))
+}
+
+/// Width and height of an image inside a pack directory or ZIP
+///
+/// # Arguments
+/// * `pack_path` - Base path to the resource pack
+/// * `is_zip` - Whether the pack is a ZIP file
+/// * `file` - Path of the image within the pack
+pub fn pack_file_dimensions(pack_path: &str, is_zip: bool, file: &str) -> Result<(u32, u32)> {
+    if !is_zip {
+        return file_dimensions(&safe_join(Path::new(pack_path), file)?);
+    }
+    let header = zip::read_zip_entry_head(pack_path, file, PROBE_BYTES)?;
+    if let Some(dimensions) = probe_dimensions(&header) {
+        return Ok(dimensions);
+    }
+    bytes_dimensions(&animation::read_pack_bytes(pack_path, is_zip, file)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use image::{ImageOutputFormat, RgbaImage};
+    use std::fs;
+
+    fn encode(width: u32, height: u32, format: ImageOutputFormat) -> Vec<u8> {
+        let image = image::DynamicImage::ImageRgba8(RgbaImage::new(width, height)).to_rgb8();
+        let mut bytes = Vec::new();
+        image
+            .write_to(&mut Cursor::new(&mut bytes), format)
+            .unwrap();
+        bytes
+    }
+
+    #[test]
+    fn test_probe_dimensions() {
+        let png = encode(48, 16, ImageOutputFormat::Png);
+        let jpeg = encode(40, 24, ImageOutputFormat::Jpeg(90));
+
+        assert_eq!(
+            probe_dimensions(&png[..PROBE_BYTES.min(png.len())]),
+            Some((48, 16))
+        );
+        assert_eq!(
+            probe_dimensions(&jpeg[..PROBE_BYTES.min(jpeg.len())]),
+            Some((40, 24))
+        );
+        // The IHDR chunk isn't complete
+        assert_eq!(probe_dimensions(&png[..20]), None);
+        assert_eq!(probe_dimensions(b"not an image"), None);
+    }
+
+    #[test]
+    fn test_jpeg_dimensions_after_large_segment() {
+        let jpeg = encode(8, 4, ImageOutputFormat::Jpeg(90));
+        // Put a 2 KB comment segment before the rest of the file
+        let mut padded = vec![0xFF, 0xD8, 0xFF, 0xFE, 0x08, 0x02];
+        padded.extend(std::iter::repeat(b' ').take(0x0800));
+        padded.extend_from_slice(&jpeg[2..]);
+
+        let temp_dir = std::env::temp_dir().join("test_image_probe");
+        fs::remove_dir_all(&temp_dir).ok();
+        fs::create_dir_all(&temp_dir).unwrap();
+        let path = temp_dir.join("padded.jpg");
+        fs::write(&path, &padded).unwrap();
+
+        let header = probe_dimensions(&padded[..PROBE_BYTES]);
+        let from_file = file_dimensions(&path).unwrap();
+
+        // Clean up
+        fs::remove_dir_all(&temp_dir).ok();
+
+        assert_eq!(header, None);
+        assert_eq!(from_file, (8, 4));
+        assert_eq!(bytes_dimensions(&padded).unwrap(), (8, 4));
+    }
+}
@@ -0,0 +1,200 @@
+/// Persistent cache of ZIP pack listings
+///
+/// Indexing a ZIP pack reads its whole central directory and, when hashing,
+/// every entry; for large packs that takes seconds on each scan. The asset
+/// listing (and content hashes, once computed) of each ZIP is saved under
+/// the user cache folder, keyed by the pack's path, size and modification
+/// time, so a rescan only re-reads packs that changed. Folder packs are not
+/// cached: telling whether any file inside changed costs as much as listing
+/// them again.
+use crate::model::{PackMeta, TextureAnimation};
+use crate::util::content_hash::{self, AssetHashes};
+use crate::util::settings;
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::UNIX_EPOCH;
+
+/// Bumped when the entry layout or asset ID rules change
+const CACHE_VERSION: u32 = 3;
+
+/// Size and modification time a cached listing was made from
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PackFingerprint {
+    pub size: u64,
+    pub modified_secs: u64,
+    pub modified_nanos: u32,
+}
+
+/// Cached listing of one ZIP pack
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CachedPackIndex {
+    pub version: u32,
+    pub path: String,
+    pub fingerprint: PackFingerprint,
+    /// Asset ID -> files
+    pub assets: HashMap<String, Vec<String>>,
+    pub os_metadata_files: usize,
+    /// Texture asset ID -> animation from its .png.mcmeta
+    #[serde(default)]
+    pub animations: HashMap<String, TextureAnimation>,
+    /// Content hashes, once a scan has computed them
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub hashes: Option<AssetHashes>,
+}
+
+/// Folder holding cached listings
+pub fn index_cache_dir() -> Option<PathBuf> {
+    dirs::cache_dir().map(|dir| dir.join("weaverbird_index"))
+}
+
+/// Fingerprint of a ZIP pack; None for folders or unreadable files
+pub fn fingerprint(pack: &PackMeta) -> Option<PackFingerprint> {
+    if !pack.is_zip {
+        return None;
+    }
+    let metadata = fs::metadata(&pack.path).ok()?;
+    let modified = metadata.modified().ok()?.duration_since(UNIX_EPOCH).ok()?;
+    Some(PackFingerprint {
+        size: metadata.len(),
+        modified_secs: modified.as_secs(),
+        modified_nanos: modified.subsec_nanos(),
+    })
+}
+
+/// Entry file for a pack path
+fn entry_path(cache_dir: &Path, pack_path: &str) -> PathBuf {
+    cache_dir.join(format!(
+        "{}.json",
+        content_hash::xxh3_hex(pack_path.as_bytes())
+    ))
+}
+
+/// Cached listing of a pack, if it is still current
+pub fn load(cache_dir: &Path, pack: &PackMeta) -> Option<CachedPackIndex> {
+    let current = fingerprint(pack)?;
+    let contents = fs::read(entry_path(cache_dir, &pack.path)).ok()?;
+    let entry: CachedPackIndex = serde_json::from_slice(&contents).ok()?;
+    if entry.version == CACHE_VERSION && entry.path == pack.path && entry.fingerprint == current {
+        Some(entry)
+    } else {
+        None
+    }
+}
+
+/// Save the listing of a ZIP pack; folder packs are skipped
+///
+/// # Arguments
+/// * `cache_dir` - Folder holding cached listings
+/// * `pack` - Pack the listing was made from
+/// * `assets` - Asset ID -> files
+/// * `os_metadata_files` - macOS metadata files left out of `assets`
+/// * `animations` - Texture asset ID -> animation
+/// * `hashes` - Content hashes, if computed
+pub fn store(
+    cache_dir: &Path,
+    pack: &PackMeta,
+    assets: &HashMap<String, Vec<String>>,
+    os_metadata_files: usize,
+    animations: &HashMap<String, TextureAnimation>,
+    hashes: Option<&AssetHashes>,
+) -> Result<()> {
+    let fingerprint = match fingerprint(pack) {
+        Some(fingerprint) => fingerprint,
+        None => return Ok(()),
+    };
+    let entry = CachedPackIndex {
+        version: CACHE_VERSION,
+        path: pack.path.clone(),
+        fingerprint,
+        assets: assets.clone(),
+        os_metadata_files,
+        animations: animations.clone(),
+        hashes: hashes.cloned(),
+    };
+    fs::create_dir_all(cache_dir).with_context(|| format!("Failed to create {:?}", cache_dir))?;
+    settings::write_file_atomic(
+        &entry_path(cache_dir, &pack.path),
+        &serde_json::to_vec(&entry)?,
+    )
+}
+
+/// Drop cached listings so the packs are read again
+///
+/// # Returns
+/// Number of entries removed
+pub fn invalidate(cache_dir: &Path, packs: &[PackMeta]) -> usize {
+    packs
+        .iter()
+        .filter(|pack| fs::remove_file(entry_path(cache_dir, &pack.path)).is_ok())
+        .count()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+
+    fn write_zip(path: &Path, files: &[&str]) {
+        let mut zip = ::zip::ZipWriter::new(fs::File::create(path).unwrap());
+        for file in files {
+            zip.start_file(*file, ::zip::write::FileOptions::default())
+                .unwrap();
+            zip.write_all(b"data").unwrap();
+        }
+        zip.finish().unwrap();
+    }
+
+    #[test]
+    fn test_store_load_and_invalidate() {
+        let temp_dir = std::env::temp_dir().join("test_index_cache");
+        fs::remove_dir_all(&temp_dir).ok();
+        fs::create_dir_all(&temp_dir).unwrap();
+        let cache_dir = temp_dir.join("cache");
+        let zip_path = temp_dir.join("pack.zip");
+        write_zip(&zip_path, &["assets/minecraft/textures/block/stone.png"]);
+        let pack = PackMeta {
+            id: "pack.zip".to_string(),
+            name: "pack".to_string(),
+            path: zip_path.to_string_lossy().to_string(),
+            is_zip: true,
+            ..Default::default()
+        };
+        let assets = HashMap::from([(
+            "minecraft:block/stone".to_string(),
+            vec!["assets/minecraft/textures/block/stone.png".to_string()],
+        )]);
+
+        let missing_before = load(&cache_dir, &pack).is_none();
+        store(&cache_dir, &pack, &assets, 2, &HashMap::new(), None).unwrap();
+        let loaded = load(&cache_dir, &pack);
+
+        // Rewriting the pack with different contents makes the entry stale
+        write_zip(
+            &zip_path,
+            &[
+                "assets/minecraft/textures/block/stone.png",
+                "assets/minecraft/textures/block/dirt.png",
+            ],
+        );
+        let stale = load(&cache_dir, &pack).is_none();
+        store(&cache_dir, &pack, &assets, 0, &HashMap::new(), None).unwrap();
+        let removed = invalidate(&cache_dir, std::slice::from_ref(&pack));
+        let gone = load(&cache_dir, &pack).is_none();
+
+        // Clean up
+        fs::remove_dir_all(&temp_dir).ok();
+
+        assert!(missing_before);
+        let loaded = loaded.expect("fresh entry should load");
+        assert_eq!(loaded.assets, assets);
+        assert_eq!(loaded.os_metadata_files, 2);
+        assert!(stale);
+        assert_eq!(removed, 1);
+        assert!(gone);
+    }
+}
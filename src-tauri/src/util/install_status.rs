@@ -0,0 +1,527 @@
+/// Whether a project's merged pack is installed in each instance
+///
+/// Every finished build leaves a stamp in the config directory recording a
+/// fingerprint of its inputs (pack order, overrides, pack files) and the SHA1
+/// of its output. With it, each detected instance can be checked for a copy of
+/// the output (by folder name, or by hash if it was renamed), whether that copy
+/// is enabled in options.txt, and whether it is out of date: either the copy
+/// differs from the latest build, or the project changed since that build.
+use crate::model::OverrideSelection;
+use crate::util::animation::read_pack_bytes;
+use crate::util::content_hash::xxh3_hex;
+use crate::util::diagnostics::{self, InstanceInfo};
+use crate::util::launcher_detection::LauncherInfo;
+use crate::util::projects::{self, Project};
+use crate::util::{post_build, settings, stack_check};
+use anyhow::{anyhow, Context, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::UNIX_EPOCH;
+use walkdir::WalkDir;
+
+/// Folder under the config directory holding build stamps
+const STAMP_DIR: &str = "build_stamps";
+
+/// Prefix options.txt uses for packs in the resourcepacks folder
+const FILE_PACK_PREFIX: &str = "file/";
+
+/// Record of the last finished build of an output folder
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BuildStamp {
+    pub output_dir: String,
+    /// Fingerprint of the build inputs (see `input_fingerprint`)
+    pub input_fingerprint: String,
+    /// SHA1 of the output, as computed by the CopySha1 hook
+    pub output_sha1: String,
+    /// Unix timestamp (seconds) when the build finished
+    pub built_at: u64,
+}
+
+/// How an installed copy of the output was found
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum InstallMatch {
+    /// The project builds straight into this instance
+    OutputFolder,
+    /// A pack with the output's name
+    Name,
+    /// A renamed pack with the output's contents
+    Hash,
+}
+
+/// Install state of the output in one instance
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum InstallState {
+    NotInstalled,
+    UpToDate,
+    Outdated,
+    /// Installed, but the output was never built with a stamp to compare to
+    Unknown,
+}
+
+/// Output status for one instance
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct InstanceInstallStatus {
+    pub instance: InstanceInfo,
+    pub game_dir: String,
+    pub installed_path: Option<String>,
+    pub matched_by: Option<InstallMatch>,
+    /// Listed in the instance's options.txt
+    pub enabled: bool,
+    pub state: InstallState,
+    /// Why the copy is outdated or unknown
+    pub reason: Option<String>,
+}
+
+/// Install status of a project's output across instances
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AppliedPackReport {
+    pub output_dir: String,
+    pub last_build: Option<BuildStamp>,
+    /// The project changed since the last build
+    pub project_changed: bool,
+    pub instances: Vec<InstanceInstallStatus>,
+}
+
+/// Default stamp directory (~/.config/weaverbird/build_stamps)
+pub fn default_stamp_dir() -> Result<PathBuf> {
+    Ok(settings::get_config_dir()?.join(STAMP_DIR))
+}
+
+fn stamp_path(stamp_dir: &Path, output_dir: &str) -> PathBuf {
+    stamp_dir.join(format!("{}.json", xxh3_hex(output_dir.as_bytes())))
+}
+
+/// Read the stamp of an output folder's last build
+pub fn read_build_stamp(stamp_dir: &Path, output_dir: &str) -> Option<BuildStamp> {
+    let contents = fs::read_to_string(stamp_path(stamp_dir, output_dir)).ok()?;
+    serde_json::from_str(&contents).ok()
+}
+
+/// Hash the output of a finished build and save its stamp
+pub fn record_build_stamp(
+    stamp_dir: &Path,
+    output_dir: &str,
+    input_fingerprint: String,
+) -> Result<BuildStamp> {
+    let stamp = BuildStamp {
+        output_dir: output_dir.to_string(),
+        input_fingerprint,
+        output_sha1: post_build::hash_output_sha1(Path::new(output_dir))?,
+        built_at: std::time::SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or(0),
+    };
+
+    fs::create_dir_all(stamp_dir).context("Failed to create stamp directory")?;
+    let json = serde_json::to_string_pretty(&stamp)?;
+    settings::write_file_atomic(&stamp_path(stamp_dir, output_dir), json.as_bytes())?;
+    Ok(stamp)
+}
+
+/// Size and modification time of a pack, summed over files for folders
+fn pack_signature(path: &Path) -> String {
+    let modified_secs = |metadata: &fs::Metadata| {
+        metadata
+            .modified()
+            .ok()
+            .and_then(|t| t.duration_since(UNIX_EPOCH).ok())
+            .map_or(0, |d| d.as_secs())
+    };
+
+    match fs::metadata(path) {
+        Ok(metadata) if metadata.is_file() => {
+            format!("{}:{}", metadata.len(), modified_secs(&metadata))
+        }
+        Ok(_) => {
+            let (mut files, mut bytes, mut newest) = (0u64, 0u64, 0u64);
+            for entry in WalkDir::new(path).into_iter().filter_map(|e| e.ok()) {
+                if let Ok(metadata) = entry.metadata() {
+                    if metadata.is_file() {
+                        files += 1;
+                        bytes += metadata.len();
+                        newest = newest.max(modified_secs(&metadata));
+                    }
+                }
+            }
+            format!("{}:{}:{}", files, bytes, newest)
+        }
+        Err(_) => "missing".to_string(),
+    }
+}
+
+/// Fingerprint of everything that decides a build's output
+///
+/// Changes when the pack order, overrides, namespace owners, alpha bleed or
+/// override dependency option, or merged text assets change, or when any
+/// merged pack's files are modified.
+pub fn input_fingerprint(
+    packs_dir: &str,
+    pack_order: &[String],
+    overrides: &HashMap<String, OverrideSelection>,
+    namespace_owners: &HashMap<String, String>,
+    fix_alpha_bleed: bool,
+    include_override_dependencies: bool,
+    merged_text_assets: &[String],
+) -> String {
+    let overrides: BTreeMap<_, _> = overrides
+        .iter()
+        .map(|(asset, selection)| {
+            (
+                asset.as_str(),
+                (
+                    selection.pack_id.as_str(),
+                    selection.variant_path.as_deref(),
+                ),
+            )
+        })
+        .collect();
+    let namespace_owners: BTreeMap<_, _> = namespace_owners.iter().collect();
+    let packs: Vec<(&str, String)> = pack_order
+        .iter()
+        .map(|id| (id.as_str(), pack_signature(&Path::new(packs_dir).join(id))))
+        .collect();
+
+    let inputs = serde_json::json!({
+        "packs": packs,
+        "overrides": overrides,
+        "namespaceOwners": namespace_owners,
+        "fixAlphaBleed": fix_alpha_bleed,
+        "includeOverrideDependencies": include_override_dependencies,
+        "mergedTextAssets": merged_text_assets,
+    });
+    xxh3_hex(inputs.to_string().as_bytes())
+}
+
+/// Fingerprint of a saved project's inputs
+///
+/// Disabled packs and overrides picking them don't go into the build, so
+/// they are left out here too.
+pub fn project_fingerprint(project: &Project) -> String {
+    input_fingerprint(
+        &project.packs_dir,
+        &projects::enabled_pack_order(&project.pack_order, &project.disabled_packs),
+        &projects::active_overrides(&project.overrides, &project.disabled_packs),
+        &project.namespace_owners,
+        project.fix_alpha_bleed,
+        project.include_override_dependencies,
+        &project.merged_text_assets,
+    )
+}
+
+/// Instances of the found launchers
+///
+/// Multi-instance launchers list their instance folders; single-folder
+/// launchers count as one instance.
+pub fn known_instances(launchers: &[LauncherInfo]) -> Vec<InstanceInfo> {
+    let mut instances = Vec::new();
+    for launcher in launchers.iter().filter(|l| l.found) {
+        let listed = diagnostics::list_instances(launcher);
+        if listed.is_empty() {
+            instances.push(InstanceInfo {
+                launcher: launcher.name.clone(),
+                name: launcher.name.clone(),
+                path: launcher.minecraft_dir.clone(),
+            });
+        } else {
+            instances.extend(listed);
+        }
+    }
+    instances
+}
+
+fn same_path(a: &Path, b: &Path) -> bool {
+    match (a.canonicalize(), b.canonicalize()) {
+        (Ok(a), Ok(b)) => a == b,
+        _ => a == b,
+    }
+}
+
+/// Find a copy of the output in a resourcepacks folder
+fn find_installed_copy(
+    resourcepacks: &Path,
+    output_dir: &Path,
+    stamp: Option<&BuildStamp>,
+) -> Option<(PathBuf, InstallMatch)> {
+    let output_name = output_dir.file_name()?;
+    let by_name = resourcepacks.join(output_name);
+    if by_name.exists() {
+        let matched = if same_path(&by_name, output_dir) {
+            InstallMatch::OutputFolder
+        } else {
+            InstallMatch::Name
+        };
+        return Some((by_name, matched));
+    }
+
+    // Renamed copies: only hash packs whose pack.mcmeta matches the output's
+    let stamp = stamp?;
+    let output_mcmeta =
+        read_pack_bytes(&output_dir.to_string_lossy(), false, "pack.mcmeta").ok()?;
+    fs::read_dir(resourcepacks)
+        .ok()?
+        .filter_map(|e| e.ok())
+        .map(|e| e.path())
+        .filter(|path| {
+            let is_zip = path.extension().map_or(false, |ext| ext == "zip");
+            read_pack_bytes(&path.to_string_lossy(), is_zip, "pack.mcmeta")
+                .map_or(false, |mcmeta| mcmeta == output_mcmeta)
+        })
+        .find(|path| {
+            post_build::hash_output_sha1(path).map_or(false, |sha1| sha1 == stamp.output_sha1)
+        })
+        .map(|path| (path, InstallMatch::Hash))
+}
+
+/// Whether options.txt enables a pack from the resourcepacks folder
+fn is_enabled(game_dir: &Path, pack_path: &Path) -> bool {
+    let name = match pack_path.file_name() {
+        Some(name) => format!("{}{}", FILE_PACK_PREFIX, name.to_string_lossy()),
+        None => return false,
+    };
+    fs::read_to_string(game_dir.join("options.txt"))
+        .map(|contents| stack_check::parse_pack_options(&contents).resource_packs)
+        .map_or(false, |packs| packs.contains(&name))
+}
+
+fn instance_status(
+    instance: InstanceInfo,
+    output_dir: &Path,
+    stamp: Option<&BuildStamp>,
+    project_changed: bool,
+) -> InstanceInstallStatus {
+    let game_dir = stack_check::resolve_game_dir(Path::new(&instance.path));
+    let installed = find_installed_copy(&game_dir.join("resourcepacks"), output_dir, stamp);
+
+    let (state, reason) = match (&installed, stamp) {
+        (None, _) => (InstallState::NotInstalled, None),
+        (Some(_), None) => (
+            InstallState::Unknown,
+            Some("No build record for this output; rebuild to track it".to_string()),
+        ),
+        (Some((path, matched)), Some(stamp)) => {
+            let copy_differs = match matched {
+                InstallMatch::Hash => false,
+                // The output folder itself can only be stale via the project
+                InstallMatch::OutputFolder => false,
+                InstallMatch::Name => post_build::hash_output_sha1(path)
+                    .map_or(true, |sha1| sha1 != stamp.output_sha1),
+            };
+            if copy_differs {
+                (
+                    InstallState::Outdated,
+                    Some("Installed copy differs from the latest build".to_string()),
+                )
+            } else if project_changed {
+                (
+                    InstallState::Outdated,
+                    Some("Project changed since the last build".to_string()),
+                )
+            } else {
+                (InstallState::UpToDate, None)
+            }
+        }
+    };
+
+    InstanceInstallStatus {
+        enabled: installed
+            .as_ref()
+            .map_or(false, |(path, _)| is_enabled(&game_dir, path)),
+        game_dir: game_dir.to_string_lossy().to_string(),
+        installed_path: installed
+            .as_ref()
+            .map(|(path, _)| path.to_string_lossy().to_string()),
+        matched_by: installed.map(|(_, matched)| matched),
+        instance,
+        state,
+        reason,
+    }
+}
+
+/// Report where a project's output is installed and whether it is current
+///
+/// # Arguments
+/// * `project` - Project whose output to look for
+/// * `instances` - Instances to check
+/// * `stamp_dir` - Folder holding build stamps
+pub fn applied_pack_status(
+    project: &Project,
+    instances: Vec<InstanceInfo>,
+    stamp_dir: &Path,
+) -> Result<AppliedPackReport> {
+    let output_dir = Path::new(&project.output_dir);
+    if output_dir.file_name().is_none() {
+        return Err(anyhow!(
+            "Output directory has no name: {}",
+            project.output_dir
+        ));
+    }
+
+    let stamp = read_build_stamp(stamp_dir, &project.output_dir);
+    let project_changed = stamp.as_ref().map_or(false, |s| {
+        s.input_fingerprint != project_fingerprint(project)
+    });
+
+    let instances = instances
+        .into_iter()
+        .map(|instance| instance_status(instance, output_dir, stamp.as_ref(), project_changed))
+        .collect();
+
+    Ok(AppliedPackReport {
+        output_dir: project.output_dir.clone(),
+        last_build: stamp,
+        project_changed,
+        instances,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn write(path: &Path, contents: &str) {
+        fs::create_dir_all(path.parent().unwrap()).expect("Failed to create test directory");
+        fs::write(path, contents).expect("Failed to write file");
+    }
+
+    fn project(temp_dir: &Path) -> Project {
+        Project {
+            name: "server".to_string(),
+            packs_dir: temp_dir.join("packs").to_string_lossy().to_string(),
+            pack_order: vec!["A".to_string()],
+            overrides: HashMap::new(),
+            output_dir: temp_dir
+                .join("out/WeaverNest")
+                .to_string_lossy()
+                .to_string(),
+            namespace_owners: HashMap::new(),
+            fix_alpha_bleed: false,
+            include_override_dependencies: true,
+            simulations: Vec::new(),
+            disabled_packs: Vec::new(),
+            merged_text_assets: Vec::new(),
+            resolution: Default::default(),
+            description_template: None,
+        }
+    }
+
+    fn instance(temp_dir: &Path, name: &str) -> InstanceInfo {
+        InstanceInfo {
+            launcher: "Prism".to_string(),
+            name: name.to_string(),
+            path: temp_dir.join(name).to_string_lossy().to_string(),
+        }
+    }
+
+    #[test]
+    fn test_fingerprint_tracks_inputs() {
+        let temp_dir = std::env::temp_dir().join("test_install_status_fingerprint");
+        write(&temp_dir.join("packs/A/pack.mcmeta"), "{}");
+        let mut project = project(&temp_dir);
+
+        let first = project_fingerprint(&project);
+        let unchanged = project_fingerprint(&project);
+        project.fix_alpha_bleed = true;
+        let toggled = project_fingerprint(&project);
+        project.fix_alpha_bleed = false;
+        write(&temp_dir.join("packs/A/extra.png"), "new file");
+        let edited = project_fingerprint(&project);
+
+        // Clean up
+        fs::remove_dir_all(&temp_dir).ok();
+
+        assert_eq!(first, unchanged);
+        assert_ne!(first, toggled);
+        assert_ne!(first, edited);
+    }
+
+    #[test]
+    fn test_applied_pack_status() {
+        let temp_dir = std::env::temp_dir().join("test_install_status_report");
+        fs::remove_dir_all(&temp_dir).ok();
+        write(&temp_dir.join("packs/A/pack.mcmeta"), "{}");
+        write(&temp_dir.join("out/WeaverNest/pack.mcmeta"), "weaver");
+        write(&temp_dir.join("out/WeaverNest/a.png"), "build 1");
+        let project = project(&temp_dir);
+        let stamps = temp_dir.join("stamps");
+        let stamp = record_build_stamp(&stamps, &project.output_dir, project_fingerprint(&project));
+
+        // Up to date and enabled
+        write(
+            &temp_dir.join("current/resourcepacks/WeaverNest/pack.mcmeta"),
+            "weaver",
+        );
+        write(
+            &temp_dir.join("current/resourcepacks/WeaverNest/a.png"),
+            "build 1",
+        );
+        write(
+            &temp_dir.join("current/options.txt"),
+            "resourcePacks:[\"vanilla\",\"file/WeaverNest\"]\n",
+        );
+        // Older copy with the same name
+        write(
+            &temp_dir.join("old/resourcepacks/WeaverNest/pack.mcmeta"),
+            "weaver",
+        );
+        write(
+            &temp_dir.join("old/resourcepacks/WeaverNest/a.png"),
+            "build 0",
+        );
+        // Renamed copy of the latest build
+        write(
+            &temp_dir.join("renamed/resourcepacks/Mine/pack.mcmeta"),
+            "weaver",
+        );
+        write(
+            &temp_dir.join("renamed/resourcepacks/Mine/a.png"),
+            "build 1",
+        );
+        fs::create_dir_all(temp_dir.join("empty/resourcepacks")).unwrap();
+
+        let report = applied_pack_status(
+            &project,
+            ["current", "old", "renamed", "empty"]
+                .iter()
+                .map(|name| instance(&temp_dir, name))
+                .collect(),
+            &stamps,
+        );
+
+        // Editing a merged pack makes every copy outdated
+        write(&temp_dir.join("packs/A/new.png"), "edit");
+        let after_edit =
+            applied_pack_status(&project, vec![instance(&temp_dir, "current")], &stamps);
+
+        // Clean up
+        fs::remove_dir_all(&temp_dir).ok();
+
+        assert!(stamp.is_ok());
+        let report = report.expect("report should succeed");
+        assert!(!report.project_changed);
+        let states: Vec<_> = report.instances.iter().map(|i| i.state).collect();
+        assert_eq!(
+            states,
+            vec![
+                InstallState::UpToDate,
+                InstallState::Outdated,
+                InstallState::UpToDate,
+                InstallState::NotInstalled,
+            ]
+        );
+        assert!(report.instances[0].enabled);
+        assert!(!report.instances[1].enabled);
+        assert_eq!(report.instances[2].matched_by, Some(InstallMatch::Hash));
+
+        let after_edit = after_edit.expect("report should succeed");
+        assert!(after_edit.project_changed);
+        assert_eq!(after_edit.instances[0].state, InstallState::Outdated);
+    }
+}
@@ -0,0 +1,403 @@
+/// Cross-process locks for shared caches and outputs
+///
+/// Two app instances (or the CLI next to the GUI) share the vanilla cache
+/// and can point builds at the same output folder. Writing into either from
+/// two processes at once leaves a mix of both, so writers take a lock file
+/// next to the target first. The lock records who holds it; a lock whose
+/// process is gone, or that is older than any real task, is taken over.
+/// Locks the app can't tell are abandoned (e.g. held from another machine)
+/// can be cleared by the user.
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::fs::{self, OpenOptions};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use tracing::{info, warn};
+
+/// Suffix of lock files, which sit next to what they protect
+const LOCK_SUFFIX: &str = ".weaverbird-lock";
+
+/// Locks older than this are treated as left behind by a crash
+const STALE_AFTER_SECS: u64 = 6 * 60 * 60;
+
+/// Unreadable locks older than this were never finished being written
+const UNREADABLE_GRACE_SECS: u64 = 10;
+
+/// Who holds a lock
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LockOwner {
+    pub pid: u32,
+    /// Machine name, when known (locks can live on network shares)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub host: Option<String>,
+    /// What the holder is doing (e.g. "building into C:/packs/Weaver Nest")
+    pub task: String,
+    /// Seconds since the Unix epoch
+    pub started_at: u64,
+}
+
+/// A lock is held by another task
+#[derive(Debug, Clone)]
+pub struct InstanceBusyError {
+    pub lock_path: PathBuf,
+    pub owner: LockOwner,
+    /// The holder is this process (e.g. a second window)
+    pub same_process: bool,
+}
+
+impl fmt::Display for InstanceBusyError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.same_process {
+            write!(f, "Weaverbird is already {}", self.owner.task)
+        } else {
+            write!(f, "Another Weaverbird instance is {}", self.owner.task)
+        }
+    }
+}
+
+impl std::error::Error for InstanceBusyError {}
+
+/// The busy lock behind an error, if acquiring one failed
+pub fn busy_instance(error: &anyhow::Error) -> Option<&InstanceBusyError> {
+    error
+        .chain()
+        .find_map(|cause| cause.downcast_ref::<InstanceBusyError>())
+}
+
+/// Held lock; the lock file is removed when this is dropped
+#[derive(Debug)]
+pub struct InstanceLock {
+    path: PathBuf,
+    owner: LockOwner,
+}
+
+/// Lock file for a target: "/packs/Weaver Nest" -> "/packs/.Weaver Nest.weaverbird-lock"
+pub fn lock_path_for(target: &Path) -> PathBuf {
+    let name = target
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_default();
+    target.with_file_name(format!(".{}{}", name, LOCK_SUFFIX))
+}
+
+fn now_secs() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+fn host_name() -> Option<String> {
+    std::env::var("COMPUTERNAME")
+        .or_else(|_| std::env::var("HOSTNAME"))
+        .ok()
+        .or_else(|| fs::read_to_string("/etc/hostname").ok())
+        .map(|h| h.trim().to_string())
+        .filter(|h| !h.is_empty())
+}
+
+/// Whether a process on this machine is still running, when that can be told
+#[cfg(target_os = "linux")]
+fn process_alive(pid: u32) -> Option<bool> {
+    Some(Path::new("/proc").join(pid.to_string()).exists())
+}
+
+#[cfg(all(unix, not(target_os = "linux")))]
+fn process_alive(pid: u32) -> Option<bool> {
+    let pid = libc::pid_t::try_from(pid).ok()?;
+    // Signal 0 only checks the process exists and may be signalled
+    if unsafe { libc::kill(pid, 0) } == 0 {
+        return Some(true);
+    }
+    match io::Error::last_os_error().raw_os_error() {
+        Some(libc::ESRCH) => Some(false),
+        // Owned by another user, but running
+        Some(libc::EPERM) => Some(true),
+        _ => None,
+    }
+}
+
+#[cfg(windows)]
+fn process_alive(pid: u32) -> Option<bool> {
+    use windows_sys::Win32::Foundation::{
+        CloseHandle, GetLastError, ERROR_ACCESS_DENIED, ERROR_INVALID_PARAMETER, STILL_ACTIVE,
+    };
+    use windows_sys::Win32::System::Threading::{
+        GetExitCodeProcess, OpenProcess, PROCESS_QUERY_LIMITED_INFORMATION,
+    };
+
+    unsafe {
+        let handle = OpenProcess(PROCESS_QUERY_LIMITED_INFORMATION, 0, pid);
+        if handle.is_null() {
+            return match GetLastError() {
+                ERROR_INVALID_PARAMETER => Some(false),
+                ERROR_ACCESS_DENIED => Some(true),
+                _ => None,
+            };
+        }
+        let mut exit_code = 0u32;
+        let queried = GetExitCodeProcess(handle, &mut exit_code) != 0;
+        CloseHandle(handle);
+        // Exited processes can still be opened while a handle is held
+        if queried {
+            Some(exit_code == STILL_ACTIVE as u32)
+        } else {
+            None
+        }
+    }
+}
+
+#[cfg(not(any(unix, windows)))]
+fn process_alive(_pid: u32) -> Option<bool> {
+    None
+}
+
+/// Whether a lock was left behind rather than held
+fn is_stale(owner: &LockOwner, now: u64) -> bool {
+    if now.saturating_sub(owner.started_at) > STALE_AFTER_SECS {
+        return true;
+    }
+    let same_host = owner.host.is_some() && owner.host == host_name();
+    same_host && owner.pid != std::process::id() && process_alive(owner.pid) == Some(false)
+}
+
+/// Current holder of a lock file, if it can be read
+fn read_owner(lock_path: &Path) -> Option<LockOwner> {
+    let contents = fs::read_to_string(lock_path).ok()?;
+    serde_json::from_str(&contents).ok()
+}
+
+fn file_age_secs(path: &Path) -> u64 {
+    fs::metadata(path)
+        .and_then(|m| m.modified())
+        .ok()
+        .and_then(|t| t.elapsed().ok())
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+/// Whether another live task holds the lock for a target
+pub fn is_locked(target: &Path) -> bool {
+    match read_owner(&lock_path_for(target)) {
+        Some(owner) => !is_stale(&owner, now_secs()),
+        None => false,
+    }
+}
+
+/// Whether a path is named like a lock file
+pub fn is_lock_file(path: &Path) -> bool {
+    path.file_name()
+        .and_then(|n| n.to_str())
+        .map_or(false, |n| n.starts_with('.') && n.ends_with(LOCK_SUFFIX))
+}
+
+/// Remove a lock left behind by a task that is no longer running
+///
+/// Locks that still look held are only removed with `force`, for when the
+/// holder can't be checked (e.g. it crashed on another machine sharing the
+/// folder). Locks held by this process are never removed.
+///
+/// # Arguments
+/// * `lock_path` - The lock file, as reported by [`InstanceBusyError`]
+///
+/// # Returns
+/// Whether there was a lock to remove
+///
+/// # Errors
+/// The path isn't a lock file, or the lock is held ([`InstanceBusyError`])
+pub fn clear_lock(lock_path: &Path, force: bool) -> Result<bool> {
+    if !is_lock_file(lock_path) {
+        return Err(anyhow::anyhow!("{:?} is not a lock file", lock_path));
+    }
+    if !lock_path.exists() {
+        return Ok(false);
+    }
+
+    if let Some(held) = read_owner(lock_path) {
+        let same_process = held.pid == std::process::id() && held.host == host_name();
+        if same_process || (!force && !is_stale(&held, now_secs())) {
+            return Err(InstanceBusyError {
+                lock_path: lock_path.to_path_buf(),
+                owner: held,
+                same_process,
+            }
+            .into());
+        }
+        info!(
+            "Clearing lock {:?} (process {} {})",
+            lock_path, held.pid, held.task
+        );
+    }
+    match fs::remove_file(lock_path) {
+        Ok(()) => Ok(true),
+        Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(false),
+        Err(e) => Err(e).with_context(|| format!("Failed to remove {:?}", lock_path)),
+    }
+}
+
+impl InstanceLock {
+    /// Take the lock for a target, failing with [`InstanceBusyError`] if held
+    ///
+    /// # Arguments
+    /// * `target` - File or folder about to be written
+    /// * `task` - What the caller is doing, shown to whoever finds it busy
+    pub fn acquire(target: &Path, task: &str) -> Result<InstanceLock> {
+        let path = lock_path_for(target);
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).with_context(|| format!("Failed to create {:?}", parent))?;
+        }
+        let owner = LockOwner {
+            pid: std::process::id(),
+            host: host_name(),
+            task: task.to_string(),
+            started_at: now_secs(),
+        };
+
+        // Second attempt only after clearing a stale lock
+        for _ in 0..2 {
+            match OpenOptions::new().write(true).create_new(true).open(&path) {
+                Ok(mut file) => {
+                    let json = serde_json::to_string_pretty(&owner)?;
+                    if let Err(e) = file.write_all(json.as_bytes()) {
+                        fs::remove_file(&path).ok();
+                        return Err(e).with_context(|| format!("Failed to write {:?}", path));
+                    }
+                    return Ok(InstanceLock { path, owner });
+                }
+                Err(e) if e.kind() == io::ErrorKind::AlreadyExists => {}
+                Err(e) => return Err(e).with_context(|| format!("Failed to create {:?}", path)),
+            }
+
+            let stale = match read_owner(&path) {
+                Some(held) if !is_stale(&held, owner.started_at) => {
+                    return Err(InstanceBusyError {
+                        lock_path: path,
+                        same_process: held.pid == owner.pid && held.host == owner.host,
+                        owner: held,
+                    }
+                    .into());
+                }
+                Some(held) => format!("process {} {}", held.pid, held.task),
+                // Another process may be between creating and writing it
+                None if file_age_secs(&path) < UNREADABLE_GRACE_SECS => {
+                    return Err(InstanceBusyError {
+                        lock_path: path,
+                        owner: LockOwner {
+                            pid: 0,
+                            host: None,
+                            task: format!("working on {}", target.display()),
+                            started_at: owner.started_at,
+                        },
+                        same_process: false,
+                    }
+                    .into());
+                }
+                None => "an unreadable lock".to_string(),
+            };
+            warn!("Taking over stale lock {:?} ({})", path, stale);
+            fs::remove_file(&path).ok();
+        }
+
+        Err(anyhow::anyhow!("Could not take the lock {:?}", path))
+    }
+}
+
+impl Drop for InstanceLock {
+    fn drop(&mut self) {
+        // A lock taken over as stale belongs to someone else now
+        if read_owner(&self.path).as_ref() == Some(&self.owner) {
+            fs::remove_file(&self.path).ok();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_lock_path_for() {
+        assert_eq!(
+            lock_path_for(Path::new("/packs/Weaver Nest")),
+            Path::new("/packs/.Weaver Nest.weaverbird-lock")
+        );
+    }
+
+    #[test]
+    fn test_acquire_busy_and_release() {
+        let temp_dir = std::env::temp_dir().join("test_instance_lock");
+        fs::remove_dir_all(&temp_dir).ok();
+        fs::create_dir_all(&temp_dir).unwrap();
+        let target = temp_dir.join("output");
+
+        let lock = InstanceLock::acquire(&target, "building into output").unwrap();
+        let busy = InstanceLock::acquire(&target, "building into output").unwrap_err();
+        let locked_while_held = is_locked(&target);
+        drop(lock);
+        let reacquired = InstanceLock::acquire(&target, "building into output").is_ok();
+
+        // A lock from long ago is taken over
+        let old = LockOwner {
+            pid: std::process::id(),
+            host: None,
+            task: "extracting".to_string(),
+            started_at: 1,
+        };
+        let stale_target = temp_dir.join("stale");
+        fs::write(
+            lock_path_for(&stale_target),
+            serde_json::to_string(&old).unwrap(),
+        )
+        .unwrap();
+        let took_over = InstanceLock::acquire(&stale_target, "extracting").is_ok();
+
+        // Clean up
+        fs::remove_dir_all(&temp_dir).ok();
+
+        let busy = busy_instance(&busy).expect("busy error");
+        assert!(busy.same_process);
+        assert_eq!(
+            busy.to_string(),
+            "Weaverbird is already building into output"
+        );
+        assert!(locked_while_held);
+        assert!(reacquired);
+        assert!(took_over);
+    }
+
+    #[test]
+    fn test_clear_lock() {
+        let temp_dir = std::env::temp_dir().join("test_clear_instance_lock");
+        fs::remove_dir_all(&temp_dir).ok();
+        fs::create_dir_all(&temp_dir).unwrap();
+        let target = temp_dir.join("output");
+        let lock_path = lock_path_for(&target);
+        // Held by a live process with a recent start, so it looks busy
+        let other = LockOwner {
+            pid: std::process::id() + 1,
+            host: None,
+            task: "building into output".to_string(),
+            started_at: now_secs(),
+        };
+        fs::write(&lock_path, serde_json::to_string(&other).unwrap()).unwrap();
+
+        let held = clear_lock(&lock_path, false);
+        let forced = clear_lock(&lock_path, true).unwrap();
+        let already_gone = clear_lock(&lock_path, false).unwrap();
+        let own = InstanceLock::acquire(&target, "building into output").unwrap();
+        let own_forced = clear_lock(&lock_path, true);
+        let not_a_lock = clear_lock(&target, true);
+        drop(own);
+
+        // Clean up
+        fs::remove_dir_all(&temp_dir).ok();
+
+        assert!(busy_instance(&held.unwrap_err()).is_some());
+        assert!(forced);
+        assert!(!already_gone);
+        assert!(busy_instance(&own_forced.unwrap_err()).map_or(false, |busy| busy.same_process));
+        assert!(not_a_lock.is_err());
+    }
+}
@@ -0,0 +1,253 @@
+/// Instances of Prism Launcher and MultiMC
+///
+/// Both launchers keep every instance in its own folder under `instances/`,
+/// with the game folder (`.minecraft`, or `minecraft` in older instances)
+/// inside it, so the launcher folder itself is no place to put a pack.
+/// Instances are listed with the name and icon from instance.cfg and the
+/// Minecraft version and mod loader from mmc-pack.json, letting the user
+/// pick the resourcepacks folder of the right one directly.
+use crate::util::stack_check;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Per-instance settings file
+pub const INSTANCE_CONFIG: &str = "instance.cfg";
+/// Per-instance component list (Minecraft, loaders, libraries)
+pub const MMC_PACK_FILE: &str = "mmc-pack.json";
+
+/// Component uid -> loader name, for mmc-pack.json
+const LOADER_COMPONENTS: &[(&str, &str)] = &[
+    ("net.fabricmc.fabric-loader", "fabric"),
+    ("org.quiltmc.quilt-loader", "quilt"),
+    ("net.minecraftforge", "forge"),
+    ("net.neoforged", "neoforge"),
+];
+
+/// A Prism Launcher, MultiMC or CurseForge instance
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LauncherInstance {
+    /// Name from the instance's metadata, or the folder name
+    pub name: String,
+    pub path: String,
+    pub resourcepacks_dir: String,
+    /// Icon key from instance.cfg (e.g., "grass" or a custom icon's name)
+    pub icon_key: Option<String>,
+    /// Image for a custom icon in the launcher's icons folder; built-in
+    /// icons have none
+    pub icon_path: Option<String>,
+    /// Minecraft version (e.g., "1.20.1")
+    pub game_version: Option<String>,
+    /// "fabric", "forge", "neoforge" or "quilt"; None for vanilla
+    pub loader: Option<String>,
+    pub loader_version: Option<String>,
+}
+
+/// Key -> value pairs of an instance.cfg
+///
+/// Section headers are skipped; values Qt quoted (because of commas and the
+/// like) are unquoted.
+pub fn parse_instance_config(contents: &str) -> HashMap<String, String> {
+    contents
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.starts_with('[') && !line.starts_with('#'))
+        .filter_map(|line| line.split_once('='))
+        .map(|(key, value)| {
+            let value = value.trim();
+            let value = value
+                .strip_prefix('"')
+                .and_then(|v| v.strip_suffix('"'))
+                .unwrap_or(value);
+            (key.trim().to_string(), value.to_string())
+        })
+        .collect()
+}
+
+/// Minecraft version, loader and loader version from mmc-pack.json
+pub fn parse_pack_components(json: &Value) -> (Option<String>, Option<String>, Option<String>) {
+    let components = json
+        .get("components")
+        .and_then(Value::as_array)
+        .map_or(&[][..], |c| c.as_slice());
+    let version_of = |uid: &str| {
+        components
+            .iter()
+            .find(|c| c.get("uid").and_then(Value::as_str) == Some(uid))
+            .map(|c| {
+                c.get("version")
+                    .and_then(Value::as_str)
+                    .unwrap_or_default()
+                    .to_string()
+            })
+    };
+
+    let game_version = version_of("net.minecraft").filter(|v| !v.is_empty());
+    let loader = LOADER_COMPONENTS
+        .iter()
+        .find_map(|(uid, loader)| version_of(uid).map(|version| (*loader, version)));
+    match loader {
+        Some((loader, version)) => (
+            game_version,
+            Some(loader.to_string()),
+            Some(version).filter(|v| !v.is_empty()),
+        ),
+        None => (game_version, None, None),
+    }
+}
+
+/// Image of a custom icon, looked up in the launcher's icons folder
+fn icon_path(instances_dir: &Path, icon_key: &str) -> Option<PathBuf> {
+    let icons_dir = instances_dir.parent()?.join("icons");
+    fs::read_dir(icons_dir)
+        .ok()?
+        .filter_map(|e| e.ok())
+        .map(|e| e.path())
+        .find(|path| path.is_file() && path.file_stem().map_or(false, |stem| stem == icon_key))
+}
+
+/// Read one instance folder
+///
+/// Missing or unreadable files leave only the folder name. Versions in
+/// instance.cfg (`IntendedVersion`) are used for old MultiMC instances
+/// without mmc-pack.json.
+pub fn read_instance(instance_dir: &Path) -> LauncherInstance {
+    let folder_name = instance_dir
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let config = fs::read_to_string(instance_dir.join(INSTANCE_CONFIG))
+        .map(|contents| parse_instance_config(&contents))
+        .unwrap_or_default();
+    let pack: Value = fs::read_to_string(instance_dir.join(MMC_PACK_FILE))
+        .ok()
+        .and_then(|contents| serde_json::from_str(&contents).ok())
+        .unwrap_or(Value::Null);
+    let (game_version, loader, loader_version) = parse_pack_components(&pack);
+    let config_value = |key: &str| config.get(key).filter(|v| !v.is_empty()).cloned();
+
+    let icon_key = config_value("iconKey").filter(|key| key != "default");
+    let icon_path = icon_key.as_deref().and_then(|key| {
+        instance_dir
+            .parent()
+            .and_then(|instances_dir| icon_path(instances_dir, key))
+    });
+
+    LauncherInstance {
+        name: config_value("name").unwrap_or(folder_name),
+        path: instance_dir.to_string_lossy().to_string(),
+        resourcepacks_dir: stack_check::resolve_game_dir(instance_dir)
+            .join("resourcepacks")
+            .to_string_lossy()
+            .to_string(),
+        icon_key,
+        icon_path: icon_path.map(|path| path.to_string_lossy().to_string()),
+        game_version: game_version.or_else(|| config_value("IntendedVersion")),
+        loader,
+        loader_version,
+    }
+}
+
+/// The instances folder of a launcher folder (either may be given)
+pub fn instances_dir(launcher_dir: &Path) -> PathBuf {
+    if launcher_dir.file_name().map_or(false, |n| n == "instances") {
+        launcher_dir.to_path_buf()
+    } else {
+        launcher_dir.join("instances")
+    }
+}
+
+/// List the instances in a Prism Launcher or MultiMC folder, sorted by name
+///
+/// Hidden folders and the launchers' `_LAUNCHER_TEMP`/`_MMC_TEMP` folders
+/// are skipped.
+pub fn scan_instances(launcher_dir: &Path) -> Vec<LauncherInstance> {
+    let entries = match fs::read_dir(instances_dir(launcher_dir)) {
+        Ok(entries) => entries,
+        Err(_) => return Vec::new(),
+    };
+
+    let mut instances: Vec<LauncherInstance> = entries
+        .filter_map(|e| e.ok())
+        .filter(|e| {
+            let name = e.file_name().to_string_lossy().to_string();
+            e.path().is_dir() && !name.starts_with('.') && !name.starts_with('_')
+        })
+        .map(|e| read_instance(&e.path()))
+        .collect();
+    instances.sort_by_key(|instance| instance.name.to_lowercase());
+    instances
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_instance_config() {
+        let config = parse_instance_config(
+            "[General]\nInstanceType=OneSix\nname=\"Survival, Modded\"\niconKey=grass\n",
+        );
+
+        assert_eq!(config["name"], "Survival, Modded");
+        assert_eq!(config["iconKey"], "grass");
+        assert!(!config.contains_key("[General]"));
+    }
+
+    #[test]
+    fn test_list_instances() {
+        let temp_dir = std::env::temp_dir().join("test_launcher_instances");
+        fs::remove_dir_all(&temp_dir).ok();
+        let instances = temp_dir.join("instances");
+        fs::create_dir_all(instances.join("fabric/.minecraft/resourcepacks")).unwrap();
+        fs::create_dir_all(instances.join("Old/minecraft/resourcepacks")).unwrap();
+        fs::create_dir_all(instances.join("_LAUNCHER_TEMP")).unwrap();
+        fs::create_dir_all(temp_dir.join("icons")).unwrap();
+        fs::write(temp_dir.join("icons/creeper_face.png"), "png").unwrap();
+        fs::write(
+            instances.join("fabric").join(INSTANCE_CONFIG),
+            "[General]\nname=Fabulous\niconKey=creeper_face\n",
+        )
+        .unwrap();
+        fs::write(
+            instances.join("fabric").join(MMC_PACK_FILE),
+            r#"{"components": [
+                {"uid": "net.minecraft", "version": "1.20.1"},
+                {"uid": "net.fabricmc.fabric-loader", "version": "0.14.21"}
+            ], "formatVersion": 1}"#,
+        )
+        .unwrap();
+        fs::write(
+            instances.join("Old").join(INSTANCE_CONFIG),
+            "IntendedVersion=1.8.9\niconKey=default\n",
+        )
+        .unwrap();
+
+        let listed = scan_instances(&temp_dir);
+
+        // Clean up
+        fs::remove_dir_all(&temp_dir).ok();
+
+        assert_eq!(listed.len(), 2);
+        let fabulous = &listed[0];
+        assert_eq!(fabulous.name, "Fabulous");
+        assert_eq!(fabulous.game_version.as_deref(), Some("1.20.1"));
+        assert_eq!(fabulous.loader.as_deref(), Some("fabric"));
+        assert_eq!(fabulous.loader_version.as_deref(), Some("0.14.21"));
+        assert_eq!(fabulous.icon_key.as_deref(), Some("creeper_face"));
+        assert!(fabulous
+            .icon_path
+            .as_deref()
+            .map_or(false, |p| p.ends_with("creeper_face.png")));
+        assert!(Path::new(&fabulous.resourcepacks_dir).ends_with(".minecraft/resourcepacks"));
+        let old = &listed[1];
+        assert_eq!(old.name, "Old");
+        assert_eq!(old.game_version.as_deref(), Some("1.8.9"));
+        assert_eq!(old.icon_key, None);
+        assert!(Path::new(&old.resourcepacks_dir).ends_with("minecraft/resourcepacks"));
+        assert!(!old.resourcepacks_dir.contains(".minecraft"));
+    }
+}
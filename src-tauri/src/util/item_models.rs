@@ -0,0 +1,386 @@
+/// Item models (`assets/<namespace>/models/item/*.json`)
+///
+/// Item models go through the same parent chain as block models, ending in
+/// `item/generated` (flat layer textures), `item/handheld` or a block model.
+/// On top of that, an item model may list `overrides`: other models to use
+/// when the item's predicates (custom model data, bow pull, damage, ...)
+/// reach given values. This module resolves an item to the model the game
+/// would show and finds items whose models several enabled packs provide.
+use crate::model::PackMeta;
+use crate::util::block_models::{self, BlockModel};
+use crate::util::{animation, model_texture_deps};
+use crate::{AppError, AppResult};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use tracing::{debug, warn};
+
+/// Model used when an item's predicates reach the given values
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ItemOverride {
+    /// Predicate -> minimum value (e.g. {"custom_model_data": 3})
+    pub predicate: BTreeMap<String, f64>,
+    pub model: String,
+}
+
+/// The parts of an item model JSON the block model parser skips
+#[derive(Debug, Default, Deserialize)]
+struct ItemModelFile {
+    #[serde(default)]
+    textures: HashMap<String, String>,
+    #[serde(default)]
+    overrides: Vec<ItemOverride>,
+}
+
+/// An item resolved to the model the game would show
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ResolvedItemModel {
+    /// e.g. "minecraft:bow"
+    pub item_id: String,
+    /// Model shown: the item's own, or the override the predicates pick
+    pub model_id: String,
+    /// Model with parents merged and generated layers built
+    pub model: BlockModel,
+    /// Layer textures, bottom first (empty unless the model is generated)
+    pub layers: Vec<String>,
+    /// Overrides declared by the item's own model, in file order
+    pub overrides: Vec<ItemOverride>,
+}
+
+/// One pack's model of a contested item
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ItemModelProvider {
+    pub pack_id: String,
+    /// Layer textures the pack's model names directly, bottom first
+    pub layers: Vec<String>,
+    /// Overrides the pack's model declares
+    pub overrides: usize,
+}
+
+/// An item whose model more than one enabled pack provides
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ItemModelConflict {
+    pub item_id: String,
+    /// Highest priority provider, whose model the game uses
+    pub winner: ItemModelProvider,
+    /// Other enabled providers, highest priority first
+    pub losers: Vec<ItemModelProvider>,
+    /// Whether a loser's model names other layer textures than the winner's
+    pub layers_differ: bool,
+    /// Whether a loser declares overrides the winner's model drops
+    pub overrides_lost: bool,
+}
+
+/// "minecraft:bow" or "bow" -> "minecraft:item/bow"
+pub fn item_model_id(item_id: &str) -> String {
+    let (namespace, item) = item_id.split_once(':').unwrap_or(("minecraft", item_id));
+    format!("{}:item/{}", namespace, item)
+}
+
+/// "assets/ns/models/item/bow.json" -> "ns:bow"
+fn item_id_for_path(path: &str) -> Option<String> {
+    let model_id = model_texture_deps::model_id_for_path(path)?;
+    let (namespace, model) = model_id.split_once(':')?;
+    Some(format!("{}:{}", namespace, model.strip_prefix("item/")?))
+}
+
+/// "minecraft:item/bow" -> "assets/minecraft/models/item/bow.json"
+fn model_path(model_id: &str) -> String {
+    let (namespace, model) = model_id.split_once(':').unwrap_or(("minecraft", model_id));
+    format!("assets/{}/models/{}.json", namespace, model)
+}
+
+/// Layer textures of a resolved model, bottom first
+///
+/// Only `layerN` variables count, as `item/generated` reads them.
+pub fn layer_textures(textures: &HashMap<String, String>) -> Vec<String> {
+    let mut layers: Vec<(u32, &String)> = textures
+        .iter()
+        .filter_map(|(key, texture)| Some((key.strip_prefix("layer")?.parse().ok()?, texture)))
+        .collect();
+    layers.sort();
+    layers
+        .into_iter()
+        .map(|(_, texture)| {
+            if texture.contains(':') {
+                texture.clone()
+            } else {
+                format!("minecraft:{}", texture)
+            }
+        })
+        .collect()
+}
+
+/// Pick the override the game would use for the given predicate values
+///
+/// Like the game, the last override whose predicates are all met wins;
+/// predicates without a value count as 0.
+pub fn select_override<'a>(
+    overrides: &'a [ItemOverride],
+    predicates: &HashMap<String, f64>,
+) -> Option<&'a ItemOverride> {
+    overrides.iter().rev().find(|o| {
+        o.predicate.iter().all(|(name, min)| {
+            let name = name.strip_prefix("minecraft:").unwrap_or(name);
+            let value = predicates
+                .get(name)
+                .or_else(|| predicates.get(&format!("minecraft:{}", name)))
+                .copied()
+                .unwrap_or(0.0);
+            value >= *min
+        })
+    })
+}
+
+/// Read an item model's own overrides and textures from a pack, or vanilla
+fn read_item_model_file(
+    pack: &PackMeta,
+    model_id: &str,
+    vanilla_pack: &PackMeta,
+) -> AppResult<ItemModelFile> {
+    let path = model_path(model_id);
+    let bytes = animation::read_pack_bytes(&pack.path, pack.is_zip, &path)
+        .or_else(|_| animation::read_pack_bytes(&vanilla_pack.path, vanilla_pack.is_zip, &path))
+        .map_err(|_| AppError::validation(format!("Item model not found: {}", path)))?;
+    serde_json::from_slice(&bytes)
+        .map_err(|e| AppError::validation(format!("Invalid item model JSON: {}", e)))
+}
+
+/// Resolve an item to the model the game would show
+///
+/// # Arguments
+/// * `pack` - Pack to read models from (vanilla fills in the rest)
+/// * `item_id` - Item ID like "minecraft:bow"
+/// * `vanilla_pack` - Vanilla assets
+/// * `predicates` - Predicate values (e.g. {"custom_model_data": 3}); the
+///   item's own model is used when no override matches
+pub fn resolve_item_model(
+    pack: &PackMeta,
+    item_id: &str,
+    vanilla_pack: &PackMeta,
+    predicates: &HashMap<String, f64>,
+) -> AppResult<ResolvedItemModel> {
+    let own_model_id = item_model_id(item_id);
+    let overrides = read_item_model_file(pack, &own_model_id, vanilla_pack)?.overrides;
+
+    let model_id = match select_override(&overrides, predicates) {
+        Some(selected) => {
+            debug!("{} uses override model {}", item_id, selected.model);
+            selected.model.clone()
+        }
+        None => own_model_id,
+    };
+    let model = block_models::resolve_block_model(pack, &model_id, vanilla_pack)?;
+    let layers = if model.builtin == Some(block_models::BuiltinModel::Generated) {
+        layer_textures(&block_models::resolve_textures(&model))
+    } else {
+        Vec::new()
+    };
+
+    Ok(ResolvedItemModel {
+        item_id: item_id.to_string(),
+        model_id,
+        model,
+        layers,
+        overrides,
+    })
+}
+
+/// Item models of a pack: item ID -> layer textures and override count
+fn pack_item_models(pack: &PackMeta) -> HashMap<String, ItemModelProvider> {
+    let files: Vec<String> = match model_texture_deps::list_model_files(pack) {
+        Ok(files) => files
+            .into_iter()
+            .filter(|f| item_id_for_path(f).is_some())
+            .collect(),
+        Err(e) => {
+            warn!("Could not list {}: {}", pack.id, e);
+            return HashMap::new();
+        }
+    };
+    let contents = match animation::read_pack_files(&pack.path, pack.is_zip, &files) {
+        Ok(contents) => contents,
+        Err(e) => {
+            warn!("Could not read {}: {}", pack.id, e);
+            return HashMap::new();
+        }
+    };
+
+    contents
+        .iter()
+        .filter_map(|(file, json)| {
+            let item_id = item_id_for_path(file)?;
+            let model: ItemModelFile = serde_json::from_slice(json).unwrap_or_default();
+            let provider = ItemModelProvider {
+                pack_id: pack.id.clone(),
+                layers: layer_textures(&model.textures),
+                overrides: model.overrides.len(),
+            };
+            Some((item_id, provider))
+        })
+        .collect()
+}
+
+/// Find items whose model more than one enabled pack provides
+///
+/// # Arguments
+/// * `packs` - Scanned packs
+/// * `pack_order` - Enabled pack IDs in priority order (first = highest)
+///
+/// # Returns
+/// Conflicts sorted by item ID
+pub fn find_item_model_conflicts(
+    packs: &[PackMeta],
+    pack_order: &[String],
+) -> Vec<ItemModelConflict> {
+    let mut providers: BTreeMap<String, Vec<ItemModelProvider>> = BTreeMap::new();
+    for pack_id in pack_order {
+        let pack = match packs.iter().find(|p| &p.id == pack_id) {
+            Some(pack) => pack,
+            None => continue,
+        };
+        for (item_id, provider) in pack_item_models(pack) {
+            providers.entry(item_id).or_default().push(provider);
+        }
+    }
+
+    providers
+        .into_iter()
+        .filter(|(_, providers)| providers.len() > 1)
+        .map(|(item_id, mut losers)| {
+            let winner = losers.remove(0);
+            ItemModelConflict {
+                layers_differ: losers.iter().any(|l| l.layers != winner.layers),
+                overrides_lost: winner.overrides == 0 && losers.iter().any(|l| l.overrides > 0),
+                item_id,
+                winner,
+                losers,
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::util::test_support::folder_pack;
+    use std::fs;
+
+    fn override_to(model: &str, cmd: f64) -> ItemOverride {
+        ItemOverride {
+            predicate: BTreeMap::from([("custom_model_data".to_string(), cmd)]),
+            model: model.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_select_override() {
+        let overrides = vec![
+            override_to("item/ruby_sword", 1.0),
+            override_to("item/flame_sword", 2.0),
+            ItemOverride {
+                predicate: BTreeMap::from([("minecraft:damaged".to_string(), 1.0)]),
+                model: "item/broken_sword".to_string(),
+            },
+        ];
+        let pick = |values: &[(&str, f64)]| {
+            let predicates = values
+                .iter()
+                .map(|(name, value)| (name.to_string(), *value))
+                .collect();
+            select_override(&overrides, &predicates).map(|o| o.model.clone())
+        };
+
+        assert_eq!(pick(&[]), None);
+        assert_eq!(
+            pick(&[("custom_model_data", 1.0)]).unwrap(),
+            "item/ruby_sword"
+        );
+        // The last matching override wins
+        assert_eq!(
+            pick(&[("custom_model_data", 5.0)]).unwrap(),
+            "item/flame_sword"
+        );
+        assert_eq!(
+            pick(&[("custom_model_data", 1.0), ("damaged", 1.0)]).unwrap(),
+            "item/broken_sword"
+        );
+    }
+
+    #[test]
+    fn test_resolve_item_model_and_conflicts() {
+        let temp_dir = std::env::temp_dir().join("test_item_models");
+        fs::remove_dir_all(&temp_dir).ok();
+        let vanilla = folder_pack(
+            &temp_dir,
+            "vanilla",
+            &[(
+                "assets/minecraft/models/item/generated.json",
+                r#"{"parent": "builtin/generated"}"#,
+            )],
+        );
+        let swords = folder_pack(
+            &temp_dir,
+            "swords",
+            &[
+                (
+                    "assets/minecraft/models/item/iron_sword.json",
+                    r#"{"parent": "item/generated",
+                        "textures": {"layer0": "item/iron_sword"},
+                        "overrides": [{"predicate": {"custom_model_data": 1},
+                                       "model": "item/ruby_sword"}]}"#,
+                ),
+                (
+                    "assets/minecraft/models/item/ruby_sword.json",
+                    r#"{"parent": "item/generated",
+                        "textures": {"layer1": "item/ruby_gem", "layer0": "item/ruby_sword"}}"#,
+                ),
+            ],
+        );
+        let plain = folder_pack(
+            &temp_dir,
+            "plain",
+            &[(
+                "assets/minecraft/models/item/iron_sword.json",
+                r#"{"parent": "item/generated", "textures": {"layer0": "item/iron_sword"}}"#,
+            )],
+        );
+
+        let own = resolve_item_model(&swords, "minecraft:iron_sword", &vanilla, &HashMap::new());
+        let ruby = resolve_item_model(
+            &swords,
+            "iron_sword",
+            &vanilla,
+            &HashMap::from([("custom_model_data".to_string(), 1.0)]),
+        );
+        let order = vec!["plain".to_string(), "swords".to_string()];
+        let conflicts = find_item_model_conflicts(&[swords, plain], &order);
+
+        // Clean up
+        fs::remove_dir_all(&temp_dir).ok();
+
+        let own = own.unwrap();
+        assert_eq!(own.model_id, "minecraft:item/iron_sword");
+        assert_eq!(own.layers, vec!["minecraft:item/iron_sword"]);
+        assert_eq!(own.overrides.len(), 1);
+        assert_eq!(own.model.elements.as_ref().map(Vec::len), Some(1));
+
+        let ruby = ruby.unwrap();
+        assert_eq!(ruby.model_id, "item/ruby_sword");
+        assert_eq!(
+            ruby.layers,
+            vec!["minecraft:item/ruby_sword", "minecraft:item/ruby_gem"]
+        );
+
+        assert_eq!(conflicts.len(), 1);
+        let conflict = &conflicts[0];
+        assert_eq!(conflict.item_id, "minecraft:iron_sword");
+        assert_eq!(conflict.winner.pack_id, "plain");
+        assert_eq!(conflict.losers[0].pack_id, "swords");
+        assert!(!conflict.layers_differ);
+        // The plain pack's sword drops the ruby sword override
+        assert!(conflict.overrides_lost);
+    }
+}
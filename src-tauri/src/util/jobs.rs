@@ -0,0 +1,161 @@
+/// Cancellation of long-running commands
+///
+/// Scans, vanilla extraction and builds can run for minutes. The frontend
+/// asks for a job ID first, passes it to the long command, and can call
+/// `cancel_job` with it while the command runs; the command's loops check
+/// their token between files and stop with a `CancelledError`.
+use anyhow::Result;
+use once_cell::sync::Lazy;
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use tracing::info;
+
+/// Tokens of jobs that were created and haven't finished
+static JOBS: Lazy<Mutex<HashMap<String, CancelToken>>> = Lazy::new(|| Mutex::new(HashMap::new()));
+
+/// Source of job IDs within this process
+static NEXT_JOB: AtomicU64 = AtomicU64::new(1);
+
+/// A job stopped because it was cancelled
+#[derive(Debug, Clone)]
+pub struct CancelledError {
+    pub job_id: String,
+}
+
+impl fmt::Display for CancelledError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Job {} was cancelled", self.job_id)
+    }
+}
+
+impl std::error::Error for CancelledError {}
+
+/// Flag a job checks to see whether it should stop
+#[derive(Debug, Clone, Default)]
+pub struct CancelToken {
+    job_id: String,
+    cancelled: Arc<AtomicBool>,
+}
+
+impl CancelToken {
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::Relaxed)
+    }
+
+    /// Fail with a `CancelledError` once the job is cancelled
+    pub fn check(&self) -> Result<()> {
+        if self.is_cancelled() {
+            return Err(CancelledError {
+                job_id: self.job_id.clone(),
+            }
+            .into());
+        }
+        Ok(())
+    }
+}
+
+/// Fail if a token is given and its job was cancelled
+pub fn check_cancelled(token: Option<&CancelToken>) -> Result<()> {
+    token.map_or(Ok(()), CancelToken::check)
+}
+
+/// The cancellation behind an error, if a job stopped because of one
+pub fn cancelled_job(error: &anyhow::Error) -> Option<&CancelledError> {
+    error
+        .chain()
+        .find_map(|cause| cause.downcast_ref::<CancelledError>())
+}
+
+/// Register a new job and return its ID
+pub fn create_job() -> String {
+    let job_id = format!("job-{}", NEXT_JOB.fetch_add(1, Ordering::Relaxed));
+    let token = CancelToken {
+        job_id: job_id.clone(),
+        cancelled: Arc::new(AtomicBool::new(false)),
+    };
+    JOBS.lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .insert(job_id.clone(), token);
+    job_id
+}
+
+/// Ask a job to stop
+///
+/// # Returns
+/// Whether the job exists; a finished job can't be cancelled
+pub fn cancel_job(job_id: &str) -> bool {
+    let jobs = JOBS.lock().unwrap_or_else(|e| e.into_inner());
+    match jobs.get(job_id) {
+        Some(token) => {
+            token.cancelled.store(true, Ordering::Relaxed);
+            info!("Cancelling {}", job_id);
+            true
+        }
+        None => false,
+    }
+}
+
+/// A job a command is running; unregistered when dropped
+pub struct RunningJob {
+    token: CancelToken,
+}
+
+impl RunningJob {
+    pub fn token(&self) -> &CancelToken {
+        &self.token
+    }
+}
+
+impl Drop for RunningJob {
+    fn drop(&mut self) {
+        JOBS.lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .remove(&self.token.job_id);
+    }
+}
+
+/// Start running a job created with `create_job`
+///
+/// An unknown ID (or none) gets a token nobody can cancel, so commands
+/// work the same without one. A job cancelled before it started stops at
+/// its first check.
+pub fn start_job(job_id: Option<&str>) -> RunningJob {
+    let token = job_id
+        .and_then(|id| {
+            JOBS.lock()
+                .unwrap_or_else(|e| e.into_inner())
+                .get(id)
+                .cloned()
+        })
+        .unwrap_or_default();
+    RunningJob { token }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cancel_job() {
+        let job_id = create_job();
+        let job = start_job(Some(&job_id));
+        assert!(job.token().check().is_ok());
+
+        assert!(cancel_job(&job_id));
+        let err = job.token().check().unwrap_err();
+        assert_eq!(cancelled_job(&err).unwrap().job_id, job_id);
+
+        drop(job);
+        assert!(!cancel_job(&job_id));
+    }
+
+    #[test]
+    fn test_start_unknown_job() {
+        let job = start_job(Some("job-unknown"));
+        assert!(!cancel_job("job-unknown"));
+        assert!(check_cancelled(Some(job.token())).is_ok());
+        assert!(check_cancelled(None).is_ok());
+    }
+}
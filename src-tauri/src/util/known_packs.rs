@@ -0,0 +1,336 @@
+/// Known-pack fingerprint database
+///
+/// A bundled list of popular packs with their identifying signatures (icon
+/// hashes, distinctive files, name and description patterns, plus marker
+/// paths confirming the layout) and the mods they need. Scan results use it
+/// to show canonical names and compatibility requirements even when a pack's
+/// file has been renamed or re-described.
+use crate::model::PackMeta;
+use crate::util::content_hash;
+use anyhow::{Context, Result};
+use base64::{engine::general_purpose, Engine as _};
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use tracing::info;
+
+/// Bundled fingerprint data
+const KNOWN_PACKS_JSON: &str = include_str!("known_packs.json");
+
+static KNOWN_PACKS: Lazy<Vec<KnownPack>> =
+    Lazy::new(|| parse_known_packs(KNOWN_PACKS_JSON).expect("bundled known_packs.json is invalid"));
+
+/// Fingerprint of a popular pack
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct KnownPack {
+    /// Stable identifier (e.g., "fresh-animations")
+    pub id: String,
+    /// Canonical display name
+    pub name: String,
+    /// Substrings identifying the pack in its name or description
+    pub patterns: Vec<String>,
+    /// Substrings that rule the pack out (e.g., a sibling resolution)
+    #[serde(default)]
+    pub exclude_patterns: Vec<String>,
+    /// Paths the pack always contains; a trailing '/' marks a directory
+    #[serde(default)]
+    pub markers: Vec<String>,
+    /// XXH3 hashes (16 hex characters) of the pack's released pack.png files
+    #[serde(default)]
+    pub icon_hashes: Vec<String>,
+    /// Paths only this pack ships; all of them present identifies it
+    /// whatever its name or description
+    #[serde(default)]
+    pub signature_files: Vec<String>,
+    /// Mods required for the pack to work as intended
+    #[serde(default)]
+    pub requirements: Vec<String>,
+    /// Mods that enable optional features
+    #[serde(default)]
+    pub recommends: Vec<String>,
+}
+
+/// Where a known pack's pattern was found
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum KnownPackSource {
+    Icon,
+    Structure,
+    Name,
+    Description,
+}
+
+/// A scanned pack identified as a known pack
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct KnownPackMatch {
+    pub known_id: String,
+    pub canonical_name: String,
+    pub matched_by: KnownPackSource,
+    /// True if the pack's files were checked and every marker path was found
+    pub structure_verified: bool,
+    pub requirements: Vec<String>,
+    pub recommends: Vec<String>,
+}
+
+/// Parse a fingerprint list from JSON
+pub fn parse_known_packs(json: &str) -> Result<Vec<KnownPack>> {
+    serde_json::from_str(json).context("Invalid known packs JSON")
+}
+
+/// Get the bundled fingerprint list
+pub fn known_packs() -> &'static [KnownPack] {
+    &KNOWN_PACKS
+}
+
+/// Lowercase text, drop Minecraft formatting codes and unify separators
+fn normalize(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut chars = text.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '§' => {
+                chars.next();
+            }
+            '_' | '-' => out.push(' '),
+            c => out.extend(c.to_lowercase()),
+        }
+    }
+    out.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+fn matches_patterns(known: &KnownPack, text: &str) -> bool {
+    let text = normalize(text);
+    known.patterns.iter().any(|p| text.contains(&normalize(p)))
+        && !known
+            .exclude_patterns
+            .iter()
+            .any(|p| text.contains(&normalize(p)))
+}
+
+/// Whether a file listing contains a path (file, or directory if it ends in '/')
+fn has_path(files: &[String], path: &str) -> bool {
+    files.iter().any(|f| {
+        if path.ends_with('/') {
+            f.starts_with(path)
+        } else {
+            f == path
+        }
+    })
+}
+
+/// XXH3 of a pack's pack.png, if it has one
+fn icon_hash(pack: &PackMeta) -> Option<String> {
+    let icon = general_purpose::STANDARD
+        .decode(pack.icon_data.as_deref()?)
+        .ok()?;
+    Some(content_hash::xxh3_hex(&icon))
+}
+
+/// Identify a pack against a fingerprint list
+///
+/// Content fingerprints win over text: a matching icon, then a full set of
+/// signature files. Name and description patterns come last, and are turned
+/// down when the pack's files are known and lack the entry's markers, so a
+/// look-alike name doesn't pass for the real pack.
+///
+/// # Arguments
+/// * `pack` - Pack to identify
+/// * `files` - The pack's file listing from the scan, if there is one
+/// * `known` - Fingerprints to match against
+pub fn identify_pack(
+    pack: &PackMeta,
+    files: Option<&[String]>,
+    known: &[KnownPack],
+) -> Option<KnownPackMatch> {
+    let markers_found =
+        |k: &KnownPack| files.map_or(false, |files| k.markers.iter().all(|m| has_path(files, m)));
+    let plausible = |k: &KnownPack| files.is_none() || markers_found(k);
+
+    let icon = icon_hash(pack);
+    let by_icon = icon.as_ref().and_then(|icon| {
+        known
+            .iter()
+            .find(|k| k.icon_hashes.iter().any(|h| h.eq_ignore_ascii_case(icon)))
+            .map(|k| (k, KnownPackSource::Icon))
+    });
+    let by_structure = || {
+        let files = files?;
+        known
+            .iter()
+            .find(|k| {
+                !k.signature_files.is_empty()
+                    && k.signature_files.iter().all(|f| has_path(files, f))
+            })
+            .map(|k| (k, KnownPackSource::Structure))
+    };
+    // Prefer a name match over a description match from another entry
+    let by_name = || {
+        known
+            .iter()
+            .find(|k| matches_patterns(k, &pack.name) && plausible(k))
+            .map(|k| (k, KnownPackSource::Name))
+    };
+    let by_description = || {
+        let description = pack.description.as_deref()?;
+        known
+            .iter()
+            .find(|k| matches_patterns(k, description) && plausible(k))
+            .map(|k| (k, KnownPackSource::Description))
+    };
+
+    let (entry, matched_by) = by_icon
+        .or_else(by_structure)
+        .or_else(by_name)
+        .or_else(by_description)?;
+
+    Some(KnownPackMatch {
+        known_id: entry.id.clone(),
+        canonical_name: entry.name.clone(),
+        matched_by,
+        structure_verified: markers_found(entry),
+        requirements: entry.requirements.clone(),
+        recommends: entry.recommends.clone(),
+    })
+}
+
+/// Identify every known pack in a scan
+///
+/// # Arguments
+/// * `packs` - Scanned packs
+/// * `pack_files` - Pack ID -> file listing from the asset index; packs
+///   without one are matched on their icon and text alone
+///
+/// # Returns
+/// Map of pack ID -> match, for recognized packs only
+pub fn identify_known_packs(
+    packs: &[PackMeta],
+    pack_files: &HashMap<String, Vec<String>>,
+) -> HashMap<String, KnownPackMatch> {
+    let matches: HashMap<String, KnownPackMatch> = packs
+        .iter()
+        .filter_map(|pack| {
+            let files = pack_files.get(&pack.id).map(Vec::as_slice);
+            identify_pack(pack, files, known_packs()).map(|m| (pack.id.clone(), m))
+        })
+        .collect();
+
+    info!("Recognized {} of {} packs", matches.len(), packs.len());
+    matches
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pack(name: &str, description: Option<&str>, path: &str) -> PackMeta {
+        PackMeta {
+            id: format!("{}.zip", name),
+            name: name.to_string(),
+            path: path.to_string(),
+            description: description.map(str::to_string),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_bundled_known_packs_parse() {
+        let packs = known_packs();
+        assert!(packs.len() >= 5);
+        assert!(packs.iter().all(|p| !p.patterns.is_empty()));
+        assert!(packs.iter().any(|p| p.id == "fresh-animations"));
+    }
+
+    #[test]
+    fn test_identify_pack_by_name_and_description() {
+        let faithful = identify_pack(
+            &pack("Faithful-32x-1.21", None, "/none"),
+            None,
+            known_packs(),
+        );
+        let faithful64 = identify_pack(
+            &pack("Faithful 64x 1.21", None, "/none"),
+            None,
+            known_packs(),
+        );
+        let renamed = identify_pack(
+            &pack("pack (3)", Some("§6Fresh Animations §7v1.9"), "/none"),
+            None,
+            known_packs(),
+        );
+        let unknown = identify_pack(
+            &pack("My Pack", Some("hello"), "/none"),
+            None,
+            known_packs(),
+        );
+
+        assert_eq!(faithful.unwrap().known_id, "faithful-32x");
+        assert_eq!(faithful64.unwrap().known_id, "faithful-64x");
+        let renamed = renamed.expect("should match by description");
+        assert_eq!(renamed.canonical_name, "Fresh Animations");
+        assert_eq!(renamed.matched_by, KnownPackSource::Description);
+        assert!(!renamed.structure_verified);
+        assert!(!renamed.requirements.is_empty());
+        assert!(unknown.is_none());
+    }
+
+    #[test]
+    fn test_identify_pack_verifies_structure() {
+        let files = vec![
+            "assets/minecraft/optifine/cem/cow.jem".to_string(),
+            "pack.mcmeta".to_string(),
+        ];
+
+        let verified = identify_pack(
+            &pack("Fresh Animations", None, "/none"),
+            Some(&files),
+            known_packs(),
+        );
+        let look_alike = identify_pack(
+            &pack("Stay True", None, "/none"),
+            Some(&files),
+            known_packs(),
+        );
+        let unlisted = identify_pack(&pack("Stay True", None, "/none"), None, known_packs());
+
+        assert!(verified.unwrap().structure_verified);
+        assert!(look_alike.is_none());
+        assert!(!unlisted.unwrap().structure_verified);
+    }
+
+    #[test]
+    fn test_identify_pack_by_content() {
+        let icon = b"not really a png";
+        let known = vec![KnownPack {
+            id: "test-pack".to_string(),
+            name: "Test Pack".to_string(),
+            patterns: vec!["test pack".to_string()],
+            exclude_patterns: Vec::new(),
+            markers: vec!["assets/minecraft/textures/block/".to_string()],
+            icon_hashes: vec![content_hash::xxh3_hex(icon)],
+            signature_files: vec!["assets/testpack/credits.txt".to_string()],
+            requirements: Vec::new(),
+            recommends: Vec::new(),
+        }];
+        let files = vec![
+            "assets/minecraft/textures/block/stone.png".to_string(),
+            "assets/testpack/credits.txt".to_string(),
+        ];
+
+        let mut with_icon = pack("pack (3)", Some("my edit"), "/none");
+        with_icon.icon_data = Some(general_purpose::STANDARD.encode(icon));
+        let by_icon = identify_pack(&with_icon, None, &known).expect("should match by icon");
+        let by_structure = identify_pack(&pack("pack (3)", None, "/none"), Some(&files), &known)
+            .expect("should match by signature files");
+        let no_signature =
+            identify_pack(&pack("pack (3)", None, "/none"), Some(&files[..1]), &known);
+
+        assert_eq!(by_icon.known_id, "test-pack");
+        assert_eq!(by_icon.matched_by, KnownPackSource::Icon);
+        assert!(!by_icon.structure_verified);
+        assert_eq!(by_structure.matched_by, KnownPackSource::Structure);
+        assert!(by_structure.structure_verified);
+        assert!(no_signature.is_none());
+    }
+}
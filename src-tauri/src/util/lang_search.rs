@@ -0,0 +1,344 @@
+/// Search language entries across packs
+///
+/// Packs can rename anything through their lang files
+/// (assets/<namespace>/lang/<locale>.json, or key=value .lang files before
+/// 1.13), and after a merge it's hard to tell where an unexpected item
+/// name came from. The game merges every pack's entries per locale, the
+/// highest-priority pack winning each key; this searches keys and values
+/// in every pack and marks which entry the game would show.
+use crate::model::PackMeta;
+use crate::util::{animation, pack_scanner};
+use anyhow::Result;
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+use std::time::SystemTime;
+use tracing::{info, warn};
+
+/// Most entries a search returns
+pub const MAX_LANG_RESULTS: usize = 500;
+
+/// One language entry of a pack
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LangEntry {
+    /// Translation key (e.g., "item.minecraft.diamond_sword")
+    pub key: String,
+    pub value: String,
+    /// Lowercase locale (e.g., "en_us")
+    pub locale: String,
+    /// Namespace of the lang file
+    pub namespace: String,
+    pub pack_id: String,
+    /// Whether the game shows this value: no higher-priority pack in the
+    /// order has the key for this locale
+    pub active: bool,
+}
+
+/// Matching entries, cut off at the result limit
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LangSearchResult {
+    /// Sorted by key, locale, then pack priority
+    pub entries: Vec<LangEntry>,
+    /// Matches before the limit was applied
+    pub total_matches: usize,
+}
+
+/// Locale -> key -> (namespace, value) of one pack
+type PackLang = HashMap<String, HashMap<String, (String, String)>>;
+
+/// Lang files of a pack with their mtimes, to tell when it changed
+type LangFingerprint = Vec<(String, Option<SystemTime>)>;
+
+/// Parsed lang files with the fingerprint they were read at
+type CachedLang = (LangFingerprint, Arc<PackLang>);
+
+/// Parsed lang files per pack path
+static LANG_INDEX: Lazy<Mutex<HashMap<String, CachedLang>>> =
+    Lazy::new(|| Mutex::new(HashMap::new()));
+
+/// Locale and namespace of a lang file path
+///
+/// "assets/minecraft/lang/en_us.json" -> ("en_us", "minecraft")
+fn lang_file_locale(path: &str) -> Option<(String, String)> {
+    let rest = path.strip_prefix("assets/")?;
+    let (namespace, rest) = rest.split_once('/')?;
+    let file_name = rest.strip_prefix("lang/")?;
+    if file_name.contains('/') {
+        return None;
+    }
+    let locale = file_name
+        .strip_suffix(".json")
+        .or_else(|| file_name.strip_suffix(".lang"))?;
+    Some((locale.to_lowercase(), namespace.to_string()))
+}
+
+/// Entries of a lang file, JSON or legacy key=value
+pub fn parse_lang_file(path: &str, bytes: &[u8]) -> Vec<(String, String)> {
+    let text = String::from_utf8_lossy(bytes);
+    let text = text.trim_start_matches('\u{feff}');
+    if path.ends_with(".json") {
+        let map: serde_json::Map<String, serde_json::Value> = match serde_json::from_str(text) {
+            Ok(map) => map,
+            Err(e) => {
+                warn!("Skipping {}: {}", path, e);
+                return Vec::new();
+            }
+        };
+        return map
+            .into_iter()
+            .filter_map(|(key, value)| Some((key, value.as_str()?.to_string())))
+            .collect();
+    }
+
+    text.lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .filter_map(|line| {
+            let (key, value) = line.split_once('=')?;
+            Some((key.trim().to_string(), value.to_string()))
+        })
+        .collect()
+}
+
+/// Lang files of a pack and their fingerprint
+fn lang_files(pack: &PackMeta) -> Result<(Vec<String>, LangFingerprint)> {
+    let files: Vec<String> = pack_scanner::list_pack_files(pack)?
+        .into_iter()
+        .filter(|file| lang_file_locale(file).is_some())
+        .collect();
+
+    let fingerprint = if pack.is_zip {
+        let modified = std::fs::metadata(&pack.path)
+            .and_then(|m| m.modified())
+            .ok();
+        vec![(pack.path.clone(), modified)]
+    } else {
+        files
+            .iter()
+            .map(|file| {
+                let modified = std::fs::metadata(Path::new(&pack.path).join(file))
+                    .and_then(|m| m.modified())
+                    .ok();
+                (file.clone(), modified)
+            })
+            .collect()
+    };
+    Ok((files, fingerprint))
+}
+
+/// Parsed lang files of a pack, reused while they're unchanged
+fn pack_lang(pack: &PackMeta) -> Result<Arc<PackLang>> {
+    let (files, fingerprint) = lang_files(pack)?;
+    if let Ok(index) = LANG_INDEX.lock() {
+        if let Some((cached, lang)) = index.get(&pack.path) {
+            if *cached == fingerprint {
+                return Ok(lang.clone());
+            }
+        }
+    }
+
+    let mut lang = PackLang::new();
+    for (file, bytes) in animation::read_pack_files(&pack.path, pack.is_zip, &files)? {
+        let (locale, namespace) = match lang_file_locale(&file) {
+            Some(found) => found,
+            None => continue,
+        };
+        let entries = lang.entry(locale).or_default();
+        for (key, value) in parse_lang_file(&file, &bytes) {
+            entries.insert(key, (namespace.clone(), value));
+        }
+    }
+    let lang = Arc::new(lang);
+
+    if let Ok(mut index) = LANG_INDEX.lock() {
+        index.insert(pack.path.clone(), (fingerprint, lang.clone()));
+    }
+    Ok(lang)
+}
+
+/// Find lang entries whose key or value contains a query
+///
+/// # Arguments
+/// * `packs` - Packs to search
+/// * `pack_order` - Pack IDs in priority order (first = highest); packs
+///   missing from it are searched but never active
+/// * `query` - Case-insensitive text to find in keys and values
+/// * `locale` - Only search this locale (e.g., "en_us"), or all if None
+pub fn search_lang_entries(
+    packs: &[PackMeta],
+    pack_order: &[String],
+    query: &str,
+    locale: Option<&str>,
+) -> LangSearchResult {
+    let query = query.to_lowercase();
+    let locale = locale.map(str::to_lowercase);
+
+    let mut ordered: Vec<&PackMeta> = packs.iter().collect();
+    ordered.sort_by_key(|pack| {
+        pack_order
+            .iter()
+            .position(|id| *id == pack.id)
+            .unwrap_or(usize::MAX)
+    });
+    let langs: Vec<(&PackMeta, Arc<PackLang>)> = ordered
+        .into_iter()
+        .filter_map(|pack| match pack_lang(pack) {
+            Ok(lang) => Some((pack, lang)),
+            Err(e) => {
+                warn!("Skipping {}: {}", pack.name, e);
+                None
+            }
+        })
+        .collect();
+
+    let mut entries = Vec::new();
+    for (position, (pack, lang)) in langs.iter().enumerate() {
+        let enabled = pack_order.contains(&pack.id);
+        for (pack_locale, keys) in lang.iter() {
+            if locale.as_ref().map_or(false, |l| l != pack_locale) {
+                continue;
+            }
+            for (key, (namespace, value)) in keys {
+                if !key.to_lowercase().contains(&query) && !value.to_lowercase().contains(&query) {
+                    continue;
+                }
+                let shadowed = langs[..position].iter().any(|(_, higher)| {
+                    higher
+                        .get(pack_locale)
+                        .map_or(false, |keys| keys.contains_key(key))
+                });
+                entries.push((
+                    position,
+                    LangEntry {
+                        key: key.clone(),
+                        value: value.clone(),
+                        locale: pack_locale.clone(),
+                        namespace: namespace.clone(),
+                        pack_id: pack.id.clone(),
+                        active: enabled && !shadowed,
+                    },
+                ));
+            }
+        }
+    }
+
+    entries.sort_by(|(a_pos, a), (b_pos, b)| {
+        (&a.key, &a.locale, a_pos).cmp(&(&b.key, &b.locale, b_pos))
+    });
+    let total_matches = entries.len();
+    let entries: Vec<LangEntry> = entries
+        .into_iter()
+        .take(MAX_LANG_RESULTS)
+        .map(|(_, entry)| entry)
+        .collect();
+
+    info!(
+        "{} match(es) for {:?} in {} pack(s)",
+        total_matches,
+        query,
+        langs.len()
+    );
+
+    LangSearchResult {
+        entries,
+        total_matches,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::util::test_support::folder_pack;
+    use std::fs;
+
+    #[test]
+    fn test_parse_lang_file() {
+        let json = parse_lang_file(
+            "assets/minecraft/lang/en_us.json",
+            br#"{"item.minecraft.diamond_sword": "Sparkly Sword", "bad": 1}"#,
+        );
+        assert_eq!(
+            json,
+            vec![(
+                "item.minecraft.diamond_sword".to_string(),
+                "Sparkly Sword".to_string()
+            )]
+        );
+
+        let legacy = parse_lang_file(
+            "assets/minecraft/lang/en_US.lang",
+            b"# comment\nitem.swordDiamond.name=Sparkly Sword\n",
+        );
+        assert_eq!(
+            legacy,
+            vec![(
+                "item.swordDiamond.name".to_string(),
+                "Sparkly Sword".to_string()
+            )]
+        );
+        assert_eq!(
+            lang_file_locale("assets/minecraft/lang/en_US.lang"),
+            Some(("en_us".to_string(), "minecraft".to_string()))
+        );
+        assert_eq!(lang_file_locale("assets/minecraft/lang/extra/x.json"), None);
+    }
+
+    #[test]
+    fn test_search_lang_entries() {
+        let temp_dir = std::env::temp_dir().join("test_lang_search");
+        fs::remove_dir_all(&temp_dir).ok();
+        let sword = "item.minecraft.diamond_sword";
+        let packs = vec![
+            folder_pack(
+                &temp_dir,
+                "renamer",
+                &[(
+                    "assets/minecraft/lang/en_us.json",
+                    r#"{"item.minecraft.diamond_sword": "Diamond Sword of Doom"}"#,
+                )],
+            ),
+            folder_pack(
+                &temp_dir,
+                "base",
+                &[
+                    (
+                        "assets/minecraft/lang/en_us.json",
+                        r#"{"item.minecraft.diamond_sword": "Diamond Sword"}"#,
+                    ),
+                    (
+                        "assets/minecraft/lang/de_de.json",
+                        r#"{"item.minecraft.diamond_sword": "Diamantschwert"}"#,
+                    ),
+                ],
+            ),
+        ];
+        let order = vec!["renamer".to_string(), "base".to_string()];
+
+        let by_value = search_lang_entries(&packs, &order, "diamond sword", None);
+        let german = search_lang_entries(&packs, &order, sword, Some("de_DE"));
+
+        // Clean up
+        fs::remove_dir_all(&temp_dir).ok();
+
+        let found: Vec<(&str, &str, bool)> = by_value
+            .entries
+            .iter()
+            .map(|e| (e.pack_id.as_str(), e.value.as_str(), e.active))
+            .collect();
+        assert_eq!(
+            found,
+            vec![
+                ("renamer", "Diamond Sword of Doom", true),
+                ("base", "Diamond Sword", false),
+            ]
+        );
+        assert_eq!(by_value.total_matches, 2);
+        assert_eq!(german.entries.len(), 1);
+        assert!(german.entries[0].active);
+        assert_eq!(german.entries[0].value, "Diamantschwert");
+    }
+}
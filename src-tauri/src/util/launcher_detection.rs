@@ -1,8 +1,11 @@
 /// Utilities for detecting Minecraft launchers and their installation directories
+use crate::util::{curseforge_instances, instances, stack_check};
 use anyhow::Result;
 use serde::{Deserialize, Serialize};
 use std::fs;
 use std::path::{Path, PathBuf};
+#[cfg(target_os = "macos")]
+use tracing::{debug, warn};
 
 /// Supported Minecraft launcher types
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
@@ -158,7 +161,7 @@ fn convert_icns_to_png(icns_path: &str) -> Option<String> {
     // Create cache directory for converted icons
     let cache_dir = dirs::cache_dir()?.join("weaverbird").join("launcher_icons");
     if let Err(e) = fs::create_dir_all(&cache_dir) {
-        println!("[convert_icns_to_png] Failed to create cache dir: {}", e);
+        warn!("Failed to create cache dir: {}", e);
         return None;
     }
 
@@ -176,7 +179,7 @@ fn convert_icns_to_png(icns_path: &str) -> Option<String> {
                 (cache_meta.modified(), source_meta.modified())
             {
                 if cache_time >= source_time {
-                    println!("[convert_icns_to_png] Using cached PNG: {:?}", cache_file);
+                    debug!("Using cached PNG: {:?}", cache_file);
                     return Some(cache_file.to_string_lossy().to_string());
                 }
             }
@@ -184,7 +187,7 @@ fn convert_icns_to_png(icns_path: &str) -> Option<String> {
     }
 
     // Read the .icns file
-    println!("[convert_icns_to_png] Converting {} to PNG", icns_path);
+    debug!("Converting {} to PNG", icns_path);
     let file = fs::File::open(icns_path).ok()?;
     let icon_family = icns::IconFamily::read(BufReader::new(file)).ok()?;
 
@@ -203,14 +206,14 @@ fn convert_icns_to_png(icns_path: &str) -> Option<String> {
             // Write the PNG to cache
             if let Ok(file) = fs::File::create(&cache_file) {
                 if image.write_png(file).is_ok() {
-                    println!("[convert_icns_to_png] Created PNG: {:?}", cache_file);
+                    debug!("Created PNG: {:?}", cache_file);
                     return Some(cache_file.to_string_lossy().to_string());
                 }
             }
         }
     }
 
-    println!("[convert_icns_to_png] Failed to extract any image from ICNS");
+    warn!("Failed to extract any image from ICNS");
     None
 }
 
@@ -633,17 +636,33 @@ pub fn get_resourcepacks_dir(launcher_dir: &Path, launcher_type: &LauncherType)
             // Official launcher: <minecraft_dir>/resourcepacks
             Ok(launcher_dir.join("resourcepacks"))
         }
+        LauncherType::PrismLauncher | LauncherType::MultiMC
+            if launcher_dir.join(instances::INSTANCE_CONFIG).is_file() =>
+        {
+            // A single instance: its game folder's resourcepacks
+            Ok(stack_check::resolve_game_dir(launcher_dir).join("resourcepacks"))
+        }
         LauncherType::Modrinth
         | LauncherType::PrismLauncher
         | LauncherType::MultiMC
         | LauncherType::ATLauncher
         | LauncherType::GDLauncher => {
             // Multi-instance launchers: just return the base dir
-            // User will need to select the specific instance
+            // User will need to select the specific instance (see
+            // `instances::scan_instances` for Prism Launcher and MultiMC)
             Ok(launcher_dir.to_path_buf())
         }
+        LauncherType::CurseForge
+            if launcher_dir
+                .join(curseforge_instances::MINECRAFT_INSTANCE_FILE)
+                .is_file() =>
+        {
+            // A single instance, which is its own game folder
+            Ok(launcher_dir.join("resourcepacks"))
+        }
         LauncherType::CurseForge => {
-            // CurseForge Install directory
+            // CurseForge Install directory (see
+            // `curseforge_instances::scan_curseforge_instances`)
             Ok(launcher_dir.to_path_buf())
         }
         LauncherType::Technic | LauncherType::Custom => {
@@ -0,0 +1,297 @@
+/// Application logging
+///
+/// Modules log through the `tracing` macros. `init_logging` installs a
+/// subscriber that prints to stdout and also appends to weaverbird.log in
+/// the app data directory (e.g. ~/.local/share/weaverbird/logs). Once the
+/// file passes `MAX_LOG_BYTES` it's rotated to weaverbird.1.log, keeping a
+/// few older files, so recent logs can be attached to bug reports without
+/// the folder growing forever. The level comes from `WEAVERBIRD_LOG`
+/// (error, warn, info, debug or trace) and defaults to info.
+use anyhow::{anyhow, Context, Result};
+use once_cell::sync::OnceCell;
+use serde::{Deserialize, Serialize};
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use tracing::level_filters::LevelFilter;
+use tracing::{info, warn};
+use tracing_subscriber::prelude::*;
+
+/// Name of the current log file
+pub const LOG_FILE_NAME: &str = "weaverbird.log";
+
+/// Environment variable holding the log level
+pub const LOG_LEVEL_ENV: &str = "WEAVERBIRD_LOG";
+
+/// Lines returned when no limit is given
+pub const DEFAULT_RECENT_LINES: usize = 500;
+
+/// Size at which the log file is rotated
+const MAX_LOG_BYTES: u64 = 5 * 1024 * 1024;
+
+/// Rotated files kept besides the current one
+const KEPT_LOG_FILES: usize = 3;
+
+/// Folder of the log files, once logging is initialized
+static LOG_DIR: OnceCell<PathBuf> = OnceCell::new();
+
+/// The end of the log, for bug reports
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RecentLogs {
+    /// Folder holding the log files
+    pub log_dir: String,
+    /// Last lines, oldest first
+    pub lines: Vec<String>,
+}
+
+/// Default log directory (~/.local/share/weaverbird/logs)
+pub fn default_log_dir() -> Result<PathBuf> {
+    Ok(dirs::data_dir()
+        .ok_or_else(|| anyhow!("Could not find data directory"))?
+        .join("weaverbird")
+        .join("logs"))
+}
+
+/// Folder logs are written to, if logging to a file is set up
+pub fn active_log_dir() -> Option<&'static Path> {
+    LOG_DIR.get().map(PathBuf::as_path)
+}
+
+fn rotated_path(log_dir: &Path, index: usize) -> PathBuf {
+    log_dir.join(format!("weaverbird.{}.log", index))
+}
+
+/// Log file that starts a new file once it grows past a size
+pub struct RotatingLogFile {
+    log_dir: PathBuf,
+    max_bytes: u64,
+    kept_files: usize,
+    file: Option<File>,
+    size: u64,
+}
+
+impl RotatingLogFile {
+    /// Open (or create) the current log file in a folder
+    ///
+    /// # Arguments
+    /// * `max_bytes` - Size at which the file is rotated
+    /// * `kept_files` - Rotated files kept; older ones are deleted
+    pub fn open(log_dir: &Path, max_bytes: u64, kept_files: usize) -> Result<Self> {
+        fs::create_dir_all(log_dir).context("Failed to create log directory")?;
+        let mut log = RotatingLogFile {
+            log_dir: log_dir.to_path_buf(),
+            max_bytes,
+            kept_files,
+            file: None,
+            size: 0,
+        };
+        log.open_current().context("Failed to open log file")?;
+        Ok(log)
+    }
+
+    fn open_current(&mut self) -> io::Result<()> {
+        let file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(self.log_dir.join(LOG_FILE_NAME))?;
+        self.size = file.metadata()?.len();
+        self.file = Some(file);
+        Ok(())
+    }
+
+    /// Shift weaverbird.log to weaverbird.1.log, .1 to .2 and so on
+    ///
+    /// The current file is reopened even if shifting fails, so a log that
+    /// can't be rotated keeps growing instead of going dark.
+    fn rotate(&mut self) -> io::Result<()> {
+        // Windows can't rename a file that is still open
+        self.file = None;
+        fs::remove_file(rotated_path(&self.log_dir, self.kept_files)).ok();
+        for index in (1..self.kept_files).rev() {
+            fs::rename(
+                rotated_path(&self.log_dir, index),
+                rotated_path(&self.log_dir, index + 1),
+            )
+            .ok();
+        }
+        let shifted = if self.kept_files > 0 {
+            fs::rename(
+                self.log_dir.join(LOG_FILE_NAME),
+                rotated_path(&self.log_dir, 1),
+            )
+        } else {
+            fs::remove_file(self.log_dir.join(LOG_FILE_NAME))
+        };
+        let reopened = self.open_current();
+        shifted.and(reopened)
+    }
+}
+
+impl Write for RotatingLogFile {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        // Events are written whole, so rotating between writes never
+        // splits a line
+        if self.size > 0 && self.size + buf.len() as u64 > self.max_bytes {
+            if let Err(e) = self.rotate() {
+                // Only fail if there is no file left to write to
+                if self.file.is_none() {
+                    return Err(e);
+                }
+            }
+        }
+        let file = match self.file.as_mut() {
+            Some(file) => file,
+            None => return Err(io::Error::new(io::ErrorKind::Other, "Log file is closed")),
+        };
+        file.write_all(buf)?;
+        self.size += buf.len() as u64;
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.file.as_mut().map_or(Ok(()), File::flush)
+    }
+}
+
+/// Last lines across the current and rotated log files
+///
+/// # Returns
+/// Up to `limit` lines, oldest first
+pub fn recent_log_lines(log_dir: &Path, limit: usize) -> Result<Vec<String>> {
+    let newest_first = std::iter::once(log_dir.join(LOG_FILE_NAME))
+        .chain((1..=KEPT_LOG_FILES).map(|index| rotated_path(log_dir, index)));
+
+    let mut lines: Vec<String> = Vec::new();
+    for path in newest_first {
+        if lines.len() >= limit {
+            break;
+        }
+        let bytes = match fs::read(&path) {
+            Ok(bytes) => bytes,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => continue,
+            Err(e) => return Err(e).with_context(|| format!("Failed to read {:?}", path)),
+        };
+        // A line cut off mid-character (or a crash) shouldn't hide the rest
+        let file_lines: Vec<String> = String::from_utf8_lossy(&bytes)
+            .lines()
+            .map(str::to_string)
+            .collect();
+        let take = (limit - lines.len()).min(file_lines.len());
+        // Older lines go in front of the ones already collected
+        lines.splice(0..0, file_lines[file_lines.len() - take..].iter().cloned());
+    }
+    Ok(lines)
+}
+
+/// Level from `WEAVERBIRD_LOG`, info if unset or unknown
+fn configured_level() -> LevelFilter {
+    std::env::var(LOG_LEVEL_ENV)
+        .ok()
+        .and_then(|level| level.trim().parse().ok())
+        .unwrap_or(LevelFilter::INFO)
+}
+
+/// Install the global subscriber: stdout plus the rotating log file
+///
+/// Without a log directory, or if it can't be created, logs only go to
+/// stdout. Calling this more than once has no effect.
+pub fn init_logging(log_dir: Option<&Path>) {
+    let log_file = log_dir.map(|dir| RotatingLogFile::open(dir, MAX_LOG_BYTES, KEPT_LOG_FILES));
+    let file_error = match &log_file {
+        Some(Err(e)) => Some(format!("{:#}", e)),
+        _ => None,
+    };
+    let file_layer = log_file.and_then(Result::ok).map(|log_file| {
+        tracing_subscriber::fmt::layer()
+            .with_ansi(false)
+            .with_writer(Mutex::new(log_file))
+    });
+    let logs_to_file = file_layer.is_some();
+
+    let installed = tracing_subscriber::registry()
+        .with(configured_level())
+        .with(tracing_subscriber::fmt::layer())
+        .with(file_layer)
+        .try_init()
+        .is_ok();
+    if !installed {
+        return;
+    }
+
+    match (log_dir, logs_to_file) {
+        (Some(dir), true) => {
+            LOG_DIR.set(dir.to_path_buf()).ok();
+            info!("Logging to {:?}", dir.join(LOG_FILE_NAME));
+        }
+        _ => warn!(
+            "Logging to stdout only: {}",
+            file_error.as_deref().unwrap_or("no data directory")
+        ),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rotating_log_file() {
+        let temp_dir = std::env::temp_dir().join("test_logging_rotate");
+        fs::remove_dir_all(&temp_dir).ok();
+
+        let mut log = RotatingLogFile::open(&temp_dir, 20, 2).unwrap();
+        for index in 0..5 {
+            writeln!(log, "line {:02} padding", index).unwrap();
+        }
+        drop(log);
+        let current = fs::read_to_string(temp_dir.join(LOG_FILE_NAME)).unwrap();
+        let rotated = fs::read_to_string(rotated_path(&temp_dir, 1)).unwrap();
+        let dropped = rotated_path(&temp_dir, 3).exists();
+        let recent = recent_log_lines(&temp_dir, 2).unwrap();
+        let all = recent_log_lines(&temp_dir, 100).unwrap();
+
+        // Clean up
+        fs::remove_dir_all(&temp_dir).ok();
+
+        // Each 16-byte line fills a file, so every write rotates
+        assert_eq!(current, "line 04 padding\n");
+        assert_eq!(rotated, "line 03 padding\n");
+        assert!(!dropped);
+        assert_eq!(recent, vec!["line 03 padding", "line 04 padding"]);
+        assert_eq!(
+            all,
+            vec!["line 02 padding", "line 03 padding", "line 04 padding"]
+        );
+    }
+
+    #[test]
+    fn test_recent_log_lines_without_logs() {
+        let temp_dir = std::env::temp_dir().join("test_logging_empty");
+        fs::remove_dir_all(&temp_dir).ok();
+
+        let lines = recent_log_lines(&temp_dir, 10).unwrap();
+
+        assert!(lines.is_empty());
+    }
+
+    #[test]
+    fn test_recent_log_lines_invalid_utf8() {
+        let temp_dir = std::env::temp_dir().join("test_logging_invalid_utf8");
+        fs::remove_dir_all(&temp_dir).ok();
+        fs::create_dir_all(&temp_dir).unwrap();
+        fs::write(
+            temp_dir.join(LOG_FILE_NAME),
+            b"first\nbad \xff byte\nlast\n",
+        )
+        .unwrap();
+
+        let lines = recent_log_lines(&temp_dir, 10).unwrap();
+
+        // Clean up
+        fs::remove_dir_all(&temp_dir).ok();
+
+        assert_eq!(lines, vec!["first", "bad \u{fffd} byte", "last"]);
+    }
+}
@@ -0,0 +1,245 @@
+/// Texture .mcmeta pass-through checks
+///
+/// A texture's sibling ".png.mcmeta" (animation, blur, clamp) only makes
+/// sense next to the texture it was written for. The indexer files
+/// "lava.png.mcmeta" under its own asset ("minecraft:block/lava.png"), so
+/// left alone a build could take the texture from one pack and the
+/// animation from another, cutting a 16x16 texture into frames that don't
+/// exist. Builds copy the .mcmeta from the pack the texture came from
+/// instead; these checks report where that drops or keeps a mismatched one.
+use crate::model::{AssetRecord, OverrideSelection};
+use crate::util::{asset_indexer, pack_scanner, weaver_nest};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
+
+/// Extension of a texture's metadata file, after the texture's own
+const MCMETA_SUFFIX: &str = ".mcmeta";
+
+/// Kind of .mcmeta problem
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum McmetaIssueKind {
+    /// The texture's pack has no .mcmeta, so another pack's was left out
+    Dropped,
+    /// An override keeps a .mcmeta from another pack than the texture
+    KeptByOverride,
+    /// The output has a .mcmeta without its texture
+    Dangling,
+}
+
+/// A texture .mcmeta that doesn't match its texture
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct McmetaIssue {
+    pub kind: McmetaIssueKind,
+    /// Texture asset ID (e.g., "minecraft:block/lava")
+    pub asset_id: String,
+    /// Pack the texture comes from, if any
+    pub texture_pack: Option<String>,
+    /// Pack whose .mcmeta was dropped or kept
+    pub mcmeta_pack: Option<String>,
+    pub message: String,
+}
+
+/// Whether an indexed asset is a texture's .mcmeta rather than the texture
+pub fn is_mcmeta_asset(asset: &AssetRecord) -> bool {
+    asset
+        .files
+        .first()
+        .map_or(false, |file| file.ends_with(".png.mcmeta"))
+}
+
+/// Asset ID the indexer gives a texture's .mcmeta
+///
+/// "minecraft:block/lava" -> "minecraft:block/lava.png"
+pub fn mcmeta_asset_id(texture_id: &str) -> String {
+    format!("{}.png", texture_id)
+}
+
+/// Path of a texture's .mcmeta inside a pack
+pub fn mcmeta_path(texture_path: &str) -> String {
+    format!("{}{}", texture_path, MCMETA_SUFFIX)
+}
+
+/// Find .mcmeta files a build would drop or keep against their texture
+///
+/// Mirrors the build: a .mcmeta comes from the pack that wins its texture,
+/// unless the .mcmeta asset itself is overridden.
+///
+/// # Arguments
+/// * `assets` - Indexed assets
+/// * `providers` - Asset ID -> pack IDs providing it
+/// * `pack_order` - Pack IDs in priority order
+/// * `overrides` - Asset ID -> chosen pack
+pub fn check_mcmeta_pairs(
+    assets: &[AssetRecord],
+    providers: &HashMap<String, Vec<String>>,
+    pack_order: &[String],
+    overrides: &HashMap<String, OverrideSelection>,
+) -> Vec<McmetaIssue> {
+    let mut issues = Vec::new();
+
+    for asset in assets.iter().filter(|asset| is_mcmeta_asset(asset)) {
+        let texture_id = match asset.id.strip_suffix(".png") {
+            Some(texture_id) => texture_id,
+            None => continue,
+        };
+        let texture_pack = weaver_nest::winning_pack(texture_id, providers, pack_order, overrides);
+
+        if let Some(selection) = overrides.get(&asset.id) {
+            if texture_pack != Some(selection.pack_id.as_str()) {
+                issues.push(McmetaIssue {
+                    kind: McmetaIssueKind::KeptByOverride,
+                    asset_id: texture_id.to_string(),
+                    texture_pack: texture_pack.map(str::to_string),
+                    mcmeta_pack: Some(selection.pack_id.clone()),
+                    message: format!(
+                        "Kept {}'s .mcmeta for {} by override, but the texture comes from {}",
+                        selection.pack_id,
+                        texture_id,
+                        texture_pack.unwrap_or("no pack")
+                    ),
+                });
+            }
+            continue;
+        }
+
+        let texture_pack = match texture_pack {
+            Some(pack_id) => pack_id,
+            None => continue,
+        };
+        let mcmeta_providers = providers.get(&asset.id).map(Vec::as_slice).unwrap_or(&[]);
+        if mcmeta_providers.iter().any(|id| id == texture_pack) {
+            continue;
+        }
+        let mcmeta_pack = weaver_nest::winning_pack(&asset.id, providers, pack_order, overrides);
+        issues.push(McmetaIssue {
+            kind: McmetaIssueKind::Dropped,
+            asset_id: texture_id.to_string(),
+            texture_pack: Some(texture_pack.to_string()),
+            mcmeta_pack: mcmeta_pack.map(str::to_string),
+            message: format!(
+                "Left out {}'s .mcmeta for {}: the texture comes from {}, which has none",
+                mcmeta_pack.unwrap_or("another pack"),
+                texture_id,
+                texture_pack
+            ),
+        });
+    }
+
+    issues.sort_by(|a, b| a.asset_id.cmp(&b.asset_id));
+    issues
+}
+
+/// Find texture .mcmeta files without their texture in a built output pack
+pub fn check_output_mcmeta(output_dir: &Path) -> Vec<McmetaIssue> {
+    let files = pack_scanner::list_folder_files(output_dir);
+    let present: HashSet<&str> = files.iter().map(String::as_str).collect();
+
+    files
+        .iter()
+        .filter(|file| file.ends_with(".png.mcmeta"))
+        .filter_map(|file| {
+            let texture = file.strip_suffix(MCMETA_SUFFIX)?;
+            if present.contains(texture) {
+                return None;
+            }
+            Some(McmetaIssue {
+                kind: McmetaIssueKind::Dangling,
+                asset_id: asset_indexer::extract_asset_id(texture)
+                    .unwrap_or_else(|| texture.to_string()),
+                texture_pack: None,
+                mcmeta_pack: None,
+                message: format!("{} has no texture next to it", file),
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    fn asset(id: &str, file_name: &str) -> AssetRecord {
+        AssetRecord {
+            id: id.to_string(),
+            labels: Vec::new(),
+            files: vec![format!("assets/minecraft/textures/block/{}", file_name)],
+            animation: None,
+        }
+    }
+
+    #[test]
+    fn test_check_mcmeta_pairs() {
+        let assets = vec![
+            asset("minecraft:block/lava", "lava.png"),
+            asset("minecraft:block/lava.png", "lava.png.mcmeta"),
+            asset("minecraft:block/fire", "fire.png"),
+            asset("minecraft:block/fire.png", "fire.png.mcmeta"),
+            asset("minecraft:block/water", "water.png"),
+            asset("minecraft:block/water.png", "water.png.mcmeta"),
+        ];
+        let providers: HashMap<String, Vec<String>> = [
+            ("minecraft:block/lava", vec!["a", "b"]),
+            ("minecraft:block/lava.png", vec!["b"]),
+            ("minecraft:block/fire", vec!["a", "b"]),
+            ("minecraft:block/fire.png", vec!["b"]),
+            ("minecraft:block/water", vec!["a", "b"]),
+            ("minecraft:block/water.png", vec!["a", "b"]),
+        ]
+        .into_iter()
+        .map(|(id, packs)| {
+            (
+                id.to_string(),
+                packs.into_iter().map(|p| p.to_string()).collect(),
+            )
+        })
+        .collect();
+        let order = vec!["a".to_string(), "b".to_string()];
+        let overrides = HashMap::from([(
+            "minecraft:block/fire.png".to_string(),
+            OverrideSelection {
+                pack_id: "b".to_string(),
+                variant_path: None,
+            },
+        )]);
+
+        let issues = check_mcmeta_pairs(&assets, &providers, &order, &overrides);
+
+        let kinds: Vec<(&str, McmetaIssueKind)> = issues
+            .iter()
+            .map(|issue| (issue.asset_id.as_str(), issue.kind))
+            .collect();
+        assert_eq!(
+            kinds,
+            vec![
+                ("minecraft:block/fire", McmetaIssueKind::KeptByOverride),
+                ("minecraft:block/lava", McmetaIssueKind::Dropped),
+            ]
+        );
+        assert_eq!(issues[1].texture_pack.as_deref(), Some("a"));
+        assert_eq!(issues[1].mcmeta_pack.as_deref(), Some("b"));
+    }
+
+    #[test]
+    fn test_check_output_mcmeta() {
+        let temp_dir = std::env::temp_dir().join("test_mcmeta_pairs_output");
+        fs::remove_dir_all(&temp_dir).ok();
+        let textures = temp_dir.join("assets/minecraft/textures/block");
+        fs::create_dir_all(&textures).unwrap();
+        fs::write(textures.join("lava.png"), "png").unwrap();
+        fs::write(textures.join("lava.png.mcmeta"), "{}").unwrap();
+        fs::write(textures.join("fire.png.mcmeta"), "{}").unwrap();
+
+        let issues = check_output_mcmeta(&temp_dir);
+
+        // Clean up
+        fs::remove_dir_all(&temp_dir).ok();
+
+        assert_eq!(issues.len(), 1);
+        assert_eq!(issues[0].kind, McmetaIssueKind::Dangling);
+        assert_eq!(issues[0].asset_id, "minecraft:block/fire");
+    }
+}
@@ -0,0 +1,56 @@
+/// Missing-texture placeholder
+///
+/// The magenta and black checkerboard the game draws when a texture can't
+/// be found. Model previews substitute it for broken texture references so
+/// they still render, and the broken references are reported instead.
+use anyhow::{anyhow, Context, Result};
+use image::{Rgba, RgbaImage};
+use std::path::PathBuf;
+use tracing::info;
+
+/// Texture ID the resolver puts in place of a broken reference
+pub const MISSING_TEXTURE_ID: &str = "weaverbird:missing";
+
+/// Magenta and black checkerboard, like the game's missing texture
+pub fn missing_texture_image() -> RgbaImage {
+    RgbaImage::from_fn(16, 16, |x, y| {
+        if (x < 8) == (y < 8) {
+            Rgba([248, 0, 248, 255])
+        } else {
+            Rgba([0, 0, 0, 255])
+        }
+    })
+}
+
+/// Path of the placeholder PNG, written to the cache on first use
+pub fn missing_texture_path() -> Result<PathBuf> {
+    let cache_dir = dirs::cache_dir()
+        .ok_or_else(|| anyhow!("Could not determine cache directory"))?
+        .join("weaverbird");
+    let path = cache_dir.join("missing_texture.png");
+
+    if !path.exists() {
+        std::fs::create_dir_all(&cache_dir)
+            .with_context(|| format!("Failed to create {:?}", cache_dir))?;
+        missing_texture_image()
+            .save(&path)
+            .with_context(|| format!("Failed to write {:?}", path))?;
+        info!("Wrote placeholder to {:?}", path);
+    }
+
+    Ok(path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_missing_texture_checkerboard() {
+        let image = missing_texture_image();
+        assert_eq!(image.dimensions(), (16, 16));
+        assert_eq!(*image.get_pixel(0, 0), Rgba([248, 0, 248, 255]));
+        assert_eq!(*image.get_pixel(8, 0), Rgba([0, 0, 0, 255]));
+        assert_eq!(*image.get_pixel(15, 15), Rgba([248, 0, 248, 255]));
+    }
+}
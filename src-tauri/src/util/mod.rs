@@ -1,20 +1,178 @@
+pub mod activity_log;
+pub mod alpha_bleed;
+pub mod animation;
+pub mod animation_export;
 pub mod asset_indexer;
+pub mod asset_protocol;
+pub mod asset_stats;
+pub mod bitmap_font;
 pub mod block_models;
 pub mod blockstates;
+pub mod build_journal;
+pub mod cache_health;
+pub mod cloud_sync;
+pub mod color_vision;
+pub mod comparison;
+pub mod conflict_presets;
+pub mod conflicts;
+pub mod contact_sheet;
+pub mod content_hash;
+pub mod curseforge_instances;
+pub mod custom_model_data;
+pub mod diagnostics;
+pub mod doc_previews;
+pub mod entity_map;
+pub mod face_textures;
+pub mod feature_requirements;
+pub mod file_manager;
+pub mod file_retry;
+pub mod gui_atlas;
+pub mod id_catalog;
+pub mod image_probe;
+pub mod index_cache;
+pub mod install_status;
+pub mod instance_lock;
+pub mod instances;
+pub mod item_models;
+pub mod jobs;
+pub mod known_packs;
+pub mod lang_search;
 pub mod launcher_detection;
+pub mod logging;
 pub mod mc_paths;
+pub mod mcmeta_pairs;
+pub mod missing_texture;
+pub mod model_diff;
+pub mod model_rotation;
+pub mod model_texture_deps;
+pub mod modrinth_profiles;
+pub mod namespace_conflicts;
+pub mod namespace_coverage;
+pub mod optifine;
+pub mod override_closure;
+pub mod override_seeding;
+pub mod pack_archive;
+pub mod pack_browser;
+pub mod pack_builder;
+pub mod pack_description;
+pub mod pack_format;
+pub mod pack_health;
+pub mod pack_migrator;
+pub mod pack_order;
+pub mod pack_quarantine;
 pub mod pack_scanner;
+pub mod palette;
+pub mod parallelism;
+pub mod path_policy;
+pub mod post_build;
+pub mod preview_scene;
+pub mod progress;
+pub mod projects;
+pub mod remote_download;
+pub mod report_export;
+pub mod resolution_queue;
+pub mod rpc_server;
+pub mod running_game;
+pub mod server_export;
+pub mod session_cache;
+pub mod settings;
+pub mod stack_check;
+pub mod stack_simulation;
+#[cfg(test)]
+pub(crate) mod test_support;
+pub mod text_assets;
+pub mod texture_history;
 pub mod texture_index;
+pub mod texture_tiles;
+pub mod tinting;
 pub mod vanilla_textures;
 pub mod weaver_nest;
 pub mod zip;
 
+pub use activity_log::*;
+pub use alpha_bleed::*;
+pub use animation::*;
+pub use animation_export::*;
 pub use asset_indexer::*;
+pub use asset_protocol::*;
+pub use asset_stats::*;
+pub use bitmap_font::*;
 pub use block_models::*;
 pub use blockstates::*;
+pub use build_journal::*;
+pub use cache_health::*;
+pub use cloud_sync::*;
+pub use color_vision::*;
+pub use comparison::*;
+pub use conflict_presets::*;
+pub use conflicts::*;
+pub use contact_sheet::*;
+pub use content_hash::*;
+pub use curseforge_instances::*;
+pub use custom_model_data::*;
+pub use diagnostics::*;
+pub use doc_previews::*;
+pub use entity_map::*;
+pub use face_textures::*;
+pub use feature_requirements::*;
+pub use file_manager::*;
+pub use file_retry::*;
+pub use gui_atlas::*;
+pub use id_catalog::*;
+pub use image_probe::*;
+pub use index_cache::*;
+pub use install_status::*;
+pub use instance_lock::*;
+pub use instances::*;
+pub use item_models::*;
+pub use jobs::*;
+pub use known_packs::*;
+pub use lang_search::*;
 pub use launcher_detection::*;
+pub use logging::*;
 pub use mc_paths::*;
+pub use mcmeta_pairs::*;
+pub use missing_texture::*;
+pub use model_diff::*;
+pub use model_rotation::*;
+pub use model_texture_deps::*;
+pub use modrinth_profiles::*;
+pub use namespace_conflicts::*;
+pub use namespace_coverage::*;
+pub use optifine::*;
+pub use override_closure::*;
+pub use override_seeding::*;
+pub use pack_archive::*;
+pub use pack_browser::*;
+pub use pack_builder::*;
+pub use pack_description::*;
+pub use pack_format::*;
+pub use pack_health::*;
+pub use pack_migrator::*;
+pub use pack_order::*;
+pub use pack_quarantine::*;
 pub use pack_scanner::*;
+pub use palette::*;
+pub use parallelism::*;
+pub use path_policy::*;
+pub use post_build::*;
+pub use preview_scene::*;
+pub use progress::*;
+pub use projects::*;
+pub use remote_download::*;
+pub use report_export::*;
+pub use resolution_queue::*;
+pub use rpc_server::*;
+pub use running_game::*;
+pub use server_export::*;
+pub use session_cache::*;
+pub use settings::*;
+pub use stack_check::*;
+pub use stack_simulation::*;
+pub use text_assets::*;
+pub use texture_history::*;
+pub use texture_tiles::*;
+pub use tinting::*;
 pub use vanilla_textures::*;
 pub use weaver_nest::*;
 pub use zip::*;
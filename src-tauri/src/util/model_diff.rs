@@ -0,0 +1,305 @@
+/// Structured diff of two packs' versions of the same model
+///
+/// When two packs both override a model JSON, the conflict is easier to
+/// judge once it is known whether they only retexture the block or change
+/// its shape. Both versions are compared after parent resolution, so a pack
+/// that repeats vanilla's parent chain inline is not reported as different.
+use crate::util::block_models::{resolve_textures, BlockModel, ModelElement};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+use tracing::debug;
+
+/// How far apart two versions of a model are
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ModelDiffKind {
+    /// Same model after resolution
+    Identical,
+    /// Same shape; only textures, face mapping or display transforms differ
+    Cosmetic,
+    /// Elements were added, removed, moved, resized or rotated
+    Geometric,
+}
+
+/// One texture variable that resolves differently
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TextureChange {
+    pub variable: String,
+    pub left: Option<String>,
+    pub right: Option<String>,
+}
+
+/// Differences between two versions of a model
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ModelDiff {
+    pub model_id: String,
+    pub left_pack_id: String,
+    pub right_pack_id: String,
+    pub kind: ModelDiffKind,
+    pub left_elements: usize,
+    pub right_elements: usize,
+    /// Shape differences, e.g. "element 1: to [16, 8, 16] -> [16, 16, 16]"
+    pub geometry_changes: Vec<String>,
+    /// Face differences that keep the shape (texture reference, UVs, tint)
+    pub face_changes: Vec<String>,
+    pub texture_changes: Vec<TextureChange>,
+    /// Display contexts (gui, head, ...) whose transform differs
+    pub display_changes: Vec<String>,
+}
+
+fn diff_element(
+    index: usize,
+    left: &ModelElement,
+    right: &ModelElement,
+    geometry: &mut Vec<String>,
+    faces: &mut Vec<String>,
+) {
+    if left.from != right.from {
+        geometry.push(format!(
+            "element {}: from {:?} -> {:?}",
+            index, left.from, right.from
+        ));
+    }
+    if left.to != right.to {
+        geometry.push(format!(
+            "element {}: to {:?} -> {:?}",
+            index, left.to, right.to
+        ));
+    }
+    if left.rotation != right.rotation {
+        geometry.push(format!("element {}: rotation differs", index));
+    }
+
+    let left_faces: BTreeSet<&String> = left.faces.keys().collect();
+    let right_faces: BTreeSet<&String> = right.faces.keys().collect();
+    if left_faces != right_faces {
+        geometry.push(format!(
+            "element {}: faces {:?} -> {:?}",
+            index, left_faces, right_faces
+        ));
+    }
+
+    for name in left_faces.intersection(&right_faces) {
+        let (a, b) = (&left.faces[*name], &right.faces[*name]);
+        if a.texture != b.texture {
+            faces.push(format!(
+                "element {} {}: texture {} -> {}",
+                index, name, a.texture, b.texture
+            ));
+        }
+        if a.uv != b.uv || a.rotation != b.rotation {
+            faces.push(format!("element {} {}: UV mapping differs", index, name));
+        }
+        if a.tintindex != b.tintindex {
+            faces.push(format!(
+                "element {} {}: tintindex {:?} -> {:?}",
+                index, name, a.tintindex, b.tintindex
+            ));
+        }
+        if a.cullface != b.cullface {
+            faces.push(format!(
+                "element {} {}: cullface {:?} -> {:?}",
+                index, name, a.cullface, b.cullface
+            ));
+        }
+    }
+    if left.shade != right.shade {
+        faces.push(format!(
+            "element {}: shade {:?} -> {:?}",
+            index, left.shade, right.shade
+        ));
+    }
+}
+
+/// Compare two resolved versions of a model
+///
+/// # Arguments
+/// * `model_id` - Model both packs provide
+/// * `left_pack_id` / `left` - First pack and its resolved model
+/// * `right_pack_id` / `right` - Second pack and its resolved model
+pub fn diff_models(
+    model_id: &str,
+    left_pack_id: &str,
+    left: &BlockModel,
+    right_pack_id: &str,
+    right: &BlockModel,
+) -> ModelDiff {
+    let no_elements = Vec::new();
+    let left_elements = left.elements.as_ref().unwrap_or(&no_elements);
+    let right_elements = right.elements.as_ref().unwrap_or(&no_elements);
+
+    let mut geometry_changes = Vec::new();
+    let mut face_changes = Vec::new();
+    if left_elements.len() != right_elements.len() {
+        geometry_changes.push(format!(
+            "element count {} -> {}",
+            left_elements.len(),
+            right_elements.len()
+        ));
+    }
+    if left.builtin != right.builtin {
+        geometry_changes.push(format!("builtin {:?} -> {:?}", left.builtin, right.builtin));
+    }
+    for (index, (a, b)) in left_elements.iter().zip(right_elements).enumerate() {
+        diff_element(index, a, b, &mut geometry_changes, &mut face_changes);
+    }
+    if left.ambientocclusion != right.ambientocclusion {
+        face_changes.push(format!(
+            "ambientocclusion {:?} -> {:?}",
+            left.ambientocclusion, right.ambientocclusion
+        ));
+    }
+
+    let left_textures = resolve_textures(left);
+    let right_textures = resolve_textures(right);
+    let variables: BTreeSet<&String> = left_textures.keys().chain(right_textures.keys()).collect();
+    let texture_changes: Vec<TextureChange> = variables
+        .into_iter()
+        .filter(|v| left_textures.get(*v) != right_textures.get(*v))
+        .map(|v| TextureChange {
+            variable: v.clone(),
+            left: left_textures.get(v).cloned(),
+            right: right_textures.get(v).cloned(),
+        })
+        .collect();
+
+    let mut display_changes = Vec::new();
+    let (left_display, right_display) = (left.display.as_ref(), right.display.as_ref());
+    let contexts: BTreeSet<&String> = left_display
+        .into_iter()
+        .chain(right_display)
+        .flat_map(|display| display.keys())
+        .collect();
+    for context in contexts {
+        let a = left_display.and_then(|d| d.get(context));
+        let b = right_display.and_then(|d| d.get(context));
+        if a != b {
+            display_changes.push(context.clone());
+        }
+    }
+
+    let kind = if !geometry_changes.is_empty() {
+        ModelDiffKind::Geometric
+    } else if !face_changes.is_empty() || !texture_changes.is_empty() || !display_changes.is_empty()
+    {
+        ModelDiffKind::Cosmetic
+    } else {
+        ModelDiffKind::Identical
+    };
+
+    debug!(
+        "{} ({} vs {}): {:?}",
+        model_id, left_pack_id, right_pack_id, kind
+    );
+
+    ModelDiff {
+        model_id: model_id.to_string(),
+        left_pack_id: left_pack_id.to_string(),
+        right_pack_id: right_pack_id.to_string(),
+        kind,
+        left_elements: left_elements.len(),
+        right_elements: right_elements.len(),
+        geometry_changes,
+        face_changes,
+        texture_changes,
+        display_changes,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::util::block_models::ElementFace;
+    use std::collections::HashMap;
+
+    fn slab(top: f32, texture: &str) -> BlockModel {
+        let faces = ["up", "down", "north"]
+            .iter()
+            .map(|f| {
+                (
+                    f.to_string(),
+                    ElementFace {
+                        texture: "#all".to_string(),
+                        uv: None,
+                        rotation: None,
+                        cullface: None,
+                        tintindex: None,
+                    },
+                )
+            })
+            .collect();
+        let textures: HashMap<String, String> = [("all".to_string(), texture.to_string())]
+            .into_iter()
+            .collect();
+
+        BlockModel {
+            parent: None,
+            textures: Some(textures),
+            elements: Some(vec![ModelElement {
+                from: [0.0, 0.0, 0.0],
+                to: [16.0, top, 16.0],
+                rotation: None,
+                faces,
+                shade: None,
+            }]),
+            ambientocclusion: None,
+            missing_textures: Vec::new(),
+            builtin: None,
+            display: None,
+            display_transform: None,
+        }
+    }
+
+    #[test]
+    fn test_identical_models() {
+        let diff = diff_models(
+            "m",
+            "a",
+            &slab(8.0, "block/stone"),
+            "b",
+            &slab(8.0, "block/stone"),
+        );
+        assert_eq!(diff.kind, ModelDiffKind::Identical);
+        assert!(diff.geometry_changes.is_empty());
+        assert!(diff.texture_changes.is_empty());
+    }
+
+    #[test]
+    fn test_texture_only_difference_is_cosmetic() {
+        let diff = diff_models(
+            "m",
+            "a",
+            &slab(8.0, "block/stone"),
+            "b",
+            &slab(8.0, "block/andesite"),
+        );
+
+        assert_eq!(diff.kind, ModelDiffKind::Cosmetic);
+        assert_eq!(
+            diff.texture_changes,
+            vec![TextureChange {
+                variable: "all".to_string(),
+                left: Some("block/stone".to_string()),
+                right: Some("block/andesite".to_string()),
+            }]
+        );
+    }
+
+    #[test]
+    fn test_shape_difference_is_geometric() {
+        let mut right = slab(16.0, "block/andesite");
+        let elements = right.elements.as_mut().unwrap();
+        elements.push(elements[0].clone());
+
+        let diff = diff_models("m", "a", &slab(8.0, "block/stone"), "b", &right);
+
+        assert_eq!(diff.kind, ModelDiffKind::Geometric);
+        assert_eq!((diff.left_elements, diff.right_elements), (1, 2));
+        assert!(diff
+            .geometry_changes
+            .contains(&"element 0: to [16.0, 8.0, 16.0] -> [16.0, 16.0, 16.0]".to_string()));
+        assert_eq!(diff.texture_changes.len(), 1);
+    }
+}
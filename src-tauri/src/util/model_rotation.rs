@@ -0,0 +1,361 @@
+/// Blockstate variant rotation baked into model geometry
+///
+/// Blockstates rotate a model in 90° steps around X, then Y (`"x": 90,
+/// "y": 180`), optionally with `uvlock` so textures stay aligned to the world
+/// instead of turning with the model (stairs, fences, logs on their side).
+/// This rotates element boxes, face directions, cull faces and element
+/// rotations and fixes up face UVs, so a renderer can draw the result as-is.
+use crate::util::block_models::{BlockModel, ElementFace, ModelElement};
+use std::collections::HashMap;
+use tracing::{debug, warn};
+
+type Axis = [i32; 3];
+
+const FACES: [(&str, Axis); 6] = [
+    ("down", [0, -1, 0]),
+    ("up", [0, 1, 0]),
+    ("north", [0, 0, -1]),
+    ("south", [0, 0, 1]),
+    ("west", [-1, 0, 0]),
+    ("east", [1, 0, 0]),
+];
+
+/// 90° steps around X, then Y, as a blockstate variant applies them
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct VariantRotation {
+    x_steps: i32,
+    y_steps: i32,
+}
+
+impl VariantRotation {
+    fn new(rot_x: i32, rot_y: i32) -> Self {
+        VariantRotation {
+            x_steps: rot_x.rem_euclid(360) / 90,
+            y_steps: rot_y.rem_euclid(360) / 90,
+        }
+    }
+
+    fn is_identity(self) -> bool {
+        self.x_steps == 0 && self.y_steps == 0
+    }
+
+    /// Rotate a vector; x=90 turns up into north, y=90 turns north into east
+    fn apply<T: Copy + std::ops::Neg<Output = T>>(self, v: [T; 3]) -> [T; 3] {
+        let mut v = v;
+        for _ in 0..self.x_steps {
+            v = [v[0], v[2], -v[1]];
+        }
+        for _ in 0..self.y_steps {
+            v = [-v[2], v[1], v[0]];
+        }
+        v
+    }
+
+    /// Rotate a point in model space (0-16) around the block center
+    fn apply_point(self, p: [f32; 3]) -> [f32; 3] {
+        let r = self.apply([p[0] - 8.0, p[1] - 8.0, p[2] - 8.0]);
+        [r[0] + 8.0, r[1] + 8.0, r[2] + 8.0]
+    }
+}
+
+fn face_normal(face: &str) -> Option<Axis> {
+    FACES
+        .iter()
+        .find(|(name, _)| *name == face)
+        .map(|(_, n)| *n)
+}
+
+fn face_name(normal: Axis) -> &'static str {
+    FACES
+        .iter()
+        .find(|(_, n)| *n == normal)
+        .map(|(name, _)| *name)
+        .unwrap_or("up")
+}
+
+/// Texture right and up directions of a face, as the game maps default UVs
+fn face_frame(face: &str) -> (Axis, Axis) {
+    match face {
+        "down" => ([1, 0, 0], [0, 0, 1]),
+        "up" => ([1, 0, 0], [0, 0, -1]),
+        "north" => ([-1, 0, 0], [0, 1, 0]),
+        "south" => ([1, 0, 0], [0, 1, 0]),
+        "west" => ([0, 0, 1], [0, 1, 0]),
+        _ => ([0, 0, -1], [0, 1, 0]),
+    }
+}
+
+fn neg(v: Axis) -> Axis {
+    [-v[0], -v[1], -v[2]]
+}
+
+/// UVs the game derives from the element box when a face gives none
+fn default_uv(face: &str, from: [f32; 3], to: [f32; 3]) -> [f32; 4] {
+    let ([x1, y1, z1], [x2, y2, z2]) = (from, to);
+    match face {
+        "down" => [x1, 16.0 - z2, x2, 16.0 - z1],
+        "up" => [x1, z1, x2, z2],
+        "north" => [16.0 - x2, 16.0 - y2, 16.0 - x1, 16.0 - y1],
+        "south" => [x1, 16.0 - y2, x2, 16.0 - y1],
+        "west" => [z1, 16.0 - y2, z2, 16.0 - y1],
+        _ => [16.0 - z2, 16.0 - y2, 16.0 - z1, 16.0 - y1],
+    }
+}
+
+/// Rotate a UV box clockwise around the texture center, keeping mirroring
+fn rotate_uv(uv: [f32; 4], quarter_turns: i32) -> [f32; 4] {
+    let mut flip_u = uv[0] > uv[2];
+    let mut flip_v = uv[1] > uv[3];
+    let mut a = (uv[0], uv[1]);
+    let mut b = (uv[2], uv[3]);
+    for _ in 0..quarter_turns {
+        a = (16.0 - a.1, a.0);
+        b = (16.0 - b.1, b.0);
+        std::mem::swap(&mut flip_u, &mut flip_v);
+    }
+
+    let (u1, u2) = (a.0.min(b.0), a.0.max(b.0));
+    let (v1, v2) = (a.1.min(b.1), a.1.max(b.1));
+    [
+        if flip_u { u2 } else { u1 },
+        if flip_v { v2 } else { v1 },
+        if flip_u { u1 } else { u2 },
+        if flip_v { v1 } else { v2 },
+    ]
+}
+
+/// Quarter turns (clockwise) the texture of `face` gains on the face it moves to
+fn texture_turns(face: &str, rotated_face: &str, rotation: VariantRotation) -> i32 {
+    let (_, up) = face_frame(face);
+    let moved_up = rotation.apply(up);
+    let (right, up) = face_frame(rotated_face);
+    if moved_up == up {
+        0
+    } else if moved_up == right {
+        1
+    } else if moved_up == neg(up) {
+        2
+    } else {
+        3
+    }
+}
+
+fn rotate_face(
+    face_key: &str,
+    face: &ElementFace,
+    from: [f32; 3],
+    to: [f32; 3],
+    rotation: VariantRotation,
+    uvlock: bool,
+) -> (String, ElementFace) {
+    let normal = match face_normal(face_key) {
+        Some(normal) => normal,
+        None => return (face_key.to_string(), face.clone()),
+    };
+    let rotated_key = face_name(rotation.apply(normal));
+    let turns = texture_turns(face_key, rotated_key, rotation);
+    let uv = face.uv.unwrap_or_else(|| default_uv(face_key, from, to));
+    let face_rotation = face.rotation.unwrap_or(0);
+
+    // Without uvlock the texture turns with the face; with it the UV box
+    // turns instead so the texture stays aligned to the world
+    let (uv, face_rotation) = if uvlock {
+        (rotate_uv(uv, turns), face_rotation)
+    } else {
+        (uv, (face_rotation + turns as u32 * 90) % 360)
+    };
+
+    let cullface = face
+        .cullface
+        .as_deref()
+        .map(|cull| match face_normal(cull) {
+            Some(normal) => face_name(rotation.apply(normal)).to_string(),
+            None => cull.to_string(),
+        });
+
+    let rotated = ElementFace {
+        texture: face.texture.clone(),
+        uv: Some(uv),
+        rotation: if face_rotation == 0 && face.rotation.is_none() {
+            None
+        } else {
+            Some(face_rotation)
+        },
+        cullface,
+        tintindex: face.tintindex,
+    };
+    (rotated_key.to_string(), rotated)
+}
+
+fn rotate_element(element: &mut ModelElement, rotation: VariantRotation, uvlock: bool) {
+    let (from, to) = (element.from, element.to);
+
+    let a = rotation.apply_point(from);
+    let b = rotation.apply_point(to);
+    element.from = [a[0].min(b[0]), a[1].min(b[1]), a[2].min(b[2])];
+    element.to = [a[0].max(b[0]), a[1].max(b[1]), a[2].max(b[2])];
+
+    if let Some(element_rotation) = &mut element.rotation {
+        element_rotation.origin = rotation.apply_point(element_rotation.origin);
+        let axis: Axis = match element_rotation.axis.as_str() {
+            "x" => [1, 0, 0],
+            "y" => [0, 1, 0],
+            _ => [0, 0, 1],
+        };
+        let rotated = rotation.apply(axis);
+        let (name, sign) = if rotated[0] != 0 {
+            ("x", rotated[0])
+        } else if rotated[1] != 0 {
+            ("y", rotated[1])
+        } else {
+            ("z", rotated[2])
+        };
+        element_rotation.axis = name.to_string();
+        if sign < 0 {
+            element_rotation.angle = -element_rotation.angle;
+        }
+    }
+
+    element.faces = element
+        .faces
+        .iter()
+        .map(|(key, face)| rotate_face(key, face, from, to, rotation, uvlock))
+        .collect::<HashMap<_, _>>();
+}
+
+/// Rotate a resolved model the way a blockstate variant places it
+///
+/// # Arguments
+/// * `model` - Resolved model (parents merged)
+/// * `rot_x` - Variant `x` rotation in degrees (multiples of 90)
+/// * `rot_y` - Variant `y` rotation in degrees (multiples of 90)
+/// * `uvlock` - Keep textures aligned to the world instead of the model
+pub fn apply_variant_rotation(model: &mut BlockModel, rot_x: i32, rot_y: i32, uvlock: bool) {
+    let rotation = VariantRotation::new(rot_x, rot_y);
+    if rotation.is_identity() {
+        return;
+    }
+    if rot_x % 90 != 0 || rot_y % 90 != 0 {
+        warn!(
+            "Rotation x={} y={} is not a multiple of 90, rounding down",
+            rot_x, rot_y
+        );
+    }
+
+    for element in model.elements.iter_mut().flatten() {
+        rotate_element(element, rotation, uvlock);
+    }
+    debug!("Applied x={} y={} uvlock={}", rot_x, rot_y, uvlock);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::util::block_models::ElementRotation;
+
+    fn face(texture: &str) -> ElementFace {
+        ElementFace {
+            texture: texture.to_string(),
+            uv: None,
+            rotation: None,
+            cullface: None,
+            tintindex: None,
+        }
+    }
+
+    fn model(from: [f32; 3], to: [f32; 3], faces: &[&str]) -> BlockModel {
+        BlockModel {
+            parent: None,
+            textures: None,
+            elements: Some(vec![ModelElement {
+                from,
+                to,
+                rotation: None,
+                faces: faces
+                    .iter()
+                    .map(|f| (f.to_string(), face(&format!("#{}", f))))
+                    .collect(),
+                shade: None,
+            }]),
+            ambientocclusion: None,
+            missing_textures: Vec::new(),
+            builtin: None,
+            display: None,
+            display_transform: None,
+        }
+    }
+
+    fn element(model: &BlockModel) -> &ModelElement {
+        &model.elements.as_ref().unwrap()[0]
+    }
+
+    #[test]
+    fn test_y_rotation_moves_boxes_and_faces() {
+        // East half, like a stair step facing east
+        let mut m = model([8.0, 0.0, 0.0], [16.0, 8.0, 16.0], &["east", "up"]);
+        apply_variant_rotation(&mut m, 0, 90, false);
+
+        let e = element(&m);
+        assert_eq!(e.from, [0.0, 0.0, 8.0]);
+        assert_eq!(e.to, [16.0, 8.0, 16.0]);
+        assert_eq!(e.faces["south"].texture, "#east");
+        assert_eq!(e.faces["up"].texture, "#up");
+        // The top texture turns with the model
+        assert_eq!(e.faces["up"].rotation, Some(90));
+    }
+
+    #[test]
+    fn test_x_rotation_lays_logs_on_their_side() {
+        let mut m = model([0.0, 0.0, 0.0], [16.0, 16.0, 16.0], &["up", "north"]);
+        let e = &mut m.elements.as_mut().unwrap()[0];
+        e.faces.get_mut("up").unwrap().cullface = Some("up".to_string());
+        e.rotation = Some(ElementRotation {
+            origin: [8.0, 8.0, 8.0],
+            axis: "y".to_string(),
+            angle: 22.5,
+            rescale: None,
+        });
+
+        apply_variant_rotation(&mut m, 90, 0, false);
+
+        let e = element(&m);
+        assert_eq!(e.faces["north"].texture, "#up");
+        assert_eq!(e.faces["north"].cullface.as_deref(), Some("north"));
+        assert_eq!(e.faces["down"].texture, "#north");
+        let rotation = e.rotation.as_ref().unwrap();
+        assert_eq!(rotation.axis, "z");
+        assert_eq!(rotation.angle, -22.5);
+    }
+
+    #[test]
+    fn test_uvlock_keeps_textures_world_aligned() {
+        // North half slab standing up
+        let bounds = ([0.0, 0.0, 0.0], [16.0, 16.0, 8.0]);
+        let mut locked = model(bounds.0, bounds.1, &["up"]);
+        let mut unlocked = model(bounds.0, bounds.1, &["up"]);
+
+        apply_variant_rotation(&mut locked, 0, 90, true);
+        apply_variant_rotation(&mut unlocked, 0, 90, false);
+
+        // Locked: same UVs as an unrotated element covering the east half
+        let up = &element(&locked).faces["up"];
+        assert_eq!(up.uv, Some([8.0, 0.0, 16.0, 16.0]));
+        assert_eq!(up.rotation, None);
+        assert_eq!(
+            up.uv.unwrap(),
+            default_uv("up", element(&locked).from, element(&locked).to)
+        );
+
+        // Unlocked: the original region, turned a quarter
+        let up = &element(&unlocked).faces["up"];
+        assert_eq!(up.uv, Some([0.0, 0.0, 16.0, 8.0]));
+        assert_eq!(up.rotation, Some(90));
+    }
+
+    #[test]
+    fn test_no_rotation_is_untouched() {
+        let mut m = model([0.0, 0.0, 0.0], [16.0, 8.0, 16.0], &["up"]);
+        apply_variant_rotation(&mut m, 0, 360, true);
+        assert_eq!(element(&m).faces["up"].uv, None);
+    }
+}
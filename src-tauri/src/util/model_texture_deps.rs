@@ -0,0 +1,303 @@
+/// Model/texture cross-dependency checks
+///
+/// A pack's custom model often points at textures named for that pack
+/// ("minecraft:block/fancy_log_side"). If an override or a higher-priority
+/// pack supplies that texture instead, the model is drawn with art made for
+/// a different layout; if nothing supplies it, the block shows the purple
+/// and black missing texture in game. These checks find both cases.
+use crate::model::{OverrideSelection, PackMeta};
+use crate::util::animation;
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
+use tracing::{info, warn};
+use walkdir::WalkDir;
+
+/// What is wrong with a texture a model references
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ModelTextureIssueKind {
+    /// No enabled pack (nor vanilla) provides the texture
+    Missing,
+    /// The model's pack ships the texture, but another pack's copy wins
+    Mismatched,
+}
+
+/// A texture a winning model needs but won't get from its own pack
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ModelTextureIssue {
+    pub model_id: String,
+    /// Pack the model comes from
+    pub model_pack_id: String,
+    pub texture_id: String,
+    /// Pack the texture will come from, None if missing
+    pub texture_pack_id: Option<String>,
+    pub kind: ModelTextureIssueKind,
+}
+
+/// "assets/ns/models/block/x.json" -> "ns:block/x"
+pub(crate) fn model_id_for_path(path: &str) -> Option<String> {
+    let rest = path.strip_prefix("assets/")?;
+    let (namespace, rest) = rest.split_once('/')?;
+    let model = rest.strip_prefix("models/")?.strip_suffix(".json")?;
+    Some(format!("{}:{}", namespace, model))
+}
+
+/// Model files of a pack, as relative paths
+pub(crate) fn list_model_files(pack: &PackMeta) -> Result<Vec<String>> {
+    let files = if pack.is_zip {
+        crate::util::zip::list_zip_files(&pack.path)?
+    } else {
+        let root = Path::new(&pack.path);
+        WalkDir::new(root.join("assets"))
+            .into_iter()
+            .filter_map(|e| e.ok())
+            .filter(|e| e.path().is_file())
+            .filter_map(|e| {
+                e.path()
+                    .strip_prefix(root)
+                    .ok()
+                    .map(|p| p.to_string_lossy().replace('\\', "/"))
+            })
+            .collect()
+    };
+    Ok(files
+        .into_iter()
+        .filter(|f| model_id_for_path(f).is_some())
+        .collect())
+}
+
+/// Texture IDs a model JSON names directly, with its own variables resolved
+pub(crate) fn referenced_textures(json: &[u8]) -> Vec<String> {
+    let value: serde_json::Value = match serde_json::from_slice(json) {
+        Ok(value) => value,
+        Err(_) => return Vec::new(),
+    };
+    let textures = match value.get("textures").and_then(|t| t.as_object()) {
+        Some(textures) => textures,
+        None => return Vec::new(),
+    };
+
+    let mut ids: Vec<String> = textures
+        .values()
+        .filter_map(|v| v.as_str())
+        // "#side" points at another variable, possibly defined by a parent
+        .filter(|v| !v.starts_with('#'))
+        .map(|v| {
+            if v.contains(':') {
+                v.to_string()
+            } else {
+                format!("minecraft:{}", v)
+            }
+        })
+        .collect();
+    ids.sort();
+    ids.dedup();
+    ids
+}
+
+/// Find textures that winning models won't get from their own pack
+///
+/// Only the model a build would use (the first enabled pack providing it)
+/// is checked. A mismatch is not reported when the winning copy of the
+/// texture is byte-identical to the model pack's own.
+///
+/// # Arguments
+/// * `packs` - Scanned packs
+/// * `pack_order` - Enabled pack IDs in priority order (first = highest)
+/// * `overrides` - Asset ID -> chosen pack
+/// * `providers` - Asset ID -> pack IDs providing it
+/// * `identical` - Asset ID -> groups of packs with identical copies
+/// * `vanilla_dir` - Vanilla texture cache; without it, `minecraft:`
+///   textures no pack provides are assumed to be vanilla
+pub fn find_model_texture_issues(
+    packs: &[PackMeta],
+    pack_order: &[String],
+    overrides: &HashMap<String, OverrideSelection>,
+    providers: &HashMap<String, Vec<String>>,
+    identical: &HashMap<String, Vec<Vec<String>>>,
+    vanilla_dir: Option<&Path>,
+) -> Vec<ModelTextureIssue> {
+    let mut issues = Vec::new();
+    let mut seen_models: HashSet<String> = HashSet::new();
+
+    for pack_id in pack_order {
+        let pack = match packs.iter().find(|p| &p.id == pack_id) {
+            Some(pack) => pack,
+            None => continue,
+        };
+        let model_files = match list_model_files(pack) {
+            Ok(files) => files,
+            Err(e) => {
+                warn!("Could not list {}: {}", pack.id, e);
+                continue;
+            }
+        };
+
+        // Higher-priority packs already claimed the other models
+        let model_files: Vec<String> = model_files
+            .into_iter()
+            .filter(|file| seen_models.insert(file.clone()))
+            .collect();
+        let contents = match animation::read_pack_files(&pack.path, pack.is_zip, &model_files) {
+            Ok(contents) => contents,
+            Err(e) => {
+                warn!("Could not read {}: {}", pack.id, e);
+                continue;
+            }
+        };
+
+        for file in &model_files {
+            let model_id = model_id_for_path(file).unwrap_or_default();
+            let json = match contents.get(file) {
+                Some(json) => json,
+                None => continue,
+            };
+
+            for texture_id in referenced_textures(json) {
+                let enabled: Vec<&String> = pack_order
+                    .iter()
+                    .filter(|id| providers.get(&texture_id).map_or(false, |p| p.contains(id)))
+                    .collect();
+                let winner = overrides
+                    .get(&texture_id)
+                    .map(|o| &o.pack_id)
+                    .filter(|id| enabled.contains(id))
+                    .or_else(|| enabled.first().copied());
+
+                let kind = match winner {
+                    None => {
+                        let in_vanilla = match vanilla_dir {
+                            Some(dir) => dir
+                                .join(animation::texture_path_for_asset(&texture_id))
+                                .is_file(),
+                            None => texture_id.starts_with("minecraft:"),
+                        };
+                        if in_vanilla {
+                            continue;
+                        }
+                        ModelTextureIssueKind::Missing
+                    }
+                    Some(winner) if winner != pack_id && enabled.contains(&pack_id) => {
+                        let same_bytes = identical.get(&texture_id).map_or(false, |groups| {
+                            groups
+                                .iter()
+                                .any(|g| g.contains(winner) && g.contains(pack_id))
+                        });
+                        if same_bytes {
+                            continue;
+                        }
+                        ModelTextureIssueKind::Mismatched
+                    }
+                    Some(_) => continue,
+                };
+
+                issues.push(ModelTextureIssue {
+                    model_id: model_id.clone(),
+                    model_pack_id: pack_id.clone(),
+                    texture_id,
+                    texture_pack_id: winner.cloned(),
+                    kind,
+                });
+            }
+        }
+    }
+
+    info!("Found {} model texture issue(s)", issues.len());
+    issues
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::util::test_support::folder_pack;
+    use std::fs;
+
+    fn ids(values: &[&str]) -> Vec<String> {
+        values.iter().map(|v| v.to_string()).collect()
+    }
+
+    #[test]
+    fn test_referenced_textures() {
+        let json = br##"{"parent":"block/cube","textures":{"side":"block/fancy","end":"custom:block/top","particle":"#side"}}"##;
+        assert_eq!(
+            referenced_textures(json),
+            vec!["custom:block/top", "minecraft:block/fancy"]
+        );
+        assert_eq!(
+            model_id_for_path("assets/minecraft/models/block/stone.json").as_deref(),
+            Some("minecraft:block/stone")
+        );
+    }
+
+    #[test]
+    fn test_find_mismatched_and_missing_textures() {
+        let temp_dir = std::env::temp_dir().join("test_model_texture_deps");
+        fs::remove_dir_all(&temp_dir).ok();
+        let model = r##"{"textures":{"side":"block/fancy_log","top":"custom:block/fancy_top","all":"block/stone"}}"##;
+        let pack_a = folder_pack(
+            &temp_dir,
+            "pack_a",
+            &[("assets/minecraft/models/block/oak_log.json", model)],
+        );
+        let pack_b = folder_pack(&temp_dir, "pack_b", &[]);
+        let packs = vec![pack_a, pack_b];
+        let providers = HashMap::from([(
+            "minecraft:block/fancy_log".to_string(),
+            ids(&["pack_a", "pack_b"]),
+        )]);
+        let overrides = HashMap::from([(
+            "minecraft:block/fancy_log".to_string(),
+            OverrideSelection {
+                pack_id: "pack_b".to_string(),
+                variant_path: None,
+            },
+        )]);
+
+        let issues = find_model_texture_issues(
+            &packs,
+            &ids(&["pack_a", "pack_b"]),
+            &overrides,
+            &providers,
+            &HashMap::new(),
+            None,
+        );
+        let harmless = find_model_texture_issues(
+            &packs,
+            &ids(&["pack_a", "pack_b"]),
+            &overrides,
+            &providers,
+            &HashMap::from([(
+                "minecraft:block/fancy_log".to_string(),
+                vec![ids(&["pack_a", "pack_b"])],
+            )]),
+            None,
+        );
+
+        // Clean up
+        fs::remove_dir_all(&temp_dir).ok();
+
+        assert_eq!(
+            issues,
+            vec![
+                ModelTextureIssue {
+                    model_id: "minecraft:block/oak_log".to_string(),
+                    model_pack_id: "pack_a".to_string(),
+                    texture_id: "custom:block/fancy_top".to_string(),
+                    texture_pack_id: None,
+                    kind: ModelTextureIssueKind::Missing,
+                },
+                ModelTextureIssue {
+                    model_id: "minecraft:block/oak_log".to_string(),
+                    model_pack_id: "pack_a".to_string(),
+                    texture_id: "minecraft:block/fancy_log".to_string(),
+                    texture_pack_id: Some("pack_b".to_string()),
+                    kind: ModelTextureIssueKind::Mismatched,
+                },
+            ]
+        );
+        assert_eq!(harmless.len(), 1);
+    }
+}
@@ -0,0 +1,128 @@
+/// Profiles of the Modrinth App
+///
+/// The Modrinth App keeps every profile (often installed from a .mrpack
+/// modpack) in its own folder under `profiles/`, each with its own
+/// resourcepacks folder, so the launcher folder itself is no place to put
+/// a pack. Profiles are listed with the Minecraft version and mod loader
+/// from their profile.json, letting the user pick the resourcepacks folder
+/// of the right one directly. Newer app versions keep this in a database
+/// instead; their profiles are listed by folder name only.
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::fs;
+use std::path::Path;
+
+/// Per-profile metadata file of the Modrinth App
+pub const PROFILE_FILE: &str = "profile.json";
+
+/// A Modrinth App profile
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ModrinthProfile {
+    /// Name from profile.json, or the folder name
+    pub name: String,
+    pub path: String,
+    pub resourcepacks_dir: String,
+    /// Minecraft version (e.g., "1.20.1")
+    pub game_version: Option<String>,
+    /// "vanilla", "fabric", "forge", "neoforge" or "quilt"
+    pub loader: Option<String>,
+    pub loader_version: Option<String>,
+    /// Modrinth project the profile was installed from, for modpack profiles
+    pub modpack_project_id: Option<String>,
+}
+
+/// String at a JSON path, if set and not empty
+fn string_at(value: &Value, path: &[&str]) -> Option<String> {
+    path.iter()
+        .try_fold(value, |value, key| value.get(key))
+        .and_then(Value::as_str)
+        .filter(|s| !s.is_empty())
+        .map(str::to_string)
+}
+
+/// Read one profile folder
+///
+/// Older app versions nest the details under "metadata"; both layouts are
+/// read. A missing or unreadable profile.json leaves only the folder name.
+pub fn read_profile(profile_dir: &Path) -> ModrinthProfile {
+    let folder_name = profile_dir
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let json: Value = fs::read_to_string(profile_dir.join(PROFILE_FILE))
+        .ok()
+        .and_then(|contents| serde_json::from_str(&contents).ok())
+        .unwrap_or(Value::Null);
+    let metadata = json.get("metadata").unwrap_or(&json);
+
+    // The loader version is an object with an "id" in older profiles
+    let loader_version = string_at(metadata, &["loader_version", "id"])
+        .or_else(|| string_at(metadata, &["loader_version"]));
+
+    ModrinthProfile {
+        name: string_at(metadata, &["name"]).unwrap_or(folder_name),
+        path: profile_dir.to_string_lossy().to_string(),
+        resourcepacks_dir: profile_dir
+            .join("resourcepacks")
+            .to_string_lossy()
+            .to_string(),
+        game_version: string_at(metadata, &["game_version"]),
+        loader: string_at(metadata, &["loader"]).map(|loader| loader.to_lowercase()),
+        loader_version,
+        modpack_project_id: string_at(metadata, &["linked_data", "project_id"]),
+    }
+}
+
+/// List the profiles in a Modrinth App profiles folder, sorted by name
+pub fn list_profiles(profiles_dir: &Path) -> Vec<ModrinthProfile> {
+    let entries = match fs::read_dir(profiles_dir) {
+        Ok(entries) => entries,
+        Err(_) => return Vec::new(),
+    };
+
+    let mut profiles: Vec<ModrinthProfile> = entries
+        .filter_map(|e| e.ok())
+        .filter(|e| e.path().is_dir() && !e.file_name().to_string_lossy().starts_with('.'))
+        .map(|e| read_profile(&e.path()))
+        .collect();
+    profiles.sort_by_key(|profile| profile.name.to_lowercase());
+    profiles
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_list_profiles() {
+        let temp_dir = std::env::temp_dir().join("test_modrinth_profiles");
+        fs::remove_dir_all(&temp_dir).ok();
+        fs::create_dir_all(temp_dir.join("fabulous")).unwrap();
+        fs::create_dir_all(temp_dir.join("Adventure")).unwrap();
+        fs::create_dir_all(temp_dir.join(".caches")).unwrap();
+        fs::write(
+            temp_dir.join("fabulous").join(PROFILE_FILE),
+            r#"{"metadata": {"name": "Fabulously Optimized", "game_version": "1.20.1",
+                "loader": "Fabric", "loader_version": {"id": "0.14.21"},
+                "linked_data": {"project_id": "1KVo5zza"}}}"#,
+        )
+        .unwrap();
+
+        let profiles = list_profiles(&temp_dir);
+
+        // Clean up
+        fs::remove_dir_all(&temp_dir).ok();
+
+        assert_eq!(profiles.len(), 2);
+        assert_eq!(profiles[0].name, "Adventure");
+        assert_eq!(profiles[0].game_version, None);
+        assert!(profiles[0].resourcepacks_dir.ends_with("resourcepacks"));
+        let fabulous = &profiles[1];
+        assert_eq!(fabulous.name, "Fabulously Optimized");
+        assert_eq!(fabulous.game_version.as_deref(), Some("1.20.1"));
+        assert_eq!(fabulous.loader.as_deref(), Some("fabric"));
+        assert_eq!(fabulous.loader_version.as_deref(), Some("0.14.21"));
+        assert_eq!(fabulous.modpack_project_id.as_deref(), Some("1KVo5zza"));
+    }
+}
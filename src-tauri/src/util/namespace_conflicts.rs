@@ -0,0 +1,188 @@
+/// Namespace collision detection
+///
+/// Custom namespaces (e.g. "fresh_animations:") usually belong to one
+/// feature pack, and merging files from two packs that both ship the same
+/// namespace tends to break the feature entirely. These collisions are
+/// reported separately from ordinary minecraft: overrides, and can be
+/// resolved by giving the namespace a single owner pack for the build.
+use crate::model::AssetRecord;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+
+/// Namespaces where overlapping packs are expected and merged per asset
+pub const SHARED_NAMESPACES: &[&str] = &["minecraft", "realms"];
+
+/// A custom namespace provided by more than one pack
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NamespaceCollision {
+    pub namespace: String,
+    /// Packs providing the namespace, highest priority first
+    pub pack_ids: Vec<String>,
+    /// Assets in the namespace across all packs
+    pub total_assets: usize,
+    /// Assets provided by more than one of the packs
+    pub shared_assets: usize,
+    /// Pack that would own the namespace if excluded from the others
+    pub suggested_owner: String,
+}
+
+/// Get the namespace of an asset ID ("minecraft" if none is given)
+pub fn namespace_of(asset_id: &str) -> &str {
+    asset_id
+        .split_once(':')
+        .map(|(namespace, _)| namespace)
+        .unwrap_or("minecraft")
+}
+
+/// Find custom namespaces provided by two or more packs
+///
+/// # Arguments
+/// * `assets` - Indexed assets
+/// * `providers` - Map of asset ID -> pack IDs providing it
+/// * `pack_order` - Pack IDs in priority order (first = highest)
+pub fn detect_namespace_collisions(
+    assets: &[AssetRecord],
+    providers: &HashMap<String, Vec<String>>,
+    pack_order: &[String],
+) -> Vec<NamespaceCollision> {
+    // namespace -> (packs, total assets, shared assets)
+    let mut namespaces: BTreeMap<&str, (Vec<String>, usize, usize)> = BTreeMap::new();
+
+    for asset in assets {
+        let namespace = namespace_of(&asset.id);
+        if SHARED_NAMESPACES.contains(&namespace) {
+            continue;
+        }
+
+        let asset_providers = providers.get(&asset.id).map(Vec::as_slice).unwrap_or(&[]);
+        let entry = namespaces.entry(namespace).or_default();
+        entry.1 += 1;
+        if asset_providers.len() > 1 {
+            entry.2 += 1;
+        }
+        for pack_id in asset_providers {
+            if !entry.0.contains(pack_id) {
+                entry.0.push(pack_id.clone());
+            }
+        }
+    }
+
+    namespaces
+        .into_iter()
+        .filter(|(_, (packs, _, _))| packs.len() > 1)
+        .map(|(namespace, (mut pack_ids, total_assets, shared_assets))| {
+            pack_ids.sort_by_key(|id| {
+                pack_order
+                    .iter()
+                    .position(|p| p == id)
+                    .unwrap_or(usize::MAX)
+            });
+            NamespaceCollision {
+                namespace: namespace.to_string(),
+                suggested_owner: pack_ids[0].clone(),
+                pack_ids,
+                total_assets,
+                shared_assets,
+            }
+        })
+        .collect()
+}
+
+/// Drop providers that don't own their asset's namespace
+///
+/// Assets left without a provider are skipped by the build, so a namespace
+/// comes entirely from its owner pack.
+///
+/// # Arguments
+/// * `providers` - Map of asset ID -> pack IDs providing it
+/// * `namespace_owners` - Map of namespace -> the only pack allowed to provide it
+pub fn restrict_namespace_providers(
+    providers: &HashMap<String, Vec<String>>,
+    namespace_owners: &HashMap<String, String>,
+) -> HashMap<String, Vec<String>> {
+    providers
+        .iter()
+        .map(|(asset_id, packs)| {
+            let packs = match namespace_owners.get(namespace_of(asset_id)) {
+                Some(owner) => packs.iter().filter(|p| *p == owner).cloned().collect(),
+                None => packs.clone(),
+            };
+            (asset_id.clone(), packs)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn asset(id: &str) -> AssetRecord {
+        AssetRecord {
+            id: id.to_string(),
+            labels: Vec::new(),
+            files: Vec::new(),
+            animation: None,
+        }
+    }
+
+    fn providers(entries: &[(&str, &[&str])]) -> HashMap<String, Vec<String>> {
+        entries
+            .iter()
+            .map(|(id, packs)| {
+                (
+                    id.to_string(),
+                    packs.iter().map(|p| p.to_string()).collect(),
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_detect_namespace_collisions_ignores_minecraft() {
+        let assets = vec![
+            asset("minecraft:block/stone"),
+            asset("fresh_animations:entity/cow"),
+            asset("fresh_animations:entity/pig"),
+            asset("solo:item/thing"),
+        ];
+        let providers = providers(&[
+            ("minecraft:block/stone", &["a", "b"]),
+            ("fresh_animations:entity/cow", &["a", "b"]),
+            ("fresh_animations:entity/pig", &["a"]),
+            ("solo:item/thing", &["a"]),
+        ]);
+        let order = vec!["b".to_string(), "a".to_string()];
+
+        let collisions = detect_namespace_collisions(&assets, &providers, &order);
+
+        assert_eq!(
+            collisions,
+            vec![NamespaceCollision {
+                namespace: "fresh_animations".to_string(),
+                pack_ids: vec!["b".to_string(), "a".to_string()],
+                total_assets: 2,
+                shared_assets: 1,
+                suggested_owner: "b".to_string(),
+            }]
+        );
+    }
+
+    #[test]
+    fn test_restrict_namespace_providers() {
+        let providers = providers(&[
+            ("minecraft:block/stone", &["a", "b"]),
+            ("fresh_animations:entity/cow", &["a", "b"]),
+            ("fresh_animations:entity/pig", &["a"]),
+        ]);
+        let owners: HashMap<String, String> = [("fresh_animations".to_string(), "b".to_string())]
+            .into_iter()
+            .collect();
+
+        let restricted = restrict_namespace_providers(&providers, &owners);
+
+        assert_eq!(restricted["minecraft:block/stone"], vec!["a", "b"]);
+        assert_eq!(restricted["fresh_animations:entity/cow"], vec!["b"]);
+        assert!(restricted["fresh_animations:entity/pig"].is_empty());
+    }
+}
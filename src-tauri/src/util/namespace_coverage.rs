@@ -0,0 +1,175 @@
+/// Per-namespace coverage of a pack order
+///
+/// Packs made for mods ship their textures under the mod's namespace
+/// ("create:", "farmersdelight:"), where there's no vanilla reference to
+/// measure them against. This breaks the indexed assets down by namespace
+/// and reports, for each, how many of its assets every pack provides and
+/// wins, so a merge of a base pack with several mod packs can be checked
+/// one mod at a time.
+use crate::model::OverrideSelection;
+use crate::util::asset_indexer;
+use crate::util::namespace_conflicts::namespace_of;
+use crate::util::weaver_nest;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use tracing::info;
+
+/// One pack's share of a namespace
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NamespacePackCoverage {
+    pub pack_id: String,
+    /// Assets of the namespace the pack provides
+    pub assets: usize,
+    /// Share (0-1) of the namespace's assets the pack provides
+    pub coverage: f32,
+    /// Assets of the namespace the pack wins in the merge
+    pub wins: usize,
+}
+
+/// Coverage of one namespace
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NamespaceCoverage {
+    pub namespace: String,
+    /// Assets of the namespace provided by any pack
+    pub total_assets: usize,
+    /// Assets provided by more than one pack
+    pub contested_assets: usize,
+    /// Packs providing the namespace, highest priority first
+    pub packs: Vec<NamespacePackCoverage>,
+}
+
+/// Break the indexed assets down by namespace
+///
+/// CEM and CTM groups are left out, as in the pack order suggestion, so
+/// counts are comparable between namespaces.
+///
+/// # Arguments
+/// * `pack_order` - Enabled pack IDs in priority order (first = highest)
+/// * `overrides` - Asset ID -> chosen pack
+/// * `providers` - Asset ID -> pack IDs providing it
+///
+/// # Returns
+/// Namespaces with the most assets first
+pub fn namespace_coverage(
+    pack_order: &[String],
+    overrides: &HashMap<String, OverrideSelection>,
+    providers: &HashMap<String, Vec<String>>,
+) -> Vec<NamespaceCoverage> {
+    // namespace -> (total, contested, pack -> (assets, wins))
+    type Counts<'a> = (usize, usize, HashMap<&'a str, (usize, usize)>);
+    let mut namespaces: BTreeMap<&str, Counts> = BTreeMap::new();
+
+    for (asset_id, pack_ids) in providers {
+        if asset_indexer::is_grouped_asset(asset_id) {
+            continue;
+        }
+        let enabled: Vec<&String> = pack_ids
+            .iter()
+            .filter(|id| pack_order.contains(id))
+            .collect();
+        if enabled.is_empty() {
+            continue;
+        }
+
+        let (total, contested, packs) = namespaces.entry(namespace_of(asset_id)).or_default();
+        *total += 1;
+        if enabled.len() > 1 {
+            *contested += 1;
+        }
+        for pack_id in &enabled {
+            packs.entry(pack_id.as_str()).or_default().0 += 1;
+        }
+        let winner = weaver_nest::winning_pack(asset_id, providers, pack_order, overrides)
+            .filter(|winner| enabled.iter().any(|id| id == winner));
+        if let Some(winner) = winner {
+            packs.entry(winner).or_default().1 += 1;
+        }
+    }
+
+    let mut coverage: Vec<NamespaceCoverage> = namespaces
+        .into_iter()
+        .map(|(namespace, (total_assets, contested_assets, packs))| {
+            let packs = pack_order
+                .iter()
+                .filter_map(|pack_id| {
+                    let (assets, wins) = packs.get(pack_id.as_str())?;
+                    Some(NamespacePackCoverage {
+                        pack_id: pack_id.clone(),
+                        assets: *assets,
+                        coverage: *assets as f32 / total_assets as f32,
+                        wins: *wins,
+                    })
+                })
+                .collect();
+            NamespaceCoverage {
+                namespace: namespace.to_string(),
+                total_assets,
+                contested_assets,
+                packs,
+            }
+        })
+        .collect();
+    coverage.sort_by_key(|n| std::cmp::Reverse(n.total_assets));
+
+    info!(
+        "{} namespace(s) across {} pack(s)",
+        coverage.len(),
+        pack_order.len()
+    );
+
+    coverage
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_namespace_coverage() {
+        let providers: HashMap<String, Vec<String>> = [
+            ("minecraft:block/stone", vec!["base", "create_pack"]),
+            ("minecraft:block/dirt", vec!["base"]),
+            ("minecraft:block/sand", vec!["base"]),
+            ("create:block/cogwheel", vec!["create_pack", "base"]),
+            ("create:block/shaft", vec!["create_pack"]),
+            ("farmersdelight:item/knife", vec!["disabled"]),
+            ("minecraft:cem/cow", vec!["base"]),
+        ]
+        .into_iter()
+        .map(|(id, packs)| {
+            (
+                id.to_string(),
+                packs.into_iter().map(|p| p.to_string()).collect(),
+            )
+        })
+        .collect();
+        let order = vec!["create_pack".to_string(), "base".to_string()];
+        let overrides = HashMap::from([(
+            "create:block/cogwheel".to_string(),
+            OverrideSelection {
+                pack_id: "base".to_string(),
+                variant_path: None,
+            },
+        )]);
+
+        let coverage = namespace_coverage(&order, &overrides, &providers);
+
+        let namespaces: Vec<(&str, usize, usize)> = coverage
+            .iter()
+            .map(|n| (n.namespace.as_str(), n.total_assets, n.contested_assets))
+            .collect();
+        assert_eq!(namespaces, vec![("minecraft", 3, 1), ("create", 2, 1)]);
+
+        let create = &coverage[1];
+        let packs: Vec<(&str, usize, usize)> = create
+            .packs
+            .iter()
+            .map(|p| (p.pack_id.as_str(), p.assets, p.wins))
+            .collect();
+        assert_eq!(packs, vec![("create_pack", 2, 1), ("base", 1, 1)]);
+        assert_eq!(create.packs[0].coverage, 1.0);
+        assert_eq!(coverage[0].packs[1].coverage, 1.0);
+    }
+}
@@ -0,0 +1,374 @@
+/// OptiFine connected textures (CTM)
+///
+/// Packs can retexture blocks through `assets/<namespace>/optifine/ctm/`:
+/// each `.properties` file names the blocks (or tiles) it applies to, a
+/// method and the tile images next to it. The indexer groups every file of
+/// a CTM folder into one asset ("minecraft:ctm/glass") so it is built and
+/// overridden as a whole. Two packs can still define CTM for the same block
+/// from differently named folders; both end up in the output and OptiFine
+/// picks one, so such blocks are reported as conflicts.
+use crate::model::PackMeta;
+use crate::util::animation;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::Path;
+use tracing::{info, warn};
+use walkdir::WalkDir;
+
+/// CTM directory relative to a namespace
+pub const CTM_DIR: &str = "optifine/ctm/";
+
+/// Files that make up a CTM definition
+const CTM_EXTENSIONS: &[&str] = &["properties", "png"];
+
+/// A parsed CTM `.properties` file
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CtmDefinition {
+    /// CTM asset the file belongs to (e.g., "minecraft:ctm/glass")
+    pub asset_id: String,
+    /// Path of the properties file within the pack
+    pub file: String,
+    /// Connection method (e.g., "ctm", "horizontal", "random")
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub method: Option<String>,
+    /// Blocks the definition applies to, without namespace or state
+    /// (e.g., "oak_log"); tile names stand in when only `matchTiles` is set
+    pub blocks: Vec<String>,
+}
+
+/// Blocks with CTM from more than one pack
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CtmConflict {
+    pub block: String,
+    /// Packs defining CTM for the block, highest priority first
+    pub pack_ids: Vec<String>,
+    /// CTM assets involved; more than one means both reach the output
+    pub asset_ids: Vec<String>,
+}
+
+/// CTM definitions of each pack and the blocks they fight over
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CtmReport {
+    /// Pack ID -> definitions (packs without CTM omitted)
+    pub definitions: BTreeMap<String, Vec<CtmDefinition>>,
+    pub conflicts: Vec<CtmConflict>,
+}
+
+/// CTM group a file inside optifine/ctm/ belongs to: its folder, or its name
+/// for files directly in ctm/
+///
+/// - "glass/glass.properties" -> Some("glass")
+/// - "glass/12.png" -> Some("glass")
+/// - "_overlays/grass/grass.properties" -> Some("_overlays/grass")
+/// - "bookshelf.properties" -> Some("bookshelf")
+pub fn ctm_group_for_file(ctm_file: &str) -> Option<String> {
+    let (folder, file_name) = match ctm_file.rsplit_once('/') {
+        Some((folder, file_name)) => (Some(folder), file_name),
+        None => (None, ctm_file),
+    };
+    let (stem, ext) = file_name.rsplit_once('.')?;
+    if !CTM_EXTENSIONS.contains(&ext.to_lowercase().as_str()) {
+        return None;
+    }
+
+    Some(folder.unwrap_or(stem).to_lowercase())
+}
+
+/// Whether an asset ID refers to a CTM group
+pub fn is_ctm_asset(asset_id: &str) -> bool {
+    asset_id
+        .split_once(':')
+        .map_or(false, |(_, path)| path.starts_with("ctm/"))
+}
+
+/// Parse a CTM `.properties` file
+///
+/// Blocks come from `matchBlocks`, else from `matchTiles`, else from the
+/// file name ("block_oak_log.properties" or "glass.properties"), as in
+/// OptiFine.
+///
+/// # Arguments
+/// * `contents` - Properties file text
+/// * `file` - Path of the file within the pack
+pub fn parse_ctm_properties(contents: &str, file: &str) -> CtmDefinition {
+    let mut properties: BTreeMap<&str, &str> = BTreeMap::new();
+    for line in contents.lines().map(str::trim) {
+        if line.is_empty() || line.starts_with('#') || line.starts_with('!') {
+            continue;
+        }
+        if let Some((key, value)) = line.split_once(['=', ':']) {
+            properties.insert(key.trim(), value.trim());
+        }
+    }
+
+    let ids = |value: &str| -> Vec<String> {
+        value
+            .split_whitespace()
+            .map(|id| {
+                let id = id.strip_prefix("minecraft:").unwrap_or(id);
+                // Block states ("oak_log:axis=y") and tile folders ("block/glass")
+                let id = id.split(':').next().unwrap_or(id);
+                id.rsplit('/').next().unwrap_or(id).to_lowercase()
+            })
+            .filter(|id| !id.is_empty())
+            .collect()
+    };
+
+    let file_stem = Path::new(file)
+        .file_stem()
+        .map(|s| s.to_string_lossy().to_lowercase())
+        .unwrap_or_default();
+    let mut blocks = match (properties.get("matchBlocks"), properties.get("matchTiles")) {
+        (Some(value), _) => ids(value),
+        (None, Some(value)) => ids(value),
+        (None, None) => vec![file_stem
+            .strip_prefix("block_")
+            .unwrap_or(&file_stem)
+            .to_string()],
+    };
+    blocks.sort();
+    blocks.dedup();
+
+    let asset_id = file
+        .strip_prefix("assets/")
+        .and_then(|rest| rest.split_once('/'))
+        .and_then(|(namespace, rest)| {
+            let group = ctm_group_for_file(rest.strip_prefix(CTM_DIR)?)?;
+            Some(format!("{}:ctm/{}", namespace, group))
+        })
+        .unwrap_or_default();
+
+    CtmDefinition {
+        asset_id,
+        file: file.to_string(),
+        method: properties.get("method").map(|m| m.to_string()),
+        blocks,
+    }
+}
+
+/// CTM properties files of a pack
+fn list_ctm_properties(pack: &PackMeta) -> Vec<String> {
+    let is_ctm_properties = |file: &str| {
+        file.ends_with(".properties")
+            && file
+                .strip_prefix("assets/")
+                .and_then(|rest| rest.split_once('/'))
+                .map_or(false, |(_, rest)| rest.starts_with(CTM_DIR))
+    };
+
+    if pack.is_zip {
+        return crate::util::zip::list_zip_files(&pack.path)
+            .unwrap_or_default()
+            .into_iter()
+            .filter(|f| is_ctm_properties(f))
+            .collect();
+    }
+
+    let root = Path::new(&pack.path);
+    WalkDir::new(root.join("assets"))
+        .into_iter()
+        .filter_map(|e| e.ok())
+        .filter(|e| e.path().is_file())
+        .filter_map(|e| {
+            let rel = e.path().strip_prefix(root).ok()?;
+            Some(rel.to_string_lossy().replace('\\', "/"))
+        })
+        .filter(|f| is_ctm_properties(f))
+        .collect()
+}
+
+/// Read the CTM definitions of a pack; unreadable files are skipped
+pub fn pack_ctm_definitions(pack: &PackMeta) -> Vec<CtmDefinition> {
+    let mut definitions: Vec<CtmDefinition> = list_ctm_properties(pack)
+        .into_iter()
+        .filter_map(
+            |file| match animation::read_pack_bytes(&pack.path, pack.is_zip, &file) {
+                Ok(bytes) => Some(parse_ctm_properties(
+                    &String::from_utf8_lossy(&bytes),
+                    &file,
+                )),
+                Err(e) => {
+                    warn!("Skipping {} in {}: {}", file, pack.name, e);
+                    None
+                }
+            },
+        )
+        .collect();
+    definitions.sort_by(|a, b| a.file.cmp(&b.file));
+    definitions
+}
+
+/// Collect CTM definitions and find blocks defined by several packs
+///
+/// # Arguments
+/// * `packs` - Packs to check
+/// * `pack_order` - Pack IDs in priority order (first = highest); packs not
+///   in it are ignored when non-empty
+pub fn ctm_report(packs: &[PackMeta], pack_order: &[String]) -> CtmReport {
+    let rank = |pack_id: &str| {
+        pack_order
+            .iter()
+            .position(|id| id == pack_id)
+            .unwrap_or(usize::MAX)
+    };
+
+    let mut report = CtmReport::default();
+    // block -> (pack ID, asset ID) pairs
+    let mut by_block: BTreeMap<String, Vec<(String, String)>> = BTreeMap::new();
+    for pack in packs {
+        if !pack_order.is_empty() && rank(&pack.id) == usize::MAX {
+            continue;
+        }
+        let definitions = pack_ctm_definitions(pack);
+        if definitions.is_empty() {
+            continue;
+        }
+        for definition in &definitions {
+            for block in &definition.blocks {
+                by_block
+                    .entry(block.clone())
+                    .or_default()
+                    .push((pack.id.clone(), definition.asset_id.clone()));
+            }
+        }
+        report.definitions.insert(pack.id.clone(), definitions);
+    }
+
+    for (block, entries) in by_block {
+        let mut pack_ids: Vec<String> =
+            entries.iter().map(|(pack_id, _)| pack_id.clone()).collect();
+        pack_ids.sort_by_key(|id| (rank(id), id.clone()));
+        pack_ids.dedup();
+        if pack_ids.len() < 2 {
+            continue;
+        }
+        let mut asset_ids: Vec<String> =
+            entries.into_iter().map(|(_, asset_id)| asset_id).collect();
+        asset_ids.sort();
+        asset_ids.dedup();
+        report.conflicts.push(CtmConflict {
+            block,
+            pack_ids,
+            asset_ids,
+        });
+    }
+
+    info!(
+        "{} pack(s) with CTM, {} conflicting block(s)",
+        report.definitions.len(),
+        report.conflicts.len()
+    );
+
+    report
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    #[test]
+    fn test_parse_ctm_properties() {
+        let glass = parse_ctm_properties(
+            "# Connected glass\nmethod=ctm\nmatchBlocks=minecraft:glass glass_pane\ntiles=0-46\n",
+            "assets/minecraft/optifine/ctm/glass/glass.properties",
+        );
+        let log = parse_ctm_properties(
+            "method=horizontal\nmatchTiles=block/oak_log\n",
+            "assets/minecraft/optifine/ctm/logs/oak.properties",
+        );
+        let named = parse_ctm_properties(
+            "method=random\n",
+            "assets/minecraft/optifine/ctm/block_stone.properties",
+        );
+
+        assert_eq!(glass.asset_id, "minecraft:ctm/glass");
+        assert_eq!(glass.method.as_deref(), Some("ctm"));
+        assert_eq!(glass.blocks, vec!["glass", "glass_pane"]);
+        assert_eq!(log.blocks, vec!["oak_log"]);
+        assert_eq!(named.asset_id, "minecraft:ctm/block_stone");
+        assert_eq!(named.blocks, vec!["stone"]);
+    }
+
+    #[test]
+    fn test_ctm_group_for_file() {
+        assert_eq!(ctm_group_for_file("glass/12.png").as_deref(), Some("glass"));
+        assert_eq!(
+            ctm_group_for_file("_overlays/Grass/grass.properties").as_deref(),
+            Some("_overlays/grass")
+        );
+        assert_eq!(
+            ctm_group_for_file("bookshelf.properties").as_deref(),
+            Some("bookshelf")
+        );
+        assert_eq!(ctm_group_for_file("glass/readme.txt"), None);
+        assert!(is_ctm_asset("minecraft:ctm/glass"));
+        assert!(!is_ctm_asset("minecraft:block/glass"));
+    }
+
+    #[test]
+    fn test_ctm_report_conflicts() {
+        let temp_dir = std::env::temp_dir().join("test_optifine_ctm");
+        fs::remove_dir_all(&temp_dir).ok();
+        let pack = |id: &str, files: &[(&str, &str)]| {
+            let dir = temp_dir.join(id);
+            for (path, contents) in files {
+                let file = dir.join(path);
+                fs::create_dir_all(file.parent().unwrap()).unwrap();
+                fs::write(file, contents).unwrap();
+            }
+            PackMeta {
+                id: id.to_string(),
+                name: id.to_string(),
+                path: dir.to_string_lossy().to_string(),
+                ..Default::default()
+            }
+        };
+        let packs = vec![
+            pack(
+                "a",
+                &[(
+                    "assets/minecraft/optifine/ctm/glass/glass.properties",
+                    "matchBlocks=glass",
+                )],
+            ),
+            pack(
+                "b",
+                &[
+                    (
+                        "assets/minecraft/optifine/ctm/connected/glass.properties",
+                        "matchBlocks=glass",
+                    ),
+                    (
+                        "assets/minecraft/optifine/ctm/bookshelf/bookshelf.properties",
+                        "method=horizontal",
+                    ),
+                ],
+            ),
+            pack("c", &[("assets/minecraft/textures/block/glass.png", "")]),
+        ];
+        let order = vec!["b".to_string(), "a".to_string(), "c".to_string()];
+
+        let report = ctm_report(&packs, &order);
+
+        // Clean up
+        fs::remove_dir_all(&temp_dir).ok();
+
+        assert_eq!(report.definitions.len(), 2);
+        assert_eq!(report.definitions["b"].len(), 2);
+        assert_eq!(
+            report.conflicts,
+            vec![CtmConflict {
+                block: "glass".to_string(),
+                pack_ids: vec!["b".to_string(), "a".to_string()],
+                asset_ids: vec![
+                    "minecraft:ctm/connected".to_string(),
+                    "minecraft:ctm/glass".to_string(),
+                ],
+            }]
+        );
+    }
+}
@@ -0,0 +1,259 @@
+/// Dependency closure for overrides
+///
+/// Overriding one texture of a block to a pack while the block's other
+/// textures still come from elsewhere mixes art made for different models
+/// (a log's side from one pack, its top from another). The closure of an
+/// override is every texture the block's models reference after parent
+/// resolution; taking those from the same pack keeps the block consistent.
+use crate::model::{OverrideSelection, PackMeta};
+use crate::util::block_models::{self, BlockModel};
+use crate::util::blockstates::{self, Blockstate, BlockstateVariant};
+use crate::util::{asset_indexer, missing_texture};
+use std::collections::{BTreeSet, HashMap};
+use std::path::Path;
+use tracing::debug;
+
+/// Serde default for the build option: on unless turned off
+pub(crate) fn enabled_by_default() -> bool {
+    true
+}
+
+/// Every model a blockstate can use, across variants and multipart cases
+pub(crate) fn blockstate_model_ids(blockstate: &Blockstate) -> BTreeSet<String> {
+    let mut variants: Vec<&BlockstateVariant> = Vec::new();
+    if let Some(map) = &blockstate.variants {
+        variants.extend(map.values());
+    }
+    if let Some(cases) = &blockstate.multipart {
+        variants.extend(cases.iter().map(|case| &case.apply));
+    }
+
+    variants
+        .into_iter()
+        .flat_map(|variant| match variant {
+            BlockstateVariant::Single(reference) => vec![reference.model.clone()],
+            BlockstateVariant::Multiple(references) => {
+                references.iter().map(|r| r.model.clone()).collect()
+            }
+        })
+        .collect()
+}
+
+/// "block/oak_log" -> "minecraft:block/oak_log"
+fn texture_asset_id(texture: &str) -> String {
+    if texture.contains(':') {
+        texture.to_string()
+    } else {
+        format!("minecraft:{}", texture)
+    }
+}
+
+/// Texture asset IDs a resolved model draws, without unresolved variables
+pub(crate) fn model_textures(model: &BlockModel) -> impl Iterator<Item = String> {
+    block_models::resolve_textures(model)
+        .into_values()
+        .filter(|t| !t.starts_with('#') && t != missing_texture::MISSING_TEXTURE_ID)
+        .map(|t| texture_asset_id(&t))
+}
+
+/// Textures the block behind a texture asset uses, as `pack` would draw it
+///
+/// The block is found from the texture name, its blockstate is read from
+/// `pack` (or vanilla), and every model it can show is resolved.
+///
+/// # Returns
+/// Texture asset IDs (e.g. "minecraft:block/oak_log_top"), including
+/// `asset_id` itself when the block uses it; empty if no block is found
+pub fn block_texture_closure(
+    asset_id: &str,
+    pack: &PackMeta,
+    vanilla_pack: &PackMeta,
+) -> Vec<String> {
+    let block_id = match blockstates::texture_id_to_block_id(asset_id) {
+        Some(block_id) => block_id,
+        None => return Vec::new(),
+    };
+    let blockstate = blockstates::read_blockstate(Path::new(&pack.path), &block_id, pack.is_zip)
+        .or_else(|_| {
+            blockstates::read_blockstate(
+                Path::new(&vanilla_pack.path),
+                &block_id,
+                vanilla_pack.is_zip,
+            )
+        });
+    let blockstate = match blockstate {
+        Ok(blockstate) => blockstate,
+        Err(_) => return Vec::new(),
+    };
+
+    let textures: BTreeSet<String> = blockstate_model_ids(&blockstate)
+        .iter()
+        .filter_map(|model_id| block_models::resolve_block_model(pack, model_id, vanilla_pack).ok())
+        .flat_map(|model| model_textures(&model).collect::<Vec<_>>())
+        .collect();
+    textures.into_iter().collect()
+}
+
+/// Add overrides so each overridden block takes all its textures from one pack
+///
+/// For every override, the textures of the block's models that the same
+/// pack provides are overridden to that pack too. Overrides the user set
+/// explicitly are never replaced, and variant overrides (a specific
+/// alternate file) and custom entity models are left alone. When two
+/// overrides pull the same texture, the one with the lower asset ID wins.
+///
+/// # Arguments
+/// * `overrides` - Asset ID -> chosen pack, as set by the user
+/// * `packs` - Scanned packs
+/// * `vanilla_pack` - Vanilla cache, for blockstates and parents packs don't ship
+/// * `providers` - Asset ID -> pack IDs providing it
+///
+/// # Returns
+/// The overrides with the pulled-in textures added
+pub fn expand_override_dependencies(
+    overrides: &HashMap<String, OverrideSelection>,
+    packs: &[PackMeta],
+    vanilla_pack: &PackMeta,
+    providers: &HashMap<String, Vec<String>>,
+) -> HashMap<String, OverrideSelection> {
+    let mut expanded = overrides.clone();
+    let mut asset_ids: Vec<&String> = overrides.keys().collect();
+    asset_ids.sort();
+
+    for asset_id in asset_ids {
+        let selection = &overrides[asset_id];
+        if selection.variant_path.is_some() || asset_indexer::is_grouped_asset(asset_id) {
+            continue;
+        }
+        let pack = match packs.iter().find(|p| p.id == selection.pack_id) {
+            Some(pack) => pack,
+            None => continue,
+        };
+
+        for texture_id in block_texture_closure(asset_id, pack, vanilla_pack) {
+            let provided = providers
+                .get(&texture_id)
+                .map_or(false, |p| p.contains(&pack.id));
+            if !provided || expanded.contains_key(&texture_id) {
+                continue;
+            }
+            debug!("{} pulls {} from {}", asset_id, texture_id, pack.id);
+            expanded.insert(
+                texture_id,
+                OverrideSelection {
+                    pack_id: pack.id.clone(),
+                    variant_path: None,
+                },
+            );
+        }
+    }
+
+    expanded
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::util::test_support::folder_pack;
+    use std::fs;
+
+    fn vanilla(root: &Path) -> PackMeta {
+        folder_pack(
+            root,
+            "vanilla",
+            &[
+                (
+                    "assets/minecraft/blockstates/oak_log.json",
+                    r#"{"variants":{"axis=y":{"model":"minecraft:block/oak_log"},"axis=x":{"model":"minecraft:block/oak_log_horizontal","x":90,"y":90}}}"#,
+                ),
+                (
+                    "assets/minecraft/models/block/oak_log.json",
+                    r#"{"textures":{"end":"minecraft:block/oak_log_top","side":"minecraft:block/oak_log"}}"#,
+                ),
+                (
+                    "assets/minecraft/models/block/oak_log_horizontal.json",
+                    r#"{"textures":{"end":"minecraft:block/oak_log_top","side":"minecraft:block/oak_log"}}"#,
+                ),
+            ],
+        )
+    }
+
+    #[test]
+    fn test_block_texture_closure_covers_all_variants() {
+        let temp_dir = std::env::temp_dir().join("test_override_closure_blocks");
+        fs::remove_dir_all(&temp_dir).ok();
+        let vanilla = vanilla(&temp_dir);
+        let pack = folder_pack(
+            &temp_dir,
+            "pack",
+            &[(
+                "assets/minecraft/models/block/oak_log_horizontal.json",
+                r#"{"textures":{"end":"minecraft:block/oak_log_top","side":"block/oak_log_side_h"}}"#,
+            )],
+        );
+
+        let closure = block_texture_closure("minecraft:block/oak_log", &pack, &vanilla);
+
+        // Clean up
+        fs::remove_dir_all(&temp_dir).ok();
+
+        assert_eq!(
+            closure,
+            vec![
+                "minecraft:block/oak_log",
+                "minecraft:block/oak_log_side_h",
+                "minecraft:block/oak_log_top",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_expand_override_dependencies() {
+        let temp_dir = std::env::temp_dir().join("test_override_closure_expand");
+        fs::remove_dir_all(&temp_dir).ok();
+        let vanilla = vanilla(&temp_dir);
+        let packs = vec![
+            folder_pack(&temp_dir, "pack_a", &[]),
+            folder_pack(&temp_dir, "pack_b", &[]),
+        ];
+        let ids = |v: &[&str]| v.iter().map(|s| s.to_string()).collect::<Vec<_>>();
+        let providers = HashMap::from([
+            (
+                "minecraft:block/oak_log".to_string(),
+                ids(&["pack_a", "pack_b"]),
+            ),
+            (
+                "minecraft:block/oak_log_top".to_string(),
+                ids(&["pack_a", "pack_b"]),
+            ),
+        ]);
+        let select = |pack_id: &str| OverrideSelection {
+            pack_id: pack_id.to_string(),
+            variant_path: None,
+        };
+
+        let pulled = expand_override_dependencies(
+            &HashMap::from([("minecraft:block/oak_log".to_string(), select("pack_b"))]),
+            &packs,
+            &vanilla,
+            &providers,
+        );
+        let explicit = expand_override_dependencies(
+            &HashMap::from([
+                ("minecraft:block/oak_log".to_string(), select("pack_b")),
+                ("minecraft:block/oak_log_top".to_string(), select("pack_a")),
+            ]),
+            &packs,
+            &vanilla,
+            &providers,
+        );
+
+        // Clean up
+        fs::remove_dir_all(&temp_dir).ok();
+
+        assert_eq!(pulled.len(), 2);
+        assert_eq!(pulled["minecraft:block/oak_log_top"].pack_id, "pack_b");
+        // An explicit choice is kept
+        assert_eq!(explicit["minecraft:block/oak_log_top"].pack_id, "pack_a");
+    }
+}
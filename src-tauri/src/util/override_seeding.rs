@@ -0,0 +1,350 @@
+/// Carrying overrides over from an older project
+///
+/// Starting a project over an updated set of packs usually means the same
+/// packs in newer versions: "Faithful 32x 1.20.zip" became "Faithful 32x
+/// 1.21.zip". Every override of the old project is mapped onto the new pack
+/// of the same lineage, tried in order:
+///
+/// 1. the same pack ID
+/// 2. the same family (name without version tokens, see
+///    `texture_history::pack_family_key`), newest member first
+/// 3. the same known pack (see `known_packs`)
+/// 4. a new pack with a byte-identical copy of the overridden asset, when
+///    the old pack is still around to hash
+///
+/// Overrides that can't be mapped, or whose asset the matched pack no
+/// longer provides, are reported instead of carried over.
+use crate::model::{OverrideSelection, PackMeta};
+use crate::util::content_hash::AssetHashes;
+use crate::util::known_packs;
+use crate::util::texture_history::{compare_chronologically, pack_family_key};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// How an old pack was matched to a new one
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum LineageMatch {
+    SameId,
+    Family,
+    KnownPack,
+    /// The new pack has a byte-identical copy of the overridden asset
+    ContentHash,
+}
+
+/// Where an old pack ended up
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PackLineage {
+    pub old_pack_id: String,
+    /// None if no new pack continues it
+    pub new_pack_id: Option<String>,
+    pub matched_by: Option<LineageMatch>,
+}
+
+/// Why an old override wasn't carried over
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum DropReason {
+    /// No new pack continues the old one
+    PackMissing,
+    /// The matched pack no longer provides the asset
+    AssetMissing,
+}
+
+/// An old override that wasn't carried over
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DroppedOverride {
+    pub asset_id: String,
+    pub old_pack_id: String,
+    /// Pack the old one was matched to, for `AssetMissing`
+    pub new_pack_id: Option<String>,
+    pub reason: DropReason,
+}
+
+/// Overrides seeded from an older project
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OverrideSeed {
+    /// Asset ID -> override on the new packs
+    pub overrides: HashMap<String, OverrideSelection>,
+    /// Every old pack an override picked, sorted by old pack ID
+    pub lineage: Vec<PackLineage>,
+    /// Sorted by asset ID
+    pub dropped: Vec<DroppedOverride>,
+}
+
+/// New pack continuing an old one, by ID, family or known pack
+///
+/// # Arguments
+/// * `old_pack` - The old pack, or a stand-in named after its ID
+/// * `new_packs` - Packs of the new project
+/// * `known` - Known-pack ID of each old and new pack that has one
+fn match_pack<'a>(
+    old_pack: &PackMeta,
+    new_packs: &'a [PackMeta],
+    known: &HashMap<String, String>,
+) -> Option<(&'a PackMeta, LineageMatch)> {
+    if let Some(pack) = new_packs.iter().find(|p| p.id == old_pack.id) {
+        return Some((pack, LineageMatch::SameId));
+    }
+
+    let family = pack_family_key(&old_pack.name);
+    let newest_in_family = new_packs
+        .iter()
+        .filter(|p| !family.is_empty() && pack_family_key(&p.name) == family)
+        .max_by(|a, b| compare_chronologically(a, b));
+    if let Some(pack) = newest_in_family {
+        return Some((pack, LineageMatch::Family));
+    }
+
+    let known_id = known.get(&old_pack.id)?;
+    new_packs
+        .iter()
+        .filter(|p| known.get(&p.id) == Some(known_id))
+        .max_by(|a, b| compare_chronologically(a, b))
+        .map(|pack| (pack, LineageMatch::KnownPack))
+}
+
+/// Map an old project's overrides onto a new set of packs
+///
+/// # Arguments
+/// * `old_overrides` - Asset ID -> override of the old project
+/// * `old_packs` - Old packs still on disk; packs missing here are matched
+///   by their ID alone
+/// * `new_packs` - Packs of the new project
+/// * `new_providers` - Asset ID -> new packs providing it
+/// * `old_hashes` - Old pack ID -> hashes of its overridden assets
+/// * `new_hashes` - New pack ID -> asset hashes
+/// * `new_files` - New pack ID -> file listing from the asset index
+pub fn seed_overrides(
+    old_overrides: &HashMap<String, OverrideSelection>,
+    old_packs: &[PackMeta],
+    new_packs: &[PackMeta],
+    new_providers: &HashMap<String, Vec<String>>,
+    old_hashes: &HashMap<String, AssetHashes>,
+    new_hashes: &HashMap<String, AssetHashes>,
+    new_files: &HashMap<String, Vec<String>>,
+) -> OverrideSeed {
+    // Old packs that are gone are named after their file
+    let mut old_by_id: HashMap<&str, PackMeta> = old_packs
+        .iter()
+        .map(|pack| (pack.id.as_str(), pack.clone()))
+        .collect();
+    for selection in old_overrides.values() {
+        old_by_id
+            .entry(selection.pack_id.as_str())
+            .or_insert_with(|| PackMeta {
+                id: selection.pack_id.clone(),
+                name: selection.pack_id.clone(),
+                path: String::new(),
+                ..Default::default()
+            });
+    }
+
+    let stand_ins: Vec<PackMeta> = old_by_id.values().cloned().collect();
+    let known: HashMap<String, String> =
+        known_packs::identify_known_packs(&stand_ins, &HashMap::new())
+            .into_iter()
+            .chain(known_packs::identify_known_packs(new_packs, new_files))
+            .map(|(pack_id, known)| (pack_id, known.known_id))
+            .collect();
+    let matches: HashMap<&str, Option<(&PackMeta, LineageMatch)>> = old_by_id
+        .iter()
+        .map(|(id, pack)| (*id, match_pack(pack, new_packs, &known)))
+        .collect();
+
+    let mut seed = OverrideSeed::default();
+    // Old pack ID -> new pack an asset of it was found in by content
+    let mut found_by_content: HashMap<&str, String> = HashMap::new();
+    for (asset_id, selection) in old_overrides {
+        let old_pack_id = &selection.pack_id;
+        let provides = |pack_id: &str| {
+            new_providers
+                .get(asset_id)
+                .map_or(false, |providers| providers.iter().any(|p| p == pack_id))
+        };
+        let matched = matches.get(old_pack_id.as_str()).copied().flatten();
+
+        let carried = match matched {
+            Some((pack, how)) if provides(&pack.id) => Some((pack.id.clone(), how)),
+            _ => old_hashes
+                .get(old_pack_id)
+                .and_then(|hashes| hashes.get(asset_id))
+                .and_then(|old_hash| {
+                    new_packs.iter().find(|pack| {
+                        new_hashes
+                            .get(&pack.id)
+                            .and_then(|hashes| hashes.get(asset_id))
+                            == Some(old_hash)
+                    })
+                })
+                .map(|pack| (pack.id.clone(), LineageMatch::ContentHash)),
+        };
+
+        match carried {
+            Some((new_pack_id, how)) => {
+                seed.overrides.insert(
+                    asset_id.clone(),
+                    OverrideSelection {
+                        pack_id: new_pack_id.clone(),
+                        // A copy found by content may sit at another path
+                        variant_path: if how == LineageMatch::ContentHash {
+                            None
+                        } else {
+                            selection.variant_path.clone()
+                        },
+                    },
+                );
+                if how == LineageMatch::ContentHash {
+                    found_by_content
+                        .entry(old_pack_id.as_str())
+                        .or_insert(new_pack_id);
+                }
+            }
+            None => {
+                seed.dropped.push(DroppedOverride {
+                    asset_id: asset_id.clone(),
+                    old_pack_id: old_pack_id.clone(),
+                    new_pack_id: matched.map(|(pack, _)| pack.id.clone()),
+                    reason: if matched.is_some() {
+                        DropReason::AssetMissing
+                    } else {
+                        DropReason::PackMissing
+                    },
+                });
+            }
+        }
+    }
+
+    let mut old_pack_ids: Vec<&str> = old_overrides
+        .values()
+        .map(|selection| selection.pack_id.as_str())
+        .collect();
+    old_pack_ids.sort_unstable();
+    old_pack_ids.dedup();
+    seed.lineage = old_pack_ids
+        .into_iter()
+        .map(|old_pack_id| {
+            let (new_pack_id, matched_by) = match matches.get(old_pack_id).copied().flatten() {
+                Some((pack, how)) => (Some(pack.id.clone()), Some(how)),
+                None => match found_by_content.get(old_pack_id) {
+                    Some(pack_id) => (Some(pack_id.clone()), Some(LineageMatch::ContentHash)),
+                    None => (None, None),
+                },
+            };
+            PackLineage {
+                old_pack_id: old_pack_id.to_string(),
+                new_pack_id,
+                matched_by,
+            }
+        })
+        .collect();
+    seed.dropped.sort_by(|a, b| a.asset_id.cmp(&b.asset_id));
+    seed
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pack(id: &str) -> PackMeta {
+        PackMeta {
+            id: id.to_string(),
+            name: id.trim_end_matches(".zip").to_string(),
+            path: String::new(),
+            is_zip: true,
+            ..Default::default()
+        }
+    }
+
+    fn selection(pack_id: &str) -> OverrideSelection {
+        OverrideSelection {
+            pack_id: pack_id.to_string(),
+            variant_path: None,
+        }
+    }
+
+    #[test]
+    fn test_seed_overrides() {
+        let old_overrides = HashMap::from([
+            (
+                "minecraft:block/stone".to_string(),
+                selection("Stay True 1.20.zip"),
+            ),
+            (
+                "minecraft:block/dirt".to_string(),
+                selection("Stay True 1.20.zip"),
+            ),
+            ("minecraft:block/sand".to_string(), selection("Kept.zip")),
+            (
+                "minecraft:block/gravel".to_string(),
+                selection("Renamed.zip"),
+            ),
+            ("minecraft:block/clay".to_string(), selection("Gone.zip")),
+        ]);
+        let new_packs = vec![
+            pack("Stay True 1.20.4.zip"),
+            pack("Stay True 1.21.zip"),
+            pack("Kept.zip"),
+            pack("Fresh Name.zip"),
+        ];
+        let new_providers: HashMap<String, Vec<String>> = [
+            ("minecraft:block/stone", "Stay True 1.21.zip"),
+            ("minecraft:block/sand", "Kept.zip"),
+            ("minecraft:block/gravel", "Fresh Name.zip"),
+        ]
+        .iter()
+        .map(|(asset, pack)| (asset.to_string(), vec![pack.to_string()]))
+        .collect();
+        let old_hashes = HashMap::from([(
+            "Renamed.zip".to_string(),
+            HashMap::from([("minecraft:block/gravel".to_string(), 7u64)]),
+        )]);
+        let new_hashes = HashMap::from([(
+            "Fresh Name.zip".to_string(),
+            HashMap::from([("minecraft:block/gravel".to_string(), 7u64)]),
+        )]);
+
+        let seed = seed_overrides(
+            &old_overrides,
+            &[],
+            &new_packs,
+            &new_providers,
+            &old_hashes,
+            &new_hashes,
+            &HashMap::new(),
+        );
+
+        assert_eq!(
+            seed.overrides["minecraft:block/stone"].pack_id,
+            "Stay True 1.21.zip"
+        );
+        assert_eq!(seed.overrides["minecraft:block/sand"].pack_id, "Kept.zip");
+        assert_eq!(
+            seed.overrides["minecraft:block/gravel"].pack_id,
+            "Fresh Name.zip"
+        );
+        let lineage = |id: &str| seed.lineage.iter().find(|l| l.old_pack_id == id).unwrap();
+        assert_eq!(
+            lineage("Stay True 1.20.zip").matched_by,
+            Some(LineageMatch::Family)
+        );
+        assert_eq!(lineage("Kept.zip").matched_by, Some(LineageMatch::SameId));
+        assert_eq!(
+            lineage("Renamed.zip").matched_by,
+            Some(LineageMatch::ContentHash)
+        );
+        assert_eq!(lineage("Gone.zip").new_pack_id, None);
+        assert_eq!(seed.dropped.len(), 2);
+        assert_eq!(seed.dropped[0].asset_id, "minecraft:block/clay");
+        assert_eq!(seed.dropped[0].reason, DropReason::PackMissing);
+        assert_eq!(seed.dropped[1].asset_id, "minecraft:block/dirt");
+        assert_eq!(seed.dropped[1].reason, DropReason::AssetMissing);
+        assert_eq!(
+            seed.dropped[1].new_pack_id.as_deref(),
+            Some("Stay True 1.21.zip")
+        );
+    }
+}
@@ -0,0 +1,468 @@
+/// Whole-pack zip extraction and re-compression
+///
+/// Users who want to edit a zipped pack need it as a folder, and usually
+/// want it zipped again afterwards. Both directions write to a staging
+/// location next to the target and move the result into place only once
+/// it is complete, so an interrupted run never leaves a half-written pack.
+/// Repacking also cleans up a messy working folder for distribution.
+use crate::util::zip::{self, PROGRESS_INTERVAL};
+use crate::util::{asset_indexer, build_journal, file_retry, instance_lock};
+use ::zip::write::FileOptions;
+use ::zip::{CompressionMethod, DateTime};
+use anyhow::{anyhow, Context, Result};
+use serde::{Deserialize, Serialize};
+use std::fs::{self, File};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use tracing::info;
+use walkdir::WalkDir;
+
+/// Suffix of a zip being written
+const PARTIAL_ZIP_SUFFIX: &str = ".weaverbird-partial";
+
+/// How deep below the source folder pack.mcmeta is looked for
+const MAX_ROOT_DEPTH: usize = 3;
+
+/// OS and editor files that don't belong in a pack (compared lowercase)
+const JUNK_NAMES: &[&str] = &[
+    ".ds_store",
+    "thumbs.db",
+    "desktop.ini",
+    ".gitignore",
+    ".gitattributes",
+];
+
+/// Folders whose contents don't belong in a pack
+const JUNK_DIRS: &[&str] = &[".git", ".svn", ".idea", ".vscode"];
+
+/// Image editor projects, backups and unfinished zips (compared lowercase)
+const JUNK_EXTENSIONS: &[&str] = &[
+    ".psd",
+    ".xcf",
+    ".kra",
+    ".ase",
+    ".aseprite",
+    ".pdn",
+    ".bak",
+    ".tmp",
+    PARTIAL_ZIP_SUFFIX,
+];
+
+fn is_empty_dir(path: &Path) -> bool {
+    fs::read_dir(path).map_or(false, |mut entries| entries.next().is_none())
+}
+
+/// Extract a whole zip pack into a folder
+///
+/// The pack is extracted into a staging folder first (entry names are
+/// checked against zip-slip before anything is written), then moved to
+/// `dest` in one rename.
+///
+/// # Arguments
+/// * `zip_path` - Zip pack to extract
+/// * `dest` - Folder to create; may exist only if empty
+/// * `progress` - Called with (files extracted, total files)
+///
+/// # Returns
+/// Number of files extracted
+pub fn extract_pack_archive(
+    zip_path: &Path,
+    dest: &Path,
+    progress: Option<&dyn Fn(usize, usize)>,
+) -> Result<usize> {
+    if dest.exists() && !is_empty_dir(dest) {
+        return Err(anyhow!("{} already exists and isn't empty", dest.display()));
+    }
+
+    let _dest_lock =
+        instance_lock::InstanceLock::acquire(dest, &format!("extracting into {}", dest.display()))?;
+    let staging = build_journal::staging_dir_for(dest)?;
+    if staging.exists() {
+        file_retry::remove_dir_all_with_retry(&staging)?;
+    }
+    let zip_path_str = zip_path
+        .to_str()
+        .ok_or_else(|| anyhow!("Invalid zip path: {:?}", zip_path))?;
+    let extracted = match zip::extract_zip_to_dir_with_progress(zip_path_str, &staging, progress) {
+        Ok(extracted) => extracted,
+        Err(e) => {
+            fs::remove_dir_all(&staging).ok();
+            return Err(e);
+        }
+    };
+
+    if dest.exists() {
+        build_journal::promote_staging(&staging, dest)?;
+    } else if let Err(e) = file_retry::rename_with_retry(&staging, dest) {
+        if file_retry::locked_file(&e).is_some() {
+            return Err(e);
+        }
+        build_journal::promote_staging(&staging, dest)?;
+    }
+
+    info!(
+        "Extracted {} file(s) from {} to {}",
+        extracted,
+        zip_path.display(),
+        dest.display()
+    );
+    Ok(extracted)
+}
+
+/// Compress a folder pack into a zip
+///
+/// Entries are written in sorted order with forward-slash paths. The zip is
+/// written next to `zip_path` and renamed over it when complete, replacing
+/// any existing file.
+///
+/// # Arguments
+/// * `source` - Folder pack (the folder containing pack.mcmeta)
+/// * `zip_path` - Zip file to write
+/// * `progress` - Called with (files compressed, total files)
+///
+/// # Returns
+/// Number of files compressed
+pub fn compress_pack_folder(
+    source: &Path,
+    zip_path: &Path,
+    progress: Option<&dyn Fn(usize, usize)>,
+) -> Result<usize> {
+    if !source.is_dir() {
+        return Err(anyhow!("{} is not a folder", source.display()));
+    }
+
+    let files = list_files(source);
+    let options = FileOptions::default().compression_method(CompressionMethod::Deflated);
+    write_zip_atomically(source, &files, zip_path, options, progress)?;
+
+    info!(
+        "Compressed {} file(s) from {} to {}",
+        files.len(),
+        source.display(),
+        zip_path.display()
+    );
+    Ok(files.len())
+}
+
+/// Result of repacking a folder pack
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RepackReport {
+    pub zip_path: String,
+    /// Files written to the zip
+    pub files: usize,
+    /// Folder inside the source that holds pack.mcmeta ("" for the source itself)
+    pub root: String,
+    /// Junk files left out, relative to the root
+    pub skipped: Vec<String>,
+    /// How many of the skipped files were macOS metadata (__MACOSX, ._*)
+    pub os_metadata_files: usize,
+}
+
+/// Whether a file is editor, OS or version-control clutter
+///
+/// Includes macOS metadata (see `asset_indexer::is_os_metadata_file`).
+///
+/// # Arguments
+/// * `path` - Path relative to the pack root, with forward slashes
+pub fn is_junk_file(path: &str) -> bool {
+    let mut parts = path.split('/');
+    let name = parts.next_back().unwrap_or(path);
+    if parts.any(|dir| JUNK_DIRS.contains(&dir)) {
+        return true;
+    }
+    let lower = name.to_lowercase();
+    asset_indexer::is_os_metadata_file(path)
+        || JUNK_NAMES.contains(&lower.as_str())
+        || name.ends_with('~')
+        || JUNK_EXTENSIONS.iter().any(|ext| lower.ends_with(ext))
+}
+
+/// Folder holding pack.mcmeta: the source itself or the shallowest nested one
+///
+/// Packs zipped as "MyPack/pack.mcmeta" and extracted again end up one
+/// level too deep; the zip must have pack.mcmeta at its root.
+fn find_pack_root(source: &Path) -> Result<PathBuf> {
+    WalkDir::new(source)
+        .max_depth(MAX_ROOT_DEPTH + 1)
+        .sort_by_file_name()
+        .into_iter()
+        .filter_map(|e| e.ok())
+        .filter(|e| e.file_type().is_file() && e.file_name() == "pack.mcmeta")
+        .filter(|e| {
+            let relative = e.path().strip_prefix(source).unwrap_or(e.path());
+            !is_junk_file(&relative.to_string_lossy().replace('\\', "/"))
+        })
+        .min_by_key(|e| e.depth())
+        .and_then(|e| e.path().parent().map(Path::to_path_buf))
+        .ok_or_else(|| anyhow!("No pack.mcmeta found in {}", source.display()))
+}
+
+/// Zip a folder pack as a clean distributable
+///
+/// Unlike `compress_pack_folder`, the zip is normalized: pack.mcmeta is
+/// moved to the zip root if the pack sits in a nested folder, junk files
+/// (.DS_Store, Thumbs.db, .git, image editor projects, ...) are left out,
+/// and entries are sorted with fixed timestamps so repacking unchanged
+/// files produces an identical zip.
+///
+/// # Arguments
+/// * `source` - Working folder of the pack
+/// * `zip_path` - Zip file to write (replaced if it exists)
+/// * `progress` - Called with (files compressed, total files)
+pub fn repack_pack_folder(
+    source: &Path,
+    zip_path: &Path,
+    progress: Option<&dyn Fn(usize, usize)>,
+) -> Result<RepackReport> {
+    if !source.is_dir() {
+        return Err(anyhow!("{} is not a folder", source.display()));
+    }
+    let root = find_pack_root(source)?;
+
+    let (skipped, files): (Vec<String>, Vec<String>) =
+        list_files(&root).into_iter().partition(|f| is_junk_file(f));
+    let options = FileOptions::default()
+        .compression_method(CompressionMethod::Deflated)
+        .last_modified_time(DateTime::default())
+        .unix_permissions(0o644);
+    write_zip_atomically(&root, &files, zip_path, options, progress)?;
+
+    let root = root
+        .strip_prefix(source)
+        .map(|p| p.to_string_lossy().replace('\\', "/"))
+        .unwrap_or_default();
+    info!(
+        "Repacked {} file(s) from {} (root {:?}, {} junk skipped)",
+        files.len(),
+        source.display(),
+        root,
+        skipped.len()
+    );
+
+    Ok(RepackReport {
+        zip_path: zip_path.to_string_lossy().to_string(),
+        files: files.len(),
+        root,
+        os_metadata_files: skipped
+            .iter()
+            .filter(|f| asset_indexer::is_os_metadata_file(f))
+            .count(),
+        skipped,
+    })
+}
+
+/// Files under a folder, relative with forward slashes, sorted
+pub(crate) fn list_files(source: &Path) -> Vec<String> {
+    let mut files: Vec<String> = WalkDir::new(source)
+        .into_iter()
+        .filter_map(|e| e.ok())
+        .filter(|e| e.file_type().is_file())
+        .filter_map(|e| {
+            e.path()
+                .strip_prefix(source)
+                .ok()
+                .map(|p| p.to_string_lossy().replace('\\', "/"))
+        })
+        .collect();
+    files.sort();
+    files
+}
+
+/// Write a zip next to `zip_path`, then rename it into place
+fn write_zip_atomically(
+    source: &Path,
+    files: &[String],
+    zip_path: &Path,
+    options: FileOptions,
+    progress: Option<&dyn Fn(usize, usize)>,
+) -> Result<()> {
+    let file_name = zip_path
+        .file_name()
+        .ok_or_else(|| anyhow!("Invalid zip path: {:?}", zip_path))?;
+    let _zip_lock =
+        instance_lock::InstanceLock::acquire(zip_path, &format!("writing {}", zip_path.display()))?;
+    let partial = zip_path.with_file_name(format!(
+        "{}{}",
+        file_name.to_string_lossy(),
+        PARTIAL_ZIP_SUFFIX
+    ));
+    let result = write_zip(source, files, &partial, options, progress)
+        .and_then(|_| file_retry::rename_with_retry(&partial, zip_path));
+    zip::release_archive(zip_path);
+    if result.is_err() {
+        fs::remove_file(&partial).ok();
+    }
+    result
+}
+
+fn write_zip(
+    source: &Path,
+    files: &[String],
+    zip_path: &Path,
+    options: FileOptions,
+    progress: Option<&dyn Fn(usize, usize)>,
+) -> Result<()> {
+    let mut writer = ::zip::ZipWriter::new(file_retry::create_file_with_retry(zip_path)?);
+
+    for (written, name) in files.iter().enumerate() {
+        writer
+            .start_file(name.as_str(), options)
+            .with_context(|| format!("Failed to add {} to zip", name))?;
+        let mut input =
+            File::open(source.join(name)).with_context(|| format!("Failed to read {}", name))?;
+        std::io::copy(&mut input, &mut writer)
+            .with_context(|| format!("Failed to compress {}", name))?;
+
+        if let Some(progress) = progress {
+            if (written + 1) % PROGRESS_INTERVAL == 0 || written + 1 == files.len() {
+                progress(written + 1, files.len());
+            }
+        }
+    }
+
+    writer
+        .finish()
+        .context("Failed to finish zip")?
+        .flush()
+        .context("Failed to write zip")?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::RefCell;
+
+    #[test]
+    fn test_extract_and_compress_round_trip() {
+        let temp_dir = std::env::temp_dir().join("test_pack_archive_round_trip");
+        fs::remove_dir_all(&temp_dir).ok();
+        let source = temp_dir.join("source");
+        fs::create_dir_all(source.join("assets/minecraft/textures/block")).unwrap();
+        fs::write(source.join("pack.mcmeta"), b"{}").unwrap();
+        fs::write(
+            source.join("assets/minecraft/textures/block/stone.png"),
+            b"stone",
+        )
+        .unwrap();
+
+        let zip_path = temp_dir.join("pack.zip");
+        let reports = RefCell::new(Vec::new());
+        let record = |done: usize, total: usize| reports.borrow_mut().push((done, total));
+        let compressed = compress_pack_folder(&source, &zip_path, Some(&record)).unwrap();
+        let entries = zip::list_zip_files(zip_path.to_str().unwrap()).unwrap();
+
+        let dest = temp_dir.join("extracted");
+        let extracted = extract_pack_archive(&zip_path, &dest, None).unwrap();
+        let stone = fs::read(dest.join("assets/minecraft/textures/block/stone.png")).unwrap();
+        let staging_left = build_journal::staging_dir_for(&dest).unwrap().exists();
+        let again = extract_pack_archive(&zip_path, &dest, None);
+
+        // Clean up
+        fs::remove_dir_all(&temp_dir).ok();
+
+        assert_eq!(compressed, 2);
+        assert_eq!(
+            entries,
+            vec!["assets/minecraft/textures/block/stone.png", "pack.mcmeta"]
+        );
+        assert_eq!(reports.into_inner(), vec![(2, 2)]);
+        assert_eq!(extracted, 2);
+        assert_eq!(stone, b"stone");
+        assert!(!staging_left);
+        assert!(again.is_err());
+    }
+
+    #[test]
+    fn test_extract_rejects_zip_slip() {
+        let temp_dir = std::env::temp_dir().join("test_pack_archive_zip_slip");
+        fs::remove_dir_all(&temp_dir).ok();
+        fs::create_dir_all(&temp_dir).unwrap();
+        let zip_path = temp_dir.join("evil.zip");
+        let mut writer = ::zip::ZipWriter::new(File::create(&zip_path).unwrap());
+        writer
+            .start_file("../escaped.txt", FileOptions::default())
+            .unwrap();
+        writer.write_all(b"data").unwrap();
+        writer.finish().unwrap();
+
+        let dest = temp_dir.join("extracted");
+        let result = extract_pack_archive(&zip_path, &dest, None);
+        let dest_exists = dest.exists();
+        let escaped = temp_dir.join("escaped.txt").exists();
+
+        // Clean up
+        fs::remove_dir_all(&temp_dir).ok();
+
+        assert!(result.is_err());
+        assert!(!dest_exists);
+        assert!(!escaped);
+    }
+
+    #[test]
+    fn test_is_junk_file() {
+        assert!(is_junk_file(".DS_Store"));
+        assert!(is_junk_file("assets/minecraft/textures/block/Thumbs.db"));
+        assert!(is_junk_file("__MACOSX/assets/._stone.png"));
+        assert!(is_junk_file(".git/config"));
+        assert!(is_junk_file("assets/minecraft/textures/block/stone.psd"));
+        assert!(!is_junk_file("assets/minecraft/textures/block/stone.png"));
+        assert!(!is_junk_file("pack.mcmeta"));
+    }
+
+    #[test]
+    fn test_repack_normalizes_nested_folder() {
+        let temp_dir = std::env::temp_dir().join("test_pack_archive_repack");
+        fs::remove_dir_all(&temp_dir).ok();
+        // Extracted with an extra folder level and macOS/editor clutter
+        let root = temp_dir.join("work/MyPack");
+        fs::create_dir_all(root.join("assets/minecraft/textures/block")).unwrap();
+        fs::create_dir_all(temp_dir.join("work/__MACOSX/MyPack")).unwrap();
+        fs::write(root.join("pack.mcmeta"), b"{}").unwrap();
+        fs::write(root.join(".DS_Store"), b"junk").unwrap();
+        fs::write(
+            root.join("assets/minecraft/textures/block/stone.png"),
+            b"png",
+        )
+        .unwrap();
+        fs::write(
+            root.join("assets/minecraft/textures/block/stone.psd"),
+            b"psd",
+        )
+        .unwrap();
+        fs::write(temp_dir.join("work/__MACOSX/MyPack/._pack.mcmeta"), b"junk").unwrap();
+        fs::write(
+            root.join("assets/minecraft/textures/block/._stone.png"),
+            b"fork",
+        )
+        .unwrap();
+
+        let first_zip = temp_dir.join("first.zip");
+        let second_zip = temp_dir.join("second.zip");
+        let report = repack_pack_folder(&temp_dir.join("work"), &first_zip, None).unwrap();
+        repack_pack_folder(&temp_dir.join("work"), &second_zip, None).unwrap();
+        let entries = zip::list_zip_files(first_zip.to_str().unwrap()).unwrap();
+        let identical = fs::read(&first_zip).unwrap() == fs::read(&second_zip).unwrap();
+
+        // Clean up
+        fs::remove_dir_all(&temp_dir).ok();
+
+        assert_eq!(report.root, "MyPack");
+        assert_eq!(report.files, 2);
+        assert_eq!(
+            report.skipped,
+            vec![
+                ".DS_Store",
+                "assets/minecraft/textures/block/._stone.png",
+                "assets/minecraft/textures/block/stone.psd",
+            ]
+        );
+        assert_eq!(report.os_metadata_files, 1);
+        assert_eq!(
+            entries,
+            vec!["assets/minecraft/textures/block/stone.png", "pack.mcmeta"]
+        );
+        assert!(identical);
+    }
+}
@@ -0,0 +1,384 @@
+/// Read-only file tree of a single pack
+///
+/// Lists the folders and files of a zip or folder pack as a tree, one level
+/// (or a few) at a time, so the UI can show a file explorer and load deeper
+/// folders only when they are expanded. Folder nodes always carry their
+/// total size and file count, even when their children are not loaded.
+use crate::util::zip::sanitize_entry_path;
+use anyhow::{anyhow, Context, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs::File;
+use std::path::Path;
+use tracing::debug;
+use walkdir::WalkDir;
+use zip::ZipArchive;
+
+/// Deepest level of children returned by one call
+pub const MAX_BROWSE_DEPTH: usize = 16;
+
+/// What a tree entry is, guessed from its extension
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum PackEntryKind {
+    Folder,
+    Image,
+    Json,
+    Mcmeta,
+    Sound,
+    Shader,
+    Properties,
+    Text,
+    Other,
+}
+
+impl PackEntryKind {
+    /// Kind of a file from its name
+    pub fn from_file_name(name: &str) -> Self {
+        let extension = match name.rsplit_once('.') {
+            Some((_, ext)) => ext.to_lowercase(),
+            None => return PackEntryKind::Other,
+        };
+        match extension.as_str() {
+            "png" | "jpg" | "jpeg" | "tga" => PackEntryKind::Image,
+            "json" | "jem" | "jpm" => PackEntryKind::Json,
+            "mcmeta" => PackEntryKind::Mcmeta,
+            "ogg" | "wav" => PackEntryKind::Sound,
+            "fsh" | "vsh" | "glsl" => PackEntryKind::Shader,
+            "properties" => PackEntryKind::Properties,
+            "txt" | "md" | "lang" => PackEntryKind::Text,
+            _ => PackEntryKind::Other,
+        }
+    }
+}
+
+/// A folder or file in a pack
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PackTreeEntry {
+    /// File or folder name ("" for the pack root)
+    pub name: String,
+    /// '/'-separated path from the pack root ("" for the pack root)
+    pub path: String,
+    pub kind: PackEntryKind,
+    /// Size in bytes (uncompressed for zips; total of all files for folders)
+    pub size: u64,
+    /// Number of files below a folder (1 for files)
+    pub file_count: usize,
+    /// Child entries, folders first; None if not loaded yet (or a file)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub children: Option<Vec<PackTreeEntry>>,
+}
+
+/// Folder contents collected before building the tree
+#[derive(Default)]
+struct DirBuilder {
+    dirs: BTreeMap<String, DirBuilder>,
+    files: BTreeMap<String, u64>,
+}
+
+impl DirBuilder {
+    /// Add a file (or an empty folder) at a '/'-separated relative path
+    fn insert(&mut self, rel_path: &str, size: u64, is_dir: bool) {
+        let mut parts: Vec<&str> = rel_path.split('/').filter(|p| !p.is_empty()).collect();
+        let leaf = match parts.pop() {
+            Some(leaf) => leaf,
+            None => return,
+        };
+
+        let mut dir = self;
+        for part in parts {
+            dir = dir.dirs.entry(part.to_string()).or_default();
+        }
+        if is_dir {
+            dir.dirs.entry(leaf.to_string()).or_default();
+        } else {
+            dir.files.insert(leaf.to_string(), size);
+        }
+    }
+
+    fn totals(&self) -> (u64, usize) {
+        self.dirs.values().map(DirBuilder::totals).fold(
+            (self.files.values().sum(), self.files.len()),
+            |(size, count), (s, c)| (size + s, count + c),
+        )
+    }
+
+    fn into_entry(self, name: String, path: String, depth: usize) -> PackTreeEntry {
+        let (size, file_count) = self.totals();
+        let children = if depth == 0 {
+            None
+        } else {
+            let join = |child: &str| {
+                if path.is_empty() {
+                    child.to_string()
+                } else {
+                    format!("{}/{}", path, child)
+                }
+            };
+
+            let mut dirs: Vec<PackTreeEntry> = self
+                .dirs
+                .into_iter()
+                .map(|(child, dir)| {
+                    let child_path = join(&child);
+                    dir.into_entry(child, child_path, depth - 1)
+                })
+                .collect();
+            let mut files: Vec<PackTreeEntry> = self
+                .files
+                .into_iter()
+                .map(|(child, size)| PackTreeEntry {
+                    kind: PackEntryKind::from_file_name(&child),
+                    path: join(&child),
+                    name: child,
+                    size,
+                    file_count: 1,
+                    children: None,
+                })
+                .collect();
+            dirs.sort_by_key(|e| e.name.to_lowercase());
+            files.sort_by_key(|e| e.name.to_lowercase());
+            dirs.extend(files);
+            Some(dirs)
+        };
+
+        PackTreeEntry {
+            name,
+            path,
+            kind: PackEntryKind::Folder,
+            size,
+            file_count,
+            children,
+        }
+    }
+}
+
+/// Collect every entry under `dir` of a zip pack
+fn collect_zip(pack_path: &Path, prefix: &str, builder: &mut DirBuilder) -> Result<bool> {
+    let file = File::open(pack_path).with_context(|| format!("Failed to open {:?}", pack_path))?;
+    let mut archive =
+        ZipArchive::new(file).with_context(|| format!("Failed to read zip {:?}", pack_path))?;
+
+    let mut found = false;
+    for i in 0..archive.len() {
+        let entry = archive
+            .by_index(i)
+            .map_err(|e| anyhow!("Failed to read zip entry {}: {}", i, e))?;
+        let name = entry.name().replace('\\', "/");
+        let rel = match name.strip_prefix(prefix) {
+            Some(rel) => rel,
+            None => continue,
+        };
+        found = true;
+        builder.insert(rel, entry.size(), entry.is_dir());
+    }
+    Ok(found)
+}
+
+/// Collect every entry under `dir` of a folder pack
+fn collect_folder(pack_path: &Path, dir: &Path, builder: &mut DirBuilder) -> Result<bool> {
+    let root = pack_path.join(dir);
+    if !root.is_dir() {
+        return Ok(false);
+    }
+
+    for entry in WalkDir::new(&root)
+        .min_depth(1)
+        .into_iter()
+        .filter_map(|e| e.ok())
+    {
+        let rel = match entry.path().strip_prefix(&root) {
+            Ok(rel) => rel.to_string_lossy().replace('\\', "/"),
+            Err(_) => continue,
+        };
+        let is_dir = entry.file_type().is_dir();
+        let size = if is_dir {
+            0
+        } else {
+            entry.metadata().map(|m| m.len()).unwrap_or(0)
+        };
+        builder.insert(&rel, size, is_dir);
+    }
+    Ok(true)
+}
+
+/// List a folder of a pack as a tree
+///
+/// # Arguments
+/// * `pack_path` - Zip file or folder of the pack
+/// * `is_zip` - Whether the pack is a zip
+/// * `dir` - '/'-separated folder inside the pack ("" for the root)
+/// * `depth` - Levels of children to load (1 = direct children only)
+///
+/// # Returns
+/// The folder with its children loaded `depth` levels deep
+///
+/// # Errors
+/// Fails if the pack can't be read, `dir` escapes the pack or doesn't exist
+pub fn browse_pack(
+    pack_path: &Path,
+    is_zip: bool,
+    dir: &str,
+    depth: usize,
+) -> Result<PackTreeEntry> {
+    let dir = dir.trim_matches(|c| c == '/' || c == '\\');
+    let dir_path = if dir.is_empty() {
+        None
+    } else {
+        Some(sanitize_entry_path(dir)?)
+    };
+    let dir = dir_path
+        .as_ref()
+        .map(|p| p.to_string_lossy().replace('\\', "/"))
+        .unwrap_or_default();
+
+    let mut builder = DirBuilder::default();
+    let found = if is_zip {
+        let prefix = if dir.is_empty() {
+            String::new()
+        } else {
+            format!("{}/", dir)
+        };
+        collect_zip(pack_path, &prefix, &mut builder)?
+    } else {
+        if !pack_path.is_dir() {
+            return Err(anyhow!("Pack folder not found: {:?}", pack_path));
+        }
+        collect_folder(
+            pack_path,
+            dir_path.as_deref().unwrap_or(Path::new("")),
+            &mut builder,
+        )?
+    };
+    if !found && !dir.is_empty() {
+        return Err(anyhow!("Folder not found in pack: {}", dir));
+    }
+
+    let name = dir.rsplit('/').next().unwrap_or("").to_string();
+    let depth = depth.clamp(1, MAX_BROWSE_DEPTH);
+    let tree = builder.into_entry(name, dir, depth);
+
+    debug!(
+        "Listed {:?} /{} ({} files)",
+        pack_path, tree.path, tree.file_count
+    );
+    Ok(tree)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use std::io::Write;
+
+    fn child<'a>(entry: &'a PackTreeEntry, name: &str) -> &'a PackTreeEntry {
+        entry
+            .children
+            .as_ref()
+            .and_then(|c| c.iter().find(|e| e.name == name))
+            .unwrap_or_else(|| panic!("missing child {}", name))
+    }
+
+    #[test]
+    fn test_entry_kinds() {
+        assert_eq!(
+            PackEntryKind::from_file_name("stone.PNG"),
+            PackEntryKind::Image
+        );
+        assert_eq!(
+            PackEntryKind::from_file_name("chest.jem"),
+            PackEntryKind::Json
+        );
+        assert_eq!(
+            PackEntryKind::from_file_name("pack.mcmeta"),
+            PackEntryKind::Mcmeta
+        );
+        assert_eq!(
+            PackEntryKind::from_file_name("LICENSE"),
+            PackEntryKind::Other
+        );
+    }
+
+    #[test]
+    fn test_browse_folder_pack_lazily() {
+        let temp_dir = std::env::temp_dir().join("test_pack_browser_folder");
+        let textures = temp_dir.join("assets/minecraft/textures/block");
+        fs::create_dir_all(&textures).expect("Failed to create test directory");
+        fs::create_dir_all(temp_dir.join("empty")).unwrap();
+        fs::write(temp_dir.join("pack.mcmeta"), b"{}").unwrap();
+        fs::write(textures.join("stone.png"), vec![0u8; 100]).unwrap();
+        fs::write(textures.join("dirt.png"), vec![0u8; 50]).unwrap();
+
+        let root = browse_pack(&temp_dir, false, "", 1);
+        let block = browse_pack(&temp_dir, false, "assets/minecraft/textures/block/", 1);
+        let escape = browse_pack(&temp_dir, false, "../outside", 1);
+        let missing = browse_pack(&temp_dir, false, "assets/other", 1);
+
+        // Clean up
+        fs::remove_dir_all(&temp_dir).ok();
+
+        let root = root.unwrap();
+        assert_eq!(root.file_count, 3);
+        assert_eq!(root.size, 152);
+        let names: Vec<&str> = root
+            .children
+            .as_ref()
+            .unwrap()
+            .iter()
+            .map(|e| e.name.as_str())
+            .collect();
+        assert_eq!(names, vec!["assets", "empty", "pack.mcmeta"]);
+        let assets = child(&root, "assets");
+        assert_eq!(assets.kind, PackEntryKind::Folder);
+        assert_eq!(assets.file_count, 2);
+        assert!(assets.children.is_none());
+
+        let block = block.unwrap();
+        assert_eq!(block.name, "block");
+        assert_eq!(block.path, "assets/minecraft/textures/block");
+        let stone = child(&block, "stone.png");
+        assert_eq!(stone.path, "assets/minecraft/textures/block/stone.png");
+        assert_eq!(stone.kind, PackEntryKind::Image);
+        assert_eq!(stone.size, 100);
+
+        assert!(escape.is_err());
+        assert!(missing.is_err());
+    }
+
+    #[test]
+    fn test_browse_zip_pack_with_depth() {
+        let temp_dir = std::env::temp_dir().join("test_pack_browser_zip");
+        fs::create_dir_all(&temp_dir).expect("Failed to create test directory");
+        let zip_path = temp_dir.join("pack.zip");
+        {
+            let mut writer = zip::ZipWriter::new(File::create(&zip_path).unwrap());
+            let options = zip::write::FileOptions::default();
+            writer.start_file("pack.mcmeta", options).unwrap();
+            writer.write_all(b"{}").unwrap();
+            writer
+                .start_file("assets/minecraft/models/block/stone.json", options)
+                .unwrap();
+            writer
+                .write_all(b"{\"parent\":\"block/cube_all\"}")
+                .unwrap();
+            writer.finish().unwrap();
+        }
+
+        let tree = browse_pack(&zip_path, true, "", 2);
+        let models = browse_pack(&zip_path, true, "assets/minecraft/models", 3);
+
+        // Clean up
+        fs::remove_dir_all(&temp_dir).ok();
+
+        let tree = tree.unwrap();
+        let minecraft = child(child(&tree, "assets"), "minecraft");
+        assert!(minecraft.children.is_none());
+        assert_eq!(minecraft.file_count, 1);
+
+        let models = models.unwrap();
+        let stone = child(child(&models, "block"), "stone.json");
+        assert_eq!(stone.kind, PackEntryKind::Json);
+        assert_eq!(stone.size, 27);
+    }
+}
@@ -0,0 +1,311 @@
+/// Merged pack output: metadata and folder or ZIP targets
+///
+/// The Weaver Nest builder decides which pack wins each asset and copies the
+/// winning files. This module wraps it for a one-off merge: the pack.mcmeta
+/// and pack.png of the result are derived from the merged packs rather than
+/// fixed, and the merge can be written as a ZIP ready to drop into
+/// resourcepacks or to host on a server. ZIP output is assembled in a
+/// staging folder next to the target and compressed in one go.
+use crate::model::{AssetRecord, OverrideSelection, PackMeta};
+use crate::util::progress::FileProgress;
+use crate::util::server_export::{self, ServerExport, ServerExportOptions};
+use crate::util::{animation, build_journal, file_retry, instance_lock, pack_archive, weaver_nest};
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::Path;
+use tracing::info;
+
+/// pack_format written when none of the merged packs declares one
+pub const DEFAULT_PACK_FORMAT: u32 = 48;
+
+/// Description of a merge without packs
+const DEFAULT_DESCRIPTION: &str = "Weaverbird - Optimized Resource Pack";
+
+/// Pack names spelled out in the description before "and N more"
+const MAX_DESCRIBED_PACKS: usize = 3;
+
+/// Shape of the merged pack
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum OutputFormat {
+    Folder,
+    Zip,
+    /// Reproducible ZIP with its SHA-1 and server.properties lines
+    ServerExport,
+}
+
+impl Default for OutputFormat {
+    fn default() -> Self {
+        OutputFormat::Folder
+    }
+}
+
+/// Result of a merge
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MergeSummary {
+    /// Folder or ZIP written
+    pub output_path: String,
+    pub format: OutputFormat,
+    /// Files in the merged pack, pack.mcmeta and pack.png included
+    pub files: usize,
+    /// Hash and server.properties lines of a server export
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub server: Option<ServerExport>,
+}
+
+/// Packs taking part in a merge, highest priority first
+fn merged_packs<'a>(packs: &'a [PackMeta], pack_order: &[String]) -> Vec<&'a PackMeta> {
+    pack_order
+        .iter()
+        .filter_map(|id| packs.iter().find(|p| &p.id == id))
+        .collect()
+}
+
+/// pack.mcmeta for a merge
+///
+/// The pack_format is the newest any merged pack declares, so the game
+/// doesn't flag the result as outdated when the packs are current. The
+/// description names the merged packs.
+pub fn merged_pack_mcmeta(merged: &[&PackMeta]) -> String {
+    let pack_format = merged
+        .iter()
+        .filter_map(|p| p.pack_format)
+        .max()
+        .unwrap_or(DEFAULT_PACK_FORMAT);
+    let names: Vec<&str> = merged.iter().map(|p| p.name.as_str()).collect();
+    let description = match names.len() {
+        0 => DEFAULT_DESCRIPTION.to_string(),
+        n if n <= MAX_DESCRIBED_PACKS => format!("Weaverbird merge of {}", names.join(", ")),
+        n => format!(
+            "Weaverbird merge of {} and {} more",
+            names[..MAX_DESCRIBED_PACKS].join(", "),
+            n - MAX_DESCRIBED_PACKS
+        ),
+    };
+
+    let mcmeta = serde_json::json!({
+        "pack": {
+            "pack_format": pack_format,
+            "description": description,
+        }
+    });
+    format!(
+        "{}\n",
+        serde_json::to_string_pretty(&mcmeta).unwrap_or_default()
+    )
+}
+
+/// pack.png of the highest-priority merged pack that has one
+pub fn merged_pack_icon(merged: &[&PackMeta]) -> Option<Vec<u8>> {
+    merged
+        .iter()
+        .find_map(|p| animation::read_pack_bytes(&p.path, p.is_zip, "pack.png").ok())
+}
+
+/// Write pack.mcmeta and, if a merged pack has one, pack.png
+///
+/// # Arguments
+/// * `output_path` - Root of the merged pack
+/// * `packs` - Scanned packs
+/// * `pack_order` - Merged pack IDs, highest priority first
+pub fn write_pack_metadata(
+    output_path: &Path,
+    packs: &[PackMeta],
+    pack_order: &[String],
+) -> Result<()> {
+    let merged = merged_packs(packs, pack_order);
+    file_retry::write_with_retry(
+        &output_path.join("pack.mcmeta"),
+        merged_pack_mcmeta(&merged).as_bytes(),
+    )?;
+    if let Some(icon) = merged_pack_icon(&merged) {
+        file_retry::write_with_retry(&output_path.join("pack.png"), &icon)?;
+    }
+    Ok(())
+}
+
+/// Merge packs into a folder or ZIP
+///
+/// Each asset comes from its override if one is set, otherwise from the
+/// first pack in `pack_order` providing it.
+///
+/// # Arguments
+/// * `output` - Folder to write into, or the ZIP file to create
+/// * `format` - Whether `output` is a folder, a ZIP or a ZIP for a server
+/// * `server` - What a server export offers players (defaults if None)
+/// * `progress` - Called with (files copied, total, file) while copying
+///
+/// # Returns
+/// What was written
+#[allow(clippy::too_many_arguments)]
+pub fn build_pack(
+    packs: &[PackMeta],
+    assets: &[AssetRecord],
+    providers: &HashMap<String, Vec<String>>,
+    pack_order: &[String],
+    overrides: &HashMap<String, OverrideSelection>,
+    output: &Path,
+    format: OutputFormat,
+    server: Option<&ServerExportOptions>,
+    progress: Option<FileProgress>,
+) -> Result<MergeSummary> {
+    let work_dir = match format {
+        OutputFormat::Folder => output.to_path_buf(),
+        OutputFormat::Zip | OutputFormat::ServerExport => build_journal::staging_dir_for(output)?,
+    };
+    let _lock = instance_lock::InstanceLock::acquire(
+        &work_dir,
+        &format!("merging packs into {}", output.display()),
+    )?;
+    if format != OutputFormat::Folder && work_dir.exists() {
+        file_retry::remove_dir_all_with_retry(&work_dir)?;
+    }
+
+    weaver_nest::build_weaver_nest_journaled(
+        packs,
+        assets,
+        providers,
+        pack_order,
+        overrides,
+        &work_dir.to_string_lossy(),
+        None,
+        false,
+        progress,
+        None,
+    )?;
+
+    let (files, server) = match format {
+        OutputFormat::Folder => (pack_archive::list_files(&work_dir).len(), None),
+        OutputFormat::Zip => {
+            let compressed = pack_archive::compress_pack_folder(&work_dir, output, None);
+            file_retry::remove_dir_all_with_retry(&work_dir).ok();
+            (compressed?, None)
+        }
+        OutputFormat::ServerExport => {
+            let options = server.cloned().unwrap_or_default();
+            let export = server_export::export_for_server(&work_dir, output, &options);
+            file_retry::remove_dir_all_with_retry(&work_dir).ok();
+            let export = export?;
+            (export.files, Some(export))
+        }
+    };
+
+    info!(
+        "Merged {} pack(s) into {} ({} file(s))",
+        pack_order.len(),
+        output.display(),
+        files
+    );
+    Ok(MergeSummary {
+        output_path: output.to_string_lossy().to_string(),
+        format,
+        files,
+        server,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::util::asset_indexer;
+    use crate::util::test_support::folder_pack;
+    use std::fs;
+
+    fn pack(root: &Path, id: &str, pack_format: Option<u32>, files: &[(&str, &str)]) -> PackMeta {
+        PackMeta {
+            pack_format,
+            ..folder_pack(root, id, files)
+        }
+    }
+
+    #[test]
+    fn test_merged_pack_mcmeta() {
+        let temp_dir = std::env::temp_dir().join("test_pack_builder_mcmeta");
+        let packs: Vec<PackMeta> = ["a", "b", "c", "d"]
+            .iter()
+            .zip([Some(34), None, Some(46), None])
+            .map(|(id, format)| pack(&temp_dir, id, format, &[]))
+            .collect();
+        let merged: Vec<&PackMeta> = packs.iter().collect();
+
+        let mcmeta: serde_json::Value = serde_json::from_str(&merged_pack_mcmeta(&merged)).unwrap();
+        let empty: serde_json::Value = serde_json::from_str(&merged_pack_mcmeta(&[])).unwrap();
+
+        // Clean up
+        fs::remove_dir_all(&temp_dir).ok();
+
+        assert_eq!(mcmeta["pack"]["pack_format"], 46);
+        assert_eq!(
+            mcmeta["pack"]["description"],
+            "Weaverbird merge of a, b, c and 1 more"
+        );
+        assert_eq!(empty["pack"]["pack_format"], DEFAULT_PACK_FORMAT);
+    }
+
+    #[test]
+    fn test_build_pack_zip_respects_overrides() {
+        let temp_dir = std::env::temp_dir().join("test_pack_builder_zip");
+        fs::remove_dir_all(&temp_dir).ok();
+        let stone = "assets/minecraft/textures/block/stone.png";
+        let dirt = "assets/minecraft/textures/block/dirt.png";
+        let packs = vec![
+            pack(
+                &temp_dir,
+                "high",
+                None,
+                &[(stone, "high stone"), (dirt, "high dirt")],
+            ),
+            pack(
+                &temp_dir,
+                "low",
+                None,
+                &[(stone, "low stone"), ("pack.png", "icon")],
+            ),
+        ];
+        let (assets, providers) = asset_indexer::index_assets(&packs).unwrap();
+        let overrides = HashMap::from([(
+            "minecraft:block/stone".to_string(),
+            OverrideSelection {
+                pack_id: "low".to_string(),
+                variant_path: None,
+            },
+        )]);
+        let calls = std::sync::Mutex::new(Vec::new());
+        let progress =
+            |done: usize, total: usize, _: Option<&str>| calls.lock().unwrap().push((done, total));
+
+        let zip_path = temp_dir.join("merged.zip");
+        let summary = build_pack(
+            &packs,
+            &assets,
+            &providers,
+            &["high".to_string(), "low".to_string()],
+            &overrides,
+            &zip_path,
+            OutputFormat::Zip,
+            None,
+            Some(&progress),
+        );
+        let zip_str = zip_path.to_string_lossy().to_string();
+        let read = |path: &str| {
+            crate::util::zip::extract_zip_entry(&zip_str, path)
+                .ok()
+                .map(|b| String::from_utf8_lossy(&b).to_string())
+        };
+        let (stone_out, dirt_out, icon) = (read(stone), read(dirt), read("pack.png"));
+        let staging_left = build_journal::staging_dir_for(&zip_path).unwrap().exists();
+
+        // Clean up
+        fs::remove_dir_all(&temp_dir).ok();
+
+        let summary = summary.expect("merge should succeed");
+        assert_eq!(summary.files, 4);
+        assert_eq!(stone_out.as_deref(), Some("low stone"));
+        assert_eq!(dirt_out.as_deref(), Some("high dirt"));
+        assert_eq!(icon.as_deref(), Some("icon"));
+        assert!(!staging_left);
+        assert_eq!(calls.into_inner().unwrap().last(), Some(&(2, 2)));
+    }
+}
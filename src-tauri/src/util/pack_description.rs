@@ -0,0 +1,198 @@
+/// Templated descriptions for built packs
+///
+/// The description in a merged pack's pack.mcmeta is what players see in
+/// the pack list, so packs shared with a server or friends read better with
+/// something consistent like "Survival textures - 12 packs, built
+/// 2024-05-01". A template fills in variables in braces and turns `&` color
+/// codes into the `§` codes the game renders:
+///
+/// - `{project_name}`, `{date}` (YYYY-MM-DD), `{pack_count}`, `{packs}`
+///   (names joined by commas) and `{top_pack}` (highest priority)
+/// - `&a`, `&l`, `&r`... for colors and formatting, `&&` for a plain `&`
+/// - `{{` and `}}` for plain braces
+use crate::util::file_retry;
+use anyhow::{anyhow, bail, Context, Result};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::Path;
+
+/// Variables a template may use
+pub const TEMPLATE_VARIABLES: &[&str] =
+    &["project_name", "date", "pack_count", "packs", "top_pack"];
+
+/// Characters that may follow `&` to form a formatting code
+const FORMAT_CODES: &str = "0123456789abcdefklmnor";
+
+/// What a template is rendered with
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DescriptionContext {
+    /// Empty outside projects
+    pub project_name: String,
+    /// Names of the merged packs, highest priority first
+    pub pack_names: Vec<String>,
+    /// Unix seconds of the build
+    pub built_at: u64,
+}
+
+/// Date of a Unix timestamp as YYYY-MM-DD (UTC)
+pub fn format_date(unix_secs: u64) -> String {
+    // Days to civil date (Howard Hinnant's algorithm)
+    let days = (unix_secs / 86_400) as i64 + 719_468;
+    let era = days.div_euclid(146_097);
+    let day_of_era = days.rem_euclid(146_097);
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month_index = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * month_index + 2) / 5 + 1;
+    let month = if month_index < 10 {
+        month_index + 3
+    } else {
+        month_index - 9
+    };
+    let year = year_of_era + era * 400 + if month <= 2 { 1 } else { 0 };
+    format!("{:04}-{:02}-{:02}", year, month, day)
+}
+
+fn variable_value(name: &str, context: &DescriptionContext) -> Option<String> {
+    Some(match name {
+        "project_name" => context.project_name.clone(),
+        "date" => format_date(context.built_at),
+        "pack_count" => context.pack_names.len().to_string(),
+        "packs" => context.pack_names.join(", "),
+        "top_pack" => context.pack_names.first().cloned().unwrap_or_default(),
+        _ => return None,
+    })
+}
+
+/// Render a description template
+///
+/// # Errors
+/// Unknown variables and unclosed braces
+pub fn render_description(template: &str, context: &DescriptionContext) -> Result<String> {
+    let mut rendered = String::with_capacity(template.len());
+    let mut chars = template.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '{' if chars.peek() == Some(&'{') => {
+                chars.next();
+                rendered.push('{');
+            }
+            '}' if chars.peek() == Some(&'}') => {
+                chars.next();
+                rendered.push('}');
+            }
+            '{' => {
+                let mut name = String::new();
+                loop {
+                    match chars.next() {
+                        Some('}') => break,
+                        Some(c) => name.push(c),
+                        None => bail!("Unclosed {{ in description template"),
+                    }
+                }
+                let value = variable_value(name.trim(), context).ok_or_else(|| {
+                    anyhow!(
+                        "Unknown variable {{{}}} (available: {})",
+                        name,
+                        TEMPLATE_VARIABLES.join(", ")
+                    )
+                })?;
+                rendered.push_str(&value);
+            }
+            '&' => match chars.peek().copied() {
+                Some('&') => {
+                    chars.next();
+                    rendered.push('&');
+                }
+                Some(code) if FORMAT_CODES.contains(code.to_ascii_lowercase()) => {
+                    chars.next();
+                    rendered.push('§');
+                    rendered.push(code.to_ascii_lowercase());
+                }
+                _ => rendered.push('&'),
+            },
+            c => rendered.push(c),
+        }
+    }
+
+    Ok(rendered)
+}
+
+/// Check a template for unknown variables and unclosed braces
+pub fn validate_template(template: &str) -> Result<()> {
+    render_description(template, &DescriptionContext::default()).map(|_| ())
+}
+
+/// Replace the description in a pack's pack.mcmeta, keeping its other fields
+pub fn apply_description(pack_root: &Path, description: &str) -> Result<()> {
+    let mcmeta_path = pack_root.join("pack.mcmeta");
+    let contents = fs::read_to_string(&mcmeta_path)
+        .with_context(|| format!("Failed to read {:?}", mcmeta_path))?;
+    let mut mcmeta: serde_json::Value =
+        serde_json::from_str(&contents).context("Failed to parse pack.mcmeta")?;
+    let pack = mcmeta
+        .get_mut("pack")
+        .and_then(|pack| pack.as_object_mut())
+        .ok_or_else(|| anyhow!("pack.mcmeta has no pack section"))?;
+    pack.insert("description".to_string(), description.into());
+
+    let json = format!("{}\n", serde_json::to_string_pretty(&mcmeta)?);
+    file_retry::write_with_retry(&mcmeta_path, json.as_bytes())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render_description() {
+        let context = DescriptionContext {
+            project_name: "Survival".to_string(),
+            pack_names: vec!["Faithful".to_string(), "Fresh Animations".to_string()],
+            // 2024-05-01 12:00 UTC
+            built_at: 1_714_564_800,
+        };
+
+        let rendered = render_description(
+            "&6{project_name}&r - {pack_count} packs ({top_pack} first), {date} {{v2}} R&&D",
+            &context,
+        );
+        let unknown = render_description("{author}", &context);
+        let unclosed = render_description("{date", &context);
+
+        assert_eq!(
+            rendered.unwrap(),
+            "§6Survival§r - 2 packs (Faithful first), 2024-05-01 {v2} R&D"
+        );
+        assert!(unknown.unwrap_err().to_string().contains("author"));
+        assert!(unclosed.is_err());
+        assert_eq!(format_date(0), "1970-01-01");
+        assert_eq!(format_date(951_782_400), "2000-02-29");
+    }
+
+    #[test]
+    fn test_apply_description() {
+        let temp_dir = std::env::temp_dir().join("test_apply_description");
+        fs::remove_dir_all(&temp_dir).ok();
+        fs::create_dir_all(&temp_dir).unwrap();
+        fs::write(
+            temp_dir.join("pack.mcmeta"),
+            r#"{"pack": {"pack_format": 34, "description": "old"}}"#,
+        )
+        .unwrap();
+
+        apply_description(&temp_dir, "§aNew").unwrap();
+        let mcmeta: serde_json::Value =
+            serde_json::from_str(&fs::read_to_string(temp_dir.join("pack.mcmeta")).unwrap())
+                .unwrap();
+
+        // Clean up
+        fs::remove_dir_all(&temp_dir).ok();
+
+        assert_eq!(mcmeta["pack"]["description"], "§aNew");
+        assert_eq!(mcmeta["pack"]["pack_format"], 34);
+    }
+}
@@ -0,0 +1,79 @@
+//! Resource pack formats and the Minecraft versions they belong to
+//!
+//! pack.mcmeta only records a number; this maps it to the releases that
+//! use it so the UI can show "1.20 - 1.20.1" instead of "15", and so a
+//! merge of packs made for different versions can be flagged.
+
+/// Minecraft releases using each resource pack format (first, last)
+const PACK_FORMAT_VERSIONS: &[(u32, &str, &str)] = &[
+    (1, "1.6.1", "1.8.9"),
+    (2, "1.9", "1.10.2"),
+    (3, "1.11", "1.12.2"),
+    (4, "1.13", "1.14.4"),
+    (5, "1.15", "1.16.1"),
+    (6, "1.16.2", "1.16.5"),
+    (7, "1.17", "1.17.1"),
+    (8, "1.18", "1.18.2"),
+    (9, "1.19", "1.19.2"),
+    (12, "1.19.3", "1.19.3"),
+    (13, "1.19.4", "1.19.4"),
+    (15, "1.20", "1.20.1"),
+    (18, "1.20.2", "1.20.2"),
+    (22, "1.20.3", "1.20.4"),
+    (32, "1.20.5", "1.20.6"),
+    (34, "1.21", "1.21.1"),
+    (42, "1.21.2", "1.21.3"),
+    (46, "1.21.4", "1.21.4"),
+    (55, "1.21.5", "1.21.5"),
+    (63, "1.21.6", "1.21.6"),
+    (64, "1.21.7", "1.21.8"),
+];
+
+/// Minecraft versions a pack format is meant for
+///
+/// - 15 -> Some("1.20 - 1.20.1")
+/// - 46 -> Some("1.21.4")
+/// - 100 -> Some("newer than 1.21.8")
+///
+/// # Returns
+/// None for formats only snapshots used
+pub fn compatible_mc_versions(pack_format: u32) -> Option<String> {
+    if let Some((_, first, last)) = PACK_FORMAT_VERSIONS
+        .iter()
+        .find(|(format, _, _)| *format == pack_format)
+    {
+        return Some(if first == last {
+            first.to_string()
+        } else {
+            format!("{} - {}", first, last)
+        });
+    }
+
+    let (newest, _, newest_version) = PACK_FORMAT_VERSIONS[PACK_FORMAT_VERSIONS.len() - 1];
+    if pack_format > newest {
+        return Some(format!("newer than {}", newest_version));
+    }
+    None
+}
+
+/// Pack format of the newest release the table knows
+pub fn latest_release_format() -> u32 {
+    PACK_FORMAT_VERSIONS[PACK_FORMAT_VERSIONS.len() - 1].0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_compatible_mc_versions() {
+        assert_eq!(compatible_mc_versions(15).as_deref(), Some("1.20 - 1.20.1"));
+        assert_eq!(compatible_mc_versions(46).as_deref(), Some("1.21.4"));
+        assert_eq!(
+            compatible_mc_versions(100).as_deref(),
+            Some("newer than 1.21.8")
+        );
+        // Snapshot-only format
+        assert_eq!(compatible_mc_versions(16), None);
+    }
+}
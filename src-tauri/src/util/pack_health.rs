@@ -0,0 +1,373 @@
+/// Pack health checks
+///
+/// Flags block and item textures that commonly break mipmapping on the
+/// texture atlas: sizes that aren't powers of two, and animations whose
+/// frames aren't square. Both show up in game as blurry, flickering or
+/// misaligned textures at a distance. Block textures are also checked for
+/// stray semi-transparent pixels, which let you see through solid blocks.
+use crate::model::PackMeta;
+use crate::util::animation::{self, read_pack_bytes};
+use crate::util::{asset_indexer, image_probe, pack_scanner, parallelism};
+use anyhow::Result;
+use rayon::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+/// Texture folders stitched into mipmapped atlases
+const ATLAS_TEXTURE_DIRS: &[&str] = &["/textures/block/", "/textures/item/"];
+
+/// Block textures that are meant to be translucent
+const TRANSLUCENT_BLOCK_NAMES: &[&str] = &[
+    "glass", "water", "ice", "slime", "honey", "portal", "bubble", "beacon",
+];
+
+/// Kind of mipmap problem
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum MipmapIssueKind {
+    /// Width or height (of the texture or its frames) isn't a power of two
+    NonPowerOfTwo,
+    /// Animation frames aren't square
+    NonSquareFrames,
+}
+
+/// A texture likely to render badly with mipmaps
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MipmapIssue {
+    pub kind: MipmapIssueKind,
+    /// Texture path relative to the pack root
+    pub file: String,
+    pub width: u32,
+    pub height: u32,
+    /// Frame size for animated textures
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub frame_size: Option<(u32, u32)>,
+    pub message: String,
+}
+
+/// A solid block texture with semi-transparent pixels
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AlphaIssue {
+    /// Texture path relative to the pack root
+    pub file: String,
+    /// Asset ID (e.g., "minecraft:block/stone")
+    pub asset_id: String,
+    /// Pixels with alpha strictly between 0 and 255
+    pub semi_transparent_pixels: u32,
+    pub total_pixels: u32,
+    pub message: String,
+}
+
+/// Health report for one pack
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PackHealthReport {
+    pub pack_id: String,
+    pub pack_name: String,
+    pub textures_checked: usize,
+    pub warnings: Vec<MipmapIssue>,
+    pub alpha_warnings: Vec<AlphaIssue>,
+}
+
+/// Whether a texture path is stitched into a mipmapped atlas
+pub fn is_atlas_texture(path: &str) -> bool {
+    path.starts_with("assets/")
+        && path.ends_with(".png")
+        && ATLAS_TEXTURE_DIRS.iter().any(|dir| path.contains(dir))
+}
+
+/// Whether a block texture is expected to be translucent (glass, water, ...)
+pub fn is_translucent_texture(path: &str) -> bool {
+    let name = path.rsplit('/').next().unwrap_or(path).to_lowercase();
+    TRANSLUCENT_BLOCK_NAMES.iter().any(|t| name.contains(t))
+}
+
+/// Check a block texture for stray semi-transparent pixels
+///
+/// Fully transparent pixels are fine (cutout textures like leaves use them);
+/// partial alpha on a solid block shows the world behind it.
+pub fn check_texture_alpha(file: &str, image: &image::RgbaImage) -> Option<AlphaIssue> {
+    if !file.contains("/textures/block/") || is_translucent_texture(file) {
+        return None;
+    }
+
+    let semi_transparent_pixels = image.pixels().filter(|p| p[3] > 0 && p[3] < 255).count() as u32;
+    if semi_transparent_pixels == 0 {
+        return None;
+    }
+
+    Some(AlphaIssue {
+        file: file.to_string(),
+        asset_id: asset_indexer::extract_asset_id(file).unwrap_or_else(|| file.to_string()),
+        semi_transparent_pixels,
+        total_pixels: image.width() * image.height(),
+        message: format!(
+            "{}: {} semi-transparent pixel(s) on a solid block texture",
+            file, semi_transparent_pixels
+        ),
+    })
+}
+
+/// Check a texture's dimensions for mipmap problems
+///
+/// # Arguments
+/// * `file` - Texture path, used in messages
+/// * `width`, `height` - Image size in pixels
+/// * `mcmeta` - Contents of the texture's .mcmeta, if any
+pub fn check_texture_dimensions(
+    file: &str,
+    width: u32,
+    height: u32,
+    mcmeta: Option<&str>,
+) -> Vec<MipmapIssue> {
+    let animation = mcmeta.and_then(|json| {
+        animation::parse_animation(json, width, height)
+            .ok()
+            .flatten()
+    });
+    let frame_size = animation.map(|info| (info.frame_width, info.frame_height));
+    let (check_width, check_height) = frame_size.unwrap_or((width, height));
+    let mut issues = Vec::new();
+
+    if let Some((frame_width, frame_height)) = frame_size {
+        if frame_width != frame_height {
+            issues.push(MipmapIssue {
+                kind: MipmapIssueKind::NonSquareFrames,
+                file: file.to_string(),
+                width,
+                height,
+                frame_size,
+                message: format!(
+                    "{}: animation frames are {}x{}, not square",
+                    file, frame_width, frame_height
+                ),
+            });
+        }
+    }
+
+    if !check_width.is_power_of_two() || !check_height.is_power_of_two() {
+        let what = if frame_size.is_some() {
+            "frame size"
+        } else {
+            "size"
+        };
+        issues.push(MipmapIssue {
+            kind: MipmapIssueKind::NonPowerOfTwo,
+            file: file.to_string(),
+            width,
+            height,
+            frame_size,
+            message: format!(
+                "{}: {} {}x{} is not a power of two",
+                file, what, check_width, check_height
+            ),
+        });
+    }
+
+    issues
+}
+
+/// Issues found in a set of atlas textures
+#[derive(Default)]
+struct TextureFindings {
+    textures_checked: usize,
+    mipmap: Vec<MipmapIssue>,
+    alpha: Vec<AlphaIssue>,
+}
+
+/// Check a pack's atlas textures
+///
+/// Only image headers are read unless `check_alpha` is set, in which case
+/// block textures are fully decoded for the alpha check.
+fn analyze_textures(
+    pack_path: &str,
+    is_zip: bool,
+    files: &[String],
+    check_alpha: bool,
+) -> TextureFindings {
+    let textures: Vec<&String> = files.iter().filter(|f| is_atlas_texture(f)).collect();
+
+    let analyze = || {
+        textures
+            .par_iter()
+            .filter_map(|file| {
+                let decode_alpha = check_alpha && file.contains("/textures/block/");
+                let (width, height, alpha) = if decode_alpha {
+                    let bytes = read_pack_bytes(pack_path, is_zip, file).ok()?;
+                    let image = image::load_from_memory(&bytes).ok()?.to_rgba8();
+                    let alpha = check_texture_alpha(file, &image);
+                    (image.width(), image.height(), alpha)
+                } else {
+                    let (width, height) =
+                        image_probe::pack_file_dimensions(pack_path, is_zip, file).ok()?;
+                    (width, height, None)
+                };
+                let mcmeta = read_pack_bytes(pack_path, is_zip, &format!("{}.mcmeta", file))
+                    .ok()
+                    .map(|bytes| String::from_utf8_lossy(&bytes).to_string());
+
+                Some((
+                    check_texture_dimensions(file, width, height, mcmeta.as_deref()),
+                    alpha,
+                ))
+            })
+            .collect::<Vec<(Vec<MipmapIssue>, Option<AlphaIssue>)>>()
+    };
+    // Decoding for the alpha check is CPU-bound; reading headers isn't
+    let results = if check_alpha {
+        parallelism::with_cpu_pool(analyze)
+    } else {
+        parallelism::with_io_pool(analyze)
+    };
+
+    let mut findings = TextureFindings {
+        textures_checked: textures.len(),
+        ..Default::default()
+    };
+    for (mipmap, alpha) in results {
+        findings.mipmap.extend(mipmap);
+        findings.alpha.extend(alpha);
+    }
+    findings
+}
+
+/// Check a pack for health problems
+pub fn check_pack_health(pack: &PackMeta) -> Result<PackHealthReport> {
+    let files = pack_scanner::list_pack_files(pack)?;
+    let findings = analyze_textures(&pack.path, pack.is_zip, &files, true);
+
+    Ok(PackHealthReport {
+        pack_id: pack.id.clone(),
+        pack_name: pack.name.clone(),
+        textures_checked: findings.textures_checked,
+        warnings: findings.mipmap,
+        alpha_warnings: findings.alpha,
+    })
+}
+
+/// Find mipmap issues in a built (unzipped) output pack
+pub fn check_output_mipmaps(output_dir: &Path) -> Result<Vec<MipmapIssue>> {
+    let files = pack_scanner::list_folder_files(output_dir);
+    Ok(analyze_textures(&output_dir.to_string_lossy(), false, &files, false).mipmap)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    #[test]
+    fn test_check_texture_dimensions() {
+        assert!(check_texture_dimensions("a.png", 16, 16, None).is_empty());
+        assert!(check_texture_dimensions("a.png", 16, 64, Some(r#"{"animation": {}}"#)).is_empty());
+
+        let npot = check_texture_dimensions("a.png", 24, 24, None);
+        assert_eq!(npot.len(), 1);
+        assert_eq!(npot[0].kind, MipmapIssueKind::NonPowerOfTwo);
+
+        let non_square =
+            check_texture_dimensions("a.png", 16, 64, Some(r#"{"animation": {"height": 8}}"#));
+        assert_eq!(non_square.len(), 1);
+        assert_eq!(non_square[0].kind, MipmapIssueKind::NonSquareFrames);
+        assert_eq!(non_square[0].frame_size, Some((16, 8)));
+
+        // 20x60 strip: three 20x20 frames, square but not a power of two
+        let npot_frames = check_texture_dimensions("a.png", 20, 60, Some(r#"{"animation": {}}"#));
+        assert_eq!(npot_frames.len(), 1);
+        assert_eq!(npot_frames[0].kind, MipmapIssueKind::NonPowerOfTwo);
+    }
+
+    #[test]
+    fn test_is_atlas_texture() {
+        assert!(is_atlas_texture(
+            "assets/minecraft/textures/block/stone.png"
+        ));
+        assert!(is_atlas_texture("assets/minecraft/textures/item/stick.png"));
+        assert!(!is_atlas_texture(
+            "assets/minecraft/textures/entity/cow/cow.png"
+        ));
+        assert!(!is_atlas_texture(
+            "assets/minecraft/textures/block/stone.png.mcmeta"
+        ));
+    }
+
+    #[test]
+    fn test_check_texture_alpha() {
+        let mut image = image::RgbaImage::from_pixel(4, 4, image::Rgba([90, 90, 90, 255]));
+        image.put_pixel(0, 0, image::Rgba([0, 0, 0, 0]));
+        let cutout = check_texture_alpha("assets/minecraft/textures/block/oak_leaves.png", &image);
+
+        image.put_pixel(1, 1, image::Rgba([90, 90, 90, 200]));
+        image.put_pixel(2, 1, image::Rgba([90, 90, 90, 1]));
+        let stray = check_texture_alpha("assets/minecraft/textures/block/stone.png", &image);
+        let glass = check_texture_alpha("assets/minecraft/textures/block/glass.png", &image);
+        let item = check_texture_alpha("assets/minecraft/textures/item/stick.png", &image);
+
+        assert!(cutout.is_none());
+        let stray = stray.expect("stone should be flagged");
+        assert_eq!(stray.asset_id, "minecraft:block/stone");
+        assert_eq!(stray.semi_transparent_pixels, 2);
+        assert_eq!(stray.total_pixels, 16);
+        assert!(glass.is_none());
+        assert!(item.is_none());
+    }
+
+    #[test]
+    fn test_check_pack_health() {
+        let temp_dir = std::env::temp_dir().join("test_pack_health_report");
+        let blocks = temp_dir.join("assets/minecraft/textures/block");
+        fs::create_dir_all(&blocks).expect("Failed to create test directory");
+        image::RgbaImage::from_pixel(16, 16, image::Rgba([1, 2, 3, 128]))
+            .save(blocks.join("dirt.png"))
+            .expect("Failed to write image");
+        image::RgbaImage::new(24, 24)
+            .save(blocks.join("stone.png"))
+            .expect("Failed to write image");
+        let pack = PackMeta {
+            id: "test_pack".to_string(),
+            name: "Test Pack".to_string(),
+            path: temp_dir.to_string_lossy().to_string(),
+            ..Default::default()
+        };
+
+        let report = check_pack_health(&pack);
+
+        // Clean up
+        fs::remove_dir_all(&temp_dir).ok();
+
+        let report = report.expect("check should succeed");
+        assert_eq!(report.textures_checked, 2);
+        assert_eq!(report.warnings.len(), 1);
+        assert_eq!(report.alpha_warnings.len(), 1);
+        assert_eq!(report.alpha_warnings[0].asset_id, "minecraft:block/dirt");
+    }
+
+    #[test]
+    fn test_check_output_mipmaps() {
+        let temp_dir = std::env::temp_dir().join("test_pack_health");
+        let blocks = temp_dir.join("assets/minecraft/textures/block");
+        let entities = temp_dir.join("assets/minecraft/textures/entity");
+        fs::create_dir_all(&blocks).expect("Failed to create test directory");
+        fs::create_dir_all(&entities).expect("Failed to create test directory");
+        image::RgbaImage::new(16, 16)
+            .save(blocks.join("good.png"))
+            .expect("Failed to write image");
+        image::RgbaImage::new(24, 24)
+            .save(blocks.join("bad.png"))
+            .expect("Failed to write image");
+        image::RgbaImage::new(24, 24)
+            .save(entities.join("ignored.png"))
+            .expect("Failed to write image");
+
+        let issues = check_output_mipmaps(&temp_dir);
+
+        // Clean up
+        fs::remove_dir_all(&temp_dir).ok();
+
+        let issues = issues.expect("check should succeed");
+        assert_eq!(issues.len(), 1);
+        assert_eq!(issues[0].file, "assets/minecraft/textures/block/bad.png");
+    }
+}
@@ -0,0 +1,495 @@
+/// Upgrade a resource pack to a newer pack format
+///
+/// Minecraft has renamed many textures over the years, most of them at
+/// once in 1.13 ("the flattening": blocks/planks_oak.png became
+/// block/oak_planks.png). A pack made for an older version merged with
+/// modern packs loses those textures. The migrator writes a copy of the
+/// pack with moved textures renamed and pack.mcmeta updated, and reports
+/// the files it couldn't map: textures with no modern counterpart, and
+/// models and blockstates that still name old textures.
+use crate::model::PackMeta;
+use crate::util::animation::read_pack_bytes;
+use crate::util::{pack_scanner, zip};
+use anyhow::{anyhow, Context, Result};
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+use tracing::info;
+
+/// Folder of vanilla textures inside a pack
+const TEXTURE_ROOT: &str = "assets/minecraft/textures/";
+
+/// First pack format after the flattening (1.13)
+const FLATTENING_FORMAT: u32 = 4;
+
+/// Pre-1.13 color names and their 1.13 counterparts
+const LEGACY_COLORS: &[(&str, &str)] = &[
+    ("white", "white"),
+    ("orange", "orange"),
+    ("magenta", "magenta"),
+    ("light_blue", "light_blue"),
+    ("yellow", "yellow"),
+    ("lime", "lime"),
+    ("pink", "pink"),
+    ("gray", "gray"),
+    ("silver", "light_gray"),
+    ("cyan", "cyan"),
+    ("purple", "purple"),
+    ("blue", "blue"),
+    ("brown", "brown"),
+    ("green", "green"),
+    ("red", "red"),
+    ("black", "black"),
+];
+
+/// Pre-1.13 wood names and their 1.13 counterparts
+const LEGACY_WOODS: &[(&str, &str)] = &[
+    ("oak", "oak"),
+    ("spruce", "spruce"),
+    ("birch", "birch"),
+    ("jungle", "jungle"),
+    ("acacia", "acacia"),
+    ("big_oak", "dark_oak"),
+];
+
+/// Textures renamed by the flattening, after "blocks/" and "items/"
+/// became "block/" and "item/" (colored and wood variants are added in
+/// `FLATTENING_RENAMES`)
+const FLATTENING_NAMES: &[(&str, &str)] = &[
+    ("block/stone_granite", "block/granite"),
+    ("block/stone_granite_smooth", "block/polished_granite"),
+    ("block/stone_diorite", "block/diorite"),
+    ("block/stone_diorite_smooth", "block/polished_diorite"),
+    ("block/stone_andesite", "block/andesite"),
+    ("block/stone_andesite_smooth", "block/polished_andesite"),
+    ("block/grass_top", "block/grass_block_top"),
+    ("block/grass_side", "block/grass_block_side"),
+    ("block/grass_side_overlay", "block/grass_block_side_overlay"),
+    ("block/grass_side_snowed", "block/grass_block_snow"),
+    ("block/dirt_podzol_top", "block/podzol_top"),
+    ("block/dirt_podzol_side", "block/podzol_side"),
+    ("block/cobblestone_mossy", "block/mossy_cobblestone"),
+    ("block/brick", "block/bricks"),
+    ("block/stonebrick", "block/stone_bricks"),
+    ("block/stonebrick_mossy", "block/mossy_stone_bricks"),
+    ("block/stonebrick_cracked", "block/cracked_stone_bricks"),
+    ("block/stonebrick_carved", "block/chiseled_stone_bricks"),
+    ("block/sandstone_normal", "block/sandstone"),
+    ("block/sandstone_carved", "block/chiseled_sandstone"),
+    ("block/sandstone_smooth", "block/cut_sandstone"),
+    ("block/red_sandstone_normal", "block/red_sandstone"),
+    ("block/red_sandstone_carved", "block/chiseled_red_sandstone"),
+    ("block/red_sandstone_smooth", "block/cut_red_sandstone"),
+    ("block/nether_brick", "block/nether_bricks"),
+    ("block/quartz_ore", "block/nether_quartz_ore"),
+    ("block/hardened_clay", "block/terracotta"),
+    ("block/ice_packed", "block/packed_ice"),
+    ("block/farmland_wet", "block/farmland_moist"),
+    ("block/farmland_dry", "block/farmland"),
+    ("block/furnace_front_off", "block/furnace_front"),
+    ("block/pumpkin_face_off", "block/carved_pumpkin"),
+    ("block/pumpkin_face_on", "block/jack_o_lantern"),
+    ("block/redstone_lamp_off", "block/redstone_lamp"),
+    ("block/noteblock", "block/note_block"),
+    ("block/torch_on", "block/torch"),
+    ("block/portal", "block/nether_portal"),
+    ("block/reeds", "block/sugar_cane"),
+    ("block/tallgrass", "block/grass"),
+    ("block/deadbush", "block/dead_bush"),
+    ("block/web", "block/cobweb"),
+    ("block/waterlily", "block/lily_pad"),
+    ("block/mushroom_red", "block/red_mushroom"),
+    ("block/mushroom_brown", "block/brown_mushroom"),
+    ("block/flower_dandelion", "block/dandelion"),
+    ("block/flower_rose", "block/poppy"),
+    ("block/trapdoor", "block/oak_trapdoor"),
+    ("block/door_wood_lower", "block/oak_door_bottom"),
+    ("block/door_wood_upper", "block/oak_door_top"),
+    ("block/door_iron_lower", "block/iron_door_bottom"),
+    ("block/door_iron_upper", "block/iron_door_top"),
+    ("item/bow_standing", "item/bow"),
+    ("item/fishing_rod_uncast", "item/fishing_rod"),
+    ("item/reeds", "item/sugar_cane"),
+    ("item/seeds_wheat", "item/wheat_seeds"),
+    ("item/potato_baked", "item/baked_potato"),
+    ("item/porkchop_raw", "item/porkchop"),
+    ("item/porkchop_cooked", "item/cooked_porkchop"),
+    ("item/beef_raw", "item/beef"),
+    ("item/beef_cooked", "item/cooked_beef"),
+    ("item/chicken_raw", "item/chicken"),
+    ("item/chicken_cooked", "item/cooked_chicken"),
+    ("item/fish_cod_raw", "item/cod"),
+    ("item/fish_cod_cooked", "item/cooked_cod"),
+    ("item/fish_salmon_raw", "item/salmon"),
+    ("item/fish_salmon_cooked", "item/cooked_salmon"),
+    ("item/bucket_empty", "item/bucket"),
+    ("item/bucket_water", "item/water_bucket"),
+    ("item/bucket_lava", "item/lava_bucket"),
+    ("item/bucket_milk", "item/milk_bucket"),
+    ("item/book_normal", "item/book"),
+    ("item/book_writable", "item/writable_book"),
+    ("item/book_written", "item/written_book"),
+    ("item/book_enchanted", "item/enchanted_book"),
+    ("item/door_wood", "item/oak_door"),
+    ("item/door_iron", "item/iron_door"),
+];
+
+/// Pre-1.13 tool and armor materials and their 1.13 names
+const LEGACY_MATERIALS: &[(&str, &str)] = &[("wood", "wooden"), ("gold", "golden")];
+
+/// Tools and armor named after their material
+const MATERIAL_ITEMS: &[&str] = &[
+    "sword",
+    "pickaxe",
+    "axe",
+    "shovel",
+    "hoe",
+    "helmet",
+    "chestplate",
+    "leggings",
+    "boots",
+];
+
+/// Texture renames of the flattening, with colored and wood variants
+static FLATTENING_RENAMES: Lazy<HashMap<String, String>> = Lazy::new(|| {
+    let mut renames: HashMap<String, String> = FLATTENING_NAMES
+        .iter()
+        .map(|(from, to)| (from.to_string(), to.to_string()))
+        .collect();
+    let mut add = |from: String, to: String| {
+        renames.insert(from, to);
+    };
+
+    for (legacy, color) in LEGACY_COLORS {
+        add(
+            format!("block/wool_colored_{}", legacy),
+            format!("block/{}_wool", color),
+        );
+        add(
+            format!("block/glass_{}", legacy),
+            format!("block/{}_stained_glass", color),
+        );
+        add(
+            format!("block/hardened_clay_stained_{}", legacy),
+            format!("block/{}_terracotta", color),
+        );
+        add(
+            format!("block/concrete_{}", legacy),
+            format!("block/{}_concrete", color),
+        );
+    }
+    for (legacy, wood) in LEGACY_WOODS {
+        add(
+            format!("block/planks_{}", legacy),
+            format!("block/{}_planks", wood),
+        );
+        add(
+            format!("block/log_{}", legacy),
+            format!("block/{}_log", wood),
+        );
+        add(
+            format!("block/log_{}_top", legacy),
+            format!("block/{}_log_top", wood),
+        );
+        add(
+            format!("block/leaves_{}", legacy),
+            format!("block/{}_leaves", wood),
+        );
+        add(
+            format!("block/sapling_{}", legacy),
+            format!("block/{}_sapling", wood),
+        );
+    }
+    for (legacy, material) in LEGACY_MATERIALS {
+        for item in MATERIAL_ITEMS {
+            add(
+                format!("item/{}_{}", legacy, item),
+                format!("item/{}_{}", material, item),
+            );
+        }
+    }
+    renames
+});
+
+/// Textures renamed in 1.17 (format 7)
+const CAVES_AND_CLIFFS_RENAMES: &[(&str, &str)] = &[
+    ("block/grass_path_top", "block/dirt_path_top"),
+    ("block/grass_path_side", "block/dirt_path_side"),
+];
+
+/// A texture moved to its new name
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RenamedFile {
+    pub from: String,
+    pub to: String,
+}
+
+/// A file the migrator copied without being able to update it
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UnmappedFile {
+    /// Path in the migrated pack
+    pub path: String,
+    pub reason: String,
+}
+
+/// Result of migrating a pack
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MigrationReport {
+    pub source_format: u32,
+    pub target_format: u32,
+    /// Folder the migrated pack was written to
+    pub output_path: String,
+    /// Files written, pack.mcmeta included
+    pub files: usize,
+    pub renamed: Vec<RenamedFile>,
+    pub unmapped: Vec<UnmappedFile>,
+}
+
+/// New path of a texture file when moving between two formats
+///
+/// Paths outside the vanilla texture folder, and textures that weren't
+/// renamed, are returned unchanged. Extensions are kept, so a texture's
+/// .png.mcmeta moves with it.
+pub fn migrated_path(path: &str, source_format: u32, target_format: u32) -> String {
+    let texture = match path.strip_prefix(TEXTURE_ROOT) {
+        Some(texture) => texture,
+        None => return path.to_string(),
+    };
+    let (folder, file_name) = texture.rsplit_once('/').unwrap_or(("", texture));
+    let (stem, extension) = match file_name.find('.') {
+        Some(dot) => file_name.split_at(dot),
+        None => (file_name, ""),
+    };
+    let mut name = if folder.is_empty() {
+        stem.to_string()
+    } else {
+        format!("{}/{}", folder, stem)
+    };
+
+    if source_format < FLATTENING_FORMAT && target_format >= FLATTENING_FORMAT {
+        for (old, new) in [("blocks/", "block/"), ("items/", "item/")] {
+            if let Some(rest) = name.strip_prefix(old) {
+                name = format!("{}{}", new, rest);
+            }
+        }
+        if let Some(renamed) = FLATTENING_RENAMES.get(&name) {
+            name = renamed.clone();
+        }
+    }
+    if source_format < 7 && target_format >= 7 {
+        if let Some((_, renamed)) = CAVES_AND_CLIFFS_RENAMES
+            .iter()
+            .find(|(old, _)| *old == name)
+        {
+            name = renamed.to_string();
+        }
+    }
+
+    format!("{}{}{}", TEXTURE_ROOT, name, extension)
+}
+
+/// pack.mcmeta with the pack format replaced
+///
+/// supported_formats is dropped since it described the old format.
+fn migrated_mcmeta(mcmeta: &[u8], target_format: u32) -> Result<Vec<u8>> {
+    let mut mcmeta: Value = serde_json::from_slice(mcmeta).context("Invalid pack.mcmeta")?;
+    let pack = mcmeta
+        .get_mut("pack")
+        .and_then(Value::as_object_mut)
+        .ok_or_else(|| anyhow!("pack.mcmeta has no pack section"))?;
+    pack.insert("pack_format".to_string(), Value::from(target_format));
+    pack.remove("supported_formats");
+    Ok(format!("{}\n", serde_json::to_string_pretty(&mcmeta)?).into_bytes())
+}
+
+/// Write a copy of a pack upgraded to a newer pack format
+///
+/// # Arguments
+/// * `pack` - Pack to migrate; it must declare a pack_format
+/// * `target_format` - Format to migrate to
+/// * `output` - Folder to write the migrated pack to; must not exist
+/// * `vanilla_dir` - Extracted vanilla assets, to report renamed textures
+///   with no vanilla counterpart (skipped if None)
+pub fn migrate_pack(
+    pack: &PackMeta,
+    target_format: u32,
+    output: &Path,
+    vanilla_dir: Option<&Path>,
+) -> Result<MigrationReport> {
+    let source_format = pack
+        .pack_format
+        .ok_or_else(|| anyhow!("{} doesn't declare a pack_format", pack.name))?;
+    if source_format >= target_format {
+        return Err(anyhow!(
+            "{} already uses pack format {}",
+            pack.name,
+            source_format
+        ));
+    }
+    if output.exists() {
+        return Err(anyhow!("{} already exists", output.display()));
+    }
+
+    let flattened = source_format < FLATTENING_FORMAT && target_format >= FLATTENING_FORMAT;
+    let files = pack_scanner::list_pack_files(pack)?;
+    let mut report = MigrationReport {
+        source_format,
+        target_format,
+        output_path: output.to_string_lossy().to_string(),
+        files: 0,
+        renamed: Vec::new(),
+        unmapped: Vec::new(),
+    };
+
+    for file in &files {
+        let bytes = read_pack_bytes(&pack.path, pack.is_zip, file)?;
+        let (path, bytes) = if file == "pack.mcmeta" {
+            (file.clone(), migrated_mcmeta(&bytes, target_format)?)
+        } else {
+            (migrated_path(file, source_format, target_format), bytes)
+        };
+
+        if path != *file {
+            report.renamed.push(RenamedFile {
+                from: file.clone(),
+                to: path.clone(),
+            });
+        }
+        if flattened && path.starts_with(TEXTURE_ROOT) && path.ends_with(".png") {
+            if let Some(vanilla_dir) = vanilla_dir {
+                if !vanilla_dir.join(&path).is_file() {
+                    report.unmapped.push(UnmappedFile {
+                        path: path.clone(),
+                        reason: "No vanilla texture has this name".to_string(),
+                    });
+                }
+            }
+        }
+        if flattened
+            && (path.starts_with("assets/minecraft/models/")
+                || path.starts_with("assets/minecraft/blockstates/"))
+        {
+            report.unmapped.push(UnmappedFile {
+                path: path.clone(),
+                reason: "May still name pre-1.13 textures and models".to_string(),
+            });
+        }
+
+        let target = zip::safe_join(output, &path)?;
+        if let Some(parent) = target.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(&target, bytes)
+            .with_context(|| format!("Failed to write {}", target.display()))?;
+        report.files += 1;
+    }
+
+    info!(
+        "Migrated {} from format {} to {}: {} renamed, {} unmapped",
+        pack.name,
+        source_format,
+        target_format,
+        report.renamed.len(),
+        report.unmapped.len()
+    );
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_migrated_path() {
+        let path = |texture: &str| format!("{}{}", TEXTURE_ROOT, texture);
+
+        assert_eq!(
+            migrated_path(&path("blocks/planks_big_oak.png"), 3, 15),
+            path("block/dark_oak_planks.png")
+        );
+        assert_eq!(
+            migrated_path(&path("blocks/wool_colored_silver.png"), 3, 15),
+            path("block/light_gray_wool.png")
+        );
+        assert_eq!(
+            migrated_path(&path("blocks/lava_still.png.mcmeta"), 3, 15),
+            path("block/lava_still.png.mcmeta")
+        );
+        assert_eq!(
+            migrated_path(&path("items/gold_sword.png"), 3, 15),
+            path("item/golden_sword.png")
+        );
+        assert_eq!(
+            migrated_path(&path("blocks/grass_path_top.png"), 3, 15),
+            path("block/dirt_path_top.png")
+        );
+        // 1.17 rename doesn't apply when staying below it
+        assert_eq!(
+            migrated_path(&path("block/grass_path_top.png"), 4, 6),
+            path("block/grass_path_top.png")
+        );
+        assert_eq!(
+            migrated_path("assets/minecraft/sounds/dig.ogg", 3, 15),
+            "assets/minecraft/sounds/dig.ogg"
+        );
+    }
+
+    #[test]
+    fn test_migrate_pack() {
+        let temp_dir = std::env::temp_dir().join("test_pack_migrator");
+        fs::remove_dir_all(&temp_dir).ok();
+        let pack_dir = temp_dir.join("Legacy");
+        let blocks = pack_dir.join("assets/minecraft/textures/blocks");
+        let models = pack_dir.join("assets/minecraft/models/block");
+        fs::create_dir_all(&blocks).unwrap();
+        fs::create_dir_all(&models).unwrap();
+        fs::write(
+            pack_dir.join("pack.mcmeta"),
+            r#"{"pack": {"pack_format": 3, "description": "Old"}}"#,
+        )
+        .unwrap();
+        fs::write(blocks.join("planks_oak.png"), "planks").unwrap();
+        fs::write(blocks.join("custom_thing.png"), "custom").unwrap();
+        fs::write(models.join("stone.json"), "{}").unwrap();
+        let vanilla = temp_dir.join("vanilla/assets/minecraft/textures/block");
+        fs::create_dir_all(&vanilla).unwrap();
+        fs::write(vanilla.join("oak_planks.png"), "vanilla").unwrap();
+        let pack = PackMeta {
+            id: "Legacy".to_string(),
+            name: "Legacy".to_string(),
+            path: pack_dir.to_string_lossy().to_string(),
+            pack_format: Some(3),
+            ..Default::default()
+        };
+
+        let output = temp_dir.join("Migrated");
+        let report = migrate_pack(&pack, 15, &output, Some(&temp_dir.join("vanilla")));
+        let planks =
+            fs::read_to_string(output.join("assets/minecraft/textures/block/oak_planks.png")).ok();
+        let mcmeta = fs::read_to_string(output.join("pack.mcmeta")).unwrap_or_default();
+        let again = migrate_pack(&pack, 15, &output, None);
+
+        // Clean up
+        fs::remove_dir_all(&temp_dir).ok();
+
+        let report = report.expect("migration should succeed");
+        assert_eq!(report.files, 4);
+        assert_eq!(report.renamed.len(), 2);
+        assert_eq!(planks.as_deref(), Some("planks"));
+        let mcmeta: Value = serde_json::from_str(&mcmeta).expect("valid mcmeta");
+        assert_eq!(mcmeta["pack"]["pack_format"], 15);
+        assert_eq!(mcmeta["pack"]["description"], "Old");
+        let unmapped: Vec<&str> = report.unmapped.iter().map(|u| u.path.as_str()).collect();
+        assert!(unmapped.contains(&"assets/minecraft/textures/block/custom_thing.png"));
+        assert!(unmapped.contains(&"assets/minecraft/models/block/stone.json"));
+        assert_eq!(unmapped.len(), 2);
+        // The output must not already exist
+        assert!(again.is_err());
+    }
+}
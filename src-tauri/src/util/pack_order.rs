@@ -0,0 +1,544 @@
+/// Pack order suggestions
+///
+/// A good starting order puts full-coverage base packs at the bottom and
+/// small addon or patch packs on top, so the addons' few changes win over
+/// the base. Addons are recognized by their models referencing textures the
+/// addon doesn't ship but another pack does: they are drawn with the other
+/// pack's art and only make sense on top of it. Each pack's closest match in
+/// color palette is listed too, to help pick packs that blend together.
+use crate::model::PackMeta;
+use crate::util::palette::{self, PackPalette, StyleMatch};
+use crate::util::vanilla_textures::VANILLA_PACK_ID;
+use crate::util::{animation, asset_indexer, model_texture_deps};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeSet, HashMap};
+use std::path::Path;
+use tracing::{info, warn};
+
+/// Share of all textures above which a pack is treated as a base pack
+const BASE_COVERAGE: f32 = 0.5;
+
+/// Share of a base pack's textures below which a pack relying on it is a patch
+const PATCH_MAX_SHARE: f32 = 0.1;
+
+/// Coverage and dependencies of one pack
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PackCoverage {
+    pub pack_id: String,
+    /// Textures the pack provides
+    pub textures: usize,
+    /// Share (0-1) of the textures any scanned pack provides
+    pub coverage: f32,
+    /// Packs providing textures this pack's models use but it doesn't ship
+    pub depends_on: Vec<String>,
+    /// Why the pack was placed where it is
+    pub reason: String,
+    /// Pack with the most similar palette (see `add_style_matches`)
+    #[serde(default)]
+    pub style_match: Option<StyleMatch>,
+}
+
+/// A suggested starting pack order
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PackOrderSuggestion {
+    /// Pack IDs in priority order (first = highest)
+    pub pack_order: Vec<String>,
+    /// Per-pack stats, in the same order
+    pub packs: Vec<PackCoverage>,
+}
+
+/// Packs each pack's models depend on for textures
+///
+/// A dependency is a texture a pack's model references that the pack
+/// itself doesn't provide, vanilla doesn't have, and another pack does.
+///
+/// # Arguments
+/// * `packs` - Scanned packs
+/// * `providers` - Asset ID -> pack IDs providing it
+/// * `vanilla_dir` - Vanilla texture cache; without it, `minecraft:`
+///   textures are assumed to be vanilla
+///
+/// # Returns
+/// Pack ID -> IDs of the packs it depends on (packs without any are omitted)
+pub fn texture_dependencies(
+    packs: &[PackMeta],
+    providers: &HashMap<String, Vec<String>>,
+    vanilla_dir: Option<&Path>,
+) -> HashMap<String, BTreeSet<String>> {
+    let mut dependencies: HashMap<String, BTreeSet<String>> = HashMap::new();
+
+    for pack in packs {
+        let model_files = match model_texture_deps::list_model_files(pack) {
+            Ok(files) => files,
+            Err(e) => {
+                warn!("Could not list {}: {}", pack.id, e);
+                continue;
+            }
+        };
+
+        let contents = match animation::read_pack_files(&pack.path, pack.is_zip, &model_files) {
+            Ok(contents) => contents,
+            Err(e) => {
+                warn!("Could not read {}: {}", pack.id, e);
+                continue;
+            }
+        };
+
+        for json in contents.values() {
+            for texture_id in model_texture_deps::referenced_textures(json) {
+                let texture_providers = match providers.get(&texture_id) {
+                    Some(p) if !p.contains(&pack.id) => p,
+                    _ => continue,
+                };
+                let in_vanilla = match vanilla_dir {
+                    Some(dir) => dir
+                        .join(animation::texture_path_for_asset(&texture_id))
+                        .is_file(),
+                    None => texture_id.starts_with("minecraft:"),
+                };
+                if in_vanilla {
+                    continue;
+                }
+                dependencies
+                    .entry(pack.id.clone())
+                    .or_default()
+                    .extend(texture_providers.iter().cloned());
+            }
+        }
+    }
+
+    dependencies
+}
+
+/// Textures each pack provides, not counting custom entity models or CTM
+fn texture_counts(providers: &HashMap<String, Vec<String>>) -> HashMap<&str, usize> {
+    let mut counts: HashMap<&str, usize> = HashMap::new();
+    for (asset_id, pack_ids) in providers {
+        if asset_indexer::is_grouped_asset(asset_id) {
+            continue;
+        }
+        for pack_id in pack_ids {
+            *counts.entry(pack_id.as_str()).or_default() += 1;
+        }
+    }
+    counts
+}
+
+/// Words of a pack name without version numbers
+///
+/// "Faithful 32x - 1.20.4" -> ["faithful", "32x"]
+fn name_words(name: &str) -> Vec<String> {
+    name.trim_end_matches(".zip")
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .filter(|word| {
+            let digits = word.strip_prefix(|c| c == 'v' || c == 'V').unwrap_or(word);
+            !digits.chars().all(|c| c.is_ascii_digit())
+        })
+        .map(|word| word.to_lowercase())
+        .collect()
+}
+
+/// Whether a pack is named as an extension of another
+///
+/// "Faithful 32x CTM Addon" extends "Faithful 32x"
+fn extends_name(name: &str, base_name: &str) -> bool {
+    let words = name_words(name);
+    let base_words = name_words(base_name);
+    !base_words.is_empty() && words.len() > base_words.len() && words.starts_with(&base_words)
+}
+
+/// Find packs designed as patches on top of a base pack
+///
+/// A patch has few textures compared to its base (under 10% of them) and
+/// either uses textures only the base provides or is named after it.
+///
+/// # Arguments
+/// * `packs` - Scanned packs (the vanilla pack is ignored)
+/// * `providers` - Asset ID -> pack IDs providing it
+/// * `vanilla_dir` - Vanilla texture cache (see `texture_dependencies`)
+///
+/// # Returns
+/// Patch pack ID -> IDs of its possible base packs
+pub fn detect_patch_packs(
+    packs: &[PackMeta],
+    providers: &HashMap<String, Vec<String>>,
+    vanilla_dir: Option<&Path>,
+) -> HashMap<String, Vec<String>> {
+    let packs: Vec<PackMeta> = packs
+        .iter()
+        .filter(|p| p.id != VANILLA_PACK_ID)
+        .cloned()
+        .collect();
+    let counts = texture_counts(providers);
+    let dependencies = texture_dependencies(&packs, providers, vanilla_dir);
+    let count = |id: &str| counts.get(id).copied().unwrap_or(0);
+
+    let mut patches = HashMap::new();
+    for pack in &packs {
+        let bases: Vec<String> = packs
+            .iter()
+            .filter(|base| base.id != pack.id)
+            .filter(|base| {
+                dependencies
+                    .get(&pack.id)
+                    .map_or(false, |d| d.contains(&base.id))
+                    || extends_name(&pack.name, &base.name)
+            })
+            .filter(|base| count(&pack.id) as f32 <= count(&base.id) as f32 * PATCH_MAX_SHARE)
+            .map(|base| base.id.clone())
+            .collect();
+        if !bases.is_empty() {
+            info!("{} looks like a patch for {:?}", pack.id, bases);
+            patches.insert(pack.id.clone(), bases);
+        }
+    }
+    patches
+}
+
+/// Set `patch_for` on every pack detected as a patch
+pub fn tag_patch_packs(
+    packs: &mut [PackMeta],
+    providers: &HashMap<String, Vec<String>>,
+    vanilla_dir: Option<&Path>,
+) {
+    let mut patches = detect_patch_packs(packs, providers, vanilla_dir);
+    for pack in packs.iter_mut() {
+        pack.patch_for = patches.remove(&pack.id).unwrap_or_default();
+    }
+}
+
+/// Warnings for enabled patch packs whose base pack isn't enabled
+///
+/// # Arguments
+/// * `packs` - Packs tagged by `tag_patch_packs`
+/// * `pack_order` - Enabled pack IDs
+pub fn missing_patch_bases(packs: &[PackMeta], pack_order: &[String]) -> Vec<String> {
+    pack_order
+        .iter()
+        .filter_map(|id| packs.iter().find(|p| &p.id == id))
+        .filter(|pack| {
+            !pack.patch_for.is_empty() && !pack.patch_for.iter().any(|b| pack_order.contains(b))
+        })
+        .map(|pack| {
+            let bases: Vec<&str> = pack
+                .patch_for
+                .iter()
+                .map(|id| {
+                    packs
+                        .iter()
+                        .find(|p| &p.id == id)
+                        .map_or(id.as_str(), |p| p.name.as_str())
+                })
+                .collect();
+            format!(
+                "{} is a patch for {}, which isn't enabled",
+                pack.name,
+                bases.join(" or ")
+            )
+        })
+        .collect()
+}
+
+/// Suggest a starting pack order from coverage and dependencies
+///
+/// Addons go on top, above every pack they depend on; the remaining packs
+/// follow from smallest to largest, so full-coverage packs end up at the
+/// bottom. Ties keep the scan order.
+///
+/// # Arguments
+/// * `packs` - Scanned packs
+/// * `providers` - Asset ID -> pack IDs providing it
+/// * `vanilla_dir` - Vanilla texture cache (see `texture_dependencies`)
+pub fn suggest_pack_order(
+    packs: &[PackMeta],
+    providers: &HashMap<String, Vec<String>>,
+    vanilla_dir: Option<&Path>,
+) -> PackOrderSuggestion {
+    let texture_counts = texture_counts(providers);
+    let total_textures = providers
+        .keys()
+        .filter(|id| !asset_indexer::is_grouped_asset(id))
+        .count();
+    let dependencies = texture_dependencies(packs, providers, vanilla_dir);
+
+    let mut stats: Vec<PackCoverage> = packs
+        .iter()
+        .map(|pack| {
+            let textures = texture_counts.get(pack.id.as_str()).copied().unwrap_or(0);
+            let coverage = if total_textures == 0 {
+                0.0
+            } else {
+                textures as f32 / total_textures as f32
+            };
+            let depends_on: Vec<String> = dependencies
+                .get(&pack.id)
+                .map(|d| d.iter().cloned().collect())
+                .unwrap_or_default();
+            let reason = if !depends_on.is_empty() {
+                format!("Addon: uses textures from {}", depends_on.join(", "))
+            } else if coverage >= BASE_COVERAGE {
+                format!("Base pack: {:.0}% of all textures", coverage * 100.0)
+            } else {
+                format!("{} texture(s)", textures)
+            };
+            PackCoverage {
+                pack_id: pack.id.clone(),
+                textures,
+                coverage,
+                depends_on,
+                reason,
+                style_match: None,
+            }
+        })
+        .collect();
+
+    // Addons first, then smaller packs above larger ones
+    stats.sort_by_key(|s| (s.depends_on.is_empty(), s.textures));
+
+    // A pack can be placed once every pack depending on it is above it
+    let mut ordered: Vec<PackCoverage> = Vec::with_capacity(stats.len());
+    while !stats.is_empty() {
+        let next = stats
+            .iter()
+            .position(|candidate| {
+                !stats
+                    .iter()
+                    .any(|other| other.depends_on.contains(&candidate.pack_id))
+            })
+            // Packs depending on each other; keep the sorted order
+            .unwrap_or(0);
+        ordered.push(stats.remove(next));
+    }
+
+    info!(
+        "Suggested order for {} pack(s), {} addon(s)",
+        ordered.len(),
+        dependencies.len()
+    );
+
+    PackOrderSuggestion {
+        pack_order: ordered.iter().map(|s| s.pack_id.clone()).collect(),
+        packs: ordered,
+    }
+}
+
+/// Add each pack's closest match in style to a suggestion
+///
+/// # Arguments
+/// * `palettes` - Palettes of the suggested packs; packs without one get no
+///   match
+pub fn add_style_matches(suggestion: &mut PackOrderSuggestion, palettes: &[PackPalette]) {
+    let mut matches = palette::closest_styles(palettes);
+    for pack in &mut suggestion.packs {
+        pack.style_match = matches.remove(&pack.pack_id);
+        if let Some(style) = &pack.style_match {
+            pack.reason.push_str(&format!(
+                "; closest in style to {} ({:.0}%)",
+                style.pack_name,
+                style.similarity * 100.0
+            ));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::util::test_support::folder_pack;
+    use std::fs;
+
+    fn ids(values: &[&str]) -> Vec<String> {
+        values.iter().map(|v| v.to_string()).collect()
+    }
+
+    #[test]
+    fn test_suggest_pack_order() {
+        let temp_dir = std::env::temp_dir().join("test_pack_order_suggest");
+        fs::remove_dir_all(&temp_dir).ok();
+        let packs = vec![
+            folder_pack(&temp_dir, "base", &[]),
+            // Retextures one block using the base pack's custom texture
+            folder_pack(
+                &temp_dir,
+                "addon",
+                &[(
+                    "assets/minecraft/models/block/oak_log.json",
+                    r#"{"textures":{"side":"fancy:block/bark","end":"block/oak_log_top"}}"#,
+                )],
+            ),
+            folder_pack(&temp_dir, "small", &[]),
+            folder_pack(&temp_dir, "medium", &[]),
+        ];
+        let providers = HashMap::from([
+            (
+                "minecraft:block/stone".to_string(),
+                ids(&["base", "medium"]),
+            ),
+            (
+                "minecraft:block/dirt".to_string(),
+                ids(&["base", "medium", "small"]),
+            ),
+            ("minecraft:block/sand".to_string(), ids(&["base"])),
+            ("fancy:block/bark".to_string(), ids(&["base"])),
+            (
+                "minecraft:block/oak_log_top".to_string(),
+                ids(&["base", "addon"]),
+            ),
+        ]);
+
+        let suggestion = suggest_pack_order(&packs, &providers, None);
+
+        // Clean up
+        fs::remove_dir_all(&temp_dir).ok();
+
+        assert_eq!(
+            suggestion.pack_order,
+            vec!["addon", "small", "medium", "base"]
+        );
+        assert_eq!(suggestion.packs[0].depends_on, vec!["base"]);
+        assert_eq!(suggestion.packs[3].coverage, 1.0);
+        assert!(suggestion.packs[3].reason.starts_with("Base pack"));
+    }
+
+    #[test]
+    fn test_add_style_matches() {
+        let coverage = |id: &str| PackCoverage {
+            pack_id: id.to_string(),
+            textures: 1,
+            coverage: 0.5,
+            depends_on: Vec::new(),
+            reason: "1 texture(s)".to_string(),
+            style_match: None,
+        };
+        let palette = |id: &str, rgb: [u8; 3]| PackPalette {
+            pack_id: id.to_string(),
+            pack_name: format!("{} pack", id),
+            colors: vec![palette::PaletteColor {
+                hex: palette::rgb_to_hex(rgb),
+                rgb,
+                weight: 1.0,
+            }],
+            average_color: palette::rgb_to_hex(rgb),
+            brightness: 0.5,
+            saturation: 0.5,
+            textures_sampled: 1,
+        };
+        let mut suggestion = PackOrderSuggestion {
+            pack_order: ids(&["a", "b", "c"]),
+            packs: vec![coverage("a"), coverage("b"), coverage("c")],
+        };
+
+        add_style_matches(
+            &mut suggestion,
+            &[palette("a", [40, 160, 40]), palette("b", [50, 150, 40])],
+        );
+
+        let a = &suggestion.packs[0];
+        assert_eq!(
+            a.style_match.as_ref().map(|m| m.pack_id.as_str()),
+            Some("b")
+        );
+        assert!(a.reason.contains("closest in style to b pack"));
+        assert!(suggestion.packs[2].style_match.is_none());
+        assert_eq!(suggestion.packs[2].reason, "1 texture(s)");
+    }
+
+    #[test]
+    fn test_addon_of_addon_stays_above_it() {
+        let temp_dir = std::env::temp_dir().join("test_pack_order_chain");
+        fs::remove_dir_all(&temp_dir).ok();
+        let model = |texture: &str| format!(r#"{{"textures":{{"all":"{}"}}}}"#, texture);
+        let packs = vec![
+            folder_pack(
+                &temp_dir,
+                "addon",
+                &[(
+                    "assets/minecraft/models/block/a.json",
+                    &model("x:block/one"),
+                )],
+            ),
+            folder_pack(
+                &temp_dir,
+                "patch",
+                &[(
+                    "assets/minecraft/models/block/b.json",
+                    &model("x:block/two"),
+                )],
+            ),
+            folder_pack(&temp_dir, "base", &[]),
+        ];
+        let providers = HashMap::from([
+            ("x:block/one".to_string(), ids(&["base"])),
+            ("x:block/two".to_string(), ids(&["addon"])),
+            ("x:block/three".to_string(), ids(&["addon"])),
+        ]);
+
+        let suggestion = suggest_pack_order(&packs, &providers, None);
+
+        // Clean up
+        fs::remove_dir_all(&temp_dir).ok();
+
+        assert_eq!(suggestion.pack_order, vec!["patch", "addon", "base"]);
+    }
+
+    #[test]
+    fn test_name_words() {
+        assert_eq!(
+            name_words("Faithful 32x - 1.20.4.zip"),
+            vec!["faithful", "32x"]
+        );
+        assert!(extends_name(
+            "Faithful 32x CTM Addon v2",
+            "Faithful 32x 1.20"
+        ));
+        assert!(!extends_name("Faithful 32x", "Faithful 32x"));
+        assert!(!extends_name("Stay True", "Faithful 32x"));
+    }
+
+    #[test]
+    fn test_detect_patch_packs() {
+        let temp_dir = std::env::temp_dir().join("test_pack_order_patches");
+        fs::remove_dir_all(&temp_dir).ok();
+        let mut base = folder_pack(&temp_dir, "base", &[]);
+        base.name = "Faithful 32x".to_string();
+        let mut named = folder_pack(&temp_dir, "named", &[]);
+        named.name = "Faithful 32x CTM".to_string();
+        let referencing = folder_pack(
+            &temp_dir,
+            "referencing",
+            &[(
+                "assets/minecraft/models/block/a.json",
+                r#"{"textures":{"all":"x:block/one"}}"#,
+            )],
+        );
+        let mut packs = vec![base, named, referencing];
+
+        let mut providers: HashMap<String, Vec<String>> = (0..20)
+            .map(|i| (format!("minecraft:block/t{}", i), ids(&["base"])))
+            .collect();
+        providers.insert("x:block/one".to_string(), ids(&["base"]));
+        providers.insert("minecraft:block/t0".to_string(), ids(&["base", "named"]));
+
+        tag_patch_packs(&mut packs, &providers, None);
+        let warnings = missing_patch_bases(&packs, &ids(&["named", "referencing"]));
+        let complete = missing_patch_bases(&packs, &ids(&["named", "base"]));
+
+        // Clean up
+        fs::remove_dir_all(&temp_dir).ok();
+
+        assert!(packs[0].patch_for.is_empty());
+        assert_eq!(packs[1].patch_for, vec!["base"]);
+        assert_eq!(packs[2].patch_for, vec!["base"]);
+        assert_eq!(
+            warnings,
+            vec![
+                "Faithful 32x CTM is a patch for Faithful 32x, which isn't enabled",
+                "referencing is a patch for Faithful 32x, which isn't enabled",
+            ]
+        );
+        assert!(complete.is_empty());
+    }
+}
@@ -0,0 +1,314 @@
+/// Safe removal of packs from the library
+///
+/// Removing a pack moves it into a hidden quarantine folder inside the packs
+/// directory instead of deleting it, so a mistaken removal can be undone.
+/// Scans skip hidden folders, and being on the same drive, moving a pack in
+/// or out is a rename however large it is. Before a pack is moved, saved
+/// projects are checked for references to it (pack order, overrides,
+/// simulated stacks) so the user sees which projects it would break.
+use crate::util::file_retry;
+use crate::util::projects::Project;
+use anyhow::{anyhow, bail, Context, Result};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Component, Path, PathBuf};
+use tracing::info;
+
+/// Hidden folder in the packs directory holding removed packs
+pub const QUARANTINE_DIR: &str = ".weaverbird-quarantine";
+
+/// How a saved project uses a pack
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PackReference {
+    pub project: String,
+    /// Whether the pack is in the project's pack order
+    pub in_order: bool,
+    /// Whether the project has the pack disabled
+    pub disabled: bool,
+    /// Overrides picking the pack
+    pub overrides: usize,
+    /// Simulated stacks naming the pack
+    pub simulations: Vec<String>,
+}
+
+/// What removing a pack would affect
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PackRemovalImpact {
+    pub pack_id: String,
+    /// Saved projects using the pack
+    pub references: Vec<PackReference>,
+}
+
+/// A pack in quarantine
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct QuarantinedPack {
+    /// "<removed at>/<pack file name>", used to restore or purge it
+    pub id: String,
+    /// File or folder name the pack had in the library
+    pub pack_id: String,
+    /// Unix seconds
+    pub removed_at: u64,
+    pub path: String,
+}
+
+fn unix_now() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+/// Quarantine folder of a packs directory
+pub fn quarantine_dir(packs_dir: &Path) -> PathBuf {
+    packs_dir.join(QUARANTINE_DIR)
+}
+
+/// Check that `name` is a single visible path component
+fn check_file_name(name: &str) -> Result<()> {
+    let mut components = Path::new(name).components();
+    match (components.next(), components.next()) {
+        (Some(Component::Normal(_)), None) if !name.starts_with('.') => Ok(()),
+        _ => bail!("Invalid pack name: {}", name),
+    }
+}
+
+/// Saved projects of `packs_dir` that use a pack
+pub fn pack_references(projects: &[Project], packs_dir: &str, pack_id: &str) -> Vec<PackReference> {
+    projects
+        .iter()
+        .filter(|project| Path::new(&project.packs_dir) == Path::new(packs_dir))
+        .filter_map(|project| {
+            let reference = PackReference {
+                project: project.name.clone(),
+                in_order: project.pack_order.iter().any(|id| id == pack_id),
+                disabled: project.disabled_packs.iter().any(|id| id == pack_id),
+                overrides: project
+                    .overrides
+                    .values()
+                    .filter(|selection| selection.pack_id == pack_id)
+                    .count(),
+                simulations: project
+                    .simulations
+                    .iter()
+                    .filter(|stack| {
+                        stack.pack_order.iter().any(|id| id == pack_id)
+                            || stack.overrides.values().any(|o| o.pack_id == pack_id)
+                    })
+                    .map(|stack| stack.name.clone())
+                    .collect(),
+            };
+            let used = reference.in_order || reference.overrides > 0;
+            (used || !reference.simulations.is_empty()).then(|| reference)
+        })
+        .collect()
+}
+
+/// Move a pack from the packs directory into quarantine
+///
+/// # Arguments
+/// * `packs_dir` - Directory containing resource packs
+/// * `pack_id` - File or folder name of the pack
+pub fn quarantine_pack(packs_dir: &Path, pack_id: &str) -> Result<QuarantinedPack> {
+    check_file_name(pack_id)?;
+    let source = packs_dir.join(pack_id);
+    if !source.exists() {
+        bail!("Pack not found: {}", pack_id);
+    }
+
+    let removed_at = unix_now();
+    let target_dir = quarantine_dir(packs_dir).join(removed_at.to_string());
+    let target = target_dir.join(pack_id);
+    if target.exists() {
+        bail!("{} was already quarantined a moment ago", pack_id);
+    }
+    fs::create_dir_all(&target_dir)
+        .with_context(|| format!("Failed to create {:?}", target_dir))?;
+    file_retry::rename_with_retry(&source, &target)?;
+
+    info!("Quarantined {} to {:?}", pack_id, target);
+    Ok(QuarantinedPack {
+        id: format!("{}/{}", removed_at, pack_id),
+        pack_id: pack_id.to_string(),
+        removed_at,
+        path: target.to_string_lossy().to_string(),
+    })
+}
+
+/// Packs in quarantine, most recently removed first
+pub fn list_quarantined(packs_dir: &Path) -> Result<Vec<QuarantinedPack>> {
+    let root = quarantine_dir(packs_dir);
+    if !root.is_dir() {
+        return Ok(Vec::new());
+    }
+
+    let mut packs = Vec::new();
+    for batch in fs::read_dir(&root).with_context(|| format!("Failed to read {:?}", root))? {
+        let batch = batch?;
+        let removed_at = match batch.file_name().to_string_lossy().parse::<u64>() {
+            Ok(removed_at) => removed_at,
+            Err(_) => continue,
+        };
+        for entry in fs::read_dir(batch.path())? {
+            let entry = entry?;
+            let pack_id = entry.file_name().to_string_lossy().to_string();
+            packs.push(QuarantinedPack {
+                id: format!("{}/{}", removed_at, pack_id),
+                pack_id,
+                removed_at,
+                path: entry.path().to_string_lossy().to_string(),
+            });
+        }
+    }
+    packs.sort_by(|a, b| {
+        b.removed_at
+            .cmp(&a.removed_at)
+            .then(a.pack_id.cmp(&b.pack_id))
+    });
+    Ok(packs)
+}
+
+/// Path of a quarantined pack from its ID, rejecting anything outside quarantine
+fn quarantined_path(packs_dir: &Path, id: &str) -> Result<(PathBuf, String)> {
+    let (removed_at, pack_id) = id
+        .split_once('/')
+        .filter(|(removed_at, _)| removed_at.parse::<u64>().is_ok())
+        .ok_or_else(|| anyhow!("Invalid quarantine entry: {}", id))?;
+    check_file_name(pack_id)?;
+
+    let path = quarantine_dir(packs_dir).join(removed_at).join(pack_id);
+    if !path.exists() {
+        bail!("Not in quarantine: {}", id);
+    }
+    Ok((path, pack_id.to_string()))
+}
+
+/// Remove a batch folder once its last pack is gone
+fn remove_empty_batch(path: &Path) {
+    if let Some(batch) = path.parent() {
+        // Fails while other packs of the batch remain, which is fine
+        fs::remove_dir(batch).ok();
+    }
+}
+
+/// Move a quarantined pack back into the packs directory
+///
+/// # Returns
+/// Path of the restored pack
+pub fn restore_quarantined(packs_dir: &Path, id: &str) -> Result<PathBuf> {
+    let (source, pack_id) = quarantined_path(packs_dir, id)?;
+    let target = packs_dir.join(&pack_id);
+    if target.exists() {
+        bail!("A pack named {} is already in the library", pack_id);
+    }
+
+    file_retry::rename_with_retry(&source, &target)?;
+    remove_empty_batch(&source);
+
+    info!("Restored {} from quarantine", pack_id);
+    Ok(target)
+}
+
+/// Permanently delete a quarantined pack
+pub fn purge_quarantined(packs_dir: &Path, id: &str) -> Result<()> {
+    let (path, pack_id) = quarantined_path(packs_dir, id)?;
+    if path.is_dir() {
+        file_retry::remove_dir_all_with_retry(&path)?;
+    } else {
+        file_retry::remove_file_with_retry(&path)?;
+    }
+    remove_empty_batch(&path);
+
+    info!("Deleted {} from quarantine", pack_id);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::OverrideSelection;
+    use crate::util::stack_simulation::StackConfig;
+    use std::collections::HashMap;
+
+    fn project(name: &str, packs_dir: &str, pack_order: &[&str]) -> Project {
+        Project {
+            name: name.to_string(),
+            packs_dir: packs_dir.to_string(),
+            pack_order: pack_order.iter().map(|s| s.to_string()).collect(),
+            overrides: HashMap::new(),
+            output_dir: "/out".to_string(),
+            namespace_owners: HashMap::new(),
+            fix_alpha_bleed: false,
+            include_override_dependencies: true,
+            simulations: Vec::new(),
+            disabled_packs: Vec::new(),
+            merged_text_assets: Vec::new(),
+            resolution: Default::default(),
+            description_template: None,
+        }
+    }
+
+    #[test]
+    fn test_pack_references() {
+        let mut survival = project("survival", "/packs/", &["a.zip", "b.zip"]);
+        survival.overrides.insert(
+            "minecraft:block/stone".to_string(),
+            OverrideSelection {
+                pack_id: "a.zip".to_string(),
+                variant_path: None,
+            },
+        );
+        let mut creative = project("creative", "/packs", &["b.zip"]);
+        creative.simulations.push(StackConfig {
+            name: "try a".to_string(),
+            pack_order: vec!["a.zip".to_string()],
+            overrides: HashMap::new(),
+        });
+        let elsewhere = project("other", "/elsewhere", &["a.zip"]);
+
+        let refs = pack_references(&[survival, creative, elsewhere], "/packs", "a.zip");
+
+        assert_eq!(refs.len(), 2);
+        assert_eq!(refs[0].project, "survival");
+        assert!(refs[0].in_order);
+        assert_eq!(refs[0].overrides, 1);
+        assert!(!refs[1].in_order);
+        assert_eq!(refs[1].simulations, vec!["try a"]);
+    }
+
+    #[test]
+    fn test_quarantine_and_restore() {
+        let temp_dir = std::env::temp_dir().join("test_pack_quarantine");
+        fs::remove_dir_all(&temp_dir).ok();
+        fs::create_dir_all(temp_dir.join("Folder Pack")).unwrap();
+        fs::write(temp_dir.join("Folder Pack/pack.mcmeta"), "{}").unwrap();
+        fs::write(temp_dir.join("zipped.zip"), b"zip").unwrap();
+
+        let folder = quarantine_pack(&temp_dir, "Folder Pack").unwrap();
+        let zipped = quarantine_pack(&temp_dir, "zipped.zip").unwrap();
+        let escape = quarantine_pack(&temp_dir, "../zipped.zip");
+        let listed = list_quarantined(&temp_dir).unwrap();
+        let folder_gone = !temp_dir.join("Folder Pack").exists();
+        let restored = restore_quarantined(&temp_dir, &folder.id).unwrap();
+        let restored_ok = restored.join("pack.mcmeta").is_file();
+        purge_quarantined(&temp_dir, &zipped.id).unwrap();
+        let bad_id = restore_quarantined(&temp_dir, "../../etc");
+        let remaining = list_quarantined(&temp_dir).unwrap();
+        let batch_removed = !quarantine_dir(&temp_dir)
+            .join(folder.removed_at.to_string())
+            .exists();
+
+        // Clean up
+        fs::remove_dir_all(&temp_dir).ok();
+
+        assert!(escape.is_err());
+        assert_eq!(listed.len(), 2);
+        assert!(folder_gone);
+        assert!(restored_ok);
+        assert!(bad_id.is_err());
+        assert!(remaining.is_empty());
+        assert!(batch_removed);
+    }
+}
@@ -1,10 +1,15 @@
 /// Scan a directory for resource packs (both .zip and uncompressed folders)
 use crate::model::PackMeta;
+use crate::util::pack_format::compatible_mc_versions;
+use crate::util::progress::FileProgress;
+use crate::util::{cloud_sync, parallelism};
 use anyhow::Result;
 use rayon::prelude::*;
 use std::fs;
 use std::io::Read;
 use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use tracing::{debug, info, warn};
 use walkdir::WalkDir;
 use zip::ZipArchive;
 
@@ -15,7 +20,15 @@ enum PackEntry {
 
 /// Scan a directory for resource packs (.zip files and uncompressed folders)
 pub fn scan_packs(packs_dir: &str) -> Result<Vec<PackMeta>> {
-    println!("[scan_packs] Starting PARALLEL scan of: {}", packs_dir);
+    scan_packs_with_progress(packs_dir, None)
+}
+
+/// Scan for resource packs, calling `progress` with (packs read, total, pack name)
+pub fn scan_packs_with_progress(
+    packs_dir: &str,
+    progress: Option<FileProgress>,
+) -> Result<Vec<PackMeta>> {
+    info!("Starting PARALLEL scan of: {}", packs_dir);
     let path = Path::new(packs_dir);
 
     if !path.exists() {
@@ -29,7 +42,7 @@ pub fn scan_packs(packs_dir: &str) -> Result<Vec<PackMeta>> {
     // First pass: collect all pack entries
     let mut pack_entries = Vec::new();
 
-    println!("[scan_packs] Reading directory entries...");
+    debug!("Reading directory entries...");
     for entry in fs::read_dir(path)? {
         let entry = entry?;
         let entry_path = entry.path();
@@ -38,6 +51,20 @@ pub fn scan_packs(packs_dir: &str) -> Result<Vec<PackMeta>> {
 
         // Skip hidden files and non-pack folders
         if file_name_str.starts_with('.') {
+            if let Some(target) = cloud_sync::icloud_stub_target(&file_name_str) {
+                warn!("Skipping {} (not downloaded from iCloud)", target);
+            }
+            continue;
+        }
+
+        // Reading cloud-only files fails or stalls; report them instead
+        let probe = if entry_path.is_dir() {
+            entry_path.join("pack.mcmeta")
+        } else {
+            entry_path.clone()
+        };
+        if cloud_sync::is_placeholder(&probe) {
+            warn!("Skipping {} (cloud placeholder)", file_name_str);
             continue;
         }
 
@@ -61,63 +88,104 @@ pub fn scan_packs(packs_dir: &str) -> Result<Vec<PackMeta>> {
         }
     }
 
-    println!(
-        "[scan_packs] Found {} packs, extracting metadata in PARALLEL",
+    info!(
+        "Found {} packs, extracting metadata in PARALLEL",
         pack_entries.len()
     );
 
     // Second pass: extract metadata in parallel
-    let packs: Vec<PackMeta> = pack_entries
-        .par_iter()
-        .filter_map(|entry| match entry {
-            PackEntry::Zip(entry_path, file_name_str, size) => {
-                println!("[scan_packs] Processing ZIP: {}", file_name_str);
-                let (description, icon_data, pack_format) =
-                    extract_pack_metadata_from_zip(entry_path);
-
-                Some(PackMeta {
-                    id: file_name_str.clone(),
-                    name: file_name_str.trim_end_matches(".zip").to_string(),
-                    path: entry_path.to_string_lossy().to_string(),
-                    size: *size,
-                    is_zip: true,
-                    description,
-                    icon_data,
-                    pack_format,
-                })
-            }
-            PackEntry::Dir(entry_path, file_name_str) => {
-                println!("[scan_packs] Processing directory: {}", file_name_str);
-                let size = calculate_dir_size(entry_path);
-                let (description, icon_data, pack_format) =
-                    extract_pack_metadata_from_dir(entry_path);
-
-                Some(PackMeta {
-                    id: file_name_str.clone(),
-                    name: file_name_str.clone(),
-                    path: entry_path.to_string_lossy().to_string(),
-                    size,
-                    is_zip: false,
-                    description,
-                    icon_data,
-                    pack_format,
-                })
-            }
-        })
-        .collect();
+    let total = pack_entries.len();
+    let scanned = AtomicUsize::new(0);
+    if let Some(progress) = progress {
+        progress(0, total, None);
+    }
+    let packs: Vec<PackMeta> = parallelism::with_io_pool(|| {
+        pack_entries
+            .par_iter()
+            .filter_map(|entry| match entry {
+                PackEntry::Zip(entry_path, file_name_str, size) => {
+                    debug!("Processing ZIP: {}", file_name_str);
+                    let (description, icon_data, pack_format) =
+                        extract_pack_metadata_from_zip(entry_path);
+
+                    Some(PackMeta {
+                        id: file_name_str.clone(),
+                        name: file_name_str.trim_end_matches(".zip").to_string(),
+                        path: entry_path.to_string_lossy().to_string(),
+                        size: *size,
+                        is_zip: true,
+                        description,
+                        icon_data,
+                        pack_format,
+                        patch_for: Vec::new(),
+                        compatible_mc_versions: pack_format.and_then(compatible_mc_versions),
+                    })
+                }
+                PackEntry::Dir(entry_path, file_name_str) => {
+                    debug!("Processing directory: {}", file_name_str);
+                    let size = calculate_dir_size(entry_path);
+                    let (description, icon_data, pack_format) =
+                        extract_pack_metadata_from_dir(entry_path);
+
+                    Some(PackMeta {
+                        id: file_name_str.clone(),
+                        name: file_name_str.clone(),
+                        path: entry_path.to_string_lossy().to_string(),
+                        size,
+                        is_zip: false,
+                        description,
+                        icon_data,
+                        pack_format,
+                        patch_for: Vec::new(),
+                        compatible_mc_versions: pack_format.and_then(compatible_mc_versions),
+                    })
+                }
+            })
+            .inspect(|pack| {
+                let count = scanned.fetch_add(1, Ordering::Relaxed) + 1;
+                if let Some(progress) = progress {
+                    progress(count, total, Some(&pack.name));
+                }
+            })
+            .collect()
+    });
 
     // Sort packs by name for consistent ordering
     let mut sorted_packs = packs;
     sorted_packs.sort_by(|a, b| a.name.cmp(&b.name));
 
-    println!("[scan_packs] Found {} packs total:", sorted_packs.len());
+    info!("Found {} packs total", sorted_packs.len());
     for pack in &sorted_packs {
-        println!("[scan_packs]   - {} (is_zip: {})", pack.name, pack.is_zip);
+        debug!("  - {} (is_zip: {})", pack.name, pack.is_zip);
     }
 
     Ok(sorted_packs)
 }
 
+/// List every file in a pack as a '/'-separated path relative to the pack root
+pub fn list_pack_files(pack: &PackMeta) -> Result<Vec<String>> {
+    if pack.is_zip {
+        return crate::util::zip::list_zip_files(&pack.path);
+    }
+
+    Ok(list_folder_files(Path::new(&pack.path)))
+}
+
+/// List every file under a folder, as '/'-separated paths relative to it
+pub fn list_folder_files(root: &Path) -> Vec<String> {
+    WalkDir::new(root)
+        .into_iter()
+        .filter_map(|e| e.ok())
+        .filter(|e| e.file_type().is_file())
+        .filter_map(|e| {
+            e.path()
+                .strip_prefix(root)
+                .ok()
+                .map(|p| p.to_string_lossy().replace('\\', "/"))
+        })
+        .collect()
+}
+
 /// Calculate total size of a directory recursively
 fn calculate_dir_size(path: &Path) -> u64 {
     WalkDir::new(path)
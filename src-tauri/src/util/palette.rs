@@ -0,0 +1,369 @@
+/// Pack color palettes
+///
+/// Samples a pack's block textures and extracts its dominant colors plus a
+/// few overall "mood" figures (average color, brightness, saturation), so
+/// packs can be compared at a glance before merging them.
+use crate::model::PackMeta;
+use crate::util::animation::read_pack_bytes;
+use crate::util::{pack_scanner, parallelism};
+use anyhow::Result;
+use rayon::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::cmp::Ordering;
+use std::collections::HashMap;
+
+/// Maximum block textures decoded per pack
+const MAX_SAMPLED_TEXTURES: usize = 256;
+
+/// Number of dominant colors kept per palette
+const PALETTE_SIZE: usize = 8;
+
+/// Bits kept per channel when bucketing similar colors
+const QUANTIZE_BITS: u32 = 4;
+
+/// One dominant color
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PaletteColor {
+    /// "#rrggbb"
+    pub hex: String,
+    pub rgb: [u8; 3],
+    /// Share of sampled pixels (0.0 - 1.0)
+    pub weight: f32,
+}
+
+/// Color palette of one pack
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PackPalette {
+    pub pack_id: String,
+    pub pack_name: String,
+    /// Dominant colors, most common first
+    pub colors: Vec<PaletteColor>,
+    /// Average color of all sampled pixels ("#rrggbb")
+    pub average_color: String,
+    /// Mean perceived brightness (0.0 - 1.0)
+    pub brightness: f32,
+    /// Mean saturation (0.0 - 1.0)
+    pub saturation: f32,
+    pub textures_sampled: usize,
+}
+
+/// The pack closest in color to another
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StyleMatch {
+    pub pack_id: String,
+    pub pack_name: String,
+    /// Palette similarity (0.0 - 1.0)
+    pub similarity: f32,
+}
+
+/// Running color totals for one quantized bucket
+#[derive(Default, Clone, Copy)]
+struct Bucket {
+    sum: [u64; 3],
+    count: u64,
+}
+
+impl Bucket {
+    fn add(&mut self, rgb: [u8; 3]) {
+        for (total, channel) in self.sum.iter_mut().zip(rgb.iter()) {
+            *total += *channel as u64;
+        }
+        self.count += 1;
+    }
+
+    fn merge(&mut self, other: &Bucket) {
+        for (total, channel) in self.sum.iter_mut().zip(other.sum.iter()) {
+            *total += channel;
+        }
+        self.count += other.count;
+    }
+
+    fn average(&self) -> [u8; 3] {
+        if self.count == 0 {
+            return [0, 0, 0];
+        }
+        self.sum.map(|total| (total / self.count) as u8)
+    }
+}
+
+/// Color statistics for a set of pixels
+#[derive(Default)]
+struct ColorStats {
+    buckets: HashMap<u16, Bucket>,
+    brightness_sum: f64,
+    saturation_sum: f64,
+}
+
+impl ColorStats {
+    fn add_pixel(&mut self, rgb: [u8; 3]) {
+        let shift = 8 - QUANTIZE_BITS;
+        let key = ((rgb[0] as u16 >> shift) << (2 * QUANTIZE_BITS))
+            | ((rgb[1] as u16 >> shift) << QUANTIZE_BITS)
+            | (rgb[2] as u16 >> shift);
+        self.buckets.entry(key).or_default().add(rgb);
+
+        let [r, g, b] = rgb.map(|c| c as f64 / 255.0);
+        self.brightness_sum += 0.299 * r + 0.587 * g + 0.114 * b;
+        let max = r.max(g).max(b);
+        let min = r.min(g).min(b);
+        if max > 0.0 {
+            self.saturation_sum += (max - min) / max;
+        }
+    }
+
+    fn merge(mut self, other: ColorStats) -> ColorStats {
+        for (key, bucket) in &other.buckets {
+            self.buckets.entry(*key).or_default().merge(bucket);
+        }
+        self.brightness_sum += other.brightness_sum;
+        self.saturation_sum += other.saturation_sum;
+        self
+    }
+
+    fn pixel_count(&self) -> u64 {
+        self.buckets.values().map(|b| b.count).sum()
+    }
+}
+
+/// Format a color as "#rrggbb"
+pub fn rgb_to_hex(rgb: [u8; 3]) -> String {
+    format!("#{:02x}{:02x}{:02x}", rgb[0], rgb[1], rgb[2])
+}
+
+/// Accumulate the mostly-opaque pixels of an image
+fn sample_image(image: &image::RgbaImage) -> ColorStats {
+    let mut stats = ColorStats::default();
+    for pixel in image.pixels().filter(|p| p[3] >= 128) {
+        stats.add_pixel([pixel[0], pixel[1], pixel[2]]);
+    }
+    stats
+}
+
+/// Turn accumulated statistics into a palette
+fn build_palette(pack: &PackMeta, stats: &ColorStats, textures_sampled: usize) -> PackPalette {
+    let total = stats.pixel_count();
+    let mut buckets: Vec<&Bucket> = stats.buckets.values().collect();
+    buckets.sort_by(|a, b| b.count.cmp(&a.count).then(a.sum.cmp(&b.sum)));
+
+    let colors = buckets
+        .iter()
+        .take(PALETTE_SIZE)
+        .map(|bucket| {
+            let rgb = bucket.average();
+            PaletteColor {
+                hex: rgb_to_hex(rgb),
+                rgb,
+                weight: bucket.count as f32 / total as f32,
+            }
+        })
+        .collect();
+
+    let mut overall = Bucket::default();
+    for bucket in stats.buckets.values() {
+        overall.merge(bucket);
+    }
+    let pixels = total.max(1) as f64;
+
+    PackPalette {
+        pack_id: pack.id.clone(),
+        pack_name: pack.name.clone(),
+        colors,
+        average_color: rgb_to_hex(overall.average()),
+        brightness: (stats.brightness_sum / pixels) as f32,
+        saturation: (stats.saturation_sum / pixels) as f32,
+        textures_sampled,
+    }
+}
+
+/// Extract a pack's palette from an evenly spaced sample of block textures
+pub fn extract_pack_palette(pack: &PackMeta) -> Result<PackPalette> {
+    let mut textures: Vec<String> = pack_scanner::list_pack_files(pack)?
+        .into_iter()
+        .filter(|f| {
+            f.starts_with("assets/") && f.contains("/textures/block/") && f.ends_with(".png")
+        })
+        .collect();
+    textures.sort();
+
+    let step = (textures.len() / MAX_SAMPLED_TEXTURES).max(1);
+    let sampled: Vec<&String> = textures
+        .iter()
+        .step_by(step)
+        .take(MAX_SAMPLED_TEXTURES)
+        .collect();
+
+    let per_texture: Vec<ColorStats> = parallelism::with_cpu_pool(|| {
+        sampled
+            .par_iter()
+            .filter_map(|file| {
+                let bytes = read_pack_bytes(&pack.path, pack.is_zip, file).ok()?;
+                let image = image::load_from_memory(&bytes).ok()?.to_rgba8();
+                Some(sample_image(&image))
+            })
+            .collect()
+    });
+    let textures_sampled = per_texture.len();
+    let stats = per_texture
+        .into_iter()
+        .fold(ColorStats::default(), ColorStats::merge);
+
+    Ok(build_palette(pack, &stats, textures_sampled))
+}
+
+/// Similarity of two palettes (1.0 = identical, 0.0 = opposite)
+///
+/// Each color of one palette is matched to its nearest color in the other,
+/// weighted by how common it is, in both directions.
+pub fn palette_similarity(a: &PackPalette, b: &PackPalette) -> f32 {
+    fn distance(x: [u8; 3], y: [u8; 3]) -> f32 {
+        let sum: f32 = x
+            .iter()
+            .zip(y.iter())
+            .map(|(p, q)| (*p as f32 - *q as f32).powi(2))
+            .sum();
+        sum.sqrt() / (3.0f32.sqrt() * 255.0)
+    }
+
+    fn directed(from: &PackPalette, to: &PackPalette) -> f32 {
+        let total: f32 = from.colors.iter().map(|c| c.weight).sum();
+        if total <= 0.0 || to.colors.is_empty() {
+            return 1.0;
+        }
+        from.colors
+            .iter()
+            .map(|c| {
+                let nearest = to
+                    .colors
+                    .iter()
+                    .map(|d| distance(c.rgb, d.rgb))
+                    .fold(f32::MAX, f32::min);
+                nearest * c.weight
+            })
+            .sum::<f32>()
+            / total
+    }
+
+    1.0 - (directed(a, b) + directed(b, a)) / 2.0
+}
+
+/// The most similar other pack for each pack, by palette
+///
+/// Packs without sampled textures neither get nor make a match.
+///
+/// # Returns
+/// Pack ID -> closest pack
+pub fn closest_styles(palettes: &[PackPalette]) -> HashMap<String, StyleMatch> {
+    let sampled: Vec<&PackPalette> = palettes.iter().filter(|p| p.textures_sampled > 0).collect();
+
+    sampled
+        .iter()
+        .filter_map(|palette| {
+            let (other, similarity) = sampled
+                .iter()
+                .filter(|other| other.pack_id != palette.pack_id)
+                .map(|other| (other, palette_similarity(palette, other)))
+                .max_by(|a, b| a.1.partial_cmp(&b.1).unwrap_or(Ordering::Equal))?;
+            Some((
+                palette.pack_id.clone(),
+                StyleMatch {
+                    pack_id: other.pack_id.clone(),
+                    pack_name: other.pack_name.clone(),
+                    similarity,
+                },
+            ))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use image::{Rgba, RgbaImage};
+    use std::fs;
+
+    fn pack_at(id: &str, path: &str) -> PackMeta {
+        PackMeta {
+            id: id.to_string(),
+            name: id.to_string(),
+            path: path.to_string(),
+            ..Default::default()
+        }
+    }
+
+    fn palette_of(id: &str, colors: &[([u8; 3], f32)]) -> PackPalette {
+        PackPalette {
+            pack_id: id.to_string(),
+            pack_name: id.to_string(),
+            colors: colors
+                .iter()
+                .map(|(rgb, weight)| PaletteColor {
+                    hex: rgb_to_hex(*rgb),
+                    rgb: *rgb,
+                    weight: *weight,
+                })
+                .collect(),
+            average_color: "#000000".to_string(),
+            brightness: 0.0,
+            saturation: 0.0,
+            textures_sampled: 1,
+        }
+    }
+
+    #[test]
+    fn test_extract_pack_palette() {
+        let temp_dir = std::env::temp_dir().join("test_palette");
+        let blocks = temp_dir.join("assets/minecraft/textures/block");
+        fs::create_dir_all(&blocks).expect("Failed to create test directory");
+        let mut grass = RgbaImage::from_pixel(4, 4, Rgba([40, 160, 40, 255]));
+        for x in 0..4 {
+            grass.put_pixel(x, 0, Rgba([120, 80, 40, 255]));
+            // Transparent pixels are ignored
+            grass.put_pixel(x, 1, Rgba([255, 255, 255, 0]));
+        }
+        grass
+            .save(blocks.join("grass.png"))
+            .expect("Failed to write image");
+
+        let palette = extract_pack_palette(&pack_at("green", &temp_dir.to_string_lossy()));
+
+        // Clean up
+        fs::remove_dir_all(&temp_dir).ok();
+
+        let palette = palette.expect("palette extraction should succeed");
+        assert_eq!(palette.textures_sampled, 1);
+        assert_eq!(palette.colors.len(), 2);
+        assert_eq!(palette.colors[0].hex, "#28a028");
+        assert!((palette.colors[0].weight - 8.0 / 12.0).abs() < 1e-6);
+        assert!(palette.saturation > 0.5);
+    }
+
+    #[test]
+    fn test_palette_similarity() {
+        let green = palette_of("green", &[([40, 160, 40], 0.7), ([120, 80, 40], 0.3)]);
+        let similar = palette_of("similar", &[([45, 150, 45], 0.6), ([110, 85, 40], 0.4)]);
+        let pink = palette_of("pink", &[([250, 120, 200], 1.0)]);
+
+        assert!((palette_similarity(&green, &green) - 1.0).abs() < 1e-6);
+        assert!(palette_similarity(&green, &similar) > 0.9);
+        assert!(palette_similarity(&green, &pink) < palette_similarity(&green, &similar));
+    }
+
+    #[test]
+    fn test_closest_styles() {
+        let green = palette_of("green", &[([40, 160, 40], 0.7), ([120, 80, 40], 0.3)]);
+        let similar = palette_of("similar", &[([45, 150, 45], 0.6), ([110, 85, 40], 0.4)]);
+        let pink = palette_of("pink", &[([250, 120, 200], 1.0)]);
+        let mut empty = palette_of("empty", &[]);
+        empty.textures_sampled = 0;
+
+        let matches = closest_styles(&[green, similar, pink, empty]);
+
+        assert_eq!(matches["green"].pack_id, "similar");
+        assert_eq!(matches["similar"].pack_id, "green");
+        assert_eq!(matches["pink"].pack_name, "similar");
+        assert!(!matches.contains_key("empty"));
+    }
+}
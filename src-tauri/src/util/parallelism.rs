@@ -0,0 +1,311 @@
+/// Thread pool configuration
+///
+/// CPU-bound work (decoding and processing images) runs on the CPU pool;
+/// file-heavy pipelines (scanning, indexing, hashing, copying, extraction)
+/// run on a separate IO pool. A spinning disk thrashes when dozens of threads seek at once, so the
+/// IO pool defaults to a couple of threads on HDDs and scales with the CPU
+/// count on SSDs. Both sizes can be overridden in settings and take effect
+/// on the next start, since rayon pools can't be resized.
+use once_cell::sync::OnceCell;
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use tracing::info;
+
+/// Upper bound for configured thread counts
+pub const MAX_THREADS: usize = 256;
+
+/// IO threads used on spinning disks
+const HDD_IO_THREADS: usize = 2;
+
+/// IO threads used when the drive type is unknown
+const UNKNOWN_DRIVE_IO_THREADS: usize = 4;
+
+static CPU_POOL: OnceCell<rayon::ThreadPool> = OnceCell::new();
+static IO_POOL: OnceCell<rayon::ThreadPool> = OnceCell::new();
+static ACTIVE: OnceCell<EffectiveParallelism> = OnceCell::new();
+
+/// User overrides; None means "use the detected default"
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct ParallelismSettings {
+    /// Threads for CPU-bound work
+    pub thread_count: Option<usize>,
+    /// Threads for file-heavy pipelines
+    pub io_parallelism: Option<usize>,
+}
+
+/// Storage type of the drive holding the packs
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum DriveKind {
+    Ssd,
+    Hdd,
+    Unknown,
+}
+
+/// Thread counts in effect
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EffectiveParallelism {
+    pub thread_count: usize,
+    pub io_parallelism: usize,
+    pub cpu_count: usize,
+    pub drive_kind: DriveKind,
+}
+
+/// Saved settings alongside what is running and what would be detected
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ParallelismStatus {
+    pub settings: ParallelismSettings,
+    /// Thread counts in effect (None if the pools weren't configured yet)
+    pub active: Option<EffectiveParallelism>,
+    /// Thread counts the saved settings resolve to on the next start
+    pub next_start: EffectiveParallelism,
+}
+
+/// Number of logical CPUs
+pub fn cpu_count() -> usize {
+    std::thread::available_parallelism()
+        .map(|n| n.get())
+        .unwrap_or(UNKNOWN_DRIVE_IO_THREADS)
+}
+
+/// Default IO parallelism for a drive type
+pub fn default_io_parallelism(cpu_count: usize, drive_kind: DriveKind) -> usize {
+    match drive_kind {
+        DriveKind::Ssd => cpu_count,
+        DriveKind::Hdd => HDD_IO_THREADS,
+        DriveKind::Unknown => cpu_count.min(UNKNOWN_DRIVE_IO_THREADS),
+    }
+    .max(1)
+}
+
+/// Combine user overrides with detected defaults
+pub fn resolve_parallelism(
+    settings: &ParallelismSettings,
+    cpu_count: usize,
+    drive_kind: DriveKind,
+) -> EffectiveParallelism {
+    let clamp = |n: usize| n.clamp(1, MAX_THREADS);
+
+    EffectiveParallelism {
+        thread_count: clamp(settings.thread_count.unwrap_or(cpu_count)),
+        io_parallelism: clamp(
+            settings
+                .io_parallelism
+                .unwrap_or_else(|| default_io_parallelism(cpu_count, drive_kind)),
+        ),
+        cpu_count,
+        drive_kind,
+    }
+}
+
+/// Find the device mounted at the deepest mount point containing `path`
+///
+/// # Arguments
+/// * `mounts` - Contents of /proc/mounts
+fn mount_device_for(mounts: &str, path: &Path) -> Option<String> {
+    mounts
+        .lines()
+        .filter_map(|line| {
+            let mut fields = line.split_whitespace();
+            let device = fields.next()?;
+            // Spaces in mount points are escaped as \040
+            let mount_point = fields.next()?.replace("\\040", " ");
+            Some((device, mount_point))
+        })
+        .filter(|(device, mount_point)| {
+            device.starts_with("/dev/") && path.starts_with(mount_point)
+        })
+        .max_by_key(|(_, mount_point)| mount_point.len())
+        .map(|(device, _)| device.to_string())
+}
+
+/// Guess whether a path lives on an SSD or a spinning disk
+///
+/// Only Linux exposes this without extra privileges (via sysfs); other
+/// platforms report Unknown.
+pub fn detect_drive_kind(path: &Path) -> DriveKind {
+    if !cfg!(target_os = "linux") {
+        return DriveKind::Unknown;
+    }
+
+    let path = match path.canonicalize() {
+        Ok(path) => path,
+        Err(_) => return DriveKind::Unknown,
+    };
+    let mounts = match std::fs::read_to_string("/proc/mounts") {
+        Ok(mounts) => mounts,
+        Err(_) => return DriveKind::Unknown,
+    };
+    let device = match mount_device_for(&mounts, &path) {
+        Some(device) => device,
+        None => return DriveKind::Unknown,
+    };
+
+    // Resolve /dev/mapper/* and /dev/disk/by-* links to the kernel name
+    let device = Path::new(&device)
+        .canonicalize()
+        .unwrap_or_else(|_| device.into());
+    let name = match device.file_name() {
+        Some(name) => name.to_string_lossy().to_string(),
+        None => return DriveKind::Unknown,
+    };
+
+    // Partitions (sda1, nvme0n1p2) report through their parent disk
+    let sys_block = Path::new("/sys/class/block").join(&name);
+    let disk = if sys_block.join("partition").exists() {
+        sys_block
+            .canonicalize()
+            .ok()
+            .and_then(|p| p.parent().map(Path::to_path_buf))
+    } else {
+        Some(sys_block)
+    };
+
+    match disk.and_then(|d| std::fs::read_to_string(d.join("queue/rotational")).ok()) {
+        Some(flag) if flag.trim() == "1" => DriveKind::Hdd,
+        Some(flag) if flag.trim() == "0" => DriveKind::Ssd,
+        _ => DriveKind::Unknown,
+    }
+}
+
+/// Configure the global, CPU and IO thread pools
+///
+/// Must run before any parallel work; later calls keep the first
+/// configuration.
+///
+/// # Arguments
+/// * `settings` - User overrides
+/// * `packs_path` - Path used to detect the drive type
+pub fn init_parallelism(
+    settings: &ParallelismSettings,
+    packs_path: Option<&Path>,
+) -> &'static EffectiveParallelism {
+    ACTIVE.get_or_init(|| {
+        let drive_kind = packs_path.map_or(DriveKind::Unknown, detect_drive_kind);
+        let effective = resolve_parallelism(settings, cpu_count(), drive_kind);
+
+        if let Err(e) = rayon::ThreadPoolBuilder::new()
+            .num_threads(effective.thread_count)
+            .build_global()
+        {
+            info!("Global pool already configured: {}", e);
+        }
+        CPU_POOL.get_or_init(|| build_pool("cpu", effective.thread_count));
+        IO_POOL.get_or_init(|| build_pool("io", effective.io_parallelism));
+
+        info!(
+            "{} CPU threads, {} IO threads ({:?} drive)",
+            effective.thread_count, effective.io_parallelism, effective.drive_kind
+        );
+        effective
+    })
+}
+
+/// Thread counts in effect, if the pools have been configured
+pub fn active_parallelism() -> Option<&'static EffectiveParallelism> {
+    ACTIVE.get()
+}
+
+fn build_pool(kind: &'static str, threads: usize) -> rayon::ThreadPool {
+    rayon::ThreadPoolBuilder::new()
+        .num_threads(threads)
+        .thread_name(move |i| format!("weaverbird-{}-{}", kind, i))
+        .build()
+        .unwrap_or_else(|e| panic!("Failed to build {} thread pool: {}", kind, e))
+}
+
+/// Run CPU-bound work on the CPU pool
+///
+/// Parallel iterators inside `op` use the CPU pool's threads, even when
+/// called from the IO pool. Falls back to one thread per CPU if the pools
+/// haven't been configured.
+pub fn with_cpu_pool<R, F>(op: F) -> R
+where
+    F: FnOnce() -> R + Send,
+    R: Send,
+{
+    CPU_POOL
+        .get_or_init(|| build_pool("cpu", cpu_count()))
+        .install(op)
+}
+
+/// Run a file-heavy pipeline on the IO pool
+///
+/// Parallel iterators inside `op` use the IO pool's threads. Falls back to
+/// the unknown-drive default if the pools haven't been configured.
+pub fn with_io_pool<R, F>(op: F) -> R
+where
+    F: FnOnce() -> R + Send,
+    R: Send,
+{
+    IO_POOL
+        .get_or_init(|| {
+            build_pool(
+                "io",
+                default_io_parallelism(cpu_count(), DriveKind::Unknown),
+            )
+        })
+        .install(op)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_resolve_parallelism_defaults() {
+        let defaults = ParallelismSettings::default();
+
+        let ssd = resolve_parallelism(&defaults, 12, DriveKind::Ssd);
+        let hdd = resolve_parallelism(&defaults, 12, DriveKind::Hdd);
+        let unknown = resolve_parallelism(&defaults, 12, DriveKind::Unknown);
+
+        assert_eq!(ssd.thread_count, 12);
+        assert_eq!(ssd.io_parallelism, 12);
+        assert_eq!(hdd.io_parallelism, 2);
+        assert_eq!(unknown.io_parallelism, 4);
+    }
+
+    #[test]
+    fn test_resolve_parallelism_overrides_are_clamped() {
+        let settings = ParallelismSettings {
+            thread_count: Some(0),
+            io_parallelism: Some(10_000),
+        };
+
+        let effective = resolve_parallelism(&settings, 8, DriveKind::Hdd);
+
+        assert_eq!(effective.thread_count, 1);
+        assert_eq!(effective.io_parallelism, MAX_THREADS);
+    }
+
+    #[test]
+    fn test_mount_device_for() {
+        let mounts = "proc /proc proc rw 0 0\n\
+                      /dev/nvme0n1p2 / ext4 rw 0 0\n\
+                      /dev/sdb1 /mnt/games\\040drive ext4 rw 0 0\n\
+                      tmpfs /mnt/games\\040drive/tmp tmpfs rw 0 0\n";
+
+        assert_eq!(
+            mount_device_for(mounts, Path::new("/home/user/packs")),
+            Some("/dev/nvme0n1p2".to_string())
+        );
+        assert_eq!(
+            mount_device_for(mounts, Path::new("/mnt/games drive/tmp/packs")),
+            Some("/dev/sdb1".to_string())
+        );
+        assert_eq!(mount_device_for("", Path::new("/")), None);
+    }
+
+    #[test]
+    fn test_with_io_pool_runs_parallel_work() {
+        use rayon::prelude::*;
+
+        let sum: u32 = with_io_pool(|| (1..=10u32).into_par_iter().sum());
+
+        assert_eq!(sum, 55);
+    }
+}
@@ -0,0 +1,284 @@
+/// Sandboxed path policy for filesystem commands
+///
+/// Commands receive arbitrary paths from the webview. Every path-taking command
+/// checks its inputs against a set of registered roots before touching the
+/// filesystem: Weaverbird's own cache/config directories, detected launcher
+/// directories, saved projects, and folders the user explicitly picked through
+/// the native folder dialog. User grants are persisted in the settings file.
+use crate::util::{launcher_detection, mc_paths, settings};
+use anyhow::{anyhow, Result};
+use once_cell::sync::Lazy;
+use std::ffi::OsString;
+use std::path::{Component, Path, PathBuf};
+use std::sync::Mutex;
+use tracing::info;
+
+/// Global policy, built lazily on first use
+static POLICY: Lazy<Mutex<Option<PathPolicy>>> = Lazy::new(|| Mutex::new(None));
+
+/// A set of directory roots that paths must fall under
+#[derive(Debug, Clone, Default)]
+pub struct PathPolicy {
+    roots: Vec<PathBuf>,
+    /// Paths refused even though a root covers them
+    denied: Vec<PathBuf>,
+}
+
+impl PathPolicy {
+    /// Create an empty policy that allows nothing
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Allow access to a directory and everything below it
+    pub fn allow(&mut self, root: &Path) {
+        if let Some(root) = normalize_path(root) {
+            if !self.roots.contains(&root) {
+                self.roots.push(root);
+            }
+        }
+    }
+
+    /// Refuse one path (not what's below it) inside an allowed root
+    pub fn deny(&mut self, path: &Path) {
+        if let Some(path) = normalize_path(path) {
+            if !self.denied.contains(&path) {
+                self.denied.push(path);
+            }
+        }
+    }
+
+    /// Check whether a path falls under one of the allowed roots and isn't denied
+    pub fn is_allowed(&self, path: &Path) -> bool {
+        match normalize_path(path) {
+            Some(path) => {
+                !self.denied.contains(&path) && self.roots.iter().any(|root| path.starts_with(root))
+            }
+            None => false,
+        }
+    }
+
+    /// All allowed roots
+    pub fn roots(&self) -> &[PathBuf] {
+        &self.roots
+    }
+}
+
+/// Resolve a path to an absolute, symlink-free form
+///
+/// Paths that don't exist yet (e.g. a new output directory) are resolved via
+/// their nearest existing ancestor. Relative paths and paths that try to climb
+/// out through `..` after the existing ancestor are rejected.
+pub fn normalize_path(path: &Path) -> Option<PathBuf> {
+    if !path.is_absolute() {
+        return None;
+    }
+
+    let mut existing = path;
+    let mut missing: Vec<OsString> = Vec::new();
+
+    loop {
+        if let Ok(canonical) = existing.canonicalize() {
+            let mut resolved = canonical;
+            for part in missing.iter().rev() {
+                resolved.push(part);
+            }
+            return Some(resolved);
+        }
+
+        match existing.components().next_back() {
+            Some(Component::Normal(name)) => missing.push(name.to_os_string()),
+            _ => return None,
+        }
+        existing = existing.parent()?;
+    }
+}
+
+/// Build the default policy from known application and launcher directories
+fn default_policy() -> PathPolicy {
+    let mut policy = PathPolicy::new();
+
+    if let Some(cache_dir) = dirs::cache_dir() {
+        policy.allow(&cache_dir.join("weaverbird"));
+        // Extracted ZIP textures live in Tauri's cache directory
+        policy.allow(&cache_dir.join("weaverbird_textures"));
+    }
+    if let Ok(config_dir) = settings::get_config_dir() {
+        policy.allow(&config_dir);
+        // The settings file holds the grants and build hooks, so a command
+        // writing there could widen its own access
+        policy.deny(&config_dir);
+        if let Ok(settings_path) = settings::get_settings_path() {
+            policy.deny(&settings_path);
+        }
+    }
+    if let Ok(minecraft_dir) = mc_paths::get_default_minecraft_dir() {
+        policy.allow(&minecraft_dir);
+    }
+    for launcher in launcher_detection::detect_all_launchers() {
+        if launcher.found {
+            policy.allow(Path::new(&launcher.minecraft_dir));
+        }
+    }
+
+    if let Ok(settings) = settings::load_settings() {
+        for granted in &settings.granted_paths {
+            policy.allow(Path::new(granted));
+        }
+        for project in &settings.projects {
+            policy.allow(Path::new(&project.packs_dir));
+            policy.allow(Path::new(&project.output_dir));
+        }
+    }
+
+    // Mock models the dev frontend reads from the working directory
+    if cfg!(debug_assertions) {
+        if let Ok(current_dir) = std::env::current_dir() {
+            policy.allow(&current_dir.join("__mocks__"));
+        }
+    }
+    #[cfg(test)]
+    policy.allow(&std::env::temp_dir());
+
+    info!("Initialized with {} allowed roots", policy.roots().len());
+
+    policy
+}
+
+/// Run a closure against the global policy, initializing it if needed
+fn with_policy<T>(f: impl FnOnce(&mut PathPolicy) -> T) -> T {
+    let mut guard = POLICY.lock().unwrap_or_else(|e| e.into_inner());
+    let policy = guard.get_or_insert_with(default_policy);
+    f(policy)
+}
+
+/// Check that a path is inside an allowed root
+pub fn check_path(path: &str) -> Result<()> {
+    check(Path::new(path))
+}
+
+/// `check_path` for a `Path`
+///
+/// The file helpers in `file_retry` and `zip` run this before touching the
+/// filesystem, so a command can't get around the policy by skipping
+/// validation in its wrapper.
+pub fn check(path: &Path) -> Result<()> {
+    if with_policy(|policy| policy.is_allowed(path)) {
+        Ok(())
+    } else {
+        Err(anyhow!(
+            "Access denied: {} is outside the allowed locations",
+            path.display()
+        ))
+    }
+}
+
+/// Allow a root for the rest of this session without persisting it
+pub fn register_root(path: &Path) {
+    with_policy(|policy| policy.allow(path));
+}
+
+/// Grant access to a user-selected directory and persist the grant
+pub fn grant_path(path: &Path) -> Result<()> {
+    let normalized = normalize_path(path).ok_or_else(|| anyhow!("Invalid path: {:?}", path))?;
+    let normalized_str = normalized.to_string_lossy().to_string();
+
+    let _settings_lock = settings::lock_settings();
+    let mut settings = settings::load_settings()?;
+    if !settings.granted_paths.contains(&normalized_str) {
+        settings.granted_paths.push(normalized_str);
+        settings::save_settings(&settings)?;
+    }
+
+    register_root(&normalized);
+    Ok(())
+}
+
+/// Revoke a persisted grant; the policy is rebuilt on next use
+pub fn revoke_path(path: &str) -> Result<()> {
+    let _settings_lock = settings::lock_settings();
+    let mut settings = settings::load_settings()?;
+    settings.granted_paths.retain(|p| p != path);
+    settings::save_settings(&settings)?;
+
+    *POLICY.lock().unwrap_or_else(|e| e.into_inner()) = None;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    #[test]
+    fn test_policy_allows_paths_under_root() {
+        let temp_dir = std::env::temp_dir().join("test_path_policy_root");
+        fs::create_dir_all(temp_dir.join("packs")).expect("Failed to create test directory");
+
+        let mut policy = PathPolicy::new();
+        policy.allow(&temp_dir.join("packs"));
+
+        let allowed = policy.is_allowed(&temp_dir.join("packs/pack.zip"));
+        let not_yet_created = policy.is_allowed(&temp_dir.join("packs/new/output"));
+        let sibling = policy.is_allowed(&temp_dir.join("other"));
+        let escape = policy.is_allowed(&temp_dir.join("packs/../other"));
+        let missing_escape = policy.is_allowed(&temp_dir.join("packs/missing/../../other"));
+
+        // Clean up
+        fs::remove_dir_all(&temp_dir).ok();
+
+        assert!(allowed);
+        assert!(not_yet_created);
+        assert!(!sibling);
+        assert!(!escape);
+        assert!(!missing_escape);
+    }
+
+    #[test]
+    fn test_policy_denies_paths_inside_root() {
+        let temp_dir = std::env::temp_dir().join("test_path_policy_denied");
+        fs::create_dir_all(&temp_dir).expect("Failed to create test directory");
+        fs::write(temp_dir.join("settings.json"), "{}").expect("Failed to write test file");
+
+        let mut policy = PathPolicy::new();
+        policy.allow(&temp_dir);
+        policy.deny(&temp_dir);
+        policy.deny(&temp_dir.join("settings.json"));
+
+        let settings = policy.is_allowed(&temp_dir.join("settings.json"));
+        let climbing = policy.is_allowed(&temp_dir.join("activity/../settings.json"));
+        let root = policy.is_allowed(&temp_dir);
+        let sibling = policy.is_allowed(&temp_dir.join("activity/log.jsonl"));
+
+        // Clean up
+        fs::remove_dir_all(&temp_dir).ok();
+
+        assert!(!settings);
+        assert!(!climbing);
+        assert!(!root);
+        assert!(sibling);
+    }
+
+    #[test]
+    fn test_settings_file_write_rejected() {
+        let settings_path = settings::get_settings_path().expect("config dir should resolve");
+
+        assert!(check(&settings_path).is_err());
+        assert!(check(settings_path.parent().unwrap()).is_err());
+    }
+
+    #[test]
+    fn test_policy_rejects_relative_paths() {
+        let mut policy = PathPolicy::new();
+        policy.allow(&std::env::temp_dir());
+
+        assert!(!policy.is_allowed(Path::new("relative/path")));
+        assert!(normalize_path(Path::new("relative")).is_none());
+    }
+
+    #[test]
+    fn test_empty_policy_denies_everything() {
+        let policy = PathPolicy::new();
+        assert!(!policy.is_allowed(&std::env::temp_dir()));
+    }
+}
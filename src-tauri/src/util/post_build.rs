@@ -0,0 +1,258 @@
+/// Post-build hooks
+///
+/// Configurable actions executed by the backend after a successful build:
+/// revealing the output in the file manager, copying the output path or its
+/// SHA1 to the clipboard, or running a user-specified shell command (e.g.
+/// rsync to a server). Hook results are returned as build log lines.
+use crate::util::file_manager::reveal_in_file_manager;
+use anyhow::{anyhow, Context, Result};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::io::Write;
+use std::path::Path;
+use std::process::{Command, Stdio};
+use tracing::info;
+use walkdir::WalkDir;
+
+/// An action to run after a build completes
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum PostBuildHook {
+    /// Open the output folder in the system file manager
+    RevealOutput,
+    /// Copy the output path to the clipboard
+    CopyOutputPath,
+    /// Copy the SHA1 of the output to the clipboard
+    CopySha1,
+    /// Run a shell command in the output directory
+    ///
+    /// The output path is also exposed as `WEAVERBIRD_OUTPUT_DIR`.
+    RunCommand { command: String },
+}
+
+/// Run all hooks in order, returning log lines describing each result
+///
+/// A failing hook is logged and doesn't prevent later hooks from running.
+pub fn run_post_build_hooks(hooks: &[PostBuildHook], output_dir: &Path) -> Vec<String> {
+    let mut log = Vec::new();
+
+    for hook in hooks {
+        match run_hook(hook, output_dir) {
+            Ok(lines) => log.extend(lines),
+            Err(e) => log.push(format!("Post-build hook failed ({:?}): {:#}", hook, e)),
+        }
+    }
+
+    for line in &log {
+        info!("{}", line);
+    }
+
+    log
+}
+
+/// Run a single hook
+fn run_hook(hook: &PostBuildHook, output_dir: &Path) -> Result<Vec<String>> {
+    match hook {
+        PostBuildHook::RevealOutput => {
+            reveal_in_file_manager(output_dir)?;
+            Ok(vec![format!("Revealed {}", output_dir.display())])
+        }
+        PostBuildHook::CopyOutputPath => {
+            let path = output_dir.to_string_lossy().to_string();
+            copy_to_clipboard(&path)?;
+            Ok(vec![format!("Copied output path: {}", path)])
+        }
+        PostBuildHook::CopySha1 => {
+            let hash = hash_output_sha1(output_dir)?;
+            copy_to_clipboard(&hash)?;
+            Ok(vec![format!("Copied SHA1: {}", hash)])
+        }
+        PostBuildHook::RunCommand { command } => run_shell_command(command, output_dir),
+    }
+}
+
+/// Compute the SHA1 of a build output
+///
+/// For a file this is the plain file hash. For a directory, every file's
+/// relative path and contents are hashed in sorted order so the result is
+/// stable across runs.
+pub fn hash_output_sha1(output: &Path) -> Result<String> {
+    let mut hasher = sha1_smol::Sha1::new();
+
+    if output.is_file() {
+        hasher.update(&fs::read(output).context("Failed to read output file")?);
+        return Ok(hasher.digest().to_string());
+    }
+
+    let mut files: Vec<_> = WalkDir::new(output)
+        .into_iter()
+        .filter_map(|e| e.ok())
+        .filter(|e| e.file_type().is_file())
+        .map(|e| e.into_path())
+        .collect();
+    files.sort();
+
+    for file in files {
+        let rel = file.strip_prefix(output).unwrap_or(&file);
+        hasher.update(rel.to_string_lossy().replace('\\', "/").as_bytes());
+        hasher.update(&[0]);
+        hasher.update(&fs::read(&file).with_context(|| format!("Failed to read {:?}", file))?);
+    }
+
+    Ok(hasher.digest().to_string())
+}
+
+/// Run a user-specified shell command with output captured
+fn run_shell_command(command: &str, output_dir: &Path) -> Result<Vec<String>> {
+    if command.trim().is_empty() {
+        return Err(anyhow!("Command is empty"));
+    }
+
+    #[cfg(target_os = "windows")]
+    let mut cmd = {
+        let mut cmd = Command::new("cmd");
+        cmd.args(["/C", command]);
+        cmd
+    };
+
+    #[cfg(not(target_os = "windows"))]
+    let mut cmd = {
+        let mut cmd = Command::new("sh");
+        cmd.args(["-c", command]);
+        cmd
+    };
+
+    if output_dir.is_dir() {
+        cmd.current_dir(output_dir);
+    }
+
+    let output = cmd
+        .env("WEAVERBIRD_OUTPUT_DIR", output_dir)
+        .output()
+        .with_context(|| format!("Failed to run command: {}", command))?;
+
+    let mut log = vec![format!("$ {}", command)];
+    log.extend(
+        String::from_utf8_lossy(&output.stdout)
+            .lines()
+            .chain(String::from_utf8_lossy(&output.stderr).lines())
+            .map(|line| line.to_string()),
+    );
+
+    if !output.status.success() {
+        return Err(anyhow!(
+            "Command exited with {}: {}",
+            output.status,
+            log[1..].join("\n")
+        ));
+    }
+
+    Ok(log)
+}
+
+/// Copy text to the system clipboard using the platform's clipboard tool
+fn copy_to_clipboard(text: &str) -> Result<()> {
+    #[cfg(target_os = "macos")]
+    let candidates: &[(&str, &[&str])] = &[("pbcopy", &[])];
+    #[cfg(target_os = "windows")]
+    let candidates: &[(&str, &[&str])] = &[("clip", &[])];
+    #[cfg(not(any(target_os = "macos", target_os = "windows")))]
+    let candidates: &[(&str, &[&str])] = &[
+        ("wl-copy", &[]),
+        ("xclip", &["-selection", "clipboard"]),
+        ("xsel", &["--clipboard", "--input"]),
+    ];
+
+    for (program, args) in candidates {
+        let child = Command::new(program)
+            .args(*args)
+            .stdin(Stdio::piped())
+            .spawn();
+
+        let mut child = match child {
+            Ok(child) => child,
+            Err(_) => continue,
+        };
+
+        if let Some(stdin) = child.stdin.as_mut() {
+            stdin
+                .write_all(text.as_bytes())
+                .context("Failed to write to clipboard")?;
+        }
+        // Close stdin so the clipboard tool sees EOF
+        drop(child.stdin.take());
+
+        if child.wait().map(|s| s.success()).unwrap_or(false) {
+            return Ok(());
+        }
+    }
+
+    Err(anyhow!("No clipboard tool available"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_hash_output_sha1_is_stable() {
+        let temp_dir = std::env::temp_dir().join("test_post_build_sha1");
+        fs::create_dir_all(temp_dir.join("assets")).expect("Failed to create test directory");
+        fs::write(temp_dir.join("pack.mcmeta"), "{}").expect("Failed to write file");
+        fs::write(temp_dir.join("assets/a.txt"), "a").expect("Failed to write file");
+
+        let first = hash_output_sha1(&temp_dir).expect("should hash");
+        let second = hash_output_sha1(&temp_dir).expect("should hash");
+
+        fs::write(temp_dir.join("assets/a.txt"), "b").expect("Failed to write file");
+        let changed = hash_output_sha1(&temp_dir).expect("should hash");
+
+        // Clean up
+        fs::remove_dir_all(&temp_dir).ok();
+
+        assert_eq!(first.len(), 40);
+        assert_eq!(first, second);
+        assert_ne!(first, changed);
+    }
+
+    #[test]
+    fn test_hook_serialization() {
+        let hooks = vec![
+            PostBuildHook::RevealOutput,
+            PostBuildHook::RunCommand {
+                command: "echo hi".to_string(),
+            },
+        ];
+        let json = serde_json::to_string(&hooks).unwrap();
+        assert!(json.contains(r#"{"type":"revealOutput"}"#));
+        assert!(json.contains(r#""type":"runCommand","command":"echo hi""#));
+
+        let parsed: Vec<PostBuildHook> = serde_json::from_str(&json).unwrap();
+        assert_eq!(parsed, hooks);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_run_command_captures_output() {
+        let temp_dir = std::env::temp_dir().join("test_post_build_command");
+        fs::create_dir_all(&temp_dir).expect("Failed to create test directory");
+
+        let log = run_post_build_hooks(
+            &[
+                PostBuildHook::RunCommand {
+                    command: "echo built to $WEAVERBIRD_OUTPUT_DIR".to_string(),
+                },
+                PostBuildHook::RunCommand {
+                    command: "exit 3".to_string(),
+                },
+            ],
+            &temp_dir,
+        );
+
+        // Clean up
+        fs::remove_dir_all(&temp_dir).ok();
+
+        assert!(log[1].starts_with("built to "));
+        assert!(log[2].contains("Post-build hook failed"));
+    }
+}
@@ -0,0 +1,787 @@
+/// Preview scenes for judging a merge in context
+///
+/// Renders a fixed miniature scene (a terrain patch with a small house and a
+/// tree) as an isometric PNG using the textures a merge would pick, so blocks
+/// can be judged next to each other rather than as isolated tiles. Faces are
+/// shaded like the game does (top brightest, east side darkest), corners
+/// next to solid blocks get ambient occlusion, and grass and leaves get the
+/// plains biome tint. Blocks whose models turn off `shade` or
+/// `ambientocclusion` (plants) are drawn without them, as in game.
+use crate::model::{OverrideSelection, PackMeta};
+use crate::util::animation;
+use crate::util::color_vision::{self, ColorVisionFilter};
+use crate::util::missing_texture::missing_texture_image;
+use crate::util::weaver_nest::{self, WinnerRules};
+use anyhow::{Context, Result};
+use image::{Rgba, RgbaImage};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::path::Path;
+use tracing::info;
+
+/// Default width of one block's top face in pixels
+pub const DEFAULT_TILE_SIZE: u32 = 32;
+
+/// Largest allowed tile size
+pub const MAX_TILE_SIZE: u32 = 256;
+
+/// Plains grass color
+const GRASS_TINT: [u8; 3] = [145, 189, 89];
+
+/// Plains foliage color
+const FOLIAGE_TINT: [u8; 3] = [119, 171, 47];
+
+/// Brightness of top, south and east faces
+const TOP_SHADE: f32 = 1.0;
+const SOUTH_SHADE: f32 = 0.8;
+const EAST_SHADE: f32 = 0.6;
+
+/// How much a fully occluded corner is darkened
+const AO_STRENGTH: f32 = 0.4;
+
+/// Geometry of a scene block
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Shape {
+    /// Full cube
+    Cube,
+    /// Two crossed planes, like flowers and grass
+    Cross,
+}
+
+/// How a block looks in the scene
+struct BlockStyle {
+    name: &'static str,
+    top: &'static str,
+    side: &'static str,
+    top_tint: Option<[u8; 3]>,
+    side_tint: Option<[u8; 3]>,
+    /// Whether the block hides the faces of its neighbors
+    opaque: bool,
+    shape: Shape,
+    /// The model's per-element `shade` flag: directional face brightness
+    shade: bool,
+    /// The model's `ambientocclusion` flag
+    ambient_occlusion: bool,
+}
+
+const BLOCK_STYLES: &[BlockStyle] = &[
+    BlockStyle {
+        name: "grass_block",
+        top: "grass_block_top",
+        side: "grass_block_side",
+        top_tint: Some(GRASS_TINT),
+        side_tint: None,
+        opaque: true,
+        shape: Shape::Cube,
+        shade: true,
+        ambient_occlusion: true,
+    },
+    BlockStyle {
+        name: "stone",
+        top: "stone",
+        side: "stone",
+        top_tint: None,
+        side_tint: None,
+        opaque: true,
+        shape: Shape::Cube,
+        shade: true,
+        ambient_occlusion: true,
+    },
+    BlockStyle {
+        name: "cobblestone",
+        top: "cobblestone",
+        side: "cobblestone",
+        top_tint: None,
+        side_tint: None,
+        opaque: true,
+        shape: Shape::Cube,
+        shade: true,
+        ambient_occlusion: true,
+    },
+    BlockStyle {
+        name: "oak_planks",
+        top: "oak_planks",
+        side: "oak_planks",
+        top_tint: None,
+        side_tint: None,
+        opaque: true,
+        shape: Shape::Cube,
+        shade: true,
+        ambient_occlusion: true,
+    },
+    BlockStyle {
+        name: "oak_log",
+        top: "oak_log_top",
+        side: "oak_log",
+        top_tint: None,
+        side_tint: None,
+        opaque: true,
+        shape: Shape::Cube,
+        shade: true,
+        ambient_occlusion: true,
+    },
+    BlockStyle {
+        name: "bricks",
+        top: "bricks",
+        side: "bricks",
+        top_tint: None,
+        side_tint: None,
+        opaque: true,
+        shape: Shape::Cube,
+        shade: true,
+        ambient_occlusion: true,
+    },
+    BlockStyle {
+        name: "glass",
+        top: "glass",
+        side: "glass",
+        top_tint: None,
+        side_tint: None,
+        opaque: false,
+        shape: Shape::Cube,
+        shade: true,
+        ambient_occlusion: true,
+    },
+    BlockStyle {
+        name: "oak_leaves",
+        top: "oak_leaves",
+        side: "oak_leaves",
+        top_tint: Some(FOLIAGE_TINT),
+        side_tint: Some(FOLIAGE_TINT),
+        opaque: false,
+        shape: Shape::Cube,
+        shade: true,
+        ambient_occlusion: true,
+    },
+    BlockStyle {
+        name: "short_grass",
+        top: "short_grass",
+        side: "short_grass",
+        top_tint: Some(GRASS_TINT),
+        side_tint: Some(GRASS_TINT),
+        opaque: false,
+        shape: Shape::Cross,
+        shade: false,
+        ambient_occlusion: false,
+    },
+    BlockStyle {
+        name: "poppy",
+        top: "poppy",
+        side: "poppy",
+        top_tint: None,
+        side_tint: None,
+        opaque: false,
+        shape: Shape::Cross,
+        shade: false,
+        ambient_occlusion: false,
+    },
+];
+
+/// A block placed in the scene (y is up)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SceneBlock {
+    pub x: i32,
+    pub y: i32,
+    pub z: i32,
+    pub block: &'static str,
+}
+
+/// Where a scene texture came from
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SceneTextureSource {
+    /// Texture name (e.g. "oak_planks")
+    pub texture: String,
+    /// Pack providing it ("vanilla" for the vanilla cache, None if missing)
+    pub pack_id: Option<String>,
+}
+
+/// A rendered preview scene
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ScenePreview {
+    pub output_path: String,
+    pub width: u32,
+    pub height: u32,
+    pub blocks: usize,
+    pub textures: Vec<SceneTextureSource>,
+    /// Textures no pack provided, drawn as the missing-texture checkerboard
+    pub missing_textures: Vec<String>,
+}
+
+fn style_of(block: &str) -> Option<&'static BlockStyle> {
+    BLOCK_STYLES.iter().find(|s| s.name == block)
+}
+
+/// The built-in scene: terrain, a small house and an oak tree
+pub fn default_scene() -> Vec<SceneBlock> {
+    let mut blocks = Vec::new();
+    let mut place =
+        |x: i32, y: i32, z: i32, block: &'static str| blocks.push(SceneBlock { x, y, z, block });
+
+    // Terrain: stone under a grass layer
+    for x in 0..8 {
+        for z in 0..8 {
+            place(x, -1, z, "stone");
+            place(x, 0, z, "grass_block");
+        }
+    }
+
+    // House: cobblestone base, plank walls with log corners and windows
+    let (x0, x1, z0, z1) = (1, 4, 1, 3);
+    for x in x0..=x1 {
+        for z in z0..=z1 {
+            let edge = x == x0 || x == x1 || z == z0 || z == z1;
+            if !edge {
+                continue;
+            }
+            let corner = (x == x0 || x == x1) && (z == z0 || z == z1);
+            place(x, 1, z, "cobblestone");
+            for y in 2..=3 {
+                let window = y == 2 && !corner && (x == x1 || z == z1) && (x + z) % 2 == 0;
+                let block = if corner {
+                    "oak_log"
+                } else if window {
+                    "glass"
+                } else {
+                    "oak_planks"
+                };
+                place(x, y, z, block);
+            }
+        }
+    }
+    for x in x0..=x1 {
+        for z in z0..=z1 {
+            place(x, 4, z, "bricks");
+        }
+    }
+
+    // Tree
+    let (tx, tz) = (6, 1);
+    for y in 1..=3 {
+        place(tx, y, tz, "oak_log");
+    }
+    for y in 3..=4 {
+        for dx in -1..=1 {
+            for dz in -1..=1 {
+                if dx != 0 || dz != 0 || y == 4 {
+                    place(tx + dx, y, tz + dz, "oak_leaves");
+                }
+            }
+        }
+    }
+    place(tx, 5, tz, "oak_leaves");
+
+    // Plants in front of the house
+    for (x, z, block) in [
+        (1, 6, "poppy"),
+        (3, 5, "short_grass"),
+        (5, 6, "short_grass"),
+    ] {
+        place(x, 1, z, block);
+    }
+
+    blocks
+}
+
+/// Texture names a scene needs
+fn scene_textures(blocks: &[SceneBlock]) -> Vec<&'static str> {
+    let mut names: Vec<&'static str> = blocks
+        .iter()
+        .filter_map(|b| style_of(b.block))
+        .flat_map(|s| [s.top, s.side])
+        .collect();
+    names.sort_unstable();
+    names.dedup();
+    names
+}
+
+/// Find the texture a merge would use, falling back to the vanilla cache
+///
+/// # Returns
+/// The first frame and the pack it came from ("vanilla" for the cache)
+pub fn resolve_asset_texture(
+    asset_id: &str,
+    packs: &[PackMeta],
+    pack_order: &[String],
+    overrides: &HashMap<String, OverrideSelection>,
+    rules: &WinnerRules,
+    vanilla_dir: Option<&Path>,
+) -> Option<(RgbaImage, String)> {
+    let (pack_id, bytes) =
+        match weaver_nest::read_winning_texture(asset_id, packs, pack_order, overrides, rules) {
+            Some(winner) => winner,
+            None => {
+                let file = animation::texture_path_for_asset(asset_id);
+                (
+                    "vanilla".to_string(),
+                    std::fs::read(vanilla_dir?.join(file)).ok()?,
+                )
+            }
+        };
+
+    animation::decode_first_frame(&bytes)
+        .ok()
+        .map(|image| (image, pack_id))
+}
+
+/// Multiply a color by a tint and a shade factor
+fn shade(pixel: Rgba<u8>, tint: Option<[u8; 3]>, factor: f32) -> Rgba<u8> {
+    let tint = tint.unwrap_or([255, 255, 255]);
+    let mut out = pixel;
+    for i in 0..3 {
+        let tinted = pixel[i] as f32 * tint[i] as f32 / 255.0;
+        out[i] = (tinted * factor).round().min(255.0) as u8;
+    }
+    out
+}
+
+/// Alpha-blend a pixel over the canvas
+fn blend(canvas: &mut RgbaImage, x: u32, y: u32, src: Rgba<u8>) {
+    let alpha = src[3] as f32 / 255.0;
+    if alpha <= 0.0 {
+        return;
+    }
+    let dst = canvas.get_pixel_mut(x, y);
+    let dst_alpha = dst[3] as f32 / 255.0;
+    let out_alpha = alpha + dst_alpha * (1.0 - alpha);
+    for i in 0..3 {
+        let color = (src[i] as f32 * alpha + dst[i] as f32 * dst_alpha * (1.0 - alpha)) / out_alpha;
+        dst[i] = color.round() as u8;
+    }
+    dst[3] = (out_alpha * 255.0).round() as u8;
+}
+
+/// Isometric projection of a world point
+fn project(x: f32, y: f32, z: f32, tile: f32) -> (f32, f32) {
+    ((x - z) * tile / 2.0, (x + z) * tile / 4.0 - y * tile / 2.0)
+}
+
+/// One face to draw: a parallelogram with a texture mapped onto it
+struct Face<'a> {
+    origin: (f32, f32),
+    u: (f32, f32),
+    v: (f32, f32),
+    texture: &'a RgbaImage,
+    tint: Option<[u8; 3]>,
+    shade: f32,
+    /// Occlusion at the origin, origin + u, origin + v and the far corner
+    light: [f32; 4],
+}
+
+/// Full light on every corner, for faces without ambient occlusion
+const NO_OCCLUSION: [f32; 4] = [1.0; 4];
+
+/// Smooth-lighting occlusion at a face corner, like the game's vertex AO
+///
+/// Looks at the two blocks beside the corner and the one diagonal to it in
+/// the layer the face looks into; both sides solid is fully occluded.
+fn corner_light(
+    solid: &HashSet<(i32, i32, i32)>,
+    block: (i32, i32, i32),
+    normal: (i32, i32, i32),
+    corner: (i32, i32, i32),
+) -> f32 {
+    let layer = (block.0 + normal.0, block.1 + normal.1, block.2 + normal.2);
+    // Step from the block toward the corner along both axes the face spans
+    let step = |c: i32, b: i32| if c > b { 1 } else { -1 };
+    let mut sides = Vec::with_capacity(2);
+    if normal.0 == 0 {
+        sides.push((step(corner.0, block.0), 0, 0));
+    }
+    if normal.1 == 0 {
+        sides.push((0, step(corner.1, block.1), 0));
+    }
+    if normal.2 == 0 {
+        sides.push((0, 0, step(corner.2, block.2)));
+    }
+    let (a, b) = (sides[0], sides[1]);
+    let at = |d: (i32, i32, i32)| solid.contains(&(layer.0 + d.0, layer.1 + d.1, layer.2 + d.2));
+
+    let (side1, side2) = (at(a), at(b));
+    let diagonal = at((a.0 + b.0, a.1 + b.1, a.2 + b.2));
+    let occluders = if side1 && side2 {
+        3
+    } else {
+        side1 as i32 + side2 as i32 + diagonal as i32
+    };
+    1.0 - AO_STRENGTH * occluders as f32 / 3.0
+}
+
+fn draw_face(canvas: &mut RgbaImage, face: &Face, offset: (f32, f32)) {
+    let (ox, oy) = (face.origin.0 + offset.0, face.origin.1 + offset.1);
+    let det = face.u.0 * face.v.1 - face.u.1 * face.v.0;
+    if det.abs() < f32::EPSILON {
+        return;
+    }
+
+    let xs = [ox, ox + face.u.0, ox + face.v.0, ox + face.u.0 + face.v.0];
+    let ys = [oy, oy + face.u.1, oy + face.v.1, oy + face.u.1 + face.v.1];
+    let min_x = xs.iter().cloned().fold(f32::MAX, f32::min).floor().max(0.0) as u32;
+    let max_x = (xs.iter().cloned().fold(f32::MIN, f32::max).ceil() as u32).min(canvas.width());
+    let min_y = ys.iter().cloned().fold(f32::MAX, f32::min).floor().max(0.0) as u32;
+    let max_y = (ys.iter().cloned().fold(f32::MIN, f32::max).ceil() as u32).min(canvas.height());
+    let (tex_w, tex_h) = face.texture.dimensions();
+
+    for py in min_y..max_y {
+        for px in min_x..max_x {
+            let dx = px as f32 + 0.5 - ox;
+            let dy = py as f32 + 0.5 - oy;
+            let s = (dx * face.v.1 - dy * face.v.0) / det;
+            let t = (face.u.0 * dy - face.u.1 * dx) / det;
+            if !(0.0..1.0).contains(&s) || !(0.0..1.0).contains(&t) {
+                continue;
+            }
+            let texel = face.texture.get_pixel(
+                ((s * tex_w as f32) as u32).min(tex_w - 1),
+                ((t * tex_h as f32) as u32).min(tex_h - 1),
+            );
+            let [l00, l10, l01, l11] = face.light;
+            let light = (l00 * (1.0 - s) + l10 * s) * (1.0 - t) + (l01 * (1.0 - s) + l11 * s) * t;
+            blend(canvas, px, py, shade(*texel, face.tint, face.shade * light));
+        }
+    }
+}
+
+/// Render blocks isometrically with the given textures
+///
+/// # Arguments
+/// * `blocks` - Blocks to draw
+/// * `textures` - Texture name -> image
+/// * `tile_size` - Width of one block's top face in pixels
+pub fn render_scene(
+    blocks: &[SceneBlock],
+    textures: &HashMap<String, RgbaImage>,
+    tile_size: u32,
+) -> RgbaImage {
+    let tile = tile_size as f32;
+    let missing = missing_texture_image();
+    let texture = |name: &str| textures.get(name).unwrap_or(&missing);
+
+    let opaque: HashSet<(i32, i32, i32)> = blocks
+        .iter()
+        .filter(|b| style_of(b.block).map_or(false, |s| s.opaque))
+        .map(|b| (b.x, b.y, b.z))
+        .collect();
+
+    // Canvas bounds from every block's outline
+    let (mut min_x, mut min_y, mut max_x, mut max_y) = (f32::MAX, f32::MAX, f32::MIN, f32::MIN);
+    for b in blocks {
+        for corner in 0..8 {
+            let (cx, cy, cz) = (corner & 1, (corner >> 1) & 1, (corner >> 2) & 1);
+            let (sx, sy) = project(
+                (b.x + cx) as f32,
+                (b.y + cy) as f32,
+                (b.z + cz) as f32,
+                tile,
+            );
+            min_x = min_x.min(sx);
+            min_y = min_y.min(sy);
+            max_x = max_x.max(sx);
+            max_y = max_y.max(sy);
+        }
+    }
+    if blocks.is_empty() {
+        return RgbaImage::new(1, 1);
+    }
+    let mut canvas = RgbaImage::new((max_x - min_x).ceil() as u32, (max_y - min_y).ceil() as u32);
+    let offset = (-min_x, -min_y);
+
+    // Painter's order: farthest from the viewer first
+    let mut ordered: Vec<&SceneBlock> = blocks.iter().collect();
+    ordered.sort_by_key(|b| (b.x + b.y + b.z, b.y));
+
+    for b in ordered {
+        let style = match style_of(b.block) {
+            Some(style) => style,
+            None => continue,
+        };
+        let (x, y, z) = (b.x as f32, b.y as f32, b.z as f32);
+        let hidden = |dx: i32, dy: i32, dz: i32| opaque.contains(&(b.x + dx, b.y + dy, b.z + dz));
+        let p = |px: f32, py: f32, pz: f32| project(px, py, pz, tile);
+        let edge = |from: (f32, f32), to: (f32, f32)| (to.0 - from.0, to.1 - from.1);
+
+        let mut faces = Vec::new();
+        if style.shape == Shape::Cross {
+            // Two diagonal planes; the one running toward the viewer is edge-on
+            for (a, b) in [((0.0, 0.0), (1.0, 1.0)), ((1.0, 0.0), (0.0, 1.0))] {
+                let origin = p(x + a.0, y + 1.0, z + a.1);
+                faces.push(Face {
+                    origin,
+                    u: edge(origin, p(x + b.0, y + 1.0, z + b.1)),
+                    v: edge(origin, p(x + a.0, y, z + a.1)),
+                    texture: texture(style.side),
+                    tint: style.side_tint,
+                    shade: if style.shade { SOUTH_SHADE } else { 1.0 },
+                    light: NO_OCCLUSION,
+                });
+            }
+        } else {
+            let brightness = |directional: f32| if style.shade { directional } else { 1.0 };
+            let block = (b.x, b.y, b.z);
+            let light = |normal: (i32, i32, i32), corners: [(i32, i32, i32); 4]| {
+                if style.ambient_occlusion {
+                    corners.map(|c| {
+                        corner_light(&opaque, block, normal, (b.x + c.0, b.y + c.1, b.z + c.2))
+                    })
+                } else {
+                    NO_OCCLUSION
+                }
+            };
+
+            if !hidden(0, 1, 0) {
+                let origin = p(x, y + 1.0, z);
+                faces.push(Face {
+                    origin,
+                    u: edge(origin, p(x + 1.0, y + 1.0, z)),
+                    v: edge(origin, p(x, y + 1.0, z + 1.0)),
+                    texture: texture(style.top),
+                    tint: style.top_tint,
+                    shade: brightness(TOP_SHADE),
+                    light: light((0, 1, 0), [(0, 1, 0), (1, 1, 0), (0, 1, 1), (1, 1, 1)]),
+                });
+            }
+            if !hidden(0, 0, 1) {
+                let origin = p(x, y + 1.0, z + 1.0);
+                faces.push(Face {
+                    origin,
+                    u: edge(origin, p(x + 1.0, y + 1.0, z + 1.0)),
+                    v: edge(origin, p(x, y, z + 1.0)),
+                    texture: texture(style.side),
+                    tint: style.side_tint,
+                    shade: brightness(SOUTH_SHADE),
+                    light: light((0, 0, 1), [(0, 1, 1), (1, 1, 1), (0, 0, 1), (1, 0, 1)]),
+                });
+            }
+            if !hidden(1, 0, 0) {
+                let origin = p(x + 1.0, y + 1.0, z + 1.0);
+                faces.push(Face {
+                    origin,
+                    u: edge(origin, p(x + 1.0, y + 1.0, z)),
+                    v: edge(origin, p(x + 1.0, y, z + 1.0)),
+                    texture: texture(style.side),
+                    tint: style.side_tint,
+                    shade: brightness(EAST_SHADE),
+                    light: light((1, 0, 0), [(1, 1, 1), (1, 1, 0), (1, 0, 1), (1, 0, 0)]),
+                });
+            }
+        }
+
+        for face in &faces {
+            draw_face(&mut canvas, face, offset);
+        }
+    }
+
+    canvas
+}
+
+/// Render one full cube with a texture on every face, like an inventory icon
+pub fn render_block_icon(texture: &RgbaImage, tile_size: u32) -> RgbaImage {
+    // Stone is a plain shaded cube using one texture for all faces
+    let block = SceneBlock {
+        x: 0,
+        y: 0,
+        z: 0,
+        block: "stone",
+    };
+    let textures = HashMap::from([("stone".to_string(), texture.clone())]);
+    render_scene(&[block], &textures, tile_size)
+}
+
+/// Render the built-in scene with the textures a merge would use
+///
+/// # Arguments
+/// * `packs` - Scanned packs
+/// * `pack_order` - Pack IDs in priority order (first = highest)
+/// * `overrides` - Asset ID -> chosen pack
+/// * `rules` - Disabled packs and namespace owners, as in a build
+/// * `vanilla_dir` - Vanilla texture cache used for anything no pack provides
+/// * `tile_size` - Width of one block's top face in pixels
+/// * `color_filter` - Optional color blindness simulation applied to the result
+/// * `output_path` - PNG to write
+#[allow(clippy::too_many_arguments)]
+pub fn render_preview_scene(
+    packs: &[PackMeta],
+    pack_order: &[String],
+    overrides: &HashMap<String, OverrideSelection>,
+    rules: &WinnerRules,
+    vanilla_dir: Option<&Path>,
+    tile_size: u32,
+    color_filter: Option<ColorVisionFilter>,
+    output_path: &Path,
+) -> Result<ScenePreview> {
+    let blocks = default_scene();
+
+    let mut textures = HashMap::new();
+    let mut sources = BTreeMap::new();
+    for name in scene_textures(&blocks) {
+        let asset_id = format!("minecraft:block/{}", name);
+        match resolve_asset_texture(&asset_id, packs, pack_order, overrides, rules, vanilla_dir) {
+            Some((image, pack_id)) => {
+                textures.insert(name.to_string(), image);
+                sources.insert(name, Some(pack_id));
+            }
+            None => {
+                sources.insert(name, None);
+            }
+        }
+    }
+
+    let mut image = render_scene(&blocks, &textures, tile_size);
+    if let Some(filter) = color_filter {
+        color_vision::simulate_color_vision(&mut image, filter);
+    }
+    if let Some(parent) = output_path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    image
+        .save(output_path)
+        .with_context(|| format!("Failed to write {:?}", output_path))?;
+
+    info!(
+        "Rendered {} blocks ({}x{}) to {:?}",
+        blocks.len(),
+        image.width(),
+        image.height(),
+        output_path
+    );
+
+    Ok(ScenePreview {
+        output_path: output_path.to_string_lossy().to_string(),
+        width: image.width(),
+        height: image.height(),
+        blocks: blocks.len(),
+        missing_textures: sources
+            .iter()
+            .filter(|(_, pack_id)| pack_id.is_none())
+            .map(|(name, _)| name.to_string())
+            .collect(),
+        textures: sources
+            .into_iter()
+            .map(|(name, pack_id)| SceneTextureSource {
+                texture: name.to_string(),
+                pack_id,
+            })
+            .collect(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    #[test]
+    fn test_default_scene_uses_known_blocks() {
+        let blocks = default_scene();
+
+        assert!(blocks.iter().all(|b| style_of(b.block).is_some()));
+        let positions: HashSet<(i32, i32, i32)> = blocks.iter().map(|b| (b.x, b.y, b.z)).collect();
+        assert_eq!(positions.len(), blocks.len(), "no two blocks share a spot");
+    }
+
+    #[test]
+    fn test_render_scene_shades_faces() {
+        let white = RgbaImage::from_pixel(16, 16, Rgba([200, 200, 200, 255]));
+        let textures: HashMap<String, RgbaImage> =
+            [("stone".to_string(), white)].into_iter().collect();
+        let block = SceneBlock {
+            x: 0,
+            y: 0,
+            z: 0,
+            block: "stone",
+        };
+
+        let image = render_scene(&[block], &textures, 32);
+
+        assert_eq!(image.dimensions(), (32, 32));
+        // Top face center, south (left) face and east (right) face
+        assert_eq!(image.get_pixel(16, 8), &Rgba([200, 200, 200, 255]));
+        assert_eq!(image.get_pixel(8, 20), &Rgba([160, 160, 160, 255]));
+        assert_eq!(image.get_pixel(24, 20), &Rgba([120, 120, 120, 255]));
+        // Corners outside the cube stay transparent
+        assert_eq!(image.get_pixel(0, 0)[3], 0);
+    }
+
+    #[test]
+    fn test_corner_light_darkens_corners_next_to_solid_blocks() {
+        // A wall block above and behind the top face
+        let solid: HashSet<(i32, i32, i32)> = [(0, 0, 0), (0, 1, -1)].into_iter().collect();
+
+        let back = corner_light(&solid, (0, 0, 0), (0, 1, 0), (0, 1, 0));
+        let front = corner_light(&solid, (0, 0, 0), (0, 1, 0), (1, 1, 1));
+        assert!(back < 1.0);
+        assert_eq!(front, 1.0);
+
+        // Both sides solid is fully occluded, whatever the diagonal
+        let boxed: HashSet<(i32, i32, i32)> = [(-1, 1, 0), (0, 1, -1)].into_iter().collect();
+        let dark = corner_light(&boxed, (0, 0, 0), (0, 1, 0), (0, 1, 0));
+        assert!((dark - (1.0 - AO_STRENGTH)).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_render_scene_draws_plants_unshaded() {
+        let white = RgbaImage::from_pixel(16, 16, Rgba([200, 200, 200, 255]));
+        let textures: HashMap<String, RgbaImage> =
+            [("poppy".to_string(), white)].into_iter().collect();
+        let block = SceneBlock {
+            x: 0,
+            y: 0,
+            z: 0,
+            block: "poppy",
+        };
+
+        let image = render_scene(&[block], &textures, 32);
+
+        let drawn: Vec<&Rgba<u8>> = image.pixels().filter(|p| p[3] > 0).collect();
+        assert!(!drawn.is_empty());
+        // shade and ambientocclusion are off for cross models
+        assert!(drawn.iter().all(|p| **p == Rgba([200, 200, 200, 255])));
+    }
+
+    #[test]
+    fn test_render_preview_scene_resolves_by_priority() {
+        let temp_dir = std::env::temp_dir().join("test_preview_scene");
+        let mut packs = Vec::new();
+        for (id, color) in [("low", [10, 10, 10, 255]), ("high", [250, 250, 250, 255])] {
+            let dir = temp_dir.join(id).join("assets/minecraft/textures/block");
+            fs::create_dir_all(&dir).expect("Failed to create test directory");
+            RgbaImage::from_pixel(16, 16, Rgba(color))
+                .save(dir.join("stone.png"))
+                .expect("Failed to write image");
+            packs.push(PackMeta {
+                id: id.to_string(),
+                name: id.to_string(),
+                path: temp_dir.join(id).to_string_lossy().to_string(),
+                ..Default::default()
+            });
+        }
+        let output = temp_dir.join("scene.png");
+
+        let preview = render_preview_scene(
+            &packs,
+            &["high".to_string(), "low".to_string()],
+            &HashMap::new(),
+            &WinnerRules::default(),
+            None,
+            16,
+            None,
+            &output,
+        );
+        let written = output.exists();
+
+        // Clean up
+        fs::remove_dir_all(&temp_dir).ok();
+
+        let preview = preview.expect("render should succeed");
+        assert!(written);
+        let stone = preview
+            .textures
+            .iter()
+            .find(|t| t.texture == "stone")
+            .expect("stone is in the scene");
+        assert_eq!(stone.pack_id.as_deref(), Some("high"));
+        assert!(preview.missing_textures.contains(&"oak_planks".to_string()));
+        assert!(!preview.missing_textures.contains(&"stone".to_string()));
+    }
+}
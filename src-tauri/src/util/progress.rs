@@ -0,0 +1,182 @@
+/// Progress reporting for long operations
+///
+/// Scanning, indexing, vanilla extraction, builds and downloads report
+/// through a plain callback, so the util layer stays free of Tauri.
+/// Commands wrap the callback in a `ProgressTracker`, which adds an ETA,
+/// limits how often events are sent, and emits `Progress` payloads on the
+/// events below for the frontend to show.
+use crate::model::Progress;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// Event for pack folder scans
+pub const SCAN_PROGRESS_EVENT: &str = "scan:progress";
+/// Event for asset indexing
+pub const INDEX_PROGRESS_EVENT: &str = "index:progress";
+/// Event for vanilla texture extraction
+pub const EXTRACT_PROGRESS_EVENT: &str = "extract:progress";
+/// Event for builds
+pub const BUILD_PROGRESS_EVENT: &str = "build:progress";
+/// Event for remote pack downloads
+pub const DOWNLOAD_PROGRESS_EVENT: &str = "download:progress";
+
+/// Shortest time between two events of one tracker
+const MIN_EVENT_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Per-item progress: (items done, total, item just finished)
+pub type FileProgress<'a> = &'a (dyn Fn(usize, usize, Option<&str>) + Sync);
+
+/// Time left at the rate items were done so far
+///
+/// # Returns
+/// None before the first item, or once nothing is left
+pub fn estimate_remaining(elapsed: Duration, completed: usize, total: usize) -> Option<Duration> {
+    if completed == 0 || completed >= total {
+        return None;
+    }
+    let per_item = elapsed.as_secs_f64() / completed as f64;
+    Some(Duration::from_secs_f64(
+        per_item * (total - completed) as f64,
+    ))
+}
+
+/// Turns progress callbacks into throttled `Progress` payloads
+pub struct ProgressTracker {
+    phase: String,
+    start: Instant,
+    last_sent: Mutex<Option<Instant>>,
+    sink: Box<dyn Fn(&Progress) + Send + Sync>,
+}
+
+impl ProgressTracker {
+    /// # Arguments
+    /// * `phase` - Operation name sent with every payload (e.g., "index")
+    /// * `sink` - Receives payloads, e.g. to emit them as events
+    pub fn new(phase: &str, sink: impl Fn(&Progress) + Send + Sync + 'static) -> Self {
+        ProgressTracker {
+            phase: phase.to_string(),
+            start: Instant::now(),
+            last_sent: Mutex::new(None),
+            sink: Box::new(sink),
+        }
+    }
+
+    /// Whether a report is sent; the first and last reports always are,
+    /// others at most every `MIN_EVENT_INTERVAL`
+    fn due(&self, completed: u64, total: u64) -> bool {
+        let now = Instant::now();
+        let mut last_sent = self.last_sent.lock().unwrap_or_else(|e| e.into_inner());
+        let due = last_sent.map_or(true, |last| now - last >= MIN_EVENT_INTERVAL);
+        if !due && completed != 0 && completed < total {
+            return false;
+        }
+        *last_sent = Some(now);
+        true
+    }
+
+    /// Report progress
+    pub fn report(&self, completed: usize, total: usize, current_file: Option<&str>) {
+        if !self.due(completed as u64, total as u64) {
+            return;
+        }
+
+        (self.sink)(&Progress {
+            phase: self.phase.clone(),
+            completed: completed as u64,
+            total: total as u64,
+            bytes: None,
+            bytes_per_sec: None,
+            current_file: current_file.map(str::to_string),
+            eta_ms: estimate_remaining(self.start.elapsed(), completed, total)
+                .map(|eta| eta.as_millis() as u64),
+        });
+    }
+
+    /// Report bytes transferred; completed and total are in bytes (total
+    /// 0 if unknown), and the ETA goes by the transfer rate
+    pub fn report_bytes(
+        &self,
+        transferred: u64,
+        total: u64,
+        bytes_per_sec: u64,
+        current_file: Option<&str>,
+    ) {
+        // Without a total no report counts as the last one
+        let last = if total == 0 { u64::MAX } else { total };
+        if !self.due(transferred, last) {
+            return;
+        }
+
+        let eta_ms = if bytes_per_sec > 0 && transferred < total {
+            Some((total - transferred) * 1000 / bytes_per_sec)
+        } else {
+            None
+        };
+        (self.sink)(&Progress {
+            phase: self.phase.clone(),
+            completed: transferred,
+            total,
+            bytes: Some(transferred),
+            bytes_per_sec: Some(bytes_per_sec),
+            current_file: current_file.map(str::to_string),
+            eta_ms,
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+
+    #[test]
+    fn test_estimate_remaining() {
+        let elapsed = Duration::from_secs(10);
+        assert_eq!(
+            estimate_remaining(elapsed, 25, 100),
+            Some(Duration::from_secs(30))
+        );
+        assert_eq!(estimate_remaining(elapsed, 0, 100), None);
+        assert_eq!(estimate_remaining(elapsed, 100, 100), None);
+    }
+
+    #[test]
+    fn test_tracker_throttles_between_first_and_last() {
+        let sent = Arc::new(Mutex::new(Vec::new()));
+        let sink = sent.clone();
+        let tracker = ProgressTracker::new("index", move |p| {
+            sink.lock()
+                .unwrap()
+                .push((p.completed, p.current_file.clone()));
+        });
+
+        tracker.report(0, 3, None);
+        tracker.report(1, 3, Some("a"));
+        tracker.report(2, 3, Some("b"));
+        tracker.report(3, 3, Some("c"));
+
+        let sent = sent.lock().unwrap();
+        assert_eq!(sent.first(), Some(&(0, None)));
+        assert_eq!(sent.last(), Some(&(3, Some("c".to_string()))));
+        // Reports 1 and 2 came within the interval of the first
+        assert_eq!(sent.len(), 2);
+    }
+
+    #[test]
+    fn test_report_bytes_eta_from_rate() {
+        let sent = Arc::new(Mutex::new(Vec::new()));
+        let sink = sent.clone();
+        let tracker = ProgressTracker::new("download", move |p| {
+            sink.lock()
+                .unwrap()
+                .push((p.bytes, p.bytes_per_sec, p.eta_ms));
+        });
+
+        tracker.report_bytes(1000, 3000, 500, Some("HD.zip"));
+        tracker.report_bytes(3000, 3000, 500, Some("HD.zip"));
+
+        let sent = sent.lock().unwrap();
+        assert_eq!(sent[0], (Some(1000), Some(500), Some(4000)));
+        assert_eq!(sent[1], (Some(3000), Some(500), None));
+    }
+}
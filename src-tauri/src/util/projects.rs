@@ -0,0 +1,316 @@
+/// Saved projects and batch builds
+///
+/// A project captures everything needed to rebuild an output pack: the packs
+/// directory, pack order, penciled overrides and output destination. Projects
+/// are stored in the application settings so several can be rebuilt at once,
+/// e.g. for people maintaining packs for multiple servers or instances.
+use crate::model::OverrideSelection;
+use crate::util::parallelism;
+use crate::util::resolution_queue::ResolutionState;
+use crate::util::stack_simulation::StackConfig;
+use anyhow::{anyhow, Result};
+use rayon::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::time::Instant;
+use tracing::info;
+
+/// A saved build configuration
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Project {
+    pub name: String,
+    pub packs_dir: String,
+    pub pack_order: Vec<String>,
+    #[serde(default)]
+    pub overrides: HashMap<String, OverrideSelection>,
+    pub output_dir: String,
+    /// Namespace -> the only pack allowed to provide it
+    #[serde(default)]
+    pub namespace_owners: HashMap<String, String>,
+    /// Recolor fully transparent texture pixels to avoid dark mipmap edges
+    #[serde(default)]
+    pub fix_alpha_bleed: bool,
+    /// Take the other textures of an overridden block from the same pack
+    #[serde(default = "crate::util::override_closure::enabled_by_default")]
+    pub include_override_dependencies: bool,
+    /// Alternative orders and overrides to compare without building
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub simulations: Vec<StackConfig>,
+    /// Packs left out of builds for now, keeping their place in the order
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub disabled_packs: Vec<String>,
+    /// Text assets built by merging lines instead of replacing
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub merged_text_assets: Vec<String>,
+    /// Conflicts accepted or skipped in guided resolution
+    #[serde(default, skip_serializing_if = "ResolutionState::is_empty")]
+    pub resolution: ResolutionState,
+    /// pack.mcmeta description template; the default one if None
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description_template: Option<String>,
+}
+
+/// Outcome of building a single project in a batch
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProjectBuildResult {
+    pub name: String,
+    pub output_dir: String,
+    pub success: bool,
+    pub message: String,
+    pub duration_ms: u64,
+}
+
+/// Combined summary of a batch build
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BatchBuildSummary {
+    pub results: Vec<ProjectBuildResult>,
+    pub succeeded: usize,
+    pub failed: usize,
+    pub duration_ms: u64,
+}
+
+/// Insert a project, replacing any existing project with the same name
+pub fn upsert_project(projects: &mut Vec<Project>, project: Project) {
+    match projects.iter_mut().find(|p| p.name == project.name) {
+        Some(existing) => *existing = project,
+        None => projects.push(project),
+    }
+}
+
+/// Look up saved projects by name, preserving the requested order
+pub fn select_projects(projects: &[Project], names: &[String]) -> Result<Vec<Project>> {
+    names
+        .iter()
+        .map(|name| {
+            projects
+                .iter()
+                .find(|p| &p.name == name)
+                .cloned()
+                .ok_or_else(|| anyhow!("Project not found: {}", name))
+        })
+        .collect()
+}
+
+/// Pack order without disabled packs
+pub fn enabled_pack_order(pack_order: &[String], disabled_packs: &[String]) -> Vec<String> {
+    pack_order
+        .iter()
+        .filter(|id| !disabled_packs.contains(id))
+        .cloned()
+        .collect()
+}
+
+/// Providers without disabled packs, dropping assets only they provide
+pub fn without_disabled_packs(
+    providers: &HashMap<String, Vec<String>>,
+    disabled_packs: &[String],
+) -> HashMap<String, Vec<String>> {
+    if disabled_packs.is_empty() {
+        return providers.clone();
+    }
+    providers
+        .iter()
+        .filter_map(|(asset_id, pack_ids)| {
+            let enabled = enabled_pack_order(pack_ids, disabled_packs);
+            if enabled.is_empty() {
+                None
+            } else {
+                Some((asset_id.clone(), enabled))
+            }
+        })
+        .collect()
+}
+
+/// Overrides that pick an enabled pack
+///
+/// Overrides of a disabled pack are kept in the project but do nothing
+/// until the pack is enabled again.
+pub fn active_overrides(
+    overrides: &HashMap<String, OverrideSelection>,
+    disabled_packs: &[String],
+) -> HashMap<String, OverrideSelection> {
+    overrides
+        .iter()
+        .filter(|(_, selection)| !disabled_packs.contains(&selection.pack_id))
+        .map(|(asset_id, selection)| (asset_id.clone(), selection.clone()))
+        .collect()
+}
+
+/// Build several projects and collect a combined summary
+///
+/// Projects are built in sequence when `concurrency` is `None`, 0 or 1;
+/// otherwise up to `concurrency` projects (and no more than the IO pool has
+/// threads) are built at the same time.
+/// A failing project doesn't stop the rest of the batch.
+pub fn run_batch<F>(
+    projects: &[Project],
+    concurrency: Option<usize>,
+    build: F,
+) -> Result<BatchBuildSummary>
+where
+    F: Fn(&Project) -> Result<String, String> + Send + Sync,
+{
+    let start = Instant::now();
+
+    let build_one = |project: &Project| {
+        let project_start = Instant::now();
+        let outcome = build(project);
+        let duration_ms = project_start.elapsed().as_millis() as u64;
+
+        info!(
+            "Project '{}' finished in {}ms (success: {})",
+            project.name,
+            duration_ms,
+            outcome.is_ok()
+        );
+
+        let (success, message) = match outcome {
+            Ok(message) => (true, message),
+            Err(message) => (false, message),
+        };
+
+        ProjectBuildResult {
+            name: project.name.clone(),
+            output_dir: project.output_dir.clone(),
+            success,
+            message,
+            duration_ms,
+        }
+    };
+
+    let results: Vec<ProjectBuildResult> = match concurrency {
+        Some(limit) if limit > 1 => {
+            // Builds run on the IO pool; splitting the list into at most
+            // `limit` runs caps how many are in flight at once
+            let per_run = (projects.len() + limit - 1) / limit;
+            parallelism::with_io_pool(|| {
+                projects
+                    .par_iter()
+                    .with_min_len(per_run.max(1))
+                    .map(build_one)
+                    .collect()
+            })
+        }
+        _ => projects.iter().map(build_one).collect(),
+    };
+
+    let succeeded = results.iter().filter(|r| r.success).count();
+    let failed = results.len() - succeeded;
+
+    Ok(BatchBuildSummary {
+        results,
+        succeeded,
+        failed,
+        duration_ms: start.elapsed().as_millis() as u64,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn make_project(name: &str) -> Project {
+        Project {
+            name: name.to_string(),
+            packs_dir: "/packs".to_string(),
+            pack_order: vec!["pack_a".to_string()],
+            overrides: HashMap::new(),
+            output_dir: format!("/out/{}", name),
+            namespace_owners: HashMap::new(),
+            fix_alpha_bleed: false,
+            include_override_dependencies: true,
+            simulations: Vec::new(),
+            disabled_packs: Vec::new(),
+            merged_text_assets: Vec::new(),
+            resolution: ResolutionState::default(),
+            description_template: None,
+        }
+    }
+
+    #[test]
+    fn test_upsert_project_replaces_by_name() {
+        let mut projects = vec![make_project("survival")];
+        let mut updated = make_project("survival");
+        updated.output_dir = "/elsewhere".to_string();
+
+        upsert_project(&mut projects, updated);
+        upsert_project(&mut projects, make_project("creative"));
+
+        assert_eq!(projects.len(), 2);
+        assert_eq!(projects[0].output_dir, "/elsewhere");
+    }
+
+    #[test]
+    fn test_select_projects_missing_name() {
+        let projects = vec![make_project("survival")];
+        assert!(select_projects(&projects, &["creative".to_string()]).is_err());
+
+        let selected = select_projects(&projects, &["survival".to_string()]).unwrap();
+        assert_eq!(selected.len(), 1);
+    }
+
+    #[test]
+    fn test_disabled_packs() {
+        let disabled = vec!["pack_b".to_string()];
+        let order: Vec<String> = ["pack_a", "pack_b", "pack_c"]
+            .iter()
+            .map(|id| id.to_string())
+            .collect();
+        let providers = HashMap::from([
+            (
+                "minecraft:block/stone".to_string(),
+                vec!["pack_b".to_string(), "pack_c".to_string()],
+            ),
+            (
+                "minecraft:block/dirt".to_string(),
+                vec!["pack_b".to_string()],
+            ),
+        ]);
+        let selection = |pack_id: &str| OverrideSelection {
+            pack_id: pack_id.to_string(),
+            variant_path: None,
+        };
+        let overrides = HashMap::from([
+            ("minecraft:block/stone".to_string(), selection("pack_b")),
+            ("minecraft:block/sand".to_string(), selection("pack_a")),
+        ]);
+
+        let providers = without_disabled_packs(&providers, &disabled);
+        let overrides = active_overrides(&overrides, &disabled);
+
+        assert_eq!(
+            enabled_pack_order(&order, &disabled),
+            vec!["pack_a", "pack_c"]
+        );
+        assert_eq!(providers["minecraft:block/stone"], vec!["pack_c"]);
+        assert!(!providers.contains_key("minecraft:block/dirt"));
+        assert_eq!(overrides.len(), 1);
+        assert!(overrides.contains_key("minecraft:block/sand"));
+    }
+
+    #[test]
+    fn test_run_batch_collects_failures() {
+        let projects = vec![make_project("a"), make_project("broken"), make_project("c")];
+
+        for concurrency in [None, Some(2)] {
+            let summary = run_batch(&projects, concurrency, |p| {
+                if p.name == "broken" {
+                    Err("boom".to_string())
+                } else {
+                    Ok("built".to_string())
+                }
+            })
+            .expect("batch should run");
+
+            assert_eq!(summary.results.len(), 3);
+            assert_eq!(summary.succeeded, 2);
+            assert_eq!(summary.failed, 1);
+            // Results keep the requested order even when built in parallel
+            assert_eq!(summary.results[1].name, "broken");
+            assert_eq!(summary.results[1].message, "boom");
+        }
+    }
+}
@@ -0,0 +1,647 @@
+/// Resumable downloads of remote packs
+///
+/// HD packs imported from Modrinth or a URL can run to hundreds of
+/// megabytes, and one dropped connection shouldn't mean starting over.
+/// Downloads go over HTTP(S) in ranged chunks appended to a partial file
+/// next to the destination. A sidecar records the URL and size, so a later
+/// attempt at the same URL carries on from the bytes already on disk. A
+/// connection that goes quiet fails the attempt instead of hanging, and
+/// cancelling stops waiting on the network right away. Once complete, the
+/// file is checked against the expected size and SHA-1 (Modrinth lists
+/// both for every file) and renamed into place; an existing file is only
+/// replaced when asked. A cancelled download removes its partial file;
+/// other failures keep it for the next attempt.
+use crate::util::jobs::{self, CancelToken};
+use crate::util::post_build;
+use anyhow::{anyhow, bail, Context, Result};
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use std::fs::{self, File, OpenOptions};
+use std::io::{Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{self, RecvTimeoutError};
+use std::time::{Duration, Instant};
+use tracing::{info, warn};
+
+/// Bytes requested per ranged request
+pub const CHUNK_SIZE: u64 = 8 * 1024 * 1024;
+
+/// Extension added to the destination while it downloads
+pub const PARTIAL_EXTENSION: &str = "weaverbird-part";
+
+/// Attempts per chunk before the download fails
+const CHUNK_ATTEMPTS: usize = 3;
+
+/// Longest wait for a connection to the server
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(15);
+
+/// Longest wait for the next bytes before a transfer counts as stalled
+const STALL_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// How often a transfer waiting on the network checks for cancellation
+const CANCEL_POLL_INTERVAL: Duration = Duration::from_millis(200);
+
+/// Bytes read from the connection at a time
+const READ_BUFFER_SIZE: usize = 64 * 1024;
+
+/// Client shared by all downloads, so connections to a CDN are reused
+static AGENT: Lazy<ureq::Agent> = Lazy::new(|| {
+    ureq::AgentBuilder::new()
+        .timeout_connect(CONNECT_TIMEOUT)
+        .timeout_read(STALL_TIMEOUT)
+        .user_agent(concat!("weaverbird/", env!("CARGO_PKG_VERSION")))
+        .build()
+});
+
+/// Download progress: (bytes on disk, total bytes or 0 if unknown,
+/// bytes per second over this attempt)
+pub type DownloadProgress<'a> = &'a (dyn Fn(u64, u64, u64) + Sync);
+
+/// What the server says about a file before it's downloaded
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct RemoteInfo {
+    pub size: Option<u64>,
+    /// Whether the server answers byte range requests
+    pub ranges: bool,
+}
+
+/// What a partial file was downloaded from, kept next to it
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct PartialDownload {
+    url: String,
+    size: Option<u64>,
+}
+
+/// A finished download
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DownloadedFile {
+    pub path: String,
+    pub size: u64,
+    /// Lowercase hex SHA-1 of the file
+    pub sha1: String,
+    /// Bytes carried over from an earlier attempt
+    pub resumed_from: u64,
+}
+
+/// Partial file a download to `dest` is written to
+pub fn partial_path(dest: &Path) -> PathBuf {
+    let mut name = dest.file_name().unwrap_or_default().to_os_string();
+    name.push(".");
+    name.push(PARTIAL_EXTENSION);
+    dest.with_file_name(name)
+}
+
+/// Sidecar describing the partial file of `dest`
+fn sidecar_path(dest: &Path) -> PathBuf {
+    let mut name = partial_path(dest)
+        .file_name()
+        .unwrap_or_default()
+        .to_os_string();
+    name.push(".json");
+    dest.with_file_name(name)
+}
+
+/// Remove the partial file of a download to `dest` and its sidecar
+pub fn discard_partial(dest: &Path) {
+    fs::remove_file(partial_path(dest)).ok();
+    fs::remove_file(sidecar_path(dest)).ok();
+}
+
+/// File name for a download, from the last segment of its URL
+///
+/// Query strings and fragments are dropped and `%XX` escapes decoded.
+pub fn file_name_from_url(url: &str) -> Option<String> {
+    let path = url.split(['?', '#']).next().unwrap_or_default();
+    let segment = path.rsplit('/').next().unwrap_or_default();
+    if segment.is_empty() || path.ends_with(':') {
+        return None;
+    }
+
+    let bytes = segment.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] == b'%' {
+            let escape = segment.get(i + 1..i + 3).unwrap_or_default();
+            if let Ok(byte) = u8::from_str_radix(escape, 16) {
+                decoded.push(byte);
+                i += 3;
+                continue;
+            }
+        }
+        decoded.push(bytes[i]);
+        i += 1;
+    }
+    let name = String::from_utf8_lossy(&decoded).to_string();
+    (!name.contains(['/', '\\']) && name != "." && name != "..").then(|| name)
+}
+
+/// Whether a URL is one downloads may fetch: http or https only
+pub fn is_download_url(url: &str) -> bool {
+    let lower = url.to_ascii_lowercase();
+    lower.starts_with("https://") || lower.starts_with("http://")
+}
+
+/// Ask the server for a file's size and range support
+///
+/// Servers that refuse HEAD requests are downloaded in one piece.
+pub fn remote_info(url: &str) -> RemoteInfo {
+    match AGENT.head(url).call() {
+        Ok(response) => RemoteInfo {
+            size: response
+                .header("Content-Length")
+                .and_then(|value| value.trim().parse().ok()),
+            ranges: response
+                .header("Accept-Ranges")
+                .map_or(false, |value| value.trim().eq_ignore_ascii_case("bytes")),
+        },
+        Err(e) => {
+            warn!("HEAD request for {} failed: {}", url, e);
+            RemoteInfo::default()
+        }
+    }
+}
+
+/// Byte ranges (inclusive) still to download after `start`
+pub fn chunk_ranges(start: u64, size: u64, chunk_size: u64) -> Vec<(u64, u64)> {
+    let chunk_size = chunk_size.max(1);
+    let mut ranges = Vec::new();
+    let mut from = start;
+    while from < size {
+        let to = (from + chunk_size).min(size) - 1;
+        ranges.push((from, to));
+        from = to + 1;
+    }
+    ranges
+}
+
+/// Check a downloaded file against the expected size and SHA-1
+///
+/// # Returns
+/// The file's SHA-1
+pub fn verify_download(path: &Path, size: Option<u64>, sha1: Option<&str>) -> Result<String> {
+    let actual_size = fs::metadata(path)?.len();
+    if let Some(size) = size {
+        if actual_size != size {
+            bail!("Downloaded {} bytes, expected {}", actual_size, size);
+        }
+    }
+    let actual_sha1 = post_build::hash_output_sha1(path)?;
+    if let Some(sha1) = sha1 {
+        if !actual_sha1.eq_ignore_ascii_case(sha1.trim()) {
+            bail!(
+                "SHA-1 mismatch: got {}, expected {}",
+                actual_sha1,
+                sha1.trim()
+            );
+        }
+    }
+    Ok(actual_sha1)
+}
+
+/// Tracks bytes on disk and the rate they arrive at
+struct Transfer<'a> {
+    downloaded: u64,
+    total: u64,
+    resumed_from: u64,
+    start: Instant,
+    progress: Option<DownloadProgress<'a>>,
+}
+
+impl Transfer<'_> {
+    fn add(&mut self, bytes: u64) {
+        self.downloaded += bytes;
+        if let Some(progress) = self.progress {
+            let elapsed = self.start.elapsed().as_secs_f64();
+            let rate = if elapsed > 0.0 {
+                ((self.downloaded - self.resumed_from) as f64 / elapsed) as u64
+            } else {
+                0
+            };
+            progress(self.downloaded, self.total, rate);
+        }
+    }
+}
+
+/// Request a URL (optionally a byte range) and append the body to a file
+///
+/// The request runs on its own thread so a quiet connection can't keep a
+/// cancelled download waiting: the token is checked every 200ms, and the
+/// thread ends at its next read once nobody is listening.
+fn fetch_into(
+    url: &str,
+    range: Option<(u64, u64)>,
+    file: &mut File,
+    transfer: &mut Transfer,
+    cancel: Option<&CancelToken>,
+) -> Result<u64> {
+    let mut request = AGENT.get(url);
+    if let Some((from, to)) = range {
+        request = request.set("Range", &format!("bytes={}-{}", from, to));
+    }
+
+    let (sender, receiver) = mpsc::sync_channel::<Result<Vec<u8>>>(4);
+    std::thread::spawn(move || {
+        let response = match request.call() {
+            Ok(response) => response,
+            Err(e) => {
+                sender.send(Err(anyhow!("Request failed: {}", e))).ok();
+                return;
+            }
+        };
+        if range.is_some() && response.status() != 206 {
+            sender
+                .send(Err(anyhow!("Server ignored the byte range")))
+                .ok();
+            return;
+        }
+        let mut reader = response.into_reader();
+        loop {
+            let mut buffer = vec![0u8; READ_BUFFER_SIZE];
+            let chunk = match reader.read(&mut buffer) {
+                Ok(read) => {
+                    buffer.truncate(read);
+                    Ok(buffer)
+                }
+                Err(e) => Err(anyhow!("Transfer stalled or dropped: {}", e)),
+            };
+            let done = !matches!(&chunk, Ok(bytes) if !bytes.is_empty());
+            if sender.send(chunk).is_err() || done {
+                return;
+            }
+        }
+    });
+
+    let mut written = 0;
+    loop {
+        jobs::check_cancelled(cancel)?;
+        let bytes = match receiver.recv_timeout(CANCEL_POLL_INTERVAL) {
+            Ok(chunk) => chunk?,
+            Err(RecvTimeoutError::Timeout) => continue,
+            Err(RecvTimeoutError::Disconnected) => bail!("Transfer ended unexpectedly"),
+        };
+        if bytes.is_empty() {
+            return Ok(written);
+        }
+        file.write_all(&bytes)?;
+        written += bytes.len() as u64;
+        transfer.add(bytes.len() as u64);
+    }
+}
+
+/// Run a blocking network call on its own thread, giving up on it once
+/// the job is cancelled
+fn cancellable<T: Send + 'static>(
+    cancel: Option<&CancelToken>,
+    call: impl FnOnce() -> T + Send + 'static,
+) -> Result<T> {
+    let (sender, receiver) = mpsc::sync_channel(1);
+    std::thread::spawn(move || {
+        sender.send(call()).ok();
+    });
+    loop {
+        jobs::check_cancelled(cancel)?;
+        match receiver.recv_timeout(CANCEL_POLL_INTERVAL) {
+            Ok(value) => return Ok(value),
+            Err(RecvTimeoutError::Timeout) => continue,
+            Err(RecvTimeoutError::Disconnected) => bail!("Request ended unexpectedly"),
+        }
+    }
+}
+
+/// Download a URL to a file, resuming an earlier partial download
+///
+/// # Arguments
+/// * `url` - File to download
+/// * `dest` - Where the finished file goes
+/// * `size` - Expected size, if known up front
+/// * `sha1` - Expected SHA-1 to verify against
+/// * `overwrite` - Replace `dest` if it exists; otherwise that's an error
+/// * `cancel` - Stops the download and removes the partial file
+/// * `progress` - Receives bytes on disk, total and the transfer rate
+pub fn download_resumable(
+    url: &str,
+    dest: &Path,
+    size: Option<u64>,
+    sha1: Option<&str>,
+    overwrite: bool,
+    cancel: Option<&CancelToken>,
+    progress: Option<DownloadProgress>,
+) -> Result<DownloadedFile> {
+    if !is_download_url(url) {
+        bail!("Only http:// and https:// URLs can be downloaded: {}", url);
+    }
+    if dest.exists() && !overwrite {
+        bail!("{} already exists", dest.display());
+    }
+    let result = download_to_partial(url, dest, size, sha1, overwrite, cancel, progress);
+    if result.is_err() && cancel_requested(cancel) {
+        discard_partial(dest);
+    }
+    result
+}
+
+/// Download into the partial file, then verify it and move it into place
+fn download_to_partial(
+    url: &str,
+    dest: &Path,
+    size: Option<u64>,
+    sha1: Option<&str>,
+    overwrite: bool,
+    cancel: Option<&CancelToken>,
+    progress: Option<DownloadProgress>,
+) -> Result<DownloadedFile> {
+    let remote = {
+        let url = url.to_string();
+        cancellable(cancel, move || remote_info(&url))?
+    };
+    let size = size.or(remote.size);
+    let partial = partial_path(dest);
+    let sidecar = sidecar_path(dest);
+    if let Some(parent) = dest.parent() {
+        fs::create_dir_all(parent)?;
+    }
+
+    // Only carry on from a partial file of the same download
+    let previous: Option<PartialDownload> = fs::read_to_string(&sidecar)
+        .ok()
+        .and_then(|json| serde_json::from_str(&json).ok());
+    let same_download = previous.map_or(false, |p| p.url == url && p.size == size);
+    let on_disk = fs::metadata(&partial).map(|m| m.len()).unwrap_or(0);
+    let resumable = remote.ranges && size.is_some();
+    let resumed_from = if same_download && resumable && size.map_or(false, |s| on_disk <= s) {
+        on_disk
+    } else {
+        0
+    };
+    if resumed_from > 0 {
+        info!("Resuming download of {} at {} bytes", url, resumed_from);
+    }
+
+    let mut file = OpenOptions::new()
+        .create(true)
+        .write(true)
+        .truncate(false)
+        .open(&partial)
+        .with_context(|| format!("Failed to open {:?}", partial))?;
+    file.set_len(resumed_from)?;
+    file.seek(SeekFrom::End(0))?;
+    let record = PartialDownload {
+        url: url.to_string(),
+        size,
+    };
+    fs::write(&sidecar, serde_json::to_string(&record)?)?;
+
+    let mut transfer = Transfer {
+        downloaded: resumed_from,
+        total: size.unwrap_or(0),
+        resumed_from,
+        start: Instant::now(),
+        progress,
+    };
+    transfer.add(0);
+
+    match size {
+        Some(size) if resumable => {
+            for (from, to) in chunk_ranges(resumed_from, size, CHUNK_SIZE) {
+                let mut attempt = 1;
+                // A failed attempt keeps what arrived; the retry asks for the rest
+                while transfer.downloaded <= to {
+                    let range = (transfer.downloaded, to);
+                    match fetch_into(url, Some(range), &mut file, &mut transfer, cancel) {
+                        Ok(0) => bail!("Server sent nothing for bytes {}-{}", range.0, to),
+                        Ok(_) => {}
+                        Err(e) if attempt < CHUNK_ATTEMPTS && !cancel_requested(cancel) => {
+                            warn!(
+                                "Bytes {}-{} of {} failed, retrying: {}",
+                                range.0, to, url, e
+                            );
+                            attempt += 1;
+                        }
+                        Err(e) => return Err(e),
+                    }
+                }
+                if transfer.downloaded != to + 1 {
+                    bail!("Server ignored the byte range {}-{}", from, to);
+                }
+            }
+        }
+        _ => {
+            fetch_into(url, None, &mut file, &mut transfer, cancel)?;
+        }
+    }
+    file.sync_all()?;
+    drop(file);
+
+    let actual_sha1 = match verify_download(&partial, size, sha1) {
+        Ok(sha1) => sha1,
+        Err(e) => {
+            // A corrupt file can't be resumed into a good one
+            discard_partial(dest);
+            return Err(e);
+        }
+    };
+    if dest.exists() {
+        if !overwrite {
+            bail!("{} appeared while downloading", dest.display());
+        }
+        fs::remove_file(dest).with_context(|| format!("Failed to replace {:?}", dest))?;
+    }
+    fs::rename(&partial, dest).with_context(|| format!("Failed to move download to {:?}", dest))?;
+    fs::remove_file(&sidecar).ok();
+
+    Ok(DownloadedFile {
+        path: dest.to_string_lossy().to_string(),
+        size: transfer.downloaded,
+        sha1: actual_sha1,
+        resumed_from,
+    })
+}
+
+/// Whether the job a token belongs to was cancelled
+fn cancel_requested(cancel: Option<&CancelToken>) -> bool {
+    cancel.map_or(false, CancelToken::is_cancelled)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::net::TcpListener;
+
+    /// Serve `contents` over HTTP on a local port, honouring byte ranges
+    ///
+    /// # Returns
+    /// The file's URL
+    fn serve(contents: Vec<u8>) -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let port = listener.local_addr().unwrap().port();
+        std::thread::spawn(move || {
+            for stream in listener.incoming() {
+                let mut stream = match stream {
+                    Ok(stream) => stream,
+                    Err(_) => return,
+                };
+                let mut request = Vec::new();
+                let mut byte = [0u8; 1];
+                while !request.ends_with(b"\r\n\r\n") && stream.read(&mut byte).unwrap_or(0) == 1 {
+                    request.push(byte[0]);
+                }
+                let request = String::from_utf8_lossy(&request).to_lowercase();
+                let range = request
+                    .lines()
+                    .find_map(|line| line.strip_prefix("range: bytes="))
+                    .and_then(|range| range.trim().split_once('-'))
+                    .and_then(|(from, to)| {
+                        Some((from.parse::<usize>().ok()?, to.parse::<usize>().ok()?))
+                    });
+                let (status, body) = match range {
+                    Some((from, to)) => ("206 Partial Content", &contents[from..=to]),
+                    None => ("200 OK", &contents[..]),
+                };
+                let head = format!(
+                    "HTTP/1.1 {}\r\nContent-Length: {}\r\nAccept-Ranges: bytes\r\nConnection: close\r\n\r\n",
+                    status,
+                    body.len()
+                );
+                stream.write_all(head.as_bytes()).ok();
+                if !request.starts_with("head ") {
+                    stream.write_all(body).ok();
+                }
+            }
+        });
+        format!("http://127.0.0.1:{}/HD.zip", port)
+    }
+
+    #[test]
+    fn test_chunk_ranges() {
+        assert_eq!(chunk_ranges(0, 10, 4), vec![(0, 3), (4, 7), (8, 9)]);
+        assert_eq!(chunk_ranges(6, 10, 4), vec![(6, 9)]);
+        assert!(chunk_ranges(10, 10, 4).is_empty());
+    }
+
+    #[test]
+    fn test_file_name_from_url() {
+        assert_eq!(
+            file_name_from_url(
+                "https://cdn.modrinth.com/data/abc/versions/def/Faithful%2032x.zip?x=1"
+            )
+            .as_deref(),
+            Some("Faithful 32x.zip")
+        );
+        assert_eq!(file_name_from_url("https://example.com/"), None);
+        assert_eq!(file_name_from_url("https://example.com/a%2Fb.zip"), None);
+        assert_eq!(
+            partial_path(Path::new("packs/HD.zip")),
+            Path::new("packs/HD.zip.weaverbird-part")
+        );
+    }
+
+    #[test]
+    fn test_verify_download() {
+        let temp_dir = std::env::temp_dir().join("test_verify_download");
+        fs::remove_dir_all(&temp_dir).ok();
+        fs::create_dir_all(&temp_dir).unwrap();
+        let path = temp_dir.join("pack.zip");
+        fs::write(&path, "abc").unwrap();
+        let sha1 = "a9993e364706816aba3e25717850c26c9cd0d89d";
+
+        let verified = verify_download(&path, Some(3), Some(&sha1.to_uppercase()));
+        let wrong_size = verify_download(&path, Some(4), None);
+        let wrong_hash = verify_download(&path, None, Some("0000"));
+
+        // Clean up
+        fs::remove_dir_all(&temp_dir).ok();
+
+        assert_eq!(verified.unwrap(), sha1);
+        assert!(wrong_size.is_err());
+        assert!(wrong_hash
+            .unwrap_err()
+            .to_string()
+            .contains("SHA-1 mismatch"));
+    }
+
+    #[test]
+    fn test_download_resumes_partial_file() {
+        let temp_dir = std::env::temp_dir().join("test_download_resumes_partial_file");
+        fs::remove_dir_all(&temp_dir).ok();
+        let contents: Vec<u8> = (0..300u32).map(|i| (i % 251) as u8).collect();
+        let url = serve(contents.clone());
+        let dest = temp_dir.join("packs").join("HD.zip");
+        fs::create_dir_all(dest.parent().unwrap()).unwrap();
+        // An earlier attempt got the first 100 bytes
+        fs::write(partial_path(&dest), &contents[..100]).unwrap();
+        let record = PartialDownload {
+            url: url.clone(),
+            size: Some(300),
+        };
+        fs::write(sidecar_path(&dest), serde_json::to_string(&record).unwrap()).unwrap();
+        let mut hasher = sha1_smol::Sha1::new();
+        hasher.update(&contents);
+        let sha1 = hasher.digest().to_string();
+
+        let result = download_resumable(&url, &dest, None, Some(&sha1), false, None, None);
+        let written = fs::read(&dest).ok();
+        let leftovers = partial_path(&dest).exists() || sidecar_path(&dest).exists();
+        let again = download_resumable(&url, &dest, None, None, false, None, None);
+
+        // Clean up
+        fs::remove_dir_all(&temp_dir).ok();
+
+        let downloaded = result.unwrap();
+        assert_eq!(downloaded.resumed_from, 100);
+        assert_eq!(downloaded.size, 300);
+        assert_eq!(downloaded.sha1, sha1);
+        assert_eq!(written, Some(contents));
+        assert!(!leftovers);
+        // The finished file isn't replaced unless asked
+        assert!(again.unwrap_err().to_string().contains("already exists"));
+    }
+
+    #[test]
+    fn test_cancel_stops_waiting_on_a_silent_server() {
+        let temp_dir = std::env::temp_dir().join("test_download_cancel_silent_server");
+        fs::remove_dir_all(&temp_dir).ok();
+        // Accepts connections and never answers
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}/HD.zip", listener.local_addr().unwrap());
+        let job_id = jobs::create_job();
+        let job = jobs::start_job(Some(&job_id));
+        std::thread::spawn(move || {
+            std::thread::sleep(Duration::from_millis(300));
+            jobs::cancel_job(&job_id);
+        });
+
+        let started = Instant::now();
+        let result = download_resumable(
+            &url,
+            &temp_dir.join("HD.zip"),
+            Some(10),
+            None,
+            false,
+            Some(job.token()),
+            None,
+        );
+        let elapsed = started.elapsed();
+        let leftovers = partial_path(&temp_dir.join("HD.zip")).exists();
+        drop(listener);
+
+        // Clean up
+        fs::remove_dir_all(&temp_dir).ok();
+
+        assert!(jobs::cancelled_job(&result.unwrap_err()).is_some());
+        assert!(elapsed < STALL_TIMEOUT);
+        assert!(!leftovers);
+    }
+
+    #[test]
+    fn test_only_http_urls_are_downloaded() {
+        let dest = std::env::temp_dir().join("test_download_file_url.zip");
+
+        let result = download_resumable("file:///etc/passwd", &dest, None, None, false, None, None);
+
+        assert!(result.is_err());
+        assert!(!dest.exists());
+    }
+}
@@ -0,0 +1,427 @@
+/// Pack inventory and conflict reports as CSV or Markdown
+///
+/// Renders what the app shows about a pack set as plain tables that can be
+/// pasted into a server's Discord or wiki (Markdown) or opened in a
+/// spreadsheet (CSV), for sharing or keeping records outside the app.
+use crate::model::{OverrideSelection, PackMeta};
+use crate::util::known_packs::KnownPackMatch;
+use crate::util::model_texture_deps::{ModelTextureIssue, ModelTextureIssueKind};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// Table format of an exported report
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ReportFormat {
+    Csv,
+    Markdown,
+}
+
+impl ReportFormat {
+    /// File extension for the format
+    pub fn extension(self) -> &'static str {
+        match self {
+            ReportFormat::Csv => "csv",
+            ReportFormat::Markdown => "md",
+        }
+    }
+}
+
+/// Which report to export
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ReportKind {
+    /// Every pack with its priority, size and format
+    Packs,
+    /// Assets provided by more than one enabled pack, with the winner
+    Conflicts,
+}
+
+/// Quote a CSV field if it contains separators, quotes or newlines
+pub(crate) fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+fn markdown_cell(value: &str) -> String {
+    value.replace('|', "\\|").replace(['\r', '\n'], " ")
+}
+
+/// Render rows as a CSV or Markdown table
+fn render_table(format: ReportFormat, headers: &[&str], rows: &[Vec<String>]) -> String {
+    let mut out = String::new();
+    match format {
+        ReportFormat::Csv => {
+            let header: Vec<String> = headers.iter().map(|h| csv_field(h)).collect();
+            out.push_str(&header.join(","));
+            out.push('\n');
+            for row in rows {
+                let cells: Vec<String> = row.iter().map(|c| csv_field(c)).collect();
+                out.push_str(&cells.join(","));
+                out.push('\n');
+            }
+        }
+        ReportFormat::Markdown => {
+            out.push_str(&format!("| {} |\n", headers.join(" | ")));
+            out.push_str(&format!("|{}\n", " --- |".repeat(headers.len())));
+            for row in rows {
+                let cells: Vec<String> = row.iter().map(|c| markdown_cell(c)).collect();
+                out.push_str(&format!("| {} |\n", cells.join(" | ")));
+            }
+        }
+    }
+    out
+}
+
+/// Human-readable size (e.g. "3.4 MB")
+fn format_size(bytes: u64) -> String {
+    const UNITS: [&str; 4] = ["B", "KB", "MB", "GB"];
+    let mut size = bytes as f64;
+    let mut unit = 0;
+    while size >= 1024.0 && unit < UNITS.len() - 1 {
+        size /= 1024.0;
+        unit += 1;
+    }
+    if unit == 0 {
+        format!("{} B", bytes)
+    } else {
+        format!("{:.1} {}", size, UNITS[unit])
+    }
+}
+
+/// Render the pack inventory
+///
+/// Enabled packs come first in priority order, then the rest by name.
+///
+/// # Arguments
+/// * `packs` - Scanned packs
+/// * `pack_order` - Enabled pack IDs in priority order (first = highest)
+/// * `providers` - Asset ID -> pack IDs, used to count each pack's assets
+/// * `known_packs` - Recognized popular packs, keyed by pack ID
+pub fn render_pack_inventory(
+    format: ReportFormat,
+    packs: &[PackMeta],
+    pack_order: &[String],
+    providers: &HashMap<String, Vec<String>>,
+    known_packs: &HashMap<String, KnownPackMatch>,
+) -> String {
+    let mut asset_counts: HashMap<&str, usize> = HashMap::new();
+    for pack_ids in providers.values() {
+        for pack_id in pack_ids {
+            *asset_counts.entry(pack_id.as_str()).or_insert(0) += 1;
+        }
+    }
+
+    let priority = |pack: &PackMeta| pack_order.iter().position(|id| *id == pack.id);
+    let mut ordered: Vec<&PackMeta> = packs.iter().collect();
+    ordered.sort_by_key(|pack| {
+        (
+            priority(pack).unwrap_or(usize::MAX),
+            pack.name.to_lowercase(),
+        )
+    });
+
+    let rows: Vec<Vec<String>> = ordered
+        .iter()
+        .map(|pack| {
+            vec![
+                priority(pack).map_or(String::new(), |p| (p + 1).to_string()),
+                pack.name.clone(),
+                pack.id.clone(),
+                if pack.is_zip { "zip" } else { "folder" }.to_string(),
+                match format {
+                    ReportFormat::Csv => pack.size.to_string(),
+                    ReportFormat::Markdown => format_size(pack.size),
+                },
+                pack.pack_format.map_or(String::new(), |f| f.to_string()),
+                asset_counts
+                    .get(pack.id.as_str())
+                    .copied()
+                    .unwrap_or(0)
+                    .to_string(),
+                known_packs
+                    .get(&pack.id)
+                    .map_or(String::new(), |k| k.canonical_name.clone()),
+                pack.description.clone().unwrap_or_default(),
+            ]
+        })
+        .collect();
+
+    let headers = [
+        "Priority",
+        "Pack",
+        "File",
+        "Type",
+        match format {
+            ReportFormat::Csv => "Size (bytes)",
+            ReportFormat::Markdown => "Size",
+        },
+        "Pack format",
+        "Assets",
+        "Known as",
+        "Description",
+    ];
+    let table = render_table(format, &headers, &rows);
+
+    match format {
+        ReportFormat::Csv => table,
+        ReportFormat::Markdown => format!(
+            "# Pack inventory\n\n{} pack(s), {} enabled.\n\n{}",
+            packs.len(),
+            packs.iter().filter(|p| priority(p).is_some()).count(),
+            table
+        ),
+    }
+}
+
+/// Render the conflict report
+///
+/// Lists every asset provided by two or more enabled packs: which pack wins
+/// (by override or priority), which packs lose, and which packs ship a
+/// byte-identical copy of the winner (harmless duplicates). Models whose
+/// textures end up missing or taken from another pack are listed after the
+/// conflicts.
+///
+/// # Arguments
+/// * `pack_order` - Enabled pack IDs in priority order (first = highest)
+/// * `overrides` - Asset ID -> chosen pack
+/// * `providers` - Asset ID -> pack IDs providing it
+/// * `identical` - Asset ID -> groups of packs with identical copies
+/// * `model_issues` - Model/texture cross-dependencies to warn about
+pub fn render_conflicts(
+    format: ReportFormat,
+    pack_order: &[String],
+    overrides: &HashMap<String, OverrideSelection>,
+    providers: &HashMap<String, Vec<String>>,
+    identical: &HashMap<String, Vec<Vec<String>>>,
+    model_issues: &[ModelTextureIssue],
+) -> String {
+    let mut asset_ids: Vec<&String> = providers.keys().collect();
+    asset_ids.sort();
+
+    let mut rows = Vec::new();
+    let mut harmless = 0;
+    for asset_id in asset_ids {
+        // Enabled providers in priority order
+        let enabled: Vec<&String> = pack_order
+            .iter()
+            .filter(|id| providers[asset_id].contains(id))
+            .collect();
+        if enabled.len() < 2 {
+            continue;
+        }
+
+        let override_pack = overrides
+            .get(asset_id)
+            .map(|o| &o.pack_id)
+            .filter(|id| enabled.contains(id));
+        let (winner, reason) = match override_pack {
+            Some(pack_id) => (pack_id, "override"),
+            None => (enabled[0], "priority"),
+        };
+        let same_as_winner: Vec<&String> = identical
+            .get(asset_id)
+            .and_then(|groups| groups.iter().find(|g| g.contains(winner)))
+            .map(|group| group.iter().filter(|id| *id != winner).collect())
+            .unwrap_or_default();
+        let losers: Vec<&str> = enabled
+            .iter()
+            .filter(|id| **id != winner)
+            .map(|id| id.as_str())
+            .collect();
+        if losers
+            .iter()
+            .all(|id| same_as_winner.iter().any(|s| s == id))
+        {
+            harmless += 1;
+        }
+
+        rows.push(vec![
+            asset_id.clone(),
+            winner.clone(),
+            reason.to_string(),
+            losers.join("; "),
+            same_as_winner
+                .iter()
+                .map(|id| id.as_str())
+                .collect::<Vec<_>>()
+                .join("; "),
+        ]);
+    }
+
+    let headers = [
+        "Asset",
+        "Winner",
+        "Reason",
+        "Overridden packs",
+        "Identical copies",
+    ];
+    let table = render_table(format, &headers, &rows);
+
+    let mut out = match format {
+        ReportFormat::Csv => table,
+        ReportFormat::Markdown => format!(
+            "# Conflict report\n\n{} asset(s) provided by more than one enabled pack, {} of them only by identical copies.\n\n{}",
+            rows.len(),
+            harmless,
+            table
+        ),
+    };
+    if !model_issues.is_empty() {
+        out.push('\n');
+        out.push_str(&render_model_issues(format, model_issues));
+    }
+    out
+}
+
+/// Table of models whose textures are missing or come from another pack
+fn render_model_issues(format: ReportFormat, issues: &[ModelTextureIssue]) -> String {
+    let rows: Vec<Vec<String>> = issues
+        .iter()
+        .map(|issue| {
+            let problem = match issue.kind {
+                ModelTextureIssueKind::Missing => "missing (purple checker in game)",
+                ModelTextureIssueKind::Mismatched => "taken from another pack",
+            };
+            vec![
+                issue.model_id.clone(),
+                issue.model_pack_id.clone(),
+                issue.texture_id.clone(),
+                issue.texture_pack_id.clone().unwrap_or_default(),
+                problem.to_string(),
+            ]
+        })
+        .collect();
+    let headers = ["Model", "Model pack", "Texture", "Texture pack", "Problem"];
+    let table = render_table(format, &headers, &rows);
+
+    match format {
+        ReportFormat::Csv => table,
+        ReportFormat::Markdown => format!(
+            "## Model texture warnings\n\n{} texture(s) referenced by a pack's model won't come from that pack.\n\n{}",
+            rows.len(),
+            table
+        ),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pack(id: &str, size: u64) -> PackMeta {
+        PackMeta {
+            id: id.to_string(),
+            name: id.trim_end_matches(".zip").to_string(),
+            path: format!("/packs/{}", id),
+            size,
+            is_zip: id.ends_with(".zip"),
+            description: Some("Nice | pack".to_string()),
+            pack_format: Some(34),
+            ..Default::default()
+        }
+    }
+
+    fn ids(values: &[&str]) -> Vec<String> {
+        values.iter().map(|v| v.to_string()).collect()
+    }
+
+    #[test]
+    fn test_csv_field() {
+        assert_eq!(csv_field("stone"), "stone");
+        assert_eq!(csv_field("a,b"), "\"a,b\"");
+        assert_eq!(csv_field("say \"hi\""), "\"say \"\"hi\"\"\"");
+    }
+
+    #[test]
+    fn test_render_pack_inventory() {
+        let packs = vec![pack("B.zip", 2048), pack("A", 10), pack("C.zip", 5)];
+        let providers = HashMap::from([
+            ("minecraft:block/stone".to_string(), ids(&["A", "B.zip"])),
+            ("minecraft:block/dirt".to_string(), ids(&["A"])),
+        ]);
+        let order = ids(&["B.zip", "A"]);
+
+        let csv = render_pack_inventory(
+            ReportFormat::Csv,
+            &packs,
+            &order,
+            &providers,
+            &HashMap::new(),
+        );
+        let markdown = render_pack_inventory(
+            ReportFormat::Markdown,
+            &packs,
+            &order,
+            &providers,
+            &HashMap::new(),
+        );
+
+        let lines: Vec<&str> = csv.lines().collect();
+        assert_eq!(lines.len(), 4);
+        assert!(lines[1].starts_with("1,B,B.zip,zip,2048,34,1,"));
+        assert!(lines[2].starts_with("2,A,A,folder,10,34,2,"));
+        assert!(lines[3].starts_with(",C,C.zip,zip,5,34,0,"));
+
+        assert!(markdown.contains("3 pack(s), 2 enabled."));
+        assert!(markdown.contains("| 1 | B | B.zip | zip | 2.0 KB | 34 | 1 |  | Nice \\| pack |"));
+    }
+
+    #[test]
+    fn test_render_conflicts() {
+        let providers = HashMap::from([
+            ("minecraft:block/stone".to_string(), ids(&["A", "B", "C"])),
+            ("minecraft:block/dirt".to_string(), ids(&["A", "B"])),
+            ("minecraft:block/sand".to_string(), ids(&["A", "Disabled"])),
+        ]);
+        let identical =
+            HashMap::from([("minecraft:block/dirt".to_string(), vec![ids(&["A", "B"])])]);
+        let overrides = HashMap::from([(
+            "minecraft:block/stone".to_string(),
+            OverrideSelection {
+                pack_id: "C".to_string(),
+                variant_path: None,
+            },
+        )]);
+
+        let issues = vec![ModelTextureIssue {
+            model_id: "minecraft:block/oak_log".to_string(),
+            model_pack_id: "B".to_string(),
+            texture_id: "minecraft:block/fancy_log".to_string(),
+            texture_pack_id: Some("A".to_string()),
+            kind: ModelTextureIssueKind::Mismatched,
+        }];
+
+        let csv = render_conflicts(
+            ReportFormat::Csv,
+            &ids(&["A", "B", "C"]),
+            &overrides,
+            &providers,
+            &identical,
+            &[],
+        );
+        let markdown = render_conflicts(
+            ReportFormat::Markdown,
+            &ids(&["A", "B", "C"]),
+            &overrides,
+            &providers,
+            &identical,
+            &issues,
+        );
+
+        assert_eq!(
+            csv,
+            "Asset,Winner,Reason,Overridden packs,Identical copies\n\
+             minecraft:block/dirt,A,priority,B,B\n\
+             minecraft:block/stone,C,override,A; B,\n"
+        );
+        assert!(markdown.contains("2 asset(s) provided by more than one enabled pack, 1 of them"));
+        assert!(markdown.contains("## Model texture warnings"));
+        assert!(markdown.contains(
+            "| minecraft:block/oak_log | B | minecraft:block/fancy_log | A | taken from another pack |"
+        ));
+    }
+}
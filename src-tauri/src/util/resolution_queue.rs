@@ -0,0 +1,244 @@
+/// Guided conflict resolution
+///
+/// Walks a project's conflicts one at a time in conflict report order (most
+/// severe group first, then namespace and category) so the frontend can run
+/// a wizard through them. Which conflicts the user accepted or put off is
+/// kept with the project. Conflicts won through an override already count as
+/// resolved, and conflicts between byte-identical files aren't queued.
+use crate::util::conflicts::{AssetConflict, ConflictReport, ConflictSeverity, WinReason};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+
+/// Conflicts the user accepted or put off in a project
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct ResolutionState {
+    /// Asset IDs whose winner was accepted
+    pub resolved: BTreeSet<String>,
+    /// Asset IDs left for later
+    pub skipped: BTreeSet<String>,
+}
+
+impl ResolutionState {
+    pub fn is_empty(&self) -> bool {
+        self.resolved.is_empty() && self.skipped.is_empty()
+    }
+}
+
+/// What the user did with a conflict
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ResolutionAction {
+    /// Accept the winner
+    Resolve,
+    /// Come back to it after the rest
+    Skip,
+    /// Put it back in the queue
+    Reopen,
+}
+
+/// A conflict with its place in the queue
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct QueuedConflict {
+    pub namespace: String,
+    pub category: String,
+    /// 1-based position among the queued conflicts
+    pub position: usize,
+    /// Whether the user skipped it before
+    pub skipped: bool,
+    pub conflict: AssetConflict,
+}
+
+/// Next conflict to resolve and how far through the queue the user is
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ResolutionProgress {
+    /// None once nothing is left (or only skipped conflicts, unless revisited)
+    pub next: Option<QueuedConflict>,
+    /// Queued conflicts
+    pub total: usize,
+    pub resolved: usize,
+    pub skipped: usize,
+    /// Neither resolved nor skipped
+    pub remaining: usize,
+    /// Resolved share of the queue, 0-100
+    pub percent_complete: f64,
+}
+
+/// Record what the user did with a conflict
+pub fn apply_action(state: &mut ResolutionState, asset_id: &str, action: ResolutionAction) {
+    state.resolved.remove(asset_id);
+    state.skipped.remove(asset_id);
+    match action {
+        ResolutionAction::Resolve => {
+            state.resolved.insert(asset_id.to_string());
+        }
+        ResolutionAction::Skip => {
+            state.skipped.insert(asset_id.to_string());
+        }
+        ResolutionAction::Reopen => {}
+    }
+}
+
+/// Find the next conflict to resolve
+///
+/// # Arguments
+/// * `report` - Conflicts of the project's current order and overrides
+/// * `state` - The project's resolved and skipped conflicts
+/// * `revisit_skipped` - Return the first skipped conflict once no
+///   untouched ones are left
+pub fn next_conflict(
+    report: &ConflictReport,
+    state: &ResolutionState,
+    revisit_skipped: bool,
+) -> ResolutionProgress {
+    let queue = report.groups.iter().flat_map(|group| {
+        group
+            .conflicts
+            .iter()
+            .filter(|c| c.severity > ConflictSeverity::Negligible)
+            .map(move |c| (group, c))
+    });
+
+    let mut progress = ResolutionProgress {
+        next: None,
+        total: 0,
+        resolved: 0,
+        skipped: 0,
+        remaining: 0,
+        percent_complete: 100.0,
+    };
+    let mut first_skipped = None;
+    for (group, conflict) in queue {
+        progress.total += 1;
+        let position = progress.total;
+        let queued = || QueuedConflict {
+            namespace: group.namespace.clone(),
+            category: group.category.clone(),
+            position,
+            skipped: state.skipped.contains(&conflict.asset_id),
+            conflict: conflict.clone(),
+        };
+
+        if conflict.reason == WinReason::Override || state.resolved.contains(&conflict.asset_id) {
+            progress.resolved += 1;
+        } else if state.skipped.contains(&conflict.asset_id) {
+            progress.skipped += 1;
+            if first_skipped.is_none() {
+                first_skipped = Some(queued());
+            }
+        } else {
+            progress.remaining += 1;
+            if progress.next.is_none() {
+                progress.next = Some(queued());
+            }
+        }
+    }
+
+    if progress.next.is_none() && revisit_skipped {
+        progress.next = first_skipped;
+    }
+    if progress.total > 0 {
+        progress.percent_complete = progress.resolved as f64 * 100.0 / progress.total as f64;
+    }
+    progress
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::util::conflicts::ConflictGroup;
+
+    fn conflict(asset_id: &str, reason: WinReason, severity: ConflictSeverity) -> AssetConflict {
+        AssetConflict {
+            asset_id: asset_id.to_string(),
+            winner: "a".to_string(),
+            reason,
+            losers: vec!["b".to_string()],
+            identical: severity == ConflictSeverity::Negligible,
+            severity,
+        }
+    }
+
+    fn group(category: &str, conflicts: Vec<AssetConflict>) -> ConflictGroup {
+        ConflictGroup {
+            namespace: "minecraft".to_string(),
+            category: category.to_string(),
+            severity: conflicts[0].severity,
+            conflicts,
+        }
+    }
+
+    #[test]
+    fn test_next_conflict() {
+        let report = ConflictReport {
+            groups: vec![
+                group(
+                    "gui",
+                    vec![
+                        conflict(
+                            "minecraft:gui/icons",
+                            WinReason::Priority,
+                            ConflictSeverity::Critical,
+                        ),
+                        conflict(
+                            "minecraft:gui/widgets",
+                            WinReason::Override,
+                            ConflictSeverity::Critical,
+                        ),
+                    ],
+                ),
+                group(
+                    "block",
+                    vec![
+                        conflict(
+                            "minecraft:block/dirt",
+                            WinReason::Priority,
+                            ConflictSeverity::Low,
+                        ),
+                        conflict(
+                            "minecraft:block/stone",
+                            WinReason::Priority,
+                            ConflictSeverity::Negligible,
+                        ),
+                    ],
+                ),
+            ],
+            ..Default::default()
+        };
+        let mut state = ResolutionState::default();
+
+        let start = next_conflict(&report, &state, false);
+        apply_action(&mut state, "minecraft:gui/icons", ResolutionAction::Skip);
+        let after_skip = next_conflict(&report, &state, false);
+        apply_action(
+            &mut state,
+            "minecraft:block/dirt",
+            ResolutionAction::Resolve,
+        );
+        let only_skipped = next_conflict(&report, &state, false);
+        let revisited = next_conflict(&report, &state, true);
+        apply_action(&mut state, "minecraft:gui/icons", ResolutionAction::Resolve);
+        let done = next_conflict(&report, &state, true);
+
+        // Identical files aren't queued; the override counts as resolved
+        assert_eq!((start.total, start.resolved, start.remaining), (3, 1, 2));
+        let next = start.next.unwrap();
+        assert_eq!(next.conflict.asset_id, "minecraft:gui/icons");
+        assert_eq!((next.category.as_str(), next.position), ("gui", 1));
+
+        let next = after_skip.next.unwrap();
+        assert_eq!(next.conflict.asset_id, "minecraft:block/dirt");
+        assert_eq!(next.position, 3);
+        assert_eq!(after_skip.skipped, 1);
+
+        assert!(only_skipped.next.is_none());
+        assert!((only_skipped.percent_complete - 200.0 / 3.0).abs() < 1e-9);
+        assert!(revisited.next.unwrap().skipped);
+
+        assert!(done.next.is_none());
+        assert_eq!(done.percent_complete, 100.0);
+        assert!(state.skipped.is_empty());
+    }
+}
@@ -0,0 +1,636 @@
+/// Local JSON-RPC server for remote control
+///
+/// Lets scripts and other tools drive weaver-bird without the UI: when
+/// enabled, a small HTTP server accepts JSON-RPC 2.0 calls on `POST /rpc`
+/// and hands them to a dispatcher the commands layer provides. Every
+/// request must carry `Authorization: Bearer <token>`; the token is
+/// generated once and kept in settings. The server listens on 127.0.0.1
+/// unless LAN access is turned on.
+///
+/// It's plain HTTP/1.1 over std sockets with one thread per connection and
+/// one call per connection, which is plenty for scripting. Connections are
+/// capped, and each must send its whole request within a deadline, so idle
+/// or slow clients can't tie up threads before they've even authenticated.
+use crate::AppError;
+use anyhow::{anyhow, Context, Result};
+use once_cell::sync::Lazy;
+use rand::RngCore;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::{Ipv4Addr, SocketAddr, TcpListener, TcpStream};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+use tracing::{debug, info, warn};
+
+/// Path the server accepts calls on
+pub const RPC_PATH: &str = "/rpc";
+
+/// Port used when none is configured
+pub const DEFAULT_RPC_PORT: u16 = 27416;
+
+/// Largest request body accepted
+const MAX_BODY_BYTES: usize = 1024 * 1024;
+
+/// Largest request line or header accepted
+const MAX_HEADER_LINE: usize = 8 * 1024;
+
+/// How long a connection may take to send its whole request
+const REQUEST_DEADLINE: Duration = Duration::from_secs(10);
+
+/// Connections handled at once; more are turned away with a 503
+const MAX_CONNECTIONS: usize = 16;
+
+/// How long turning a connection away may block the accept loop
+const REJECT_TIMEOUT: Duration = Duration::from_secs(1);
+
+/// JSON-RPC error codes
+const PARSE_ERROR: i64 = -32700;
+const INVALID_REQUEST: i64 = -32600;
+const METHOD_NOT_FOUND: i64 = -32601;
+const INVALID_PARAMS: i64 = -32602;
+/// A command failed; the `AppError` is in the error's data
+const COMMAND_ERROR: i64 = -32000;
+
+/// The server that is running, if any
+static SERVER: Lazy<Mutex<Option<RunningServer>>> = Lazy::new(|| Mutex::new(None));
+
+/// Connections being handled
+static ACTIVE_CONNECTIONS: AtomicUsize = AtomicUsize::new(0);
+
+/// Remote control settings
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct RemoteServerSettings {
+    /// Start the server when the app starts
+    pub enabled: bool,
+    pub port: u16,
+    /// Listen on all interfaces instead of only 127.0.0.1
+    pub allow_lan: bool,
+    /// Bearer token clients must send; generated on first start
+    pub token: Option<String>,
+    /// Run post-build hooks for builds started over RPC; off unless the user
+    /// turns it on in the app, since hooks run shell commands
+    pub run_build_hooks: bool,
+}
+
+impl Default for RemoteServerSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            port: DEFAULT_RPC_PORT,
+            allow_lan: false,
+            token: None,
+            run_build_hooks: false,
+        }
+    }
+}
+
+/// Whether the server is running and how to reach it
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RemoteServerStatus {
+    pub running: bool,
+    /// Address the server listens on (e.g., "127.0.0.1:27416")
+    pub address: Option<String>,
+    /// Token clients must send
+    pub token: Option<String>,
+}
+
+/// Why a call failed
+#[derive(Debug, Clone)]
+pub enum RpcError {
+    MethodNotFound,
+    InvalidParams(String),
+    /// The command ran and returned an error
+    Command(AppError),
+}
+
+impl From<AppError> for RpcError {
+    fn from(err: AppError) -> Self {
+        RpcError::Command(err)
+    }
+}
+
+/// Runs one call: (method, params) -> result
+pub type RpcHandler = Arc<dyn Fn(&str, Value) -> Result<Value, RpcError> + Send + Sync>;
+
+/// A parsed HTTP request
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HttpRequest {
+    pub method: String,
+    pub path: String,
+    /// Header names lowercased
+    pub headers: HashMap<String, String>,
+    pub body: Vec<u8>,
+}
+
+struct RunningServer {
+    address: SocketAddr,
+    /// Shared with the connections so the token can be replaced in place
+    token: Arc<Mutex<String>>,
+    stop: Arc<AtomicBool>,
+}
+
+/// A connection slot, freed when dropped
+struct ConnectionSlot;
+
+impl ConnectionSlot {
+    /// Take a slot, unless `MAX_CONNECTIONS` are already in use
+    fn acquire() -> Option<Self> {
+        ACTIVE_CONNECTIONS
+            .fetch_update(Ordering::AcqRel, Ordering::Acquire, |active| {
+                if active < MAX_CONNECTIONS {
+                    Some(active + 1)
+                } else {
+                    None
+                }
+            })
+            .ok()
+            .map(|_| ConnectionSlot)
+    }
+}
+
+impl Drop for ConnectionSlot {
+    fn drop(&mut self) {
+        ACTIVE_CONNECTIONS.fetch_sub(1, Ordering::AcqRel);
+    }
+}
+
+/// Reads from a socket until a deadline
+///
+/// Each read waits only as long as is left, so a client trickling bytes
+/// can't stretch the request past the deadline.
+struct DeadlineReader {
+    stream: TcpStream,
+    deadline: Instant,
+}
+
+impl Read for DeadlineReader {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        let remaining = self.deadline.saturating_duration_since(Instant::now());
+        if remaining.is_zero() {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::TimedOut,
+                "Request took too long to arrive",
+            ));
+        }
+        self.stream.set_read_timeout(Some(remaining))?;
+        self.stream.read(buf)
+    }
+}
+
+/// Random 256-bit token as hex
+pub fn generate_token() -> String {
+    let mut bytes = [0u8; 32];
+    rand::thread_rng().fill_bytes(&mut bytes);
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+fn read_line_limited<R: BufRead>(reader: &mut R) -> Result<String> {
+    let mut line = Vec::new();
+    reader
+        .by_ref()
+        .take(MAX_HEADER_LINE as u64 + 1)
+        .read_until(b'\n', &mut line)?;
+    if line.len() > MAX_HEADER_LINE {
+        return Err(anyhow!("Header line too long"));
+    }
+    if !line.ends_with(b"\n") {
+        return Err(anyhow!("Connection closed mid-request"));
+    }
+    Ok(String::from_utf8_lossy(&line).trim_end().to_string())
+}
+
+/// Read one HTTP/1.1 request
+///
+/// Only requests with a Content-Length body (or none) are supported.
+pub fn read_request<R: BufRead>(reader: &mut R) -> Result<HttpRequest> {
+    let request_line = read_line_limited(reader)?;
+    let mut parts = request_line.split_whitespace();
+    let (method, path) = match (parts.next(), parts.next()) {
+        (Some(method), Some(path)) => (method.to_string(), path.to_string()),
+        _ => return Err(anyhow!("Malformed request line: {}", request_line)),
+    };
+
+    let mut headers = HashMap::new();
+    loop {
+        let line = read_line_limited(reader)?;
+        if line.is_empty() {
+            break;
+        }
+        if let Some((name, value)) = line.split_once(':') {
+            headers.insert(name.trim().to_lowercase(), value.trim().to_string());
+        }
+    }
+
+    let length = match headers.get("content-length") {
+        Some(value) => value
+            .parse::<usize>()
+            .with_context(|| format!("Invalid Content-Length: {}", value))?,
+        None => 0,
+    };
+    if length > MAX_BODY_BYTES {
+        return Err(anyhow!(
+            "Request body is larger than {} bytes",
+            MAX_BODY_BYTES
+        ));
+    }
+    let mut body = vec![0u8; length];
+    reader.read_exact(&mut body)?;
+
+    Ok(HttpRequest {
+        method,
+        path,
+        headers,
+        body,
+    })
+}
+
+/// Whether a request carries the bearer token
+pub fn is_authorized(request: &HttpRequest, token: &str) -> bool {
+    let presented = match request
+        .headers
+        .get("authorization")
+        .and_then(|value| value.strip_prefix("Bearer "))
+    {
+        Some(presented) => presented.trim().as_bytes(),
+        None => return false,
+    };
+    // Compare every byte so timing doesn't leak how much matched
+    presented.len() == token.len()
+        && presented
+            .iter()
+            .zip(token.as_bytes())
+            .fold(0u8, |diff, (a, b)| diff | (a ^ b))
+            == 0
+}
+
+fn rpc_error(id: Value, code: i64, message: &str, data: Option<Value>) -> Value {
+    let mut error = json!({ "code": code, "message": message });
+    if let Some(data) = data {
+        error["data"] = data;
+    }
+    json!({ "jsonrpc": "2.0", "id": id, "error": error })
+}
+
+/// Run a JSON-RPC 2.0 request body and build its response
+pub fn handle_rpc(body: &[u8], handler: &RpcHandler) -> Value {
+    let call: Value = match serde_json::from_slice(body) {
+        Ok(call) => call,
+        Err(e) => return rpc_error(Value::Null, PARSE_ERROR, &e.to_string(), None),
+    };
+    let id = call.get("id").cloned().unwrap_or(Value::Null);
+    let method = match call.get("method").and_then(Value::as_str) {
+        Some(method) if call.get("jsonrpc") == Some(&json!("2.0")) => method,
+        _ => return rpc_error(id, INVALID_REQUEST, "Not a JSON-RPC 2.0 request", None),
+    };
+    let params = call.get("params").cloned().unwrap_or(Value::Null);
+
+    debug!("Call {}", method);
+    match handler(method, params) {
+        Ok(result) => json!({ "jsonrpc": "2.0", "id": id, "result": result }),
+        Err(RpcError::MethodNotFound) => rpc_error(
+            id,
+            METHOD_NOT_FOUND,
+            &format!("Unknown method: {}", method),
+            None,
+        ),
+        Err(RpcError::InvalidParams(message)) => rpc_error(id, INVALID_PARAMS, &message, None),
+        Err(RpcError::Command(err)) => {
+            let data = serde_json::to_value(&err).ok();
+            rpc_error(id, COMMAND_ERROR, &err.message, data)
+        }
+    }
+}
+
+/// Status code and JSON body for a request
+pub fn respond(request: &HttpRequest, token: &str, handler: &RpcHandler) -> (u16, Value) {
+    if request.path != RPC_PATH {
+        return (404, json!({ "error": "Not found" }));
+    }
+    if request.method != "POST" {
+        return (405, json!({ "error": "Use POST" }));
+    }
+    if !is_authorized(request, token) {
+        return (401, json!({ "error": "Missing or wrong bearer token" }));
+    }
+    (200, handle_rpc(&request.body, handler))
+}
+
+fn write_response(stream: &mut TcpStream, status: u16, body: &Value) -> Result<()> {
+    let reason = match status {
+        200 => "OK",
+        400 => "Bad Request",
+        401 => "Unauthorized",
+        404 => "Not Found",
+        405 => "Method Not Allowed",
+        503 => "Service Unavailable",
+        _ => "Error",
+    };
+    let body = serde_json::to_vec(body)?;
+    write!(
+        stream,
+        "HTTP/1.1 {} {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\n\
+         Connection: close\r\n\r\n",
+        status,
+        reason,
+        body.len()
+    )?;
+    stream.write_all(&body)?;
+    stream.flush()?;
+    Ok(())
+}
+
+fn handle_connection(
+    mut stream: TcpStream,
+    token: &Mutex<String>,
+    handler: &RpcHandler,
+) -> Result<()> {
+    let reader = DeadlineReader {
+        stream: stream.try_clone()?,
+        deadline: Instant::now() + REQUEST_DEADLINE,
+    };
+    let request = read_request(&mut BufReader::new(reader));
+    let (status, body) = match &request {
+        Ok(request) => {
+            let token = token.lock().unwrap_or_else(|e| e.into_inner()).clone();
+            respond(request, &token, handler)
+        }
+        Err(e) => (400, json!({ "error": e.to_string() })),
+    };
+    write_response(&mut stream, status, &body)
+}
+
+/// Turn a connection away because too many are open
+fn reject_connection(mut stream: TcpStream) {
+    stream.set_write_timeout(Some(REJECT_TIMEOUT)).ok();
+    let body = json!({ "error": "Too many connections" });
+    if let Err(e) = write_response(&mut stream, 503, &body) {
+        debug!("Failed to turn connection away: {}", e);
+    }
+}
+
+/// Start the server
+///
+/// # Arguments
+/// * `port` - Port to listen on; 0 picks a free one
+/// * `allow_lan` - Listen on all interfaces instead of only 127.0.0.1
+/// * `token` - Bearer token clients must send
+///
+/// # Errors
+/// The server is already running or the port can't be bound
+pub fn start_server(
+    port: u16,
+    allow_lan: bool,
+    token: &str,
+    handler: RpcHandler,
+) -> Result<RemoteServerStatus> {
+    let mut server = SERVER.lock().unwrap_or_else(|e| e.into_inner());
+    if server.is_some() {
+        return Err(anyhow!("Remote server is already running"));
+    }
+
+    let host = if allow_lan {
+        Ipv4Addr::UNSPECIFIED
+    } else {
+        Ipv4Addr::LOCALHOST
+    };
+    let listener = TcpListener::bind((host, port))
+        .with_context(|| format!("Failed to listen on {}:{}", host, port))?;
+    let address = listener.local_addr()?;
+    let stop = Arc::new(AtomicBool::new(false));
+
+    let token = Arc::new(Mutex::new(token.to_string()));
+
+    let thread_stop = stop.clone();
+    let thread_token = token.clone();
+    thread::spawn(move || {
+        for stream in listener.incoming() {
+            if thread_stop.load(Ordering::Relaxed) {
+                break;
+            }
+            let stream = match stream {
+                Ok(stream) => stream,
+                Err(e) => {
+                    warn!("Failed to accept connection: {}", e);
+                    continue;
+                }
+            };
+            let slot = match ConnectionSlot::acquire() {
+                Some(slot) => slot,
+                None => {
+                    reject_connection(stream);
+                    continue;
+                }
+            };
+            let token = thread_token.clone();
+            let handler = handler.clone();
+            thread::spawn(move || {
+                let _slot = slot;
+                if let Err(e) = handle_connection(stream, &token, &handler) {
+                    warn!("Connection failed: {}", e);
+                }
+            });
+        }
+        info!("Stopped listening on {}", address);
+    });
+
+    info!("Listening on {}", address);
+    *server = Some(RunningServer {
+        address,
+        token,
+        stop,
+    });
+    Ok(server_status_locked(&server))
+}
+
+/// Stop the server
+///
+/// Calls already running finish; new connections are refused.
+///
+/// # Returns
+/// Whether a server was running
+pub fn stop_server() -> bool {
+    let running = SERVER.lock().unwrap_or_else(|e| e.into_inner()).take();
+    match running {
+        Some(running) => {
+            running.stop.store(true, Ordering::Relaxed);
+            // Wake the accept loop so it sees the flag
+            let wake = SocketAddr::from((Ipv4Addr::LOCALHOST, running.address.port()));
+            TcpStream::connect_timeout(&wake, Duration::from_secs(1)).ok();
+            true
+        }
+        None => false,
+    }
+}
+
+/// Replace the token of the running server
+///
+/// Takes effect for the next request; the server keeps listening.
+///
+/// # Returns
+/// Whether a server was running
+pub fn set_server_token(token: &str) -> bool {
+    match SERVER.lock().unwrap_or_else(|e| e.into_inner()).as_ref() {
+        Some(server) => {
+            *server.token.lock().unwrap_or_else(|e| e.into_inner()) = token.to_string();
+            true
+        }
+        None => false,
+    }
+}
+
+fn server_status_locked(server: &Option<RunningServer>) -> RemoteServerStatus {
+    match server {
+        Some(server) => RemoteServerStatus {
+            running: true,
+            address: Some(server.address.to_string()),
+            token: Some(
+                server
+                    .token
+                    .lock()
+                    .unwrap_or_else(|e| e.into_inner())
+                    .clone(),
+            ),
+        },
+        None => RemoteServerStatus {
+            running: false,
+            address: None,
+            token: None,
+        },
+    }
+}
+
+/// Whether the server is running and where
+pub fn server_status() -> RemoteServerStatus {
+    server_status_locked(&SERVER.lock().unwrap_or_else(|e| e.into_inner()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn echo_handler() -> RpcHandler {
+        Arc::new(|method, params| match method {
+            "echo" => Ok(params),
+            "fail" => Err(AppError::validation("Bad input").into()),
+            _ => Err(RpcError::MethodNotFound),
+        })
+    }
+
+    fn post(body: &str, auth: Option<&str>) -> HttpRequest {
+        let mut raw = format!("POST {} HTTP/1.1\r\nHost: localhost\r\n", RPC_PATH);
+        if let Some(auth) = auth {
+            raw.push_str(&format!("Authorization: {}\r\n", auth));
+        }
+        raw.push_str(&format!("Content-Length: {}\r\n\r\n{}", body.len(), body));
+        read_request(&mut raw.as_bytes()).unwrap()
+    }
+
+    #[test]
+    fn test_read_request() {
+        let request = post(r#"{"a":1}"#, Some("Bearer abc"));
+
+        assert_eq!(request.method, "POST");
+        assert_eq!(request.path, RPC_PATH);
+        assert_eq!(request.headers["authorization"], "Bearer abc");
+        assert_eq!(request.body, br#"{"a":1}"#);
+        assert!(read_request(&mut "GARBAGE\r\n\r\n".as_bytes()).is_err());
+        let oversized = format!(
+            "POST /rpc HTTP/1.1\r\nContent-Length: {}\r\n\r\n",
+            usize::MAX
+        );
+        assert!(read_request(&mut oversized.as_bytes()).is_err());
+    }
+
+    #[test]
+    fn test_respond_requires_token() {
+        let handler = echo_handler();
+        let body = r#"{"jsonrpc":"2.0","id":1,"method":"echo","params":{"x":2}}"#;
+
+        let (missing, _) = respond(&post(body, None), "secret", &handler);
+        let (wrong, _) = respond(&post(body, Some("Bearer secreT")), "secret", &handler);
+        let (ok, response) = respond(&post(body, Some("Bearer secret")), "secret", &handler);
+
+        assert_eq!(missing, 401);
+        assert_eq!(wrong, 401);
+        assert_eq!(ok, 200);
+        assert_eq!(
+            response,
+            json!({ "jsonrpc": "2.0", "id": 1, "result": { "x": 2 } })
+        );
+    }
+
+    #[test]
+    fn test_handle_rpc_errors() {
+        let handler = echo_handler();
+
+        let parse = handle_rpc(b"{", &handler);
+        let unknown = handle_rpc(br#"{"jsonrpc":"2.0","id":2,"method":"nope"}"#, &handler);
+        let failed = handle_rpc(br#"{"jsonrpc":"2.0","id":3,"method":"fail"}"#, &handler);
+
+        assert_eq!(parse["error"]["code"], PARSE_ERROR);
+        assert_eq!(unknown["error"]["code"], METHOD_NOT_FOUND);
+        assert_eq!(unknown["id"], 2);
+        assert_eq!(failed["error"]["code"], COMMAND_ERROR);
+        assert_eq!(failed["error"]["data"]["code"], "VALIDATION_ERROR");
+    }
+
+    fn call(address: &str, token: &str, body: &str) -> String {
+        let mut stream = TcpStream::connect(address).unwrap();
+        write!(
+            stream,
+            "POST /rpc HTTP/1.1\r\nAuthorization: Bearer {}\r\nContent-Length: {}\r\n\r\n{}",
+            token,
+            body.len(),
+            body
+        )
+        .unwrap();
+        let mut response = String::new();
+        stream.read_to_string(&mut response).unwrap();
+        response
+    }
+
+    #[test]
+    fn test_server_round_trip() {
+        let token = generate_token();
+        let status = start_server(0, false, &token, echo_handler()).unwrap();
+        let address = status.address.clone().unwrap();
+
+        let body = r#"{"jsonrpc":"2.0","id":7,"method":"echo","params":"hi"}"#;
+        let response = call(&address, &token, body);
+        let new_token = generate_token();
+        let swapped = set_server_token(&new_token);
+        let old_token_response = call(&address, &token, body);
+        let new_token_response = call(&address, &new_token, body);
+        // Idle connections fill every slot; the next one is turned away
+        while ACTIVE_CONNECTIONS.load(Ordering::Acquire) > 0 {
+            thread::sleep(Duration::from_millis(10));
+        }
+        let idle: Vec<TcpStream> = (0..MAX_CONNECTIONS)
+            .map(|_| TcpStream::connect(&address).unwrap())
+            .collect();
+        let mut rejected = String::new();
+        TcpStream::connect(&address)
+            .unwrap()
+            .read_to_string(&mut rejected)
+            .unwrap();
+        drop(idle);
+        let stopped = stop_server();
+
+        assert!(status.running);
+        assert_eq!(token.len(), 64);
+        assert!(response.starts_with("HTTP/1.1 200 OK"));
+        assert!(response.ends_with(r#""result":"hi"}"#));
+        assert!(swapped);
+        assert!(old_token_response.starts_with("HTTP/1.1 401"));
+        assert!(new_token_response.starts_with("HTTP/1.1 200 OK"));
+        assert!(rejected.starts_with("HTTP/1.1 503"));
+        assert!(stopped);
+        assert!(!server_status().running);
+    }
+}
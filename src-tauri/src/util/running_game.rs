@@ -0,0 +1,353 @@
+/// Detecting a running Minecraft and holding installs until it exits
+///
+/// The game rewrites options.txt (which lists the enabled packs) while it runs
+/// and again on exit, and it reads pack files lazily, so replacing a pack in an
+/// instance's resourcepacks folder under a running game races it. Game
+/// processes are recognized by their Java main class and tied to an instance
+/// through `--gameDir`, or their working directory for launchers that rely on
+/// it instead. What a build does when the game is running is configurable:
+/// warn and install anyway, wait for the game to exit, or leave the finished
+/// build staged in a queue to apply later.
+use crate::util::jobs::{self, CancelToken};
+use crate::util::settings;
+use anyhow::{Context, Result};
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::Duration;
+use tracing::info;
+
+/// File in the config directory listing queued installs
+const QUEUE_FILE: &str = "queued_installs.json";
+
+/// Held from loading the queue to saving it, so concurrent builds don't
+/// drop each other's entries
+static QUEUE_LOCK: Lazy<Mutex<()>> = Lazy::new(|| Mutex::new(()));
+
+/// How often a deferred install checks whether the game exited
+const EXIT_POLL_INTERVAL: Duration = Duration::from_secs(2);
+
+/// Main classes of the game and of the loaders and launchers that start it
+const GAME_MAIN_CLASSES: &[&str] = &[
+    "net.minecraft.client.main.Main",
+    "net.minecraft.launchwrapper.Launch",
+    "net.fabricmc.loader.impl.launch.knot.KnotClient",
+    "net.fabricmc.loader.launch.knot.KnotClient",
+    "org.quiltmc.loader.impl.launch.knot.KnotClient",
+    "cpw.mods.bootstraplauncher.BootstrapLauncher",
+    "cpw.mods.modlauncher.Launcher",
+    "net.neoforged.fml.startup.Client",
+    "org.prismlauncher.EntryPoint",
+    "org.multimc.EntryPoint",
+];
+
+/// What a build does when the game is running from its output's instance
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum RunningGamePolicy {
+    /// Install anyway and warn in the build log
+    Warn,
+    /// Keep the build running until the game exits, then install
+    Defer,
+    /// Leave the build staged and queue the install for later
+    Queue,
+}
+
+impl Default for RunningGamePolicy {
+    fn default() -> Self {
+        RunningGamePolicy::Warn
+    }
+}
+
+/// A running game process
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RunningGame {
+    pub pid: u32,
+    /// Game folder (the one holding options.txt), when it could be told
+    pub game_dir: Option<String>,
+}
+
+/// A finished build waiting for the game to exit before being installed
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct QueuedInstall {
+    pub output_dir: String,
+    /// Where the finished build is staged
+    pub staging_dir: String,
+    pub packs_dir: String,
+    /// Fingerprint of the build inputs, stamped once installed
+    pub input_fingerprint: String,
+    /// Unix seconds
+    pub queued_at: u64,
+}
+
+/// Value of `--gameDir` in a command line, up to the next option
+fn game_dir_argument(command_line: &str) -> Option<String> {
+    let (_, rest) = command_line.split_once("--gameDir ")?;
+    let value = rest.split(" --").next().unwrap_or(rest).trim();
+    let value = value.trim_matches('"');
+    (!value.is_empty()).then(|| value.to_string())
+}
+
+/// Recognize a game process from its command line
+///
+/// # Arguments
+/// * `cwd` - Working directory of the process, used when there is no
+///   `--gameDir`
+pub fn parse_game_process(pid: u32, command_line: &str, cwd: Option<&Path>) -> Option<RunningGame> {
+    if !GAME_MAIN_CLASSES
+        .iter()
+        .any(|class| command_line.contains(class))
+    {
+        return None;
+    }
+    let game_dir = game_dir_argument(command_line)
+        .or_else(|| cwd.map(|cwd| cwd.to_string_lossy().to_string()));
+    Some(RunningGame { pid, game_dir })
+}
+
+/// Command lines of running processes with their working directories
+#[cfg(target_os = "linux")]
+fn process_command_lines() -> Vec<(u32, String, Option<PathBuf>)> {
+    let entries = match fs::read_dir("/proc") {
+        Ok(entries) => entries,
+        Err(_) => return Vec::new(),
+    };
+    entries
+        .filter_map(|e| e.ok())
+        .filter_map(|entry| {
+            let pid = entry.file_name().to_string_lossy().parse::<u32>().ok()?;
+            let raw = fs::read(entry.path().join("cmdline")).ok()?;
+            let command_line = String::from_utf8_lossy(&raw).replace('\0', " ");
+            Some((
+                pid,
+                command_line,
+                fs::read_link(entry.path().join("cwd")).ok(),
+            ))
+        })
+        .collect()
+}
+
+/// Command lines of running processes with their working directories
+#[cfg(target_os = "macos")]
+fn process_command_lines() -> Vec<(u32, String, Option<PathBuf>)> {
+    let output = std::process::Command::new("ps")
+        .args(["-axww", "-o", "pid=,command="])
+        .output();
+    let stdout = match output {
+        Ok(output) => String::from_utf8_lossy(&output.stdout).to_string(),
+        Err(e) => {
+            tracing::warn!("Failed to list processes: {}", e);
+            return Vec::new();
+        }
+    };
+    stdout
+        .lines()
+        .filter_map(|line| {
+            let (pid, command_line) = line.trim_start().split_once(' ')?;
+            Some((pid.parse().ok()?, command_line.to_string(), None))
+        })
+        .collect()
+}
+
+/// Command lines of running processes with their working directories
+#[cfg(target_os = "windows")]
+fn process_command_lines() -> Vec<(u32, String, Option<PathBuf>)> {
+    let script = "Get-CimInstance Win32_Process -Filter \"Name LIKE 'java%'\" | \
+                  ForEach-Object { \"$($_.ProcessId) $($_.CommandLine)\" }";
+    let output = std::process::Command::new("powershell")
+        .args(["-NoProfile", "-NonInteractive", "-Command", script])
+        .output();
+    let stdout = match output {
+        Ok(output) => String::from_utf8_lossy(&output.stdout).to_string(),
+        Err(e) => {
+            tracing::warn!("Failed to list processes: {}", e);
+            return Vec::new();
+        }
+    };
+    stdout
+        .lines()
+        .filter_map(|line| {
+            let (pid, command_line) = line.trim().split_once(' ')?;
+            Some((pid.parse().ok()?, command_line.to_string(), None))
+        })
+        .collect()
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "macos", target_os = "windows")))]
+fn process_command_lines() -> Vec<(u32, String, Option<PathBuf>)> {
+    Vec::new()
+}
+
+/// Running game processes
+pub fn list_running_games() -> Vec<RunningGame> {
+    process_command_lines()
+        .into_iter()
+        .filter_map(|(pid, command_line, cwd)| {
+            parse_game_process(pid, &command_line, cwd.as_deref())
+        })
+        .collect()
+}
+
+/// Games that may be using a pack folder or file
+///
+/// A game matches when the target is inside its game folder. A game whose
+/// folder couldn't be told matches any target in a resourcepacks folder.
+pub fn games_using(games: &[RunningGame], target: &Path) -> Vec<RunningGame> {
+    let in_resourcepacks = target
+        .parent()
+        .and_then(|parent| parent.file_name())
+        .map_or(false, |name| name.eq_ignore_ascii_case("resourcepacks"));
+    games
+        .iter()
+        .filter(|game| match &game.game_dir {
+            Some(game_dir) => target.starts_with(game_dir),
+            None => in_resourcepacks,
+        })
+        .cloned()
+        .collect()
+}
+
+/// Block until no game uses the target any more
+///
+/// # Errors
+/// Fails with a `CancelledError` once the job is cancelled
+pub fn wait_for_games_to_exit(target: &Path, cancel: Option<&CancelToken>) -> Result<()> {
+    let mut waiting = games_using(&list_running_games(), target);
+    if !waiting.is_empty() {
+        info!(
+            "Waiting for Minecraft to exit before installing into {:?}",
+            target
+        );
+    }
+    while !waiting.is_empty() {
+        jobs::check_cancelled(cancel)?;
+        std::thread::sleep(EXIT_POLL_INTERVAL);
+        waiting = games_using(&list_running_games(), target);
+    }
+    Ok(())
+}
+
+/// Path of the install queue in the config directory
+pub fn default_queue_path() -> Result<PathBuf> {
+    Ok(settings::get_config_dir()?.join(QUEUE_FILE))
+}
+
+/// Queued installs, oldest first
+pub fn load_queued_installs(queue_path: &Path) -> Vec<QueuedInstall> {
+    fs::read_to_string(queue_path)
+        .ok()
+        .and_then(|contents| serde_json::from_str(&contents).ok())
+        .unwrap_or_default()
+}
+
+fn save_queued_installs(queue_path: &Path, installs: &[QueuedInstall]) -> Result<()> {
+    if let Some(parent) = queue_path.parent() {
+        fs::create_dir_all(parent).context("Failed to create config directory")?;
+    }
+    let json = serde_json::to_string_pretty(installs)?;
+    settings::write_file_atomic(queue_path, json.as_bytes())
+}
+
+/// Queue an install, replacing one queued earlier for the same output
+pub fn queue_install(queue_path: &Path, install: QueuedInstall) -> Result<()> {
+    let _queue_lock = QUEUE_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    let mut installs = load_queued_installs(queue_path);
+    installs.retain(|queued| queued.output_dir != install.output_dir);
+    installs.push(install);
+    save_queued_installs(queue_path, &installs)
+}
+
+/// Remove the queued install of an output
+///
+/// # Returns
+/// The removed install, if one was queued
+pub fn take_queued_install(queue_path: &Path, output_dir: &str) -> Result<Option<QueuedInstall>> {
+    let _queue_lock = QUEUE_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    let mut installs = load_queued_installs(queue_path);
+    let position = installs
+        .iter()
+        .position(|queued| queued.output_dir == output_dir);
+    let taken = position.map(|position| installs.remove(position));
+    if taken.is_some() {
+        save_queued_installs(queue_path, &installs)?;
+    }
+    Ok(taken)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_game_process() {
+        let vanilla = "java -Xmx4G -cp client.jar net.minecraft.client.main.Main \
+                       --username Steve --gameDir /home/steve/.minecraft --assetsDir /x";
+        let prism = "java -cp NewLaunch.jar org.prismlauncher.EntryPoint";
+        let other = "java -jar server.jar --gameDir /srv/mc";
+
+        let vanilla = parse_game_process(1, vanilla, None).unwrap();
+        let prism = parse_game_process(2, prism, Some(Path::new("/inst/.minecraft"))).unwrap();
+
+        assert_eq!(vanilla.game_dir.as_deref(), Some("/home/steve/.minecraft"));
+        assert_eq!(prism.game_dir.as_deref(), Some("/inst/.minecraft"));
+        assert!(parse_game_process(3, other, None).is_none());
+    }
+
+    #[test]
+    fn test_games_using() {
+        let games = vec![
+            RunningGame {
+                pid: 1,
+                game_dir: Some("/mc/a".to_string()),
+            },
+            RunningGame {
+                pid: 2,
+                game_dir: None,
+            },
+        ];
+
+        let in_a = games_using(&games, Path::new("/mc/a/resourcepacks/Weaver Nest"));
+        let in_b = games_using(&games, Path::new("/mc/b/resourcepacks/Weaver Nest"));
+        let elsewhere = games_using(&games, Path::new("/home/me/builds/Weaver Nest"));
+
+        assert_eq!(in_a.len(), 2);
+        assert_eq!(in_b.len(), 1);
+        assert_eq!(in_b[0].pid, 2);
+        assert!(elsewhere.is_empty());
+    }
+
+    #[test]
+    fn test_install_queue() {
+        let temp_dir = std::env::temp_dir().join("test_install_queue");
+        fs::remove_dir_all(&temp_dir).ok();
+        let queue_path = temp_dir.join(QUEUE_FILE);
+        let install = |output_dir: &str, queued_at: u64| QueuedInstall {
+            output_dir: output_dir.to_string(),
+            staging_dir: format!("{}.staging", output_dir),
+            packs_dir: "/packs".to_string(),
+            input_fingerprint: "abc".to_string(),
+            queued_at,
+        };
+
+        queue_install(&queue_path, install("/mc/a", 1)).unwrap();
+        queue_install(&queue_path, install("/mc/b", 2)).unwrap();
+        queue_install(&queue_path, install("/mc/a", 3)).unwrap();
+        let queued = load_queued_installs(&queue_path);
+        let taken = take_queued_install(&queue_path, "/mc/b").unwrap();
+        let missing = take_queued_install(&queue_path, "/mc/b").unwrap();
+        let remaining = load_queued_installs(&queue_path);
+
+        // Clean up
+        fs::remove_dir_all(&temp_dir).ok();
+
+        assert_eq!(queued.len(), 2);
+        assert_eq!(queued[1].queued_at, 3);
+        assert_eq!(taken.unwrap().output_dir, "/mc/b");
+        assert!(missing.is_none());
+        assert_eq!(remaining.len(), 1);
+    }
+}
@@ -0,0 +1,154 @@
+/// Zipped merges for hosting on a server
+///
+/// A server hands players its resource pack through server.properties: a
+/// download URL, the zip's SHA-1 so clients can verify and cache it, and
+/// optionally a prompt and whether the pack is required. Many users merge
+/// packs just to host the result, so the pack builder's server export mode
+/// writes a reproducible zip (see `pack_archive::repack_pack_folder`) along
+/// with its hash and the lines to paste into server.properties.
+use crate::util::{pack_archive, post_build};
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::Path;
+
+/// What the server offers players
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ServerExportOptions {
+    /// Where the zip will be downloaded from, for `resource-pack=`
+    #[serde(default)]
+    pub download_url: Option<String>,
+    /// Kick players who decline the pack
+    #[serde(default)]
+    pub require: bool,
+    /// Message shown when the server offers the pack
+    #[serde(default)]
+    pub prompt: Option<String>,
+}
+
+/// A zip ready to host
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ServerExport {
+    /// Lowercase hex SHA-1 of the zip
+    pub sha1: String,
+    pub size: u64,
+    /// Files in the zip
+    pub files: usize,
+    /// Lines for server.properties
+    pub properties: String,
+}
+
+/// Escape a value for a .properties file
+fn properties_value(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    for (i, c) in value.chars().enumerate() {
+        match c {
+            '\\' => escaped.push_str("\\\\"),
+            '\n' => escaped.push_str("\\n"),
+            '\r' => escaped.push_str("\\r"),
+            // Leading whitespace would be dropped when read back
+            ' ' if i == 0 => escaped.push_str("\\ "),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// server.properties lines offering a pack
+///
+/// Without a download URL `resource-pack=` is left empty to fill in once
+/// the zip is uploaded.
+pub fn server_properties(sha1: &str, options: &ServerExportOptions) -> String {
+    let mut lines = vec![
+        format!(
+            "resource-pack={}",
+            properties_value(options.download_url.as_deref().unwrap_or_default())
+        ),
+        format!("resource-pack-sha1={}", sha1),
+        format!("require-resource-pack={}", options.require),
+    ];
+    if let Some(prompt) = options.prompt.as_deref().filter(|p| !p.trim().is_empty()) {
+        // The prompt is a JSON text component; a JSON string is the plain form
+        let prompt = serde_json::Value::from(prompt).to_string();
+        lines.push(format!(
+            "resource-pack-prompt={}",
+            properties_value(&prompt)
+        ));
+    }
+    lines.join("\n") + "\n"
+}
+
+/// Zip a pack folder for a server and hash the zip
+///
+/// # Arguments
+/// * `pack_dir` - Folder holding the pack
+/// * `zip_path` - Zip to write (replaced if it exists)
+/// * `options` - What the server offers players
+pub fn export_for_server(
+    pack_dir: &Path,
+    zip_path: &Path,
+    options: &ServerExportOptions,
+) -> Result<ServerExport> {
+    let report = pack_archive::repack_pack_folder(pack_dir, zip_path, None)?;
+    let sha1 = post_build::hash_output_sha1(zip_path)?;
+
+    Ok(ServerExport {
+        properties: server_properties(&sha1, options),
+        sha1,
+        size: fs::metadata(zip_path)?.len(),
+        files: report.files,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_export_for_server() {
+        let temp_dir = std::env::temp_dir().join("test_export_for_server");
+        fs::remove_dir_all(&temp_dir).ok();
+        let pack_dir = temp_dir.join("merged");
+        fs::create_dir_all(&pack_dir).unwrap();
+        fs::write(
+            pack_dir.join("pack.mcmeta"),
+            r#"{"pack": {"pack_format": 34}}"#,
+        )
+        .unwrap();
+        fs::write(pack_dir.join(".DS_Store"), "junk").unwrap();
+        let zip_path = temp_dir.join("server.zip");
+        let options = ServerExportOptions {
+            download_url: Some("https://example.com/pack.zip".to_string()),
+            require: true,
+            prompt: Some("Textures for \"Survival\"".to_string()),
+        };
+
+        let export = export_for_server(&pack_dir, &zip_path, &options).unwrap();
+        let again = export_for_server(&pack_dir, &zip_path, &options).unwrap();
+        let bare = server_properties("abc", &ServerExportOptions::default());
+
+        // Clean up
+        fs::remove_dir_all(&temp_dir).ok();
+
+        assert_eq!(export.files, 1);
+        assert_eq!(export.sha1.len(), 40);
+        // Repacked zips are reproducible, so the hash only changes with the pack
+        assert_eq!(again.sha1, export.sha1);
+        assert_eq!(
+            export.properties,
+            format!(
+                "resource-pack=https://example.com/pack.zip\n\
+                 resource-pack-sha1={}\n\
+                 require-resource-pack=true\n\
+                 resource-pack-prompt=\"Textures for \\\\\"Survival\\\\\"\"\n",
+                export.sha1
+            )
+        );
+        assert_eq!(
+            bare,
+            "resource-pack=\nresource-pack-sha1=abc\nrequire-resource-pack=false\n"
+        );
+    }
+}
@@ -0,0 +1,352 @@
+/// Snapshot of the last session for instant startup
+///
+/// Scanning a large library takes a while even with cached pack listings.
+/// After every scan the pack list, asset index and the winning pack of each
+/// asset are saved to one file in the user cache folder, together with the
+/// pack order and overrides the UI last reported. On launch the snapshot is
+/// shown right away, marked as possibly stale, while a fresh scan runs in
+/// the background; `diff_snapshots` tells the UI what that scan changed.
+use crate::model::{OverrideSelection, PackMeta, ScanResult};
+use crate::util::stack_simulation::{self, WinnerChange};
+use crate::util::vanilla_textures::VANILLA_PACK_ID;
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+use std::fs::{self, File};
+use std::io::{BufReader, BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+use tracing::{info, warn};
+
+/// Bumped when the snapshot layout changes; older snapshots are ignored
+const SNAPSHOT_VERSION: u32 = 1;
+
+/// Event sent once the background scan after a restore finished
+pub const SESSION_RECONCILED_EVENT: &str = "session:reconciled";
+/// Event sent if the background scan after a restore failed
+pub const SESSION_RECONCILE_FAILED_EVENT: &str = "session:reconcile-failed";
+
+/// Everything needed to show the last session before a scan finishes
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SessionSnapshot {
+    pub version: u32,
+    pub packs_dir: String,
+    /// Pack IDs in priority order (first = highest)
+    pub pack_order: Vec<String>,
+    #[serde(default)]
+    pub overrides: HashMap<String, OverrideSelection>,
+    pub scan: ScanResult,
+    /// Asset ID -> winning pack under `pack_order` and `overrides`
+    pub winners: BTreeMap<String, String>,
+    /// When the snapshot was saved, in seconds since the Unix epoch
+    pub saved_at: u64,
+}
+
+/// What a fresh scan changed compared to a snapshot
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SessionChanges {
+    pub added_packs: Vec<String>,
+    pub removed_packs: Vec<String>,
+    /// Assets that only one of the two snapshots has
+    pub added_assets: usize,
+    pub removed_assets: usize,
+    /// Assets won by a different pack, sorted by asset ID
+    pub changed_winners: Vec<WinnerChange>,
+}
+
+impl SessionChanges {
+    pub fn is_empty(&self) -> bool {
+        *self == SessionChanges::default()
+    }
+}
+
+/// The last session, restored before it could be checked
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RestoredSession {
+    pub snapshot: SessionSnapshot,
+    /// Packs may have changed on disk since the snapshot was saved
+    pub possibly_stale: bool,
+    /// Job of the background scan, for `cancel_job`
+    pub reconcile_job_id: Option<String>,
+}
+
+/// Payload of `SESSION_RECONCILED_EVENT`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SessionReconciled {
+    /// The snapshot after the fresh scan
+    pub snapshot: SessionSnapshot,
+    pub changes: SessionChanges,
+}
+
+/// File holding the snapshot
+pub fn session_snapshot_path() -> Option<PathBuf> {
+    dirs::cache_dir().map(|dir| dir.join("weaverbird_session.json"))
+}
+
+fn now_secs() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |elapsed| elapsed.as_secs())
+}
+
+/// Keep the previous order of packs still present; new packs go just
+/// above vanilla, or at the end if the order has no vanilla pack
+pub fn merge_pack_order(previous: &[String], packs: &[PackMeta]) -> Vec<String> {
+    let present: HashSet<&str> = packs.iter().map(|pack| pack.id.as_str()).collect();
+    let mut order: Vec<String> = previous
+        .iter()
+        .filter(|id| present.contains(id.as_str()))
+        .cloned()
+        .collect();
+    let known: HashSet<String> = order.iter().cloned().collect();
+    let added: Vec<String> = packs
+        .iter()
+        .filter(|pack| !known.contains(&pack.id))
+        .map(|pack| pack.id.clone())
+        .collect();
+
+    let insert_at = order
+        .iter()
+        .position(|id| id == VANILLA_PACK_ID)
+        .unwrap_or(order.len());
+    order.splice(insert_at..insert_at, added);
+    order
+}
+
+/// Build a snapshot from a scan
+///
+/// # Arguments
+/// * `previous` - Last snapshot; its order and overrides carry over when
+///   it's of the same packs folder
+pub fn snapshot_from_scan(
+    packs_dir: &str,
+    scan: ScanResult,
+    previous: Option<&SessionSnapshot>,
+) -> SessionSnapshot {
+    let previous = previous.filter(|snapshot| snapshot.packs_dir == packs_dir);
+    let pack_order = match previous {
+        Some(snapshot) => merge_pack_order(&snapshot.pack_order, &scan.packs),
+        None => scan.packs.iter().map(|pack| pack.id.clone()).collect(),
+    };
+    let overrides = previous.map_or_else(HashMap::new, |snapshot| snapshot.overrides.clone());
+    let winners = stack_simulation::compute_winners(&pack_order, &overrides, &scan.providers);
+
+    SessionSnapshot {
+        version: SNAPSHOT_VERSION,
+        packs_dir: packs_dir.to_string(),
+        pack_order,
+        overrides,
+        scan,
+        winners,
+        saved_at: now_secs(),
+    }
+}
+
+/// Replace the order and overrides of a snapshot, recomputing winners
+pub fn update_stack(
+    snapshot: &mut SessionSnapshot,
+    pack_order: Vec<String>,
+    overrides: HashMap<String, OverrideSelection>,
+) {
+    snapshot.winners =
+        stack_simulation::compute_winners(&pack_order, &overrides, &snapshot.scan.providers);
+    snapshot.pack_order = pack_order;
+    snapshot.overrides = overrides;
+    snapshot.saved_at = now_secs();
+}
+
+/// Load a snapshot
+///
+/// # Returns
+/// None if there is none, or it was written by another version
+pub fn load_snapshot(path: &Path) -> Result<Option<SessionSnapshot>> {
+    let file = match File::open(path) {
+        Ok(file) => file,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+        Err(e) => return Err(e).with_context(|| format!("Failed to open {:?}", path)),
+    };
+    let snapshot: SessionSnapshot = match serde_json::from_reader(BufReader::new(file)) {
+        Ok(snapshot) => snapshot,
+        Err(e) => {
+            warn!("Ignoring unreadable session snapshot {:?}: {}", path, e);
+            return Ok(None);
+        }
+    };
+    if snapshot.version != SNAPSHOT_VERSION {
+        info!(
+            "Ignoring session snapshot of version {} (expected {})",
+            snapshot.version, SNAPSHOT_VERSION
+        );
+        return Ok(None);
+    }
+    Ok(Some(snapshot))
+}
+
+/// Save a snapshot, replacing the file only once it's fully written
+pub fn save_snapshot(path: &Path, snapshot: &SessionSnapshot) -> Result<()> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).with_context(|| format!("Failed to create {:?}", parent))?;
+    }
+    let temp_path = path.with_extension("json.tmp");
+    let file =
+        File::create(&temp_path).with_context(|| format!("Failed to create {:?}", temp_path))?;
+    let mut writer = BufWriter::new(file);
+    serde_json::to_writer(&mut writer, snapshot).context("Failed to write session snapshot")?;
+    writer.flush().context("Failed to write session snapshot")?;
+    drop(writer);
+    fs::rename(&temp_path, path).with_context(|| format!("Failed to replace {:?}", path))?;
+    Ok(())
+}
+
+/// Save a scan as the last session
+///
+/// The order and overrides of the saved snapshot carry over if it's of
+/// the same packs folder.
+pub fn remember_scan(path: &Path, packs_dir: &str, scan: ScanResult) -> Result<SessionSnapshot> {
+    let previous = load_snapshot(path)?;
+    let snapshot = snapshot_from_scan(packs_dir, scan, previous.as_ref());
+    save_snapshot(path, &snapshot)?;
+    Ok(snapshot)
+}
+
+/// What changed between an old and a new snapshot
+pub fn diff_snapshots(old: &SessionSnapshot, new: &SessionSnapshot) -> SessionChanges {
+    let old_packs: BTreeSet<&str> = old.scan.packs.iter().map(|pack| pack.id.as_str()).collect();
+    let new_packs: BTreeSet<&str> = new.scan.packs.iter().map(|pack| pack.id.as_str()).collect();
+    let old_assets: HashSet<&str> = old
+        .scan
+        .assets
+        .iter()
+        .map(|asset| asset.id.as_str())
+        .collect();
+    let new_assets: HashSet<&str> = new
+        .scan
+        .assets
+        .iter()
+        .map(|asset| asset.id.as_str())
+        .collect();
+
+    let asset_ids: BTreeSet<&String> = old.winners.keys().chain(new.winners.keys()).collect();
+    let changed_winners = asset_ids
+        .into_iter()
+        .filter_map(|asset_id| {
+            let a = old.winners.get(asset_id);
+            let b = new.winners.get(asset_id);
+            (a != b).then(|| WinnerChange {
+                asset_id: asset_id.clone(),
+                a: a.cloned(),
+                b: b.cloned(),
+            })
+        })
+        .collect();
+
+    SessionChanges {
+        added_packs: new_packs
+            .difference(&old_packs)
+            .map(|id| id.to_string())
+            .collect(),
+        removed_packs: old_packs
+            .difference(&new_packs)
+            .map(|id| id.to_string())
+            .collect(),
+        added_assets: new_assets.difference(&old_assets).count(),
+        removed_assets: old_assets.difference(&new_assets).count(),
+        changed_winners,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pack(id: &str) -> PackMeta {
+        PackMeta {
+            id: id.to_string(),
+            name: id.to_string(),
+            path: id.to_string(),
+            ..Default::default()
+        }
+    }
+
+    fn scan(pack_ids: &[&str], providers: &[(&str, &[&str])]) -> ScanResult {
+        ScanResult {
+            packs: pack_ids.iter().map(|id| pack(id)).collect(),
+            providers: providers
+                .iter()
+                .map(|(asset, packs)| {
+                    (
+                        asset.to_string(),
+                        packs.iter().map(|id| id.to_string()).collect(),
+                    )
+                })
+                .collect(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_merge_pack_order() {
+        let previous = vec![
+            "b".to_string(),
+            "gone".to_string(),
+            "a".to_string(),
+            VANILLA_PACK_ID.to_string(),
+        ];
+        let packs = vec![pack("a"), pack("b"), pack("new"), pack(VANILLA_PACK_ID)];
+
+        let order = merge_pack_order(&previous, &packs);
+
+        assert_eq!(order, vec!["b", "a", "new", VANILLA_PACK_ID]);
+    }
+
+    #[test]
+    fn test_snapshot_round_trip_and_diff() {
+        let temp_dir = std::env::temp_dir().join("test_session_cache");
+        fs::remove_dir_all(&temp_dir).ok();
+        let path = temp_dir.join("session.json");
+
+        let mut old = snapshot_from_scan(
+            "/packs",
+            scan(&["a", "b"], &[("minecraft:block/stone", &["a", "b"])]),
+            None,
+        );
+        update_stack(
+            &mut old,
+            vec!["b".to_string(), "a".to_string()],
+            HashMap::new(),
+        );
+        save_snapshot(&path, &old).unwrap();
+        let loaded = load_snapshot(&path).unwrap().unwrap();
+        let missing = load_snapshot(&temp_dir.join("missing.json")).unwrap();
+
+        // "b" was removed; the saved order carries over for the rest
+        let new = snapshot_from_scan(
+            "/packs",
+            scan(&["a", "c"], &[("minecraft:block/stone", &["a", "c"])]),
+            Some(&loaded),
+        );
+        let changes = diff_snapshots(&loaded, &new);
+
+        // Clean up
+        fs::remove_dir_all(&temp_dir).ok();
+
+        assert_eq!(loaded.winners["minecraft:block/stone"], "b");
+        assert!(missing.is_none());
+        assert_eq!(new.pack_order, vec!["a", "c"]);
+        assert_eq!(changes.added_packs, vec!["c"]);
+        assert_eq!(changes.removed_packs, vec!["b"]);
+        assert_eq!(
+            changes.changed_winners,
+            vec![WinnerChange {
+                asset_id: "minecraft:block/stone".to_string(),
+                a: Some("b".to_string()),
+                b: Some("a".to_string()),
+            }]
+        );
+        assert!(!changes.is_empty());
+    }
+}
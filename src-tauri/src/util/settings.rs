@@ -0,0 +1,380 @@
+/// Persistent application settings
+///
+/// Settings are stored as a single JSON document in the user's config directory
+/// (e.g. ~/.config/weaverbird/settings.json). Missing fields fall back to their
+/// defaults so older settings files keep loading as new options are added.
+///
+/// The document carries a schema version. Older files are migrated forward on
+/// load (after backing up the original), and fields this version doesn't know
+/// about are kept so a downgrade never drops configuration. Writes go to a
+/// temporary file that is renamed over the old one, so a crash mid-write
+/// leaves the previous settings intact. Commands that change settings hold
+/// [`lock_settings`] from load to save so concurrent changes aren't lost.
+use crate::util::conflict_presets::ConflictPreset;
+use crate::util::conflicts::ConflictSeveritySettings;
+use crate::util::parallelism::ParallelismSettings;
+use crate::util::post_build::PostBuildHook;
+use crate::util::projects::Project;
+use crate::util::rpc_server::RemoteServerSettings;
+use crate::util::running_game::RunningGamePolicy;
+use crate::util::vanilla_textures::VanillaVersionSettings;
+use anyhow::{anyhow, Context, Result};
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Mutex, MutexGuard};
+use tracing::info;
+
+const SETTINGS_FILE: &str = "settings.json";
+
+/// Held from load to save by everything that changes settings
+static SETTINGS_LOCK: Lazy<Mutex<()>> = Lazy::new(|| Mutex::new(()));
+
+/// Numbers the temp files of `write_file_atomic` within this process
+static TEMP_FILE_COUNTER: AtomicU64 = AtomicU64::new(0);
+
+/// Current settings schema version
+pub const SETTINGS_SCHEMA_VERSION: u32 = 2;
+
+/// Key holding the schema version in the settings document
+const SCHEMA_VERSION_KEY: &str = "schemaVersion";
+
+/// Upgrades the settings document by one schema version
+type Migration = fn(&mut Map<String, Value>) -> Result<()>;
+
+/// MIGRATIONS[i] upgrades version i + 1 to version i + 2
+const MIGRATIONS: &[Migration] = &[migrate_v1_to_v2];
+
+/// User-configurable application settings
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct AppSettings {
+    /// Saved conflict resolution presets
+    pub conflict_presets: Vec<ConflictPreset>,
+    /// How severe conflicts of each asset category are
+    pub conflict_severity: ConflictSeveritySettings,
+    /// Saved projects available for batch builds
+    pub projects: Vec<Project>,
+    /// Actions run after every successful build
+    pub post_build_hooks: Vec<PostBuildHook>,
+    /// Directories the user granted filesystem access to
+    pub granted_paths: Vec<String>,
+    /// Thread pool sizes (applied on the next start)
+    pub parallelism: ParallelismSettings,
+    /// Which installed version vanilla assets come from
+    pub vanilla_version: VanillaVersionSettings,
+    /// Local JSON-RPC server for scripting
+    pub remote_server: RemoteServerSettings,
+    /// What builds do when Minecraft is running from the output's instance
+    pub running_game: RunningGamePolicy,
+    /// Description template for builds that don't set their own
+    pub description_template: Option<String>,
+    /// Schema version the document was written with
+    pub schema_version: u32,
+    /// Fields from newer versions, preserved when saving
+    #[serde(flatten)]
+    pub unknown_fields: Map<String, Value>,
+}
+
+/// Get the directory where Weaverbird stores its configuration
+pub fn get_config_dir() -> Result<PathBuf> {
+    let config_dir = dirs::config_dir()
+        .ok_or_else(|| anyhow!("Could not find config directory"))?
+        .join("weaverbird");
+
+    fs::create_dir_all(&config_dir).context("Failed to create config directory")?;
+
+    Ok(config_dir)
+}
+
+/// Get the path of the settings file
+pub fn get_settings_path() -> Result<PathBuf> {
+    Ok(get_config_dir()?.join(SETTINGS_FILE))
+}
+
+/// Load settings from the default location
+pub fn load_settings() -> Result<AppSettings> {
+    load_settings_from(&get_settings_path()?)
+}
+
+/// Save settings to the default location
+pub fn save_settings(settings: &AppSettings) -> Result<()> {
+    save_settings_to(&get_settings_path()?, settings)
+}
+
+/// Version 1 files predate the schema version field; the layout is unchanged
+fn migrate_v1_to_v2(_settings: &mut Map<String, Value>) -> Result<()> {
+    Ok(())
+}
+
+/// Migrate a settings document to the current schema version
+///
+/// Documents without a version are treated as version 1. Documents from a
+/// newer version are left untouched.
+///
+/// # Returns
+/// The version the document had before migrating
+pub fn migrate_settings(document: &mut Value) -> Result<u32> {
+    let settings = document
+        .as_object_mut()
+        .ok_or_else(|| anyhow!("Settings file is not a JSON object"))?;
+    let version = settings
+        .get(SCHEMA_VERSION_KEY)
+        .and_then(Value::as_u64)
+        .map_or(1, |v| v.max(1) as u32);
+
+    if version > SETTINGS_SCHEMA_VERSION {
+        info!(
+            "Settings were written by a newer version (schema {}), keeping unknown fields",
+            version
+        );
+        return Ok(version);
+    }
+
+    for (from, migration) in MIGRATIONS
+        .iter()
+        .enumerate()
+        .skip(version as usize - 1)
+        .map(|(i, m)| (i + 1, m))
+    {
+        migration(settings)
+            .with_context(|| format!("Failed to migrate settings from schema {}", from))?;
+    }
+    settings.insert(
+        SCHEMA_VERSION_KEY.to_string(),
+        Value::from(SETTINGS_SCHEMA_VERSION),
+    );
+
+    Ok(version)
+}
+
+/// Load settings from a specific file, returning defaults if it doesn't exist
+///
+/// Older files are backed up next to the original (settings.json.v1.bak)
+/// before being migrated; the migrated form is written on the next save.
+pub fn load_settings_from(path: &Path) -> Result<AppSettings> {
+    if !path.exists() {
+        return Ok(AppSettings {
+            schema_version: SETTINGS_SCHEMA_VERSION,
+            ..Default::default()
+        });
+    }
+
+    let contents = fs::read_to_string(path).context("Failed to read settings file")?;
+    let mut document: Value =
+        serde_json::from_str(&contents).context("Failed to parse settings file")?;
+
+    let from_version = migrate_settings(&mut document)?;
+    if from_version < SETTINGS_SCHEMA_VERSION {
+        let backup = path.with_file_name(format!("{}.v{}.bak", SETTINGS_FILE, from_version));
+        if !backup.exists() {
+            fs::copy(path, &backup).context("Failed to back up settings before migrating")?;
+        }
+        info!(
+            "Migrated settings from schema {} to {}",
+            from_version, SETTINGS_SCHEMA_VERSION
+        );
+    }
+
+    serde_json::from_value(document).context("Failed to parse settings file")
+}
+
+/// Lock the settings for a load -> change -> save
+///
+/// Without it, two commands saving at once each write back what they
+/// loaded and one change is lost. Hold the guard until the save is done.
+pub fn lock_settings() -> MutexGuard<'static, ()> {
+    SETTINGS_LOCK.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+/// Write a file atomically: write a sibling temp file, flush it, then rename
+///
+/// The temp file is named after the process and a counter, so concurrent
+/// writes of the same file never share one.
+pub fn write_file_atomic(path: &Path, contents: &[u8]) -> Result<()> {
+    let file_name = path
+        .file_name()
+        .ok_or_else(|| anyhow!("Invalid file path: {:?}", path))?;
+    let temp_path = path.with_file_name(format!(
+        "{}.{}.{}.tmp",
+        file_name.to_string_lossy(),
+        std::process::id(),
+        TEMP_FILE_COUNTER.fetch_add(1, Ordering::Relaxed)
+    ));
+
+    let result = (|| -> Result<()> {
+        let mut file = fs::File::create(&temp_path)
+            .with_context(|| format!("Failed to create {:?}", temp_path))?;
+        file.write_all(contents)
+            .with_context(|| format!("Failed to write {:?}", temp_path))?;
+        file.sync_all()
+            .with_context(|| format!("Failed to flush {:?}", temp_path))?;
+        fs::rename(&temp_path, path).with_context(|| format!("Failed to replace {:?}", path))
+    })();
+
+    if result.is_err() {
+        fs::remove_file(&temp_path).ok();
+    }
+    result
+}
+
+/// Save settings to a specific file
+///
+/// The current schema version is stamped unless the settings came from a
+/// newer version, whose number is kept along with its unknown fields.
+pub fn save_settings_to(path: &Path, settings: &AppSettings) -> Result<()> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).context("Failed to create settings directory")?;
+    }
+
+    let mut settings = settings.clone();
+    settings.schema_version = settings.schema_version.max(SETTINGS_SCHEMA_VERSION);
+
+    let json = serde_json::to_string_pretty(&settings)?;
+    write_file_atomic(path, json.as_bytes()).context("Failed to write settings file")?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::util::conflict_presets::ResolutionPolicy;
+
+    #[test]
+    fn test_load_settings_missing_file() {
+        let path = std::env::temp_dir().join("test_settings_missing/settings.json");
+        let settings = load_settings_from(&path).expect("should load defaults");
+        assert!(settings.conflict_presets.is_empty());
+    }
+
+    #[test]
+    fn test_settings_roundtrip() {
+        let temp_dir = std::env::temp_dir().join("test_settings_roundtrip");
+        let path = temp_dir.join("settings.json");
+
+        let settings = AppSettings {
+            conflict_presets: vec![ConflictPreset {
+                name: "HD-first".to_string(),
+                description: None,
+                policy: ResolutionPolicy::HighestResolution,
+                rules: Vec::new(),
+            }],
+            ..Default::default()
+        };
+
+        save_settings_to(&path, &settings).expect("should save");
+        let loaded = load_settings_from(&path).expect("should load");
+
+        // Clean up
+        fs::remove_dir_all(&temp_dir).ok();
+
+        assert_eq!(loaded.conflict_presets.len(), 1);
+        assert_eq!(loaded.conflict_presets[0].name, "HD-first");
+    }
+
+    #[test]
+    fn test_concurrent_atomic_writes_use_their_own_temp_files() {
+        let temp_dir = std::env::temp_dir().join("test_settings_concurrent_writes");
+        fs::remove_dir_all(&temp_dir).ok();
+        fs::create_dir_all(&temp_dir).unwrap();
+        let path = temp_dir.join("state.json");
+
+        let writers: Vec<_> = (0..8)
+            .map(|i| {
+                let path = path.clone();
+                std::thread::spawn(move || {
+                    write_file_atomic(&path, i.to_string().repeat(4096).as_bytes())
+                })
+            })
+            .collect();
+        let results: Vec<Result<()>> = writers.into_iter().map(|w| w.join().unwrap()).collect();
+        let contents = fs::read_to_string(&path).unwrap();
+        let leftovers = fs::read_dir(&temp_dir).unwrap().count();
+
+        // Clean up
+        fs::remove_dir_all(&temp_dir).ok();
+
+        assert!(results.iter().all(|r| r.is_ok()));
+        // One writer's contents in full, not a mix
+        assert_eq!(contents.len(), 4096);
+        assert_eq!(contents, contents[..1].repeat(4096));
+        assert_eq!(leftovers, 1);
+    }
+
+    #[test]
+    fn test_load_settings_tolerates_missing_fields() {
+        let temp_dir = std::env::temp_dir().join("test_settings_partial");
+        let path = temp_dir.join("settings.json");
+        fs::create_dir_all(&temp_dir).expect("Failed to create test directory");
+        fs::write(&path, "{}").expect("Failed to write settings");
+
+        let loaded = load_settings_from(&path);
+
+        // Clean up
+        fs::remove_dir_all(&temp_dir).ok();
+
+        assert!(loaded.is_ok());
+        assert!(loaded.unwrap().conflict_presets.is_empty());
+    }
+
+    #[test]
+    fn test_load_settings_migrates_unversioned_file() {
+        let temp_dir = std::env::temp_dir().join("test_settings_migrate");
+        let path = temp_dir.join("settings.json");
+        fs::create_dir_all(&temp_dir).expect("Failed to create test directory");
+        fs::write(&path, r#"{"grantedPaths": ["/packs"]}"#).expect("Failed to write settings");
+
+        let loaded = load_settings_from(&path);
+        let backup = fs::read_to_string(temp_dir.join("settings.json.v1.bak"));
+
+        // Clean up
+        fs::remove_dir_all(&temp_dir).ok();
+
+        let loaded = loaded.expect("should migrate");
+        assert_eq!(loaded.schema_version, SETTINGS_SCHEMA_VERSION);
+        assert_eq!(loaded.granted_paths, vec!["/packs"]);
+        assert!(loaded.unknown_fields.is_empty());
+        assert!(backup.expect("backup should exist").contains("/packs"));
+    }
+
+    #[test]
+    fn test_settings_from_newer_version_keep_unknown_fields() {
+        let temp_dir = std::env::temp_dir().join("test_settings_newer");
+        let path = temp_dir.join("settings.json");
+        fs::create_dir_all(&temp_dir).expect("Failed to create test directory");
+        fs::write(
+            &path,
+            r#"{"schemaVersion": 99, "grantedPaths": ["/packs"], "futureOption": {"a": 1}}"#,
+        )
+        .expect("Failed to write settings");
+
+        let loaded = load_settings_from(&path).expect("should load");
+        save_settings_to(&path, &loaded).expect("should save");
+        let saved: Value = serde_json::from_str(&fs::read_to_string(&path).unwrap()).unwrap();
+        let leftovers: Vec<_> = fs::read_dir(&temp_dir)
+            .unwrap()
+            .filter_map(|e| e.ok())
+            .map(|e| e.file_name().to_string_lossy().to_string())
+            .collect();
+
+        // Clean up
+        fs::remove_dir_all(&temp_dir).ok();
+
+        assert_eq!(saved["schemaVersion"], 99);
+        assert_eq!(saved["futureOption"]["a"], 1);
+        assert_eq!(saved["grantedPaths"][0], "/packs");
+        // No temp file or migration backup left behind
+        assert_eq!(leftovers, vec!["settings.json"]);
+    }
+
+    #[test]
+    fn test_migrate_settings_rejects_non_object() {
+        let mut document = serde_json::json!([1, 2, 3]);
+        assert!(migrate_settings(&mut document).is_err());
+    }
+}
@@ -0,0 +1,575 @@
+/// Dry-run validation of an instance's enabled pack stack
+///
+/// Reads the packs enabled in an instance's options.txt, in the order the
+/// game applies them, and runs the usual checks against that exact stack:
+/// missing packs, pack format mismatches, overrides between packs, namespace
+/// collisions and mods the packs need but the instance doesn't have. Nothing
+/// is built; this answers "why does my game look wrong".
+use crate::model::PackMeta;
+use crate::util::animation::read_pack_bytes;
+use crate::util::feature_requirements::{self, FeatureRequirement};
+use crate::util::{asset_indexer, namespace_conflicts, pack_scanner};
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Prefix options.txt uses for packs in the resourcepacks folder
+const FILE_PACK_PREFIX: &str = "file/";
+
+/// Latest data version the format table below knows about (1.21.8)
+const LATEST_KNOWN_DATA_VERSION: u32 = 4440;
+
+/// First data version of each resource pack format, newest first
+const PACK_FORMATS_BY_DATA_VERSION: &[(u32, u32)] = &[
+    (4438, 64), // 1.21.7
+    (4435, 63), // 1.21.6
+    (4325, 55), // 1.21.5
+    (4189, 46), // 1.21.4
+    (4080, 42), // 1.21.2
+    (3953, 34), // 1.21
+    (3837, 32), // 1.20.5
+    (3698, 22), // 1.20.3
+    (3578, 18), // 1.20.2
+    (3463, 15), // 1.20
+    (3337, 13), // 1.19.4
+    (3218, 12), // 1.19.3
+    (3105, 9),  // 1.19
+    (2860, 8),  // 1.18
+    (2724, 7),  // 1.17
+    (2578, 6),  // 1.16.2
+    (2225, 5),  // 1.15
+    (1519, 4),  // 1.13
+    (819, 3),   // 1.11
+    (169, 2),   // 1.9
+];
+
+/// Keywords identifying a mod's jar in the mods folder (lowercase)
+const MOD_JAR_KEYWORDS: &[(&str, &[&str])] = &[
+    ("OptiFine", &["optifine"]),
+    ("Continuity", &["continuity"]),
+    ("CIT Resewn", &["citresewn", "cit-resewn"]),
+    (
+        "Entity Texture Features",
+        &[
+            "entity_texture_features",
+            "entitytexturefeatures",
+            "entity-texture-features",
+        ],
+    ),
+    (
+        "Entity Model Features",
+        &[
+            "entity_model_features",
+            "entitymodelfeatures",
+            "entity-model-features",
+        ],
+    ),
+    (
+        "FabricSkyBoxes Interop",
+        &["fabricskyboxes-interop", "fsb-interop"],
+    ),
+];
+
+/// How serious a finding is
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum IssueSeverity {
+    Error,
+    Warning,
+    Info,
+}
+
+/// What a finding is about
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum StackIssueKind {
+    /// Enabled in options.txt but not in the resourcepacks folder
+    MissingPack,
+    /// Made for a different pack format than the game uses
+    FormatMismatch,
+    /// Force-enabled although the game flagged it incompatible
+    ForcedIncompatible,
+    /// One pack replaces assets of another
+    Override,
+    /// Two packs ship the same custom namespace
+    NamespaceCollision,
+    /// A pack needs a mod the instance doesn't have
+    MissingDependency,
+}
+
+/// One finding about the stack
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StackIssue {
+    pub severity: IssueSeverity,
+    pub kind: StackIssueKind,
+    /// Packs involved, highest priority first
+    pub pack_ids: Vec<String>,
+    pub message: String,
+}
+
+/// One enabled pack
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StackEntry {
+    /// Entry as written in options.txt (e.g. "file/Faithful.zip", "vanilla")
+    pub entry: String,
+    /// Pack ID in the resourcepacks folder (None for built-in packs)
+    pub pack_id: Option<String>,
+    pub builtin: bool,
+    pub present: bool,
+    pub pack_format: Option<u32>,
+}
+
+/// Result of checking an instance's pack stack
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StackReport {
+    /// Folder holding options.txt and resourcepacks
+    pub game_dir: String,
+    pub options_found: bool,
+    /// Data version recorded in options.txt
+    pub data_version: Option<u32>,
+    /// Resource pack format of that game version, if known
+    pub game_pack_format: Option<u32>,
+    /// Enabled packs, highest priority first
+    pub packs: Vec<StackEntry>,
+    pub issues: Vec<StackIssue>,
+    pub requirements: Vec<FeatureRequirement>,
+    pub summary: String,
+}
+
+/// Pack settings read from options.txt
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PackOptions {
+    /// Enabled packs in options.txt order (lowest priority first)
+    pub resource_packs: Vec<String>,
+    pub incompatible_packs: Vec<String>,
+    pub data_version: Option<u32>,
+}
+
+/// Parse the pack-related lines of options.txt
+pub fn parse_pack_options(contents: &str) -> PackOptions {
+    let parse_list = |value: &str| serde_json::from_str::<Vec<String>>(value).unwrap_or_default();
+    let mut options = PackOptions::default();
+
+    for line in contents.lines() {
+        let (key, value) = match line.split_once(':') {
+            Some(pair) => pair,
+            None => continue,
+        };
+        match key.trim() {
+            "resourcePacks" => options.resource_packs = parse_list(value.trim()),
+            "incompatibleResourcePacks" => options.incompatible_packs = parse_list(value.trim()),
+            "version" => options.data_version = value.trim().parse().ok(),
+            _ => {}
+        }
+    }
+
+    options
+}
+
+/// Resource pack format for a data version
+///
+/// # Returns
+/// None for versions before 1.9 or newer than the table knows
+pub fn pack_format_for_data_version(data_version: u32) -> Option<u32> {
+    if data_version > LATEST_KNOWN_DATA_VERSION {
+        return None;
+    }
+    PACK_FORMATS_BY_DATA_VERSION
+        .iter()
+        .find(|(first, _)| data_version >= *first)
+        .map(|(_, format)| *format)
+}
+
+/// Find the game folder of an instance
+///
+/// Multi-instance launchers keep it in a ".minecraft" or "minecraft"
+/// subfolder; a plain game folder is used as is.
+pub fn resolve_game_dir(instance_dir: &Path) -> PathBuf {
+    let candidates = [
+        instance_dir.to_path_buf(),
+        instance_dir.join(".minecraft"),
+        instance_dir.join("minecraft"),
+    ];
+
+    candidates
+        .iter()
+        .find(|dir| dir.join("options.txt").is_file())
+        .or_else(|| {
+            candidates
+                .iter()
+                .find(|dir| dir.join("resourcepacks").is_dir())
+        })
+        .cloned()
+        .unwrap_or_else(|| instance_dir.to_path_buf())
+}
+
+/// Formats a pack declares support for (pack_format plus supported_formats)
+fn supported_format_range(pack: &PackMeta) -> Option<(u32, u32)> {
+    let declared = pack.pack_format?;
+    let range = read_pack_bytes(&pack.path, pack.is_zip, "pack.mcmeta")
+        .ok()
+        .and_then(|bytes| serde_json::from_slice::<Value>(&bytes).ok())
+        .and_then(|mcmeta| mcmeta.get("pack")?.get("supported_formats").cloned())
+        .and_then(|supported| match supported {
+            Value::Number(n) => n.as_u64().map(|n| (n, n)),
+            Value::Array(bounds) if bounds.len() == 2 => {
+                Some((bounds[0].as_u64()?, bounds[1].as_u64()?))
+            }
+            Value::Object(bounds) => Some((
+                bounds.get("min_inclusive")?.as_u64()?,
+                bounds.get("max_inclusive")?.as_u64()?,
+            )),
+            _ => None,
+        });
+
+    match range {
+        Some((min, max)) => Some(((min as u32).min(declared), (max as u32).max(declared))),
+        None => Some((declared, declared)),
+    }
+}
+
+/// Lowercase file names of the jars in an instance's mods folder
+fn installed_mod_jars(game_dir: &Path) -> Option<Vec<String>> {
+    let entries = fs::read_dir(game_dir.join("mods")).ok()?;
+    Some(
+        entries
+            .filter_map(|e| e.ok())
+            .map(|e| e.file_name().to_string_lossy().to_lowercase())
+            .filter(|name| name.ends_with(".jar"))
+            .collect(),
+    )
+}
+
+fn has_mod(jars: &[String], mod_name: &str) -> bool {
+    MOD_JAR_KEYWORDS
+        .iter()
+        .find(|(name, _)| *name == mod_name)
+        .map_or(false, |(_, keywords)| {
+            jars.iter()
+                .any(|jar| keywords.iter().any(|k| jar.contains(k)))
+        })
+}
+
+/// Issues for mods the stack needs but the instance doesn't have
+fn dependency_issues(requirements: &[FeatureRequirement], game_dir: &Path) -> Vec<StackIssue> {
+    let jars = match installed_mod_jars(game_dir) {
+        Some(jars) => jars,
+        None if requirements.is_empty() => return Vec::new(),
+        None => {
+            return vec![StackIssue {
+                severity: IssueSeverity::Info,
+                kind: StackIssueKind::MissingDependency,
+                pack_ids: Vec::new(),
+                message: "No mods folder found; couldn't check the mods these packs need"
+                    .to_string(),
+            }]
+        }
+    };
+    let optifine = has_mod(&jars, "OptiFine");
+
+    requirements
+        .iter()
+        .filter(|req| !(optifine && req.optifine_supported))
+        .filter_map(|req| {
+            let message = match &req.mod_name {
+                Some(mod_name) if has_mod(&jars, mod_name) => return None,
+                Some(mod_name) => format!(
+                    "{} won't show without {}{}",
+                    req.label,
+                    mod_name,
+                    if req.optifine_supported {
+                        " or OptiFine"
+                    } else {
+                        ""
+                    }
+                ),
+                // Core shaders work in vanilla but break with some mods
+                None => return None,
+            };
+            Some(StackIssue {
+                severity: IssueSeverity::Warning,
+                kind: StackIssueKind::MissingDependency,
+                pack_ids: req.pack_ids.clone(),
+                message,
+            })
+        })
+        .collect()
+}
+
+/// Issues for packs overriding each other and sharing custom namespaces
+///
+/// # Arguments
+/// * `packs` - Enabled packs present on disk, highest priority first
+fn overlap_issues(packs: &[PackMeta]) -> Result<Vec<StackIssue>> {
+    let (assets, providers) = asset_indexer::index_assets(packs)?;
+    let order: Vec<String> = packs.iter().map(|p| p.id.clone()).collect();
+    let rank = |id: &String| order.iter().position(|o| o == id).unwrap_or(usize::MAX);
+
+    // (winner, overridden) -> asset count
+    let mut overrides: BTreeMap<(usize, usize), usize> = BTreeMap::new();
+    for pack_ids in providers.values() {
+        let mut ranks: Vec<usize> = pack_ids.iter().map(rank).collect();
+        ranks.sort_unstable();
+        for loser in ranks.iter().skip(1) {
+            *overrides.entry((ranks[0], *loser)).or_default() += 1;
+        }
+    }
+
+    let mut issues: Vec<StackIssue> = overrides
+        .into_iter()
+        .map(|((winner, loser), count)| StackIssue {
+            severity: IssueSeverity::Info,
+            kind: StackIssueKind::Override,
+            pack_ids: vec![order[winner].clone(), order[loser].clone()],
+            message: format!(
+                "{} overrides {} asset(s) from {}",
+                order[winner], count, order[loser]
+            ),
+        })
+        .collect();
+
+    issues.extend(
+        namespace_conflicts::detect_namespace_collisions(&assets, &providers, &order)
+            .into_iter()
+            .map(|collision| StackIssue {
+                severity: IssueSeverity::Warning,
+                kind: StackIssueKind::NamespaceCollision,
+                message: format!(
+                    "Namespace \"{}\" is shipped by {} packs ({} shared asset(s)); mixing them may break it",
+                    collision.namespace,
+                    collision.pack_ids.len(),
+                    collision.shared_assets
+                ),
+                pack_ids: collision.pack_ids,
+            }),
+    );
+
+    Ok(issues)
+}
+
+/// Run every check against the packs an instance has enabled
+///
+/// # Arguments
+/// * `instance_dir` - Instance or game folder
+pub fn check_instance_stack(instance_dir: &Path) -> Result<StackReport> {
+    let game_dir = resolve_game_dir(instance_dir);
+    let options_path = game_dir.join("options.txt");
+    let options_found = options_path.is_file();
+    let options = if options_found {
+        let contents = fs::read_to_string(&options_path)
+            .with_context(|| format!("Failed to read {:?}", options_path))?;
+        parse_pack_options(&contents)
+    } else {
+        PackOptions::default()
+    };
+    let game_pack_format = options.data_version.and_then(pack_format_for_data_version);
+
+    let resourcepacks_dir = game_dir.join("resourcepacks");
+    let available = if resourcepacks_dir.is_dir() {
+        pack_scanner::scan_packs(&resourcepacks_dir.to_string_lossy())?
+    } else {
+        Vec::new()
+    };
+    let by_id: HashMap<&str, &PackMeta> = available.iter().map(|p| (p.id.as_str(), p)).collect();
+
+    let mut issues = Vec::new();
+    let mut entries = Vec::new();
+    let mut stack: Vec<PackMeta> = Vec::new();
+
+    // options.txt lists packs bottom-up; report them top-down
+    for entry in options.resource_packs.iter().rev() {
+        let pack_id = entry.strip_prefix(FILE_PACK_PREFIX);
+        let pack = pack_id.and_then(|id| by_id.get(id).copied());
+
+        match (pack_id, pack) {
+            (Some(id), None) => issues.push(StackIssue {
+                severity: IssueSeverity::Error,
+                kind: StackIssueKind::MissingPack,
+                pack_ids: vec![id.to_string()],
+                message: format!(
+                    "{} is enabled but missing from the resourcepacks folder",
+                    id
+                ),
+            }),
+            (Some(_), Some(pack)) => {
+                if let (Some(game_format), Some((min, max))) =
+                    (game_pack_format, supported_format_range(pack))
+                {
+                    if game_format < min || game_format > max {
+                        let made_for = if min == max {
+                            format!("format {}", min)
+                        } else {
+                            format!("formats {}-{}", min, max)
+                        };
+                        issues.push(StackIssue {
+                            severity: IssueSeverity::Warning,
+                            kind: StackIssueKind::FormatMismatch,
+                            pack_ids: vec![pack.id.clone()],
+                            message: format!(
+                                "{} is made for pack {}, the game uses {}",
+                                pack.id, made_for, game_format
+                            ),
+                        });
+                    }
+                }
+                if options.incompatible_packs.contains(entry) {
+                    issues.push(StackIssue {
+                        severity: IssueSeverity::Warning,
+                        kind: StackIssueKind::ForcedIncompatible,
+                        pack_ids: vec![pack.id.clone()],
+                        message: format!(
+                            "{} was enabled although the game marked it incompatible",
+                            pack.id
+                        ),
+                    });
+                }
+                stack.push(pack.clone());
+            }
+            _ => {}
+        }
+
+        entries.push(StackEntry {
+            entry: entry.clone(),
+            pack_id: pack_id.map(str::to_string),
+            builtin: pack_id.is_none(),
+            present: pack_id.is_none() || pack.is_some(),
+            pack_format: pack.and_then(|p| p.pack_format),
+        });
+    }
+
+    issues.extend(overlap_issues(&stack)?);
+
+    let stack_refs: Vec<&PackMeta> = stack.iter().collect();
+    let requirements = feature_requirements::collect_requirements(&stack_refs).requirements;
+    issues.extend(dependency_issues(&requirements, &game_dir));
+
+    issues.sort_by_key(|issue| issue.severity);
+
+    let count = |severity| issues.iter().filter(|i| i.severity == severity).count();
+    let summary = if !options_found {
+        "No options.txt found; the instance hasn't been launched yet".to_string()
+    } else {
+        format!(
+            "{} pack(s) enabled: {} error(s), {} warning(s)",
+            entries.len(),
+            count(IssueSeverity::Error),
+            count(IssueSeverity::Warning)
+        )
+    };
+
+    Ok(StackReport {
+        game_dir: game_dir.to_string_lossy().to_string(),
+        options_found,
+        data_version: options.data_version,
+        game_pack_format,
+        packs: entries,
+        issues,
+        requirements,
+        summary,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_pack_options() {
+        let options = parse_pack_options(
+            "version:3953\n\
+             lang:en_us\n\
+             resourcePacks:[\"vanilla\",\"file/Base.zip\",\"file/Top\"]\n\
+             incompatibleResourcePacks:[\"file/Top\"]\n",
+        );
+
+        assert_eq!(options.data_version, Some(3953));
+        assert_eq!(
+            options.resource_packs,
+            vec!["vanilla", "file/Base.zip", "file/Top"]
+        );
+        assert_eq!(options.incompatible_packs, vec!["file/Top"]);
+    }
+
+    #[test]
+    fn test_pack_format_for_data_version() {
+        assert_eq!(pack_format_for_data_version(3953), Some(34));
+        assert_eq!(pack_format_for_data_version(3955), Some(34));
+        assert_eq!(pack_format_for_data_version(3465), Some(15));
+        assert_eq!(pack_format_for_data_version(100), None);
+        assert_eq!(pack_format_for_data_version(99_999), None);
+    }
+
+    #[test]
+    fn test_check_instance_stack() {
+        let temp_dir = std::env::temp_dir().join("test_stack_check");
+        let game_dir = temp_dir.join(".minecraft");
+        let packs_dir = game_dir.join("resourcepacks");
+        let stone = "assets/minecraft/textures/block/stone.png";
+        for (pack, format, extra) in [
+            ("Base", 34, "assets/minecraft/optifine/ctm/glass/0.png"),
+            ("Top", 15, "assets/minecraft/textures/block/dirt.png"),
+        ] {
+            let dir = packs_dir.join(pack);
+            for file in [stone, extra] {
+                let path = dir.join(file);
+                fs::create_dir_all(path.parent().unwrap())
+                    .expect("Failed to create test directory");
+                fs::write(path, pack).expect("Failed to write file");
+            }
+            fs::write(
+                dir.join("pack.mcmeta"),
+                format!(
+                    r#"{{"pack": {{"pack_format": {}, "description": ""}}}}"#,
+                    format
+                ),
+            )
+            .expect("Failed to write file");
+        }
+        fs::create_dir_all(game_dir.join("mods")).expect("Failed to create test directory");
+        fs::write(
+            game_dir.join("options.txt"),
+            "version:3953\nresourcePacks:[\"vanilla\",\"file/Base\",\"file/Top\",\"file/Gone.zip\"]\n",
+        )
+        .expect("Failed to write file");
+
+        let report = check_instance_stack(&temp_dir);
+
+        // Clean up
+        fs::remove_dir_all(&temp_dir).ok();
+
+        let report = report.expect("stack check should succeed");
+        assert!(report.options_found);
+        assert_eq!(report.game_pack_format, Some(34));
+        let order: Vec<&str> = report.packs.iter().map(|p| p.entry.as_str()).collect();
+        assert_eq!(
+            order,
+            vec!["file/Gone.zip", "file/Top", "file/Base", "vanilla"]
+        );
+        assert!(!report.packs[0].present);
+        assert!(report.packs[3].builtin);
+
+        let kinds: Vec<StackIssueKind> = report.issues.iter().map(|i| i.kind).collect();
+        assert_eq!(kinds[0], StackIssueKind::MissingPack);
+        let mismatch = report
+            .issues
+            .iter()
+            .find(|i| i.kind == StackIssueKind::FormatMismatch)
+            .expect("Top should be flagged");
+        assert_eq!(mismatch.pack_ids, vec!["Top"]);
+        let overrides = report
+            .issues
+            .iter()
+            .find(|i| i.kind == StackIssueKind::Override)
+            .expect("Top overrides Base");
+        assert_eq!(overrides.pack_ids, vec!["Top", "Base"]);
+        assert!(report.issues.iter().any(
+            |i| i.kind == StackIssueKind::MissingDependency && i.message.contains("Continuity")
+        ));
+    }
+}
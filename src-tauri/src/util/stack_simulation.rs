@@ -0,0 +1,187 @@
+/// Side-by-side "what if" pack stacks
+///
+/// A project can hold alternative pack orders and override sets (e.g.
+/// "Faithful first" vs "Bare Bones first"). Simulating two of them picks
+/// the winner of every asset under each, the way a build would, and lists
+/// the assets whose winner differs. Nothing is built or saved, so users can
+/// compare strategies without changing their working order.
+use crate::model::OverrideSelection;
+use crate::util::weaver_nest;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use tracing::info;
+
+/// A named pack order and override set
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StackConfig {
+    pub name: String,
+    pub pack_order: Vec<String>,
+    #[serde(default)]
+    pub overrides: HashMap<String, OverrideSelection>,
+}
+
+/// An asset won by different packs under the two stacks
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WinnerChange {
+    pub asset_id: String,
+    /// Winning pack under stack A; None if no pack provides it there
+    pub a: Option<String>,
+    /// Winning pack under stack B
+    pub b: Option<String>,
+}
+
+/// Winners of two stacks and how they differ
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StackSimulation {
+    pub a_name: String,
+    pub b_name: String,
+    /// Asset ID -> winning pack under stack A
+    pub a_winners: BTreeMap<String, String>,
+    /// Asset ID -> winning pack under stack B
+    pub b_winners: BTreeMap<String, String>,
+    /// Assets whose winner differs, sorted by asset ID
+    pub changed: Vec<WinnerChange>,
+    /// Assets with the same winner under both stacks
+    pub unchanged: usize,
+}
+
+/// Winning pack of every asset under a pack order and overrides
+///
+/// # Arguments
+/// * `providers` - Asset ID -> pack IDs providing it
+pub fn compute_winners(
+    pack_order: &[String],
+    overrides: &HashMap<String, OverrideSelection>,
+    providers: &HashMap<String, Vec<String>>,
+) -> BTreeMap<String, String> {
+    providers
+        .keys()
+        .chain(overrides.keys())
+        .filter_map(|asset_id| {
+            weaver_nest::winning_pack(asset_id, providers, pack_order, overrides)
+                .map(|pack_id| (asset_id.clone(), pack_id.to_string()))
+        })
+        .collect()
+}
+
+/// Compute the winners of two stacks and diff them
+///
+/// # Arguments
+/// * `a` - First stack
+/// * `b` - Second stack
+/// * `providers` - Asset ID -> pack IDs providing it
+pub fn simulate_stacks(
+    a: &StackConfig,
+    b: &StackConfig,
+    providers: &HashMap<String, Vec<String>>,
+) -> StackSimulation {
+    let a_winners = compute_winners(&a.pack_order, &a.overrides, providers);
+    let b_winners = compute_winners(&b.pack_order, &b.overrides, providers);
+
+    let mut changed = Vec::new();
+    let mut unchanged = 0;
+    let asset_ids: BTreeSet<&String> = a_winners.keys().chain(b_winners.keys()).collect();
+    for asset_id in asset_ids {
+        let a_winner = a_winners.get(asset_id);
+        let b_winner = b_winners.get(asset_id);
+        if a_winner == b_winner {
+            unchanged += 1;
+        } else {
+            changed.push(WinnerChange {
+                asset_id: asset_id.clone(),
+                a: a_winner.cloned(),
+                b: b_winner.cloned(),
+            });
+        }
+    }
+
+    info!(
+        "{} vs {}: {} asset(s) change winner, {} don't",
+        a.name,
+        b.name,
+        changed.len(),
+        unchanged
+    );
+
+    StackSimulation {
+        a_name: a.name.clone(),
+        b_name: b.name.clone(),
+        a_winners,
+        b_winners,
+        changed,
+        unchanged,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn stack(name: &str, order: &[&str], overrides: &[(&str, &str)]) -> StackConfig {
+        StackConfig {
+            name: name.to_string(),
+            pack_order: order.iter().map(|id| id.to_string()).collect(),
+            overrides: overrides
+                .iter()
+                .map(|(asset_id, pack_id)| {
+                    (
+                        asset_id.to_string(),
+                        OverrideSelection {
+                            pack_id: pack_id.to_string(),
+                            variant_path: None,
+                        },
+                    )
+                })
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn test_simulate_stacks() {
+        let providers: HashMap<String, Vec<String>> = [
+            ("minecraft:block/stone", vec!["faithful", "barebones"]),
+            ("minecraft:block/dirt", vec!["faithful", "barebones"]),
+            ("minecraft:block/sand", vec!["faithful"]),
+            ("minecraft:block/glass", vec!["faithful", "barebones"]),
+        ]
+        .into_iter()
+        .map(|(id, packs)| {
+            (
+                id.to_string(),
+                packs.into_iter().map(|p| p.to_string()).collect(),
+            )
+        })
+        .collect();
+        let a = stack("Faithful first", &["faithful", "barebones"], &[]);
+        let b = stack(
+            "Bare Bones first",
+            &["barebones", "faithful"],
+            &[("minecraft:block/glass", "faithful")],
+        );
+
+        let simulation = simulate_stacks(&a, &b, &providers);
+
+        assert_eq!(simulation.a_winners.len(), 4);
+        assert_eq!(simulation.b_winners["minecraft:block/stone"], "barebones");
+        // Only provider, and overridden back to the same pack
+        assert_eq!(simulation.unchanged, 2);
+        assert_eq!(
+            simulation.changed,
+            vec![
+                WinnerChange {
+                    asset_id: "minecraft:block/dirt".to_string(),
+                    a: Some("faithful".to_string()),
+                    b: Some("barebones".to_string()),
+                },
+                WinnerChange {
+                    asset_id: "minecraft:block/stone".to_string(),
+                    a: Some("faithful".to_string()),
+                    b: Some("barebones".to_string()),
+                },
+            ]
+        );
+    }
+}
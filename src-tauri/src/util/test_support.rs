@@ -0,0 +1,21 @@
+/// Fixtures shared by the util modules' tests
+use crate::model::PackMeta;
+use std::fs;
+use std::path::Path;
+
+/// Create a folder pack `root/id` holding the given (path, contents) files
+pub(crate) fn folder_pack(root: &Path, id: &str, files: &[(&str, &str)]) -> PackMeta {
+    let dir = root.join(id);
+    fs::create_dir_all(&dir).unwrap();
+    for (path, contents) in files {
+        let file = dir.join(path);
+        fs::create_dir_all(file.parent().unwrap()).unwrap();
+        fs::write(file, contents).unwrap();
+    }
+    PackMeta {
+        id: id.to_string(),
+        name: id.to_string(),
+        path: dir.to_string_lossy().to_string(),
+        ..Default::default()
+    }
+}
@@ -0,0 +1,281 @@
+/// Splash text, credits and other text assets
+///
+/// Packs can replace the files in assets/<namespace>/texts/ (splashes.txt,
+/// end.txt, credits.json, postcredits.txt). The indexer files them as
+/// "<namespace>:texts/<name>" assets, so by default the highest-priority
+/// pack's copy replaces everyone else's like any texture. For line-based
+/// files that's rarely what people want from several splash packs, so a
+/// build can instead merge them: every enabled pack's lines, highest
+/// priority first, each line once.
+use crate::model::{AssetRecord, OverrideSelection, PackMeta};
+use crate::util::animation::read_pack_bytes;
+use crate::util::build_journal::{self, JournalWriter};
+use crate::util::{file_retry, weaver_nest, zip};
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::Path;
+use tracing::info;
+
+/// Folder of text assets inside a namespace
+pub const TEXTS_DIR: &str = "texts/";
+
+/// Extension of text files that can be merged line by line
+const LINE_FILE_EXTENSION: &str = ".txt";
+
+/// A text asset more than one enabled pack replaces
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TextConflict {
+    /// Asset ID (e.g., "minecraft:texts/splashes")
+    pub asset_id: String,
+    /// Pack whose file the build takes
+    pub winner: String,
+    /// Other enabled packs whose file is dropped, highest priority first
+    pub replaced: Vec<String>,
+}
+
+/// Whether an asset ID refers to a file in texts/
+pub fn is_text_asset(asset_id: &str) -> bool {
+    asset_id
+        .split_once(':')
+        .map_or(false, |(_, path)| path.starts_with(TEXTS_DIR))
+}
+
+/// Whether a text asset's file is line-based and can be merged
+pub fn is_line_mergeable(asset: &AssetRecord) -> bool {
+    is_text_asset(&asset.id)
+        && asset
+            .files
+            .iter()
+            .all(|file| file.to_lowercase().ends_with(LINE_FILE_EXTENSION))
+}
+
+/// Concatenate the unique lines of several files
+///
+/// Files come highest priority first; a line keeps the position of its
+/// first appearance. Blank lines are dropped and CRLF endings normalized.
+pub fn merge_lines(contents: &[Vec<u8>]) -> Vec<u8> {
+    let mut seen = HashSet::new();
+    let mut merged = String::new();
+    for content in contents {
+        let text = String::from_utf8_lossy(content);
+        for line in text.trim_start_matches('\u{feff}').lines() {
+            let line = line.trim_end_matches('\r');
+            if line.trim().is_empty() || !seen.insert(line.to_string()) {
+                continue;
+            }
+            merged.push_str(line);
+            merged.push('\n');
+        }
+    }
+    merged.into_bytes()
+}
+
+/// Text assets where one pack's file replaces other enabled packs'
+///
+/// # Arguments
+/// * `providers` - Asset ID -> pack IDs providing it
+/// * `pack_order` - Enabled pack IDs in priority order (first = highest)
+/// * `overrides` - Asset ID -> chosen pack
+/// * `merged` - Asset IDs the build merges instead, left out
+///
+/// # Returns
+/// Conflicts sorted by asset ID
+pub fn text_conflicts(
+    providers: &HashMap<String, Vec<String>>,
+    pack_order: &[String],
+    overrides: &HashMap<String, OverrideSelection>,
+    merged: &[String],
+) -> Vec<TextConflict> {
+    let mut conflicts: Vec<TextConflict> = providers
+        .iter()
+        .filter(|(asset_id, _)| is_text_asset(asset_id) && !merged.contains(asset_id))
+        .filter_map(|(asset_id, pack_ids)| {
+            let winner = weaver_nest::winning_pack(asset_id, providers, pack_order, overrides)?;
+            let replaced: Vec<String> = pack_order
+                .iter()
+                .filter(|id| *id != winner && pack_ids.contains(id))
+                .cloned()
+                .collect();
+            if replaced.is_empty() {
+                return None;
+            }
+            Some(TextConflict {
+                asset_id: asset_id.clone(),
+                winner: winner.to_string(),
+                replaced,
+            })
+        })
+        .collect();
+    conflicts.sort_by(|a, b| a.asset_id.cmp(&b.asset_id));
+    conflicts
+}
+
+/// Write merged copies of line-based text assets into a build's output
+///
+/// Runs after the winners are copied and replaces the winner's file.
+/// Assets that aren't line-based or have no enabled provider are skipped.
+///
+/// # Arguments
+/// * `asset_ids` - Text assets to merge (e.g., "minecraft:texts/splashes")
+/// * `journal` - Record written files in this build journal, if any
+///
+/// # Returns
+/// Number of files written
+pub fn write_merged_text_assets(
+    packs: &[PackMeta],
+    assets: &[AssetRecord],
+    providers: &HashMap<String, Vec<String>>,
+    pack_order: &[String],
+    asset_ids: &[String],
+    output_dir: &Path,
+    journal: Option<&JournalWriter>,
+) -> Result<usize> {
+    let mut written = 0;
+    for asset in assets.iter().filter(|a| asset_ids.contains(&a.id)) {
+        if !is_line_mergeable(asset) {
+            info!(
+                "{} isn't a line-based text file, keeping the winner's",
+                asset.id
+            );
+            continue;
+        }
+        let pack_ids = match providers.get(&asset.id) {
+            Some(pack_ids) => pack_ids,
+            None => continue,
+        };
+
+        for file in &asset.files {
+            let contents: Vec<Vec<u8>> = pack_order
+                .iter()
+                .filter(|id| pack_ids.contains(id))
+                .filter_map(|id| packs.iter().find(|p| p.id == *id))
+                .filter_map(|pack| read_pack_bytes(&pack.path, pack.is_zip, file).ok())
+                .collect();
+            if contents.is_empty() {
+                continue;
+            }
+
+            let merged = merge_lines(&contents);
+            let output_file = zip::safe_join(output_dir, file)?;
+            if let Some(parent) = output_file.parent() {
+                fs::create_dir_all(parent)?;
+            }
+            match journal {
+                Some(journal) => {
+                    build_journal::write_staged_file(&output_file, &merged)?;
+                    journal.record_file(file);
+                }
+                None => file_retry::write_with_retry(&output_file, &merged)?,
+            }
+            info!("Merged {} from {} pack(s)", file, contents.len());
+            written += 1;
+        }
+    }
+    Ok(written)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::util::test_support::folder_pack;
+
+    fn text_pack(root: &Path, id: &str, splashes: &str) -> PackMeta {
+        folder_pack(
+            root,
+            id,
+            &[("assets/minecraft/texts/splashes.txt", splashes)],
+        )
+    }
+
+    #[test]
+    fn test_merge_lines() {
+        let merged = merge_lines(&[
+            b"Also try Terraria!\r\nHello\n\n".to_vec(),
+            b"Hello\nNew splash\n".to_vec(),
+        ]);
+        assert_eq!(
+            String::from_utf8(merged).unwrap(),
+            "Also try Terraria!\nHello\nNew splash\n"
+        );
+    }
+
+    #[test]
+    fn test_text_conflicts() {
+        let providers: HashMap<String, Vec<String>> = HashMap::from([
+            (
+                "minecraft:texts/splashes".to_string(),
+                vec!["b".to_string(), "a".to_string(), "off".to_string()],
+            ),
+            ("minecraft:texts/end".to_string(), vec!["a".to_string()]),
+            (
+                "minecraft:block/stone".to_string(),
+                vec!["a".to_string(), "b".to_string()],
+            ),
+        ]);
+        let order = vec!["a".to_string(), "b".to_string()];
+
+        let conflicts = text_conflicts(&providers, &order, &HashMap::new(), &[]);
+        let merged = text_conflicts(
+            &providers,
+            &order,
+            &HashMap::new(),
+            &["minecraft:texts/splashes".to_string()],
+        );
+
+        assert_eq!(
+            conflicts,
+            vec![TextConflict {
+                asset_id: "minecraft:texts/splashes".to_string(),
+                winner: "a".to_string(),
+                replaced: vec!["b".to_string()],
+            }]
+        );
+        assert!(merged.is_empty());
+        assert!(is_text_asset("minecraft:texts/splashes"));
+        assert!(!is_text_asset("minecraft:block/stone"));
+    }
+
+    #[test]
+    fn test_write_merged_text_assets() {
+        let temp_dir = std::env::temp_dir().join("test_text_assets_merge");
+        fs::remove_dir_all(&temp_dir).ok();
+        let packs = vec![
+            text_pack(&temp_dir, "first", "Made by first!\nShared\n"),
+            text_pack(&temp_dir, "second", "Shared\nMade by second!\n"),
+        ];
+        let asset_id = "minecraft:texts/splashes".to_string();
+        let assets = vec![AssetRecord {
+            id: asset_id.clone(),
+            labels: Vec::new(),
+            files: vec!["assets/minecraft/texts/splashes.txt".to_string()],
+            animation: None,
+        }];
+        let providers = HashMap::from([(
+            asset_id.clone(),
+            vec!["first".to_string(), "second".to_string()],
+        )]);
+        let order = vec!["second".to_string(), "first".to_string()];
+        let output = temp_dir.join("output");
+
+        let written = write_merged_text_assets(
+            &packs,
+            &assets,
+            &providers,
+            &order,
+            &[asset_id],
+            &output,
+            None,
+        )
+        .unwrap();
+        let merged = fs::read_to_string(output.join("assets/minecraft/texts/splashes.txt"));
+
+        // Clean up
+        fs::remove_dir_all(&temp_dir).ok();
+
+        assert_eq!(written, 1);
+        assert_eq!(merged.unwrap(), "Shared\nMade by second!\nMade by first!\n");
+    }
+}
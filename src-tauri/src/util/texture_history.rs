@@ -0,0 +1,250 @@
+/// Texture history across pack versions
+///
+/// Groups packs into families (the same pack released for different
+/// Minecraft versions, e.g. "Faithful 32x 1.20" and "Faithful 32x 1.21.4")
+/// and collects one asset's image from every member in chronological order.
+/// Each entry carries the pack ID so an older variant can be selected with a
+/// regular file-level override.
+use crate::model::PackMeta;
+use crate::util::animation::{read_pack_bytes, texture_path_for_asset};
+use anyhow::{anyhow, Result};
+use serde::{Deserialize, Serialize};
+use std::cmp::Ordering;
+use tracing::info;
+use xxhash_rust::xxh3::xxh3_64;
+
+/// One version of a texture within a pack family
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TextureHistoryEntry {
+    pub pack_id: String,
+    pub pack_name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub pack_format: Option<u32>,
+    /// Version label parsed from the pack name (e.g. "1.20.4")
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub version: Option<String>,
+    /// Base64-encoded PNG, or None if this version doesn't ship the texture
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub image_data: Option<String>,
+    /// True if the image differs from the previous version that had one
+    pub changed: bool,
+}
+
+/// Split a pack name into words on common separators
+fn name_tokens(name: &str) -> impl Iterator<Item = &str> {
+    name.trim_end_matches(".zip")
+        .split(|c: char| c.is_whitespace() || "-_()[]{}+,".contains(c))
+        .filter(|token| !token.is_empty())
+}
+
+/// Parse a version-like token ("1.20.4", "v2", "mc1.21", "r3") into its numbers
+fn parse_version_token(token: &str) -> Option<Vec<u32>> {
+    let lower = token.to_lowercase();
+    let digits = ["version", "mc", "v", "r"]
+        .iter()
+        .find_map(|prefix| lower.strip_prefix(prefix))
+        .unwrap_or(&lower);
+
+    if !digits.starts_with(|c: char| c.is_ascii_digit()) {
+        return None;
+    }
+
+    digits
+        .split('.')
+        .map(|part| part.parse::<u32>().ok())
+        .collect()
+}
+
+/// Family key for a pack name: the name with version tokens removed
+///
+/// Example: "Faithful 32x - 1.20.4" -> "faithful 32x"
+pub fn pack_family_key(name: &str) -> String {
+    name_tokens(name)
+        .filter(|token| parse_version_token(token).is_none())
+        .map(|token| token.to_lowercase())
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Version label found in a pack name, if any
+pub fn pack_version_label(name: &str) -> Option<String> {
+    let versions: Vec<&str> = name_tokens(name)
+        .filter(|token| parse_version_token(token).is_some())
+        .collect();
+
+    if versions.is_empty() {
+        None
+    } else {
+        Some(versions.join(" "))
+    }
+}
+
+fn version_numbers(name: &str) -> Vec<u32> {
+    name_tokens(name)
+        .filter_map(parse_version_token)
+        .flatten()
+        .collect()
+}
+
+/// Order packs oldest first: by version in the name, then by pack format
+pub(crate) fn compare_chronologically(a: &PackMeta, b: &PackMeta) -> Ordering {
+    version_numbers(&a.name)
+        .cmp(&version_numbers(&b.name))
+        .then_with(|| a.pack_format.cmp(&b.pack_format))
+        .then_with(|| a.name.cmp(&b.name))
+}
+
+/// Get every pack in the same family as `pack_id`, oldest first
+pub fn find_pack_family<'a>(packs: &'a [PackMeta], pack_id: &str) -> Result<Vec<&'a PackMeta>> {
+    let pack = packs
+        .iter()
+        .find(|p| p.id == pack_id)
+        .ok_or_else(|| anyhow!("Pack not found: {}", pack_id))?;
+    let family = pack_family_key(&pack.name);
+
+    let mut members: Vec<&PackMeta> = packs
+        .iter()
+        .filter(|p| pack_family_key(&p.name) == family)
+        .collect();
+    members.sort_by(|a, b| compare_chronologically(a, b));
+
+    Ok(members)
+}
+
+/// Collect an asset's image from every version of a pack, oldest first
+///
+/// # Arguments
+/// * `packs` - All scanned packs
+/// * `pack_id` - Any member of the pack family
+/// * `asset_id` - Texture asset ID (e.g., "minecraft:block/stone")
+pub fn get_texture_history(
+    packs: &[PackMeta],
+    pack_id: &str,
+    asset_id: &str,
+) -> Result<Vec<TextureHistoryEntry>> {
+    use base64::{engine::general_purpose, Engine as _};
+
+    let texture_path = texture_path_for_asset(asset_id);
+    let mut previous_hash: Option<u64> = None;
+    let mut history = Vec::new();
+
+    for pack in find_pack_family(packs, pack_id)? {
+        let bytes = read_pack_bytes(&pack.path, pack.is_zip, &texture_path).ok();
+
+        let changed = match &bytes {
+            Some(bytes) => {
+                let hash = xxh3_64(bytes);
+                let changed = previous_hash.as_ref() != Some(&hash);
+                previous_hash = Some(hash);
+                changed
+            }
+            None => false,
+        };
+
+        history.push(TextureHistoryEntry {
+            pack_id: pack.id.clone(),
+            pack_name: pack.name.clone(),
+            pack_format: pack.pack_format,
+            version: pack_version_label(&pack.name),
+            image_data: bytes.map(|b| general_purpose::STANDARD.encode(b)),
+            changed,
+        });
+    }
+
+    info!(
+        "{} has {} versions in the family of {}",
+        asset_id,
+        history.len(),
+        pack_id
+    );
+
+    Ok(history)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    fn pack(name: &str, pack_format: Option<u32>, path: &str) -> PackMeta {
+        PackMeta {
+            id: name.to_string(),
+            name: name.to_string(),
+            path: path.to_string(),
+            pack_format,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_pack_family_key_strips_versions() {
+        assert_eq!(pack_family_key("Faithful 32x - 1.20.4"), "faithful 32x");
+        assert_eq!(pack_family_key("Faithful_32x_v2"), "faithful 32x");
+        assert_eq!(pack_family_key("faithful 32x (mc1.21)"), "faithful 32x");
+        assert_ne!(pack_family_key("Faithful 64x 1.21"), "faithful 32x");
+        assert_eq!(
+            pack_version_label("Faithful 32x - 1.20.4").as_deref(),
+            Some("1.20.4")
+        );
+        assert_eq!(pack_version_label("Faithful 32x"), None);
+    }
+
+    #[test]
+    fn test_find_pack_family_orders_chronologically() {
+        let packs = vec![
+            pack("Stay True 1.21", Some(34), ""),
+            pack("Stay True 1.9", None, ""),
+            pack("Stay True 1.20.1", Some(15), ""),
+            pack("Stay True 1.20.4", Some(15), ""),
+            pack("Other Pack 1.20", Some(15), ""),
+        ];
+
+        let family = find_pack_family(&packs, "Stay True 1.20.4").unwrap();
+        let names: Vec<&str> = family.iter().map(|p| p.name.as_str()).collect();
+
+        assert_eq!(
+            names,
+            vec![
+                "Stay True 1.9",
+                "Stay True 1.20.1",
+                "Stay True 1.20.4",
+                "Stay True 1.21"
+            ]
+        );
+        assert!(find_pack_family(&packs, "missing").is_err());
+    }
+
+    #[test]
+    fn test_get_texture_history_marks_changes() {
+        let temp_dir = std::env::temp_dir().join("test_texture_history");
+        let versions = [
+            ("Pack 1.19", b"old".as_ref()),
+            ("Pack 1.20", b"old"),
+            ("Pack 1.21", b"new"),
+        ];
+        let mut packs = Vec::new();
+        for (name, data) in versions {
+            let dir = temp_dir.join(name);
+            let textures = dir.join("assets/minecraft/textures/block");
+            fs::create_dir_all(&textures).expect("Failed to create test directory");
+            fs::write(textures.join("stone.png"), data).expect("Failed to write texture");
+            packs.push(pack(name, None, &dir.to_string_lossy()));
+        }
+        let empty = temp_dir.join("Pack 1.22");
+        fs::create_dir_all(&empty).expect("Failed to create test directory");
+        packs.push(pack("Pack 1.22", None, &empty.to_string_lossy()));
+
+        let history = get_texture_history(&packs, "Pack 1.21", "minecraft:block/stone");
+
+        // Clean up
+        fs::remove_dir_all(&temp_dir).ok();
+
+        let history = history.expect("history should load");
+        let changed: Vec<bool> = history.iter().map(|e| e.changed).collect();
+        assert_eq!(history.len(), 4);
+        assert_eq!(changed, vec![true, false, true, false]);
+        assert!(history[3].image_data.is_none());
+        assert_eq!(history[0].version.as_deref(), Some("1.19"));
+    }
+}
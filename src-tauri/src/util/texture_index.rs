@@ -6,12 +6,13 @@
 /// This is the "correct" way to determine which block a texture belongs to,
 /// rather than guessing from the filename.
 use crate::model::PackMeta;
-use crate::util::{block_models, blockstates};
-use anyhow::{Context, Result};
+use crate::util::{block_models, blockstates, override_closure};
+use anyhow::Result;
 use serde::{Deserialize, Serialize};
-use std::collections::{HashMap, HashSet};
+use std::collections::{BTreeSet, HashMap, HashSet};
 use std::fs;
 use std::path::{Path, PathBuf};
+use tracing::{info, warn};
 
 /// Maps texture paths to the blocks that use them
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -23,14 +24,14 @@ pub struct TextureIndex {
 impl TextureIndex {
     /// Build a texture index by scanning all blockstates and models in a pack
     pub fn build(pack: &PackMeta, vanilla_pack: &PackMeta) -> Result<Self> {
-        println!("[TextureIndex] Building index for pack: {}", pack.name);
+        info!("Building index for pack: {}", pack.name);
 
         let mut texture_to_blocks: HashMap<String, HashSet<String>> = HashMap::new();
 
         let blockstates_dir = if pack.is_zip {
             // For ZIP packs, we'd need to enumerate ZIP entries
             // For now, we'll return empty index and rely on fallback
-            println!("[TextureIndex] ZIP pack - skipping index (use fallback logic)");
+            warn!("ZIP pack - skipping index (use fallback logic)");
             return Ok(Self {
                 texture_to_blocks: HashMap::new(),
             });
@@ -50,10 +51,7 @@ impl TextureIndex {
                 continue;
             }
 
-            println!(
-                "[TextureIndex] Scanning blockstates in: {}",
-                blockstates_path.display()
-            );
+            info!("Scanning blockstates in: {}", blockstates_path.display());
 
             // Scan all blockstate files
             let entries = match fs::read_dir(&blockstates_path) {
@@ -90,8 +88,8 @@ impl TextureIndex {
             .map(|(k, v)| (k, v.into_iter().collect()))
             .collect();
 
-        println!(
-            "[TextureIndex] Built index with {} texture mappings",
+        info!(
+            "Built index with {} texture mappings",
             texture_to_blocks.len()
         );
 
@@ -112,14 +110,127 @@ impl TextureIndex {
     }
 }
 
+/// Blockstates and models of one pack that reference a texture
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TextureUsage {
+    pub pack_id: String,
+    /// Block IDs whose blockstate can show the texture (e.g. "oak_log")
+    pub blockstates: Vec<String>,
+    /// Model IDs whose resolved textures include it (e.g. "minecraft:block/oak_log")
+    pub models: Vec<String>,
+}
+
+/// Block IDs a pack ships blockstates for
+pub(crate) fn list_block_ids(pack: &PackMeta) -> Vec<String> {
+    const PREFIX: &str = "assets/minecraft/blockstates/";
+
+    if pack.is_zip {
+        return crate::util::zip::list_zip_files(&pack.path)
+            .unwrap_or_default()
+            .iter()
+            .filter_map(|f| f.strip_prefix(PREFIX)?.strip_suffix(".json"))
+            .filter(|name| !name.contains('/'))
+            .map(|name| name.to_string())
+            .collect();
+    }
+
+    match fs::read_dir(Path::new(&pack.path).join(PREFIX)) {
+        Ok(entries) => entries
+            .flatten()
+            .map(|entry| entry.path())
+            .filter(|path| path.extension().and_then(|s| s.to_str()) == Some("json"))
+            .filter_map(|path| path.file_stem()?.to_str().map(|s| s.to_string()))
+            .collect(),
+        Err(_) => Vec::new(),
+    }
+}
+
+/// Find every blockstate and model of a pack that draws a texture
+///
+/// Like `TextureIndex::build`, blockstates are read from the pack with
+/// vanilla as fallback, but every model a blockstate can use is checked,
+/// not only the default one. Vanilla blocks the pack doesn't redefine are
+/// included, since the pack's models may still retexture them.
+///
+/// # Arguments
+/// * `texture_id` - Texture asset ID (e.g., "minecraft:block/oak_log")
+/// * `pack` - Pack to search
+/// * `vanilla_pack` - Vanilla cache, for blockstates and parents the pack doesn't ship
+pub fn find_texture_usage(
+    texture_id: &str,
+    pack: &PackMeta,
+    vanilla_pack: &PackMeta,
+) -> TextureUsage {
+    let texture_id = if texture_id.contains(':') {
+        texture_id.to_string()
+    } else {
+        format!("minecraft:{}", texture_id)
+    };
+    let block_ids: BTreeSet<String> = list_block_ids(pack)
+        .into_iter()
+        .chain(list_block_ids(vanilla_pack))
+        .collect();
+
+    let mut blockstates = Vec::new();
+    let mut models: BTreeSet<String> = BTreeSet::new();
+    // Model ID -> whether it draws the texture; blocks share many models
+    let mut checked: HashMap<String, bool> = HashMap::new();
+
+    for block_id in block_ids {
+        let blockstate =
+            blockstates::read_blockstate(Path::new(&pack.path), &block_id, pack.is_zip).or_else(
+                |_| {
+                    blockstates::read_blockstate(
+                        Path::new(&vanilla_pack.path),
+                        &block_id,
+                        vanilla_pack.is_zip,
+                    )
+                },
+            );
+        let blockstate = match blockstate {
+            Ok(blockstate) => blockstate,
+            Err(_) => continue,
+        };
+
+        let mut uses_texture = false;
+        for model_id in override_closure::blockstate_model_ids(&blockstate) {
+            let draws = *checked.entry(model_id.clone()).or_insert_with(|| {
+                block_models::resolve_block_model(pack, &model_id, vanilla_pack)
+                    .map(|model| override_closure::model_textures(&model).any(|t| t == texture_id))
+                    .unwrap_or(false)
+            });
+            if draws {
+                uses_texture = true;
+                models.insert(model_id);
+            }
+        }
+        if uses_texture {
+            blockstates.push(block_id);
+        }
+    }
+
+    info!(
+        "{} in {}: {} blockstate(s), {} model(s)",
+        texture_id,
+        pack.id,
+        blockstates.len(),
+        models.len()
+    );
+
+    TextureUsage {
+        pack_id: pack.id.clone(),
+        blockstates,
+        models: models.into_iter().collect(),
+    }
+}
+
 /// Extract all texture paths used by a block
 fn extract_textures_from_block(
     block_id: &str,
     pack: &PackMeta,
     vanilla_pack: &PackMeta,
 ) -> Result<HashSet<String>> {
-    let mut textures = HashSet::new();
-
     // Read the blockstate
     let blockstate =
         match blockstates::read_blockstate(&PathBuf::from(&pack.path), block_id, pack.is_zip) {
@@ -135,36 +246,54 @@ fn extract_textures_from_block(
             }
         };
 
-    // Get the default model from blockstate
-    let model_id =
-        blockstates::get_default_model(&blockstate).context("No default model in blockstate")?;
-
-    // Load and resolve the model
-    let model = block_models::resolve_block_model(pack, &model_id, vanilla_pack)
-        .map_err(|e| anyhow::anyhow!("Failed to resolve block model: {}", e))?;
+    Ok(extract_textures_from_blockstate(
+        &blockstate,
+        pack,
+        vanilla_pack,
+    ))
+}
 
-    // Extract texture references
-    if let Some(model_textures) = model.textures {
-        for (_key, texture_path) in model_textures {
-            // Remove "minecraft:" prefix if present
-            let clean_path = texture_path
-                .strip_prefix("minecraft:")
-                .unwrap_or(&texture_path)
-                .to_string();
+/// Extract all texture paths a blockstate can draw
+///
+/// Every model referenced by a variant (weighted alternatives included) or a
+/// multipart case is resolved through its parents, so blocks whose states
+/// use different models (logs, stairs, fences) map all their textures, not
+/// only those of the default state. Models that fail to resolve are skipped.
+///
+/// # Returns
+/// Texture paths without the "minecraft:" namespace (e.g., "block/oak_log")
+fn extract_textures_from_blockstate(
+    blockstate: &blockstates::Blockstate,
+    pack: &PackMeta,
+    vanilla_pack: &PackMeta,
+) -> HashSet<String> {
+    let mut textures = HashSet::new();
 
-            // Skip texture variables (start with #)
-            if !clean_path.starts_with('#') {
-                textures.insert(clean_path);
+    for model_id in override_closure::blockstate_model_ids(blockstate) {
+        let model = match block_models::resolve_block_model(pack, &model_id, vanilla_pack) {
+            Ok(model) => model,
+            Err(e) => {
+                warn!("Skipping model {}: {}", model_id, e);
+                continue;
             }
+        };
+
+        for texture in override_closure::model_textures(&model) {
+            let clean_path = texture
+                .strip_prefix("minecraft:")
+                .map(|s| s.to_string())
+                .unwrap_or(texture);
+            textures.insert(clean_path);
         }
     }
 
-    Ok(textures)
+    textures
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::util::test_support::folder_pack;
 
     #[test]
     fn test_texture_index_creation() {
@@ -175,4 +304,124 @@ mod tests {
 
         assert_eq!(index.get_blocks_for_texture("block/dirt"), None);
     }
+
+    #[test]
+    fn test_build_maps_every_model_texture() {
+        let temp_dir = std::env::temp_dir().join("test_texture_index_build");
+        fs::remove_dir_all(&temp_dir).ok();
+        let pack = folder_pack(
+            &temp_dir,
+            "pack",
+            &[
+                (
+                    "assets/minecraft/blockstates/oak_fence.json",
+                    r#"{"multipart":[{"apply":{"model":"minecraft:block/oak_fence_post"}},{"when":{"north":"true"},"apply":{"model":"minecraft:block/oak_fence_side","uvlock":true}}]}"#,
+                ),
+                (
+                    "assets/minecraft/blockstates/stone.json",
+                    r#"{"variants":{"":[{"model":"minecraft:block/stone"},{"model":"minecraft:block/stone_mirrored"}]}}"#,
+                ),
+                (
+                    "assets/minecraft/models/block/fence_side.json",
+                    r##"{"textures":{"particle":"#texture"}}"##,
+                ),
+                (
+                    "assets/minecraft/models/block/oak_fence_post.json",
+                    r#"{"textures":{"texture":"minecraft:block/oak_planks"}}"#,
+                ),
+                (
+                    "assets/minecraft/models/block/oak_fence_side.json",
+                    r#"{"parent":"minecraft:block/fence_side","textures":{"texture":"block/oak_fence_side"}}"#,
+                ),
+                (
+                    "assets/minecraft/models/block/stone.json",
+                    r#"{"textures":{"all":"block/stone"}}"#,
+                ),
+                (
+                    "assets/minecraft/models/block/stone_mirrored.json",
+                    r#"{"textures":{"all":"block/stone_mirrored"}}"#,
+                ),
+            ],
+        );
+
+        let index = TextureIndex::build(&pack, &pack).unwrap();
+
+        // Clean up
+        fs::remove_dir_all(&temp_dir).ok();
+
+        let blocks = |texture: &str| index.get_blocks_for_texture(texture).cloned();
+        assert_eq!(
+            blocks("block/oak_planks"),
+            Some(vec!["oak_fence".to_string()])
+        );
+        assert_eq!(
+            blocks("block/oak_fence_side"),
+            Some(vec!["oak_fence".to_string()])
+        );
+        assert_eq!(
+            blocks("block/stone_mirrored"),
+            Some(vec!["stone".to_string()])
+        );
+        assert!(index.texture_to_blocks.keys().all(|t| !t.starts_with('#')));
+    }
+
+    #[test]
+    fn test_find_texture_usage() {
+        let temp_dir = std::env::temp_dir().join("test_texture_index_usage");
+        fs::remove_dir_all(&temp_dir).ok();
+        let vanilla = folder_pack(
+            &temp_dir,
+            "vanilla",
+            &[
+                (
+                    "assets/minecraft/blockstates/oak_log.json",
+                    r#"{"variants":{"axis=y":{"model":"minecraft:block/oak_log"},"axis=x":{"model":"minecraft:block/oak_log_horizontal","x":90}}}"#,
+                ),
+                (
+                    "assets/minecraft/blockstates/oak_wood.json",
+                    r#"{"variants":{"":{"model":"minecraft:block/oak_wood"}}}"#,
+                ),
+                (
+                    "assets/minecraft/models/block/oak_log.json",
+                    r#"{"textures":{"end":"block/oak_log_top","side":"block/oak_log"}}"#,
+                ),
+                (
+                    "assets/minecraft/models/block/oak_log_horizontal.json",
+                    r#"{"textures":{"end":"block/oak_log_top","side":"block/oak_log"}}"#,
+                ),
+                (
+                    "assets/minecraft/models/block/oak_wood.json",
+                    r#"{"textures":{"all":"block/oak_log"}}"#,
+                ),
+            ],
+        );
+        // Retextures oak wood so it no longer uses the log texture
+        let pack = folder_pack(
+            &temp_dir,
+            "pack",
+            &[(
+                "assets/minecraft/models/block/oak_wood.json",
+                r#"{"textures":{"all":"block/oak_bark"}}"#,
+            )],
+        );
+
+        let in_vanilla = find_texture_usage("minecraft:block/oak_log", &vanilla, &vanilla);
+        let in_pack = find_texture_usage("block/oak_log", &pack, &vanilla);
+
+        // Clean up
+        fs::remove_dir_all(&temp_dir).ok();
+
+        assert_eq!(in_vanilla.blockstates, vec!["oak_log", "oak_wood"]);
+        assert_eq!(
+            in_vanilla.models,
+            vec![
+                "minecraft:block/oak_log",
+                "minecraft:block/oak_log_horizontal",
+                "minecraft:block/oak_wood",
+            ]
+        );
+        assert_eq!(in_pack.pack_id, "pack");
+        assert_eq!(in_pack.blockstates, vec!["oak_log"]);
+        assert_eq!(in_pack.models.len(), 2);
+    }
 }
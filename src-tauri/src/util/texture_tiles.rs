@@ -0,0 +1,256 @@
+/// Tiled previews of large textures
+///
+/// Sky boxes and HD paintings can be 2048x2048 or more, which is slow to
+/// send to the detail viewer as one base64 PNG. Instead the texture is
+/// split into a pyramid of levels, each half the size of the one before,
+/// down to a level that fits in a single tile. The viewer shows the small
+/// level first and asks for tiles of sharper levels as the user zooms.
+/// The decoded pyramid of the texture viewed last is kept in memory so tile
+/// requests don't decode the PNG again.
+use crate::util::{animation, image_probe};
+use anyhow::{Context, Result};
+use image::imageops::{self, FilterType};
+use image::RgbaImage;
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+use std::time::SystemTime;
+use tracing::info;
+
+/// Width and height of a preview tile in pixels
+pub const PREVIEW_TILE_SIZE: u32 = 256;
+
+/// Pack path, asset ID and modification time a pyramid was built from
+type PyramidKey = (String, String, Option<SystemTime>);
+
+/// Levels of a texture with the key they were built for
+type CachedPyramid = (PyramidKey, Arc<Vec<RgbaImage>>);
+
+/// Pyramid of the texture viewed last
+static CACHED: Lazy<Mutex<Option<CachedPyramid>>> = Lazy::new(|| Mutex::new(None));
+
+/// One level of a texture pyramid
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PyramidLevel {
+    /// 0 is full size; each level halves the one before
+    pub level: u32,
+    pub width: u32,
+    pub height: u32,
+    /// Tiles across and down
+    pub columns: u32,
+    pub rows: u32,
+}
+
+/// Levels available for a texture
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TexturePyramid {
+    pub width: u32,
+    pub height: u32,
+    pub tile_size: u32,
+    /// Full size first; the last level fits in one tile
+    pub levels: Vec<PyramidLevel>,
+}
+
+/// A tile of one pyramid level
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TextureTile {
+    pub level: u32,
+    pub column: u32,
+    pub row: u32,
+    /// Tile size in pixels; smaller than the tile size at the right and bottom edges
+    pub width: u32,
+    pub height: u32,
+    /// Base64-encoded PNG
+    pub png_data: String,
+}
+
+/// Halve an image until it fits in one tile
+fn build_levels(image: RgbaImage) -> Vec<RgbaImage> {
+    let mut levels = vec![image];
+    loop {
+        let last = &levels[levels.len() - 1];
+        let (width, height) = last.dimensions();
+        if width <= PREVIEW_TILE_SIZE && height <= PREVIEW_TILE_SIZE {
+            break;
+        }
+        let next = imageops::resize(
+            last,
+            (width / 2).max(1),
+            (height / 2).max(1),
+            FilterType::Triangle,
+        );
+        levels.push(next);
+    }
+    levels
+}
+
+/// Levels `build_levels` makes for a texture of this size
+fn describe_levels(width: u32, height: u32) -> TexturePyramid {
+    let tiles = |size: u32| (size + PREVIEW_TILE_SIZE - 1) / PREVIEW_TILE_SIZE;
+    let mut levels = Vec::new();
+    let (mut level_width, mut level_height) = (width, height);
+    loop {
+        levels.push(PyramidLevel {
+            level: levels.len() as u32,
+            width: level_width,
+            height: level_height,
+            columns: tiles(level_width),
+            rows: tiles(level_height),
+        });
+        if level_width <= PREVIEW_TILE_SIZE && level_height <= PREVIEW_TILE_SIZE {
+            break;
+        }
+        level_width = (level_width / 2).max(1);
+        level_height = (level_height / 2).max(1);
+    }
+    TexturePyramid {
+        width,
+        height,
+        tile_size: PREVIEW_TILE_SIZE,
+        levels,
+    }
+}
+
+/// Decoded levels of a texture, reusing those of the texture viewed last
+fn load_levels(pack_path: &str, is_zip: bool, asset_id: &str) -> Result<Arc<Vec<RgbaImage>>> {
+    let texture_path = animation::texture_path_for_asset(asset_id);
+    // A ZIP changes as a whole; a folder pack's texture changes on its own
+    let modified_path = if is_zip {
+        Path::new(pack_path).to_path_buf()
+    } else {
+        Path::new(pack_path).join(&texture_path)
+    };
+    let modified = std::fs::metadata(modified_path)
+        .and_then(|m| m.modified())
+        .ok();
+    let key = (pack_path.to_string(), asset_id.to_string(), modified);
+
+    let mut cached = CACHED.lock().unwrap_or_else(|e| e.into_inner());
+    if let Some((cached_key, levels)) = cached.as_ref() {
+        if cached_key == &key && key.2.is_some() {
+            return Ok(levels.clone());
+        }
+    }
+
+    let png = animation::read_pack_bytes(pack_path, is_zip, &texture_path)?;
+    let image = image::load_from_memory(&png)
+        .context("Failed to decode texture")?
+        .to_rgba8();
+    let levels = Arc::new(build_levels(image));
+    info!("Built {} level(s) for {}", levels.len(), asset_id);
+    *cached = Some((key, levels.clone()));
+    Ok(levels)
+}
+
+/// Levels and tile grid of a texture
+///
+/// Only the texture's header is read; pixels are decoded once tiles are
+/// requested.
+///
+/// # Arguments
+/// * `pack_path` - Base path to the resource pack
+/// * `is_zip` - Whether the pack is a ZIP file
+/// * `asset_id` - Texture asset ID (e.g., "minecraft:environment/end_sky")
+pub fn texture_pyramid(pack_path: &str, is_zip: bool, asset_id: &str) -> Result<TexturePyramid> {
+    let texture_path = animation::texture_path_for_asset(asset_id);
+    let (width, height) = image_probe::pack_file_dimensions(pack_path, is_zip, &texture_path)?;
+    Ok(describe_levels(width, height))
+}
+
+/// Crop one tile of a pyramid level
+///
+/// # Returns
+/// None if the level or tile is out of range
+pub fn texture_tile(
+    pack_path: &str,
+    is_zip: bool,
+    asset_id: &str,
+    level: u32,
+    column: u32,
+    row: u32,
+) -> Result<Option<TextureTile>> {
+    use base64::{engine::general_purpose, Engine as _};
+
+    let levels = load_levels(pack_path, is_zip, asset_id)?;
+    let image = match levels.get(level as usize) {
+        Some(image) => image,
+        None => return Ok(None),
+    };
+    let x = column.saturating_mul(PREVIEW_TILE_SIZE);
+    let y = row.saturating_mul(PREVIEW_TILE_SIZE);
+    if x >= image.width() || y >= image.height() {
+        return Ok(None);
+    }
+    let width = PREVIEW_TILE_SIZE.min(image.width() - x);
+    let height = PREVIEW_TILE_SIZE.min(image.height() - y);
+
+    let tile = imageops::crop_imm(image, x, y, width, height).to_image();
+    let mut buffer = Vec::new();
+    tile.write_to(
+        &mut std::io::Cursor::new(&mut buffer),
+        image::ImageOutputFormat::Png,
+    )
+    .context("Failed to encode tile")?;
+
+    Ok(Some(TextureTile {
+        level,
+        column,
+        row,
+        width,
+        height,
+        png_data: general_purpose::STANDARD.encode(&buffer),
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    #[test]
+    fn test_build_levels() {
+        let levels = build_levels(RgbaImage::new(1000, 300));
+        let pyramid = describe_levels(1000, 300);
+
+        let sizes: Vec<(u32, u32)> = pyramid.levels.iter().map(|l| (l.width, l.height)).collect();
+        let built: Vec<(u32, u32)> = levels.iter().map(RgbaImage::dimensions).collect();
+        assert_eq!(sizes, vec![(1000, 300), (500, 150), (250, 75)]);
+        assert_eq!(sizes, built);
+        assert_eq!((pyramid.levels[0].columns, pyramid.levels[0].rows), (4, 2));
+        assert_eq!((pyramid.levels[2].columns, pyramid.levels[2].rows), (1, 1));
+        // Small textures are a single level
+        assert_eq!(build_levels(RgbaImage::new(16, 16)).len(), 1);
+    }
+
+    #[test]
+    fn test_texture_tile() {
+        let temp_dir = std::env::temp_dir().join("test_texture_tiles");
+        fs::remove_dir_all(&temp_dir).ok();
+        let textures = temp_dir.join("assets/minecraft/textures/painting");
+        fs::create_dir_all(&textures).unwrap();
+        RgbaImage::from_pixel(300, 260, image::Rgba([10, 20, 30, 255]))
+            .save(textures.join("huge.png"))
+            .unwrap();
+        let pack_path = temp_dir.to_string_lossy().to_string();
+
+        let pyramid = texture_pyramid(&pack_path, false, "minecraft:painting/huge").unwrap();
+        let corner = texture_tile(&pack_path, false, "minecraft:painting/huge", 0, 1, 1).unwrap();
+        let outside = texture_tile(&pack_path, false, "minecraft:painting/huge", 0, 2, 0).unwrap();
+        let no_level = texture_tile(&pack_path, false, "minecraft:painting/huge", 5, 0, 0).unwrap();
+
+        // Clean up
+        fs::remove_dir_all(&temp_dir).ok();
+
+        assert_eq!((pyramid.width, pyramid.height), (300, 260));
+        assert_eq!(pyramid.levels.len(), 2);
+        let corner = corner.expect("tile exists");
+        assert_eq!((corner.width, corner.height), (44, 4));
+        assert!(!corner.png_data.is_empty());
+        assert!(outside.is_none());
+        assert!(no_level.is_none());
+    }
+}
@@ -0,0 +1,352 @@
+/// Biome colormap tinting
+///
+/// Grass, leaves and vines are stored grayscale and colored in game by
+/// multiplying with a color sampled from colormap/grass.png or
+/// colormap/foliage.png. The sample position comes from the biome's
+/// temperature and downfall, so the same texture is lush in a jungle and
+/// brown in a savanna. A few biomes ignore the colormap (badlands, swamps)
+/// or adjust it (dark forest), and birch and spruce leaves have fixed colors.
+use crate::util::animation::{self, decode_first_frame, texture_path_for_asset};
+use crate::util::vanilla_textures;
+use anyhow::{anyhow, Context, Result};
+use image::RgbaImage;
+use serde::{Deserialize, Serialize};
+
+/// Biome used when none is given
+pub const DEFAULT_BIOME: &str = "minecraft:plains";
+
+/// Biome -> (temperature, downfall)
+const BIOME_CLIMATES: &[(&str, f32, f32)] = &[
+    ("plains", 0.8, 0.4),
+    ("sunflower_plains", 0.8, 0.4),
+    ("meadow", 0.5, 0.8),
+    ("cherry_grove", 0.5, 0.8),
+    ("forest", 0.7, 0.8),
+    ("flower_forest", 0.7, 0.8),
+    ("birch_forest", 0.6, 0.6),
+    ("old_growth_birch_forest", 0.6, 0.6),
+    ("dark_forest", 0.7, 0.8),
+    ("pale_garden", 0.7, 0.8),
+    ("taiga", 0.25, 0.8),
+    ("old_growth_pine_taiga", 0.3, 0.8),
+    ("old_growth_spruce_taiga", 0.25, 0.8),
+    ("snowy_taiga", -0.5, 0.4),
+    ("snowy_plains", 0.0, 0.5),
+    ("ice_spikes", 0.0, 0.5),
+    ("grove", -0.2, 0.8),
+    ("snowy_slopes", -0.3, 0.9),
+    ("frozen_peaks", -0.7, 0.9),
+    ("jagged_peaks", -0.7, 0.9),
+    ("stony_peaks", 1.0, 0.3),
+    ("windswept_hills", 0.2, 0.3),
+    ("windswept_forest", 0.2, 0.3),
+    ("windswept_gravelly_hills", 0.2, 0.3),
+    ("windswept_savanna", 2.0, 0.0),
+    ("savanna", 2.0, 0.0),
+    ("savanna_plateau", 2.0, 0.0),
+    ("desert", 2.0, 0.0),
+    ("badlands", 2.0, 0.0),
+    ("eroded_badlands", 2.0, 0.0),
+    ("wooded_badlands", 2.0, 0.0),
+    ("jungle", 0.95, 0.9),
+    ("sparse_jungle", 0.95, 0.8),
+    ("bamboo_jungle", 0.95, 0.9),
+    ("swamp", 0.8, 0.9),
+    ("mangrove_swamp", 0.8, 0.9),
+    ("mushroom_fields", 0.9, 1.0),
+    ("beach", 0.8, 0.4),
+    ("snowy_beach", 0.05, 0.3),
+    ("stony_shore", 0.2, 0.3),
+    ("river", 0.5, 0.5),
+    ("frozen_river", 0.0, 0.5),
+    ("ocean", 0.5, 0.5),
+    ("lush_caves", 0.5, 0.5),
+    ("dripstone_caves", 0.8, 0.4),
+    ("deep_dark", 0.8, 0.4),
+    ("nether_wastes", 2.0, 0.0),
+    ("the_end", 0.5, 0.5),
+];
+
+/// Fixed leaf colors that ignore the colormap
+const FIXED_LEAF_TINTS: &[(&str, u32)] = &[
+    ("block/birch_leaves", 0x80A755),
+    ("block/spruce_leaves", 0x619961),
+];
+
+/// Which colormap a texture is tinted with
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum Colormap {
+    Grass,
+    Foliage,
+}
+
+impl Colormap {
+    /// Name of the colormap file (colormap/<name>.png)
+    pub fn file_name(self) -> &'static str {
+        match self {
+            Colormap::Grass => "grass",
+            Colormap::Foliage => "foliage",
+        }
+    }
+
+    /// Colormap a texture is normally tinted with: leaves and vines use
+    /// foliage, everything else grass
+    pub fn for_texture(asset_id: &str) -> Colormap {
+        let path = asset_id.split_once(':').map_or(asset_id, |(_, path)| path);
+        if path.ends_with("_leaves") || path.ends_with("vine") {
+            Colormap::Foliage
+        } else {
+            Colormap::Grass
+        }
+    }
+}
+
+/// A biome's climate
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BiomeClimate {
+    pub biome: String,
+    pub temperature: f32,
+    pub downfall: f32,
+}
+
+/// A texture multiplied with its biome color
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TintedTexture {
+    pub asset_id: String,
+    pub biome: String,
+    pub colormap: Colormap,
+    /// The color multiplied in, e.g. "#91BD59"
+    pub tint: String,
+    /// Base64-encoded PNG
+    pub png_data: String,
+}
+
+/// Built-in biomes with their climates
+pub fn biome_climates() -> Vec<BiomeClimate> {
+    BIOME_CLIMATES
+        .iter()
+        .map(|&(biome, temperature, downfall)| BiomeClimate {
+            biome: format!("minecraft:{}", biome),
+            temperature,
+            downfall,
+        })
+        .collect()
+}
+
+/// Climate of a built-in biome ("plains" or "minecraft:plains")
+pub fn biome_climate(biome: &str) -> Option<(f32, f32)> {
+    let name = biome.strip_prefix("minecraft:").unwrap_or(biome);
+    BIOME_CLIMATES
+        .iter()
+        .find(|(id, _, _)| *id == name)
+        .map(|&(_, temperature, downfall)| (temperature, downfall))
+}
+
+fn rgb(color: u32) -> [u8; 3] {
+    [(color >> 16) as u8, (color >> 8) as u8, color as u8]
+}
+
+/// Sample a colormap the way the game does
+///
+/// Temperature and downfall are clamped to 0..1 and downfall is scaled by
+/// temperature, which keeps samples in the map's lower-left triangle.
+pub fn sample_colormap(colormap: &RgbaImage, temperature: f32, downfall: f32) -> [u8; 3] {
+    let temperature = temperature.clamp(0.0, 1.0);
+    let downfall = downfall.clamp(0.0, 1.0) * temperature;
+    let (width, height) = colormap.dimensions();
+    let x = ((1.0 - temperature) * (width - 1) as f32) as u32;
+    let y = ((1.0 - downfall) * (height - 1) as f32) as u32;
+    let pixel = colormap.get_pixel(x.min(width - 1), y.min(height - 1));
+    [pixel[0], pixel[1], pixel[2]]
+}
+
+/// Color a texture gets in a biome
+///
+/// # Arguments
+/// * `asset_id` - Texture being tinted (for leaves with fixed colors)
+/// * `colormap_kind` - Which colormap `colormap` is
+/// * `colormap` - The colormap image
+/// * `biome` - Built-in biome ID
+pub fn biome_tint(
+    asset_id: &str,
+    colormap_kind: Colormap,
+    colormap: &RgbaImage,
+    biome: &str,
+) -> Result<[u8; 3]> {
+    let name = biome.strip_prefix("minecraft:").unwrap_or(biome);
+    let path = asset_id.split_once(':').map_or(asset_id, |(_, path)| path);
+    if let Some((_, color)) = FIXED_LEAF_TINTS.iter().find(|(id, _)| *id == path) {
+        return Ok(rgb(*color));
+    }
+
+    // Biomes whose colors don't come from the colormap
+    match (name, colormap_kind) {
+        ("badlands" | "eroded_badlands" | "wooded_badlands", Colormap::Grass) => {
+            return Ok(rgb(0x90814D))
+        }
+        ("badlands" | "eroded_badlands" | "wooded_badlands", Colormap::Foliage) => {
+            return Ok(rgb(0x9E814D))
+        }
+        ("swamp", _) => return Ok(rgb(0x6A7039)),
+        ("mangrove_swamp", Colormap::Foliage) => return Ok(rgb(0x8DB127)),
+        ("mangrove_swamp", Colormap::Grass) => return Ok(rgb(0x6A7039)),
+        _ => {}
+    }
+
+    let (temperature, downfall) =
+        biome_climate(name).ok_or_else(|| anyhow!("Unknown biome: {}", biome))?;
+    let sampled = sample_colormap(colormap, temperature, downfall);
+    if name == "dark_forest" && colormap_kind == Colormap::Grass {
+        // Averaged with a dark green, as the game does
+        let dark = rgb(0x28340A);
+        return Ok([0, 1, 2].map(|i| ((sampled[i] as u16 + dark[i] as u16) / 2) as u8));
+    }
+    Ok(sampled)
+}
+
+/// Multiply a texture's color channels with a tint, keeping alpha
+pub fn apply_tint(texture: &RgbaImage, tint: [u8; 3]) -> RgbaImage {
+    let mut tinted = texture.clone();
+    for pixel in tinted.pixels_mut() {
+        for i in 0..3 {
+            pixel[i] = (pixel[i] as u16 * tint[i] as u16 / 255) as u8;
+        }
+    }
+    tinted
+}
+
+/// Load a colormap from a pack, falling back to the vanilla one
+fn load_colormap(pack_path: &str, is_zip: bool, colormap: Colormap) -> Result<RgbaImage> {
+    let relative = format!(
+        "assets/minecraft/textures/colormap/{}.png",
+        colormap.file_name()
+    );
+    let bytes = match animation::read_pack_bytes(pack_path, is_zip, &relative) {
+        Ok(bytes) => bytes,
+        Err(_) => {
+            let path = vanilla_textures::get_colormap_path(colormap.file_name())?;
+            std::fs::read(&path).with_context(|| format!("Failed to read {:?}", path))?
+        }
+    };
+    Ok(image::load_from_memory(&bytes)
+        .context("Failed to decode colormap")?
+        .to_rgba8())
+}
+
+/// Tint a pack's texture for a biome
+///
+/// The pack's own colormap is used when it has one.
+///
+/// # Arguments
+/// * `colormap` - Colormap to use; guessed from the texture if None
+pub fn tint_pack_texture(
+    pack_path: &str,
+    is_zip: bool,
+    asset_id: &str,
+    biome: &str,
+    colormap: Option<Colormap>,
+) -> Result<TintedTexture> {
+    use base64::{engine::general_purpose, Engine as _};
+
+    let colormap = colormap.unwrap_or_else(|| Colormap::for_texture(asset_id));
+    let colormap_image = load_colormap(pack_path, is_zip, colormap)?;
+    let tint = biome_tint(asset_id, colormap, &colormap_image, biome)?;
+
+    let bytes = animation::read_pack_bytes(pack_path, is_zip, &texture_path_for_asset(asset_id))?;
+    let tinted = apply_tint(&decode_first_frame(&bytes)?, tint);
+    let mut buffer = Vec::new();
+    tinted
+        .write_to(
+            &mut std::io::Cursor::new(&mut buffer),
+            image::ImageOutputFormat::Png,
+        )
+        .context("Failed to encode tinted texture")?;
+
+    Ok(TintedTexture {
+        asset_id: asset_id.to_string(),
+        biome: biome.to_string(),
+        colormap,
+        tint: format!("#{:02X}{:02X}{:02X}", tint[0], tint[1], tint[2]),
+        png_data: general_purpose::STANDARD.encode(&buffer),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use image::Rgba;
+
+    /// Colormap whose red channel is x and green channel is y
+    fn gradient() -> RgbaImage {
+        RgbaImage::from_fn(256, 256, |x, y| Rgba([x as u8, y as u8, 0, 255]))
+    }
+
+    #[test]
+    fn test_biome_tint() {
+        let map = gradient();
+
+        let plains = biome_tint(
+            "minecraft:block/grass_block_top",
+            Colormap::Grass,
+            &map,
+            "plains",
+        );
+        let desert = biome_tint(
+            "minecraft:block/grass_block_top",
+            Colormap::Grass,
+            &map,
+            "desert",
+        );
+        let birch = biome_tint(
+            "minecraft:block/birch_leaves",
+            Colormap::Foliage,
+            &map,
+            "plains",
+        );
+        let swamp = biome_tint(
+            "minecraft:block/oak_leaves",
+            Colormap::Foliage,
+            &map,
+            "swamp",
+        );
+        let unknown = biome_tint(
+            "minecraft:block/grass_block_top",
+            Colormap::Grass,
+            &map,
+            "mars",
+        );
+
+        // plains: x = (1 - 0.8) * 255, y = (1 - 0.4 * 0.8) * 255, truncated
+        // like the game does (0.8 as a float is a bit above 0.8)
+        assert_eq!(plains.unwrap(), [50, 173, 0]);
+        // Hot and dry: the bottom-left corner
+        assert_eq!(desert.unwrap(), [0, 255, 0]);
+        assert_eq!(birch.unwrap(), [0x80, 0xA7, 0x55]);
+        assert_eq!(swamp.unwrap(), [0x6A, 0x70, 0x39]);
+        assert!(unknown.is_err());
+    }
+
+    #[test]
+    fn test_apply_tint() {
+        let texture = RgbaImage::from_pixel(2, 2, Rgba([255, 128, 0, 100]));
+
+        let tinted = apply_tint(&texture, [145, 189, 89]);
+
+        assert_eq!(tinted.get_pixel(0, 0), &Rgba([145, 94, 0, 100]));
+        assert_eq!(
+            Colormap::for_texture("minecraft:block/oak_leaves"),
+            Colormap::Foliage
+        );
+        assert_eq!(
+            Colormap::for_texture("minecraft:block/vine"),
+            Colormap::Foliage
+        );
+        assert_eq!(
+            Colormap::for_texture("minecraft:block/grass_block_top"),
+            Colormap::Grass
+        );
+    }
+}
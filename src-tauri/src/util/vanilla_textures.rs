@@ -1,18 +1,220 @@
 /// Utilities for extracting and caching vanilla Minecraft textures
+///
+/// Each source JAR is extracted into its own folder, `versions/<JAR SHA1>`
+/// under the cache root, and `.active_version` names the one in use, so
+/// switching Minecraft versions never serves another version's assets.
 use anyhow::{anyhow, Context, Result};
 use rayon::prelude::*;
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use std::fs;
 
+use once_cell::sync::Lazy;
 use std::path::{Path, PathBuf};
 use std::sync::atomic::{AtomicUsize, Ordering};
-use std::sync::Arc;
+use std::sync::{Arc, Mutex};
+use tracing::{error, info, warn};
+use walkdir::WalkDir;
 use zip::ZipArchive;
 
-use crate::util::mc_paths;
+use crate::util::jobs::{self, CancelToken};
+use crate::util::{file_retry, instance_lock, mc_paths, parallelism, settings};
 
-/// Progress callback type for extraction
-pub type ProgressCallback = Arc<dyn Fn(usize, usize) + Send + Sync>;
+/// Progress callback type for extraction: (files done, total, file just extracted)
+pub type ProgressCallback = Arc<dyn Fn(usize, usize, Option<&str>) + Send + Sync>;
+
+/// Pack ID of the vanilla cache in scans, pack orders and providers
+pub const VANILLA_PACK_ID: &str = "minecraft:vanilla";
+
+/// Manifest describing what the cache was extracted from
+pub const MANIFEST_FILE: &str = ".extraction_manifest.json";
+
+/// Plain-text version marker written by older releases
+pub const LEGACY_VERSION_MARKER_FILE: &str = ".extracted_version";
+
+/// Bump when the set of extracted files or their layout changes
+pub const EXTRACTION_SCHEMA_VERSION: u32 = 1;
+
+/// Folder in the cache root holding one folder per JAR, named by its SHA1
+const VERSIONS_DIR: &str = "versions";
+
+/// File in the cache root naming the version folder in use
+const ACTIVE_VERSION_FILE: &str = ".active_version";
+
+/// Cap on the total size of cached versions unless the user set one
+pub const DEFAULT_CACHE_LIMIT_MB: u64 = 1024;
+
+/// Folder of the version in use, once looked up
+static ACTIVE_CACHE_DIR: Lazy<Mutex<Option<PathBuf>>> = Lazy::new(|| Mutex::new(None));
+
+/// Record of the JAR a vanilla cache was extracted from
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct ExtractionManifest {
+    /// Version name (e.g., "1.21.4")
+    pub version: String,
+    /// Source JAR path
+    pub jar_path: String,
+    /// SHA1 of the source JAR
+    pub jar_hash: String,
+    /// Source JAR size in bytes
+    pub jar_size: u64,
+    /// Source JAR modification time (Unix seconds)
+    pub jar_modified: u64,
+    /// Extraction schema the cache was produced with
+    pub schema_version: u32,
+    /// When the extraction finished (Unix seconds)
+    pub extracted_at: u64,
+    /// When the cache was last switched to (Unix seconds), for eviction
+    #[serde(default)]
+    pub last_used: u64,
+}
+
+impl ExtractionManifest {
+    /// Describe a JAR as the source of a fresh extraction
+    pub fn for_jar(jar_path: &Path) -> Result<Self> {
+        let (jar_size, jar_modified) = file_size_and_mtime(jar_path)?;
+        let now = unix_now();
+        Ok(Self {
+            version: version_name_from_jar(jar_path)?,
+            jar_path: jar_path.to_string_lossy().to_string(),
+            jar_hash: hash_file_sha1(jar_path)?,
+            jar_size,
+            jar_modified,
+            schema_version: EXTRACTION_SCHEMA_VERSION,
+            extracted_at: now,
+            last_used: now,
+        })
+    }
+
+    /// Check whether the cache described by this manifest is valid for a JAR
+    ///
+    /// An unchanged JAR (same path, size and mtime) is accepted without
+    /// hashing; otherwise the contents are compared by SHA1, so a copied or
+    /// renamed JAR with identical contents is still a match.
+    pub fn matches_jar(&self, jar_path: &Path) -> Result<bool> {
+        if self.schema_version != EXTRACTION_SCHEMA_VERSION {
+            return Ok(false);
+        }
+
+        let (size, modified) = file_size_and_mtime(jar_path)?;
+        if self.jar_path == jar_path.to_string_lossy()
+            && self.jar_size == size
+            && self.jar_modified == modified
+        {
+            return Ok(true);
+        }
+
+        Ok(size == self.jar_size && hash_file_sha1(jar_path)? == self.jar_hash)
+    }
+}
+
+/// Read the extraction manifest from a cache directory
+pub fn read_manifest(cache_dir: &Path) -> Option<ExtractionManifest> {
+    let contents = fs::read_to_string(cache_dir.join(MANIFEST_FILE)).ok()?;
+    serde_json::from_str(&contents).ok()
+}
+
+/// Write the extraction manifest, replacing any legacy marker
+pub fn write_manifest(cache_dir: &Path, manifest: &ExtractionManifest) -> Result<()> {
+    let manifest_file = cache_dir.join(MANIFEST_FILE);
+    info!("Writing manifest for version: {}", manifest.version);
+    let json = serde_json::to_string_pretty(manifest)?;
+    fs::write(&manifest_file, json).context("Failed to write extraction manifest")?;
+
+    let legacy_marker = cache_dir.join(LEGACY_VERSION_MARKER_FILE);
+    if legacy_marker.exists() {
+        fs::remove_file(legacy_marker).ok();
+    }
+    Ok(())
+}
+
+fn unix_now() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+/// Size and modification time (Unix seconds) of a file
+fn file_size_and_mtime(path: &Path) -> Result<(u64, u64)> {
+    let metadata = fs::metadata(path).with_context(|| format!("Failed to stat {:?}", path))?;
+    let modified = metadata
+        .modified()
+        .ok()
+        .and_then(|t| t.duration_since(std::time::UNIX_EPOCH).ok())
+        .map(|d| d.as_secs())
+        .unwrap_or(0);
+    Ok((metadata.len(), modified))
+}
+
+/// Stream a file through SHA1
+fn hash_file_sha1(path: &Path) -> Result<String> {
+    use std::io::Read;
+
+    let mut file = fs::File::open(path).with_context(|| format!("Failed to open {:?}", path))?;
+    let mut hasher = sha1_smol::Sha1::new();
+    let mut buffer = vec![0u8; 64 * 1024];
+    loop {
+        let read = file
+            .read(&mut buffer)
+            .context("Failed to read file for hashing")?;
+        if read == 0 {
+            break;
+        }
+        hasher.update(&buffer[..read]);
+    }
+    Ok(hasher.digest().to_string())
+}
+
+/// A JAR entry that belongs in the vanilla cache
+#[derive(Debug, Clone)]
+pub struct ExtractableEntry {
+    /// Index of the entry in the archive
+    pub index: usize,
+    /// Sanitized path relative to the cache directory
+    pub rel_path: PathBuf,
+    /// Uncompressed size in bytes
+    pub size: u64,
+}
+
+/// Release channel of a Minecraft version
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum VersionType {
+    Release,
+    Snapshot,
+    /// old_beta and old_alpha
+    Old,
+    Unknown,
+}
+
+impl VersionType {
+    /// Map the version JSON's "type" field
+    fn from_json(value: &str) -> VersionType {
+        match value {
+            "release" => VersionType::Release,
+            "snapshot" => VersionType::Snapshot,
+            "old_beta" | "old_alpha" => VersionType::Old,
+            _ => VersionType::Unknown,
+        }
+    }
+
+    /// Guess from the version name when there is no version JSON
+    fn from_name(version: &str) -> VersionType {
+        let is_release = version.split('.').all(|part| part.parse::<u32>().is_ok());
+        let is_snapshot = version.len() >= 5
+            && version.chars().nth(2) == Some('w')
+            && version[..2].parse::<u32>().is_ok();
+        if is_release {
+            VersionType::Release
+        } else if is_snapshot {
+            VersionType::Snapshot
+        } else {
+            VersionType::Unknown
+        }
+    }
+}
 
 /// Information about a Minecraft version
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
@@ -23,6 +225,83 @@ pub struct MinecraftVersion {
     pub jar_path: String,
     /// Last modification time (for sorting)
     pub modified_time: u64,
+    /// Channel, from the version JSON or else the name
+    pub version_type: VersionType,
+    /// Release date from the version JSON (ISO 8601)
+    pub release_time: Option<String>,
+}
+
+/// Which installed version vanilla assets come from when none was chosen
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum VersionPreference {
+    /// Newest release, ignoring snapshots
+    LatestRelease,
+    /// Newest version, snapshots included
+    LatestSnapshot,
+    /// Always the pinned version
+    Pinned,
+}
+
+impl Default for VersionPreference {
+    fn default() -> Self {
+        VersionPreference::LatestRelease
+    }
+}
+
+/// Vanilla version selection settings
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct VanillaVersionSettings {
+    pub preference: VersionPreference,
+    /// Version used with `Pinned` (e.g. "1.21.4")
+    pub pinned_version: Option<String>,
+    /// Cap on the total size of cached versions in MB;
+    /// `DEFAULT_CACHE_LIMIT_MB` if None
+    pub cache_limit_mb: Option<u64>,
+}
+
+/// Pick the version vanilla assets should come from
+///
+/// Versions are expected newest first (as listed). Among releases and
+/// snapshots, the newest is decided by the JSON release time when both have
+/// one. A pinned version that isn't installed falls back to the latest
+/// release.
+///
+/// # Returns
+/// The chosen version, or None if the list is empty
+pub fn select_version<'a>(
+    versions: &'a [MinecraftVersion],
+    settings: &VanillaVersionSettings,
+) -> Option<&'a MinecraftVersion> {
+    if settings.preference == VersionPreference::Pinned {
+        match &settings.pinned_version {
+            Some(pinned) => match versions.iter().find(|v| &v.version == pinned) {
+                Some(version) => return Some(version),
+                None => warn!(
+                    "Pinned version {} is not installed, using the latest release",
+                    pinned
+                ),
+            },
+            None => info!("No version pinned, using the latest release"),
+        }
+    }
+
+    let wanted: &[VersionType] = match settings.preference {
+        VersionPreference::LatestSnapshot => &[VersionType::Release, VersionType::Snapshot],
+        _ => &[VersionType::Release],
+    };
+    let newest = versions
+        .iter()
+        .filter(|v| wanted.contains(&v.version_type))
+        .fold(None, |best: Option<&MinecraftVersion>, v| match best {
+            Some(b) => match (&v.release_time, &b.release_time) {
+                (Some(v_time), Some(b_time)) if v_time > b_time => Some(v),
+                _ => Some(b),
+            },
+            None => Some(v),
+        });
+    newest.or_else(|| versions.first())
 }
 
 impl MinecraftVersion {
@@ -71,23 +350,347 @@ impl MinecraftVersion {
             return std::cmp::Ordering::Greater; // b (release) > a (snapshot)
         }
 
+        // Unrecognized names (custom profiles) go after every known version
+        let known = |v: &str| parse_release(v).is_some() || parse_snapshot(v).is_some();
+        match (known(a), known(b)) {
+            (true, false) => return std::cmp::Ordering::Less,
+            (false, true) => return std::cmp::Ordering::Greater,
+            _ => {}
+        }
+
         // Fallback to string comparison
         b.cmp(a) // Reversed for descending order
     }
 }
 
-/// Get the directory where vanilla textures are cached
-pub fn get_vanilla_cache_dir() -> Result<PathBuf> {
-    let cache_dir = dirs::cache_dir()
+/// Parts of a launcher version JSON (versions/<id>/<id>.json)
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct VersionJson {
+    /// "release", "snapshot", "old_beta" or "old_alpha"
+    #[serde(rename = "type")]
+    version_type: Option<String>,
+    release_time: Option<String>,
+    /// Set by mod loader profiles (Fabric, Forge, Quilt) that run another version
+    inherits_from: Option<String>,
+    /// Download entries; vanilla versions list the client JAR
+    #[serde(default)]
+    downloads: HashMap<String, serde_json::Value>,
+}
+
+/// inheritsFrom links followed before giving up on a loop
+const MAX_INHERITANCE_DEPTH: usize = 8;
+
+fn read_version_json(versions_dir: &Path, name: &str) -> Option<VersionJson> {
+    let contents =
+        fs::read_to_string(versions_dir.join(name).join(format!("{}.json", name))).ok()?;
+    serde_json::from_str(&contents).ok()
+}
+
+/// Whether a JAR contains vanilla textures
+fn jar_has_assets(jar_path: &Path) -> bool {
+    let archive = fs::File::open(jar_path)
+        .ok()
+        .and_then(|file| ZipArchive::new(file).ok());
+    match archive {
+        Some(archive) => archive
+            .file_names()
+            .any(|name| name.starts_with("assets/minecraft/textures/")),
+        None => false,
+    }
+}
+
+/// Whether a version folder holds a real client JAR
+///
+/// Loader profiles (with `inheritsFrom`) are skipped; their JAR is a stub or
+/// missing and the version they run is listed on its own. Versions whose
+/// JSON lists a client download are trusted; others (no JSON, hand-made
+/// profiles) must actually contain textures.
+fn is_client_version(versions_dir: &Path, name: &str, jar_path: &Path) -> bool {
+    match read_version_json(versions_dir, name) {
+        Some(json) if json.inherits_from.is_some() => false,
+        Some(json) if json.downloads.contains_key("client") => true,
+        _ => jar_has_assets(jar_path),
+    }
+}
+
+/// Client JAR behind a version, following `inheritsFrom` for loader profiles
+fn resolve_client_jar(versions_dir: &Path, name: &str) -> Option<PathBuf> {
+    let mut current = name.to_string();
+    for _ in 0..MAX_INHERITANCE_DEPTH {
+        let json = read_version_json(versions_dir, &current);
+        if let Some(parent) = json.as_ref().and_then(|j| j.inherits_from.clone()) {
+            current = parent;
+            continue;
+        }
+        let jar_path = versions_dir.join(&current).join(format!("{}.jar", current));
+        return if jar_path.exists() && is_client_version(versions_dir, &current, &jar_path) {
+            Some(jar_path)
+        } else {
+            None
+        };
+    }
+    None
+}
+
+/// A version held in the vanilla cache
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct CachedVanillaVersion {
+    pub version: String,
+    /// SHA1 of the JAR, which names the version's folder
+    pub jar_hash: String,
+    pub cache_dir: String,
+    pub size_bytes: u64,
+    /// When the version was last switched to (Unix seconds)
+    pub last_used: u64,
+    /// Whether vanilla assets currently come from this version
+    pub active: bool,
+}
+
+/// Outcome of clearing the vanilla cache
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct VanillaCacheClearResult {
+    /// Names of the versions removed
+    pub removed: Vec<String>,
+    pub freed_bytes: u64,
+}
+
+/// Get the folder holding every cached version
+pub fn get_vanilla_cache_root() -> Result<PathBuf> {
+    let cache_root = dirs::cache_dir()
         .ok_or_else(|| anyhow!("Could not find cache directory"))?
         .join("weaverbird")
         .join("vanilla_textures");
 
-    fs::create_dir_all(&cache_dir).context("Failed to create vanilla textures cache directory")?;
+    fs::create_dir_all(&cache_root).context("Failed to create vanilla textures cache directory")?;
+
+    Ok(cache_root)
+}
+
+/// Get the directory where vanilla textures are cached
+///
+/// This is the folder of the version in use. Until a version has been
+/// extracted it's the cache root, which holds no assets.
+pub fn get_vanilla_cache_dir() -> Result<PathBuf> {
+    let mut active = ACTIVE_CACHE_DIR.lock().unwrap_or_else(|e| e.into_inner());
+    if let Some(cache_dir) = active.as_ref() {
+        return Ok(cache_dir.clone());
+    }
 
+    let cache_root = get_vanilla_cache_root()?;
+    migrate_flat_cache(&cache_root);
+    let cache_dir = read_active_version(&cache_root).unwrap_or(cache_root);
+    *active = Some(cache_dir.clone());
     Ok(cache_dir)
 }
 
+/// Folder of the version extracted from a JAR with this SHA1
+pub fn version_cache_dir(cache_root: &Path, jar_hash: &str) -> PathBuf {
+    cache_root.join(VERSIONS_DIR).join(jar_hash)
+}
+
+/// Folder of the version in use, if it still exists
+pub fn read_active_version(cache_root: &Path) -> Option<PathBuf> {
+    let name = fs::read_to_string(cache_root.join(ACTIVE_VERSION_FILE)).ok()?;
+    let cache_dir = version_cache_dir(cache_root, name.trim());
+    read_manifest(&cache_dir).map(|_| cache_dir)
+}
+
+/// Make a version folder the one in use and mark it as just used
+pub fn set_active_version(cache_root: &Path, cache_dir: &Path) -> Result<()> {
+    let mut manifest = read_manifest(cache_dir)
+        .ok_or_else(|| anyhow!("No extracted version in {:?}", cache_dir))?;
+    manifest.last_used = unix_now();
+    write_manifest(cache_dir, &manifest)?;
+    fs::write(cache_root.join(ACTIVE_VERSION_FILE), &manifest.jar_hash)
+        .context("Failed to record the active vanilla version")?;
+    Ok(())
+}
+
+/// Switch the app's vanilla assets to a version folder
+fn activate(cache_root: &Path, cache_dir: &Path) -> Result<()> {
+    set_active_version(cache_root, cache_dir)?;
+    *ACTIVE_CACHE_DIR.lock().unwrap_or_else(|e| e.into_inner()) = Some(cache_dir.to_path_buf());
+    Ok(())
+}
+
+/// Move a cache from before per-version folders into its version folder
+///
+/// A cache with a manifest keeps its files; one with only the plain-text
+/// version marker can't be tied to a JAR and is removed, so the version is
+/// extracted again when next needed.
+fn migrate_flat_cache(cache_root: &Path) {
+    let flat_assets = cache_root.join("assets");
+    let legacy_marker = cache_root.join(LEGACY_VERSION_MARKER_FILE);
+    if !flat_assets.exists() && !legacy_marker.exists() {
+        return;
+    }
+
+    let migrated = read_manifest(cache_root).map(|manifest| {
+        let cache_dir = version_cache_dir(cache_root, &manifest.jar_hash);
+        fs::create_dir_all(&cache_dir)?;
+        fs::rename(&flat_assets, cache_dir.join("assets"))?;
+        fs::rename(
+            cache_root.join(MANIFEST_FILE),
+            cache_dir.join(MANIFEST_FILE),
+        )?;
+        fs::write(cache_root.join(ACTIVE_VERSION_FILE), &manifest.jar_hash)?;
+        info!(
+            "Moved cached version {} into {:?}",
+            manifest.version, cache_dir
+        );
+        Ok::<_, std::io::Error>(())
+    });
+    match migrated {
+        Some(Ok(())) => {}
+        Some(Err(e)) => warn!("Failed to migrate the vanilla cache: {}", e),
+        None => {
+            info!("Removing vanilla cache without a manifest");
+            file_retry::remove_dir_all_with_retry(&flat_assets).ok();
+            fs::remove_file(&legacy_marker).ok();
+        }
+    }
+}
+
+/// Total size of the files under a folder
+fn dir_size(dir: &Path) -> u64 {
+    WalkDir::new(dir)
+        .into_iter()
+        .filter_map(|entry| entry.ok())
+        .filter_map(|entry| entry.metadata().ok())
+        .filter(|metadata| metadata.is_file())
+        .map(|metadata| metadata.len())
+        .sum()
+}
+
+/// Versions in the cache, least recently used first
+///
+/// Folders without a manifest (extractions still running or interrupted)
+/// aren't listed.
+pub fn list_cached_versions(cache_root: &Path) -> Vec<CachedVanillaVersion> {
+    let active = read_active_version(cache_root);
+    let entries = match fs::read_dir(cache_root.join(VERSIONS_DIR)) {
+        Ok(entries) => entries,
+        Err(_) => return Vec::new(),
+    };
+
+    let mut versions: Vec<CachedVanillaVersion> = entries
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.path())
+        .filter(|path| path.is_dir())
+        .filter_map(|cache_dir| {
+            let manifest = read_manifest(&cache_dir)?;
+            Some(CachedVanillaVersion {
+                version: manifest.version,
+                jar_hash: manifest.jar_hash,
+                size_bytes: dir_size(&cache_dir),
+                // Manifests from before eviction only know the extraction time
+                last_used: if manifest.last_used > 0 {
+                    manifest.last_used
+                } else {
+                    manifest.extracted_at
+                },
+                active: active.as_deref() == Some(cache_dir.as_path()),
+                cache_dir: cache_dir.to_string_lossy().to_string(),
+            })
+        })
+        .collect();
+    versions.sort_by(|a, b| (a.last_used, &a.version).cmp(&(b.last_used, &b.version)));
+    versions
+}
+
+/// The cached folder extracted from a JAR, if any
+pub fn find_cached_version(cache_root: &Path, jar_path: &Path) -> Result<Option<PathBuf>> {
+    for cached in list_cached_versions(cache_root) {
+        let cache_dir = PathBuf::from(&cached.cache_dir);
+        if let Some(manifest) = read_manifest(&cache_dir) {
+            if manifest.matches_jar(jar_path)? {
+                return Ok(Some(cache_dir));
+            }
+        }
+    }
+    Ok(None)
+}
+
+/// Remove cached versions, skipping any that another instance is using
+///
+/// # Arguments
+/// * `keep` - Whether a version stays; the others are removed
+fn remove_versions(
+    cache_root: &Path,
+    versions: &[CachedVanillaVersion],
+    keep: impl Fn(&CachedVanillaVersion) -> bool,
+) -> VanillaCacheClearResult {
+    let mut result = VanillaCacheClearResult::default();
+    for cached in versions.iter().filter(|cached| !keep(cached)) {
+        let cache_dir = Path::new(&cached.cache_dir);
+        if instance_lock::is_locked(cache_dir) {
+            warn!("Not removing {}: it's in use", cached.version);
+            continue;
+        }
+        match file_retry::remove_dir_all_with_retry(cache_dir) {
+            Ok(()) => {
+                result.removed.push(cached.version.clone());
+                result.freed_bytes += cached.size_bytes;
+            }
+            Err(e) => warn!("Failed to remove cached version {}: {}", cached.version, e),
+        }
+    }
+    if read_active_version(cache_root).is_none() {
+        fs::remove_file(cache_root.join(ACTIVE_VERSION_FILE)).ok();
+    }
+    result
+}
+
+/// Remove the least recently used versions until the cache fits a size
+///
+/// The version in `keep_dir` is never removed, even if it alone is larger.
+pub fn evict_to_limit(
+    cache_root: &Path,
+    limit_bytes: u64,
+    keep_dir: &Path,
+) -> VanillaCacheClearResult {
+    let versions = list_cached_versions(cache_root);
+    let mut total: u64 = versions.iter().map(|cached| cached.size_bytes).sum();
+    let mut evicted = Vec::new();
+    for cached in &versions {
+        if total <= limit_bytes {
+            break;
+        }
+        if Path::new(&cached.cache_dir) != keep_dir {
+            total -= cached.size_bytes;
+            evicted.push(cached.jar_hash.clone());
+        }
+    }
+    remove_versions(cache_root, &versions, |cached| {
+        !evicted.contains(&cached.jar_hash)
+    })
+}
+
+/// Remove one cached version, or all of them
+///
+/// # Arguments
+/// * `version` - Version name (e.g. "1.21.4"); every version if None
+pub fn clear_vanilla_cache(cache_root: &Path, version: Option<&str>) -> VanillaCacheClearResult {
+    let versions = list_cached_versions(cache_root);
+    let result = remove_versions(cache_root, &versions, |cached| {
+        version.map_or(false, |version| cached.version != version)
+    });
+    info!(
+        "Removed {} cached version(s), {} bytes",
+        result.removed.len(),
+        result.freed_bytes
+    );
+    result
+}
+
+/// Forget the active folder so the next lookup reads it from disk again
+pub fn reset_active_cache_dir() {
+    *ACTIVE_CACHE_DIR.lock().unwrap_or_else(|e| e.into_inner()) = None;
+}
+
 /// Check if Minecraft is installed at the given directory
 /// Works with both official launcher (.minecraft/versions) and Modrinth (meta/versions)
 pub fn check_minecraft_installation(mc_dir: &Path) -> Result<bool> {
@@ -179,6 +782,10 @@ pub fn list_available_versions_from_dir(mc_dir: &Path) -> Result<Vec<MinecraftVe
 
         if let Some(version_name) = path.file_name().and_then(|n| n.to_str()) {
             let jar_path = path.join(format!("{}.jar", version_name));
+            if jar_path.exists() && !is_client_version(&versions_dir, version_name, &jar_path) {
+                warn!("Skipping {}: not a vanilla client JAR", version_name);
+                continue;
+            }
             if jar_path.exists() {
                 // Get modification time
                 let modified_time = fs::metadata(&jar_path)
@@ -188,10 +795,18 @@ pub fn list_available_versions_from_dir(mc_dir: &Path) -> Result<Vec<MinecraftVe
                     .map(|d| d.as_secs())
                     .unwrap_or(0);
 
+                let json = read_version_json(&versions_dir, version_name).unwrap_or_default();
+                let version_type = match &json.version_type {
+                    Some(value) => VersionType::from_json(value),
+                    None => VersionType::from_name(version_name),
+                };
+
                 versions.push(MinecraftVersion {
                     version: version_name.to_string(),
                     jar_path: jar_path.to_string_lossy().to_string(),
                     modified_time,
+                    version_type,
+                    release_time: json.release_time,
                 });
             }
         }
@@ -210,18 +825,23 @@ pub fn list_available_versions_from_dir(mc_dir: &Path) -> Result<Vec<MinecraftVe
     Ok(versions)
 }
 
-/// Find the latest Minecraft version JAR file from a specific Minecraft directory
+/// Saved version preference, defaults if settings can't be read
+fn version_settings() -> VanillaVersionSettings {
+    settings::load_settings()
+        .map(|s| s.vanilla_version)
+        .unwrap_or_default()
+}
+
+/// Find the preferred Minecraft version JAR file from a specific Minecraft directory
 pub fn find_latest_version_jar_from_dir(mc_dir: &Path) -> Result<PathBuf> {
     let versions = list_available_versions_from_dir(mc_dir)?;
-    let latest = versions
-        .first()
+    let latest = select_version(&versions, &version_settings())
         .ok_or_else(|| anyhow!("No versions found"))?;
     Ok(PathBuf::from(&latest.jar_path))
 }
 
-/// List all available Minecraft versions from all detected launcher locations
-pub fn list_all_available_versions() -> Result<Vec<MinecraftVersion>> {
-    let mut all_versions = Vec::new();
+/// Minecraft directories of all detected launchers
+fn launcher_dirs() -> Vec<PathBuf> {
     let mut search_paths = Vec::new();
 
     // Try official Minecraft launcher first
@@ -257,8 +877,15 @@ pub fn list_all_available_versions() -> Result<Vec<MinecraftVersion>> {
         }
     }
 
+    search_paths
+}
+
+/// List all available Minecraft versions from all detected launcher locations
+pub fn list_all_available_versions() -> Result<Vec<MinecraftVersion>> {
+    let mut all_versions = Vec::new();
+
     // Collect versions from all paths
-    for path in search_paths {
+    for path in launcher_dirs() {
         if let Ok(versions) = list_available_versions_from_dir(&path) {
             all_versions.extend(versions);
         }
@@ -277,134 +904,223 @@ pub fn list_all_available_versions() -> Result<Vec<MinecraftVersion>> {
     Ok(all_versions)
 }
 
-/// Find the latest Minecraft version JAR file
+/// Find the preferred Minecraft version JAR file (the latest release unless
+/// the settings say otherwise)
 /// Checks multiple launcher locations in order of preference
 pub fn find_latest_version_jar() -> Result<PathBuf> {
     let versions = list_all_available_versions()?;
-    let latest = versions
-        .first()
+    let latest = select_version(&versions, &version_settings())
         .ok_or_else(|| anyhow!("No versions found"))?;
     Ok(PathBuf::from(&latest.jar_path))
 }
 
-/// Get the currently cached version info (if any)
+/// Whether the cache should be (re)extracted for the saved preference
+///
+/// Any cache satisfies the "latest" preferences (switching is explicit);
+/// a pin needs the pinned version.
+fn needs_extraction(cached: Option<String>) -> bool {
+    let settings = version_settings();
+    match (cached, settings.preference, settings.pinned_version) {
+        (None, _, _) => true,
+        (Some(cached), VersionPreference::Pinned, Some(pinned)) => cached != pinned,
+        _ => false,
+    }
+}
+
+/// Get the version vanilla assets currently come from (if any)
 pub fn get_cached_version() -> Result<Option<String>> {
     let cache_dir = get_vanilla_cache_dir()?;
-    let marker_file = cache_dir.join(".extracted_version");
-
-    if marker_file.exists() {
-        let version = fs::read_to_string(marker_file).context("Failed to read version marker")?;
-        Ok(Some(version.trim().to_string()))
-    } else {
-        Ok(None)
-    }
+    Ok(read_manifest(&cache_dir).map(|manifest| manifest.version))
 }
 
 /// Extract vanilla textures from the Minecraft JAR to cache
 pub fn extract_vanilla_textures(jar_path: &Path) -> Result<PathBuf> {
-    extract_vanilla_textures_with_progress(jar_path, None)
+    extract_vanilla_textures_with_progress(jar_path, None, None)
 }
 
 /// Extract vanilla textures with optional progress callback
+///
+/// Each JAR gets its own folder, so switching back to a version extracted
+/// before only makes it active again. After a new extraction the least
+/// recently used versions are evicted to stay under the size cap. A
+/// cancelled extraction leaves no manifest, so the next one starts over.
 pub fn extract_vanilla_textures_with_progress(
     jar_path: &Path,
     progress_callback: Option<ProgressCallback>,
+    cancel: Option<&CancelToken>,
 ) -> Result<PathBuf> {
-    let cache_dir = get_vanilla_cache_dir()?;
+    let cache_root = get_vanilla_cache_root()?;
 
     // Extract version name from jar path
-    let version_name = jar_path
-        .parent()
-        .and_then(|p| p.file_name())
-        .and_then(|n| n.to_str())
-        .ok_or_else(|| anyhow!("Could not determine version name from JAR path"))?;
-
-    // Check if already extracted with this version
-    let marker_file = cache_dir.join(".extracted_version");
-    if marker_file.exists() {
-        if let Ok(cached_version) = fs::read_to_string(&marker_file) {
-            if cached_version.trim() == version_name {
-                println!("[vanilla_textures] Version {} already cached", version_name);
-                return Ok(cache_dir);
-            }
-        }
+    let version_name = version_name_from_jar(jar_path)?;
+
+    // Check if already extracted from this JAR
+    if let Some(cache_dir) = find_cached_version(&cache_root, jar_path)? {
+        info!("Version {} already cached", version_name);
+        activate(&cache_root, &cache_dir)?;
+        return Ok(cache_dir);
     }
 
-    // Clean old cache if it exists
+    let mut manifest = ExtractionManifest::for_jar(jar_path)?;
+    let cache_dir = version_cache_dir(&cache_root, &manifest.jar_hash);
+
+    // Another instance may be extracting into the same cache
+    let _cache_lock = instance_lock::InstanceLock::acquire(
+        &cache_dir,
+        &format!("extracting vanilla textures for {}", version_name),
+    )?;
+
+    // Clean up an earlier extraction that didn't finish
     if cache_dir.exists() {
-        println!(
-            "[vanilla_textures] Cleaning old cache to extract version {}",
-            version_name
-        );
-        fs::remove_dir_all(&cache_dir).context("Failed to clean old cache")?;
-        fs::create_dir_all(&cache_dir).context("Failed to recreate cache directory")?;
+        info!("Cleaning incomplete cache of version {}", version_name);
+        file_retry::remove_dir_all_with_retry(&cache_dir).context("Failed to clean old cache")?;
     }
+    fs::create_dir_all(&cache_dir).context("Failed to create version cache directory")?;
 
     // First pass: collect all files that need to be extracted
+    let files_to_extract = list_extractable_entries(jar_path)?;
+
+    info!(
+        "Found {} files to extract, extracting in PARALLEL",
+        files_to_extract.len()
+    );
+
+    // Second pass: extract files in parallel using chunked batches
+    let extracted = extract_entries(
+        jar_path,
+        &cache_dir,
+        &files_to_extract,
+        progress_callback,
+        cancel,
+    );
+    if let Err(e) = extracted {
+        error!("Error during extraction: {}", e);
+        return Err(e);
+    }
+
+    info!("All files extracted successfully");
+
+    // Record what the cache was extracted from
+    manifest.extracted_at = unix_now();
+    manifest.last_used = manifest.extracted_at;
+    write_manifest(&cache_dir, &manifest)?;
+    activate(&cache_root, &cache_dir)?;
+
+    let limit_mb = version_settings()
+        .cache_limit_mb
+        .unwrap_or(DEFAULT_CACHE_LIMIT_MB);
+    let evicted = evict_to_limit(&cache_root, limit_mb * 1024 * 1024, &cache_dir);
+    if !evicted.removed.is_empty() {
+        info!(
+            "Evicted cached version(s) {} to stay under {} MB",
+            evicted.removed.join(", "),
+            limit_mb
+        );
+    }
+
+    info!(
+        "Successfully extracted vanilla assets for version {} (textures, models, blockstates) in PARALLEL",
+        version_name
+    );
+    Ok(cache_dir)
+}
+
+/// Determine the version name from a version JAR path (its parent folder)
+pub fn version_name_from_jar(jar_path: &Path) -> Result<String> {
+    jar_path
+        .parent()
+        .and_then(|p| p.file_name())
+        .and_then(|n| n.to_str())
+        .map(|n| n.to_string())
+        .ok_or_else(|| anyhow!("Could not determine version name from JAR path"))
+}
+
+/// Whether a JAR entry belongs in the vanilla cache
+///
+/// Textures (PNG), animation metadata (PNG.MCMETA), models (JSON), and blockstates (JSON)
+pub fn should_extract_entry(file_path: &str) -> bool {
+    (file_path.starts_with("assets/minecraft/textures/")
+        && (file_path.ends_with(".png") || file_path.ends_with(".png.mcmeta")))
+        || (file_path.starts_with("assets/minecraft/models/") && file_path.ends_with(".json"))
+        || (file_path.starts_with("assets/minecraft/blockstates/") && file_path.ends_with(".json"))
+}
+
+/// List the JAR entries that belong in the vanilla cache
+pub fn list_extractable_entries(jar_path: &Path) -> Result<Vec<ExtractableEntry>> {
     let jar_file = fs::File::open(jar_path).context("Failed to open Minecraft JAR file")?;
     let mut archive = ZipArchive::new(jar_file).context("Failed to read JAR archive")?;
 
-    let mut files_to_extract = Vec::new();
+    let mut entries = Vec::new();
 
     for i in 0..archive.len() {
         let file = archive
             .by_index(i)
             .context("Failed to read archive entry")?;
 
-        let file_path = file.name().to_string();
-
-        // Extract textures (PNG), animation metadata (PNG.MCMETA), models (JSON), and blockstates (JSON)
-        let should_extract = (file_path.starts_with("assets/minecraft/textures/")
-            && (file_path.ends_with(".png") || file_path.ends_with(".png.mcmeta")))
-            || (file_path.starts_with("assets/minecraft/models/") && file_path.ends_with(".json"))
-            || (file_path.starts_with("assets/minecraft/blockstates/")
-                && file_path.ends_with(".json"));
+        if !should_extract_entry(file.name()) {
+            continue;
+        }
 
-        if should_extract {
-            files_to_extract.push((i, file_path));
+        // Never trust entry names: reject anything that could escape the cache
+        match crate::util::zip::sanitize_entry_path(file.name()) {
+            Ok(rel_path) => entries.push(ExtractableEntry {
+                index: i,
+                rel_path,
+                size: file.size(),
+            }),
+            Err(e) => warn!("Skipping unsafe entry: {}", e),
         }
     }
 
-    let total_files = files_to_extract.len();
-    println!(
-        "[vanilla_textures] Found {} files to extract, extracting in PARALLEL",
-        total_files
-    );
+    Ok(entries)
+}
+
+/// Extract the given JAR entries into the cache directory in parallel,
+/// stopping between files once `cancel` is set
+pub fn extract_entries(
+    jar_path: &Path,
+    cache_dir: &Path,
+    entries: &[ExtractableEntry],
+    progress_callback: Option<ProgressCallback>,
+    cancel: Option<&CancelToken>,
+) -> Result<()> {
+    let total_files = entries.len();
 
     // Report initial progress
     if let Some(ref callback) = progress_callback {
-        callback(0, total_files);
+        callback(0, total_files, None);
+    }
+
+    if total_files == 0 {
+        return Ok(());
     }
 
-    // Second pass: extract files in parallel using chunked batches
     // This is much faster than opening the JAR for each file
-    let jar_path_clone = jar_path.to_path_buf();
-    let cache_dir_clone = cache_dir.clone();
     let extracted_count = Arc::new(AtomicUsize::new(0));
-    let progress_callback_clone = progress_callback.clone();
 
-    // Determine optimal chunk size based on CPU count
-    let num_threads = rayon::current_num_threads();
-    let chunk_size = (total_files + num_threads - 1) / num_threads; // Ceiling division
+    parallelism::with_io_pool(|| {
+        // One chunk per thread of the pool running the extraction
+        let num_threads = rayon::current_num_threads();
+        let chunk_size = (total_files + num_threads - 1) / num_threads; // Ceiling division
 
-    let extraction_result: Result<()> =
-        files_to_extract
+        entries
             .par_chunks(chunk_size)
             .try_for_each(|chunk| -> Result<()> {
                 // Open JAR once per chunk (per thread)
                 let jar_file =
-                    fs::File::open(&jar_path_clone).context("Failed to open Minecraft JAR file")?;
+                    fs::File::open(jar_path).context("Failed to open Minecraft JAR file")?;
                 let mut archive =
                     ZipArchive::new(jar_file).context("Failed to read JAR archive")?;
 
                 // Process all files in this chunk
-                for (index, file_path) in chunk {
+                for entry in chunk {
+                    jobs::check_cancelled(cancel)?;
                     let mut file = archive
-                        .by_index(*index)
+                        .by_index(entry.index)
                         .context("Failed to read archive entry")?;
 
                     // Keep the full structure: assets/minecraft/...
-                    let output_path = cache_dir_clone.join(file_path);
+                    let output_path = cache_dir.join(&entry.rel_path);
 
                     // Create parent directories
                     if let Some(parent) = output_path.parent() {
@@ -412,47 +1128,23 @@ pub fn extract_vanilla_textures_with_progress(
                     }
 
                     // Extract the file
-                    let mut output_file =
-                        fs::File::create(&output_path).context("Failed to create file")?;
+                    let mut output_file = file_retry::create_file_with_retry(&output_path)?;
                     std::io::copy(&mut file, &mut output_file).context("Failed to write file")?;
 
                     // Update progress
                     let count = extracted_count.fetch_add(1, Ordering::Relaxed) + 1;
-                    if let Some(ref callback) = progress_callback_clone {
+                    if let Some(ref callback) = progress_callback {
                         // Report progress every 50 files or on completion
                         if count % 50 == 0 || count == total_files {
-                            callback(count, total_files);
+                            let file = entry.rel_path.to_string_lossy();
+                            callback(count, total_files, Some(&file));
                         }
                     }
                 }
 
                 Ok(())
-            });
-
-    // Check if extraction succeeded
-    if let Err(e) = extraction_result {
-        eprintln!("[vanilla_textures] ERROR during extraction: {}", e);
-        return Err(e);
-    }
-
-    println!("[vanilla_textures] All files extracted successfully");
-
-    // Create marker file with version name
-    println!(
-        "[vanilla_textures] Writing marker file for version: {}",
-        version_name
-    );
-    fs::write(&marker_file, version_name).context("Failed to create extraction marker")?;
-    println!(
-        "[vanilla_textures] Marker file written to: {}",
-        marker_file.display()
-    );
-
-    println!(
-        "[vanilla_textures] Successfully extracted vanilla assets for version {} (textures, models, blockstates) in PARALLEL",
-        version_name
-    );
-    Ok(cache_dir)
+            })
+    })
 }
 
 /// Get the path to a vanilla texture file by asset ID
@@ -513,71 +1205,80 @@ pub fn get_colormap_path(colormap_type: &str) -> Result<PathBuf> {
 
 /// Initialize vanilla textures from a specific Minecraft directory
 pub fn initialize_vanilla_textures_from_dir(mc_dir: &Path) -> Result<PathBuf> {
-    initialize_vanilla_textures_from_dir_with_progress(mc_dir, None)
+    initialize_vanilla_textures_from_dir_with_progress(mc_dir, None, None)
 }
 
 /// Initialize vanilla textures from a specific Minecraft directory with progress
 pub fn initialize_vanilla_textures_from_dir_with_progress(
     mc_dir: &Path,
     progress_callback: Option<ProgressCallback>,
+    cancel: Option<&CancelToken>,
 ) -> Result<PathBuf> {
     let cache_dir = get_vanilla_cache_dir()?;
 
     // If already extracted, return cache dir
-    if get_cached_version()?.is_some() {
+    if !needs_extraction(get_cached_version()?) {
         return Ok(cache_dir);
     }
 
     // Find and extract from latest version JAR in the given directory
     let jar_path = find_latest_version_jar_from_dir(mc_dir)?;
-    extract_vanilla_textures_with_progress(&jar_path, progress_callback)
+    extract_vanilla_textures_with_progress(&jar_path, progress_callback, cancel)
 }
 
 /// Initialize vanilla textures (extract if not already cached)
 pub fn initialize_vanilla_textures() -> Result<PathBuf> {
-    initialize_vanilla_textures_with_progress(None)
+    initialize_vanilla_textures_with_progress(None, None)
 }
 
 /// Initialize vanilla textures with progress callback
 pub fn initialize_vanilla_textures_with_progress(
     progress_callback: Option<ProgressCallback>,
+    cancel: Option<&CancelToken>,
 ) -> Result<PathBuf> {
     let cache_dir = get_vanilla_cache_dir()?;
 
     // If already extracted, return cache dir
-    if get_cached_version()?.is_some() {
+    if !needs_extraction(get_cached_version()?) {
         return Ok(cache_dir);
     }
 
     // Find and extract from latest version JAR
     let jar_path = find_latest_version_jar()?;
-    extract_vanilla_textures_with_progress(&jar_path, progress_callback)
+    extract_vanilla_textures_with_progress(&jar_path, progress_callback, cancel)
 }
 
 /// Extract vanilla textures for a specific version
 pub fn extract_vanilla_textures_for_version(version: &str) -> Result<PathBuf> {
-    extract_vanilla_textures_for_version_with_progress(version, None)
+    extract_vanilla_textures_for_version_with_progress(version, None, None)
 }
 
 /// Extract vanilla textures for a specific version with progress callback
 pub fn extract_vanilla_textures_for_version_with_progress(
     version: &str,
     progress_callback: Option<ProgressCallback>,
+    cancel: Option<&CancelToken>,
 ) -> Result<PathBuf> {
+    // Extract textures for this version
+    let jar_path = find_version_jar(version)?;
+    extract_vanilla_textures_with_progress(&jar_path, progress_callback, cancel)
+}
+
+/// Find the JAR for a specific installed version
+pub fn find_version_jar(version: &str) -> Result<PathBuf> {
     // Find all available versions
     let versions = list_all_available_versions()?;
 
     // Find the requested version
-    let target_version = versions
-        .iter()
-        .find(|v| v.version == version)
-        .ok_or_else(|| anyhow!("Version {} not found", version))?;
+    if let Some(target_version) = versions.iter().find(|v| v.version == version) {
+        return Ok(PathBuf::from(&target_version.jar_path));
+    }
 
-    // Extract textures for this version
-    extract_vanilla_textures_with_progress(
-        &PathBuf::from(&target_version.jar_path),
-        progress_callback,
-    )
+    // A loader profile isn't listed, but runs a version that may be
+    launcher_dirs()
+        .iter()
+        .find_map(|mc_dir| resolve_client_jar(&mc_dir.join("versions"), version))
+        .ok_or_else(|| anyhow!("Version {} not found", version))
 }
 
 #[cfg(test)]
@@ -604,4 +1305,211 @@ mod tests {
         let paths = get_suggested_minecraft_paths();
         assert!(!paths.is_empty());
     }
+
+    fn write_version(versions_dir: &Path, name: &str, json: Option<&str>, files: &[&str]) {
+        let dir = versions_dir.join(name);
+        fs::create_dir_all(&dir).expect("Failed to create test directory");
+        if let Some(json) = json {
+            fs::write(dir.join(format!("{}.json", name)), json).expect("Failed to write json");
+        }
+        let jar =
+            fs::File::create(dir.join(format!("{}.jar", name))).expect("Failed to create jar");
+        let mut writer = zip::ZipWriter::new(jar);
+        for file in files {
+            writer
+                .start_file(*file, zip::write::FileOptions::default())
+                .expect("Failed to add entry");
+        }
+        writer.finish().expect("Failed to finish jar");
+    }
+
+    #[test]
+    fn test_list_versions_skips_loader_profiles() {
+        let temp_dir = std::env::temp_dir().join("test_vanilla_loader_profiles");
+        fs::remove_dir_all(&temp_dir).ok();
+        let versions_dir = temp_dir.join("versions");
+        write_version(
+            &versions_dir,
+            "1.21.4",
+            Some(r#"{"id":"1.21.4","downloads":{"client":{"url":"x"}}}"#),
+            &[],
+        );
+        write_version(
+            &versions_dir,
+            "fabric-loader-0.16.9-1.21.4",
+            Some(r#"{"id":"fabric-loader-0.16.9-1.21.4","inheritsFrom":"1.21.4"}"#),
+            &[],
+        );
+        write_version(&versions_dir, "stub", None, &["net/Main.class"]);
+        write_version(
+            &versions_dir,
+            "custom",
+            None,
+            &["assets/minecraft/textures/block/stone.png"],
+        );
+
+        let versions = list_available_versions_from_dir(&temp_dir);
+        let resolved = resolve_client_jar(&versions_dir, "fabric-loader-0.16.9-1.21.4");
+        let stub = resolve_client_jar(&versions_dir, "stub");
+
+        // Clean up
+        fs::remove_dir_all(&temp_dir).ok();
+
+        let names: Vec<String> = versions.unwrap().into_iter().map(|v| v.version).collect();
+        assert_eq!(names, vec!["1.21.4", "custom"]);
+        assert_eq!(resolved, Some(versions_dir.join("1.21.4/1.21.4.jar")));
+        assert_eq!(stub, None);
+    }
+
+    #[test]
+    fn test_select_version() {
+        let version = |name: &str, version_type: VersionType, time: &str| MinecraftVersion {
+            version: name.to_string(),
+            jar_path: format!("versions/{0}/{0}.jar", name),
+            modified_time: 0,
+            version_type,
+            release_time: Some(time.to_string()),
+        };
+        // Listed newest first by name, releases ahead of snapshots
+        let versions = vec![
+            version("1.21.4", VersionType::Release, "2024-12-03T10:12:57+00:00"),
+            version("1.21.3", VersionType::Release, "2024-10-23T12:28:15+00:00"),
+            version("25w02a", VersionType::Snapshot, "2025-01-08T13:36:40+00:00"),
+        ];
+        let pick = |preference, pinned: Option<&str>| {
+            let settings = VanillaVersionSettings {
+                preference,
+                pinned_version: pinned.map(|p| p.to_string()),
+                ..Default::default()
+            };
+            select_version(&versions, &settings).map(|v| v.version.clone())
+        };
+
+        assert_eq!(
+            pick(VersionPreference::LatestRelease, None).as_deref(),
+            Some("1.21.4")
+        );
+        assert_eq!(
+            pick(VersionPreference::LatestSnapshot, None).as_deref(),
+            Some("25w02a")
+        );
+        assert_eq!(
+            pick(VersionPreference::Pinned, Some("1.21.3")).as_deref(),
+            Some("1.21.3")
+        );
+        // A pin that isn't installed falls back to the latest release
+        assert_eq!(
+            pick(VersionPreference::Pinned, Some("1.20.1")).as_deref(),
+            Some("1.21.4")
+        );
+        assert_eq!(VersionType::from_name("24w45a"), VersionType::Snapshot);
+    }
+
+    #[test]
+    fn test_extraction_manifest_matches_jar() {
+        let temp_dir = std::env::temp_dir().join("test_extraction_manifest");
+        let jar_path = temp_dir.join("versions/1.21.4/1.21.4.jar");
+        let copy_path = temp_dir.join("copy/1.21.4/1.21.4.jar");
+        let other_path = temp_dir.join("versions/1.21.5/1.21.5.jar");
+        for (path, data) in [
+            (&jar_path, &b"jar contents"[..]),
+            (&copy_path, &b"jar contents"[..]),
+            (&other_path, &b"other contents"[..]),
+        ] {
+            fs::create_dir_all(path.parent().unwrap()).expect("Failed to create test directory");
+            fs::write(path, data).expect("Failed to write test jar");
+        }
+
+        let manifest = ExtractionManifest::for_jar(&jar_path).expect("should describe jar");
+        write_manifest(&temp_dir, &manifest).expect("should write manifest");
+        let loaded = read_manifest(&temp_dir);
+
+        let same = manifest.matches_jar(&jar_path).unwrap();
+        let copy = manifest.matches_jar(&copy_path).unwrap();
+        let other = manifest.matches_jar(&other_path).unwrap();
+        let mut old_schema = manifest.clone();
+        old_schema.schema_version = 0;
+        let schema = old_schema.matches_jar(&jar_path).unwrap();
+
+        // Clean up
+        fs::remove_dir_all(&temp_dir).ok();
+
+        assert_eq!(manifest.version, "1.21.4");
+        assert_eq!(loaded, Some(manifest));
+        assert!(same);
+        assert!(
+            copy,
+            "identical contents should match without re-extracting"
+        );
+        assert!(!other);
+        assert!(!schema);
+    }
+
+    #[test]
+    fn test_per_version_cache() {
+        let temp_dir = std::env::temp_dir().join("test_vanilla_version_cache");
+        fs::remove_dir_all(&temp_dir).ok();
+        let cache_root = temp_dir.join("cache");
+
+        // A cache from before per-version folders
+        let flat_texture = cache_root.join("assets/minecraft/textures/block/stone.png");
+        fs::create_dir_all(flat_texture.parent().unwrap()).unwrap();
+        fs::write(&flat_texture, vec![0u8; 100]).unwrap();
+        let mut jars = Vec::new();
+        for (index, version) in ["1.20.1", "1.21.1", "1.21.4"].iter().enumerate() {
+            let jar = temp_dir.join(format!("versions/{}/{}.jar", version, version));
+            fs::create_dir_all(jar.parent().unwrap()).unwrap();
+            fs::write(&jar, version.as_bytes()).unwrap();
+            let mut manifest = ExtractionManifest::for_jar(&jar).unwrap();
+            manifest.last_used = index as u64 + 1;
+            jars.push((jar, manifest));
+        }
+        write_manifest(&cache_root, &jars[0].1).unwrap();
+        migrate_flat_cache(&cache_root);
+        let migrated = read_active_version(&cache_root);
+
+        for (_, manifest) in &jars[1..] {
+            let cache_dir = version_cache_dir(&cache_root, &manifest.jar_hash);
+            let texture = cache_dir.join("assets/minecraft/textures/block/stone.png");
+            fs::create_dir_all(texture.parent().unwrap()).unwrap();
+            fs::write(texture, vec![0u8; 100]).unwrap();
+            write_manifest(&cache_dir, manifest).unwrap();
+        }
+        let newest = version_cache_dir(&cache_root, &jars[2].1.jar_hash);
+        set_active_version(&cache_root, &newest).unwrap();
+        let found = find_cached_version(&cache_root, &jars[1].0).unwrap();
+        let listed: Vec<String> = list_cached_versions(&cache_root)
+            .into_iter()
+            .map(|cached| cached.version)
+            .collect();
+
+        // Each version is about 400 bytes with its manifest; keep two
+        let evicted = evict_to_limit(&cache_root, 1000, &newest);
+        let cleared = clear_vanilla_cache(&cache_root, Some("1.21.4"));
+        let active_after_clear = read_active_version(&cache_root);
+        let left: Vec<String> = list_cached_versions(&cache_root)
+            .into_iter()
+            .map(|cached| cached.version)
+            .collect();
+
+        // Clean up
+        fs::remove_dir_all(&temp_dir).ok();
+
+        assert_eq!(
+            migrated,
+            Some(version_cache_dir(&cache_root, &jars[0].1.jar_hash))
+        );
+        assert!(!flat_texture.exists());
+        assert_eq!(
+            found,
+            Some(version_cache_dir(&cache_root, &jars[1].1.jar_hash))
+        );
+        // 1.21.4 was just activated, so it's the most recently used
+        assert_eq!(listed, vec!["1.20.1", "1.21.1", "1.21.4"]);
+        assert_eq!(evicted.removed, vec!["1.20.1"]);
+        assert_eq!(cleared.removed, vec!["1.21.4"]);
+        assert!(cleared.freed_bytes >= 100);
+        assert_eq!(active_after_clear, None);
+        assert_eq!(left, vec!["1.21.1"]);
+    }
 }
@@ -1,11 +1,24 @@
 /// Build Weaver Nest - the optimized output resource pack
 use crate::model::{AssetRecord, OverrideSelection, PackMeta};
-use crate::util::zip;
+use crate::util::build_journal::{self, JournalWriter};
+use crate::util::jobs::{self, CancelToken};
+use crate::util::pack_builder;
+use crate::util::progress::FileProgress;
+use crate::util::zip::{self, PROGRESS_INTERVAL};
+use crate::util::{
+    animation, asset_indexer, file_retry, mcmeta_pairs, namespace_conflicts, parallelism, projects,
+};
 use anyhow::{anyhow, Result};
 use rayon::prelude::*;
-use std::collections::HashMap;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
 use std::fs;
 use std::path::Path;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use tracing::info;
+
+/// Files copied per batch; a batch reads all its ZIP entries at once
+const COPY_BATCH_SIZE: usize = 64;
 
 /// Entry representing a winning asset to be copied
 #[allow(dead_code)]
@@ -15,6 +28,45 @@ struct WinnerEntry {
     source_pack_id: String,
     source_path: String,
     source_is_zip: bool,
+    /// Skip instead of failing if the winning pack lacks this file
+    optional: bool,
+}
+
+/// Build settings besides the order and overrides that decide which pack
+/// wins an asset
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct WinnerRules {
+    /// Packs left out of the merge, along with overrides picking them
+    pub disabled_packs: Vec<String>,
+    /// Namespace -> the only pack allowed to provide it
+    pub namespace_owners: HashMap<String, String>,
+}
+
+/// Pack whose copy of an asset goes into the output
+///
+/// An override wins outright; otherwise the provider highest in
+/// `pack_order` does (providers missing from the order rank last).
+pub fn winning_pack<'a>(
+    asset_id: &str,
+    providers: &'a HashMap<String, Vec<String>>,
+    pack_order: &[String],
+    overrides: &'a HashMap<String, OverrideSelection>,
+) -> Option<&'a str> {
+    if let Some(override_entry) = overrides.get(asset_id) {
+        return Some(&override_entry.pack_id);
+    }
+
+    providers
+        .get(asset_id)?
+        .iter()
+        .min_by_key(|pack_id| {
+            pack_order
+                .iter()
+                .position(|id| id == *pack_id)
+                .unwrap_or(usize::MAX)
+        })
+        .map(|pack_id| pack_id.as_str())
 }
 
 /// Build Weaver Nest output pack
@@ -29,47 +81,57 @@ pub fn build_weaver_nest(
     pack_order: &[String],
     overrides: &HashMap<String, OverrideSelection>, // asset_id -> override payload
     output_dir: &str,
+) -> Result<()> {
+    build_weaver_nest_journaled(
+        packs, assets, providers, pack_order, overrides, output_dir, None, false, None, None,
+    )
+}
+
+/// Build a Weaver Nest while recording progress in a build journal
+///
+/// With a journal, every file is written to a temporary name and renamed into
+/// place, so a file that exists is complete. When `resume` is set, files that
+/// already exist in `output_dir` are skipped. `progress` is called with
+/// (files copied, total, file) as winning files are written. Once `cancel`
+/// is set the build stops between files, leaving what it staged for a
+/// resume.
+#[allow(clippy::too_many_arguments)]
+pub fn build_weaver_nest_journaled(
+    packs: &[PackMeta],
+    assets: &[AssetRecord],
+    providers: &HashMap<String, Vec<String>>,
+    pack_order: &[String],
+    overrides: &HashMap<String, OverrideSelection>,
+    output_dir: &str,
+    journal: Option<&JournalWriter>,
+    resume: bool,
+    progress: Option<FileProgress>,
+    cancel: Option<&CancelToken>,
 ) -> Result<()> {
     let output_path = Path::new(output_dir);
 
     // Create output directory
     fs::create_dir_all(output_path)?;
 
-    // Create pack.mcmeta
-    create_pack_mcmeta(output_path)?;
+    // Create pack.mcmeta and pack.png from the merged packs
+    pack_builder::write_pack_metadata(output_path, packs, pack_order)?;
 
     // Determine winners for each asset
     let mut winners = Vec::new();
 
     for asset in assets {
-        let mut override_source_path: Option<String> = None;
-        let winner_pack_id = if let Some(override_entry) = overrides.get(&asset.id) {
-            if let Some(path) = &override_entry.variant_path {
-                override_source_path = Some(path.clone());
-            }
-            override_entry.pack_id.clone()
-        } else {
-            // Use first pack in order that provides this asset
-            let providing_packs = providers.get(&asset.id).cloned().unwrap_or_default();
-            if providing_packs.is_empty() {
-                continue;
-            }
+        // A texture's .mcmeta follows the texture (below) unless overridden
+        if mcmeta_pairs::is_mcmeta_asset(asset) && !overrides.contains_key(&asset.id) {
+            continue;
+        }
 
-            let winner = providing_packs
-                .iter()
-                .min_by_key(|pack_id| {
-                    pack_order
-                        .iter()
-                        .position(|id| id == *pack_id)
-                        .unwrap_or(usize::MAX)
-                })
-                .cloned();
-
-            match winner {
-                Some(pack_id) => pack_id,
-                None => continue,
-            }
+        let winner_pack_id = match winning_pack(&asset.id, providers, pack_order, overrides) {
+            Some(pack_id) => pack_id,
+            None => continue,
         };
+        let override_source_path = overrides
+            .get(&asset.id)
+            .and_then(|o| o.variant_path.clone());
 
         // Find the pack metadata
         let winner_pack = packs
@@ -77,63 +139,197 @@ pub fn build_weaver_nest(
             .find(|p| p.id == winner_pack_id)
             .ok_or_else(|| anyhow!("Pack not found: {}", winner_pack_id))?;
 
+        // Custom entity models and CTM span several files (jem, jpm parts,
+        // properties, textures); take every one the winning pack has so
+        // they aren't mixed across packs
+        if override_source_path.is_none() && asset_indexer::is_grouped_asset(&asset.id) {
+            for source_file in &asset.files {
+                winners.push(WinnerEntry {
+                    asset_id: asset.id.clone(),
+                    source_pack_id: winner_pack.id.clone(),
+                    source_path: source_file.clone(),
+                    source_is_zip: winner_pack.is_zip,
+                    optional: true,
+                });
+            }
+            continue;
+        }
+
         // Find the file to copy (first one in the asset's file list or overridden variant)
         // In a real implementation, you might want to merge multiple files
+        let is_variant = override_source_path.is_some();
         if let Some(source_file) = override_source_path.or_else(|| asset.files.first().cloned()) {
+            // Take the texture's .mcmeta from the same pack, never another's
+            let mcmeta_id = mcmeta_pairs::mcmeta_asset_id(&asset.id);
+            let has_mcmeta = is_variant
+                || providers
+                    .get(&mcmeta_id)
+                    .map_or(false, |ids| ids.iter().any(|id| id == winner_pack_id));
+            if source_file.ends_with(".png") && has_mcmeta && !overrides.contains_key(&mcmeta_id) {
+                winners.push(WinnerEntry {
+                    asset_id: mcmeta_id,
+                    source_pack_id: winner_pack.id.clone(),
+                    source_path: mcmeta_pairs::mcmeta_path(&source_file),
+                    source_is_zip: winner_pack.is_zip,
+                    optional: true,
+                });
+            }
+
             winners.push(WinnerEntry {
                 asset_id: asset.id.clone(),
                 source_pack_id: winner_pack.id.clone(),
                 source_path: source_file,
                 source_is_zip: winner_pack.is_zip,
+                optional: false,
             });
         }
     }
 
     // Copy winner files to output in parallel
-    println!("[build_weaver_nest] Copying {} files in PARALLEL", winners.len());
+    info!(
+        "Copying {} files in PARALLEL{}",
+        winners.len(),
+        if resume { " (resuming)" } else { "" }
+    );
     let pack_map: HashMap<String, &PackMeta> = packs.iter().map(|p| (p.id.clone(), p)).collect();
+    let total = winners.len();
+    let copied = AtomicUsize::new(0);
+    let report = |count: usize, file: Option<&str>| {
+        if let Some(progress) = progress {
+            if count % PROGRESS_INTERVAL == 0 || count == total {
+                progress(count, total, file);
+            }
+        }
+    };
+    report(0, None);
+
+    // Batch files by source pack so each batch opens a ZIP only once
+    let mut by_pack: BTreeMap<&str, Vec<&WinnerEntry>> = BTreeMap::new();
+    for winner in &winners {
+        by_pack
+            .entry(winner.source_pack_id.as_str())
+            .or_default()
+            .push(winner);
+    }
+    let batches: Vec<&[&WinnerEntry]> = by_pack
+        .values()
+        .flat_map(|entries| entries.chunks(COPY_BATCH_SIZE))
+        .collect();
 
-    // Process files in parallel
-    winners
-        .par_iter()
-        .try_for_each(|winner| -> Result<()> {
+    // Process batches in parallel
+    parallelism::with_io_pool(|| {
+        batches.par_iter().try_for_each(|batch| -> Result<()> {
+            let source_pack_id = &batch[0].source_pack_id;
             let source_pack = pack_map
-                .get(&winner.source_pack_id)
-                .ok_or_else(|| anyhow!("Pack not found: {}", winner.source_pack_id))?;
+                .get(source_pack_id)
+                .ok_or_else(|| anyhow!("Pack not found: {}", source_pack_id))?;
 
-            let content = if winner.source_is_zip {
-                zip::extract_zip_entry(&source_pack.path, &winner.source_path)?
+            jobs::check_cancelled(cancel)?;
+            let mut pending = Vec::with_capacity(batch.len());
+            for winner in batch.iter() {
+                if resume && zip::safe_join(output_path, &winner.source_path)?.exists() {
+                    report(
+                        copied.fetch_add(1, Ordering::Relaxed) + 1,
+                        Some(&winner.source_path),
+                    );
+                    continue;
+                }
+                pending.push(*winner);
+            }
+            let zip_contents = if batch[0].source_is_zip && !pending.is_empty() {
+                let paths: Vec<&str> = pending.iter().map(|w| w.source_path.as_str()).collect();
+                zip::extract_zip_entries(&source_pack.path, &paths)?
             } else {
-                let full_path = Path::new(&source_pack.path).join(&winner.source_path);
-                fs::read(&full_path)?
+                HashMap::new()
             };
 
-            // Write to output
-            let output_file_path = output_path.join(&winner.source_path);
-            fs::create_dir_all(output_file_path.parent().unwrap())?;
-            fs::write(&output_file_path, content)?;
+            for winner in pending {
+                jobs::check_cancelled(cancel)?;
+                let content = if winner.source_is_zip {
+                    match zip_contents.get(&winner.source_path) {
+                        Some(content) => Ok(content.clone()),
+                        // Left out of the batch read; read it alone to learn why
+                        None => zip::extract_zip_entry(&source_pack.path, &winner.source_path),
+                    }
+                } else {
+                    zip::safe_join(Path::new(&source_pack.path), &winner.source_path)
+                        .and_then(|full_path| Ok(fs::read(full_path)?))
+                };
+                let content = match content {
+                    Ok(content) => content,
+                    // Optional files may be missing, but not unreadable
+                    Err(e) if winner.optional && zip::is_missing_file(&e) => {
+                        report(
+                            copied.fetch_add(1, Ordering::Relaxed) + 1,
+                            Some(&winner.source_path),
+                        );
+                        continue;
+                    }
+                    Err(e) => return Err(e),
+                };
+
+                // Write to output (entry paths may come from zips or the frontend)
+                let output_file_path = zip::safe_join(output_path, &winner.source_path)?;
+                fs::create_dir_all(output_file_path.parent().unwrap())?;
+                match journal {
+                    Some(journal) => {
+                        build_journal::write_staged_file(&output_file_path, &content)?;
+                        journal.record_file(&winner.source_path);
+                    }
+                    None => file_retry::write_with_retry(&output_file_path, &content)?,
+                }
+                report(
+                    copied.fetch_add(1, Ordering::Relaxed) + 1,
+                    Some(&winner.source_path),
+                );
+            }
 
             Ok(())
-        })?;
+        })
+    })?;
 
-    println!("[build_weaver_nest] Successfully copied all files");
+    info!("Successfully copied all files");
     Ok(())
 }
 
-/// Create pack.mcmeta file
-fn create_pack_mcmeta(output_path: &Path) -> Result<()> {
-    let pack_mcmeta = r#"{
-  "pack": {
-    "pack_format": 48,
-    "description": "Weaverbird - Optimized Resource Pack"
-  }
-}
-"#;
+/// Read the texture a build would pick for an asset
+///
+/// Same resolution as a build: disabled packs and their overrides are left
+/// out, an override wins, then the first pack in priority order providing
+/// the texture, restricted to the namespace's owner if it has one.
+///
+/// # Returns
+/// Winning pack ID and the file contents, or None if no pack has the texture
+pub fn read_winning_texture(
+    asset_id: &str,
+    packs: &[PackMeta],
+    pack_order: &[String],
+    overrides: &HashMap<String, OverrideSelection>,
+    rules: &WinnerRules,
+) -> Option<(String, Vec<u8>)> {
+    let file = animation::texture_path_for_asset(asset_id);
+    let read = |pack_id: &str, path: &str| {
+        let pack = packs.iter().find(|p| p.id == pack_id)?;
+        animation::read_pack_bytes(&pack.path, pack.is_zip, path).ok()
+    };
 
-    let mcmeta_path = output_path.join("pack.mcmeta");
-    fs::write(mcmeta_path, pack_mcmeta)?;
+    let selection = overrides
+        .get(asset_id)
+        .filter(|s| !rules.disabled_packs.contains(&s.pack_id));
+    if let Some(selection) = selection {
+        let path = selection.variant_path.as_deref().unwrap_or(&file);
+        if let Some(bytes) = read(&selection.pack_id, path) {
+            return Some((selection.pack_id.clone(), bytes));
+        }
+    }
 
-    Ok(())
+    let enabled = projects::enabled_pack_order(pack_order, &rules.disabled_packs);
+    let candidates = HashMap::from([(asset_id.to_string(), enabled)]);
+    let allowed =
+        namespace_conflicts::restrict_namespace_providers(&candidates, &rules.namespace_owners);
+    allowed[asset_id]
+        .iter()
+        .find_map(|pack_id| read(pack_id, &file).map(|bytes| (pack_id.clone(), bytes)))
 }
 
 #[cfg(test)]
@@ -142,6 +338,259 @@ mod tests {
 
     #[test]
     fn test_create_pack_mcmeta() {
-        // Placeholder test
+        let temp_dir = std::env::temp_dir().join("test_weaver_nest_mcmeta");
+        fs::remove_dir_all(&temp_dir).ok();
+        let pack_dir = temp_dir.join("pack");
+        fs::create_dir_all(&pack_dir).expect("Failed to create test directory");
+        fs::write(pack_dir.join("pack.png"), "icon").expect("Failed to write file");
+        let packs = vec![PackMeta {
+            id: "pack".to_string(),
+            name: "Faithful".to_string(),
+            path: pack_dir.to_string_lossy().to_string(),
+            pack_format: Some(34),
+            ..Default::default()
+        }];
+
+        let output = temp_dir.join("out");
+        let result = build_weaver_nest(
+            &packs,
+            &[],
+            &HashMap::new(),
+            &["pack".to_string()],
+            &HashMap::new(),
+            &output.to_string_lossy(),
+        );
+        let mcmeta = fs::read_to_string(output.join("pack.mcmeta")).unwrap_or_default();
+        let icon = fs::read_to_string(output.join("pack.png")).ok();
+
+        // Clean up
+        fs::remove_dir_all(&temp_dir).ok();
+
+        assert!(result.is_ok());
+        let mcmeta: serde_json::Value = serde_json::from_str(&mcmeta).expect("valid mcmeta");
+        assert_eq!(mcmeta["pack"]["pack_format"], 34);
+        assert_eq!(
+            mcmeta["pack"]["description"],
+            "Weaverbird merge of Faithful"
+        );
+        assert_eq!(icon.as_deref(), Some("icon"));
+    }
+
+    #[test]
+    fn test_build_copies_whole_cem_group_from_winner() {
+        let temp_dir = std::env::temp_dir().join("test_weaver_nest_cem");
+        let cem = "assets/minecraft/optifine/cem";
+        let files: &[(&str, &[&str])] = &[
+            ("pack_a", &["cow.jem", "cow2.jem"]),
+            ("pack_b", &["cow.jem", "cow.properties"]),
+        ];
+        let mut packs = Vec::new();
+        for (pack_id, names) in files {
+            let dir = temp_dir.join(pack_id).join(cem);
+            fs::create_dir_all(&dir).expect("Failed to create test directory");
+            for name in *names {
+                fs::write(dir.join(name), *pack_id).expect("Failed to write file");
+            }
+            packs.push(PackMeta {
+                id: pack_id.to_string(),
+                name: pack_id.to_string(),
+                path: temp_dir.join(pack_id).to_string_lossy().to_string(),
+                ..Default::default()
+            });
+        }
+
+        let (assets, providers) =
+            asset_indexer::index_assets(&packs).expect("indexing should succeed");
+        let output = temp_dir.join("out");
+        let result = build_weaver_nest(
+            &packs,
+            &assets,
+            &providers,
+            &["pack_b".to_string(), "pack_a".to_string()],
+            &HashMap::new(),
+            &output.to_string_lossy(),
+        );
+        let out_cem = output.join(cem);
+        let jem = fs::read_to_string(out_cem.join("cow.jem")).ok();
+        let properties = out_cem.join("cow.properties").exists();
+        let mixed_in = out_cem.join("cow2.jem").exists();
+
+        // Clean up
+        fs::remove_dir_all(&temp_dir).ok();
+
+        assert!(result.is_ok());
+        assert_eq!(assets.len(), 1);
+        assert_eq!(jem.as_deref(), Some("pack_b"));
+        assert!(properties);
+        assert!(!mixed_in);
+    }
+
+    #[test]
+    fn test_build_takes_mcmeta_from_texture_pack() {
+        let temp_dir = std::env::temp_dir().join("test_weaver_nest_texture_mcmeta");
+        fs::remove_dir_all(&temp_dir).ok();
+        let block = "assets/minecraft/textures/block";
+        let files: &[(&str, &[&str])] = &[
+            ("pack_a", &["lava.png", "lava.png.mcmeta", "fire.png"]),
+            (
+                "pack_b",
+                &["lava.png", "lava.png.mcmeta", "fire.png", "fire.png.mcmeta"],
+            ),
+        ];
+        let mut packs = Vec::new();
+        for (pack_id, names) in files {
+            let dir = temp_dir.join(pack_id).join(block);
+            fs::create_dir_all(&dir).expect("Failed to create test directory");
+            for name in *names {
+                fs::write(dir.join(name), *pack_id).expect("Failed to write file");
+            }
+            packs.push(PackMeta {
+                id: pack_id.to_string(),
+                name: pack_id.to_string(),
+                path: temp_dir.join(pack_id).to_string_lossy().to_string(),
+                ..Default::default()
+            });
+        }
+
+        let (assets, providers) =
+            asset_indexer::index_assets(&packs).expect("indexing should succeed");
+        let output = temp_dir.join("out");
+        let result = build_weaver_nest(
+            &packs,
+            &assets,
+            &providers,
+            &["pack_a".to_string(), "pack_b".to_string()],
+            &HashMap::new(),
+            &output.to_string_lossy(),
+        );
+        let out_block = output.join(block);
+        let lava_mcmeta = fs::read_to_string(out_block.join("lava.png.mcmeta")).ok();
+        let fire = fs::read_to_string(out_block.join("fire.png")).ok();
+        let fire_mcmeta = out_block.join("fire.png.mcmeta").exists();
+
+        // Clean up
+        fs::remove_dir_all(&temp_dir).ok();
+
+        assert!(result.is_ok());
+        assert_eq!(lava_mcmeta.as_deref(), Some("pack_a"));
+        assert_eq!(fire.as_deref(), Some("pack_a"));
+        // pack_a's fire has no .mcmeta; pack_b's must not be paired with it
+        assert!(!fire_mcmeta);
+    }
+
+    #[test]
+    fn test_resumed_build_skips_staged_files() {
+        let temp_dir = std::env::temp_dir().join("test_weaver_nest_resume");
+        let pack_dir = temp_dir.join("pack");
+        let textures = pack_dir.join("assets/minecraft/textures/block");
+        fs::create_dir_all(&textures).expect("Failed to create test directory");
+        fs::write(textures.join("stone.png"), "pack stone").expect("Failed to write file");
+        fs::write(textures.join("dirt.png"), "pack dirt").expect("Failed to write file");
+        let packs = vec![PackMeta {
+            id: "pack".to_string(),
+            name: "pack".to_string(),
+            path: pack_dir.to_string_lossy().to_string(),
+            ..Default::default()
+        }];
+
+        // An interrupted run already staged stone.png
+        let staging = temp_dir.join("staging");
+        let staged_textures = staging.join("assets/minecraft/textures/block");
+        fs::create_dir_all(&staged_textures).expect("Failed to create test directory");
+        fs::write(staged_textures.join("stone.png"), "staged stone").expect("Failed to write file");
+
+        let (assets, providers) =
+            asset_indexer::index_assets(&packs).expect("indexing should succeed");
+        let journal = JournalWriter::start(
+            &temp_dir.join("journals"),
+            &temp_dir.join("out").to_string_lossy(),
+            &staging,
+            serde_json::Value::Null,
+        )
+        .expect("should start journal");
+        let result = build_weaver_nest_journaled(
+            &packs,
+            &assets,
+            &providers,
+            &["pack".to_string()],
+            &HashMap::new(),
+            &staging.to_string_lossy(),
+            Some(&journal),
+            true,
+            None,
+            None,
+        );
+        let written = journal.files_written();
+        let stone = fs::read_to_string(staged_textures.join("stone.png")).ok();
+        let dirt = fs::read_to_string(staged_textures.join("dirt.png")).ok();
+        journal.complete().expect("should complete");
+
+        // Clean up
+        fs::remove_dir_all(&temp_dir).ok();
+
+        assert!(result.is_ok());
+        assert_eq!(written, 1);
+        assert_eq!(stone.as_deref(), Some("staged stone"));
+        assert_eq!(dirt.as_deref(), Some("pack dirt"));
+    }
+
+    #[test]
+    fn test_read_winning_texture_follows_build_rules() {
+        let temp_dir = std::env::temp_dir().join("test_weaver_nest_winning_texture");
+        fs::remove_dir_all(&temp_dir).ok();
+        let mut packs = Vec::new();
+        for pack_id in ["high", "low", "owner"] {
+            let dir = temp_dir
+                .join(pack_id)
+                .join("assets/minecraft/textures/block");
+            fs::create_dir_all(&dir).expect("Failed to create test directory");
+            fs::write(dir.join("stone.png"), pack_id).expect("Failed to write file");
+            packs.push(PackMeta {
+                id: pack_id.to_string(),
+                name: pack_id.to_string(),
+                path: temp_dir.join(pack_id).to_string_lossy().to_string(),
+                ..Default::default()
+            });
+        }
+        let order: Vec<String> = ["high", "low", "owner"]
+            .iter()
+            .map(|s| s.to_string())
+            .collect();
+        let overrides = HashMap::from([(
+            "minecraft:block/stone".to_string(),
+            OverrideSelection {
+                pack_id: "low".to_string(),
+                variant_path: None,
+            },
+        )]);
+        let winner = |overrides: &HashMap<String, OverrideSelection>, rules: &WinnerRules| {
+            read_winning_texture("minecraft:block/stone", &packs, &order, overrides, rules)
+                .map(|(pack_id, _)| pack_id)
+        };
+
+        let by_order = winner(&HashMap::new(), &WinnerRules::default());
+        let overridden = winner(&overrides, &WinnerRules::default());
+        let disabled = winner(
+            &overrides,
+            &WinnerRules {
+                disabled_packs: vec!["low".to_string(), "high".to_string()],
+                ..Default::default()
+            },
+        );
+        let owned = winner(
+            &HashMap::new(),
+            &WinnerRules {
+                namespace_owners: HashMap::from([("minecraft".to_string(), "owner".to_string())]),
+                ..Default::default()
+            },
+        );
+
+        // Clean up
+        fs::remove_dir_all(&temp_dir).ok();
+
+        assert_eq!(by_order.as_deref(), Some("high"));
+        assert_eq!(overridden.as_deref(), Some("low"));
+        assert_eq!(disabled.as_deref(), Some("owner"));
+        assert_eq!(owned.as_deref(), Some("owner"));
     }
 }
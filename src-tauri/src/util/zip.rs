@@ -1,26 +1,196 @@
 //! Zip file utilities for indexing and extracting pack entries
 
-use anyhow::{anyhow, Result};
-use std::fs::File;
-use std::io::Read;
-use std::path::Path;
+use crate::util::{file_retry, path_policy};
+use anyhow::{anyhow, Context, Result};
+use once_cell::sync::Lazy;
+use std::collections::HashMap;
+use std::fmt;
+use std::fs::{self, File};
+use std::io::{self, Cursor, Read, Seek};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::SystemTime;
+use tracing::{debug, warn};
+use zip::result::ZipError;
 use zip::ZipArchive;
 
+/// Files processed between progress reports
+pub const PROGRESS_INTERVAL: usize = 100;
+
+/// Total size of the archives kept in the pool
+const ARCHIVE_POOL_MAX_BYTES: u64 = 512 * 1024 * 1024;
+
+/// Archives larger than this are read from disk on every call instead
+const POOLED_ARCHIVE_MAX_BYTES: u64 = 256 * 1024 * 1024;
+
+/// An entry isn't in the archive
+#[derive(Debug, Clone)]
+pub struct MissingEntryError {
+    pub entry_path: String,
+}
+
+impl fmt::Display for MissingEntryError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Entry not found in zip: {}", self.entry_path)
+    }
+}
+
+impl std::error::Error for MissingEntryError {}
+
+/// Whether reading a pack file failed only because it isn't there (a
+/// missing zip entry or file), rather than because it couldn't be read
+pub fn is_missing_file(error: &anyhow::Error) -> bool {
+    error.chain().any(|cause| {
+        cause.is::<MissingEntryError>()
+            || cause
+                .downcast_ref::<io::Error>()
+                .map_or(false, |e| e.kind() == io::ErrorKind::NotFound)
+    })
+}
+
+fn entry_error(entry_path: &str, error: ZipError) -> anyhow::Error {
+    match error {
+        ZipError::FileNotFound => MissingEntryError {
+            entry_path: entry_path.to_string(),
+        }
+        .into(),
+        e => anyhow!("Failed to open zip entry {}: {}", entry_path, e),
+    }
+}
+
+/// A ZIP held in memory; clones share the bytes and the parsed central
+/// directory, so every thread can read its own copy
+pub type MemoryArchive = ZipArchive<Cursor<Arc<[u8]>>>;
+
+/// A pooled archive with the file size and mtime it was read at
+struct PooledArchive {
+    archive: MemoryArchive,
+    len: u64,
+    modified: Option<SystemTime>,
+    last_used: u64,
+}
+
+/// Parsed archives by path, evicted least recently used first
+#[derive(Default)]
+struct ArchivePool {
+    archives: HashMap<PathBuf, PooledArchive>,
+    bytes: u64,
+    clock: u64,
+}
+
+impl ArchivePool {
+    fn insert(&mut self, path: PathBuf, mut entry: PooledArchive) {
+        if let Some(old) = self.archives.remove(&path) {
+            self.bytes -= old.len;
+        }
+        while self.bytes + entry.len > ARCHIVE_POOL_MAX_BYTES {
+            let oldest = self
+                .archives
+                .iter()
+                .min_by_key(|(_, a)| a.last_used)
+                .map(|(path, _)| path.clone());
+            match oldest.and_then(|path| self.archives.remove(&path)) {
+                Some(evicted) => self.bytes -= evicted.len,
+                None => break,
+            }
+        }
+        self.clock += 1;
+        entry.last_used = self.clock;
+        self.bytes += entry.len;
+        self.archives.insert(path, entry);
+    }
+}
+
+/// Archives opened by `pooled_archive`
+static ARCHIVE_POOL: Lazy<Mutex<ArchivePool>> = Lazy::new(|| Mutex::new(ArchivePool::default()));
+
+/// Get a ZIP from the archive pool, reading it into memory on first use
+///
+/// Pack ZIPs are read entry by entry from many places (indexing,
+/// animations, builds), and opening one means parsing its whole central
+/// directory again. The pool parses each archive once and hands out cheap
+/// clones. An archive whose size or mtime changed on disk is read again.
+///
+/// # Returns
+/// None if the archive is too large to pool; read it from disk instead
+pub fn pooled_archive(zip_path: &str) -> Result<Option<MemoryArchive>> {
+    let path = Path::new(zip_path);
+    path_policy::check(path)?;
+    let metadata =
+        fs::metadata(path).map_err(|e| anyhow!("Failed to open zip {}: {}", zip_path, e))?;
+    let (len, modified) = (metadata.len(), metadata.modified().ok());
+    if len > POOLED_ARCHIVE_MAX_BYTES {
+        return Ok(None);
+    }
+
+    if let Ok(mut pool) = ARCHIVE_POOL.lock() {
+        pool.clock += 1;
+        let clock = pool.clock;
+        if let Some(entry) = pool.archives.get_mut(path) {
+            if entry.len == len && entry.modified == modified {
+                entry.last_used = clock;
+                return Ok(Some(entry.archive.clone()));
+            }
+        }
+    }
+
+    // Read outside the lock so other archives stay available meanwhile
+    let bytes: Arc<[u8]> = fs::read(path)
+        .map_err(|e| anyhow!("Failed to open zip {}: {}", zip_path, e))?
+        .into();
+    let archive = ZipArchive::new(Cursor::new(bytes))
+        .map_err(|e| anyhow!("Failed to read zip {}: {}", zip_path, e))?;
+    if let Ok(mut pool) = ARCHIVE_POOL.lock() {
+        pool.insert(
+            path.to_path_buf(),
+            PooledArchive {
+                archive: archive.clone(),
+                len,
+                modified,
+                last_used: 0,
+            },
+        );
+    }
+    Ok(Some(archive))
+}
+
+/// Drop an archive from the pool, e.g. after rewriting it
+pub fn release_archive(zip_path: &Path) {
+    if let Ok(mut pool) = ARCHIVE_POOL.lock() {
+        if let Some(old) = pool.archives.remove(zip_path) {
+            pool.bytes -= old.len;
+        }
+    }
+}
+
+/// Open a zip on disk, if the path policy allows it
+fn open_zip(zip_path: &str) -> Result<File> {
+    path_policy::check(Path::new(zip_path))?;
+    File::open(zip_path).map_err(|e| anyhow!("Failed to open zip {}: {}", zip_path, e))
+}
+
 /// List all files in a zip archive without extracting
 pub fn list_zip_files(zip_path: &str) -> Result<Vec<String>> {
-    println!("[list_zip_files] Opening ZIP: {}", zip_path);
-    let file =
-        File::open(zip_path).map_err(|e| anyhow!("Failed to open zip {}: {}", zip_path, e))?;
-    let mut archive =
-        ZipArchive::new(file).map_err(|e| anyhow!("Failed to read zip {}: {}", zip_path, e))?;
+    debug!("Opening ZIP: {}", zip_path);
+    match pooled_archive(zip_path)? {
+        Some(mut archive) => list_archive_files(&mut archive),
+        None => {
+            let file = open_zip(zip_path)?;
+            let mut archive = ZipArchive::new(file)
+                .map_err(|e| anyhow!("Failed to read zip {}: {}", zip_path, e))?;
+            list_archive_files(&mut archive)
+        }
+    }
+}
 
+fn list_archive_files<R: Read + Seek>(archive: &mut ZipArchive<R>) -> Result<Vec<String>> {
     let archive_len = archive.len();
-    println!("[list_zip_files] ZIP contains {} entries", archive_len);
+    debug!("ZIP contains {} entries", archive_len);
     let mut files = Vec::new();
 
     for i in 0..archive_len {
         if i % 5000 == 0 && i > 0 {
-            println!("[list_zip_files] Processed {}/{} entries", i, archive_len);
+            debug!("Processed {}/{} entries", i, archive_len);
         }
         let file = archive
             .by_index(i)
@@ -29,23 +199,137 @@ pub fn list_zip_files(zip_path: &str) -> Result<Vec<String>> {
             files.push(file.name().to_string());
         }
     }
-    println!(
-        "[list_zip_files] Found {} files (excluding directories)",
-        files.len()
-    );
+    debug!("Found {} files (excluding directories)", files.len());
 
     Ok(files)
 }
 
+/// Uncompressed size of every file in a zip archive, keyed by path
+///
+/// Sizes come from the central directory, so nothing is decompressed.
+pub fn list_zip_file_sizes(zip_path: &str) -> Result<HashMap<String, u64>> {
+    match pooled_archive(zip_path)? {
+        Some(mut archive) => archive_file_sizes(&mut archive),
+        None => {
+            let file = open_zip(zip_path)?;
+            let mut archive = ZipArchive::new(file)
+                .map_err(|e| anyhow!("Failed to read zip {}: {}", zip_path, e))?;
+            archive_file_sizes(&mut archive)
+        }
+    }
+}
+
+fn archive_file_sizes<R: Read + Seek>(archive: &mut ZipArchive<R>) -> Result<HashMap<String, u64>> {
+    let mut sizes = HashMap::with_capacity(archive.len());
+    for i in 0..archive.len() {
+        let file = archive
+            .by_index(i)
+            .map_err(|e| anyhow!("Failed to read zip entry {}: {}", i, e))?;
+        if !file.is_dir() {
+            sizes.insert(file.name().to_string(), file.size());
+        }
+    }
+    Ok(sizes)
+}
+
 /// Extract a specific file from a zip to bytes
+///
+/// The archive comes from the archive pool, so repeated reads from the
+/// same pack don't reopen it.
 pub fn extract_zip_entry(zip_path: &str, entry_path: &str) -> Result<Vec<u8>> {
-    let file =
-        File::open(zip_path).map_err(|e| anyhow!("Failed to open zip {}: {}", zip_path, e))?;
-    let mut archive = ZipArchive::new(file).map_err(|e| anyhow!("Failed to read zip: {}", e))?;
+    match pooled_archive(zip_path)? {
+        Some(mut archive) => read_archive_entry(&mut archive, entry_path),
+        None => {
+            let file = open_zip(zip_path)?;
+            let mut archive =
+                ZipArchive::new(file).map_err(|e| anyhow!("Failed to read zip: {}", e))?;
+            read_archive_entry(&mut archive, entry_path)
+        }
+    }
+}
+
+/// Read at most `limit` bytes from the start of a zip entry
+///
+/// Only the beginning of the entry is decompressed, which is all a file
+/// header needs.
+pub fn read_zip_entry_head(zip_path: &str, entry_path: &str, limit: usize) -> Result<Vec<u8>> {
+    match pooled_archive(zip_path)? {
+        Some(mut archive) => read_archive_entry_head(&mut archive, entry_path, limit),
+        None => {
+            let file = open_zip(zip_path)?;
+            let mut archive =
+                ZipArchive::new(file).map_err(|e| anyhow!("Failed to read zip: {}", e))?;
+            read_archive_entry_head(&mut archive, entry_path, limit)
+        }
+    }
+}
+
+fn read_archive_entry_head<R: Read + Seek>(
+    archive: &mut ZipArchive<R>,
+    entry_path: &str,
+    limit: usize,
+) -> Result<Vec<u8>> {
+    let file = archive
+        .by_name(entry_path)
+        .map_err(|e| entry_error(entry_path, e))?;
 
+    let mut buffer = Vec::with_capacity(limit);
+    file.take(limit as u64)
+        .read_to_end(&mut buffer)
+        .map_err(|e| anyhow!("Failed to read zip entry: {}", e))?;
+
+    Ok(buffer)
+}
+
+/// Extract many files from a zip to bytes, opening the archive once
+///
+/// Entries that are missing or can't be read are left out of the result
+/// (and logged), so callers can treat them like a missing file.
+///
+/// # Returns
+/// Entry path -> contents
+pub fn extract_zip_entries<S: AsRef<str>>(
+    zip_path: &str,
+    entry_paths: &[S],
+) -> Result<HashMap<String, Vec<u8>>> {
+    match pooled_archive(zip_path)? {
+        Some(mut archive) => Ok(read_archive_entries(&mut archive, entry_paths)),
+        None => {
+            let file = open_zip(zip_path)?;
+            let mut archive =
+                ZipArchive::new(file).map_err(|e| anyhow!("Failed to read zip: {}", e))?;
+            Ok(read_archive_entries(&mut archive, entry_paths))
+        }
+    }
+}
+
+fn read_archive_entries<R: Read + Seek, S: AsRef<str>>(
+    archive: &mut ZipArchive<R>,
+    entry_paths: &[S],
+) -> HashMap<String, Vec<u8>> {
+    entry_paths
+        .iter()
+        .filter_map(|path| {
+            let path = path.as_ref();
+            match read_archive_entry(archive, path) {
+                Ok(bytes) => Some((path.to_string(), bytes)),
+                Err(e) => {
+                    warn!("Skipping {}: {}", path, e);
+                    None
+                }
+            }
+        })
+        .collect()
+}
+
+/// Read one entry of an open archive to bytes
+pub fn read_archive_entry<R: Read + Seek>(
+    archive: &mut ZipArchive<R>,
+    entry_path: &str,
+) -> Result<Vec<u8>> {
     let mut file = archive
         .by_name(entry_path)
-        .map_err(|e| anyhow!("Entry not found in zip: {}", e))?;
+        .map_err(|e| entry_error(entry_path, e))?;
 
     let mut buffer = Vec::new();
     file.read_to_end(&mut buffer)
@@ -54,6 +338,138 @@ pub fn extract_zip_entry(zip_path: &str, entry_path: &str) -> Result<Vec<u8>> {
     Ok(buffer)
 }
 
+/// Validate a zip entry name and convert it to a safe relative path
+///
+/// Rejects entries that could escape the extraction directory ("zip-slip"):
+/// parent directory components, absolute paths, Windows drive prefixes and
+/// NUL bytes. Both `/` and `\\` are treated as separators.
+pub fn sanitize_entry_path(name: &str) -> Result<PathBuf> {
+    if name.contains('\0') {
+        return Err(anyhow!("Zip entry name contains a NUL byte: {:?}", name));
+    }
+    if name.starts_with('/') || name.starts_with('\\') {
+        return Err(anyhow!("Zip entry has an absolute path: {}", name));
+    }
+    if name.len() >= 2 && name.as_bytes()[1] == b':' {
+        return Err(anyhow!("Zip entry has a drive prefix: {}", name));
+    }
+
+    let mut path = PathBuf::new();
+    for part in name.split(['/', '\\']) {
+        match part {
+            "" | "." => continue,
+            ".." => return Err(anyhow!("Zip entry escapes its directory: {}", name)),
+            _ if part.contains(':') => {
+                return Err(anyhow!("Zip entry has an invalid component: {}", name))
+            }
+            _ => path.push(part),
+        }
+    }
+
+    if path.as_os_str().is_empty() {
+        return Err(anyhow!("Zip entry has an empty path: {:?}", name));
+    }
+
+    Ok(path)
+}
+
+/// Join a zip entry name onto a destination directory, rejecting unsafe names
+pub fn safe_join(dest: &Path, entry_name: &str) -> Result<PathBuf> {
+    Ok(dest.join(sanitize_entry_path(entry_name)?))
+}
+
+/// Extract every file in a zip into a directory
+///
+/// Entry names are validated before anything is written, so a malicious
+/// archive is rejected as a whole rather than partially extracted.
+///
+/// # Returns
+/// Number of files extracted
+pub fn extract_zip_to_dir(zip_path: &str, dest: &Path) -> Result<usize> {
+    extract_zip_to_dir_with_progress(zip_path, dest, None)
+}
+
+/// Extract every file in a zip into a directory, reporting progress
+///
+/// Like `extract_zip_to_dir`; `progress` is called with (files written,
+/// total files) every 100 files and once at the end.
+pub fn extract_zip_to_dir_with_progress(
+    zip_path: &str,
+    dest: &Path,
+    progress: Option<&dyn Fn(usize, usize)>,
+) -> Result<usize> {
+    let file = open_zip(zip_path)?;
+    let mut archive = ZipArchive::new(file).map_err(|e| anyhow!("Failed to read zip: {}", e))?;
+
+    // Validate every entry first
+    let mut entries = Vec::with_capacity(archive.len());
+    for i in 0..archive.len() {
+        let entry = archive
+            .by_index(i)
+            .map_err(|e| anyhow!("Failed to read zip entry {}: {}", i, e))?;
+        if entry.is_dir() {
+            continue;
+        }
+        entries.push((i, sanitize_entry_path(entry.name())?));
+    }
+
+    for (written, (index, rel_path)) in entries.iter().enumerate() {
+        let mut entry = archive
+            .by_index(*index)
+            .map_err(|e| anyhow!("Failed to read zip entry {}: {}", index, e))?;
+        let output_path = dest.join(rel_path);
+        if let Some(parent) = output_path.parent() {
+            fs::create_dir_all(parent).context("Failed to create directory")?;
+        }
+        let mut output_file = file_retry::create_file_with_retry(&output_path)?;
+        std::io::copy(&mut entry, &mut output_file).context("Failed to write file")?;
+
+        if let Some(progress) = progress {
+            if (written + 1) % PROGRESS_INTERVAL == 0 || written + 1 == entries.len() {
+                progress(written + 1, entries.len());
+            }
+        }
+    }
+
+    Ok(entries.len())
+}
+
+/// Extract a single file from a zip to a destination file
+///
+/// If `dest` is an existing directory the file keeps its name inside it.
+/// The entry is streamed to disk, so large files are not held in memory.
+///
+/// # Returns
+/// Path of the written file
+pub fn extract_zip_entry_to(zip_path: &str, entry_path: &str, dest: &Path) -> Result<PathBuf> {
+    let rel_path = sanitize_entry_path(entry_path)?;
+    let file = open_zip(zip_path)?;
+    let mut archive = ZipArchive::new(file).map_err(|e| anyhow!("Failed to read zip: {}", e))?;
+
+    let mut entry = archive
+        .by_name(entry_path)
+        .map_err(|e| entry_error(entry_path, e))?;
+    if entry.is_dir() {
+        return Err(anyhow!("{} is a folder, not a file", entry_path));
+    }
+
+    let output_path = if dest.is_dir() {
+        match rel_path.file_name() {
+            Some(name) => dest.join(name),
+            None => return Err(anyhow!("Zip entry has no file name: {}", entry_path)),
+        }
+    } else {
+        dest.to_path_buf()
+    };
+    if let Some(parent) = output_path.parent() {
+        fs::create_dir_all(parent).context("Failed to create directory")?;
+    }
+    let mut output_file = file_retry::create_file_with_retry(&output_path)?;
+    std::io::copy(&mut entry, &mut output_file).context("Failed to write file")?;
+
+    Ok(output_path)
+}
+
 /// Get size of a zip file
 pub fn get_zip_size(zip_path: &str) -> Result<u64> {
     let path = Path::new(zip_path);
@@ -66,9 +482,221 @@ pub fn get_zip_size(zip_path: &str) -> Result<u64> {
 mod tests {
     use super::*;
 
+    use std::io::Write;
+    use zip::write::FileOptions;
+
+    fn write_test_zip(path: &Path, entries: &[&str]) {
+        let file = File::create(path).expect("Failed to create test zip");
+        let mut writer = zip::ZipWriter::new(file);
+        for name in entries {
+            writer
+                .start_file(*name, FileOptions::default())
+                .expect("Failed to start zip entry");
+            writer
+                .write_all(b"data")
+                .expect("Failed to write zip entry");
+        }
+        writer.finish().expect("Failed to finish test zip");
+    }
+
+    #[test]
+    fn test_extract_zip_entries() {
+        let temp_dir = std::env::temp_dir().join("test_zip_extract_entries");
+        fs::remove_dir_all(&temp_dir).ok();
+        fs::create_dir_all(&temp_dir).unwrap();
+        let zip_path = temp_dir.join("pack.zip");
+        write_test_zip(&zip_path, &["a.txt", "b/c.txt", "d.txt"]);
+
+        let entries = extract_zip_entries(
+            &zip_path.to_string_lossy(),
+            &["a.txt", "b/c.txt", "missing.txt"],
+        );
+        let missing = extract_zip_entry(&zip_path.to_string_lossy(), "missing.txt");
+        release_archive(&zip_path);
+
+        // Clean up
+        fs::remove_dir_all(&temp_dir).ok();
+
+        let entries = entries.expect("zip should open");
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries["b/c.txt"], b"data");
+        assert!(!entries.contains_key("missing.txt"));
+        assert!(is_missing_file(&missing.unwrap_err()));
+    }
+
+    #[test]
+    fn test_pooled_archive_reloads_changed_zip() {
+        let temp_dir = std::env::temp_dir().join("test_zip_archive_pool");
+        fs::remove_dir_all(&temp_dir).ok();
+        fs::create_dir_all(&temp_dir).unwrap();
+        let zip_path = temp_dir.join("pack.zip");
+        let zip_str = zip_path.to_string_lossy().to_string();
+        write_test_zip(&zip_path, &["a.txt"]);
+
+        let first = extract_zip_entry(&zip_str, "a.txt").ok();
+        let pooled = pooled_archive(&zip_str).ok().flatten().map(|a| a.len());
+        write_test_zip(&zip_path, &["a.txt", "b/c.txt"]);
+        release_archive(&zip_path);
+        let rewritten = list_zip_files(&zip_str).unwrap_or_default();
+
+        // Clean up
+        fs::remove_dir_all(&temp_dir).ok();
+
+        assert_eq!(first.as_deref(), Some(&b"data"[..]));
+        assert_eq!(pooled, Some(1));
+        assert_eq!(rewritten, vec!["a.txt", "b/c.txt"]);
+    }
+
+    /// Compare pooled and unpooled entry reads on a pack-sized ZIP
+    ///
+    /// Run with `cargo test --release bench_extract_zip_entry -- --ignored --nocapture`
+    #[test]
+    #[ignore]
+    fn bench_extract_zip_entry() {
+        let temp_dir = std::env::temp_dir().join("bench_zip_archive_pool");
+        fs::remove_dir_all(&temp_dir).ok();
+        fs::create_dir_all(&temp_dir).unwrap();
+        let zip_path = temp_dir.join("pack.zip");
+        let zip_str = zip_path.to_string_lossy().to_string();
+        let names: Vec<String> = (0..1000)
+            .map(|i| format!("assets/minecraft/textures/block/texture_{}.png", i))
+            .collect();
+        let name_refs: Vec<&str> = names.iter().map(String::as_str).collect();
+        write_test_zip(&zip_path, &name_refs);
+
+        let start = std::time::Instant::now();
+        for name in &names {
+            let file = File::open(&zip_path).unwrap();
+            let mut archive = ZipArchive::new(file).unwrap();
+            read_archive_entry(&mut archive, name).unwrap();
+        }
+        let unpooled = start.elapsed();
+
+        let start = std::time::Instant::now();
+        for name in &names {
+            extract_zip_entry(&zip_str, name).unwrap();
+        }
+        let pooled = start.elapsed();
+        release_archive(&zip_path);
+
+        // Clean up
+        fs::remove_dir_all(&temp_dir).ok();
+
+        println!(
+            "[bench_extract_zip_entry] {} entries: reopening {:?}, pooled {:?}",
+            names.len(),
+            unpooled,
+            pooled
+        );
+        assert!(pooled < unpooled);
+    }
+
     #[test]
     fn test_list_zip_files() {
         // This test requires a test zip file
         // Skipping for now
     }
+
+    #[test]
+    fn test_sanitize_entry_path_accepts_normal_paths() {
+        assert_eq!(
+            sanitize_entry_path("assets/minecraft/textures/block/stone.png").unwrap(),
+            Path::new("assets/minecraft/textures/block/stone.png")
+        );
+        assert_eq!(
+            sanitize_entry_path("./assets//minecraft\\pack.png").unwrap(),
+            Path::new("assets/minecraft/pack.png")
+        );
+    }
+
+    #[test]
+    fn test_sanitize_entry_path_rejects_malicious_paths() {
+        let malicious = [
+            "../evil.png",
+            "assets/../../evil.png",
+            "assets\\..\\..\\evil.png",
+            "/etc/passwd",
+            "\\windows\\system32",
+            "C:/Windows/evil.dll",
+            "C:evil.dll",
+            "assets/c:/evil.png",
+            "assets/evil\0.png",
+            "",
+            "./",
+        ];
+
+        for name in malicious {
+            assert!(
+                sanitize_entry_path(name).is_err(),
+                "should reject {:?}",
+                name
+            );
+        }
+    }
+
+    #[test]
+    fn test_extract_zip_to_dir_rejects_zip_slip() {
+        let temp_dir = std::env::temp_dir().join("test_zip_slip");
+        let dest = temp_dir.join("out");
+        fs::create_dir_all(&dest).expect("Failed to create test directory");
+        let zip_path = temp_dir.join("evil.zip");
+        write_test_zip(&zip_path, &["pack.mcmeta", "../escaped.txt"]);
+
+        let result = extract_zip_to_dir(&zip_path.to_string_lossy(), &dest);
+        let escaped = temp_dir.join("escaped.txt").exists();
+        let partial = dest.join("pack.mcmeta").exists();
+
+        // Clean up
+        fs::remove_dir_all(&temp_dir).ok();
+
+        assert!(result.is_err());
+        assert!(!escaped);
+        assert!(!partial, "nothing should be extracted from a malicious zip");
+    }
+
+    #[test]
+    fn test_extract_zip_to_dir_extracts_safe_zip() {
+        let temp_dir = std::env::temp_dir().join("test_zip_extract_safe");
+        let dest = temp_dir.join("out");
+        fs::create_dir_all(&dest).expect("Failed to create test directory");
+        let zip_path = temp_dir.join("pack.zip");
+        write_test_zip(
+            &zip_path,
+            &["pack.mcmeta", "assets/minecraft/textures/block/stone.png"],
+        );
+
+        let result = extract_zip_to_dir(&zip_path.to_string_lossy(), &dest);
+        let extracted = dest
+            .join("assets/minecraft/textures/block/stone.png")
+            .exists();
+
+        // Clean up
+        fs::remove_dir_all(&temp_dir).ok();
+
+        assert_eq!(result.unwrap(), 2);
+        assert!(extracted);
+    }
+
+    #[test]
+    fn test_extract_zip_entry_to_file_or_directory() {
+        let temp_dir = std::env::temp_dir().join("test_zip_extract_entry");
+        fs::create_dir_all(&temp_dir).expect("Failed to create test directory");
+        let zip_path = temp_dir.join("pack.zip");
+        let zip_str = zip_path.to_string_lossy().to_string();
+        write_test_zip(&zip_path, &["assets/minecraft/textures/block/stone.png"]);
+
+        let entry = "assets/minecraft/textures/block/stone.png";
+        let to_file = extract_zip_entry_to(&zip_str, entry, &temp_dir.join("copy.png"));
+        let to_dir = extract_zip_entry_to(&zip_str, entry, &temp_dir);
+        let missing = extract_zip_entry_to(&zip_str, "pack.png", &temp_dir);
+        let contents = fs::read(temp_dir.join("stone.png"));
+
+        // Clean up
+        fs::remove_dir_all(&temp_dir).ok();
+
+        assert_eq!(to_file.unwrap(), temp_dir.join("copy.png"));
+        assert_eq!(to_dir.unwrap(), temp_dir.join("stone.png"));
+        assert_eq!(contents.unwrap(), b"data");
+        assert!(missing.is_err());
+    }
 }
@@ -1,4 +1,4 @@
-use crate::error::AppResult;
+use crate::error::{AppResult, Severity, ValidationIssue};
 /**
  * Input validation utilities for Tauri commands
  *
@@ -6,6 +6,7 @@ use crate::error::AppResult;
  * Enables centralized, reusable validation logic.
  */
 use std::path::Path;
+use tracing::warn;
 
 /// Validates a directory path exists and is readable
 pub fn validate_directory(path: &str, label: &str) -> AppResult<()> {
@@ -18,22 +19,33 @@ pub fn validate_directory(path: &str, label: &str) -> AppResult<()> {
 
     let p = Path::new(path);
     if !p.exists() {
-        return Err(crate::error::AppError::io(format!(
-            "{} does not exist: {}",
-            label, path
-        )));
+        return Err(
+            crate::error::AppError::io(format!("{} does not exist: {}", label, path))
+                .with_path(path),
+        );
     }
 
     if !p.is_dir() {
         return Err(crate::error::AppError::validation(format!(
             "{} is not a directory: {}",
             label, path
-        )));
+        ))
+        .with_path(path));
     }
 
     Ok(())
 }
 
+/// Validates a path is inside a location the path policy allows
+pub fn validate_path_access(path: &str, label: &str) -> AppResult<()> {
+    crate::util::path_policy::check_path(path).map_err(|e| {
+        crate::error::AppError::validation(format!("{} is not accessible: {}", label, path))
+            .with_details(e.to_string())
+            .with_hint(crate::error::ErrorHint::GrantFolderAccess)
+            .with_path(path)
+    })
+}
+
 /// Validates that a pack order is not empty
 pub fn validate_pack_order(order: &[String]) -> AppResult<()> {
     if order.is_empty() {
@@ -44,54 +56,245 @@ pub fn validate_pack_order(order: &[String]) -> AppResult<()> {
     Ok(())
 }
 
-/// Validates that all pack IDs in overrides are present in pack order
-pub fn validate_overrides(
+/// Problems with overrides: empty IDs, unknown packs, blank variant paths
+///
+/// Overrides are checked in asset ID order so the list is stable.
+pub fn override_issues(
     overrides: &std::collections::HashMap<String, crate::model::OverrideSelection>,
     pack_order: &[String],
-) -> AppResult<()> {
-    for (asset_id, override_entry) in overrides {
+) -> Vec<ValidationIssue> {
+    let mut entries: Vec<_> = overrides.iter().collect();
+    entries.sort_by(|a, b| a.0.cmp(b.0));
+
+    let mut issues = Vec::new();
+    for (asset_id, override_entry) in entries {
         if asset_id.is_empty() {
-            return Err(crate::error::AppError::validation(
+            issues.push(ValidationIssue::error(crate::error::AppError::validation(
                 "Asset ID in overrides cannot be empty".to_string(),
-            ));
+            )));
+            continue;
         }
         let pack_id = &override_entry.pack_id;
         if pack_id.is_empty() {
-            return Err(crate::error::AppError::validation(format!(
-                "Pack ID for asset {} cannot be empty",
-                asset_id
+            issues.push(ValidationIssue::error(crate::error::AppError::validation(
+                format!("Pack ID for asset {} cannot be empty", asset_id),
             )));
-        }
-        if !pack_order.contains(pack_id) {
-            return Err(crate::error::AppError::validation(format!(
-                "Override references non-existent pack: {}",
-                pack_id
+        } else if !pack_order.contains(pack_id) {
+            issues.push(ValidationIssue::error(crate::error::AppError::validation(
+                format!("Override references non-existent pack: {}", pack_id),
             )));
         }
         if let Some(path) = &override_entry.variant_path {
             if path.trim().is_empty() {
-                return Err(crate::error::AppError::validation(format!(
-                    "Variant path for asset {} cannot be empty",
-                    asset_id
+                issues.push(ValidationIssue::error(crate::error::AppError::validation(
+                    format!("Variant path for asset {} cannot be empty", asset_id),
                 )));
             }
         }
     }
-    Ok(())
+    issues
+}
+
+/// Validates that all pack IDs in overrides are present in pack order
+pub fn validate_overrides(
+    overrides: &std::collections::HashMap<String, crate::model::OverrideSelection>,
+    pack_order: &[String],
+) -> AppResult<()> {
+    into_result(override_issues(overrides, pack_order))
 }
 
-/// Validates build request parameters
+/// Problems overrides have against the indexed assets
+///
+/// An override of an asset no pack provides does nothing, and one that picks
+/// a pack without the asset has no file to copy. Both are reported rather
+/// than left to the build.
+///
+/// # Arguments
+/// * `overrides` - Asset ID -> selected pack
+/// * `providers` - Asset ID -> pack IDs providing it, from the asset index
+pub fn override_index_issues(
+    overrides: &std::collections::HashMap<String, crate::model::OverrideSelection>,
+    providers: &std::collections::HashMap<String, Vec<String>>,
+) -> Vec<ValidationIssue> {
+    let mut entries: Vec<_> = overrides.iter().collect();
+    entries.sort_by(|a, b| a.0.cmp(b.0));
+
+    let mut issues = Vec::new();
+    for (asset_id, override_entry) in entries {
+        match providers.get(asset_id) {
+            None => issues.push(ValidationIssue::error(crate::error::AppError::validation(
+                format!("Override targets an asset no pack provides: {}", asset_id),
+            ))),
+            Some(pack_ids) if !pack_ids.contains(&override_entry.pack_id) => issues.push(
+                ValidationIssue::error(crate::error::AppError::validation(format!(
+                    "Override for {} picks {}, which doesn't provide it (provided by: {})",
+                    asset_id,
+                    override_entry.pack_id,
+                    pack_ids.join(", ")
+                ))),
+            ),
+            Some(_) => {}
+        }
+    }
+    issues
+}
+
+/// Problems with disabled packs: overrides left picking them, and packs
+/// disabled that aren't in the order
+///
+/// Disabled packs stay in the order and index, so overrides picking one are
+/// valid; they are reported as inert rather than rejected.
+///
+/// # Arguments
+/// * `overrides` - Asset ID -> selected pack
+/// * `pack_order` - Pack IDs in priority order, disabled ones included
+/// * `disabled_packs` - Pack IDs left out of builds
+pub fn disabled_pack_issues(
+    overrides: &std::collections::HashMap<String, crate::model::OverrideSelection>,
+    pack_order: &[String],
+    disabled_packs: &[String],
+) -> Vec<ValidationIssue> {
+    let mut issues = Vec::new();
+    for pack_id in disabled_packs {
+        if !pack_order.contains(pack_id) {
+            issues.push(ValidationIssue::warning(format!(
+                "Disabled pack {} isn't in the pack order",
+                pack_id
+            )));
+        }
+    }
+
+    let mut entries: Vec<_> = overrides
+        .iter()
+        .filter(|(_, selection)| disabled_packs.contains(&selection.pack_id))
+        .collect();
+    entries.sort_by(|a, b| a.0.cmp(b.0));
+    for (asset_id, selection) in entries {
+        issues.push(ValidationIssue::warning(format!(
+            "Override for {} picks disabled pack {} and does nothing until it is enabled",
+            asset_id, selection.pack_id
+        )));
+    }
+
+    if !pack_order.is_empty() && pack_order.iter().all(|id| disabled_packs.contains(id)) {
+        issues.push(ValidationIssue::error(crate::error::AppError::validation(
+            "Every pack in the order is disabled",
+        )));
+    }
+    issues
+}
+
+/// Validates that disabled packs leave something to build, logging inert overrides
+pub fn validate_disabled_packs(
+    overrides: &std::collections::HashMap<String, crate::model::OverrideSelection>,
+    pack_order: &[String],
+    disabled_packs: &[String],
+) -> AppResult<()> {
+    into_result(disabled_pack_issues(overrides, pack_order, disabled_packs))
+}
+
+/// Validates that every override names an indexed asset its pack provides
+pub fn validate_overrides_against_index(
+    overrides: &std::collections::HashMap<String, crate::model::OverrideSelection>,
+    providers: &std::collections::HashMap<String, Vec<String>>,
+) -> AppResult<()> {
+    into_result(override_index_issues(overrides, providers))
+}
+
+/// Every problem with a build request, rather than only the first
+pub fn build_request_issues(
+    packs_dir: &str,
+    pack_order: &[String],
+    overrides: &std::collections::HashMap<String, crate::model::OverrideSelection>,
+    output_dir: &str,
+) -> Vec<ValidationIssue> {
+    let mut issues = Vec::new();
+    for result in [
+        validate_directory(packs_dir, "Packs directory"),
+        validate_directory(output_dir, "Output directory"),
+        validate_pack_order(pack_order),
+    ] {
+        if let Err(err) = result {
+            issues.push(ValidationIssue::error(err));
+        }
+    }
+
+    let mut seen = std::collections::HashSet::new();
+    for pack_id in pack_order {
+        if !seen.insert(pack_id) {
+            issues.push(ValidationIssue::warning(format!(
+                "Pack {} appears more than once in the pack order",
+                pack_id
+            )));
+        }
+    }
+
+    issues.extend(override_issues(overrides, pack_order));
+    issues
+}
+
+/// Validates build request parameters, reporting all problems at once
 pub fn validate_build_request(
     packs_dir: &str,
     pack_order: &[String],
     overrides: &std::collections::HashMap<String, crate::model::OverrideSelection>,
     output_dir: &str,
 ) -> AppResult<()> {
-    validate_directory(packs_dir, "Packs directory")?;
-    validate_directory(output_dir, "Output directory")?;
-    validate_pack_order(pack_order)?;
-    validate_overrides(overrides, pack_order)?;
-    Ok(())
+    into_result(build_request_issues(
+        packs_dir, pack_order, overrides, output_dir,
+    ))
+}
+
+/// Warnings for merged packs made for other Minecraft versions than the merge
+///
+/// The merged pack.mcmeta takes the newest format of the merged packs, so
+/// each pack declaring an older format is reported with the versions it
+/// targets. Packs without a format are skipped.
+///
+/// # Arguments
+/// * `packs` - Scanned packs
+/// * `pack_order` - Pack IDs being merged
+pub fn pack_format_issues(
+    packs: &[crate::model::PackMeta],
+    pack_order: &[String],
+) -> Vec<ValidationIssue> {
+    let merged: Vec<&crate::model::PackMeta> = pack_order
+        .iter()
+        .filter_map(|id| packs.iter().find(|p| &p.id == id))
+        .collect();
+    let newest = match merged.iter().filter_map(|p| p.pack_format).max() {
+        Some(format) => format,
+        None => return Vec::new(),
+    };
+    let versions = |format: u32| {
+        crate::util::pack_format::compatible_mc_versions(format)
+            .map(|versions| format!("Minecraft {} (pack format {})", versions, format))
+            .unwrap_or_else(|| format!("pack format {}", format))
+    };
+
+    merged
+        .iter()
+        .filter_map(|pack| {
+            let format = pack.pack_format.filter(|format| *format != newest)?;
+            Some(ValidationIssue::warning(format!(
+                "{} targets {}, but the merge targets {}; some of its textures may not load",
+                pack.name,
+                versions(format),
+                versions(newest)
+            )))
+        })
+        .collect()
+}
+
+/// Fail with every collected issue if any is an error; log warnings otherwise
+fn into_result(issues: Vec<ValidationIssue>) -> AppResult<()> {
+    for issue in issues.iter().filter(|i| i.severity == Severity::Warning) {
+        warn!("{}", issue.message);
+    }
+    match crate::error::AppError::from_issues(issues) {
+        Some(err) => Err(err),
+        None => Ok(()),
+    }
 }
 
 #[cfg(test)]
@@ -99,6 +302,60 @@ mod tests {
     use super::*;
     use std::fs;
 
+    #[test]
+    fn test_disabled_pack_issues() {
+        let order = vec!["a".to_string(), "b".to_string()];
+        let overrides = std::collections::HashMap::from([(
+            "minecraft:block/stone".to_string(),
+            crate::model::OverrideSelection {
+                pack_id: "b".to_string(),
+                variant_path: None,
+            },
+        )]);
+
+        let issues = disabled_pack_issues(&overrides, &order, &["b".to_string()]);
+        assert_eq!(issues.len(), 1);
+        assert_eq!(issues[0].severity, Severity::Warning);
+        assert!(issues[0].message.contains("disabled pack b"));
+        assert!(validate_disabled_packs(&overrides, &order, &["b".to_string()]).is_ok());
+
+        let all_disabled = validate_disabled_packs(&overrides, &order, &order);
+        assert!(all_disabled.is_err());
+    }
+
+    #[test]
+    fn test_pack_format_issues() {
+        let pack = |id: &str, pack_format: Option<u32>| crate::model::PackMeta {
+            id: id.to_string(),
+            name: id.to_string(),
+            path: String::new(),
+            pack_format,
+            ..Default::default()
+        };
+        let packs = vec![
+            pack("old", Some(15)),
+            pack("new", Some(46)),
+            pack("unknown", None),
+            pack("unused", Some(4)),
+        ];
+        let order: Vec<String> = ["new", "old", "unknown"]
+            .iter()
+            .map(|id| id.to_string())
+            .collect();
+
+        let issues = pack_format_issues(&packs, &order);
+
+        assert_eq!(issues.len(), 1);
+        assert_eq!(issues[0].severity, Severity::Warning);
+        assert!(issues[0]
+            .message
+            .starts_with("old targets Minecraft 1.20 - 1.20.1"));
+        assert!(issues[0]
+            .message
+            .contains("Minecraft 1.21.4 (pack format 46)"));
+        assert!(pack_format_issues(&packs, &order[..1]).is_empty());
+    }
+
     #[test]
     fn test_validate_directory_empty_path() {
         let result = validate_directory("", "Test directory");
@@ -352,4 +609,76 @@ mod tests {
         let err = result.unwrap_err();
         assert!(err.message.contains("non-existent pack"));
     }
+
+    #[test]
+    fn test_validate_build_request_collects_all_issues() {
+        let temp_dir = std::env::temp_dir();
+        let output_dir = temp_dir.to_str().unwrap();
+        let pack_order = vec!["pack1".to_string(), "pack1".to_string()];
+
+        let mut overrides = std::collections::HashMap::new();
+        overrides.insert(
+            "minecraft:block/stone".to_string(),
+            crate::model::OverrideSelection {
+                pack_id: "missing_pack".to_string(),
+                variant_path: Some(" ".to_string()),
+            },
+        );
+
+        let result =
+            validate_build_request("/nonexistent/path", &pack_order, &overrides, output_dir);
+        let err = result.unwrap_err();
+        let messages: Vec<(Severity, &str)> = err
+            .issues
+            .iter()
+            .map(|i| (i.severity, i.message.as_str()))
+            .collect();
+
+        assert_eq!(err.code, "VALIDATION_ERROR");
+        assert!(err.message.starts_with("3 problems need fixing"));
+        assert_eq!(messages.len(), 4);
+        assert!(messages[0].1.contains("Packs directory does not exist"));
+        assert_eq!(
+            messages[1],
+            (
+                Severity::Warning,
+                "Pack pack1 appears more than once in the pack order"
+            )
+        );
+        assert!(messages[2].1.contains("non-existent pack: missing_pack"));
+        assert!(messages[3].1.contains("Variant path"));
+    }
+
+    #[test]
+    fn test_validate_overrides_against_index() {
+        let providers = std::collections::HashMap::from([(
+            "minecraft:block/stone".to_string(),
+            vec!["pack1".to_string(), "pack2".to_string()],
+        )]);
+        let selection = |pack_id: &str| crate::model::OverrideSelection {
+            pack_id: pack_id.to_string(),
+            variant_path: None,
+        };
+
+        let valid = std::collections::HashMap::from([(
+            "minecraft:block/stone".to_string(),
+            selection("pack2"),
+        )]);
+        let invalid = std::collections::HashMap::from([
+            ("minecraft:block/stone".to_string(), selection("pack3")),
+            ("minecraft:block/ruby".to_string(), selection("pack1")),
+        ]);
+
+        assert!(validate_overrides_against_index(&valid, &providers).is_ok());
+        let err = validate_overrides_against_index(&invalid, &providers).unwrap_err();
+        assert_eq!(err.issues.len(), 2);
+        assert_eq!(
+            err.issues[0].message,
+            "Override targets an asset no pack provides: minecraft:block/ruby"
+        );
+        assert_eq!(
+            err.issues[1].message,
+            "Override for minecraft:block/stone picks pack3, which doesn't provide it (provided by: pack1, pack2)"
+        );
+    }
 }
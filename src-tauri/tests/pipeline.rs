@@ -0,0 +1,256 @@
+/// End-to-end pipeline test against a bundled mini vanilla fixture
+///
+/// tests/fixtures/mini_vanilla is a tiny "client jar" (a few blocks with
+/// their models, blockstates and an animated texture) and
+/// tests/fixtures/packs holds two packs that compete over it. The test
+/// zips the jar and one pack, then runs extraction, scan, index, block
+/// resolution and a build, and compares a summary of every stage with
+/// tests/fixtures/golden/pipeline.txt.
+///
+/// After an intended change in output, regenerate the golden file with
+/// `UPDATE_GOLDEN=1 cargo test --test pipeline` and review its diff.
+use std::collections::HashMap;
+use std::fmt::Write as _;
+use std::fs;
+use std::io::Write as _;
+use std::path::{Path, PathBuf};
+use walkdir::WalkDir;
+use weaverbird_lib::model::{OverrideSelection, PackMeta};
+use weaverbird_lib::util::{
+    asset_indexer, block_models, blockstates, pack_scanner, path_policy, text_assets,
+    vanilla_textures, weaver_nest,
+};
+
+/// Minecraft version the fixture jar poses as
+const FIXTURE_VERSION: &str = "1.21.4";
+
+fn fixtures_dir() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures")
+}
+
+/// Files under a folder as sorted '/'-separated relative paths
+fn relative_files(root: &Path) -> Vec<String> {
+    let mut files: Vec<String> = WalkDir::new(root)
+        .into_iter()
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| entry.file_type().is_file())
+        .map(|entry| {
+            entry
+                .path()
+                .strip_prefix(root)
+                .unwrap()
+                .to_string_lossy()
+                .replace('\\', "/")
+        })
+        .collect();
+    files.sort();
+    files
+}
+
+/// Zip a fixture folder, with entries in a fixed order
+fn zip_folder(source: &Path, zip_path: &Path) {
+    fs::create_dir_all(zip_path.parent().unwrap()).unwrap();
+    let mut writer = zip::ZipWriter::new(fs::File::create(zip_path).unwrap());
+    for file in relative_files(source) {
+        writer
+            .start_file(file.as_str(), zip::write::FileOptions::default())
+            .unwrap();
+        writer
+            .write_all(&fs::read(source.join(&file)).unwrap())
+            .unwrap();
+    }
+    writer.finish().unwrap();
+}
+
+fn copy_folder(source: &Path, dest: &Path) {
+    for file in relative_files(source) {
+        let target = dest.join(&file);
+        fs::create_dir_all(target.parent().unwrap()).unwrap();
+        fs::copy(source.join(&file), target).unwrap();
+    }
+}
+
+/// Short content hash that is stable across platforms
+fn short_hash(bytes: &[u8]) -> String {
+    format!("{:016x}", xxhash_rust::xxh3::xxh3_64(bytes))
+}
+
+fn vanilla_pack(path: &Path) -> PackMeta {
+    PackMeta {
+        id: "minecraft:vanilla".to_string(),
+        name: "Minecraft (Vanilla)".to_string(),
+        path: path.to_string_lossy().to_string(),
+        ..Default::default()
+    }
+}
+
+/// Run every stage and summarize what it produced
+fn run_pipeline(work_dir: &Path) -> String {
+    let fixtures = fixtures_dir();
+    let mut summary = String::new();
+
+    // Extract the fixture jar the way a real client jar is
+    let jar_path = work_dir
+        .join("versions")
+        .join(FIXTURE_VERSION)
+        .join(format!("{}.jar", FIXTURE_VERSION));
+    zip_folder(&fixtures.join("mini_vanilla"), &jar_path);
+    let vanilla_dir = work_dir.join("vanilla");
+    let entries = vanilla_textures::list_extractable_entries(&jar_path).unwrap();
+    vanilla_textures::extract_entries(&jar_path, &vanilla_dir, &entries, None, None).unwrap();
+    writeln!(summary, "# extract").unwrap();
+    for file in relative_files(&vanilla_dir) {
+        writeln!(summary, "{}", file).unwrap();
+    }
+
+    // One folder pack and one zipped pack
+    let packs_dir = work_dir.join("resourcepacks");
+    copy_folder(
+        &fixtures.join("packs/Crisp Textures"),
+        &packs_dir.join("Crisp Textures"),
+    );
+    zip_folder(
+        &fixtures.join("packs/Warm Tones"),
+        &packs_dir.join("Warm Tones.zip"),
+    );
+    let mut packs = pack_scanner::scan_packs(&packs_dir.to_string_lossy()).unwrap();
+    packs.sort_by(|a, b| a.id.cmp(&b.id));
+    writeln!(summary, "\n# scan").unwrap();
+    for pack in &packs {
+        writeln!(
+            summary,
+            "{} zip={} format={:?} description={:?}",
+            pack.id, pack.is_zip, pack.pack_format, pack.description
+        )
+        .unwrap();
+    }
+
+    let vanilla = vanilla_pack(&vanilla_dir);
+    packs.push(vanilla.clone());
+    let (assets, providers) = asset_indexer::index_assets(&packs).unwrap();
+    writeln!(summary, "\n# index").unwrap();
+    for asset in &assets {
+        let mut asset_providers = providers[&asset.id].clone();
+        asset_providers.sort();
+        writeln!(summary, "{} <- {}", asset.id, asset_providers.join(", ")).unwrap();
+    }
+
+    let pack_order: Vec<String> = vec![
+        "Crisp Textures".to_string(),
+        "Warm Tones.zip".to_string(),
+        vanilla.id.clone(),
+    ];
+    let overrides = HashMap::from([(
+        "minecraft:block/stone".to_string(),
+        OverrideSelection {
+            pack_id: "Warm Tones.zip".to_string(),
+            variant_path: None,
+        },
+    )]);
+
+    // Resolve blocks through the pack that wins their model
+    writeln!(summary, "\n# resolve").unwrap();
+    for (block, props) in [("stone", None), ("oak_log", Some(("axis", "x")))] {
+        let state_props =
+            props.map(|(key, value)| HashMap::from([(key.to_string(), value.to_string())]));
+        let blockstate = blockstates::read_blockstate(&vanilla_dir, block, false).unwrap();
+        let resolution =
+            blockstates::resolve_blockstate(&blockstate, block, state_props, Some(0)).unwrap();
+        for resolved in &resolution.models {
+            let model_pack = packs
+                .iter()
+                .find(|pack| {
+                    pack.id != vanilla.id
+                        && block_models::read_block_model(pack, &resolved.model_id).is_ok()
+                })
+                .unwrap_or(&vanilla);
+            let model = block_models::resolve_block_model(model_pack, &resolved.model_id, &vanilla)
+                .unwrap();
+            writeln!(
+                summary,
+                "{} -> {} from {} (x={} y={}, {} element(s))",
+                block,
+                resolved.model_id,
+                model_pack.id,
+                resolved.rot_x,
+                resolved.rot_y,
+                model.elements.as_ref().map_or(0, Vec::len)
+            )
+            .unwrap();
+            let mut textures: Vec<(String, String)> =
+                block_models::resolve_textures(&model).into_iter().collect();
+            textures.sort();
+            for (slot, texture) in textures {
+                let winner =
+                    weaver_nest::winning_pack(&texture, &providers, &pack_order, &overrides);
+                writeln!(
+                    summary,
+                    "  {} = {} (from {})",
+                    slot,
+                    texture,
+                    winner.unwrap_or("nowhere")
+                )
+                .unwrap();
+            }
+        }
+    }
+
+    // Build, merging the splash texts of both packs
+    let output_dir = work_dir.join("output");
+    weaver_nest::build_weaver_nest(
+        &packs,
+        &assets,
+        &providers,
+        &pack_order,
+        &overrides,
+        &output_dir.to_string_lossy(),
+    )
+    .unwrap();
+    text_assets::write_merged_text_assets(
+        &packs,
+        &assets,
+        &providers,
+        &pack_order,
+        &["minecraft:texts/splashes".to_string()],
+        &output_dir,
+        None,
+    )
+    .unwrap();
+    writeln!(summary, "\n# build").unwrap();
+    for file in relative_files(&output_dir) {
+        let bytes = fs::read(output_dir.join(&file)).unwrap();
+        writeln!(summary, "{} {}", file, short_hash(&bytes)).unwrap();
+    }
+    let splashes =
+        fs::read_to_string(output_dir.join("assets/minecraft/texts/splashes.txt")).unwrap();
+    writeln!(summary, "\n# splashes.txt\n{}", splashes.trim_end()).unwrap();
+
+    summary
+}
+
+#[test]
+fn test_pipeline_matches_golden() {
+    let work_dir = std::env::temp_dir().join("test_pipeline_golden");
+    fs::remove_dir_all(&work_dir).ok();
+    path_policy::register_root(&work_dir);
+    path_policy::register_root(&fixtures_dir());
+
+    let summary = run_pipeline(&work_dir);
+
+    // Clean up
+    fs::remove_dir_all(&work_dir).ok();
+
+    let golden_path = fixtures_dir().join("golden/pipeline.txt");
+    if std::env::var("UPDATE_GOLDEN").is_ok() {
+        fs::create_dir_all(golden_path.parent().unwrap()).unwrap();
+        fs::write(&golden_path, &summary).unwrap();
+        return;
+    }
+    let golden = fs::read_to_string(&golden_path)
+        .expect("golden file missing; run with UPDATE_GOLDEN=1 to create it")
+        .replace("\r\n", "\n");
+    assert_eq!(
+        summary, golden,
+        "pipeline output changed; if intended, rerun with UPDATE_GOLDEN=1"
+    );
+}
@@ -369,6 +369,8 @@ export const MinecraftCSSBlock = ({
                   packId,
                   resolvedModel.modelId,
                   packsDir,
+                  undefined,
+                  resolvedModel,
                 );
 
                 // Merge textures from all models
@@ -31,6 +31,8 @@ export const PackList = ({
   disabledPacks = [],
   onReorder,
   onReorderDisabled,
+  onSuggestOrder,
+  warnings = [],
   onDisable,
   onEnable,
   onBrowse,
@@ -199,6 +201,22 @@ export const PackList = ({
           <div className={s.sectionHeader}>
             <h3 className={s.sectionTitle}>Enabled Packs</h3>
             <p className={s.sectionHint}>Higher packs override lower ones.</p>
+            {onSuggestOrder && packs.length > 1 && (
+              <Button
+                className={s.suggestButton}
+                onClick={onSuggestOrder}
+                variant="secondary"
+                size="md"
+                title="Put addon packs on top and full-coverage packs at the bottom"
+              >
+                Suggest order
+              </Button>
+            )}
+            {warnings.map((warning) => (
+              <p key={warning} className={s.sectionWarning}>
+                {warning}
+              </p>
+            ))}
           </div>
           <DroppableArea id={ENABLED_CONTAINER_ID}>
             {({ setNodeRef, isDropTarget }) => (
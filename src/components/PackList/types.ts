@@ -13,6 +13,10 @@ export interface PackListProps {
     disabledPacks?: PackItem[];
     onReorder?: (order: string[]) => void;
     onReorderDisabled?: (order: string[]) => void;
+    /** Replace the enabled order with a suggested starting order */
+    onSuggestOrder?: () => void;
+    /** Problems with the enabled selection, e.g. a patch without its base pack */
+    warnings?: string[];
     onDisable?: (packId: string, targetIndex?: number) => void;
     onEnable?: (packId: string, targetIndex?: number) => void;
     onBrowse?: () => void;
@@ -116,6 +116,8 @@ describe("BlockModel", () => {
       mockPackId,
       mockAssetId,
       mockPacksDir,
+      undefined,
+      mockResolution.models[0],
     );
   });
 
@@ -215,11 +215,13 @@ function BlockModel({
         for (let i = 0; i < resolution.models.length; i++) {
           const resolvedModel = resolution.models[i];
 
-          // Load the actual model JSON directly by model ID
+          // Load the model JSON with the variant's rotation and uvlock applied
           const model = await loadModelJson(
             packId,
             resolvedModel.modelId,
             packsDirPath,
+            undefined,
+            resolvedModel,
           );
 
           // Check if this model has tintindex
@@ -252,7 +254,6 @@ function BlockModel({
             model,
             textureLoader,
             biomeColor,
-            resolvedModel, // Pass resolved model for rotations and uvlock
           );
 
           // Apply polygon offset to prevent Z-fighting in multipart models
@@ -3,8 +3,12 @@ import {
   listAvailableMinecraftVersions,
   getCachedVanillaVersion,
   setVanillaTextureVersion,
+  getVanillaVersionSettings,
+  setVanillaVersionSettings,
   formatError,
   type MinecraftVersion,
+  type VanillaVersionSettings,
+  type VersionPreference,
 } from "@lib/tauri";
 import s from "./styles.module.scss";
 
@@ -12,6 +16,9 @@ export const VanillaTextureVersion = () => {
   const [versions, setVersions] = useState<MinecraftVersion[]>([]);
   const [currentVersion, setCurrentVersion] = useState<string | null>(null);
   const [selectedVersion, setSelectedVersion] = useState<string>("");
+  const [versionSettings, setVersionSettings] = useState<VanillaVersionSettings>({
+    preference: "latestRelease",
+  });
   const [loading, setLoading] = useState(true);
   const [switching, setSwitching] = useState(false);
   const [error, setError] = useState<string>();
@@ -21,13 +28,15 @@ export const VanillaTextureVersion = () => {
     const load = async () => {
       try {
         setLoading(true);
-        const [availableVersions, cached] = await Promise.all([
+        const [availableVersions, cached, savedSettings] = await Promise.all([
           listAvailableMinecraftVersions(),
           getCachedVanillaVersion(),
+          getVanillaVersionSettings(),
         ]);
 
         setVersions(availableVersions);
         setCurrentVersion(cached);
+        setVersionSettings(savedSettings);
         setSelectedVersion(cached || availableVersions[0]?.version || "");
         setError(undefined);
       } catch (err) {
@@ -49,12 +58,33 @@ export const VanillaTextureVersion = () => {
       await setVanillaTextureVersion(version);
       setCurrentVersion(version);
       setSelectedVersion(version);
+      // A pin follows the version picked by hand
+      if (versionSettings.preference === "pinned") {
+        setVersionSettings(
+          await setVanillaVersionSettings({ ...versionSettings, pinnedVersion: version }),
+        );
+      }
     } catch (err) {
       setError(formatError(err));
     } finally {
       setSwitching(false);
     }
-  }, [currentVersion]);
+  }, [currentVersion, versionSettings]);
+
+  // Handle preference change
+  const handlePreferenceChange = useCallback(async (preference: VersionPreference) => {
+    try {
+      setError(undefined);
+      setVersionSettings(
+        await setVanillaVersionSettings({
+          preference,
+          pinnedVersion: preference === "pinned" ? currentVersion || selectedVersion : null,
+        }),
+      );
+    } catch (err) {
+      setError(formatError(err));
+    }
+  }, [currentVersion, selectedVersion]);
 
   return (
     <div className={s.root}>
@@ -86,11 +116,25 @@ export const VanillaTextureVersion = () => {
             {versions.map((version) => (
               <option key={version.version} value={version.version}>
                 {version.version}
+                {version.version_type === "snapshot" ? " (snapshot)" : ""}
                 {version.version === currentVersion ? " (current)" : ""}
               </option>
             ))}
           </select>
 
+          <label htmlFor="version-preference">Automatic selection:</label>
+          <select
+            id="version-preference"
+            value={versionSettings.preference}
+            onChange={(e) => handlePreferenceChange(e.target.value as VersionPreference)}
+            disabled={switching}
+            className={s.versionDropdown}
+          >
+            <option value="latestRelease">Latest release</option>
+            <option value="latestSnapshot">Latest, including snapshots</option>
+            <option value="pinned">Keep the selected version</option>
+          </select>
+
           {switching && (
             <div className={s.switchingMessage}>
               Extracting textures for version {selectedVersion}...
@@ -190,6 +190,15 @@ export function shouldUseItemTextureForCard(assetId: string): boolean {
   return crossShapedPlants.includes(blockName);
 }
 
+/**
+ * Check if an asset is a custom entity model group (OptiFine/EMF .jem/.jpm files)
+ * These are indexed as "<namespace>:cem/<entity>" and carry no texture of their own
+ */
+export function isCemAsset(assetId: string): boolean {
+  const path = assetId.includes(":") ? assetId.split(":")[1] : assetId;
+  return path.startsWith("cem/");
+}
+
 /**
  * Check if an asset is an entity texture (any entity)
  * Entity textures are rendered with a 2D/3D toggle preview using the universal entity renderer
@@ -5,8 +5,11 @@
  */
 
 import { invoke } from "@tauri-apps/api/core";
-import { open } from "@tauri-apps/plugin-dialog";
-import type { OverrideWirePayload, ScanResult } from "@state";
+import type {
+  FolderAccessReport,
+  OverrideWirePayload,
+  ScanResult,
+} from "@state";
 
 /**
  * Structured error response from Tauri backend
@@ -19,9 +22,39 @@ export interface AppError {
     | "IO_ERROR"
     | "SCAN_ERROR"
     | "BUILD_ERROR"
+    | "FILE_LOCKED"
+    | "INSTANCE_BUSY"
+    | "CANCELLED"
     | "INTERNAL_ERROR";
   message: string;
   details?: string;
+  /** Every problem found, when validation collected several checks */
+  issues?: ValidationIssue[];
+  /** Trying the same operation again may succeed */
+  retryable?: boolean;
+  /** Remediation to offer as a one-click action */
+  hint?: AppErrorHint;
+  /** File or folder the error is about */
+  path?: string;
+}
+
+/**
+ * Remediations the backend can suggest for an error
+ */
+export type AppErrorHint =
+  | "grantFolderAccess"
+  | "freeDiskSpace"
+  | "waitAndRetry"
+  | "chooseAnotherFolder";
+
+/**
+ * One problem found while validating a request
+ */
+export interface ValidationIssue {
+  severity: "error" | "warning";
+  /** Error code the issue would have on its own */
+  code: AppError["code"];
+  message: string;
 }
 
 /**
@@ -54,23 +87,218 @@ export function formatError(error: unknown): string {
   return String(error);
 }
 
+/**
+ * Events carrying a `Progress` payload for long operations
+ */
+export const PROGRESS_EVENTS = {
+  scan: "scan:progress",
+  index: "index:progress",
+  extract: "extract:progress",
+  build: "build:progress",
+  download: "download:progress",
+} as const;
+
+/**
+ * Progress of a scan, index, extraction, build or download
+ *
+ * Downloads count completed and total in bytes (total 0 if unknown).
+ */
+export interface Progress {
+  /** "scan", "index", "extract", "build" or "download" */
+  phase: string;
+  completed: number;
+  total: number;
+  bytes: number | null;
+  /** Transfer rate, for downloads */
+  bytesPerSec: number | null;
+  /** Pack or file just finished */
+  currentFile: string | null;
+  /** Estimated milliseconds left */
+  etaMs: number | null;
+}
+
+/**
+ * Create a job ID to pass to a scan, vanilla extraction or build
+ *
+ * While that command runs, `cancelJob` makes it fail with a CANCELLED error.
+ */
+export async function createJob(): Promise<string> {
+  return invoke<string>("create_job");
+}
+
+/**
+ * Ask a running job to stop
+ *
+ * @returns Whether the job was still running
+ */
+export async function cancelJob(jobId: string): Promise<boolean> {
+  return invoke<boolean>("cancel_job", { jobId });
+}
+
+/** Remote control server state */
+export interface RemoteServerStatus {
+  running: boolean;
+  /** Address it listens on, e.g. "127.0.0.1:27416" */
+  address: string | null;
+  /** Bearer token clients send in the Authorization header */
+  token: string | null;
+}
+
+/**
+ * Start the local JSON-RPC server so scripts can drive weaver-bird
+ *
+ * Calls go to `POST http://<address>/rpc` with `Authorization: Bearer <token>`.
+ * The server starts again with the app until `stopRemoteServer` is called.
+ *
+ * @param port - Port to listen on; the saved one if omitted
+ * @param allowLan - Listen on all interfaces instead of only 127.0.0.1
+ * @param runBuildHooks - Run post-build hooks for builds started over RPC
+ *   (off by default, since hooks run shell commands); the saved choice if omitted
+ */
+export async function startRemoteServer(
+  port?: number,
+  allowLan?: boolean,
+  runBuildHooks?: boolean,
+): Promise<RemoteServerStatus> {
+  return invoke<RemoteServerStatus>("start_remote_server", {
+    port,
+    allowLan,
+    runBuildHooks,
+  });
+}
+
+/** Stop the remote control server and keep it off on later starts */
+export async function stopRemoteServer(): Promise<RemoteServerStatus> {
+  return invoke<RemoteServerStatus>("stop_remote_server");
+}
+
+/** Whether the remote control server is running, and its address and token */
+export async function getRemoteServerStatus(): Promise<RemoteServerStatus> {
+  return invoke<RemoteServerStatus>("get_remote_server_status");
+}
+
+/** Replace the remote control token; a running server restarts with it */
+export async function regenerateRemoteServerToken(): Promise<RemoteServerStatus> {
+  return invoke<RemoteServerStatus>("regenerate_remote_server_token");
+}
+
 /**
  * Scan a resource packs folder for all packs and assets
+ *
+ * Unchanged ZIP packs are read from a cached index; pass `forceReindex` to
+ * re-read every pack. Progress arrives as "scan:progress" and
+ * "index:progress" events.
+ */
+export async function scanPacksFolder(
+  path: string,
+  forceReindex = false,
+  jobId?: string,
+): Promise<ScanResult> {
+  return invoke<ScanResult>("scan_packs_folder", {
+    packsDir: path,
+    forceReindex,
+    jobId,
+  });
+}
+
+/**
+ * The last session as saved after its scan
+ */
+export interface SessionSnapshot {
+  version: number;
+  packsDir: string;
+  /** Pack IDs in priority order (first = highest) */
+  packOrder: string[];
+  overrides: Record<string, OverrideWirePayload>;
+  scan: ScanResult;
+  /** Asset ID -> winning pack under packOrder and overrides */
+  winners: Record<string, string>;
+  /** Seconds since the Unix epoch */
+  savedAt: number;
+}
+
+/**
+ * What a fresh scan changed compared to the restored session
+ */
+export interface SessionChanges {
+  addedPacks: string[];
+  removedPacks: string[];
+  addedAssets: number;
+  removedAssets: number;
+  changedWinners: WinnerChange[];
+}
+
+/**
+ * The last session, shown before it could be checked
+ */
+export interface RestoredSession {
+  snapshot: SessionSnapshot;
+  /** Packs may have changed on disk since the snapshot was saved */
+  possiblyStale: boolean;
+  /** Job of the background scan, for cancelJob */
+  reconcileJobId: string | null;
+}
+
+/** Payload of the "session:reconciled" event */
+export interface SessionReconciled {
+  snapshot: SessionSnapshot;
+  changes: SessionChanges;
+}
+
+/**
+ * Restore the last session from cache, without waiting for a scan
+ *
+ * The packs folder is rescanned in the background; listen for
+ * "session:reconciled" (SessionReconciled) or "session:reconcile-failed"
+ * (AppError). Returns null if no session was saved yet.
+ */
+export async function restoreLastSession(): Promise<RestoredSession | null> {
+  return invoke<RestoredSession | null>("restore_last_session");
+}
+
+/**
+ * Record the pack order and overrides to restore with the session
+ *
+ * @returns Whether the saved session is of packsDir and was updated
  */
-export async function scanPacksFolder(path: string): Promise<ScanResult> {
-  return invoke<ScanResult>("scan_packs_folder", { packsDir: path });
+export async function updateSessionStack(
+  packsDir: string,
+  packOrder: string[],
+  overrides?: Record<string, OverrideWirePayload>,
+): Promise<boolean> {
+  return invoke<boolean>("update_session_stack", {
+    packsDir,
+    packOrder,
+    overrides,
+  });
 }
 
 /**
  * Build the Weaver Nest output pack
+ * Progress arrives as "build-progress" events with [filesCopied, total],
+ * and as "build:progress" events with the current file and ETA.
  */
 export async function buildWeaverNest(request: {
   packsDir: string;
   packOrder: string[];
   overrides: Record<string, OverrideWirePayload>;
   outputDir: string;
-}): Promise<string> {
-  return invoke<string>("build_weaver_nest", request);
+  /** Namespace -> the only pack allowed to provide it */
+  namespaceOwners?: Record<string, string>;
+  /** Recolor fully transparent texture pixels to avoid dark mipmap edges */
+  fixAlphaBleed?: boolean;
+  /** Take the other textures of an overridden block from the same pack (default on) */
+  includeOverrideDependencies?: boolean;
+  /** Packs in the order left out of this build */
+  disabledPacks?: string[];
+  /** Text assets (e.g. "minecraft:texts/splashes") built from every pack's unique lines */
+  mergedTextAssets?: string[];
+  /** Name for {project_name} in the description template */
+  projectName?: string;
+  /** pack.mcmeta description template; the settings default when unset */
+  descriptionTemplate?: string;
+}, jobId?: string): Promise<string> {
+  return invoke<string>("build_weaver_nest", { request, jobId });
 }
 
 /**
@@ -82,15 +310,15 @@ export async function getDefaultPacksDir(): Promise<string> {
 
 /**
  * Open a folder browser dialog
+ *
+ * The dialog is shown by the backend, which grants filesystem access to the
+ * selected folder under its path policy.
  */
 export async function openFolderDialog(
   defaultPath?: string,
 ): Promise<string | null> {
   try {
-    return await open({
-      directory: true,
-      defaultPath,
-    });
+    return await invoke<string | null>("select_folder", { defaultPath });
   } catch (error) {
     console.error("Failed to open folder dialog:", error);
     return null;
@@ -101,8 +329,10 @@ export async function openFolderDialog(
  * Initialize vanilla textures (extract from Minecraft JAR if needed)
  * Should be called on app startup
  */
-export async function initializeVanillaTextures(): Promise<string> {
-  return invoke<string>("initialize_vanilla_textures");
+export async function initializeVanillaTextures(
+  jobId?: string,
+): Promise<string> {
+  return invoke<string>("initialize_vanilla_textures", { jobId });
 }
 
 /**
@@ -168,8 +398,9 @@ export async function getCachedVanillaVersion(): Promise<string | null> {
  */
 export async function setVanillaTextureVersion(
   version: string,
+  jobId?: string,
 ): Promise<string> {
-  return invoke<string>("set_vanilla_texture_version", { version });
+  return invoke<string>("set_vanilla_texture_version", { version, jobId });
 }
 
 /**
@@ -191,6 +422,90 @@ export interface MinecraftVersion {
   version: string;
   jar_path: string;
   modified_time: number;
+  /** Channel, from the version JSON or else the name */
+  version_type: "release" | "snapshot" | "old" | "unknown";
+  /** Release date from the version JSON (ISO 8601) */
+  release_time: string | null;
+}
+
+export interface ConflictSeveritySettings {
+  /** Category -> severity */
+  categories: Record<string, ConflictSeverity>;
+  /** Severity of categories not listed */
+  defaultSeverity: ConflictSeverity;
+  /** Unresolved conflicts at or above this need confirming before a build */
+  confirmBuildAt: ConflictSeverity;
+}
+
+/**
+ * Get how severe conflicts of each asset category are
+ */
+export async function getConflictSeveritySettings(): Promise<ConflictSeveritySettings> {
+  return invoke<ConflictSeveritySettings>("get_conflict_severity_settings");
+}
+
+/**
+ * Save how severe conflicts of each asset category are
+ */
+export async function setConflictSeveritySettings(
+  conflictSeverity: ConflictSeveritySettings,
+): Promise<ConflictSeveritySettings> {
+  return invoke<ConflictSeveritySettings>("set_conflict_severity_settings", {
+    conflictSeverity,
+  });
+}
+
+/**
+ * Which installed version vanilla assets come from when none was chosen
+ */
+export type VersionPreference = "latestRelease" | "latestSnapshot" | "pinned";
+
+export interface VanillaVersionSettings {
+  preference: VersionPreference;
+  /** Version used with "pinned" */
+  pinnedVersion?: string | null;
+  /** Cap on the total size of cached versions in MB; 1024 if unset */
+  cacheLimitMb?: number | null;
+}
+
+/**
+ * Get the vanilla version preference
+ */
+export async function getVanillaVersionSettings(): Promise<VanillaVersionSettings> {
+  return invoke<VanillaVersionSettings>("get_vanilla_version_settings");
+}
+
+/**
+ * Save the vanilla version preference
+ */
+export async function setVanillaVersionSettings(
+  vanillaVersion: VanillaVersionSettings,
+): Promise<VanillaVersionSettings> {
+  return invoke<VanillaVersionSettings>("set_vanilla_version_settings", {
+    vanillaVersion,
+  });
+}
+
+/**
+ * Versions removed by clearVanillaCache
+ */
+export interface VanillaCacheClearResult {
+  removed: string[];
+  freedBytes: number;
+}
+
+/**
+ * Remove cached vanilla versions
+ *
+ * Removing the version in use means vanilla assets are extracted again
+ * the next time they're needed.
+ *
+ * @param version - Version to remove (e.g. "1.21.4"); every version if omitted
+ */
+export async function clearVanillaCache(
+  version?: string,
+): Promise<VanillaCacheClearResult> {
+  return invoke<VanillaCacheClearResult>("clear_vanilla_cache", { version });
 }
 
 /**
@@ -221,6 +536,109 @@ export async function getLauncherResourcepacksDir(
   return invoke<string>("get_launcher_resourcepacks_dir", { launcherInfo });
 }
 
+/**
+ * A Modrinth App profile with its own resourcepacks folder
+ */
+export interface ModrinthProfile {
+  name: string;
+  path: string;
+  resourcepacksDir: string;
+  /** Minecraft version (e.g., "1.20.1") */
+  gameVersion: string | null;
+  /** "vanilla", "fabric", "forge", "neoforge" or "quilt" */
+  loader: string | null;
+  loaderVersion: string | null;
+  /** Modrinth project the profile was installed from (.mrpack modpacks) */
+  modpackProjectId: string | null;
+}
+
+/**
+ * List the Modrinth App's profiles, sorted by name
+ * @param profilesDir - The app's profiles folder; detected when omitted
+ */
+export async function listModrinthProfiles(profilesDir?: string): Promise<ModrinthProfile[]> {
+  return invoke<ModrinthProfile[]>("list_modrinth_profiles", {
+    profilesDir: profilesDir ?? null,
+  });
+}
+
+/**
+ * A Prism Launcher, MultiMC or CurseForge instance
+ */
+export interface LauncherInstance {
+  /** Name from the instance's metadata, or the folder name */
+  name: string;
+  path: string;
+  resourcepacksDir: string;
+  /** Icon key from instance.cfg (e.g., "grass"); null for CurseForge */
+  iconKey: string | null;
+  /** Image of a custom icon; built-in icons have none */
+  iconPath: string | null;
+  /** Minecraft version (e.g., "1.20.1") */
+  gameVersion: string | null;
+  /** "fabric", "forge", "neoforge" or "quilt"; null for vanilla */
+  loader: string | null;
+  loaderVersion: string | null;
+}
+
+/**
+ * List Prism Launcher, MultiMC and CurseForge instances, sorted by name
+ * within each launcher
+ * @param instancesDir - A launcher's folder or its instances folder; every
+ *   detected Prism Launcher, MultiMC and CurseForge folder when omitted
+ */
+export async function listLauncherInstances(instancesDir?: string): Promise<LauncherInstance[]> {
+  return invoke<LauncherInstance[]>("list_launcher_instances", {
+    instancesDir: instancesDir ?? null,
+  });
+}
+
+/**
+ * A remote pack downloaded into the packs folder
+ */
+export interface DownloadedFile {
+  path: string;
+  size: number;
+  /** Lowercase hex SHA-1 of the file */
+  sha1: string;
+  /** Bytes carried over from an earlier, interrupted attempt */
+  resumedFrom: number;
+}
+
+/**
+ * Download a remote pack (e.g., a Modrinth file) into the packs folder
+ *
+ * Downloads in chunks and carries on from an interrupted attempt at the
+ * same URL. Progress arrives as "download:progress" events with the
+ * transfer rate; cancelling the job removes the partial file.
+ * @param url - File to download
+ * @param packsDir - Directory containing resource packs
+ * @param fileName - Name to save as; taken from the URL when omitted
+ * @param sha1 - Expected SHA-1, checked once complete
+ * @param size - Expected size in bytes
+ * @param overwrite - Replace a pack of the same name instead of failing
+ * @param jobId - From `createJob`, to allow cancelling
+ */
+export async function downloadRemotePack(
+  url: string,
+  packsDir: string,
+  fileName?: string,
+  sha1?: string,
+  size?: number,
+  overwrite?: boolean,
+  jobId?: string,
+): Promise<DownloadedFile> {
+  return invoke<DownloadedFile>("download_remote_pack", {
+    url,
+    packsDir,
+    fileName: fileName ?? null,
+    sha1: sha1 ?? null,
+    size: size ?? null,
+    overwrite: overwrite ?? null,
+    jobId: jobId ?? null,
+  });
+}
+
 /**
  * Get the full path to a texture file from a resource pack
  * @param packPath - Base path to the resource pack
@@ -250,3 +668,2330 @@ export async function getEntityVersionVariants(
     packsDir,
   });
 }
+
+/**
+ * Rule that pins matching assets to a preferred pack
+ */
+export interface PresetRule {
+  /** Asset ID pattern, `*` matches any sequence */
+  pattern: string;
+  /** Case-insensitive substring of the preferred pack's name or ID */
+  preferPack: string;
+}
+
+/**
+ * Named conflict resolution preset
+ */
+export interface ConflictPreset {
+  name: string;
+  description?: string;
+  policy: "packOrder" | "highestResolution" | "vanillaFaithful";
+  rules: PresetRule[];
+}
+
+/**
+ * List saved conflict resolution presets
+ */
+export async function listConflictPresets(): Promise<ConflictPreset[]> {
+  return invoke<ConflictPreset[]>("list_conflict_presets");
+}
+
+/**
+ * Save a conflict preset (replaces an existing preset with the same name)
+ * @returns All saved presets
+ */
+export async function saveConflictPreset(
+  preset: ConflictPreset,
+): Promise<ConflictPreset[]> {
+  return invoke<ConflictPreset[]>("save_conflict_preset", { preset });
+}
+
+/**
+ * Delete a saved conflict preset
+ * @returns Remaining saved presets
+ */
+export async function deleteConflictPreset(
+  name: string,
+): Promise<ConflictPreset[]> {
+  return invoke<ConflictPreset[]>("delete_conflict_preset", { name });
+}
+
+/**
+ * Export a saved conflict preset as shareable JSON
+ */
+export async function exportConflictPreset(name: string): Promise<string> {
+  return invoke<string>("export_conflict_preset", { name });
+}
+
+/**
+ * Import a conflict preset from shared JSON and save it
+ */
+export async function importConflictPreset(
+  json: string,
+): Promise<ConflictPreset> {
+  return invoke<ConflictPreset>("import_conflict_preset", { json });
+}
+
+/**
+ * Apply a conflict preset to the packs in a directory
+ * @returns Overrides that realise the preset on top of the pack order
+ */
+export async function applyConflictPreset(
+  packsDir: string,
+  packOrder: string[],
+  preset: ConflictPreset,
+): Promise<Record<string, OverrideWirePayload>> {
+  return invoke<Record<string, OverrideWirePayload>>("apply_conflict_preset", {
+    packsDir,
+    packOrder,
+    preset,
+  });
+}
+
+/**
+ * Saved build configuration
+ */
+export interface Project {
+  name: string;
+  packsDir: string;
+  packOrder: string[];
+  overrides: Record<string, OverrideWirePayload>;
+  outputDir: string;
+  /** Namespace -> the only pack allowed to provide it */
+  namespaceOwners?: Record<string, string>;
+  /** Recolor fully transparent texture pixels to avoid dark mipmap edges */
+  fixAlphaBleed?: boolean;
+  /** Take the other textures of an overridden block from the same pack (default on) */
+  includeOverrideDependencies?: boolean;
+  /** Alternative orders and overrides to compare without building */
+  simulations?: StackConfig[];
+  /** Packs left out of builds for now, keeping their place in the order */
+  disabledPacks?: string[];
+  /** Text assets built by merging lines instead of replacing */
+  mergedTextAssets?: string[];
+  /** Conflicts accepted or skipped in guided resolution */
+  resolution?: ResolutionState;
+  /** pack.mcmeta description template, e.g. "&6{project_name}&r - {pack_count} packs" */
+  descriptionTemplate?: string;
+}
+
+/**
+ * A named pack order and override set to simulate
+ */
+export interface StackConfig {
+  name: string;
+  packOrder: string[];
+  overrides: Record<string, OverrideWirePayload>;
+}
+
+/**
+ * Outcome of building one project in a batch
+ */
+export interface ProjectBuildResult {
+  name: string;
+  outputDir: string;
+  success: boolean;
+  message: string;
+  durationMs: number;
+}
+
+/**
+ * Combined summary of a batch build
+ */
+export interface BatchBuildSummary {
+  results: ProjectBuildResult[];
+  succeeded: number;
+  failed: number;
+  durationMs: number;
+}
+
+/**
+ * List saved projects
+ */
+export async function listProjects(): Promise<Project[]> {
+  return invoke<Project[]>("list_projects");
+}
+
+/**
+ * Save a project (replaces an existing project with the same name)
+ * @returns All saved projects
+ */
+export async function saveProject(project: Project): Promise<Project[]> {
+  return invoke<Project[]>("save_project", { project });
+}
+
+/**
+ * Delete a saved project
+ * @returns Remaining saved projects
+ */
+export async function deleteProject(name: string): Promise<Project[]> {
+  return invoke<Project[]>("delete_project", { name });
+}
+
+/**
+ * One recorded state-changing operation
+ */
+export interface ActivityEntry {
+  /** Unix timestamp in milliseconds */
+  timestamp: number;
+  /** What was done (e.g. "build", "saveProject", "setOverride") */
+  action: string;
+  /** Project it was made in; absent for changes to the packs folder itself */
+  project?: string;
+  params: unknown;
+  /** State the operation replaced, for undo */
+  previous?: unknown;
+}
+
+/**
+ * Record a frontend change (override, pack order) in the activity log
+ * @param packsDir - Packs directory the change belongs to
+ * @param previous - State the change replaced, so it can be undone
+ * @param projectName - Project the change was made in, if one is open
+ */
+export async function recordActivity(
+  packsDir: string,
+  action: string,
+  params: unknown,
+  previous?: unknown,
+  projectName?: string,
+): Promise<void> {
+  return invoke<void>("record_activity", {
+    packsDir,
+    action,
+    params,
+    previous: previous ?? null,
+    projectName: projectName ?? null,
+  });
+}
+
+/**
+ * Recent operations on a packs directory, newest first
+ * @param actions - Only these actions (all if omitted)
+ * @param limit - Maximum entries returned
+ * @param projectName - Open project; other projects' changes in the same
+ *   packs folder are left out
+ */
+export async function getActivityLog(
+  packsDir: string,
+  actions?: string[],
+  limit?: number,
+  projectName?: string,
+): Promise<ActivityEntry[]> {
+  return invoke<ActivityEntry[]>("get_activity_log", {
+    packsDir,
+    actions: actions ?? null,
+    limit: limit ?? null,
+    projectName: projectName ?? null,
+  });
+}
+
+/**
+ * Build several saved projects, each to its own output directory
+ * @param names - Projects to build, in order
+ * @param concurrency - Maximum projects built at once (sequential if omitted)
+ */
+export async function batchBuildProjects(
+  names: string[],
+  concurrency?: number,
+): Promise<BatchBuildSummary> {
+  return invoke<BatchBuildSummary>("batch_build_projects", {
+    names,
+    concurrency,
+  });
+}
+
+/**
+ * Action run by the backend after a successful build
+ */
+export type PostBuildHook =
+  | { type: "revealOutput" }
+  | { type: "copyOutputPath" }
+  | { type: "copySha1" }
+  | { type: "runCommand"; command: string };
+
+/**
+ * Get the configured post-build hooks
+ */
+export async function getPostBuildHooks(): Promise<PostBuildHook[]> {
+  return invoke<PostBuildHook[]>("get_post_build_hooks");
+}
+
+/**
+ * Replace the configured post-build hooks
+ */
+export async function setPostBuildHooks(
+  hooks: PostBuildHook[],
+): Promise<PostBuildHook[]> {
+  return invoke<PostBuildHook[]>("set_post_build_hooks", { hooks });
+}
+
+/**
+ * List folders the user has granted filesystem access to
+ */
+export async function listPathGrants(): Promise<string[]> {
+  return invoke<string[]>("list_path_grants");
+}
+
+/**
+ * Revoke filesystem access to a previously granted folder
+ * @returns Remaining granted folders
+ */
+export async function revokePathGrant(path: string): Promise<string[]> {
+  return invoke<string[]>("revoke_path_grant", { path });
+}
+
+/**
+ * Full detection state for support bundles (secrets redacted)
+ */
+export interface DiagnosticsReport {
+  appVersion: string;
+  os: string;
+  arch: string;
+  generatedAt: number;
+  defaultMinecraftDir: string | null;
+  launchers: LauncherInfo[];
+  instances: { launcher: string; name: string; path: string }[];
+  minecraftVersions: MinecraftVersion[];
+  cachedVanillaVersion: string | null;
+  cachePaths: {
+    vanillaCacheDir: string | null;
+    configDir: string | null;
+    settingsFile: string | null;
+  };
+  settings: unknown;
+  errors: string[];
+}
+
+/**
+ * Collect a diagnostics report
+ */
+export async function getDiagnostics(): Promise<DiagnosticsReport> {
+  return invoke<DiagnosticsReport>("get_diagnostics");
+}
+
+/**
+ * Write the diagnostics report to a JSON file
+ * @returns Path of the written report
+ */
+export async function exportDiagnostics(outputPath: string): Promise<string> {
+  return invoke<string>("export_diagnostics", { outputPath });
+}
+
+export interface RecentLogs {
+  /** Folder holding weaverbird.log and its rotated copies */
+  logDir: string;
+  /** Last lines, oldest first */
+  lines: string[];
+}
+
+/**
+ * Get the last lines of the application log, e.g. to attach to a bug report
+ * @param limit - Number of lines (500 if omitted)
+ */
+export async function getRecentLogs(limit?: number): Promise<RecentLogs> {
+  return invoke<RecentLogs>("get_recent_logs", { limit });
+}
+
+export type CacheStatus = "healthy" | "missing" | "partial" | "repaired";
+
+/**
+ * Health report for the vanilla and preview caches
+ */
+export interface CacheHealthReport {
+  vanilla: {
+    status: CacheStatus;
+    cacheDir: string;
+    markerVersion: string | null;
+    jarPath: string | null;
+    expectedFiles: number;
+    missingFiles: number;
+    repairedFiles: number;
+    error?: string;
+  };
+  preview: {
+    status: CacheStatus;
+    cacheDir: string;
+    files: number;
+    corruptFiles: number;
+    removedFiles: number;
+  };
+}
+
+/**
+ * Verify the vanilla and preview caches
+ * @param repair - Re-extract missing vanilla files and drop corrupt previews
+ */
+export async function verifyCaches(repair: boolean): Promise<CacheHealthReport> {
+  return invoke<CacheHealthReport>("verify_caches", { repair });
+}
+
+/**
+ * One step of an animated texture's timeline
+ */
+export interface AnimationFrame {
+  /** Index of the frame within the texture */
+  index: number;
+  durationMs: number;
+  /** Frame rectangle origin in the texture, in pixels */
+  x: number;
+  y: number;
+}
+
+/**
+ * Resolved animation timeline for a texture
+ */
+export interface AnimationInfo {
+  frameWidth: number;
+  frameHeight: number;
+  frameCount: number;
+  interpolate: boolean;
+  frames: AnimationFrame[];
+  totalDurationMs: number;
+}
+
+/**
+ * Get the animation timeline of a texture
+ * @returns Timeline, or null if the texture isn't animated
+ */
+export async function getAnimationInfo(
+  packPath: string,
+  assetId: string,
+  isZip: boolean,
+): Promise<AnimationInfo | null> {
+  return invoke<AnimationInfo | null>("get_animation_info", {
+    packPath,
+    assetId,
+    isZip,
+  });
+}
+
+/**
+ * Animation timeline with every frame as its own image
+ */
+export interface AnimationFrames {
+  /** Playback order and timing; each frame's index points into images */
+  info: AnimationInfo;
+  /** Base64-encoded PNG of each frame in the texture, by frame index */
+  images: string[];
+}
+
+/**
+ * Split an animated texture into frame images for previews
+ * @returns Frames and timing, or null if the texture isn't animated
+ */
+export async function getAnimationFrames(
+  packPath: string,
+  assetId: string,
+  isZip: boolean,
+): Promise<AnimationFrames | null> {
+  return invoke<AnimationFrames | null>("get_animation_frames", {
+    packPath,
+    assetId,
+    isZip,
+  });
+}
+
+/**
+ * One level of a large texture's preview pyramid
+ */
+export interface PyramidLevel {
+  /** 0 is full size; each level halves the one before */
+  level: number;
+  width: number;
+  height: number;
+  /** Tiles across and down */
+  columns: number;
+  rows: number;
+}
+
+/**
+ * Preview levels of a large texture
+ */
+export interface TexturePyramid {
+  width: number;
+  height: number;
+  tileSize: number;
+  /** Full size first; the last level fits in one tile */
+  levels: PyramidLevel[];
+}
+
+/**
+ * A tile of one pyramid level
+ */
+export interface TextureTile {
+  level: number;
+  column: number;
+  row: number;
+  /** Smaller than tileSize at the right and bottom edges */
+  width: number;
+  height: number;
+  /** Base64-encoded PNG */
+  pngData: string;
+}
+
+/**
+ * Get the preview levels of a texture, for zooming on huge images
+ */
+export async function getTexturePyramid(
+  packPath: string,
+  assetId: string,
+  isZip: boolean,
+): Promise<TexturePyramid> {
+  return invoke<TexturePyramid>("get_texture_pyramid", {
+    packPath,
+    assetId,
+    isZip,
+  });
+}
+
+/**
+ * Get one tile of a texture's preview pyramid
+ */
+export async function getTextureTile(
+  packPath: string,
+  assetId: string,
+  isZip: boolean,
+  level: number,
+  column: number,
+  row: number,
+): Promise<TextureTile> {
+  return invoke<TextureTile>("get_texture_tile", {
+    packPath,
+    assetId,
+    isZip,
+    level,
+    column,
+    row,
+  });
+}
+
+export type AnimationExportFormat = "gif" | "webp";
+
+/**
+ * Result of an animated texture export
+ */
+export interface AnimationExport {
+  outputPath: string;
+  width: number;
+  height: number;
+  /** Frames written (interpolated textures get one per tick) */
+  frameCount: number;
+  totalDurationMs: number;
+}
+
+/**
+ * Export an animated texture as a looping GIF or WebP
+ * @param size - Output width in pixels, scaled with nearest-neighbor
+ */
+export async function exportAnimation(
+  packPath: string,
+  assetId: string,
+  isZip: boolean,
+  format: AnimationExportFormat,
+  size: number,
+  outputPath: string,
+): Promise<AnimationExport> {
+  return invoke<AnimationExport>("export_animation", {
+    packPath,
+    assetId,
+    isZip,
+    format,
+    size,
+    outputPath,
+  });
+}
+
+/**
+ * One version of a texture within a pack family
+ */
+export interface TextureHistoryEntry {
+  packId: string;
+  packName: string;
+  packFormat?: number;
+  /** Version label parsed from the pack name (e.g. "1.20.4") */
+  version?: string;
+  /** Base64-encoded PNG, absent if this version doesn't ship the texture */
+  imageData?: string;
+  /** True if the image differs from the previous version */
+  changed: boolean;
+}
+
+/**
+ * Get a texture across every version of a pack family, oldest first
+ * @param packId - Any pack in the family
+ */
+export async function getTextureHistory(
+  packsDir: string,
+  packId: string,
+  assetId: string,
+): Promise<TextureHistoryEntry[]> {
+  return invoke<TextureHistoryEntry[]>("get_texture_history", {
+    packsDir,
+    packId,
+    assetId,
+  });
+}
+
+/**
+ * Textures of one entity
+ */
+export interface EntityGroup {
+  /** e.g. "minecraft:zombie" */
+  entity: string;
+  /** e.g. "Zombie" */
+  label: string;
+  /** Texture asset IDs, sorted */
+  textures: string[];
+}
+
+/**
+ * Group the packs' entity textures by the entity they belong to
+ * (husk, trapped chest, armor layers, ...)
+ */
+export async function getEntityGroups(packsDir: string): Promise<EntityGroup[]> {
+  return invoke<EntityGroup[]>("get_entity_groups", { packsDir });
+}
+
+/**
+ * A named part of a GUI texture (a button state, a slot, an icon), in the
+ * texture's own pixels
+ */
+export interface GuiRegion {
+  name: string;
+  x: number;
+  y: number;
+  width: number;
+  height: number;
+}
+
+/**
+ * Regions of one GUI sprite sheet
+ */
+export interface GuiAtlas {
+  assetId: string;
+  width: number;
+  height: number;
+  /** Texture pixels per vanilla pixel (2 for a 512x512 sheet) */
+  scale: number;
+  regions: GuiRegion[];
+}
+
+/**
+ * Get the named regions of a GUI sprite sheet (e.g. "minecraft:gui/widgets"),
+ * scaled to a pack's copy when packPath is given
+ * @returns null for textures without known regions
+ */
+export async function getGuiRegions(
+  assetId: string,
+  packPath?: string,
+  isZip = false,
+): Promise<GuiAtlas | null> {
+  return invoke<GuiAtlas | null>("get_gui_regions", {
+    assetId,
+    packPath,
+    isZip,
+  });
+}
+
+/**
+ * GUI textures that getGuiRegions knows the layout of
+ */
+export async function listGuiAtlases(): Promise<string[]> {
+  return invoke<string[]>("list_gui_atlases");
+}
+
+export type Colormap = "grass" | "foliage";
+
+/**
+ * A built-in biome and where it samples the colormaps
+ */
+export interface BiomeClimate {
+  /** e.g. "minecraft:plains" */
+  biome: string;
+  temperature: number;
+  downfall: number;
+}
+
+/**
+ * A grayscale texture multiplied with its biome color
+ */
+export interface TintedTexture {
+  assetId: string;
+  biome: string;
+  colormap: Colormap;
+  /** e.g. "#91BD59" */
+  tint: string;
+  /** Base64-encoded PNG */
+  pngData: string;
+}
+
+/**
+ * Tint a texture like grass_block_top or oak_leaves for a biome, using the
+ * pack's colormap if it has one
+ */
+export async function getTintedTexture(
+  packPath: string,
+  isZip: boolean,
+  assetId: string,
+  biome?: string,
+  colormap?: Colormap,
+): Promise<TintedTexture> {
+  return invoke<TintedTexture>("get_tinted_texture", {
+    packPath,
+    isZip,
+    assetId,
+    biome,
+    colormap,
+  });
+}
+
+/**
+ * Biomes getTintedTexture accepts
+ */
+export async function listBiomes(): Promise<BiomeClimate[]> {
+  return invoke<BiomeClimate[]>("list_biomes");
+}
+
+/**
+ * Default pack.mcmeta description template for builds
+ */
+export async function getDescriptionTemplate(): Promise<string | null> {
+  return invoke<string | null>("get_description_template");
+}
+
+/**
+ * Set the default description template; null or blank turns it off
+ */
+export async function setDescriptionTemplate(template: string | null): Promise<string | null> {
+  return invoke<string | null>("set_description_template", { template });
+}
+
+/**
+ * Preview a description template with today's date
+ */
+export async function renderPackDescription(
+  template: string,
+  context: { projectName: string; packNames: string[] },
+): Promise<string> {
+  return invoke<string>("render_pack_description", {
+    template,
+    context: { ...context, builtAt: 0 },
+  });
+}
+
+/**
+ * How a saved project uses a pack
+ */
+export interface PackReference {
+  project: string;
+  /** Whether the pack is in the project's pack order */
+  inOrder: boolean;
+  /** Whether the project has the pack disabled */
+  disabled: boolean;
+  /** Overrides picking the pack */
+  overrides: number;
+  /** Simulated stacks naming the pack */
+  simulations: string[];
+}
+
+/**
+ * What removing a pack would affect
+ */
+export interface PackRemovalImpact {
+  packId: string;
+  references: PackReference[];
+}
+
+/**
+ * A pack removed from the library, kept until purged
+ */
+export interface QuarantinedPack {
+  /** Pass to restoreQuarantinedPack / purgeQuarantinedPack */
+  id: string;
+  packId: string;
+  /** Unix seconds */
+  removedAt: number;
+  path: string;
+}
+
+/**
+ * Check which saved projects use a pack before removing it
+ */
+export async function checkPackRemoval(
+  packsDir: string,
+  packId: string,
+): Promise<PackRemovalImpact> {
+  return invoke<PackRemovalImpact>("check_pack_removal", { packsDir, packId });
+}
+
+/**
+ * Remove a pack from the library by moving it to quarantine. Fails with a
+ * validation error naming the projects that use it unless confirmReferenced
+ * is set.
+ */
+export async function quarantinePack(
+  packsDir: string,
+  packId: string,
+  confirmReferenced: boolean,
+): Promise<QuarantinedPack> {
+  return invoke<QuarantinedPack>("quarantine_pack", {
+    packsDir,
+    packId,
+    confirmReferenced,
+  });
+}
+
+/**
+ * List removed packs, most recent first
+ */
+export async function listQuarantinedPacks(
+  packsDir: string,
+): Promise<QuarantinedPack[]> {
+  return invoke<QuarantinedPack[]>("list_quarantined_packs", { packsDir });
+}
+
+/**
+ * Move a removed pack back into the library
+ * @returns Path of the restored pack
+ */
+export async function restoreQuarantinedPack(
+  packsDir: string,
+  quarantineId: string,
+): Promise<string> {
+  return invoke<string>("restore_quarantined_pack", { packsDir, quarantineId });
+}
+
+/**
+ * Permanently delete a removed pack
+ */
+export async function purgeQuarantinedPack(
+  packsDir: string,
+  quarantineId: string,
+): Promise<void> {
+  return invoke<void>("purge_quarantined_pack", { packsDir, quarantineId });
+}
+
+/**
+ * A custom_model_data value declared by a pack
+ */
+export interface CmdEntry {
+  /** Item ID (e.g. "minecraft:diamond_sword") */
+  item: string;
+  value: number;
+  /** Model the value selects */
+  model: string;
+  packId: string;
+  /** File the value was declared in, relative to the pack root */
+  file: string;
+  source: "legacyOverride" | "itemDefinition";
+}
+
+/**
+ * Custom model data usage across packs
+ */
+export interface CmdReport {
+  items: Array<{ item: string; values: number[]; entries: CmdEntry[] }>;
+  /** Item/value pairs mapped to more than one model */
+  collisions: Array<{ item: string; value: number; entries: CmdEntry[] }>;
+  totalEntries: number;
+}
+
+/**
+ * Get all custom_model_data values in use per item, flagging collisions
+ * @param packIds - Packs to include (all packs if omitted)
+ */
+export async function getCustomModelDataReport(
+  packsDir: string,
+  packIds?: string[],
+): Promise<CmdReport> {
+  return invoke<CmdReport>("get_custom_model_data_report", {
+    packsDir,
+    packIds,
+  });
+}
+
+/**
+ * Write the custom model data report as CSV
+ * @returns Path of the written file
+ */
+export async function exportCustomModelDataReport(
+  packsDir: string,
+  outputPath: string,
+  packIds?: string[],
+): Promise<string> {
+  return invoke<string>("export_custom_model_data_report", {
+    packsDir,
+    packIds,
+    outputPath,
+  });
+}
+
+/**
+ * A custom namespace provided by more than one pack
+ */
+export interface NamespaceCollision {
+  namespace: string;
+  /** Packs providing the namespace, highest priority first */
+  packIds: string[];
+  totalAssets: number;
+  /** Assets provided by more than one of the packs */
+  sharedAssets: number;
+  /** Pack to keep if the namespace is excluded from the others */
+  suggestedOwner: string;
+}
+
+/**
+ * Find custom namespaces (anything but minecraft:) shipped by several packs
+ *
+ * Resolve a collision by passing `{ [namespace]: owner }` as
+ * `namespaceOwners` when building.
+ */
+export async function detectNamespaceCollisions(
+  packsDir: string,
+  packOrder: string[],
+): Promise<NamespaceCollision[]> {
+  return invoke<NamespaceCollision[]>("detect_namespace_collisions", {
+    packsDir,
+    packOrder,
+  });
+}
+
+/**
+ * A parsed OptiFine CTM .properties file
+ */
+export interface CtmDefinition {
+  /** CTM asset the file belongs to (e.g. "minecraft:ctm/glass") */
+  assetId: string;
+  file: string;
+  /** Connection method (e.g. "ctm", "horizontal", "random") */
+  method?: string;
+  /** Blocks it applies to, without namespace or state */
+  blocks: string[];
+}
+
+/**
+ * A block with connected textures from more than one pack
+ */
+export interface CtmConflict {
+  block: string;
+  /** Packs defining CTM for the block, highest priority first */
+  packIds: string[];
+  /** CTM assets involved; more than one means all reach the output */
+  assetIds: string[];
+}
+
+export interface CtmReport {
+  /** Pack ID -> definitions (packs without CTM omitted) */
+  definitions: Record<string, CtmDefinition[]>;
+  conflicts: CtmConflict[];
+}
+
+/**
+ * List OptiFine connected texture definitions and blocks several packs define
+ *
+ * @param packOrder - Enabled packs in priority order; all packs if empty
+ */
+export async function getCtmReport(
+  packsDir: string,
+  packOrder: string[],
+): Promise<CtmReport> {
+  return invoke<CtmReport>("get_ctm_report", { packsDir, packOrder });
+}
+
+export type PackFeature =
+  | "connectedTextures"
+  | "customItemTextures"
+  | "emissive"
+  | "customEntityModels"
+  | "randomEntities"
+  | "customSky"
+  | "coreShaders";
+
+/**
+ * Mods needed for one detected feature
+ */
+export interface FeatureRequirement {
+  feature: PackFeature;
+  label: string;
+  /** Fabric/Quilt mod providing the feature, if one is needed */
+  modName?: string;
+  optifineSupported: boolean;
+  /** Packs using the feature */
+  packIds: string[];
+}
+
+/**
+ * Mods a merged pack needs to work as intended
+ */
+export interface RequirementsSummary {
+  requirements: FeatureRequirement[];
+  /** e.g. "Requires Continuity + CIT Resewn, or OptiFine" */
+  summary: string;
+  notes: string[];
+}
+
+/**
+ * Summarize the mods a merge of the given packs would need
+ */
+export async function getFeatureRequirements(
+  packsDir: string,
+  packOrder: string[],
+): Promise<RequirementsSummary> {
+  return invoke<RequirementsSummary>("get_feature_requirements", {
+    packsDir,
+    packOrder,
+  });
+}
+
+export type MipmapIssueKind = "nonPowerOfTwo" | "nonSquareFrames";
+
+/**
+ * A block/item texture likely to render badly with mipmaps
+ */
+export interface MipmapIssue {
+  kind: MipmapIssueKind;
+  /** Texture path relative to the pack root */
+  file: string;
+  width: number;
+  height: number;
+  /** [width, height] of one frame, for animated textures */
+  frameSize?: [number, number];
+  message: string;
+}
+
+/**
+ * A solid block texture with stray semi-transparent pixels
+ */
+export interface AlphaIssue {
+  /** Texture path relative to the pack root */
+  file: string;
+  /** e.g. "minecraft:block/stone" */
+  assetId: string;
+  semiTransparentPixels: number;
+  totalPixels: number;
+  message: string;
+}
+
+/**
+ * Health warnings for one pack
+ */
+export interface PackHealthReport {
+  packId: string;
+  packName: string;
+  texturesChecked: number;
+  warnings: MipmapIssue[];
+  alphaWarnings: AlphaIssue[];
+}
+
+/**
+ * Check packs for textures that break mipmaps or have stray transparency
+ *
+ * @param packIds - Packs to check (all packs if omitted)
+ */
+export async function getPackHealthReport(
+  packsDir: string,
+  packIds?: string[],
+): Promise<PackHealthReport[]> {
+  return invoke<PackHealthReport[]>("get_pack_health_report", {
+    packsDir,
+    packIds,
+  });
+}
+
+/**
+ * Coverage and texture dependencies of one pack
+ */
+export interface PackCoverage {
+  packId: string;
+  /** Textures the pack provides */
+  textures: number;
+  /** Share (0-1) of the textures any scanned pack provides */
+  coverage: number;
+  /** Packs providing textures this pack's models use but it doesn't ship */
+  dependsOn: string[];
+  /** Why the pack was placed where it is */
+  reason: string;
+  /** Pack with the most similar palette */
+  styleMatch?: StyleMatch | null;
+}
+
+/**
+ * The pack closest in color to another
+ */
+export interface StyleMatch {
+  packId: string;
+  packName: string;
+  /** Palette similarity (0-1) */
+  similarity: number;
+}
+
+/**
+ * A suggested starting pack order
+ */
+export interface PackOrderSuggestion {
+  /** Pack IDs in priority order (first = highest) */
+  packOrder: string[];
+  /** Per-pack stats, in the same order */
+  packs: PackCoverage[];
+}
+
+/**
+ * Suggest a starting pack order: addons on top, full-coverage packs at the bottom
+ *
+ * @param packIds - Packs to order (all packs if omitted)
+ */
+export async function suggestPackOrder(
+  packsDir: string,
+  packIds?: string[],
+): Promise<PackOrderSuggestion> {
+  return invoke<PackOrderSuggestion>("suggest_pack_order", {
+    packsDir,
+    packIds,
+  });
+}
+
+/**
+ * One dominant color of a pack
+ */
+export interface PaletteColor {
+  /** "#rrggbb" */
+  hex: string;
+  rgb: [number, number, number];
+  /** Share of sampled pixels (0-1) */
+  weight: number;
+}
+
+/**
+ * Color palette and overall mood of a pack, sampled from block textures
+ */
+export interface PackPalette {
+  packId: string;
+  packName: string;
+  /** Dominant colors, most common first */
+  colors: PaletteColor[];
+  averageColor: string;
+  /** 0-1 */
+  brightness: number;
+  /** 0-1 */
+  saturation: number;
+  texturesSampled: number;
+}
+
+/**
+ * Extract the dominant color palette of each pack
+ *
+ * @param packIds - Packs to include (all packs if omitted)
+ */
+export async function getPackPalettes(
+  packsDir: string,
+  packIds?: string[],
+): Promise<PackPalette[]> {
+  return invoke<PackPalette[]>("get_pack_palettes", {
+    packsDir,
+    packIds,
+  });
+}
+
+/**
+ * SHA-1 of a built pack, with how long it took to compute
+ */
+export interface Sha1Result {
+  sha1: string;
+  durationMs: number;
+}
+
+/**
+ * Compute the SHA-1 of a built pack (folder or zip) for server distribution
+ */
+export async function computeOutputSha1(outputPath: string): Promise<Sha1Result> {
+  return invoke<Sha1Result>("compute_output_sha1", { outputPath });
+}
+
+/**
+ * Thread pool overrides; omitted values use the detected defaults
+ */
+export interface ParallelismSettings {
+  /** Threads for CPU-bound work */
+  threadCount?: number | null;
+  /** Threads for file-heavy work (scanning, hashing, copying) */
+  ioParallelism?: number | null;
+}
+
+export type DriveKind = "ssd" | "hdd" | "unknown";
+
+/**
+ * Thread counts in effect
+ */
+export interface EffectiveParallelism {
+  threadCount: number;
+  ioParallelism: number;
+  cpuCount: number;
+  driveKind: DriveKind;
+}
+
+/**
+ * Saved thread pool settings and what they resolve to
+ */
+export interface ParallelismStatus {
+  settings: ParallelismSettings;
+  /** Thread counts in effect (null before the pools are configured) */
+  active: EffectiveParallelism | null;
+  /** Thread counts the saved settings resolve to on the next start */
+  nextStart: EffectiveParallelism;
+}
+
+/**
+ * Get thread pool settings
+ *
+ * @param packsDir - Packs directory used to detect the drive type
+ */
+export async function getParallelismSettings(
+  packsDir?: string,
+): Promise<ParallelismStatus> {
+  return invoke<ParallelismStatus>("get_parallelism_settings", { packsDir });
+}
+
+/**
+ * Save thread pool settings (applied on the next start)
+ */
+export async function setParallelismSettings(
+  parallelism: ParallelismSettings,
+): Promise<ParallelismSettings> {
+  return invoke<ParallelismSettings>("set_parallelism_settings", {
+    parallelism,
+  });
+}
+
+export type BuildPhase =
+  | "copying"
+  | "postProcessing"
+  | "waitingForGame"
+  | "finalizing";
+
+/**
+ * Journal of a build that didn't finish (crash or app closed mid-build)
+ */
+export interface InterruptedBuild {
+  outputDir: string;
+  stagingDir: string;
+  phase: BuildPhase;
+  filesWritten: number;
+  lastFile?: string;
+  /** Seconds since the Unix epoch */
+  startedAt: number;
+  updatedAt: number;
+  /** Whether staged files are still there to resume from */
+  stagingExists: boolean;
+}
+
+/**
+ * List builds that were interrupted, most recent first (check on launch)
+ */
+export async function listInterruptedBuilds(): Promise<InterruptedBuild[]> {
+  return invoke<InterruptedBuild[]>("list_interrupted_builds");
+}
+
+/**
+ * Remove an interrupted build's staging folder; the output is left untouched
+ */
+export async function discardInterruptedBuild(
+  outputDir: string,
+): Promise<boolean> {
+  return invoke<boolean>("discard_interrupted_build", { outputDir });
+}
+
+/**
+ * Clear a lock left behind by a crashed task, from an INSTANCE_BUSY
+ * error's path
+ *
+ * @param force - Clear it even if the holder still looks alive
+ * @returns Whether a lock was removed
+ */
+export async function clearStaleLock(
+  lockPath: string,
+  force?: boolean,
+): Promise<boolean> {
+  return invoke<boolean>("clear_stale_lock", { lockPath, force });
+}
+
+/**
+ * Resume an interrupted build, skipping files it already wrote
+ *
+ * @returns Build log
+ */
+export async function resumeInterruptedBuild(
+  outputDir: string,
+  jobId?: string,
+): Promise<string> {
+  return invoke<string>("resume_interrupted_build", { outputDir, jobId });
+}
+
+/**
+ * What builds do when Minecraft is running from the output's instance:
+ * install anyway with a warning, wait for the game to exit, or queue the
+ * install for applyQueuedInstalls
+ */
+export type RunningGamePolicy = "warn" | "defer" | "queue";
+
+/**
+ * A running Minecraft process
+ */
+export interface RunningGame {
+  pid: number;
+  /** Game folder (the one holding options.txt), when it could be told */
+  gameDir: string | null;
+}
+
+/**
+ * A finished build waiting for the game to exit before being installed
+ */
+export interface QueuedInstall {
+  outputDir: string;
+  stagingDir: string;
+  packsDir: string;
+  inputFingerprint: string;
+  /** Unix seconds */
+  queuedAt: number;
+}
+
+/**
+ * List running Minecraft processes, optionally only those that may be
+ * using an output folder
+ */
+export async function getRunningGames(
+  targetDir?: string,
+): Promise<RunningGame[]> {
+  return invoke<RunningGame[]>("get_running_games", { targetDir });
+}
+
+/**
+ * List builds waiting for Minecraft to exit
+ */
+export async function listQueuedInstalls(): Promise<QueuedInstall[]> {
+  return invoke<QueuedInstall[]>("list_queued_installs");
+}
+
+/**
+ * Install queued builds whose game has exited
+ * @returns A log line per queued build
+ */
+export async function applyQueuedInstalls(): Promise<string[]> {
+  return invoke<string[]>("apply_queued_installs");
+}
+
+/**
+ * Drop a queued build without installing it
+ */
+export async function discardQueuedInstall(
+  outputDir: string,
+): Promise<boolean> {
+  return invoke<boolean>("discard_queued_install", { outputDir });
+}
+
+export async function getRunningGamePolicy(): Promise<RunningGamePolicy> {
+  return invoke<RunningGamePolicy>("get_running_game_policy");
+}
+
+export async function setRunningGamePolicy(
+  policy: RunningGamePolicy,
+): Promise<RunningGamePolicy> {
+  return invoke<RunningGamePolicy>("set_running_game_policy", { policy });
+}
+
+export type IssueSeverity = "error" | "warning" | "info";
+
+export type StackIssueKind =
+  | "missingPack"
+  | "formatMismatch"
+  | "forcedIncompatible"
+  | "override"
+  | "namespaceCollision"
+  | "missingDependency";
+
+/**
+ * One finding about an instance's pack stack
+ */
+export interface StackIssue {
+  severity: IssueSeverity;
+  kind: StackIssueKind;
+  /** Packs involved, highest priority first */
+  packIds: string[];
+  message: string;
+}
+
+/**
+ * One pack enabled in options.txt
+ */
+export interface StackEntry {
+  /** Entry as written in options.txt (e.g. "file/Faithful.zip", "vanilla") */
+  entry: string;
+  /** Pack ID in the resourcepacks folder (null for built-in packs) */
+  packId: string | null;
+  builtin: boolean;
+  present: boolean;
+  packFormat: number | null;
+}
+
+/**
+ * Result of checking an instance's enabled pack stack
+ */
+export interface StackReport {
+  gameDir: string;
+  optionsFound: boolean;
+  dataVersion: number | null;
+  gamePackFormat: number | null;
+  /** Enabled packs, highest priority first */
+  packs: StackEntry[];
+  issues: StackIssue[];
+  requirements: FeatureRequirement[];
+  summary: string;
+}
+
+/**
+ * Check the packs an instance has enabled without building anything
+ *
+ * @param instanceDir - Instance or game folder (the one holding options.txt)
+ */
+export async function checkInstanceStack(
+  instanceDir: string,
+): Promise<StackReport> {
+  return invoke<StackReport>("check_instance_stack", { instanceDir });
+}
+
+/**
+ * Pack a preview scene texture came from
+ */
+export interface SceneTextureSource {
+  texture: string;
+  /** "vanilla" for the vanilla cache, null if no pack provides it */
+  packId: string | null;
+}
+
+/**
+ * A rendered preview scene
+ */
+export interface ScenePreview {
+  outputPath: string;
+  width: number;
+  height: number;
+  blocks: number;
+  textures: SceneTextureSource[];
+  /** Textures drawn as the missing-texture checkerboard */
+  missingTextures: string[];
+}
+
+/**
+ * Build settings besides the order and overrides that decide which pack wins
+ * an asset; previews pass them so they show what a build would produce
+ */
+export interface WinnerRules {
+  /** Packs left out of the merge, along with overrides picking them */
+  disabledPacks?: string[];
+  /** Namespace -> the only pack allowed to provide it */
+  namespaceOwners?: Record<string, string>;
+}
+
+/**
+ * Render a small scene (terrain, a house, a tree) with the textures a merge
+ * would use, to judge blocks in context
+ *
+ * @param tileSize - Width of one block in pixels (defaults to 32)
+ * @param colorFilter - Optional color blindness simulation
+ * @param rules - Disabled packs and namespace owners, as in a build
+ */
+export async function renderPreviewScene(
+  packsDir: string,
+  packOrder: string[],
+  overrides: Record<string, OverrideWirePayload>,
+  outputPath: string,
+  tileSize?: number,
+  colorFilter?: ColorVisionFilter,
+  rules?: WinnerRules,
+): Promise<ScenePreview> {
+  return invoke<ScenePreview>("render_preview_scene", {
+    packsDir,
+    packOrder,
+    overrides,
+    outputPath,
+    tileSize,
+    colorFilter,
+    rules,
+  });
+}
+
+/**
+ * A written contact sheet
+ */
+export interface ContactSheetExport {
+  outputPath: string;
+  width: number;
+  height: number;
+  /** Textures on the sheet */
+  textures: number;
+  /** Textures left off because the sheet was full */
+  truncated: number;
+}
+
+/**
+ * Export a labeled grid of one texture category as a PNG
+ *
+ * @param packOrder - Packs in priority order; pass a single pack to export just that pack
+ * @param category - Texture folder under textures/ (e.g. "block", "item")
+ * @param tileSize - Size of each texture in pixels, 8 to 256 (defaults to 64)
+ * @param columns - Textures per row, up to 64 (defaults to 12)
+ */
+export async function exportContactSheet(
+  packsDir: string,
+  packOrder: string[],
+  overrides: Record<string, OverrideWirePayload>,
+  category: string,
+  outputPath: string,
+  tileSize?: number,
+  columns?: number,
+): Promise<ContactSheetExport> {
+  return invoke<ContactSheetExport>("export_contact_sheet", {
+    packsDir,
+    packOrder,
+    overrides,
+    category,
+    outputPath,
+    tileSize,
+    columns,
+  });
+}
+
+/**
+ * How before/after comparisons are arranged
+ * - sideBySide: vanilla and merged next to each other, with captions
+ * - split: left half vanilla, right half merged, in one image
+ * - pair: separate _before/_after images of the same size, for a slider
+ */
+export type ComparisonLayout = "sideBySide" | "split" | "pair";
+
+/**
+ * Comparison written for one asset
+ */
+export interface ComparisonImage {
+  assetId: string;
+  /** Pack the merged texture comes from */
+  mergedPackId: string;
+  /** Written files (two for the pair layout) */
+  files: string[];
+  width: number;
+  height: number;
+}
+
+/**
+ * Result of a comparison export
+ */
+export interface ComparisonExport {
+  images: ComparisonImage[];
+  /** Assets that couldn't be compared, with the reason */
+  errors: string[];
+}
+
+/**
+ * Generate vanilla vs merged comparison PNGs for selected textures
+ *
+ * @param assetIds - Textures to compare (e.g. "minecraft:block/stone")
+ * @param size - Output width of each texture in pixels (max 2048)
+ * @param outputDir - Folder to write the images into
+ * @param rules - Disabled packs and namespace owners, as in a build
+ */
+export async function exportComparisonImages(
+  packsDir: string,
+  packOrder: string[],
+  overrides: Record<string, OverrideWirePayload>,
+  assetIds: string[],
+  layout: ComparisonLayout,
+  size: number,
+  outputDir: string,
+  rules?: WinnerRules,
+): Promise<ComparisonExport> {
+  return invoke<ComparisonExport>("export_comparison_images", {
+    packsDir,
+    packOrder,
+    overrides,
+    assetIds,
+    layout,
+    size,
+    outputDir,
+    rules,
+  });
+}
+
+/**
+ * Which documentation images to render; omitted fields use the defaults
+ */
+export interface DocPreviewOptions {
+  /** One image per asset: a block render, or the texture scaled up (default true) */
+  renders?: boolean;
+  /** Also write vanilla comparisons in this layout */
+  comparisonLayout?: ComparisonLayout | null;
+  /** A contact sheet per texture category of the assets (default true) */
+  contactSheets?: boolean;
+  /** Also render the preview scene (default false) */
+  scene?: boolean;
+  /** Width of renders and comparisons in pixels (default 128, max 256) */
+  size?: number;
+}
+
+/**
+ * What a documentation preview batch wrote
+ */
+export interface DocPreviewSummary {
+  outputDir: string;
+  /** Images relative to outputDir, also listed in previews.json */
+  files: string[];
+  /** Assets or images that couldn't be rendered */
+  errors: string[];
+}
+
+/**
+ * Render documentation images for a list of assets into one folder, e.g.
+ * after each build
+ *
+ * @param assetIds - Textures to render (e.g. "minecraft:block/stone")
+ * @param outputDir - Folder for the images and previews.json
+ * @param rules - Disabled packs and namespace owners, as in a build
+ */
+export async function renderDocPreviews(
+  packsDir: string,
+  packOrder: string[],
+  overrides: Record<string, OverrideWirePayload>,
+  assetIds: string[],
+  outputDir: string,
+  options?: DocPreviewOptions,
+  rules?: WinnerRules,
+): Promise<DocPreviewSummary> {
+  return invoke<DocPreviewSummary>("render_doc_previews", {
+    packsDir,
+    packOrder,
+    overrides,
+    assetIds,
+    outputDir,
+    options,
+    rules,
+  });
+}
+
+/**
+ * Color blindness types the backend can simulate
+ */
+export type ColorVisionFilter = "protanopia" | "deuteranopia" | "tritanopia";
+
+/**
+ * Write a copy of a preview image (texture, contact sheet, comparison) as
+ * seen with color blindness
+ *
+ * @param outputPath - PNG to write; may equal inputPath
+ * @returns Path of the written image
+ */
+export async function simulateColorVision(
+  inputPath: string,
+  filter: ColorVisionFilter,
+  outputPath: string,
+): Promise<string> {
+  return invoke<string>("simulate_color_vision", {
+    inputPath,
+    filter,
+    outputPath,
+  });
+}
+
+/**
+ * Check the packs folder for cloud sync, network and read-only problems
+ */
+export async function inspectPacksFolder(
+  packsDir: string,
+): Promise<FolderAccessReport> {
+  return invoke<FolderAccessReport>("inspect_packs_folder", { packsDir });
+}
+
+/**
+ * Download result for one cloud-only pack
+ * - downloaded: contents are now on disk
+ * - requested: the sync client downloads in the background; rescan later
+ */
+export interface PlaceholderDownload {
+  name: string;
+  status: "downloaded" | "requested" | "failed";
+  error: string | null;
+}
+
+/**
+ * Ask the sync client to download packs that are only in the cloud
+ */
+export async function downloadCloudPlaceholders(
+  packsDir: string,
+): Promise<PlaceholderDownload[]> {
+  return invoke<PlaceholderDownload[]>("download_cloud_placeholders", {
+    packsDir,
+  });
+}
+
+/**
+ * Record of the last finished build of an output folder
+ */
+export interface BuildStamp {
+  outputDir: string;
+  inputFingerprint: string;
+  outputSha1: string;
+  /** Unix timestamp (seconds) */
+  builtAt: number;
+}
+
+/**
+ * Output status in one instance, for "Installed • Up to date / Outdated" badges
+ */
+export interface InstanceInstallStatus {
+  instance: { launcher: string; name: string; path: string };
+  gameDir: string;
+  installedPath: string | null;
+  /** outputFolder: the project builds straight into this instance */
+  matchedBy: "outputFolder" | "name" | "hash" | null;
+  /** Listed in the instance's options.txt */
+  enabled: boolean;
+  state: "notInstalled" | "upToDate" | "outdated" | "unknown";
+  /** Why the copy is outdated or unknown */
+  reason: string | null;
+}
+
+/**
+ * Install status of a project's output across detected instances
+ */
+export interface AppliedPackReport {
+  outputDir: string;
+  lastBuild: BuildStamp | null;
+  /** The project changed since the last build */
+  projectChanged: boolean;
+  instances: InstanceInstallStatus[];
+}
+
+/**
+ * Check which detected instances have a project's output installed, enabled
+ * and up to date
+ *
+ * @param project - Current project (need not be saved)
+ */
+export async function getAppliedPackStatus(
+  project: Project,
+): Promise<AppliedPackReport> {
+  return invoke<AppliedPackReport>("get_applied_pack_status", { project });
+}
+
+/**
+ * An asset won by different packs under two simulated stacks
+ */
+export interface WinnerChange {
+  assetId: string;
+  /** Winning pack under stack A; null if no pack provides it there */
+  a: string | null;
+  b: string | null;
+}
+
+/**
+ * Winners of two simulated stacks and how they differ
+ */
+export interface StackSimulation {
+  aName: string;
+  bName: string;
+  /** Asset ID -> winning pack under each stack */
+  aWinners: Record<string, string>;
+  bWinners: Record<string, string>;
+  /** Assets whose winner differs, sorted by asset ID */
+  changed: WinnerChange[];
+  /** Assets with the same winner under both stacks */
+  unchanged: number;
+}
+
+/**
+ * Compare two of a project's simulated stacks without building
+ *
+ * @param project - Current project (need not be saved)
+ * @param a - Name of the first stack in project.simulations
+ * @param b - Name of the second stack
+ */
+export async function simulateStacks(
+  project: Project,
+  a: string,
+  b: string,
+): Promise<StackSimulation> {
+  return invoke<StackSimulation>("simulate_stacks", { project, a, b });
+}
+
+/** How an old pack was matched to a new one */
+export type LineageMatch = "sameId" | "family" | "knownPack" | "contentHash";
+
+/**
+ * Where a pack of the older project ended up
+ */
+export interface PackLineage {
+  oldPackId: string;
+  /** null if no new pack continues it */
+  newPackId: string | null;
+  matchedBy: LineageMatch | null;
+}
+
+/**
+ * An override of the older project that couldn't be carried over
+ */
+export interface DroppedOverride {
+  assetId: string;
+  oldPackId: string;
+  /** Pack the old one was matched to, when it no longer has the asset */
+  newPackId: string | null;
+  reason: "packMissing" | "assetMissing";
+}
+
+/**
+ * Overrides seeded from an older project
+ */
+export interface OverrideSeed {
+  overrides: Record<string, OverrideWirePayload>;
+  lineage: PackLineage[];
+  dropped: DroppedOverride[];
+}
+
+/**
+ * Seed a new project's overrides from a saved project, matching each old
+ * pack to its newer version by ID, name family, known pack or content
+ *
+ * @param projectName - Saved project to take the overrides from
+ * @param packsDir - Packs directory of the new project
+ */
+export async function seedOverridesFromProject(
+  projectName: string,
+  packsDir: string,
+): Promise<OverrideSeed> {
+  return invoke<OverrideSeed>("seed_overrides_from_project", { projectName, packsDir });
+}
+
+export type ReportKind = "packs" | "conflicts";
+export type ReportFormat = "csv" | "markdown";
+
+/**
+ * Write the pack inventory or conflict report as CSV or Markdown for
+ * sharing outside the app
+ *
+ * @param packOrder - Enabled pack IDs in priority order
+ * @param overrides - Per-asset pack selections (used by the conflict report)
+ * @returns Path of the written file
+ */
+export async function exportPackReport(
+  packsDir: string,
+  packOrder: string[],
+  overrides: Record<string, OverrideWirePayload>,
+  kind: ReportKind,
+  format: ReportFormat,
+  outputPath: string,
+): Promise<string> {
+  return invoke<string>("export_pack_report", {
+    packsDir,
+    packOrder,
+    overrides,
+    kind,
+    format,
+    outputPath,
+  });
+}
+
+/**
+ * How much a conflict matters, least first
+ */
+export type ConflictSeverity =
+  | "negligible"
+  | "low"
+  | "medium"
+  | "high"
+  | "critical";
+
+/**
+ * An asset provided by more than one enabled pack
+ */
+export interface AssetConflict {
+  assetId: string;
+  winner: string;
+  reason: "override" | "priority";
+  /** Other enabled providers, highest priority first */
+  losers: string[];
+  /** Whether the losers' copies are byte-identical to the winner's */
+  identical: boolean;
+  severity: ConflictSeverity;
+}
+
+/**
+ * Conflicts of one namespace and asset category
+ */
+export interface ConflictGroup {
+  namespace: string;
+  /** First folder of the asset path (e.g. "block", "entity", "cem") */
+  category: string;
+  /** Most severe of the group's conflicts */
+  severity: ConflictSeverity;
+  /** Most severe first, then by asset ID */
+  conflicts: AssetConflict[];
+}
+
+/**
+ * How many assets one pack takes from another
+ */
+export interface PackOverride {
+  winner: string;
+  loser: string;
+  count: number;
+  /** Category -> assets */
+  byCategory: Record<string, number>;
+}
+
+export interface ConflictReport {
+  /** Most severe first, then by namespace and category */
+  groups: ConflictGroup[];
+  /** Most assets first */
+  packOverrides: PackOverride[];
+  totalConflicts: number;
+  /** Conflicts won through an override */
+  overridden: number;
+  /** Unresolved severe conflicts to confirm before building anyway */
+  needsConfirmation: string[];
+}
+
+/**
+ * Work out which pack wins each contested asset and which packs lose it
+ *
+ * @param packOrder - Enabled pack IDs in priority order
+ * @param overrides - Per-asset pack selections
+ * @param namespaces - Only report these namespaces (all if omitted)
+ */
+export async function getConflictReport(
+  packsDir: string,
+  packOrder: string[],
+  overrides: Record<string, OverrideWirePayload>,
+  namespaces?: string[],
+): Promise<ConflictReport> {
+  return invoke<ConflictReport>("get_conflict_report", {
+    packsDir,
+    packOrder,
+    overrides,
+    namespaces,
+  });
+}
+
+/**
+ * Conflicts the user accepted or put off in a project
+ */
+export interface ResolutionState {
+  /** Asset IDs whose winner was accepted */
+  resolved: string[];
+  /** Asset IDs left for later */
+  skipped: string[];
+}
+
+export type ResolutionAction = "resolve" | "skip" | "reopen";
+
+/**
+ * A conflict with its place in the resolution queue
+ */
+export interface QueuedConflict {
+  namespace: string;
+  category: string;
+  /** 1-based position among the queued conflicts */
+  position: number;
+  /** Whether the user skipped it before */
+  skipped: boolean;
+  conflict: AssetConflict;
+}
+
+/**
+ * Next conflict to resolve and how far through the queue the user is
+ */
+export interface ResolutionProgress {
+  /** Null once nothing is left (or only skipped conflicts, unless revisited) */
+  next: QueuedConflict | null;
+  total: number;
+  resolved: number;
+  skipped: number;
+  /** Neither resolved nor skipped */
+  remaining: number;
+  /** Resolved share of the queue, 0-100 */
+  percentComplete: number;
+}
+
+/**
+ * Get the next conflict to resolve in a saved project, most severe first
+ *
+ * @param revisitSkipped - Return skipped conflicts once nothing else is left
+ */
+export async function getNextConflict(
+  projectName: string,
+  revisitSkipped = false,
+): Promise<ResolutionProgress> {
+  return invoke<ResolutionProgress>("get_next_conflict", {
+    projectName,
+    revisitSkipped,
+  });
+}
+
+/**
+ * Mark a conflict resolved, skipped or open again, then get the next one
+ */
+export async function recordConflictResolution(
+  projectName: string,
+  assetId: string,
+  action: ResolutionAction,
+  revisitSkipped = false,
+): Promise<ResolutionProgress> {
+  return invoke<ResolutionProgress>("record_conflict_resolution", {
+    projectName,
+    assetId,
+    action,
+    revisitSkipped,
+  });
+}
+
+/**
+ * Forget which conflicts of a project were resolved or skipped
+ * @returns The updated project
+ */
+export async function resetConflictResolution(
+  projectName: string,
+): Promise<Project> {
+  return invoke<Project>("reset_conflict_resolution", { projectName });
+}
+
+/**
+ * One pack's share of a namespace
+ */
+export interface NamespacePackCoverage {
+  packId: string;
+  /** Assets of the namespace the pack provides */
+  assets: number;
+  /** Share (0-1) of the namespace's assets */
+  coverage: number;
+  /** Assets of the namespace the pack wins in the merge */
+  wins: number;
+}
+
+export interface NamespaceCoverage {
+  namespace: string;
+  totalAssets: number;
+  /** Assets provided by more than one pack */
+  contestedAssets: number;
+  /** Highest priority first */
+  packs: NamespacePackCoverage[];
+}
+
+/**
+ * Break a pack order down by namespace (minecraft, create, ...)
+ *
+ * @param packOrder - Enabled pack IDs in priority order
+ * @param overrides - Per-asset pack selections
+ */
+export async function getNamespaceCoverage(
+  packsDir: string,
+  packOrder: string[],
+  overrides: Record<string, OverrideWirePayload>,
+): Promise<NamespaceCoverage[]> {
+  return invoke<NamespaceCoverage[]>("get_namespace_coverage", {
+    packsDir,
+    packOrder,
+    overrides,
+  });
+}
+
+export type PackEntryKind =
+  | "folder"
+  | "image"
+  | "json"
+  | "mcmeta"
+  | "sound"
+  | "shader"
+  | "properties"
+  | "text"
+  | "other";
+
+/**
+ * A folder or file in a pack's file tree
+ */
+export interface PackTreeEntry {
+  /** File or folder name ("" for the pack root) */
+  name: string;
+  /** "/"-separated path from the pack root */
+  path: string;
+  kind: PackEntryKind;
+  /** Size in bytes (total of all files for folders) */
+  size: number;
+  /** Number of files below a folder (1 for files) */
+  fileCount: number;
+  /** Child entries, folders first; absent if not loaded yet */
+  children?: PackTreeEntry[];
+}
+
+/**
+ * List a folder of a pack (zip or folder) as a file tree
+ *
+ * Children are loaded lazily: call again with a folder's `path` to expand it.
+ *
+ * @param dir - Folder inside the pack (pack root if omitted)
+ * @param depth - Levels of children to load (defaults to 1)
+ */
+export async function browsePack(
+  packPath: string,
+  isZip: boolean,
+  dir?: string,
+  depth?: number,
+): Promise<PackTreeEntry> {
+  return invoke<PackTreeEntry>("browse_pack", { packPath, isZip, dir, depth });
+}
+
+/**
+ * Show a pack (or any allowed file or folder) in the system file manager.
+ * Files such as zip packs are shown selected in their folder.
+ */
+export async function revealInFileManager(path: string): Promise<void> {
+  return invoke<void>("reveal_in_file_manager", { path });
+}
+
+/**
+ * Copy a single file out of a pack (zip or folder)
+ *
+ * @param filePath - Path of the file inside the pack
+ * @param destination - File to write, or a folder to write it into
+ * @returns Path of the written file
+ */
+export async function extractPackFile(
+  packPath: string,
+  isZip: boolean,
+  filePath: string,
+  destination: string,
+): Promise<string> {
+  return invoke<string>("extract_pack_file", {
+    packPath,
+    isZip,
+    filePath,
+    destination,
+  });
+}
+
+/**
+ * Extract a whole zip pack into a folder, e.g. to edit it.
+ * Progress arrives as "pack-archive-progress" events with [current, total].
+ *
+ * @param destination - Folder to create (may exist only if empty)
+ * @returns Path of the extracted folder
+ */
+export async function extractPackArchive(
+  packPath: string,
+  destination: string,
+): Promise<string> {
+  return invoke<string>("extract_pack_archive", { packPath, destination });
+}
+
+/**
+ * Compress a folder pack back into a zip.
+ * Progress arrives as "pack-archive-progress" events with [current, total].
+ *
+ * @param destination - Zip file to write (replaced if it exists)
+ * @returns Path of the written zip
+ */
+export async function compressPackFolder(
+  folder: string,
+  destination: string,
+): Promise<string> {
+  return invoke<string>("compress_pack_folder", { folder, destination });
+}
+
+/**
+ * Result of repacking a folder pack
+ */
+export interface RepackReport {
+  zipPath: string;
+  /** Files written to the zip */
+  files: number;
+  /** Folder inside the source that holds pack.mcmeta ("" for the source itself) */
+  root: string;
+  /** Junk files left out (.DS_Store, .git, editor projects, ...) */
+  skipped: string[];
+  /** How many of the skipped files were macOS metadata (__MACOSX, ._*) */
+  osMetadataFiles: number;
+}
+
+/**
+ * Zip a folder pack as a clean distributable: pack.mcmeta at the zip root,
+ * junk files stripped and entries in a fixed order.
+ * Progress arrives as "pack-archive-progress" events with [current, total].
+ *
+ * @param folder - Working folder of the pack (pack.mcmeta may be nested)
+ * @param destination - Zip file to write (replaced if it exists)
+ */
+export async function repackPackFolder(
+  folder: string,
+  destination: string,
+): Promise<RepackReport> {
+  return invoke<RepackReport>("repack_pack_folder", { folder, destination });
+}
+
+/** "serverExport" writes a reproducible zip with its SHA-1 for hosting */
+export type MergeOutputFormat = "folder" | "zip" | "serverExport";
+
+/**
+ * What a server offers players along with an exported pack
+ */
+export interface ServerExportOptions {
+  /** Download URL for resource-pack= (left empty to fill in after uploading) */
+  downloadUrl?: string;
+  /** Kick players who decline the pack */
+  require?: boolean;
+  /** Message shown when the server offers the pack */
+  prompt?: string;
+}
+
+/**
+ * A zip ready to host on a server
+ */
+export interface ServerExport {
+  /** Lowercase hex SHA-1 for resource-pack-sha1= */
+  sha1: string;
+  size: number;
+  files: number;
+  /** Lines to paste into server.properties */
+  properties: string;
+}
+
+/**
+ * Where a standalone merge was written
+ */
+export interface MergeSummary {
+  outputPath: string;
+  format: MergeOutputFormat;
+  /** Files in the merged pack, pack.mcmeta and pack.png included */
+  files: number;
+  /** Set for server exports */
+  server?: ServerExport;
+}
+
+/**
+ * Merge packs into a standalone folder or zip, with pack.mcmeta and pack.png
+ * derived from the merged packs. Unlike buildWeaverNest there is no journal
+ * or build stamp.
+ * Progress arrives as "build-progress" events with [filesCopied, total].
+ *
+ * @param packOrder - Pack IDs to merge, highest priority first
+ * @param outputPath - Folder to write into, or zip file to create
+ * @param server - Used with the "serverExport" format
+ */
+export async function buildMergedPack(
+  packsDir: string,
+  packOrder: string[],
+  overrides: Record<string, OverrideWirePayload>,
+  outputPath: string,
+  format: MergeOutputFormat,
+  server?: ServerExportOptions,
+): Promise<MergeSummary> {
+  return invoke<MergeSummary>("build_merged_pack", {
+    packsDir,
+    packOrder,
+    overrides,
+    outputPath,
+    format,
+    server: server ?? null,
+  });
+}
+
+/**
+ * Result of upgrading a pack to a newer pack format
+ */
+export interface MigrationReport {
+  sourceFormat: number;
+  targetFormat: number;
+  /** Folder the migrated pack was written to */
+  outputPath: string;
+  /** Files written, pack.mcmeta included */
+  files: number;
+  renamed: { from: string; to: string }[];
+  /** Files copied that may need updating by hand */
+  unmapped: { path: string; reason: string }[];
+}
+
+/**
+ * Write a copy of a pack upgraded to a newer pack format
+ *
+ * @param targetFormat - Format to migrate to (newest known release if omitted)
+ * @param outputPath - Folder to write the migrated pack to; must not exist
+ */
+export async function migratePack(
+  packsDir: string,
+  packId: string,
+  outputPath: string,
+  targetFormat?: number,
+): Promise<MigrationReport> {
+  return invoke<MigrationReport>("migrate_pack", {
+    packsDir,
+    packId,
+    targetFormat,
+    outputPath,
+  });
+}
+
+/**
+ * URL of a file inside a pack, served by the weaverbird:// protocol
+ *
+ * Usable directly as an <img>/<audio> src or with fetch(); supports range
+ * requests, so audio can seek without loading the whole file.
+ *
+ * @param packPath - Pack folder or ZIP (PackMeta.path)
+ * @param filePath - Path inside the pack (e.g., "assets/minecraft/sounds/dig.ogg")
+ */
+export function packAssetUrl(packPath: string, filePath: string): string {
+  const path = `${encodeURIComponent(packPath)}/${filePath
+    .split("/")
+    .map(encodeURIComponent)
+    .join("/")}`;
+  // Windows webviews serve custom protocols over http://<scheme>.localhost
+  return navigator.userAgent.includes("Windows")
+    ? `http://weaverbird.localhost/asset/${path}`
+    : `weaverbird://asset/${path}`;
+}
+
+/**
+ * A language entry of a pack
+ */
+export interface LangEntry {
+  /** Translation key (e.g. "item.minecraft.diamond_sword") */
+  key: string;
+  value: string;
+  /** Lowercase locale (e.g. "en_us") */
+  locale: string;
+  namespace: string;
+  packId: string;
+  /** Whether the game shows this value (no higher-priority pack has the key) */
+  active: boolean;
+}
+
+export interface LangSearchResult {
+  /** Sorted by key, locale, then pack priority; capped at 500 */
+  entries: LangEntry[];
+  /** Matches before the cap */
+  totalMatches: number;
+}
+
+/**
+ * Find which packs define or rename a translation key or string
+ *
+ * @param query - Text to find in keys and values (case-insensitive)
+ * @param locale - Only search this locale (e.g. "en_us"); all if omitted
+ */
+export async function searchLangEntries(
+  packsDir: string,
+  packOrder: string[],
+  query: string,
+  locale?: string,
+): Promise<LangSearchResult> {
+  return invoke<LangSearchResult>("search_lang_entries", {
+    packsDir,
+    packOrder,
+    query,
+    locale,
+  });
+}
@@ -1,4 +1,5 @@
 import { invoke } from "@tauri-apps/api/core";
+import type { OverrideWirePayload } from "@state";
 
 /**
  * Minecraft block model JSON structure
@@ -8,6 +9,52 @@ export interface BlockModel {
   textures?: Record<string, string>;
   elements?: ModelElement[];
   ambientocclusion?: boolean;
+  /** Broken texture references, now pointing at MISSING_TEXTURE_ID */
+  missingTextures?: MissingTextureRef[];
+  /**
+   * Builtin the parent chain ends in. "generated" models come with flat
+   * layer elements; "entity" models have none and need entity rendering.
+   */
+  builtin?: "generated" | "entity";
+  /** Display transforms per context, as in the model JSON */
+  display?: Partial<Record<DisplayContext, Partial<DisplayTransform>>>;
+  /** Transform for the requested display context, resolved by the backend */
+  displayTransform?: DisplayTransform;
+}
+
+/** Where an item model is shown */
+export type DisplayContext =
+  | "gui"
+  | "ground"
+  | "fixed"
+  | "head"
+  | "thirdperson_righthand"
+  | "thirdperson_lefthand"
+  | "firstperson_righthand"
+  | "firstperson_lefthand";
+
+/**
+ * How a model is posed in a display context: translate (1/16 block units),
+ * rotate (degrees, X then Y then Z), then scale, around the block center
+ */
+export interface DisplayTransform {
+  rotation: [number, number, number];
+  translation: [number, number, number];
+  scale: [number, number, number];
+}
+
+/** Texture ID the backend substitutes for broken references */
+export const MISSING_TEXTURE_ID = "weaverbird:missing";
+
+/**
+ * A texture reference in a model that points at nothing
+ */
+export interface MissingTextureRef {
+  /** Texture variable (e.g. "side") */
+  variable: string;
+  /** What it resolved to (e.g. "minecraft:block/foo" or "#top") */
+  reference: string;
+  reason: string;
 }
 
 /**
@@ -90,17 +137,20 @@ export interface ResolutionResult {
  * @param packId - ID of the resource pack to read from
  * @param modelId - Model ID (e.g., "minecraft:block/dirt" or "block/dirt")
  * @param packsDir - Directory containing resource packs
+ * @param displayContext - Resolve this context's transform into `displayTransform`
  * @returns Fully resolved BlockModel with parent inheritance applied
  */
 export async function readBlockModel(
   packId: string,
   modelId: string,
   packsDir: string,
+  displayContext?: DisplayContext,
 ): Promise<BlockModel> {
   return invoke<BlockModel>("read_block_model", {
     packId,
     modelId,
     packsDir,
+    displayContext,
   });
 }
 
@@ -159,16 +209,285 @@ export async function resolveBlockState(
  * @param packId - ID of the resource pack to read from
  * @param modelId - Model ID (e.g., "minecraft:block/acacia_log_horizontal")
  * @param packsDir - Directory containing resource packs
+ * @param displayContext - Resolve this context's transform into `displayTransform`
+ * @param variant - Blockstate variant whose rotation and uvlock to bake into the elements
  * @returns BlockModel with parent inheritance applied
  */
 export async function loadModelJson(
   packId: string,
   modelId: string,
   packsDir: string,
+  displayContext?: DisplayContext,
+  variant?: ResolvedModel,
 ): Promise<BlockModel> {
   return invoke<BlockModel>("load_model_json", {
     packId,
     modelId,
     packsDir,
+    displayContext,
+    variant,
   });
 }
+
+/**
+ * Model used when an item's predicates reach the given values
+ */
+export interface ItemOverride {
+  /** Predicate -> minimum value (e.g. { custom_model_data: 3 }) */
+  predicate: Record<string, number>;
+  model: string;
+}
+
+/**
+ * An item resolved to the model the game would show
+ */
+export interface ResolvedItemModel {
+  itemId: string;
+  /** Model shown: the item's own, or the override the predicates pick */
+  modelId: string;
+  model: BlockModel;
+  /** Layer textures, bottom first (empty unless the model is generated) */
+  layers: string[];
+  /** Overrides declared by the item's own model, in file order */
+  overrides: ItemOverride[];
+}
+
+/**
+ * Resolve an item (e.g. "minecraft:bow") to the model the game would show
+ *
+ * @param predicates - Predicate values picking an override
+ * @param displayContext - Context whose transform to resolve (gui if omitted)
+ */
+export async function loadItemModel(
+  packId: string,
+  itemId: string,
+  packsDir: string,
+  predicates?: Record<string, number>,
+  displayContext?: DisplayContext,
+): Promise<ResolvedItemModel> {
+  return invoke<ResolvedItemModel>("load_item_model", {
+    packId,
+    itemId,
+    packsDir,
+    predicates,
+    displayContext,
+  });
+}
+
+/**
+ * One pack's model of a contested item
+ */
+export interface ItemModelProvider {
+  packId: string;
+  /** Layer textures the pack's model names directly, bottom first */
+  layers: string[];
+  /** Overrides the pack's model declares */
+  overrides: number;
+}
+
+/**
+ * An item whose model more than one enabled pack provides
+ */
+export interface ItemModelConflict {
+  itemId: string;
+  /** Highest priority provider, whose model the game uses */
+  winner: ItemModelProvider;
+  /** Other enabled providers, highest priority first */
+  losers: ItemModelProvider[];
+  /** Whether a loser's model names other layer textures than the winner's */
+  layersDiffer: boolean;
+  /** Whether a loser declares overrides the winner's model drops */
+  overridesLost: boolean;
+}
+
+/**
+ * Find items whose model more than one enabled pack provides
+ *
+ * @param packOrder - Enabled pack IDs in priority order
+ */
+export async function getItemModelConflicts(
+  packsDir: string,
+  packOrder: string[],
+): Promise<ItemModelConflict[]> {
+  return invoke<ItemModelConflict[]>("get_item_model_conflicts", {
+    packsDir,
+    packOrder,
+  });
+}
+
+/**
+ * Where one face texture comes from
+ */
+export interface FaceTextureSource {
+  /** Texture reference on the face (e.g. "#side") */
+  variable: string;
+  /** Texture ID after resolving variables, null if the variable is undefined */
+  textureId: string | null;
+  /** Texture file inside the source pack */
+  texturePath: string | null;
+  /** Pack a build would take the texture from, null if nothing provides it */
+  packId: string | null;
+}
+
+/**
+ * Texture sources of every face direction of a model
+ */
+export interface FaceTextureBreakdown {
+  modelId: string;
+  /** Direction (north, up, ...) -> textures drawn on it, base layer first */
+  faces: Record<string, FaceTextureSource[]>;
+  /** Packs the faces come from; more than one means the block mixes sources */
+  sourcePacks: string[];
+}
+
+/**
+ * Get the texture and source pack of each face of a model
+ *
+ * @param packId - ID of the pack to load the model from
+ * @param modelId - Model ID (e.g., "minecraft:block/oak_log")
+ * @param packsDir - Directory containing resource packs
+ * @param packOrder - Enabled pack IDs in priority order
+ * @param overrides - Asset ID -> chosen pack
+ * @param variant - Blockstate variant whose rotation to apply first
+ * @returns Per-face breakdown, e.g. top from one pack and sides from another
+ */
+export async function getFaceTextures(
+  packId: string,
+  modelId: string,
+  packsDir: string,
+  packOrder: string[],
+  overrides: Record<string, OverrideWirePayload>,
+  variant?: ResolvedModel,
+): Promise<FaceTextureBreakdown> {
+  return invoke<FaceTextureBreakdown>("get_face_textures", {
+    packId,
+    modelId,
+    packsDir,
+    packOrder,
+    overrides,
+    variant,
+  });
+}
+
+/**
+ * How far apart two versions of a model are: "cosmetic" keeps the shape and
+ * only changes textures, face mapping or display transforms
+ */
+export type ModelDiffKind = "identical" | "cosmetic" | "geometric";
+
+export interface TextureChange {
+  variable: string;
+  left: string | null;
+  right: string | null;
+}
+
+/**
+ * Differences between two packs' versions of a model
+ */
+export interface ModelDiff {
+  modelId: string;
+  leftPackId: string;
+  rightPackId: string;
+  kind: ModelDiffKind;
+  leftElements: number;
+  rightElements: number;
+  /** Shape differences, e.g. "element 1: to [16, 8, 16] -> [16, 16, 16]" */
+  geometryChanges: string[];
+  /** Face differences that keep the shape (texture reference, UVs, tint) */
+  faceChanges: string[];
+  textureChanges: TextureChange[];
+  /** Display contexts whose transform differs */
+  displayChanges: string[];
+}
+
+/**
+ * Compare two packs' versions of the same model after parent resolution
+ *
+ * @param packsDir - Directory containing resource packs
+ * @param modelId - Model both packs override (e.g., "minecraft:block/oak_stairs")
+ * @param leftPackId - First pack ("minecraft:vanilla" for the base game)
+ * @param rightPackId - Second pack
+ * @returns Structured diff, classified as cosmetic or geometric
+ */
+export async function diffPackModels(
+  packsDir: string,
+  modelId: string,
+  leftPackId: string,
+  rightPackId: string,
+): Promise<ModelDiff> {
+  return invoke<ModelDiff>("diff_pack_models", {
+    packsDir,
+    modelId,
+    leftPackId,
+    rightPackId,
+  });
+}
+
+/**
+ * Preview which textures overriding a block to a pack takes along
+ *
+ * Builds take an overridden block's other textures from the same pack, so a
+ * log's top and sides don't come from different packs.
+ *
+ * @param packsDir - Directory containing resource packs
+ * @param assetId - Texture being overridden (e.g., "minecraft:block/oak_log")
+ * @param packId - Pack it is overridden to
+ * @returns Other texture IDs of the same block the pack provides
+ */
+export async function getOverrideDependencies(
+  packsDir: string,
+  assetId: string,
+  packId: string,
+): Promise<string[]> {
+  return invoke<string[]>("get_override_dependencies", {
+    packsDir,
+    assetId,
+    packId,
+  });
+}
+
+/**
+ * Blockstates and models of one pack that reference a texture
+ */
+export interface TextureUsage {
+  packId: string;
+  /** Block IDs whose blockstate can show the texture (e.g. "oak_log") */
+  blockstates: string[];
+  /** Model IDs whose resolved textures include it */
+  models: string[];
+}
+
+/**
+ * Find every blockstate and model that draws a texture, per pack
+ *
+ * @param packsDir - Directory containing resource packs
+ * @param textureId - Texture ID (e.g., "minecraft:block/oak_log")
+ * @returns Usage in each pack that draws the texture, vanilla last
+ */
+export async function getTextureUsage(
+  packsDir: string,
+  textureId: string,
+): Promise<TextureUsage[]> {
+  return invoke<TextureUsage[]>("get_texture_usage", { packsDir, textureId });
+}
+
+/**
+ * Block and model IDs shipped with vanilla, for autocomplete
+ */
+export interface IdCatalog {
+  /** Block IDs with a blockstate (e.g. "oak_stairs"), sorted */
+  blockIds: string[];
+  /** Model IDs (e.g. "minecraft:block/oak_stairs"), sorted */
+  modelIds: string[];
+}
+
+/**
+ * List vanilla block and model IDs
+ *
+ * Both lists are empty until vanilla textures have been extracted.
+ *
+ * @returns Sorted block and model IDs from the vanilla cache
+ */
+export async function listVanillaIds(): Promise<IdCatalog> {
+  return invoke<IdCatalog>("list_vanilla_ids");
+}
@@ -283,5 +283,33 @@ describe('modelConverter', () => {
       expect(mockTextureLoader).toHaveBeenCalledWith('minecraft:block/grass_block_side')
       expect(mockTextureLoader).toHaveBeenCalledWith('minecraft:block/dirt')
     })
+
+    it('should pose the model with the resolved display transform', async () => {
+      const model: BlockModel = {
+        textures: { all: 'minecraft:block/dirt' },
+        elements: [
+          {
+            from: [0, 0, 0],
+            to: [16, 16, 16],
+            faces: { north: { texture: '#all' } },
+          },
+        ],
+        displayTransform: {
+          rotation: [30, 225, 0],
+          translation: [0, 8, 0],
+          scale: [0.625, 0.625, 0.625],
+        },
+      }
+
+      const mockTextureLoader = vi.fn().mockResolvedValue(null)
+      const posed = await blockModelToThreeJs(model, mockTextureLoader)
+
+      expect(posed.children.length).toBe(1)
+      expect(posed.children[0]).toBeInstanceOf(THREE.Group)
+      expect(posed.position.y).toBeCloseTo(0.5)
+      expect(posed.rotation.x).toBeCloseTo(THREE.MathUtils.degToRad(30))
+      expect(posed.rotation.y).toBeCloseTo(THREE.MathUtils.degToRad(225))
+      expect(posed.scale.x).toBeCloseTo(0.625)
+    })
   })
 })
@@ -12,9 +12,9 @@ import * as THREE from "three";
 import type {
   BlockModel,
   ModelElement,
-  ResolvedModel,
   ElementFace,
   ElementRotation,
+  DisplayTransform,
 } from "@lib/tauri/blockModels";
 
 const MINECRAFT_UNIT = 16; // Minecraft uses 16x16x16 units per block
@@ -25,14 +25,12 @@ const MINECRAFT_UNIT = 16; // Minecraft uses 16x16x16 units per block
  * @param model - The resolved block model JSON
  * @param textureLoader - Function to load textures (returns THREE.Texture or null)
  * @param biomeColor - Optional biome color for tinting
- * @param resolvedModel - Optional resolved model with rotations and uvlock
  * @returns Three.js Group containing all model elements
  */
 export async function blockModelToThreeJs(
   model: BlockModel,
   textureLoader: (textureId: string) => Promise<THREE.Texture | null>,
   biomeColor?: { r: number; g: number; b: number } | null,
-  resolvedModel?: ResolvedModel,
 ): Promise<THREE.Group> {
   console.log("=== [modelConverter] Converting Model to Three.js ===");
   const startTime = performance.now();
@@ -144,41 +142,55 @@ export async function blockModelToThreeJs(
     `[modelConverter] ✓ Conversion complete. Group has ${group.children.length} meshes`,
   );
 
-  // Apply blockstate rotations if provided
-  if (resolvedModel) {
-    console.log("[modelConverter] Applying blockstate rotations:");
-    console.log(`[modelConverter] - X rotation: ${resolvedModel.rotX}°`);
-    console.log(`[modelConverter] - Y rotation: ${resolvedModel.rotY}°`);
-    console.log(`[modelConverter] - Z rotation: ${resolvedModel.rotZ}°`);
-    console.log(`[modelConverter] - UV Lock: ${resolvedModel.uvlock}`);
-
-    // Apply rotations in order: X, Y, Z (Minecraft order)
-    // Convert degrees to radians
-    if (resolvedModel.rotX !== 0) {
-      group.rotateX(THREE.MathUtils.degToRad(resolvedModel.rotX));
-    }
-    if (resolvedModel.rotY !== 0) {
-      group.rotateY(THREE.MathUtils.degToRad(resolvedModel.rotY));
-    }
-    if (resolvedModel.rotZ !== 0) {
-      group.rotateZ(THREE.MathUtils.degToRad(resolvedModel.rotZ));
-    }
+  // Blockstate rotations and uvlock are baked into the elements by
+  // loadModelJson (pass the variant), so the group needs no extra rotation
 
-    // TODO: Implement uvlock if needed (rotates UV coordinates to compensate for model rotation)
-    // For now, this is a placeholder - uvlock requires rotating UV coordinates in the opposite direction
-    if (resolvedModel.uvlock) {
-      console.log(
-        "[modelConverter] Note: uvlock is set but not yet fully implemented",
-      );
-    }
-  }
+  // Pose the model for its display context (e.g. inventory), if requested
+  const posed = model.displayTransform
+    ? applyDisplayTransform(group, model.displayTransform)
+    : group;
 
   const totalTime = performance.now() - startTime;
   console.log(
     `[modelConverter] Total conversion time: ${totalTime.toFixed(2)}ms`,
   );
   console.log("=====================================================");
-  return group;
+  return posed;
+}
+
+/**
+ * Pose a converted model for a display context (gui, ground, fixed, ...)
+ *
+ * Wraps the model so the transform applies after blockstate rotations:
+ * translate (1/16 block units), rotate X then Y then Z, then scale, all
+ * around the block center, like Minecraft.
+ */
+export function applyDisplayTransform(
+  group: THREE.Group,
+  transform: DisplayTransform,
+): THREE.Group {
+  const posed = new THREE.Group();
+  posed.add(group);
+
+  const [tx, ty, tz] = transform.translation;
+  posed.position.set(
+    tx / MINECRAFT_UNIT,
+    ty / MINECRAFT_UNIT,
+    tz / MINECRAFT_UNIT,
+  );
+  const [rx, ry, rz] = transform.rotation;
+  posed.rotation.set(
+    THREE.MathUtils.degToRad(rx),
+    THREE.MathUtils.degToRad(ry),
+    THREE.MathUtils.degToRad(rz),
+    "XYZ",
+  );
+  posed.scale.set(...transform.scale);
+
+  console.log(
+    `[modelConverter] Applied display transform: rot=${transform.rotation} trans=${transform.translation} scale=${transform.scale}`,
+  );
+  return posed;
 }
 
 /**
@@ -89,6 +89,7 @@ import {
   detectLaunchers,
   getLauncherResourcepacksDir,
   getEntityVersionVariants,
+  suggestPackOrder,
 } from "@lib/tauri";
 import { getCurrentWindow } from "@tauri-apps/api/window";
 import { listen } from "@tauri-apps/api/event";
@@ -106,6 +107,7 @@ import {
 import {
   getColormapTypeFromAssetId,
   is2DOnlyTexture,
+  isCemAsset,
   isEntityTexture,
   isMinecraftItem,
 } from "@lib/assetUtils";
@@ -670,6 +672,24 @@ export default function MainRoute() {
     [packs],
   );
 
+  // Patch packs enabled without any of their base packs
+  const patchWarnings = useMemo(() => {
+    const enabledIds = new Set(packs.map((p: PackMeta) => p.id));
+    const allPacks = [...packs, ...disabledPacks];
+    return packs
+      .filter(
+        (p: PackMeta) =>
+          p.patch_for?.length &&
+          !p.patch_for.some((id) => enabledIds.has(id)),
+      )
+      .map((p: PackMeta) => {
+        const bases = (p.patch_for ?? []).map(
+          (id) => allPacks.find((base) => base.id === id)?.name ?? id,
+        );
+        return `${p.name} is a patch for ${bases.join(" or ")}, which isn't enabled`;
+      });
+  }, [packs, disabledPacks]);
+
   const disabledPackListItems = useMemo(
     () =>
       disabledPacks.map((p: PackMeta) => ({
@@ -720,6 +740,18 @@ export default function MainRoute() {
     [setDisabledPackOrder],
   );
 
+  // One-click starting order: addons on top, full-coverage packs at the bottom
+  const handleSuggestPackOrder = useCallback(async () => {
+    if (!packsDir) return;
+    try {
+      const suggestion = await suggestPackOrder(packsDir, packOrder);
+      console.log("[handleSuggestPackOrder] Suggested:", suggestion.packs);
+      handleReorderPacks(suggestion.packOrder);
+    } catch (error) {
+      setErrorMessage(formatError(error));
+    }
+  }, [packsDir, packOrder, handleReorderPacks, setErrorMessage]);
+
   const handleDisablePack = useCallback(
     (packId: string, targetIndex?: number) => {
       disablePack(packId, targetIndex);
@@ -790,7 +822,8 @@ export default function MainRoute() {
         setPackFormats(packFormats);
 
         console.log("[handleBrowsePacksFolder] Ingesting assets...");
-        ingestAssets(result.assets);
+        // Entity model groups take part in conflicts and builds but aren't browsable textures
+        ingestAssets(result.assets.filter((asset) => !isCemAsset(asset.id)));
 
         console.log("[handleBrowsePacksFolder] Ingesting providers (batch)...");
         // Ingest providers (batch operation for performance)
@@ -877,7 +910,7 @@ export default function MainRoute() {
           );
           setPackFormats(packFormats);
 
-          ingestAssets(result.assets);
+          ingestAssets(result.assets.filter((asset) => !isCemAsset(asset.id)));
 
           // Ingest providers (batch operation for performance)
           ingestAllProviders(result.providers);
@@ -1000,6 +1033,8 @@ export default function MainRoute() {
               disabledPacks={disabledPackListItems}
               onReorder={handleReorderPacks}
               onReorderDisabled={handleReorderDisabledPacks}
+              onSuggestOrder={handleSuggestPackOrder}
+              warnings={patchWarnings}
               onDisable={handleDisablePack}
               onEnable={handleEnablePack}
               onBrowse={handleBrowsePacksFolder}
@@ -1182,8 +1217,10 @@ export default function MainRoute() {
     [
       packListItems,
       disabledPackListItems,
+      patchWarnings,
       handleReorderPacks,
       handleReorderDisabledPacks,
+      handleSuggestPackOrder,
       handleDisablePack,
       handleEnablePack,
       handleBrowsePacksFolder,
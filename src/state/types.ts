@@ -17,6 +17,8 @@ export interface PackMeta {
   description?: string;
   icon_data?: string; // Base64-encoded PNG
   pack_format?: number; // Pack format version from pack.mcmeta
+  patch_for?: string[]; // Base packs this small patch/addon pack is made for
+  compatible_mc_versions?: string; // Minecraft versions the pack format is meant for
 }
 
 /**
@@ -26,6 +28,18 @@ export interface AssetRecord {
   id: AssetId;
   labels: string[];
   files: string[];
+  animation?: TextureAnimation; // From the texture's .png.mcmeta, if animated
+}
+
+/**
+ * Animation section of a texture's .png.mcmeta, as declared
+ */
+export interface TextureAnimation {
+  frametime: number; // Default game ticks per frame
+  interpolate: boolean;
+  width?: number; // Frame size in pixels, if set
+  height?: number;
+  frames?: number[]; // Custom frame order; omitted plays every frame in turn
 }
 
 /**
@@ -182,4 +196,88 @@ export interface ScanResult {
   packs: PackMeta[];
   assets: AssetRecord[];
   providers: Record<AssetId, PackId[]>;
+  /** Packs recognized from the known-pack database */
+  knownPacks?: Record<PackId, KnownPackMatch>;
+  /** Per asset, groups of packs providing byte-identical copies */
+  identicalProviders?: Record<AssetId, PackId[][]>;
+  /** Cost of content hashing during the scan */
+  hashTimings?: HashTimings;
+  /** Sync, network and read-only problems with the packs folder */
+  folderAccess?: FolderAccessReport | null;
+  /** Per pack, macOS metadata files (__MACOSX, ._*) left out of the scan */
+  osMetadataFiles?: Record<PackId, number>;
+  /** Asset counts and sizes by category (block, item, entity, gui...) */
+  assetStats?: AssetStats;
+}
+
+/**
+ * Totals for one asset category
+ */
+export interface CategoryStats {
+  assets: number;
+  files: number;
+  /** Uncompressed size of the files */
+  bytes: number;
+}
+
+/**
+ * Category totals per pack and across all packs
+ */
+export interface AssetStats {
+  /** Assets count once; files and bytes add up every pack's copy */
+  overall: Record<string, CategoryStats>;
+  byPack: Record<PackId, Record<string, CategoryStats>>;
+}
+
+/**
+ * Where a packs folder lives and what that means for reading it
+ */
+export interface FolderAccessReport {
+  path: string;
+  syncProvider: "oneDrive" | "dropbox" | "iCloud" | "googleDrive" | null;
+  /** Folder is on a network share */
+  network: boolean;
+  /** Nothing can be written into the folder */
+  readOnly: boolean;
+  /** Packs that need downloading before they can be read (skipped by scans) */
+  placeholders: PlaceholderFile[];
+  /** Human-readable explanation of each problem found */
+  warnings: string[];
+}
+
+/**
+ * A pack whose contents are only in the cloud
+ */
+export interface PlaceholderFile {
+  /** Pack file name as it will appear once downloaded */
+  name: string;
+  path: string;
+  kind: "iCloudStub" | "dataless" | "cloudOnly";
+}
+
+/**
+ * Cost of hashing asset contents while indexing
+ */
+export interface HashTimings {
+  filesHashed: number;
+  bytesHashed: number;
+  /** Time spent hashing, summed across packs */
+  hashMs: number;
+  /** Wall-clock time of the whole indexing pass */
+  totalMs: number;
+}
+
+/**
+ * A scanned pack identified as a popular pack
+ */
+export interface KnownPackMatch {
+  knownId: string;
+  canonicalName: string;
+  matchedBy: "icon" | "structure" | "name" | "description";
+  /** True if the pack's files were checked and matched the fingerprint */
+  structureVerified: boolean;
+  /** Mods required for the pack to work as intended */
+  requirements: string[];
+  /** Mods that enable optional features */
+  recommends: string[];
 }